@@ -1,13 +1,26 @@
 use actix_web::{web::Bytes, *};
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
+use std::{cell::RefCell, pin::Pin, rc::Rc, sync::Arc};
 
 /// An Actix [Route](actix_web::Route) that listens for a `POST` request with
 /// Leptos server function arguments in the body, runs the server function if found,
 /// and returns the resulting [HttpResponse].
 ///
+/// Server functions declared with `method = "GET"` are called with a `GET` request that sends
+/// the arguments in the query string instead, and their `cache` setting is sent as the
+/// `Cache-Control` header of the response.
+///
+/// All other server functions are only run if the request sends back the
+/// [CsrfToken](leptos::CsrfToken) issued by [render_app_to_stream], unless they opt out with
+/// `csrf = false`; otherwise, the response is `403 Forbidden`.
+///
+/// A `POST` to the [BATCH_PATH](leptos::BATCH_PATH) under the prefix runs the calls of a batch
+/// that a client with [enable_server_fn_batching](leptos::enable_server_fn_batching) sent,
+/// each as if it had been sent on its own, and responds with their results as a JSON array.
+///
 /// This provides the [HttpRequest] to the server [Scope](leptos::Scope).
 ///
 /// This can then be set up at an appropriate route in your application:
@@ -38,82 +51,683 @@ use leptos_router::*;
 /// # }
 /// ```
 pub fn handle_server_fns() -> Route {
-    web::post().to(
-        |req: HttpRequest, params: web::Path<String>, body: web::Bytes| async move {
-            {
-                let path = params.into_inner();
-                let accept_header = req
-                    .headers()
-                    .get("Accept")
-                    .and_then(|value| value.to_str().ok());
-
-                if let Some(server_fn) = server_fn_by_path(path.as_str()) {
-                    let body: &[u8] = &body;
-
-                    let runtime = create_runtime();
-                    let (cx, disposer) = raw_scope_and_disposer(runtime);
-
-                    // provide HttpRequest as context in server scope
-                    provide_context(cx, req.clone());
-
-                    match server_fn(cx, body).await {
-                        Ok(serialized) => {
-                            // clean up the scope, which we only needed to run the server fn
-                            disposer.dispose();
-                            runtime.dispose();
-
-                            let mut res: HttpResponseBuilder;
-                            if accept_header == Some("application/json")
-                                || accept_header == Some("application/x-www-form-urlencoded")
-                                || accept_header == Some("application/cbor")
-                            {
-                                res = HttpResponse::Ok()
+    server_fn_route(None, None, None)
+}
+
+/// An Actix [Route](actix_web::Route) that works like [handle_server_fns], but enforces the
+/// `max_request_size`, `max_upload_size` and `server_fn_timeout` limits set in [RenderOptions].
+///
+/// A request body larger than `max_request_size`, or a `multipart/form-data` body larger than
+/// `max_upload_size`, is rejected with `413 Payload Too Large` before more of it than the limit
+/// has been read, and a server function that runs longer than `server_fn_timeout` is abandoned
+/// with `504 Gateway Timeout`. Without a limit, Actix applies its own default payload limit,
+/// which can be raised with [PayloadConfig](actix_web::web::PayloadConfig).
+///
+/// A server function can set its own limits with
+/// `#[server(Name, timeout = "10s", max_body = "2mb")]`, which take the place of these, and
+/// which [handle_server_fns] enforces as well. Either way,
+/// the response holds a [ServerFnError::Timeout] or [ServerFnError::PayloadTooLarge] in the
+/// error type of the function, so that the client can tell them apart from other errors.
+/// ```
+/// use actix_web::App;
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// App::new().route(
+///     "/api/{tail:.*}",
+///     leptos_actix::handle_server_fns_with_options(&render_options),
+/// );
+/// # }
+/// ```
+pub fn handle_server_fns_with_options(options: &RenderOptions) -> Route {
+    server_fn_route(
+        options.server_fn_timeout,
+        options.max_request_size,
+        options.max_upload_size,
+    )
+}
+
+fn server_fn_route(
+    timeout: Option<std::time::Duration>,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> Route {
+    // functions declared with `method = "GET"` are called with GET, and all others with POST
+    let route = web::route().guard(guard::Any(guard::Post()).or(guard::Get()));
+    route.to(
+        move |req: HttpRequest, params: web::Path<String>, payload: web::Payload| async move {
+            let path = params.into_inner();
+            if path == BATCH_PATH && req.method() == http::Method::POST {
+                let body = match read_body(&req, payload, BATCH_PATH, max_request_size).await {
+                    Ok(body) => body,
+                    Err(res) => return res,
+                };
+                return server_fn_batch_response(
+                    req,
+                    body,
+                    timeout,
+                    max_request_size,
+                    max_upload_size,
+                )
+                .await;
+            }
+            let max_size = body_limit(&req, path.as_str(), max_request_size, max_upload_size);
+            let body = match read_body(&req, payload, path.as_str(), max_size).await {
+                Ok(body) => body,
+                Err(res) => return res,
+            };
+            server_fn_response(
+                req,
+                path,
+                body,
+                timeout,
+                max_request_size,
+                max_upload_size,
+                false,
+            )
+            .await
+        },
+    )
+}
+
+/// The limit on the size of the body of a call of the server function at `path`: the one the
+/// function sets for itself, if any, or else the global one for uploads or other requests.
+fn body_limit(
+    req: &HttpRequest,
+    path: &str,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> Option<u64> {
+    let is_upload = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with("multipart/form-data"));
+    let max_size = if is_upload {
+        max_upload_size.or(max_request_size)
+    } else {
+        max_request_size
+    };
+    server_fn_info(path)
+        .and_then(|info| info.max_body_size)
+        .or(max_size)
+}
+
+/// Reads the body of a call of the server function at `path`, but no more than `max_size`
+/// bytes of it, so that a client can't make the server buffer a body of any size. A body that
+/// declares a larger `Content-Length` is rejected before any of it is read. Without a limit,
+/// the body is read with the limit of the [PayloadConfig](actix_web::web::PayloadConfig).
+async fn read_body(
+    req: &HttpRequest,
+    payload: web::Payload,
+    path: &str,
+    max_size: Option<u64>,
+) -> Result<Bytes, HttpResponse> {
+    let max = match max_size {
+        Some(max) => max,
+        None => {
+            return web::Bytes::from_request(req, &mut payload.into_inner())
+                .await
+                .map_err(|e| e.error_response())
+        }
+    };
+    let content_length = req
+        .headers()
+        .get("Content-Length")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(len) = content_length {
+        if len > max {
+            return Err(payload_too_large(path, max));
+        }
+    }
+    let limit = usize::try_from(max).unwrap_or(usize::MAX);
+    match payload.to_bytes_limited(limit).await {
+        Ok(Ok(body)) => Ok(body),
+        Ok(Err(e)) => Err(e.error_response()),
+        Err(_) => Err(payload_too_large(path, max)),
+    }
+}
+
+/// Responds to a call of the server function at `path` whose body is larger than `max` bytes.
+fn payload_too_large(path: &str, max: u64) -> HttpResponse {
+    match server_fn_error_response(path, ServerFnError::PayloadTooLarge(max)) {
+        Some(e) => error_response(e),
+        None => HttpResponse::PayloadTooLarge()
+            .body(format!("Request body is larger than the limit of {max} bytes.")),
+    }
+}
+
+/// Runs the server function at `path` and responds with its result. The results of calls in
+/// a batch are always sent as JSON, rather than negotiated with the `Accept` header.
+async fn server_fn_response(
+    req: HttpRequest,
+    path: String,
+    body: Bytes,
+    timeout: Option<std::time::Duration>,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+    batched: bool,
+) -> HttpResponse {
+    // a GET request sends the arguments in the query string
+    let is_get = req.method() == http::Method::GET;
+    if is_get && server_fn_method(path.as_str()) != Some(HttpMethod::Get) {
+        return HttpResponse::MethodNotAllowed()
+            .body("This server function must be called with POST.");
+    }
+    let body = if is_get {
+        Bytes::copy_from_slice(req.query_string().as_bytes())
+    } else {
+        body
+    };
+    let info = server_fn_info(path.as_str());
+    let max_size = body_limit(&req, path.as_str(), max_request_size, max_upload_size);
+    let timeout = info.as_ref().and_then(|info| info.timeout).or(timeout);
+    // the body of a POST request was read up to the limit, but the arguments of a GET request
+    // are in the query string, and those of a call in a batch in the body of the batch
+    if let Some(max) = max_size {
+        if body.len() as u64 > max {
+            return payload_too_large(path.as_str(), max);
+        }
+    }
+    let accept_header = if batched {
+        Some("application/json")
+    } else {
+        req.headers()
+            .get("Accept")
+            .and_then(|value| value.to_str().ok())
+    };
+
+    if let Some(server_fn) = server_fn_by_path(path.as_str()) {
+        let body: &[u8] = &body;
+        let request_parts = request_parts(&req, path.clone());
+        let check_csrf = !is_get && info.map_or(false, |info| info.csrf);
+        if check_csrf {
+            if let Err(e) = verify_csrf_token(req.method().as_str(), &request_parts, body) {
+                return HttpResponse::Forbidden().body(e.to_string());
+            }
+        }
+
+        let runtime = create_runtime();
+        let (cx, disposer) = raw_scope_and_disposer(runtime);
+
+        // provide HttpRequest as context in server scope
+        provide_context(cx, req.clone());
+        // and the parts of it that server function middleware can inspect
+        provide_context(cx, request_parts);
+        // and the options it can set for the response
+        let response_options = ResponseOptions::default();
+        provide_context(cx, response_options.clone());
+
+        let result = match timeout {
+            Some(timeout) => rt::time::timeout(timeout, server_fn(cx, body)).await,
+            None => Ok(server_fn(cx, body).await),
+        };
+        let mut res = match result {
+            Ok(Ok(Payload::Stream(stream))) => {
+                // the values are sent as they're produced, so the scope is only
+                // cleaned up once the stream has ended
+                let sse = accept_header == Some("text/event-stream");
+                let body = stream
+                    .into_body(sse)
+                    .chain(futures::stream::once(async move {
+                        disposer.dispose();
+                        runtime.dispose();
+                        Default::default()
+                    }));
+                HttpResponse::Ok()
+                    .content_type(if sse {
+                        "text/event-stream"
+                    } else {
+                        "application/x-ndjson"
+                    })
+                    .streaming(body.map(|chunk| Ok::<_, Error>(Bytes::from(chunk))))
+            }
+            Ok(Ok(serialized)) => {
+                // clean up the scope, which we only needed to run the server fn
+                disposer.dispose();
+                runtime.dispose();
+
+                let mut res: HttpResponseBuilder;
+                if is_get
+                    || accept_header == Some("application/json")
+                    || accept_header == Some("application/x-www-form-urlencoded")
+                    || accept_header == Some("application/cbor")
+                    || matches!(
+                        serialized,
+                        Payload::Codec(content_type, _)
+                            if accept_header == Some(content_type)
+                    )
+                {
+                    res = HttpResponse::Ok()
+                }
+                // otherwise, it's probably a <form> submit or something: redirect back to the referrer
+                else {
+                    let referer = req
+                        .headers()
+                        .get("Referer")
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or("/");
+                    res = HttpResponse::SeeOther();
+                    res.insert_header(("Location", referer))
+                        .content_type("application/json");
+                };
+                if let Some(cache_control) =
+                    server_fn_cache_control(path.as_str()).filter(|_| is_get)
+                {
+                    res.insert_header(("Cache-Control", cache_control));
+                }
+                match serialized {
+                    Payload::Binary(data) => {
+                        res.content_type("application/cbor");
+                        res.body(Bytes::from(data))
+                    }
+                    Payload::Url(data) => {
+                        res.content_type("application/x-www-form-urlencoded");
+                        res.body(data)
+                    }
+                    Payload::Json(data) => {
+                        res.content_type("application/json");
+                        res.body(data)
+                    }
+                    Payload::Codec(content_type, data) => {
+                        res.content_type(content_type);
+                        res.body(Bytes::from(data))
+                    }
+                    Payload::Stream(_) => unreachable!("streams are sent above"),
+                }
+            }
+            Ok(Err(e)) => {
+                disposer.dispose();
+                runtime.dispose();
+                report_error(&e, ErrorLocation::ServerFn(path.to_string()));
+                error_response(e)
+            }
+            Err(_) => {
+                disposer.dispose();
+                runtime.dispose();
+                let e = ServerFnError::Timeout(timeout.unwrap_or_default());
+                report_error(&e, ErrorLocation::ServerFn(path.to_string()));
+                match server_fn_error_response(path.as_str(), e) {
+                    Some(e) => error_response(e),
+                    None => HttpResponse::GatewayTimeout().body("Server function timed out."),
+                }
+            }
+        };
+        apply_response_options(&mut res, response_options.parts());
+        res
+    } else {
+        HttpResponse::BadRequest().body(format!("Could not find a server function at that route."))
+    }
+}
+
+/// Runs the calls of a batch that a client with [enable_server_fn_batching] sent, each as if
+/// it had been sent in a request of its own, and responds with their results in order.
+///
+/// The calls were made by the same page, so each is run with the [HttpRequest] of the batch,
+/// and checked with its headers, e.g., with its cookies and its CSRF token. They run together
+/// on the worker thread that handles the batch.
+async fn server_fn_batch_response(
+    req: HttpRequest,
+    body: Bytes,
+    timeout: Option<std::time::Duration>,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> HttpResponse {
+    let calls = match parse_server_fn_batch(&body) {
+        Ok(calls) => calls,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+
+    let results = calls.into_iter().map(|call| {
+        let req = req.clone();
+        async move {
+            // a batch can't hold another one
+            if call.path == BATCH_PATH {
+                return BatchedResult {
+                    status: http::StatusCode::BAD_REQUEST.as_u16(),
+                    body: "Batches can't be nested.".to_string(),
+                };
+            }
+            let res = server_fn_response(
+                req,
+                call.path,
+                Bytes::from(call.args),
+                timeout,
+                max_request_size,
+                max_upload_size,
+                true,
+            )
+            .await;
+            let status = res.status().as_u16();
+            let body = body::to_bytes(res.into_body()).await.unwrap_or_default();
+            BatchedResult {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+        }
+    });
+    let results = futures::future::join_all(results).await;
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(encode_server_fn_batch(&results))
+}
+
+/// Responds with an error of a server function, which the body holds so that the client can
+/// deserialize it.
+fn error_response(e: ServerFnErrorResponse) -> HttpResponse {
+    let status =
+        http::StatusCode::from_u16(e.status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+    let mut res = HttpResponse::build(status);
+    match e.payload {
+        Payload::Binary(data) => {
+            res.content_type("application/cbor");
+            res.body(Bytes::from(data))
+        }
+        Payload::Url(data) | Payload::Json(data) => {
+            res.content_type("application/json");
+            res.body(data)
+        }
+        Payload::Codec(content_type, data) => {
+            res.content_type(content_type);
+            res.body(Bytes::from(data))
+        }
+        Payload::Stream(_) => unreachable!("errors aren't streamed"),
+    }
+}
+
+/// Applies the status code and headers a server function, or the app while it was rendered,
+/// set with [ResponseOptions].
+fn apply_response_options(res: &mut HttpResponse, options: ResponseParts) {
+    if let Some(status) = options
+        .status
+        .and_then(|status| http::StatusCode::from_u16(status).ok())
+    {
+        *res.status_mut() = status;
+    }
+    // headers that were set replace the ones with the same name
+    for (name, _) in &options.headers {
+        if let Ok(name) = http::header::HeaderName::try_from(name.as_str()) {
+            res.headers_mut().remove(name);
+        }
+    }
+    for (name, value) in options.headers {
+        if let (Ok(name), Ok(value)) = (
+            http::header::HeaderName::try_from(name),
+            http::header::HeaderValue::try_from(value),
+        ) {
+            res.headers_mut().append(name, value);
+        }
+    }
+}
+
+/// The parts of a request that server function middleware and channel handlers can inspect.
+fn request_parts(req: &HttpRequest, path: String) -> RequestParts {
+    RequestParts {
+        path,
+        headers: req
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+    }
+}
+
+/// An Actix [Route](actix_web::Route) that accepts WebSocket connections for the
+/// [ServerChannel](leptos::ServerChannel)s registered with
+/// [register_server_channel](leptos::register_server_channel), and runs the handler of the
+/// channel for each of them.
+///
+/// The handler runs in a [Scope](leptos::Scope) that the [HttpRequest] and the
+/// [RequestParts] of the connection are provided to. The connection is closed once the handler
+/// has returned and it has dropped its senders, or when the client disconnects.
+/// ```
+/// use actix_web::App;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// // "/ws" should match the prefix of the channels
+/// App::new().route("/ws/{tail:.*}", leptos_actix::handle_server_channels());
+/// # }
+/// ```
+pub fn handle_server_channels() -> Route {
+    web::get().to(
+        |req: HttpRequest, params: web::Path<String>, mut payload: web::Payload| async move {
+            use actix_codec::{Decoder, Encoder};
+            use actix_http::ws;
+
+            let path = params.into_inner();
+            let handler = match server_channel_by_path(path.as_str()) {
+                Some(handler) => handler,
+                None => {
+                    return HttpResponse::NotFound()
+                        .body("Could not find a server channel at that route.")
+                }
+            };
+            if let Err(e) = ws::verify_handshake(req.head()) {
+                return HttpResponse::from_error(e);
+            }
+            let mut response = ws::handshake_response(req.head());
+
+            let (incoming_tx, incoming_rx) = futures::channel::mpsc::unbounded::<String>();
+            let (outgoing_tx, outgoing_rx) = futures::channel::mpsc::unbounded::<String>();
+            // replies to the client's control frames, like pongs to its pings
+            let (control_tx, control_rx) = futures::channel::mpsc::unbounded::<ws::Message>();
+
+            // read the messages of the client
+            rt::spawn(async move {
+                let mut codec = ws::Codec::new();
+                let mut buffer = web::BytesMut::new();
+                // a message that was split into several frames
+                let mut partial: Option<Vec<u8>> = None;
+                while let Some(Ok(chunk)) = payload.next().await {
+                    buffer.extend_from_slice(&chunk);
+                    loop {
+                        let frame = match codec.decode(&mut buffer) {
+                            Ok(Some(frame)) => frame,
+                            Ok(None) => break,
+                            Err(_) => {
+                                _ = control_tx.unbounded_send(ws::Message::Close(Some(
+                                    ws::CloseCode::Protocol.into(),
+                                )));
+                                return;
                             }
-                            // otherwise, it's probably a <form> submit or something: redirect back to the referrer
-                            else {
-                                let referer = req
-                                    .headers()
-                                    .get("Referer")
-                                    .and_then(|value| value.to_str().ok())
-                                    .unwrap_or("/");
-                                res = HttpResponse::SeeOther();
-                                res.insert_header(("Location", referer))
-                                    .content_type("application/json");
-                            };
-                            match serialized {
-                                Payload::Binary(data) => {
-                                    res.content_type("application/cbor");
-                                    res.body(Bytes::from(data))
-                                }
-                                Payload::Url(data) => {
-                                    res.content_type("application/x-www-form-urlencoded");
-                                    res.body(data)
+                        };
+                        let message = match frame {
+                            ws::Frame::Text(data) | ws::Frame::Binary(data) => data.to_vec(),
+                            ws::Frame::Continuation(ws::Item::FirstText(data))
+                            | ws::Frame::Continuation(ws::Item::FirstBinary(data)) => {
+                                partial = Some(data.to_vec());
+                                continue;
+                            }
+                            ws::Frame::Continuation(ws::Item::Continue(data)) => {
+                                if let Some(partial) = &mut partial {
+                                    partial.extend_from_slice(&data);
                                 }
-                                Payload::Json(data) => {
-                                    res.content_type("application/json");
-                                    res.body(data)
+                                continue;
+                            }
+                            ws::Frame::Continuation(ws::Item::Last(data)) => match partial.take() {
+                                Some(mut partial) => {
+                                    partial.extend_from_slice(&data);
+                                    partial
                                 }
+                                None => continue,
+                            },
+                            ws::Frame::Ping(data) => {
+                                _ = control_tx.unbounded_send(ws::Message::Pong(data));
+                                continue;
+                            }
+                            ws::Frame::Pong(_) => continue,
+                            ws::Frame::Close(reason) => {
+                                _ = control_tx.unbounded_send(ws::Message::Close(reason));
+                                return;
                             }
+                        };
+                        let message = String::from_utf8_lossy(&message).into_owned();
+                        if incoming_tx.unbounded_send(message).is_err() {
+                            return;
                         }
-                        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
                     }
-                } else {
-                    HttpResponse::BadRequest()
-                        .body(format!("Could not find a server function at that route."))
                 }
+            });
+
+            // run the handler of the channel
+            let request_parts = request_parts(&req, path);
+            rt::spawn(async move {
+                let runtime = create_runtime();
+                let (cx, disposer) = raw_scope_and_disposer(runtime);
+                provide_context(cx, req);
+                provide_context(cx, request_parts);
+                handler(
+                    cx,
+                    ChannelConnection {
+                        incoming: Box::pin(incoming_rx),
+                        outgoing: outgoing_tx,
+                    },
+                )
+                .await;
+                disposer.dispose();
+                runtime.dispose();
+            });
+
+            // the connection is closed once the handler's senders are dropped, or once the
+            // client has closed it
+            let messages = outgoing_rx
+                .map(|message| ws::Message::Text(message.into()))
+                .chain(futures::stream::once(async { ws::Message::Close(None) }));
+            let mut codec = ws::Codec::new();
+            let frames = futures::stream::select(messages, control_rx)
+                .scan(false, |closed, message| {
+                    if *closed {
+                        return futures::future::ready(None);
+                    }
+                    *closed = matches!(message, ws::Message::Close(_));
+                    futures::future::ready(Some(message))
+                })
+                .map(move |message| {
+                    let mut frame = web::BytesMut::new();
+                    codec
+                        .encode(message, &mut frame)
+                        .map(|_| frame.freeze())
+                        .map_err(Error::from)
+                });
+
+            match response.message_body(body::BodyStream::new(frames)) {
+                Ok(res) => HttpResponse::from(res).map_into_boxed_body(),
+                Err(e) => HttpResponse::from_error(e),
             }
         },
     )
 }
 
+/// Extracts a value from the request that called the server function running in `cx`, using
+/// any of Actix's [extractors](actix_web::FromRequest), like
+/// [ConnectionInfo](actix_web::dev::ConnectionInfo), [Query](actix_web::web::Query) or
+/// [ReqData](actix_web::web::ReqData) for the data that authentication middleware attached
+/// to the request. As the body has been read to get the arguments of the function,
+/// extractors that read the body, like [Json](actix_web::web::Json), fail.
+///
+/// This works the same way as `leptos_axum::extract`, so that a server function can be
+/// used with either integration by switching the import.
+/// ```
+/// use actix_web::dev::ConnectionInfo;
+/// use leptos::*;
+///
+/// #[server(WhoAmI)]
+/// pub async fn who_am_i(cx: Scope) -> Result<String, ServerFnError> {
+///     let info: ConnectionInfo = leptos_actix::extract(cx).await?;
+///     Ok(info.realip_remote_addr().unwrap_or("unknown").to_string())
+/// }
+/// ```
+pub async fn extract<T: FromRequest>(cx: leptos::Scope) -> Result<T, ServerFnError> {
+    let req = use_context::<HttpRequest>(cx).ok_or_else(|| {
+        ServerFnError::ServerError("HttpRequest is missing from the context".to_string())
+    })?;
+    T::extract(&req)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.into().to_string()))
+}
+
+/// Sends the values of a stream as
+/// [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+/// serialized as JSON, so that a page can subscribe to them with
+/// [create_sse_signal](leptos::create_sse_signal). The stream ends at the first value that
+/// can't be serialized.
+/// ```
+/// use actix_web::{web, App, HttpResponse};
+/// use std::time::Duration;
+///
+/// async fn visitors() -> HttpResponse {
+///     let counts = futures::stream::unfold(0u32, |count| async move {
+///         actix_web::rt::time::sleep(Duration::from_secs(1)).await;
+///         Some((count, count + 1))
+///     });
+///     leptos_actix::sse_response(counts)
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let app = App::new().route("/events/visitors", web::get().to(visitors));
+/// # }
+/// ```
+pub fn sse_response<T: serde::Serialize>(
+    stream: impl futures::Stream<Item = T> + 'static,
+) -> HttpResponse {
+    let events = stream.scan((), |_, value| {
+        futures::future::ready(sse_event(&value).ok())
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events.map(|event| Ok::<_, Error>(Bytes::from(event))))
+}
+
 /// Returns an Actix [Route](actix_web::Route) that listens for a `GET` request and tries
 /// to route it using [leptos_router], serving an HTML stream of your application.
 ///
 /// The provides a [MetaContext] and a [RouterIntegrationContext] to app’s context before
 /// rendering it, and includes any meta tags injected using [leptos_meta].
 ///
+/// It issues a [CsrfToken](leptos::CsrfToken) in a cookie if the browser doesn't hold one yet,
+/// writes it into the `<head>` and provides it as context, so that [handle_server_fns] can
+/// check that calls to server functions come from the app.
+///
+/// It also provides [ResponseOptions], and sends the status code and headers the app sets
+/// with it while it's rendered, e.g., the `status` of a matched
+/// [Route](leptos_router::Route) like a catch-all `404` page, or a `301` and a `Location`
+/// header to redirect.
+///
 /// The HTML stream is rendered using [render_to_stream], and includes everything described in
-/// the documentation for that function.
+/// the documentation for that function. If the `ssr_mode` of the options is
+/// [SsrMode::OutOfOrder], it's rendered using [render_to_stream_out_of_order] instead, which
+/// sends the shell of the page right away, and the status code and headers along with it. If
+/// it's [SsrMode::Async], the whole page is sent at once with [render_to_string_async], once
+/// every resource has resolved or the `ssr_timeout` of the options has passed. Unless the shell
+/// is sent right away, the `<head>` is rendered once the resources created with
+/// [create_blocking_resource] have resolved, so that the meta tags set from their data, like
+/// the `<title>` of an article, are in the first HTML that is sent. The meta tags set by the
+/// parts of the page that are streamed after it, e.g., under a [Suspense](leptos::Suspense),
+/// are sent along with them, in a small script that adds them to the `<head>`, with
+/// [MetaContext::head_patch].
+///
+/// If the `islands` option is set, the app isn't hydrated as a whole: an [IslandManifest] is
+/// provided as context, and only the components marked with `#[island]` are hydrated, by a
+/// script that is sent at the end of the page and only loads the WASM if the page has islands.
+///
+/// If the `server_navigation` option is set, the app isn't hydrated as a whole either: a
+/// [ServerNavigation] is provided as context, and the script it sends at the end of the page
+/// navigates between pages by fetching each one from the server and swapping its
+/// [Routes](leptos_router::Routes) into the current one, hydrating the islands of the new page.
+///
+/// Every inline script of the page is marked with a new [Nonce] for each response, which is
+/// provided as context, so that the app can send a strict `Content-Security-Policy` header
+/// with [use_nonce] and [ResponseOptions].
+///
+/// If the `no_js_audit` option is set in the [RustEnv::DEV] environment, the page is sent as a
+/// browser without JavaScript gets it: without the script that hydrates it, and with a
+/// `Content-Security-Policy` that keeps the browser from running any other, so that links
+/// navigate and forms post as they would. A [NoJsAudit] is provided as context, and the
+/// interactive elements it records are reported on the console once the page is rendered.
 ///
 /// This can then be set up at an appropriate route in your application:
 /// ```
@@ -149,78 +763,745 @@ pub fn render_app_to_stream(
     options: RenderOptions,
     app_fn: impl Fn(leptos::Scope) -> Element + Clone + 'static,
 ) -> Route {
+    stream_app(options, None, app_fn)
+}
+
+/// Returns an Actix [Route](actix_web::Route) that serves your application like
+/// [render_app_to_stream], with the given [RenderCache] provided as context, so that the HTML
+/// of each view that is rendered with [cached] is kept there by the path and query of the page,
+/// the place of the view on it, and the key it's given, e.g., the locale, and sent again from
+/// there without rendering the view while it's kept. The cache is shared by all the workers of
+/// the server.
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///     let locale = "en";
+///     view! {
+///         cx,
+///         <main>
+///             {cached(cx, locale, |cx| view! { cx, <p>"Rendered once per locale"</p> })}
+///         </main>
+///     }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let cache = MemoryRenderCache::with_max_age(std::time::Duration::from_secs(600));
+/// let render =
+///     leptos_actix::render_app_with_cache(render_options, cache, |cx| view! { cx, <MyApp/> });
+/// HttpServer::new(move || App::new().route("/{tail:.*}", render.clone()));
+/// # }
+/// ```
+pub fn render_app_with_cache(
+    options: RenderOptions,
+    cache: impl RenderCache,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + 'static,
+) -> Route {
+    stream_app(options, Some(Arc::new(cache)), app_fn)
+}
+
+/// Streams the app as [render_app_to_stream] does, with the given [RenderCache], if any.
+fn stream_app(
+    options: RenderOptions,
+    render_cache: Option<Arc<dyn RenderCache>>,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + 'static,
+) -> Route {
+    let assets = options
+        .asset_paths()
+        .expect("couldn't resolve the paths of the JS and WASM files");
+    let runtime_config = options.runtime_config();
+
     web::get().to(move |req: HttpRequest| {
         let options = options.clone();
+        let assets = assets.clone();
+        let runtime_config = runtime_config.clone();
         let app_fn = app_fn.clone();
+        let render_cache = render_cache
+            .clone()
+            .map(|cache| RenderCacheContext::new(cache, req.uri().to_string()));
         async move {
-            let path = req.path();
+            // issue a CSRF token, unless the browser already holds one
+            let existing_token = csrf_cookie(&req);
+            let is_new_token = existing_token.is_none();
+            let csrf_token = existing_token.unwrap_or_default();
+            let csrf_cookie = is_new_token.then(|| csrf_token.cookie());
 
-            let query = req.query_string();
-            let path = if query.is_empty() {
-                "http://leptos".to_string() + path
-            } else {
-                "http://leptos".to_string() + path + "?" + query
-            };
+            // the status and headers have to be sent before the body, so the response waits
+            // for the app to be rendered, which sets the status of its routes
+            let (parts, html) = render_app(
+                &options,
+                &assets,
+                &runtime_config,
+                app_fn,
+                req,
+                csrf_token,
+                Nonce::new(),
+                render_cache,
+            )
+            .await;
 
-            let app = {
-                let app_fn = app_fn.clone();
-                move |cx| {
-                    let integration = ServerIntegration { path: path.clone() };
-                    provide_context(cx, RouterIntegrationContext::new(integration));
-                    provide_context(cx, MetaContext::new());
-                    provide_context(cx, req.clone());
+            let chunk_size = options.stream_chunk_size;
+            let mut res = HttpResponse::Ok().content_type("text/html").streaming(
+                html.flat_map(move |html| futures::stream::iter(chunk_html(html, chunk_size)))
+                    .map(|html| Ok(html) as Result<web::Bytes>),
+            );
+            apply_response_options(&mut res, parts);
+            if let Some(Ok(cookie)) = csrf_cookie.map(http::header::HeaderValue::try_from) {
+                res.headers_mut().append(http::header::SET_COOKIE, cookie);
+            }
+            res
+        }
+    })
+}
 
-                    (app_fn)(cx)
-                }
-            };
+/// Returns an Actix [Route](actix_web::Route) that serves your application like
+/// [render_app_to_stream], with incremental static regeneration: a page the app sets a
+/// revalidation time for, e.g., with the `revalidate` prop of a [Route](leptos_router::Route),
+/// is rendered once, and then served from an [IsrCache] until that time has passed, after
+/// which it's served once more while it's rendered again in the background.
+///
+/// A page is cached only if it's rendered with a `200 OK` status and sets no cookies and no
+/// `Vary` header, as described in [ResponseParts::revalidate], and is shared between all the
+/// users who request its URL, with the [CsrfToken] of each user in the place of the one it was
+/// rendered with; it shouldn't show anything that depends on who requests it. Any other page is
+/// streamed as it is by [render_app_to_stream]. The cache is shared by all the workers of the server.
+///
+/// A [Router](leptos_router::Router) with `locales` renders a URL without a locale prefix in
+/// the locale detected from the `Cookie` and `Accept-Language` headers of each request, and
+/// sends it with `Vary: Accept-Language, Cookie`, so such a page is never cached; only the URLs
+/// with a locale prefix are.
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///   view! { cx, <main>"Hello, world!"</main> }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let render = leptos_actix::render_app_with_isr(render_options, |cx| view! { cx, <MyApp/> });
+/// HttpServer::new(move || App::new().route("/{tail:.*}", render.clone()));
+/// # }
+/// ```
+pub fn render_app_with_isr(
+    options: RenderOptions,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + 'static,
+) -> Route {
+    let assets = options
+        .asset_paths()
+        .expect("couldn't resolve the paths of the JS and WASM files");
+    let runtime_config = options.runtime_config();
+    let cache = IsrCache::default();
 
-            let pkg_path = &options.pkg_path;
-            let socket_ip = &options.socket_address.ip().to_string();
-            let reload_port = options.reload_port;
-
-            let leptos_autoreload = match options.environment {
-                RustEnv::DEV => format!(
-                    r#"
-                        <script crossorigin="">(function () {{
-                            var ws = new WebSocket('ws://{socket_ip}:{reload_port}/autoreload');
-                            ws.onmessage = (ev) => {{
-                                console.log(`Reload message: `);
-                                if (ev.data === 'reload') window.location.reload();
-                            }};
-                            ws.onclose = () => console.warn('Autoreload stopped. Manual reload necessary.');
-                        }})()
-                        </script>
-                    "#
+    web::get().to(move |req: HttpRequest| {
+        let options = options.clone();
+        let assets = assets.clone();
+        let runtime_config = runtime_config.clone();
+        let app_fn = app_fn.clone();
+        let cache = cache.clone();
+        async move {
+            // issue a CSRF token, unless the browser already holds one
+            let existing_token = csrf_cookie(&req);
+            let is_new_token = existing_token.is_none();
+            let csrf_token = existing_token.unwrap_or_default();
+            let csrf_cookie = is_new_token.then(|| csrf_token.cookie());
+            let nonce = Nonce::new();
+
+            let url = req.uri().to_string();
+            let chunk_size = options.stream_chunk_size;
+            let (parts, html) = match cache.get(&url) {
+                IsrLookup::Fresh(page) => (
+                    page.parts_for(&nonce),
+                    cached_page(page, &csrf_token, &nonce),
                 ),
-                RustEnv::PROD => "".to_string(),
+                IsrLookup::Stale(page) => {
+                    // the page is rendered again with a token of its own, as it's shared
+                    actix_web::rt::spawn({
+                        let cache = cache.clone();
+                        let url = url.clone();
+                        let req = req.clone();
+                        let csrf_token = CsrfToken::new();
+                        let nonce = Nonce::new();
+                        async move {
+                            let (parts, html) = render_app(
+                                &options,
+                                &assets,
+                                &runtime_config,
+                                app_fn,
+                                req,
+                                csrf_token.clone(),
+                                nonce.clone(),
+                                None,
+                            )
+                            .await;
+                            cache.insert(
+                                &url,
+                                CachedPage {
+                                    html: html.collect::<String>().await,
+                                    csrf_token,
+                                    nonce,
+                                    parts,
+                                },
+                            );
+                        }
+                    });
+                    (
+                        page.parts_for(&nonce),
+                        cached_page(page, &csrf_token, &nonce),
+                    )
+                }
+                IsrLookup::Miss => {
+                    let (parts, html) = render_app(
+                        &options,
+                        &assets,
+                        &runtime_config,
+                        app_fn,
+                        req,
+                        csrf_token.clone(),
+                        nonce.clone(),
+                        None,
+                    )
+                    .await;
+                    // the page is kept as it's streamed, if it can be cached
+                    let html: Pin<Box<dyn Stream<Item = String>>> = if parts.revalidate().is_some()
+                    {
+                        let page = Rc::new(RefCell::new(String::new()));
+                        let store = {
+                            let page = Rc::clone(&page);
+                            let parts = parts.clone();
+                            async move {
+                                cache.insert(
+                                    &url,
+                                    CachedPage {
+                                        html: page.take(),
+                                        csrf_token,
+                                        nonce,
+                                        parts,
+                                    },
+                                );
+                                None
+                            }
+                        };
+                        Box::pin(
+                            html.inspect(move |html| page.borrow_mut().push_str(html))
+                                .chain(
+                                    futures::stream::once(store)
+                                        .filter_map(|html: Option<String>| async move { html }),
+                                ),
+                        )
+                    } else {
+                        Box::pin(html)
+                    };
+                    (parts, html)
+                }
             };
 
-            let head = format!(
-                r#"<!DOCTYPE html>
-                <html lang="en">
-                    <head>
-                        <meta charset="utf-8"/>
-                        <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                        <script type="module">import init, {{ hydrate }} from '{pkg_path}.js'; init().then(hydrate);</script>
-                        {leptos_autoreload}
-                        "#
+            let mut res = HttpResponse::Ok().content_type("text/html").streaming(
+                html.flat_map(move |html| futures::stream::iter(chunk_html(html, chunk_size)))
+                    .map(|html| Ok(html) as Result<web::Bytes>),
             );
+            apply_response_options(&mut res, parts);
+            if let Some(Ok(cookie)) = csrf_cookie.map(http::header::HeaderValue::try_from) {
+                res.headers_mut().append(http::header::SET_COOKIE, cookie);
+            }
+            res
+        }
+    })
+}
 
-            let tail = "</body></html>";
-
-            HttpResponse::Ok().content_type("text/html").streaming(
-                futures::stream::once(async move { head.clone() })
-                    // TODO this leaks a runtime once per invocation
-                    .chain(render_to_stream(move |cx| {
-                        let app = app(cx);
-                        let head = use_context::<MetaContext>(cx)
-                            .map(|meta| meta.dehydrate())
-                            .unwrap_or_default();
-                        format!("{head}</head><body>{app}")
-                    }))
-                    .chain(futures::stream::once(async { tail.to_string() }))
-                    .map(|html| Ok(web::Bytes::from(html)) as Result<web::Bytes>),
-            )
+/// The HTML of a page from an [IsrCache], as it's served to the user with the given token and
+/// [Nonce].
+fn cached_page(
+    page: CachedPage,
+    csrf_token: &CsrfToken,
+    nonce: &Nonce,
+) -> Pin<Box<dyn Stream<Item = String>>> {
+    Box::pin(futures::stream::once(futures::future::ready(
+        page.html_for(csrf_token, nonce),
+    )))
+}
+
+/// Reads the [CsrfToken] from the cookie of a request, if it sent one.
+fn csrf_cookie(req: &HttpRequest) -> Option<CsrfToken> {
+    req.cookie(CSRF_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+        .filter(|token| !token.is_empty())
+        .map(CsrfToken::from)
+}
+
+/// The values of a header of a request, joined like those of `Cookie`, if it sent any.
+fn header_value(req: &HttpRequest, name: &str) -> Option<String> {
+    let values = req
+        .headers()
+        .get_all(name)
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+    (!values.is_empty()).then(|| values.join("; "))
+}
+
+/// Renders the app for the given request, returning the status code and headers it sets, once
+/// the shell of the page has been rendered, and the HTML of the page.
+#[allow(clippy::too_many_arguments)]
+async fn render_app(
+    options: &RenderOptions,
+    assets: &AssetPaths,
+    runtime_config: &RuntimeConfig,
+    app_fn: impl Fn(leptos::Scope) -> Element + 'static,
+    req: HttpRequest,
+    csrf_token: CsrfToken,
+    nonce: Nonce,
+    render_cache: Option<RenderCacheContext>,
+) -> (ResponseParts, impl Stream<Item = String>) {
+    let AssetPaths { js, wasm } = assets;
+    let csrf_meta = csrf_token.meta_tag();
+    let nonce_attr = nonce.to_attribute();
+
+    let locale = LocaleRequest {
+        cookie: header_value(&req, "Cookie"),
+        accept_language: header_value(&req, "Accept-Language"),
+    };
+
+    let path = req.path();
+
+    let query = req.query_string();
+    let path = if query.is_empty() {
+        "http://leptos".to_string() + path
+    } else {
+        "http://leptos".to_string() + path + "?" + query
+    };
+
+    let url = path.trim_start_matches("http://leptos").to_string();
+    let response_options = ResponseOptions::default();
+    let manifest = options.islands.then(IslandManifest::new);
+    let server_navigation = options.server_navigation;
+    let no_js_audit = options.no_js_audit && options.environment == RustEnv::DEV;
+    let audit = no_js_audit.then(NoJsAudit::new);
+    let meta = MetaContext::new();
+    let app = {
+        let meta = meta.clone();
+        let runtime_config = runtime_config.clone();
+        let response_options = response_options.clone();
+        let manifest = manifest.clone();
+        let audit = audit.clone();
+        let nonce = nonce.clone();
+        move |cx| {
+            let integration = ServerIntegration { path: path.clone() };
+            provide_context(cx, RouterIntegrationContext::new(integration));
+            provide_context(cx, meta.clone());
+            provide_context(cx, req.clone());
+            provide_context(cx, locale.clone());
+            provide_context(cx, runtime_config.clone());
+            provide_context(cx, csrf_token.clone());
+            provide_context(cx, nonce.clone());
+            provide_context(cx, response_options.clone());
+            if let Some(manifest) = manifest.clone() {
+                provide_context(cx, manifest);
+            }
+            if server_navigation {
+                provide_context(cx, ServerNavigation);
+            }
+            if let Some(audit) = audit.clone() {
+                provide_context(cx, audit);
+            }
+            if let Some(render_cache) = render_cache.clone() {
+                provide_context(cx, render_cache);
+            }
+
+            (app_fn)(cx)
+        }
+    };
+
+    let runtime_config_script = runtime_config.to_script();
+    let reload_url = options.reload_url();
+
+    // no script runs in a no-JS audit
+    let leptos_autoreload = match options.environment {
+        RustEnv::DEV if !no_js_audit => format!(
+            r#"
+                <script crossorigin=""{nonce_attr}>(function () {{
+                    var ws = new WebSocket('{reload_url}');
+                    ws.onmessage = (ev) => {{
+                        console.log(`Reload message: `);
+                        if (ev.data === 'reload') window.location.reload();
+                    }};
+                    ws.onclose = () => console.warn('Autoreload stopped. Manual reload necessary.');
+                }})()
+                </script>
+            "#
+        ),
+        RustEnv::DEV | RustEnv::PROD | RustEnv::TEST => "".to_string(),
+    };
+
+    // with islands or server navigation, the app isn't hydrated, and the WASM is only loaded if
+    // the page has islands
+    let hydrate_script = if options.islands || server_navigation || no_js_audit {
+        String::new()
+    } else {
+        format!(
+            r#"<link rel="modulepreload" href="{js}">
+                <link rel="preload" href="{wasm}" as="fetch" type="application/wasm" crossorigin="">
+                <script type="module"{nonce_attr}>import init, {{ hydrate }} from '{js}'; init('{wasm}').then(hydrate);</script>"#
+        )
+    };
+
+    let head = format!(
+        r#"<!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                {csrf_meta}
+                {hydrate_script}
+                {runtime_config_script}
+                {leptos_autoreload}
+                "#
+    );
+
+    let tail = "</body></html>";
+
+    // TODO this leaks a runtime once per invocation
+    // the meta tags are rendered once blocking resources have resolved
+    let meta_tags = |cx| {
+        let head = use_context::<MetaContext>(cx)
+            .map(|meta| meta.dehydrate())
+            .unwrap_or_default();
+        format!("{head}</head><body>")
+    };
+    let mut app: Pin<Box<dyn Stream<Item = String>>> = match options.ssr_mode {
+        SsrMode::Prioritized => Box::pin(render_to_stream_with_prefix(app, meta_tags)),
+        SsrMode::OutOfOrder => Box::pin(render_to_stream_out_of_order_with_prefix(app, meta_tags)),
+        SsrMode::Async => Box::pin(futures::stream::once(render_to_string_async_with_prefix(
+            app,
+            meta_tags,
+            options.ssr_timeout,
+        ))),
+    };
+    let shell = app.next().await.unwrap_or_default();
+    if no_js_audit {
+        response_options.insert_header("Content-Security-Policy", "script-src 'none'");
+    }
+
+    // the metadata set by each fragment streamed after the head was sent
+    let app = {
+        let nonce = nonce.clone();
+        app.map(move |mut fragment| {
+            if let Some(patch) = meta.head_patch(Some(&nonce)).filter(|_| !no_js_audit) {
+                fragment.push_str(&patch);
+            }
+            fragment
+        })
+    };
+
+    // every island has been rendered once the stream has ended
+    let (js, wasm) = (js.clone(), wasm.clone());
+    let scripts = futures::stream::once(async move {
+        for element in audit.iter().flat_map(NoJsAudit::dead_elements) {
+            leptos::warn!("[no_js_audit] {url}: {element} does nothing without JavaScript");
+        }
+        if no_js_audit {
+            return String::new();
+        }
+        let mut scripts = manifest
+            .map(|manifest| manifest.to_script(&js, &wasm, Some(&nonce)))
+            .unwrap_or_default();
+        if server_navigation {
+            scripts.push_str(&ServerNavigation.to_script(&js, &wasm, Some(&nonce)));
+        }
+        scripts
+    });
+
+    let html = futures::stream::once(async move { head })
+        .chain(futures::stream::once(async move { shell }))
+        .chain(app)
+        .chain(scripts)
+        .chain(futures::stream::once(async { tail.to_string() }));
+    (response_options.parts(), html)
+}
+
+/// Splits a fragment of HTML into chunks of at most `chunk_size` bytes, if a size is set.
+fn chunk_html(html: String, chunk_size: Option<u64>) -> Vec<Bytes> {
+    let html = Bytes::from(html);
+    match chunk_size {
+        Some(size) if size > 0 && html.len() as u64 > size => {
+            let size = size as usize;
+            (0..html.len())
+                .step_by(size)
+                .map(|start| html.slice(start..(start + size).min(html.len())))
+                .collect()
+        }
+        _ => vec![html],
+    }
+}
+
+/// An Actix [Route](actix_web::Route) that serves the `sitemap.xml` of your application, as
+/// generated by [generate_sitemap] from the [Sitemap] that `sitemap_fn` returns. Both are run
+/// again for every request, so the sitemap follows the routes of the app and any URL that
+/// `sitemap_fn` looks up, e.g., in a database.
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+/// use leptos_router::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///     view! {
+///         cx,
+///         <div>
+///             <Router>
+///                 <main>
+///                     <Routes>
+///                         <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                         <Route path="posts/:slug" element=|cx| view! { cx, <p>"Post"</p> }/>
+///                     </Routes>
+///                 </main>
+///             </Router>
+///         </div>
+///     }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// HttpServer::new(|| {
+///     App::new()
+///         .route(
+///             "/sitemap.xml",
+///             leptos_actix::render_sitemap(|cx| view! { cx, <MyApp/> }, || async {
+///                 let slugs = vec!["hello-world".to_string()]; // e.g., from a database
+///                 Sitemap::new("https://example.com").routes(StaticRoutes::new().params(
+///                     "posts/:slug",
+///                     slugs
+///                         .into_iter()
+///                         .map(|slug| params_map! { "slug".to_string() => slug }),
+///                 ))
+///             }),
+///         )
+///         .route("/robots.txt", leptos_actix::render_robots_txt("https://example.com"))
+/// });
+/// # }
+/// ```
+pub fn render_sitemap<Fut>(
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + 'static,
+    sitemap_fn: impl Fn() -> Fut + Clone + 'static,
+) -> Route
+where
+    Fut: std::future::Future<Output = Sitemap> + 'static,
+{
+    web::get().to(move || {
+        let app_fn = app_fn.clone();
+        let sitemap_fn = sitemap_fn.clone();
+        async move {
+            let sitemap = sitemap_fn().await;
+            match generate_sitemap(app_fn, &sitemap).await {
+                Ok(xml) => HttpResponse::Ok().content_type("application/xml").body(xml),
+                Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+            }
         }
     })
 }
+
+/// An Actix [Route](actix_web::Route) that serves the `robots.txt` of your application served
+/// at `base_url`, as given by [robots_txt], which points crawlers to the `sitemap.xml` served
+/// by [render_sitemap].
+pub fn render_robots_txt(base_url: &str) -> Route {
+    let robots = robots_txt(base_url);
+    web::get().to(move || {
+        let robots = robots.clone();
+        async move { HttpResponse::Ok().content_type("text/plain").body(robots) }
+    })
+}
+
+/// An Actix [Route](actix_web::Route) that serves the static files in `dir`, following the
+/// `precompress`, `static_cache_control`, and `wasm_cache_control` settings in [RenderOptions].
+///
+/// The remainder of the URL is read from a `{tail:.*}` segment and resolved relative to `dir`:
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// App::new()
+///     .route("/pkg/{tail:.*}", leptos_actix::file_handler(render_options.clone(), "./pkg"))
+///     .route("/static/{tail:.*}", leptos_actix::file_handler(render_options, "./static"));
+/// # }
+/// ```
+pub fn file_handler(options: RenderOptions, dir: impl Into<std::path::PathBuf>) -> Route {
+    let dir = dir.into();
+    web::get().to(move |req: HttpRequest| {
+        let options = options.clone();
+        let dir = dir.clone();
+        async move {
+            let path = req.match_info().query("tail").to_string();
+            let accept_encoding = req
+                .headers()
+                .get("Accept-Encoding")
+                .and_then(|value| value.to_str().ok());
+            let file = match options.static_file(&dir, &path, accept_encoding) {
+                Some(file) => file,
+                None => return HttpResponse::NotFound().body("Not found"),
+            };
+            let content_type = mime_guess::from_path(&path).first_or_octet_stream();
+
+            let file_path = file.path.clone();
+            match web::block(move || std::fs::read(file_path)).await {
+                Ok(Ok(data)) => {
+                    let mut res = HttpResponse::Ok();
+                    res.content_type(content_type.as_ref());
+                    if let Some(encoding) = file.content_encoding {
+                        res.insert_header(("Content-Encoding", encoding));
+                    }
+                    if options.precompress {
+                        res.insert_header(("Vary", "Accept-Encoding"));
+                    }
+                    if let Some(cache_control) = file.cache_control {
+                        res.insert_header(("Cache-Control", cache_control));
+                    }
+                    res.body(data)
+                }
+                Ok(Err(e)) => HttpResponse::InternalServerError().body(e.to_string()),
+                Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+            }
+        }
+    })
+}
+
+/// Loads the certificate and private key configured in [RenderOptions::tls_cert_path] and
+/// [RenderOptions::tls_key_path] into a [rustls::ServerConfig] that can be passed to
+/// [HttpServer::bind_rustls](actix_web::HttpServer::bind_rustls). Returns `Ok(None)` if TLS
+/// has not been configured.
+///
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     let render_options = get_configuration(None).unwrap();
+///     let addr = render_options.socket_address;
+///     let tls = leptos_actix::tls_config(&render_options)?;
+///
+///     let server = HttpServer::new(move || App::new());
+///     match tls {
+///         Some(tls) => server.bind_rustls(addr, tls)?,
+///         None => server.bind(addr)?,
+///     }
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+#[cfg(feature = "rustls")]
+pub fn tls_config(options: &RenderOptions) -> std::io::Result<Option<rustls::ServerConfig>> {
+    use std::{
+        fs::File,
+        io::{BufReader, Error, ErrorKind},
+    };
+
+    let (cert_path, key_path) = match options.tls_paths() {
+        Some(paths) => paths,
+        None => return Ok(None),
+    };
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    let key = rustls_pemfile::read_all(&mut BufReader::new(File::open(key_path)?))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("no private key found in {}", key_path.display()),
+            )
+        })?;
+
+    rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map(Some)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // not actix's `Scope`, which the glob import of `actix_web` brings in too
+    use leptos::Scope;
+
+    #[component]
+    fn Greeting(cx: Scope) -> Element {
+        let locale = use_locale(cx);
+        view! { cx, <p>{move || if locale.get() == "de" { "Hallo" } else { "Hello" }}</p> }
+    }
+
+    #[component]
+    fn LocalizedApp(cx: Scope) -> Element {
+        view! {
+            cx,
+            <div>
+                <Router locales=&["en", "de"]>
+                    <main>
+                        <Routes>
+                            <Route path="about" revalidate=60 element=|cx| view! { cx, <Greeting/> }/>
+                        </Routes>
+                    </main>
+                </Router>
+            </div>
+        }
+    }
+
+    fn render_options() -> RenderOptions {
+        RenderOptions::builder()
+            .pkg_path("/pkg/app")
+            .socket_address(std::net::SocketAddr::from(([127, 0, 0, 1], 3000)))
+            .reload_port(3001)
+            .environment(&Ok("prod".to_string()))
+            .build()
+    }
+
+    #[actix_web::test]
+    async fn isr_doesnt_share_pages_rendered_in_a_detected_locale() {
+        let app = actix_web::test::init_service(App::new().default_service(
+            render_app_with_isr(render_options(), |cx| view! { cx, <LocalizedApp/> }),
+        ))
+        .await;
+        let get = |path: &'static str, accept_language: &'static str| {
+            let req = actix_web::test::TestRequest::get()
+                .uri(path)
+                .insert_header(("Accept-Language", accept_language))
+                .to_request();
+            let res = actix_web::test::call_service(&app, req);
+            async move {
+                let res = res.await;
+                let vary = res.headers().get("Vary").cloned();
+                let html = actix_web::test::read_body(res).await;
+                (String::from_utf8(html.to_vec()).unwrap(), vary)
+            }
+        };
+
+        // without a locale prefix, each visitor gets their own language
+        let (html, vary) = get("/about", "de").await;
+        assert!(html.contains("Hallo"));
+        assert_eq!(vary.unwrap(), "Accept-Language, Cookie");
+        let (html, _) = get("/about", "en").await;
+        assert!(html.contains("Hello"));
+
+        // with one, the page is the same for everyone, and is cached
+        let (html, vary) = get("/de/about", "de").await;
+        assert!(html.contains("Hallo") && vary.is_none());
+        let (html, _) = get("/de/about", "en").await;
+        assert!(html.contains("Hallo"));
+    }
+}
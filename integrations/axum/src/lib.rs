@@ -1,19 +1,35 @@
 use axum::{
-    body::{Body, Bytes, Full, StreamBody},
-    extract::Path,
-    http::{HeaderMap, HeaderValue, Request, StatusCode},
+    body::{boxed, Body, BoxBody, Bytes, Full, StreamBody},
+    extract::{FromRequest, FromRequestParts, Path},
+    http::{request::Parts, HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode},
     response::{IntoResponse, Response},
 };
 use futures::{Future, SinkExt, Stream, StreamExt};
 use leptos::*;
 use leptos_meta::MetaContext;
 use leptos_router::*;
-use std::{io, pin::Pin, sync::Arc};
+use std::{io, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+mod websocket;
 /// An Axum handlers to listens for a request with Leptos server function arguments in the body,
 /// run the server function if found, and return the resulting [Response].
 ///
 /// This provides an `Arc<[Request<Body>](axum::http::Request)>` [Scope](leptos::Scope).
 ///
+/// Server functions declared with `method = "GET"` are called with a `GET` request that sends
+/// the arguments in the query string instead, and their `cache` setting is sent as the
+/// `Cache-Control` header of the response. To call them, route `GET` requests to the handler
+/// as well: `get(handle_server_fns).post(handle_server_fns)`.
+///
+/// All other server functions are only run if the request sends back the
+/// [CsrfToken](leptos::CsrfToken) issued by [render_app_to_stream], unless they opt out with
+/// `csrf = false`; otherwise, the response is `403 Forbidden`.
+///
+/// A `POST` to the [BATCH_PATH](leptos::BATCH_PATH) under the prefix runs the calls of a batch
+/// that a client with [enable_server_fn_batching](leptos::enable_server_fn_batching) sent,
+/// each as if it had been sent on its own, and responds with their results as a JSON array.
+///
 /// This can then be set up at an appropriate route in your application:
 ///
 /// ```
@@ -40,16 +56,94 @@ use std::{io, pin::Pin, sync::Arc};
 /// # }
 pub async fn handle_server_fns(
     Path(fn_name): Path<String>,
-    headers: HeaderMap<HeaderValue>,
-    body: Bytes,
-    // req: Request<Body>,
+    req: Request<Body>,
 ) -> impl IntoResponse {
+    handle_server_fns_inner(fn_name, req, None, None, None).await
+}
+
+/// Returns an Axum handler that works like [handle_server_fns], but enforces the
+/// `max_request_size`, `max_upload_size` and `server_fn_timeout` limits set in [RenderOptions].
+///
+/// A request body larger than `max_request_size`, or a `multipart/form-data` body larger than
+/// `max_upload_size`, is rejected with `413 Payload Too Large` before more of it than the limit
+/// has been read, and a server function that runs longer than `server_fn_timeout` is abandoned
+/// with `504 Gateway Timeout`. Without a limit, Axum applies its own default body limit.
+///
+/// A server function can set its own limits with
+/// `#[server(Name, timeout = "10s", max_body = "2mb")]`, which take the place of these, and
+/// which [handle_server_fns] enforces as well. Either way,
+/// the response holds a [ServerFnError::Timeout] or [ServerFnError::PayloadTooLarge] in the
+/// error type of the function, so that the client can tell them apart from other errors.
+/// ```
+/// use axum::{routing::post, Router};
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let app: Router = Router::new().route(
+///     "/api/*fn_name",
+///     post(leptos_axum::handle_server_fns_with_options(render_options)),
+/// );
+/// # }
+/// ```
+pub fn handle_server_fns_with_options(
+    options: RenderOptions,
+) -> impl Fn(
+    Path<String>,
+    Request<Body>,
+) -> Pin<Box<dyn Future<Output = Response<BoxBody>> + Send + 'static>>
+       + Clone
+       + Send
+       + 'static {
+    move |Path(fn_name): Path<String>, req: Request<Body>| {
+        Box::pin(handle_server_fns_inner(
+            fn_name,
+            req,
+            options.server_fn_timeout,
+            options.max_request_size,
+            options.max_upload_size,
+        ))
+    }
+}
+
+async fn handle_server_fns_inner(
+    fn_name: String,
+    req: Request<Body>,
+    timeout: Option<Duration>,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> Response<BoxBody> {
     // Axum Path extractor doesn't remove the first slash from the path, while Actix does
     let fn_name: String = match fn_name.strip_prefix("/") {
         Some(path) => path.to_string(),
         None => fn_name,
     };
 
+    let (parts, body) = req.into_parts();
+
+    if fn_name == BATCH_PATH && parts.method == Method::POST {
+        let body = match read_body(BATCH_PATH, &parts.headers, body, max_request_size).await {
+            Ok(body) => body,
+            Err(res) => return res,
+        };
+        return handle_server_fn_batch(parts, body, timeout, max_request_size, max_upload_size)
+            .await;
+    }
+
+    let call = match ServerFnCall::new(
+        fn_name,
+        parts,
+        body,
+        timeout,
+        max_request_size,
+        max_upload_size,
+    )
+    .await
+    {
+        Ok(call) => call,
+        Err(res) => return res,
+    };
+
     let (tx, rx) = futures::channel::oneshot::channel();
     std::thread::spawn({
         move || {
@@ -57,70 +151,23 @@ pub async fn handle_server_fns(
                 .expect("couldn't spawn runtime")
                 .block_on({
                     async move {
-                        let res = if let Some(server_fn) = server_fn_by_path(fn_name.as_str()) {
-                            let runtime = create_runtime();
-                            let (cx, disposer) = raw_scope_and_disposer(runtime);
-
-                            // provide request as context in server scope
-                            // provide_context(cx, Arc::new(req));
-
-                            match server_fn(cx, body.as_ref()).await {
-                                Ok(serialized) => {
-                                    // clean up the scope, which we only needed to run the server fn
-                                    disposer.dispose();
-                                    runtime.dispose();
-
-                                    // if this is Accept: application/json then send a serialized JSON response
-                                    let accept_header =
-                                        headers.get("Accept").and_then(|value| value.to_str().ok());
-                                    let mut res = Response::builder();
-
-                                    if accept_header == Some("application/json")
-                                        || accept_header
-                                            == Some("application/x-www-form-urlencoded")
-                                        || accept_header == Some("application/cbor")
-                                    {
-                                        res = res.status(StatusCode::OK);
-                                    }
-                                    // otherwise, it's probably a <form> submit or something: redirect back to the referrer
-                                    else {
-                                        let referer = headers
-                                            .get("Referer")
-                                            .and_then(|value| value.to_str().ok())
-                                            .unwrap_or("/");
-                                        res = res
-                                            .status(StatusCode::SEE_OTHER)
-                                            .header("Location", referer);
-                                    }
-                                    match serialized {
-                                        Payload::Binary(data) => res
-                                            .header("Content-Type", "application/cbor")
-                                            .body(Full::from(data)),
-                                        Payload::Url(data) => res
-                                            .header(
-                                                "Content-Type",
-                                                "application/x-www-form-urlencoded",
-                                            )
-                                            .body(Full::from(data)),
-                                        Payload::Json(data) => res
-                                            .header("Content-Type", "application/json")
-                                            .body(Full::from(data)),
-                                    }
+                        // a streamed response is sent as soon as the function returns, and
+                        // its values are sent from here as they're produced
+                        let (res, streaming) = call.run().await;
+
+                        _ = tx.send(res);
+
+                        if let Some((mut stream, mut chunk_tx, disposer, runtime)) = streaming {
+                            while let Some(chunk) = stream.next().await {
+                                // stop once the client has disconnected
+                                if chunk_tx.send(chunk).await.is_err() {
+                                    break;
                                 }
-                                Err(e) => Response::builder()
-                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                                    .body(Full::from(e.to_string())),
                             }
-                        } else {
-                            Response::builder()
-                                .status(StatusCode::BAD_REQUEST)
-                                .body(Full::from(
-                                    "Could not find a server function at that route.".to_string(),
-                                ))
+                            // the scope is only cleaned up once the stream has ended
+                            disposer.dispose();
+                            runtime.dispose();
                         }
-                        .expect("could not build Response");
-
-                        _ = tx.send(res);
                     }
                 })
         }
@@ -129,16 +176,742 @@ pub async fn handle_server_fns(
     rx.await.unwrap()
 }
 
+/// The values a streaming server function has yet to send, the sender of the body they're
+/// sent in, and the scope the function runs in, which is only cleaned up once they've all been
+/// sent.
+type PendingStream = (
+    ServerStream<String>,
+    futures::channel::mpsc::Sender<String>,
+    ScopeDisposer,
+    RuntimeId,
+);
+
+/// A call of a server function whose request has been checked, and whose arguments have been
+/// read, but which hasn't run yet.
+struct ServerFnCall {
+    fn_name: String,
+    parts: Parts,
+    headers: HeaderMap,
+    body: Bytes,
+    timeout: Option<Duration>,
+    is_get: bool,
+    request_parts: RequestParts,
+}
+
+impl ServerFnCall {
+    /// Checks the method, the size and the CSRF token of a request to call `fn_name`, and reads
+    /// its arguments, or responds with the reason it was refused.
+    async fn new(
+        fn_name: String,
+        parts: Parts,
+        body: Body,
+        timeout: Option<Duration>,
+        max_request_size: Option<u64>,
+        max_upload_size: Option<u64>,
+    ) -> Result<Self, Response<BoxBody>> {
+        let headers = parts.headers.clone();
+
+        // a GET request sends the arguments in the query string
+        let is_get = parts.method == Method::GET;
+        if is_get && server_fn_method(&fn_name) != Some(HttpMethod::Get) {
+            return Err(Response::builder()
+                .status(StatusCode::METHOD_NOT_ALLOWED)
+                .body(boxed(Full::from(
+                    "This server function must be called with POST.".to_string(),
+                )))
+                .expect("could not build Response"));
+        }
+        let info = server_fn_info(&fn_name);
+        let max_size = body_limit(&headers, &fn_name, max_request_size, max_upload_size);
+        let timeout = info.as_ref().and_then(|info| info.timeout).or(timeout);
+        let body = if is_get {
+            Bytes::from(parts.uri.query().unwrap_or_default().to_string())
+        } else {
+            read_body(&fn_name, &headers, body, max_size).await?
+        };
+        // the body of a POST request was read up to the limit, but the arguments of a GET
+        // request are in the query string
+        if let Some(max) = max_size {
+            if body.len() as u64 > max {
+                return Err(payload_too_large(&fn_name, max));
+            }
+        }
+
+        let request_parts = request_parts(&headers, fn_name.clone());
+        let check_csrf = !is_get && info.map_or(false, |info| info.csrf);
+        if check_csrf {
+            if let Err(e) = verify_csrf_token(parts.method.as_str(), &request_parts, body.as_ref()) {
+                return Err(Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(boxed(Full::from(e.to_string())))
+                    .expect("could not build Response"));
+            }
+        }
+
+        Ok(Self {
+            fn_name,
+            parts,
+            headers,
+            body,
+            timeout,
+            is_get,
+            request_parts,
+        })
+    }
+
+    /// Runs the server function, and responds with its result. As the future the function
+    /// returns isn't `Send`, this has to run on a single thread.
+    async fn run(self) -> (Response<BoxBody>, Option<PendingStream>) {
+        let Self {
+            fn_name,
+            parts,
+            headers,
+            body,
+            timeout,
+            is_get,
+            request_parts,
+        } = self;
+        let mut streaming = None;
+        let res = if let Some(server_fn) = server_fn_by_path(fn_name.as_str()) {
+            let runtime = create_runtime();
+            let (cx, disposer) = raw_scope_and_disposer(runtime);
+
+            // provide request as context in server scope, so values can be
+            // extracted from it
+            provide_context(cx, ServerFnRequest(Arc::new(Mutex::new(parts))));
+            // and the parts of it that server function middleware can inspect
+            provide_context(cx, request_parts);
+            // and the options it can set for the response
+            let response_options = ResponseOptions::default();
+            provide_context(cx, response_options.clone());
+
+            let result = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, server_fn(cx, body.as_ref())).await,
+                None => Ok(server_fn(cx, body.as_ref()).await),
+            };
+            let res = match result {
+                Ok(Ok(Payload::Stream(stream))) => {
+                    let sse = headers.get("Accept").and_then(|value| value.to_str().ok())
+                        == Some("text/event-stream");
+                    let (chunk_tx, chunk_rx) = futures::channel::mpsc::channel(8);
+                    streaming = Some((stream.into_body(sse), chunk_tx, disposer, runtime));
+                    Response::builder()
+                        .status(StatusCode::OK)
+                        .header(
+                            "Content-Type",
+                            if sse {
+                                "text/event-stream"
+                            } else {
+                                "application/x-ndjson"
+                            },
+                        )
+                        .body(boxed(StreamBody::new(chunk_rx.map(Ok::<_, io::Error>))))
+                }
+                Ok(Ok(serialized)) => {
+                    // clean up the scope, which we only needed to run the server fn
+                    disposer.dispose();
+                    runtime.dispose();
+
+                    // if this is Accept: application/json then send a serialized JSON response
+                    let accept_header = headers.get("Accept").and_then(|value| value.to_str().ok());
+                    let mut res = Response::builder();
+
+                    if is_get
+                        || accept_header == Some("application/json")
+                        || accept_header == Some("application/x-www-form-urlencoded")
+                        || accept_header == Some("application/cbor")
+                        || matches!(
+                            serialized,
+                            Payload::Codec(content_type, _)
+                                if accept_header == Some(content_type)
+                        )
+                    {
+                        res = res.status(StatusCode::OK);
+                    }
+                    // otherwise, it's probably a <form> submit or something: redirect back to the referrer
+                    else {
+                        let referer = headers
+                            .get("Referer")
+                            .and_then(|value| value.to_str().ok())
+                            .unwrap_or("/");
+                        res = res
+                            .status(StatusCode::SEE_OTHER)
+                            .header("Location", referer);
+                    }
+                    if let Some(cache_control) =
+                        server_fn_cache_control(&fn_name).filter(|_| is_get)
+                    {
+                        res = res.header("Cache-Control", cache_control);
+                    }
+                    match serialized {
+                        Payload::Binary(data) => res
+                            .header("Content-Type", "application/cbor")
+                            .body(boxed(Full::from(data))),
+                        Payload::Url(data) => res
+                            .header("Content-Type", "application/x-www-form-urlencoded")
+                            .body(boxed(Full::from(data))),
+                        Payload::Json(data) => res
+                            .header("Content-Type", "application/json")
+                            .body(boxed(Full::from(data))),
+                        Payload::Codec(content_type, data) => res
+                            .header("Content-Type", content_type)
+                            .body(boxed(Full::from(data))),
+                        Payload::Stream(_) => {
+                            unreachable!("streams are sent above")
+                        }
+                    }
+                }
+                Ok(Err(e)) => {
+                    disposer.dispose();
+                    runtime.dispose();
+                    report_error(&e, ErrorLocation::ServerFn(fn_name.clone()));
+                    Ok(error_response(e))
+                }
+                Err(_) => {
+                    disposer.dispose();
+                    runtime.dispose();
+                    let e = ServerFnError::Timeout(timeout.unwrap_or_default());
+                    report_error(&e, ErrorLocation::ServerFn(fn_name.clone()));
+                    match server_fn_error_response(&fn_name, e) {
+                        Some(e) => Ok(error_response(e)),
+                        None => Response::builder()
+                            .status(StatusCode::GATEWAY_TIMEOUT)
+                            .body(boxed(Full::from("Server function timed out.".to_string()))),
+                    }
+                }
+            };
+            res.map(|mut res| {
+                apply_response_options(&mut res, response_options.parts());
+                res
+            })
+        } else {
+            Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(boxed(Full::from(
+                    "Could not find a server function at that route.".to_string(),
+                )))
+        }
+        .expect("could not build Response");
+
+        (res, streaming)
+    }
+}
+
+/// The limit on the size of the body of a call of `fn_name`: the one the function sets for
+/// itself, if any, or else the global one for uploads or other requests.
+fn body_limit(
+    headers: &HeaderMap,
+    fn_name: &str,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> Option<u64> {
+    let is_upload = headers
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with("multipart/form-data"));
+    let max_size = if is_upload {
+        max_upload_size.or(max_request_size)
+    } else {
+        max_request_size
+    };
+    server_fn_info(fn_name)
+        .and_then(|info| info.max_body_size)
+        .or(max_size)
+}
+
+/// Reads the body of a call of `fn_name`, but no more than `max_size` bytes of it, so that a
+/// client can't make the server buffer a body of any size. A body that declares a larger
+/// `Content-Length` is rejected before any of it is read. Without a limit, the body is read
+/// with Axum's own default limit.
+async fn read_body(
+    fn_name: &str,
+    headers: &HeaderMap,
+    body: Body,
+    max_size: Option<u64>,
+) -> Result<Bytes, Response<BoxBody>> {
+    let max = match max_size {
+        Some(max) => max,
+        None => {
+            return Bytes::from_request(Request::new(body), &())
+                .await
+                .map_err(IntoResponse::into_response)
+        }
+    };
+    let content_length = headers
+        .get("Content-Length")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(len) = content_length {
+        if len > max {
+            return Err(payload_too_large(fn_name, max));
+        }
+    }
+    let limit = usize::try_from(max).unwrap_or(usize::MAX);
+    hyper::body::to_bytes(http_body::Limited::new(body, limit))
+        .await
+        .map_err(|e| {
+            if e.is::<http_body::LengthLimitError>() {
+                payload_too_large(fn_name, max)
+            } else {
+                Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(boxed(Full::from(e.to_string())))
+                    .expect("could not build Response")
+            }
+        })
+}
+
+/// Responds to a call of `fn_name` whose body is larger than `max` bytes.
+fn payload_too_large(fn_name: &str, max: u64) -> Response<BoxBody> {
+    match server_fn_error_response(fn_name, ServerFnError::PayloadTooLarge(max)) {
+        Some(e) => error_response(e),
+        None => Response::builder()
+            .status(StatusCode::PAYLOAD_TOO_LARGE)
+            .body(boxed(Full::from(format!(
+                "Request body is larger than the limit of {max} bytes."
+            ))))
+            .expect("could not build Response"),
+    }
+}
+
+/// Runs the calls of a batch that a client with [enable_server_fn_batching] sent, each as if
+/// it had been sent in a request of its own, and responds with their results in order.
+///
+/// The calls were made by the same page, so each is checked with the headers of the batch,
+/// e.g., with its cookies and its CSRF token; only the headers that describe the body are its
+/// own. As the futures of server functions aren't `Send`, the calls run together on a single
+/// thread of the blocking pool of the current runtime.
+async fn handle_server_fn_batch(
+    parts: Parts,
+    body: Bytes,
+    timeout: Option<Duration>,
+    max_request_size: Option<u64>,
+    max_upload_size: Option<u64>,
+) -> Response<BoxBody> {
+    let calls = match parse_server_fn_batch(&body) {
+        Ok(calls) => calls,
+        Err(e) => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(boxed(Full::from(e.to_string())))
+                .expect("could not build Response")
+        }
+    };
+
+    let mut checked = Vec::with_capacity(calls.len());
+    for call in calls {
+        // a batch can't hold another one
+        if call.path == BATCH_PATH {
+            checked.push(Err(BatchedResult {
+                status: StatusCode::BAD_REQUEST.as_u16(),
+                body: "Batches can't be nested.".to_string(),
+            }));
+            continue;
+        }
+        let (mut call_parts, _) = Request::post(parts.uri.clone())
+            .body(())
+            .expect("could not build Request")
+            .into_parts();
+        call_parts.headers = parts.headers.clone();
+        call_parts.headers.remove("Content-Length");
+        call_parts.headers.insert(
+            "Content-Type",
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        call_parts
+            .headers
+            .insert("Accept", HeaderValue::from_static("application/json"));
+        let call = ServerFnCall::new(
+            call.path,
+            call_parts,
+            Body::from(call.args),
+            timeout,
+            max_request_size,
+            max_upload_size,
+        )
+        .await;
+        checked.push(match call {
+            Ok(call) => Ok(call),
+            Err(res) => Err(batched_result(res).await),
+        });
+    }
+
+    let runtime = tokio::runtime::Handle::current();
+    let results = tokio::task::spawn_blocking(move || {
+        runtime.block_on(tokio::task::LocalSet::new().run_until(async move {
+            let results = checked.into_iter().map(|call| async move {
+                match call {
+                    Ok(call) => {
+                        let (res, streaming) = call.run().await;
+                        // streaming functions aren't batched, but if one is, its values are
+                        // dropped, which ends the body of its response
+                        if let Some((_, _, disposer, runtime)) = streaming {
+                            disposer.dispose();
+                            runtime.dispose();
+                        }
+                        batched_result(res).await
+                    }
+                    Err(result) => result,
+                }
+            });
+            futures::future::join_all(results).await
+        }))
+    })
+    .await;
+
+    match results {
+        Ok(results) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json")
+            .body(boxed(Full::from(encode_server_fn_batch(&results))))
+            .expect("could not build Response"),
+        Err(e) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(boxed(Full::from(e.to_string())))
+            .expect("could not build Response"),
+    }
+}
+
+/// The status and the body of the response to a call in a batch.
+async fn batched_result(res: Response<BoxBody>) -> BatchedResult {
+    let status = res.status().as_u16();
+    let body = hyper::body::to_bytes(res.into_body())
+        .await
+        .unwrap_or_default();
+    BatchedResult {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
+
+/// The request that called a server function, which values are extracted from with
+/// [extract].
+#[derive(Clone)]
+struct ServerFnRequest(Arc<Mutex<Parts>>);
+
+/// Responds with an error of a server function, which the body holds so that the client can
+/// deserialize it.
+fn error_response(e: ServerFnErrorResponse) -> Response<BoxBody> {
+    let res = Response::builder()
+        .status(StatusCode::from_u16(e.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR));
+    match e.payload {
+        Payload::Binary(data) => res
+            .header("Content-Type", "application/cbor")
+            .body(boxed(Full::from(data))),
+        Payload::Url(data) | Payload::Json(data) => res
+            .header("Content-Type", "application/json")
+            .body(boxed(Full::from(data))),
+        Payload::Codec(content_type, data) => res
+            .header("Content-Type", content_type)
+            .body(boxed(Full::from(data))),
+        Payload::Stream(_) => unreachable!("errors aren't streamed"),
+    }
+    .expect("could not build Response")
+}
+
+/// Applies the status code and headers a server function, or the app while it was rendered,
+/// set with [ResponseOptions].
+fn apply_response_options<B>(res: &mut Response<B>, options: ResponseParts) {
+    if let Some(status) = options
+        .status
+        .and_then(|status| StatusCode::from_u16(status).ok())
+    {
+        *res.status_mut() = status;
+    }
+    // headers that were set replace the ones with the same name
+    for (name, _) in &options.headers {
+        if let Ok(name) = HeaderName::try_from(name.as_str()) {
+            res.headers_mut().remove(name);
+        }
+    }
+    for (name, value) in options.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            res.headers_mut().append(name, value);
+        }
+    }
+}
+
+/// Extracts a value from the request that called the server function running in `cx`, using
+/// any of Axum's [extractors](axum::extract) that implement [FromRequestParts], like
+/// [HeaderMap], [ConnectInfo](axum::extract::ConnectInfo), [Query](axum::extract::Query) or
+/// [Extension](axum::Extension) for the data that authentication middleware attached to the
+/// request. As the body has been read to get the arguments of the function, extractors that
+/// read the body can't be used.
+///
+/// This works the same way as `leptos_actix::extract`, so that a server function can be
+/// used with either integration by switching the import.
+/// ```
+/// use axum::http::HeaderMap;
+/// use leptos::*;
+///
+/// #[server(UserAgent)]
+/// pub async fn user_agent(cx: Scope) -> Result<String, ServerFnError> {
+///     let headers: HeaderMap = leptos_axum::extract(cx).await?;
+///     Ok(headers
+///         .get("User-Agent")
+///         .and_then(|value| value.to_str().ok())
+///         .unwrap_or("unknown")
+///         .to_string())
+/// }
+/// ```
+pub async fn extract<T>(cx: Scope) -> Result<T, ServerFnError>
+where
+    T: FromRequestParts<()>,
+    T::Rejection: std::fmt::Display,
+{
+    let req = use_context::<ServerFnRequest>(cx).ok_or_else(|| {
+        ServerFnError::ServerError("the request is missing from the context".to_string())
+    })?;
+    let mut parts = req.0.lock().await;
+    T::from_request_parts(&mut parts, &())
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.to_string()))
+}
+
+/// Sends the values of a stream as
+/// [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+/// serialized as JSON, so that a page can subscribe to them with
+/// [create_sse_signal](leptos::create_sse_signal). The stream ends at the first value that
+/// can't be serialized.
+/// ```
+/// use axum::{routing::get, Router};
+/// use std::time::Duration;
+///
+/// async fn visitors() -> axum::response::Response {
+///     let counts = futures::stream::unfold(0u32, |count| async move {
+///         tokio::time::sleep(Duration::from_secs(1)).await;
+///         Some((count, count + 1))
+///     });
+///     leptos_axum::sse_response(counts)
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let app: Router = Router::new().route("/events/visitors", get(visitors));
+/// # }
+/// ```
+pub fn sse_response<T: serde::Serialize>(
+    stream: impl Stream<Item = T> + Send + 'static,
+) -> Response<BoxBody> {
+    let events = stream.scan((), |_, value| {
+        futures::future::ready(sse_event(&value).ok())
+    });
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(boxed(StreamBody::new(
+            events.map(|event| Ok::<_, io::Error>(Bytes::from(event))),
+        )))
+        .expect("could not build Response")
+}
+
+/// The parts of a request that server function middleware and channel handlers can inspect.
+fn request_parts(headers: &HeaderMap, path: String) -> RequestParts {
+    RequestParts {
+        path,
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+    }
+}
+
+/// An Axum handler that accepts WebSocket connections for the
+/// [ServerChannel](leptos::ServerChannel)s registered with
+/// [register_server_channel](leptos::register_server_channel), and runs the handler of the
+/// channel for each of them.
+///
+/// The handler runs in a [Scope](leptos::Scope) that the [RequestParts] of the connection are
+/// provided to. The connection is closed once the handler has returned and it has dropped
+/// its senders, or when the client disconnects.
+/// ```
+/// use axum::{routing::get, Router};
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// // "/ws" should match the prefix of the channels
+/// let app: Router = Router::new().route("/ws/*fn_name", get(leptos_axum::handle_server_channels));
+/// # }
+/// ```
+pub async fn handle_server_channels(
+    Path(path): Path<String>,
+    req: Request<Body>,
+) -> Response<BoxBody> {
+    // Axum Path extractor doesn't remove the first slash from the path, while Actix does
+    let path = match path.strip_prefix('/') {
+        Some(path) => path.to_string(),
+        None => path,
+    };
+    let handler = match server_channel_by_path(&path) {
+        Some(handler) => handler,
+        None => {
+            return Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(boxed(Full::from(
+                    "Could not find a server channel at that route.".to_string(),
+                )))
+                .expect("could not build Response")
+        }
+    };
+
+    let headers = req.headers();
+    let is_upgrade = headers
+        .get("Upgrade")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.eq_ignore_ascii_case("websocket"));
+    let key = match headers.get("Sec-WebSocket-Key") {
+        Some(key) if is_upgrade => key,
+        _ => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(boxed(Full::from(
+                    "Expected a WebSocket handshake.".to_string(),
+                )))
+                .expect("could not build Response")
+        }
+    };
+    let accept = websocket::accept_key(key.as_bytes());
+    let request_parts = request_parts(headers, path);
+
+    let on_upgrade = hyper::upgrade::on(req);
+    tokio::spawn(async move {
+        if let Ok(upgraded) = on_upgrade.await {
+            serve_channel(upgraded, handler, request_parts).await;
+        }
+    });
+
+    Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Accept", accept)
+        .body(boxed(Body::empty()))
+        .expect("could not build Response")
+}
+
+/// Passes the messages of a WebSocket connection to and from the handler of a channel.
+async fn serve_channel(
+    upgraded: hyper::upgrade::Upgraded,
+    handler: Arc<ServerChannelHandler>,
+    request_parts: RequestParts,
+) {
+    use tokio::io::AsyncWriteExt;
+    use websocket::{Frame, FrameReader};
+
+    let (reader, mut writer) = tokio::io::split(upgraded);
+    let (incoming_tx, incoming_rx) = futures::channel::mpsc::unbounded::<String>();
+    let (outgoing_tx, outgoing_rx) = futures::channel::mpsc::unbounded::<String>();
+    // replies to the client's control frames, like pongs to its pings
+    let (control_tx, control_rx) = futures::channel::mpsc::unbounded::<Frame>();
+
+    // the handler isn't Send, so it runs in a thread of its own, like server functions
+    std::thread::spawn(move || {
+        tokio::runtime::Runtime::new()
+            .expect("couldn't spawn runtime")
+            .block_on(tokio::task::LocalSet::new().run_until(async move {
+                let runtime = create_runtime();
+                let (cx, disposer) = raw_scope_and_disposer(runtime);
+                provide_context(cx, request_parts);
+                handler(
+                    cx,
+                    ChannelConnection {
+                        incoming: Box::pin(incoming_rx),
+                        outgoing: outgoing_tx,
+                    },
+                )
+                .await;
+                disposer.dispose();
+                runtime.dispose();
+            }));
+    });
+
+    // read the messages of the client
+    tokio::spawn(async move {
+        let mut reader = FrameReader::new(reader);
+        loop {
+            match reader.next().await {
+                Ok(Frame::Text(message)) => {
+                    if incoming_tx.unbounded_send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(Frame::Ping(data)) => _ = control_tx.unbounded_send(Frame::Pong(data)),
+                Ok(Frame::Pong(_)) => {}
+                Ok(Frame::Close) | Err(_) => {
+                    _ = control_tx.unbounded_send(Frame::Close);
+                    break;
+                }
+            }
+        }
+    });
+
+    // the connection is closed once the handler's senders are dropped, or once the client
+    // has closed it
+    let messages = outgoing_rx
+        .map(Frame::Text)
+        .chain(futures::stream::iter([Frame::Close]));
+    let mut frames = futures::stream::select(messages, control_rx);
+    while let Some(frame) = frames.next().await {
+        let is_close = frame == Frame::Close;
+        if writer.write_all(&frame.encode()).await.is_err() || is_close {
+            break;
+        }
+    }
+    _ = writer.shutdown().await;
+}
+
 pub type PinnedHtmlStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
 
+type PinnedHtmlResponse =
+    Pin<Box<dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>> + Send + 'static>>;
+
+type PinnedFullResponse = Pin<Box<dyn Future<Output = Response<Full<Bytes>>> + Send + 'static>>;
+
 /// Returns an Axum [Handler](axum::handler::Handler) that listens for a `GET` request and tries
 /// to route it using [leptos_router], serving an HTML stream of your application.
 ///
 /// The provides a [MetaContext] and a [RouterIntegrationContext] to app’s context before
 /// rendering it, and includes any meta tags injected using [leptos_meta].
 ///
+/// It issues a [CsrfToken](leptos::CsrfToken) in a cookie if the browser doesn't hold one yet,
+/// writes it into the `<head>` and provides it as context, so that [handle_server_fns] can
+/// check that calls to server functions come from the app.
+///
+/// It also provides [ResponseOptions], and sends the status code and headers the app sets
+/// with it while it's rendered, e.g., the `status` of a matched
+/// [Route](leptos_router::Route) like a catch-all `404` page, or a `301` and a `Location`
+/// header to redirect.
+///
 /// The HTML stream is rendered using [render_to_stream], and includes everything described in
-/// the documentation for that function.
+/// the documentation for that function. If the `ssr_mode` of the options is
+/// [SsrMode::OutOfOrder], it's rendered using [render_to_stream_out_of_order] instead, which
+/// sends the shell of the page right away, and the status code and headers along with it. If
+/// it's [SsrMode::Async], the whole page is sent at once with [render_to_string_async], once
+/// every resource has resolved or the `ssr_timeout` of the options has passed. Unless the shell
+/// is sent right away, the `<head>` is rendered once the resources created with
+/// [create_blocking_resource] have resolved, so that the meta tags set from their data, like
+/// the `<title>` of an article, are in the first HTML that is sent. The meta tags set by the
+/// parts of the page that are streamed after it, e.g., under a [Suspense](leptos::Suspense),
+/// are sent along with them, in a small script that adds them to the `<head>`, with
+/// [MetaContext::head_patch].
+///
+/// If the `islands` option is set, the app isn't hydrated as a whole: an [IslandManifest] is
+/// provided as context, and only the components marked with `#[island]` are hydrated, by a
+/// script that is sent at the end of the page and only loads the WASM if the page has islands.
+///
+/// If the `server_navigation` option is set, the app isn't hydrated as a whole either: a
+/// [ServerNavigation] is provided as context, and the script it sends at the end of the page
+/// navigates between pages by fetching each one from the server and swapping its
+/// [Routes](leptos_router::Routes) into the current one, hydrating the islands of the new page.
+///
+/// Every inline script of the page is marked with a new [Nonce] for each response, which is
+/// provided as context, so that the app can send a strict `Content-Security-Policy` header
+/// with [use_nonce] and [ResponseOptions].
+///
+/// If the `no_js_audit` option is set in the [RustEnv::DEV] environment, the page is sent as a
+/// browser without JavaScript gets it: without the script that hydrates it, and with a
+/// `Content-Security-Policy` that keeps the browser from running any other, so that links
+/// navigate and forms post as they would. A [NoJsAudit] is provided as context, and the
+/// interactive elements it records are reported on the console once the page is rendered.
 ///
 /// This can then be set up at an appropriate route in your application:
 /// ```
@@ -179,108 +952,803 @@ pub type PinnedHtmlStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>
 pub fn render_app_to_stream(
     options: RenderOptions,
     app_fn: impl Fn(leptos::Scope) -> Element + Clone + Send + 'static,
-) -> impl Fn(
-    Request<Body>,
-) -> Pin<Box<dyn Future<Output = StreamBody<PinnedHtmlStream>> + Send + 'static>>
-       + Clone
-       + Send
-       + 'static {
+) -> impl Fn(Request<Body>) -> PinnedHtmlResponse + Clone + Send + 'static {
+    stream_app(options, None, app_fn)
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves your application like
+/// [render_app_to_stream], with the given [RenderCache] provided as context, so that the HTML
+/// of each view that is rendered with [cached] is kept there by the path and query of the page,
+/// the place of the view on it, and the key it's given, e.g., the locale, and sent again from
+/// there without rendering the view while it's kept.
+/// ```
+/// use axum::Router;
+/// use leptos::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///     let locale = "en";
+///     view! {
+///         cx,
+///         <main>
+///             {cached(cx, locale, |cx| view! { cx, <p>"Rendered once per locale"</p> })}
+///         </main>
+///     }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let cache = MemoryRenderCache::with_max_age(std::time::Duration::from_secs(600));
+/// let app: Router = Router::new().fallback(leptos_axum::render_app_with_cache(
+///     render_options,
+///     cache,
+///     |cx| view! { cx, <MyApp/> },
+/// ));
+/// # }
+/// ```
+pub fn render_app_with_cache(
+    options: RenderOptions,
+    cache: impl RenderCache,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + Send + 'static,
+) -> impl Fn(Request<Body>) -> PinnedHtmlResponse + Clone + Send + 'static {
+    stream_app(options, Some(Arc::new(cache)), app_fn)
+}
+
+/// Streams the app as [render_app_to_stream] does, with the given [RenderCache], if any.
+fn stream_app(
+    options: RenderOptions,
+    render_cache: Option<Arc<dyn RenderCache>>,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + Send + 'static,
+) -> impl Fn(Request<Body>) -> PinnedHtmlResponse + Clone + Send + 'static {
+    let assets = options
+        .asset_paths()
+        .expect("couldn't resolve the paths of the JS and WASM files");
+    let runtime_config = options.runtime_config();
+
     move |req: Request<Body>| {
         Box::pin({
             let options = options.clone();
+            let assets = assets.clone();
+            let runtime_config = runtime_config.clone();
             let app_fn = app_fn.clone();
+            let render_cache = render_cache
+                .clone()
+                .map(|cache| RenderCacheContext::new(cache, req.uri().to_string()));
             async move {
-                // Need to get the path and query string of the Request
-                let path = req.uri();
-                let query = path.query();
-
-                let full_path;
-                if let Some(query) = query {
-                    full_path = "http://leptos".to_string() + &path.to_string() + "?" + query
-                } else {
-                    full_path = "http://leptos".to_string() + &path.to_string()
+                // issue a CSRF token, unless the browser already holds one
+                let existing_token = csrf_cookie(req.headers());
+                let is_new_token = existing_token.is_none();
+                let csrf_token = existing_token.unwrap_or_default();
+                let csrf_cookie = is_new_token.then(|| csrf_token.cookie());
+
+                let (parts, html) = render_app(
+                    &options,
+                    &assets,
+                    &runtime_config,
+                    app_fn,
+                    full_path(&req),
+                    locale_request(req.headers()),
+                    csrf_token,
+                    Nonce::new(),
+                    render_cache,
+                );
+
+                let chunk_size = options.stream_chunk_size;
+                let stream = html
+                    .flat_map(move |html| futures::stream::iter(chunk_html(html, chunk_size)))
+                    .map(Ok);
+                let mut res = Response::new(StreamBody::new(Box::pin(stream) as PinnedHtmlStream));
+                // the status and headers have to be sent before the body, so the response waits
+                // for the app to be rendered
+                apply_response_options(&mut res, parts.await.unwrap_or_default());
+                if let Some(Ok(cookie)) = csrf_cookie.map(HeaderValue::try_from) {
+                    res.headers_mut().append("Set-Cookie", cookie);
                 }
+                res
+            }
+        })
+    }
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves your application like
+/// [render_app_to_stream], with incremental static regeneration: a page the app sets a
+/// revalidation time for, e.g., with the `revalidate` prop of a [Route](leptos_router::Route),
+/// is rendered once, and then served from an [IsrCache] until that time has passed, after
+/// which it's served once more while it's rendered again in the background.
+///
+/// A page is cached only if it's rendered with a `200 OK` status and sets no cookies and no
+/// `Vary` header, as described in [ResponseParts::revalidate], and is shared between all the
+/// users who request its URL, with the [CsrfToken] of each user in the place of the one it was
+/// rendered with; it shouldn't show anything that depends on who requests it. Any other page is
+/// streamed as it is by [render_app_to_stream].
+///
+/// A [Router](leptos_router::Router) with `locales` renders a URL without a locale prefix in
+/// the locale detected from the `Cookie` and `Accept-Language` headers of each request, and
+/// sends it with `Vary: Accept-Language, Cookie`, so such a page is never cached; only the URLs
+/// with a locale prefix are.
+/// ```
+/// use axum::Router;
+/// use leptos::*;
+/// use leptos_router::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///     view! {
+///         cx,
+///         <div>
+///             <Router>
+///                 <main>
+///                     <Routes>
+///                         // rendered at most once a minute
+///                         <Route path="" revalidate=60 element=|cx| view! { cx, <p>"Home"</p> }/>
+///                     </Routes>
+///                 </main>
+///             </Router>
+///         </div>
+///     }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let app: Router = Router::new()
+///     .fallback(leptos_axum::render_app_with_isr(render_options, |cx| view! { cx, <MyApp/> }));
+/// # }
+/// ```
+pub fn render_app_with_isr(
+    options: RenderOptions,
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + Send + 'static,
+) -> impl Fn(Request<Body>) -> PinnedHtmlResponse + Clone + Send + 'static {
+    let assets = options
+        .asset_paths()
+        .expect("couldn't resolve the paths of the JS and WASM files");
+    let runtime_config = options.runtime_config();
+    let cache = IsrCache::default();
+
+    move |req: Request<Body>| {
+        Box::pin({
+            let options = options.clone();
+            let assets = assets.clone();
+            let runtime_config = runtime_config.clone();
+            let app_fn = app_fn.clone();
+            let cache = cache.clone();
+            async move {
+                // issue a CSRF token, unless the browser already holds one
+                let existing_token = csrf_cookie(req.headers());
+                let is_new_token = existing_token.is_none();
+                let csrf_token = existing_token.unwrap_or_default();
+                let csrf_cookie = is_new_token.then(|| csrf_token.cookie());
+                let nonce = Nonce::new();
 
-                let pkg_path = &options.pkg_path;
-                let socket_ip = &options.socket_address.ip().to_string();
-                let reload_port = options.reload_port;
-
-                let leptos_autoreload = match options.environment {
-                    RustEnv::DEV => format!(
-                        r#"
-                            <script crossorigin="">(function () {{
-                                var ws = new WebSocket('ws://{socket_ip}:{reload_port}/autoreload');
-                                ws.onmessage = (ev) => {{
-                                    console.log(`Reload message: `);
-                                    if (ev.data === 'reload') window.location.reload();
-                                }};
-                                ws.onclose = () => console.warn('Autoreload stopped. Manual reload necessary.');
-                            }})()
-                            </script>
-                        "#
+                let url = full_path(&req);
+                let chunk_size = options.stream_chunk_size;
+                let (parts, stream) = match cache.get(&url) {
+                    IsrLookup::Fresh(page) => (
+                        page.parts_for(&nonce),
+                        cached_page(page, &csrf_token, &nonce),
                     ),
-                    RustEnv::PROD => "".to_string(),
+                    IsrLookup::Stale(page) => {
+                        // the page is rendered again with a token and nonce of its own, as it's
+                        // shared
+                        let page_token = CsrfToken::new();
+                        let page_nonce = Nonce::new();
+                        let (parts, html) = render_app(
+                            &options,
+                            &assets,
+                            &runtime_config,
+                            app_fn,
+                            url.clone(),
+                            locale_request(req.headers()),
+                            page_token.clone(),
+                            page_nonce.clone(),
+                            None,
+                        );
+                        tokio::spawn({
+                            let cache = cache.clone();
+                            let url = url.clone();
+                            async move {
+                                match parts.await {
+                                    Ok(parts) => cache.insert(
+                                        &url,
+                                        CachedPage {
+                                            html: html.collect::<String>().await,
+                                            csrf_token: page_token,
+                                            nonce: page_nonce,
+                                            parts,
+                                        },
+                                    ),
+                                    Err(_) => cache.regeneration_failed(&url),
+                                }
+                            }
+                        });
+                        (
+                            page.parts_for(&nonce),
+                            cached_page(page, &csrf_token, &nonce),
+                        )
+                    }
+                    IsrLookup::Miss => {
+                        let (parts, html) = render_app(
+                            &options,
+                            &assets,
+                            &runtime_config,
+                            app_fn,
+                            url.clone(),
+                            locale_request(req.headers()),
+                            csrf_token.clone(),
+                            nonce.clone(),
+                            None,
+                        );
+                        let parts = parts.await.unwrap_or_default();
+                        // the page is kept as it's streamed, if it can be cached
+                        let stream: Pin<Box<dyn Stream<Item = String> + Send>> =
+                            if parts.revalidate().is_some() {
+                                let page = Arc::new(std::sync::Mutex::new(String::new()));
+                                let store = {
+                                    let page = Arc::clone(&page);
+                                    let parts = parts.clone();
+                                    async move {
+                                        let html = std::mem::take(&mut *page.lock().unwrap());
+                                        cache.insert(
+                                            &url,
+                                            CachedPage {
+                                                html,
+                                                csrf_token,
+                                                nonce,
+                                                parts,
+                                            },
+                                        );
+                                        None
+                                    }
+                                };
+                                Box::pin(
+                                    html.inspect(move |html| page.lock().unwrap().push_str(html))
+                                        .chain(futures::stream::once(store).filter_map(
+                                            |html: Option<String>| async move { html },
+                                        )),
+                                )
+                            } else {
+                                Box::pin(html)
+                            };
+                        (parts, stream)
+                    }
                 };
 
-                let head = format!(
-                    r#"<!DOCTYPE html>
-                    <html lang="en">
-                        <head>
-                            <meta charset="utf-8"/>
-                            <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                            <script type="module">import init, {{ hydrate }} from '{pkg_path}.js'; init().then(hydrate);</script>
-                            {leptos_autoreload}
-                            "#
-                );
-                let tail = "</body></html>";
-
-                let (mut tx, rx) = futures::channel::mpsc::channel(8);
-
-                std::thread::spawn({
-                    let app_fn = app_fn.clone();
-                    move || {
-                        tokio::runtime::Runtime::new()
-                            .expect("couldn't spawn runtime")
-                            .block_on({
-                                let app_fn = app_fn.clone();
-                                async move {
-                                    tokio::task::LocalSet::new()
-                                        .run_until(async {
-                                            let mut shell = Box::pin(render_to_stream({
-                                                let full_path = full_path.clone();
-                                                move |cx| {
-                                                    let integration = ServerIntegration {
-                                                        path: full_path.clone(),
-                                                    };
-                                                    provide_context(
-                                                        cx,
-                                                        RouterIntegrationContext::new(integration),
-                                                    );
-                                                    provide_context(cx, MetaContext::new());
-                                                    let app = app_fn(cx);
-                                                    let head = use_context::<MetaContext>(cx)
-                                                        .map(|meta| meta.dehydrate())
-                                                        .unwrap_or_default();
-                                                    format!("{head}</head><body>{app}")
-                                                }
-                                            }));
-                                            while let Some(fragment) = shell.next().await {
-                                                _ = tx.send(fragment).await;
-                                            }
-                                            tx.close_channel();
-                                        })
-                                        .await;
+                let stream = stream
+                    .flat_map(move |html| futures::stream::iter(chunk_html(html, chunk_size)))
+                    .map(Ok);
+                let mut res = Response::new(StreamBody::new(Box::pin(stream) as PinnedHtmlStream));
+                apply_response_options(&mut res, parts);
+                if let Some(Ok(cookie)) = csrf_cookie.map(HeaderValue::try_from) {
+                    res.headers_mut().append("Set-Cookie", cookie);
+                }
+                res
+            }
+        })
+    }
+}
+
+/// The HTML of a page from an [IsrCache], as it's served to the user with the given token and
+/// [Nonce].
+fn cached_page(
+    page: CachedPage,
+    csrf_token: &CsrfToken,
+    nonce: &Nonce,
+) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+    Box::pin(futures::stream::once(futures::future::ready(
+        page.html_for(csrf_token, nonce),
+    )))
+}
+
+/// The URL the router is given for a request.
+fn full_path(req: &Request<Body>) -> String {
+    // Need to get the path and query string of the Request
+    let path = req.uri();
+    let query = path.query();
+
+    if let Some(query) = query {
+        "http://leptos".to_string() + &path.to_string() + "?" + query
+    } else {
+        "http://leptos".to_string() + &path.to_string()
+    }
+}
+
+/// Renders the app at the given URL on a thread of its own, returning the status code and
+/// headers it sets, once the shell of the page has been rendered, and the HTML of the page.
+#[allow(clippy::too_many_arguments)]
+fn render_app(
+    options: &RenderOptions,
+    assets: &AssetPaths,
+    runtime_config: &RuntimeConfig,
+    app_fn: impl Fn(leptos::Scope) -> Element + Send + 'static,
+    full_path: String,
+    locale: LocaleRequest,
+    csrf_token: CsrfToken,
+    nonce: Nonce,
+    render_cache: Option<RenderCacheContext>,
+) -> (
+    futures::channel::oneshot::Receiver<ResponseParts>,
+    impl Stream<Item = String> + Send,
+) {
+    let AssetPaths { js, wasm } = assets;
+    let csrf_meta = csrf_token.meta_tag();
+    let nonce_attr = nonce.to_attribute();
+    let runtime_config_script = runtime_config.to_script();
+    let reload_url = options.reload_url();
+    let ssr_mode = options.ssr_mode;
+    let ssr_timeout = options.ssr_timeout;
+    let islands = options.islands;
+    let server_navigation = options.server_navigation;
+    let no_js_audit = options.no_js_audit && options.environment == RustEnv::DEV;
+
+    // no script runs in a no-JS audit
+    let leptos_autoreload = match options.environment {
+        RustEnv::DEV if !no_js_audit => format!(
+            r#"
+                <script crossorigin=""{nonce_attr}>(function () {{
+                    var ws = new WebSocket('{reload_url}');
+                    ws.onmessage = (ev) => {{
+                        console.log(`Reload message: `);
+                        if (ev.data === 'reload') window.location.reload();
+                    }};
+                    ws.onclose = () => console.warn('Autoreload stopped. Manual reload necessary.');
+                }})()
+                </script>
+            "#
+        ),
+        RustEnv::DEV | RustEnv::PROD | RustEnv::TEST => "".to_string(),
+    };
+
+    // with islands or server navigation, the app isn't hydrated, and the WASM is only loaded if
+    // the page has islands
+    let hydrate_script = if islands || server_navigation || no_js_audit {
+        String::new()
+    } else {
+        format!(
+            r#"<link rel="modulepreload" href="{js}">
+                <link rel="preload" href="{wasm}" as="fetch" type="application/wasm" crossorigin="">
+                <script type="module"{nonce_attr}>import init, {{ hydrate }} from '{js}'; init('{wasm}').then(hydrate);</script>"#
+        )
+    };
+    let (js, wasm) = (js.clone(), wasm.clone());
+
+    let head = format!(
+        r#"<!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                {csrf_meta}
+                {hydrate_script}
+                {runtime_config_script}
+                {leptos_autoreload}
+                "#
+    );
+    let tail = "</body></html>";
+
+    let (mut tx, rx) = futures::channel::mpsc::channel(8);
+    let (parts_tx, parts_rx) = futures::channel::oneshot::channel::<ResponseParts>();
+    let runtime_config = runtime_config.clone();
+
+    std::thread::spawn(move || {
+        tokio::runtime::Runtime::new()
+            .expect("couldn't spawn runtime")
+            .block_on(async move {
+                tokio::task::LocalSet::new()
+                    .run_until(async {
+                        let response_options = ResponseOptions::default();
+                        let manifest = islands.then(IslandManifest::new);
+                        let audit = no_js_audit.then(NoJsAudit::new);
+                        let url = full_path.trim_start_matches("http://leptos").to_string();
+                        let meta = MetaContext::new();
+                        let app = {
+                            let meta = meta.clone();
+                            let response_options = response_options.clone();
+                            let manifest = manifest.clone();
+                            let audit = audit.clone();
+                            let nonce = nonce.clone();
+                            move |cx| {
+                                let integration = ServerIntegration { path: full_path };
+                                provide_context(cx, RouterIntegrationContext::new(integration));
+                                provide_context(cx, locale);
+                                provide_context(cx, meta);
+                                provide_context(cx, runtime_config);
+                                provide_context(cx, csrf_token);
+                                provide_context(cx, nonce);
+                                provide_context(cx, response_options);
+                                if let Some(manifest) = manifest {
+                                    provide_context(cx, manifest);
                                 }
-                            });
-                    }
-                });
+                                if server_navigation {
+                                    provide_context(cx, ServerNavigation);
+                                }
+                                if let Some(audit) = audit {
+                                    provide_context(cx, audit);
+                                }
+                                if let Some(render_cache) = render_cache {
+                                    provide_context(cx, render_cache);
+                                }
+                                app_fn(cx)
+                            }
+                        };
+                        // the meta tags are rendered once blocking resources have resolved
+                        let meta_tags = |cx| {
+                            let head = use_context::<MetaContext>(cx)
+                                .map(|meta| meta.dehydrate())
+                                .unwrap_or_default();
+                            format!("{head}</head><body>")
+                        };
+                        let mut shell: Pin<Box<dyn Stream<Item = String>>> = match ssr_mode {
+                            SsrMode::Prioritized => {
+                                Box::pin(render_to_stream_with_prefix(app, meta_tags))
+                            }
+                            SsrMode::OutOfOrder => {
+                                Box::pin(render_to_stream_out_of_order_with_prefix(app, meta_tags))
+                            }
+                            SsrMode::Async => Box::pin(futures::stream::once(
+                                render_to_string_async_with_prefix(app, meta_tags, ssr_timeout),
+                            )),
+                        };
+                        let mut parts_tx = Some(parts_tx);
+                        while let Some(fragment) = shell.next().await {
+                            // the first fragment is the app itself, which has set the status of
+                            // its routes by now
+                            if let Some(parts_tx) = parts_tx.take() {
+                                if no_js_audit {
+                                    response_options
+                                        .insert_header("Content-Security-Policy", "script-src 'none'");
+                                }
+                                _ = parts_tx.send(response_options.parts());
+                            }
+                            _ = tx.send(fragment).await;
+                            // the metadata set by what was just streamed, after the head was sent
+                            if let Some(patch) = meta.head_patch(Some(&nonce)).filter(|_| !no_js_audit) {
+                                _ = tx.send(patch).await;
+                            }
+                        }
+                        // every island has been rendered once the stream has ended
+                        if let Some(manifest) = manifest.filter(|_| !no_js_audit) {
+                            _ = tx.send(manifest.to_script(&js, &wasm, Some(&nonce))).await;
+                        }
+                        if server_navigation && !no_js_audit {
+                            _ = tx.send(ServerNavigation.to_script(&js, &wasm, Some(&nonce))).await;
+                        }
+                        for element in audit.iter().flat_map(NoJsAudit::dead_elements) {
+                            leptos::warn!("[no_js_audit] {url}: {element} does nothing without JavaScript");
+                        }
+                        tx.close_channel();
+                    })
+                    .await;
+            });
+    });
+
+    let html = futures::stream::once(async move { head })
+        .chain(rx)
+        .chain(futures::stream::once(async { tail.to_string() }));
+    (parts_rx, html)
+}
+
+/// Reads the [CsrfToken] from the cookie of a request, if it sent one.
+fn csrf_cookie(headers: &HeaderMap) -> Option<CsrfToken> {
+    headers
+        .get_all("Cookie")
+        .iter()
+        .filter_map(|cookies| cookies.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == CSRF_COOKIE && !value.is_empty()).then(|| CsrfToken::from(value.to_string()))
+        })
+}
+
+/// Reads the locale preferences of the user from the headers of a request.
+fn locale_request(headers: &HeaderMap) -> LocaleRequest {
+    let header = |name| {
+        let values = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>();
+        (!values.is_empty()).then(|| values.join("; "))
+    };
+    LocaleRequest {
+        cookie: header("Cookie"),
+        accept_language: header("Accept-Language"),
+    }
+}
+
+/// Splits a fragment of HTML into chunks of at most `chunk_size` bytes, if a size is set.
+fn chunk_html(html: String, chunk_size: Option<u64>) -> Vec<Bytes> {
+    let html = Bytes::from(html);
+    match chunk_size {
+        Some(size) if size > 0 && html.len() as u64 > size => {
+            let size = size as usize;
+            (0..html.len())
+                .step_by(size)
+                .map(|start| html.slice(start..(start + size).min(html.len())))
+                .collect()
+        }
+        _ => vec![html],
+    }
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves the `sitemap.xml` of your
+/// application, as generated by [generate_sitemap] from the [Sitemap] that `sitemap_fn`
+/// returns. Both are run again for every request, on a thread of their own, so the sitemap
+/// follows the routes of the app and any URL that `sitemap_fn` looks up, e.g., in a database.
+/// ```
+/// use axum::{routing::get, Router};
+/// use leptos::*;
+/// use leptos_router::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///     view! {
+///         cx,
+///         <div>
+///             <Router>
+///                 <main>
+///                     <Routes>
+///                         <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                         <Route path="posts/:slug" element=|cx| view! { cx, <p>"Post"</p> }/>
+///                     </Routes>
+///                 </main>
+///             </Router>
+///         </div>
+///     }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let app: Router = Router::new()
+///     .route(
+///         "/sitemap.xml",
+///         get(leptos_axum::render_sitemap(|cx| view! { cx, <MyApp/> }, || async {
+///             let slugs = vec!["hello-world".to_string()]; // e.g., from a database
+///             Sitemap::new("https://example.com").routes(StaticRoutes::new().params(
+///                 "posts/:slug",
+///                 slugs
+///                     .into_iter()
+///                     .map(|slug| params_map! { "slug".to_string() => slug }),
+///             ))
+///         })),
+///     )
+///     .route("/robots.txt", get(leptos_axum::render_robots_txt("https://example.com")));
+/// # }
+/// ```
+pub fn render_sitemap<Fut>(
+    app_fn: impl Fn(leptos::Scope) -> Element + Clone + Send + 'static,
+    sitemap_fn: impl Fn() -> Fut + Clone + Send + 'static,
+) -> impl Fn(Request<Body>) -> PinnedFullResponse + Clone + Send + 'static
+where
+    Fut: Future<Output = Sitemap> + 'static,
+{
+    move |_: Request<Body>| {
+        let app_fn = app_fn.clone();
+        let sitemap_fn = sitemap_fn.clone();
+        Box::pin(async move {
+            let (tx, rx) = futures::channel::oneshot::channel();
+            // the app isn't Send, so it runs in a thread of its own, like server functions
+            std::thread::spawn(move || {
+                tokio::runtime::Runtime::new()
+                    .expect("couldn't spawn runtime")
+                    .block_on(tokio::task::LocalSet::new().run_until(async move {
+                        let sitemap = sitemap_fn().await;
+                        _ = tx.send(generate_sitemap(app_fn, &sitemap).await);
+                    }));
+            });
+
+            match rx.await {
+                Ok(Ok(xml)) => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "application/xml")
+                    .body(Full::from(xml)),
+                Ok(Err(e)) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::from(e.to_string())),
+                Err(_) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::from("the sitemap couldn't be generated")),
+            }
+            .expect("could not build Response")
+        })
+    }
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves the `robots.txt` of your
+/// application served at `base_url`, as given by [robots_txt], which points crawlers to the
+/// `sitemap.xml` served by [render_sitemap].
+pub fn render_robots_txt(
+    base_url: &str,
+) -> impl Fn(Request<Body>) -> PinnedFullResponse + Clone + Send + 'static {
+    let robots = robots_txt(base_url);
+    move |_: Request<Body>| {
+        let robots = robots.clone();
+        Box::pin(async move {
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/plain")
+                .body(Full::from(robots))
+                .expect("could not build Response")
+        })
+    }
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves the static files in `dir`,
+/// following the `precompress`, `static_cache_control`, and `wasm_cache_control` settings
+/// in [RenderOptions].
+///
+/// The request path is resolved relative to `dir`, so this is usually mounted with
+/// `nest_service`, which strips the prefix:
+/// ```
+/// use axum::{routing::get, Router};
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// # let render_options: RenderOptions = todo!();
+/// let app: Router = Router::new()
+///     .nest_service("/pkg", get(leptos_axum::file_handler(render_options.clone(), "./pkg")))
+///     .nest_service("/static", get(leptos_axum::file_handler(render_options, "./static")));
+/// # }
+/// ```
+pub fn file_handler(
+    options: RenderOptions,
+    dir: impl Into<PathBuf>,
+) -> impl Fn(Request<Body>) -> PinnedFullResponse + Clone + Send + 'static {
+    let dir = dir.into();
+    move |req: Request<Body>| {
+        let options = options.clone();
+        let dir = dir.clone();
+        Box::pin(async move {
+            let path = req.uri().path();
+            let accept_encoding = req
+                .headers()
+                .get("Accept-Encoding")
+                .and_then(|value| value.to_str().ok());
+            let file = match options.static_file(&dir, path, accept_encoding) {
+                Some(file) => file,
+                None => {
+                    return Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .body(Full::from("Not found"))
+                        .expect("could not build Response")
+                }
+            };
+            let content_type = mime_guess::from_path(path).first_or_octet_stream();
 
-                let stream = futures::stream::once(async move { head.clone() })
-                    .chain(rx)
-                    .chain(futures::stream::once(async { tail.to_string() }))
-                    .map(|html| Ok(Bytes::from(html)));
-                StreamBody::new(Box::pin(stream) as PinnedHtmlStream)
+            match tokio::fs::read(&file.path).await {
+                Ok(data) => {
+                    let mut res = Response::builder()
+                        .status(StatusCode::OK)
+                        .header("Content-Type", content_type.as_ref());
+                    if let Some(encoding) = file.content_encoding {
+                        res = res.header("Content-Encoding", encoding);
+                    }
+                    if options.precompress {
+                        res = res.header("Vary", "Accept-Encoding");
+                    }
+                    if let Some(cache_control) = &file.cache_control {
+                        res = res.header("Cache-Control", cache_control);
+                    }
+                    res.body(Full::from(data))
+                }
+                Err(e) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Full::from(e.to_string())),
             }
+            .expect("could not build Response")
         })
     }
 }
+
+/// Loads the certificate and private key configured in [RenderOptions::tls_cert_path] and
+/// [RenderOptions::tls_key_path] into an [axum_server] TLS config. Returns `Ok(None)` if TLS
+/// has not been configured.
+///
+/// ```
+/// use axum::Router;
+/// use leptos::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let render_options = get_configuration(None).unwrap();
+///     let addr = render_options.socket_address;
+///     let app = Router::new();
+///
+///     match leptos_axum::tls_config(&render_options).await? {
+///         Some(tls) => {
+///             axum_server::bind_rustls(addr, tls)
+///                 .serve(app.into_make_service())
+///                 .await
+///         }
+///         None => {
+///             axum_server::bind(addr)
+///                 .serve(app.into_make_service())
+///                 .await
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[cfg(feature = "tls")]
+pub async fn tls_config(
+    options: &RenderOptions,
+) -> io::Result<Option<axum_server::tls_rustls::RustlsConfig>> {
+    match options.tls_paths() {
+        Some((cert_path, key_path)) => {
+            axum_server::tls_rustls::RustlsConfig::from_pem_file(cert_path, key_path)
+                .await
+                .map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body::Body as _;
+
+    #[component]
+    fn Greeting(cx: Scope) -> Element {
+        let locale = use_locale(cx);
+        view! { cx, <p>{move || if locale.get() == "de" { "Hallo" } else { "Hello" }}</p> }
+    }
+
+    #[component]
+    fn App(cx: Scope) -> Element {
+        view! {
+            cx,
+            <div>
+                <Router locales=&["en", "de"]>
+                    <main>
+                        <Routes>
+                            <Route path="about" revalidate=60 element=|cx| view! { cx, <Greeting/> }/>
+                        </Routes>
+                    </main>
+                </Router>
+            </div>
+        }
+    }
+
+    fn render_options() -> RenderOptions {
+        RenderOptions::builder()
+            .pkg_path("/pkg/app")
+            .socket_address(std::net::SocketAddr::from(([127, 0, 0, 1], 3000)))
+            .reload_port(3001)
+            .environment(&Ok("prod".to_string()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn isr_doesnt_share_pages_rendered_in_a_detected_locale() {
+        let handler = render_app_with_isr(render_options(), |cx| view! { cx, <App/> });
+        let get = |path: &'static str, accept_language: &'static str| {
+            let handler = handler.clone();
+            async move {
+                let req = Request::get(path)
+                    .header("Accept-Language", accept_language)
+                    .body(Body::empty())
+                    .unwrap();
+                let mut res = handler(req).await;
+                let vary = res.headers().get("Vary").cloned();
+                let mut html = Vec::new();
+                while let Some(chunk) = res.body_mut().data().await {
+                    html.extend_from_slice(&chunk.unwrap());
+                }
+                (String::from_utf8(html).unwrap(), vary)
+            }
+        };
+
+        // without a locale prefix, each visitor gets their own language
+        let (html, vary) = get("/about", "de").await;
+        assert!(html.contains("Hallo"));
+        assert_eq!(vary.unwrap(), "Accept-Language, Cookie");
+        let (html, _) = get("/about", "en").await;
+        assert!(html.contains("Hello"));
+
+        // with one, the page is the same for everyone, and is cached
+        let (html, vary) = get("/de/about", "de").await;
+        assert!(html.contains("Hallo") && vary.is_none());
+        let (html, _) = get("/de/about", "en").await;
+        assert!(html.contains("Hallo"));
+    }
+}
@@ -0,0 +1,132 @@
+//! The parts of the WebSocket protocol ([RFC 6455](https://www.rfc-editor.org/rfc/rfc6455))
+//! that a server needs to answer a server channel: the handshake, and reading and writing
+//! frames over the upgraded connection.
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Appended to the key of the client to compute the key the server accepts it with.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The largest message a client may send, so that it can't make the server buffer without
+/// limit.
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// The value of the `Sec-WebSocket-Accept` header that accepts the `Sec-WebSocket-Key` of
+/// a client.
+pub(crate) fn accept_key(key: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key);
+    hasher.update(HANDSHAKE_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// A message or a control frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Frame {
+    Text(String),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    Close,
+}
+
+impl Frame {
+    /// Encodes the frame as the server sends it: in one piece, and without a mask.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let (opcode, payload) = match self {
+            Frame::Text(text) => (OPCODE_TEXT, text.as_bytes()),
+            Frame::Ping(data) => (OPCODE_PING, data.as_slice()),
+            Frame::Pong(data) => (OPCODE_PONG, data.as_slice()),
+            Frame::Close => (OPCODE_CLOSE, &[][..]),
+        };
+        let mut frame = Vec::with_capacity(payload.len() + 10);
+        frame.push(0x80 | opcode);
+        match payload.len() {
+            len if len < 126 => frame.push(len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(payload);
+        frame
+    }
+}
+
+/// Reads the frames a client sends, joining the frames of a fragmented message.
+pub(crate) struct FrameReader<R> {
+    reader: R,
+    partial: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> FrameReader<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Reads the next complete message or control frame. Binary messages are read as text,
+    /// as channels send JSON. Fails if the connection is lost or the client breaks the
+    /// protocol, after which the connection should be closed.
+    pub(crate) async fn next(&mut self) -> io::Result<Frame> {
+        loop {
+            let mut head = [0; 2];
+            self.reader.read_exact(&mut head).await?;
+            let fin = head[0] & 0x80 != 0;
+            let opcode = head[0] & 0x0F;
+            let masked = head[1] & 0x80 != 0;
+            let len = match head[1] & 0x7F {
+                126 => self.reader.read_u16().await? as u64,
+                127 => self.reader.read_u64().await?,
+                len => len as u64,
+            };
+            // clients have to mask every frame they send
+            if !masked {
+                return Err(protocol_error("received an unmasked frame"));
+            }
+            if len > (MAX_MESSAGE_SIZE - self.partial.len()) as u64 {
+                return Err(protocol_error("message is too large"));
+            }
+            let mut mask = [0; 4];
+            self.reader.read_exact(&mut mask).await?;
+            let mut payload = vec![0; len as usize];
+            self.reader.read_exact(&mut payload).await?;
+            for (i, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[i % 4];
+            }
+
+            match opcode {
+                OPCODE_CLOSE => return Ok(Frame::Close),
+                OPCODE_PING => return Ok(Frame::Ping(payload)),
+                OPCODE_PONG => return Ok(Frame::Pong(payload)),
+                OPCODE_CONTINUATION | OPCODE_TEXT | OPCODE_BINARY => {
+                    self.partial.extend_from_slice(&payload);
+                    if fin {
+                        let message = std::mem::take(&mut self.partial);
+                        return Ok(Frame::Text(String::from_utf8_lossy(&message).into_owned()));
+                    }
+                }
+                _ => return Err(protocol_error("unknown opcode")),
+            }
+        }
+    }
+}
+
+fn protocol_error(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
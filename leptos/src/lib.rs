@@ -82,6 +82,12 @@
 //! - `stable` By default, Leptos requires `nightly` Rust, which is what allows the ergonomics
 //!   of calling signals as functions. If you need to use `stable`, you will need to call `.get()`
 //!   and `.set()` manually.
+//! - `local` Compiles [server](crate::leptos_server) functions into the client as ordinary async
+//!   functions, which run in the same process rather than calling the server over HTTP, e.g., for
+//!   a desktop or Tauri build of an app that also runs on the web.
+//! - `schemars` Generates the JSON schemas of the arguments and return types of [server](crate::leptos_server)
+//!   functions with [schemars](https://docs.rs/schemars), so that [openapi_spec] describes them fully. Those types
+//!   then have to implement `JsonSchema`.
 //! - `serde` (*Default*) In SSR/hydrate mode, uses [serde](https://docs.rs/serde/latest/serde/) to serialize resources and send them
 //!   from the server to the client.
 //! - `serde-lite` In SSR/hydrate mode, uses [serde-lite](https://docs.rs/serde-lite/latest/serde_lite/) to serialize resources and send them
@@ -92,6 +98,8 @@
 //!   to reduce the cost of copying class names, attribute names, attribute values, and properties through JavaScript to the DOM. This feature
 //!   (included by default) makes DOM updates marginally faster and WASM binary size marginally larger. Disabling the feature makes binary sizes
 //!   marginally smaller at the cost of a small decrease in speed.
+//! - `debug-graph` Records the type of every signal, memo, and effect so the whole reactive graph can be
+//!   inspected with [reactive_graph](leptos_reactive::reactive_graph), e.g., by devtools or to track down leaks.
 //!
 //! **Important Note:** You must enable one of `csr`, `hydrate`, or `ssr` to tell Leptos
 //! which mode your app is operating in.
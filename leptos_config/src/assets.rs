@@ -0,0 +1,74 @@
+use crate::{LeptosConfigError, RenderOptions};
+use std::path::{Path, PathBuf};
+
+/// The URLs of the JS and WASM files generated by wasm-bindgen, taking content hashes into
+/// account if [RenderOptions::hash_files] is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetPaths {
+    /// The JS module that loads the WASM, e.g. `/pkg/app.4fa2c1.js`.
+    pub js: String,
+    /// The WASM binary, e.g. `/pkg/app_bg.9e0b1d.wasm`.
+    pub wasm: String,
+}
+
+impl RenderOptions {
+    /// Resolves the URLs of the JS and WASM files for this app.
+    ///
+    /// Without `hash_files`, these are `{pkg_path}.js` and `{pkg_path}_bg.wasm`, the names
+    /// wasm-bindgen uses. With `hash_files`, the hashes are read from the `hash_file_name` file
+    /// next to the server executable, which cargo-leptos writes in the form
+    /// ```text
+    /// js: 4fa2c1
+    /// wasm: 9e0b1d
+    /// ```
+    /// and the files are expected at `{pkg_path}.{js hash}.js` and `{pkg_path}_bg.{wasm hash}.wasm`.
+    ///
+    /// The integrations call this once when creating their handlers, rather than on each request.
+    pub fn asset_paths(&self) -> Result<AssetPaths, LeptosConfigError> {
+        let pkg_path = &self.pkg_path;
+        if !self.hash_files {
+            return Ok(AssetPaths {
+                js: format!("{pkg_path}.js"),
+                wasm: format!("{pkg_path}_bg.wasm"),
+            });
+        }
+
+        let hash_file = self.hash_file_path();
+        let text = std::fs::read_to_string(&hash_file)
+            .map_err(|e| LeptosConfigError::HashFile(hash_file.clone(), e.to_string()))?;
+        let (js, wasm) = parse_hashes(&text).ok_or_else(|| {
+            LeptosConfigError::HashFile(
+                hash_file,
+                "expected a `js: <hash>` and a `wasm: <hash>` line".to_string(),
+            )
+        })?;
+        Ok(AssetPaths {
+            js: format!("{pkg_path}.{js}.js"),
+            wasm: format!("{pkg_path}_bg.{wasm}.wasm"),
+        })
+    }
+
+    fn hash_file_path(&self) -> PathBuf {
+        let hash_file = Path::new(&self.hash_file_name);
+        if hash_file.is_absolute() {
+            return hash_file.to_path_buf();
+        }
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(hash_file)))
+            .unwrap_or_else(|| hash_file.to_path_buf())
+    }
+}
+
+fn parse_hashes(text: &str) -> Option<(String, String)> {
+    let mut js = None;
+    let mut wasm = None;
+    for line in text.lines() {
+        match line.split_once(':') {
+            Some((name, hash)) if name.trim() == "js" => js = Some(hash.trim().to_string()),
+            Some((name, hash)) if name.trim() == "wasm" => wasm = Some(hash.trim().to_string()),
+            _ => {}
+        }
+    }
+    Some((js?, wasm?))
+}
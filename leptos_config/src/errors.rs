@@ -0,0 +1,81 @@
+use std::{fmt, path::PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while loading [RenderOptions](crate::RenderOptions) from a file
+/// or from the environment.
+#[derive(Debug, Clone, Error)]
+pub enum LeptosConfigError {
+    #[error("No config file found. Looked for {0:?}")]
+    ConfigNotFound(Vec<PathBuf>),
+    #[error(
+        "Unsupported config file format for {0:?}. Expected a .toml, .json, .yaml, or .yml file"
+    )]
+    UnsupportedFormat(PathBuf),
+    #[error("Invalid configuration:\n{}", display_list(.0))]
+    Invalid(Vec<InvalidValue>),
+    #[error("Unknown environment {0:?}. Expected `dev`, `prod`, or `test`")]
+    UnknownEnv(String),
+    #[error("Could not read asset hashes from {0:?}: {1}")]
+    HashFile(PathBuf, String),
+    #[error("Config error: {0}")]
+    ConfigError(String),
+}
+
+impl From<config::ConfigError> for LeptosConfigError {
+    fn from(e: config::ConfigError) -> Self {
+        Self::ConfigError(e.to_string())
+    }
+}
+
+fn display_list(errors: &[InvalidValue]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("  {error}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A single key that failed validation, and where its value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// The name of the field, e.g. `socket_address`.
+    pub key: String,
+    /// What was wrong with the value.
+    pub message: String,
+    /// Where the value was set.
+    pub origin: ValueSource,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.key, self.origin, self.message)
+    }
+}
+
+/// Where a config value was set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// The value was not set, so the default was used.
+    Default,
+    /// The value was read from a config file, at the given line if it could be found.
+    File { path: PathBuf, line: Option<usize> },
+    /// The value was read from the named environment variable.
+    Env(String),
+    /// The value was read from the named command-line flag.
+    Arg(String),
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::Default => write!(f, "default"),
+            ValueSource::File {
+                path,
+                line: Some(line),
+            } => write!(f, "{}:{line}", path.display()),
+            ValueSource::File { path, line: None } => write!(f, "{}", path.display()),
+            ValueSource::Env(var) => write!(f, "env var {var}"),
+            ValueSource::Arg(flag) => write!(f, "argument {flag}"),
+        }
+    }
+}
@@ -1,12 +1,42 @@
-use std::{env::VarError, net::SocketAddr, str::FromStr};
+mod assets;
+mod errors;
+mod loader;
+mod runtime_config;
+mod sources;
+mod static_files;
+mod units;
+mod validate;
+mod watch;
+
+pub use assets::AssetPaths;
+pub use errors::{InvalidValue, LeptosConfigError, ValueSource};
+pub use loader::{ConfigLoader, CONFIG_FILE_NAMES};
+pub use runtime_config::{RuntimeConfig, CLIENT_CONFIG_FIELDS, RUNTIME_CONFIG_ELEMENT_ID};
+use serde::{Deserialize, Deserializer};
+pub use sources::{ConfigSource, Secret};
+pub use static_files::StaticFile;
+use std::{
+    collections::BTreeMap,
+    env::VarError,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 use typed_builder::TypedBuilder;
+pub use units::{parse_duration, parse_size};
+pub use watch::{watch_configuration, ConfigWatcher};
 
 /// This struct serves as a convenient place to store details used for rendering.
 /// It's serialized into a file in the root called `.leptos.kdl` for cargo-leptos
 /// to watch. It's also used in our actix and axum integrations to generate the
 /// correct path for WASM, JS, and Websockets. Its goal is to be the single source
 /// of truth for render options
-#[derive(TypedBuilder, Clone)]
+///
+/// When loaded with [get_configuration] or [get_configuration_from_file], each field is
+/// resolved from defaults, then the config file, then its `dev`/`prod` profile, then
+/// environment variables. See [ConfigLoader] for the details.
+#[derive(TypedBuilder, Debug, Clone, Deserialize)]
 pub struct RenderOptions {
     /// The path and name of the WASM and JS files generated by wasm-bindgen
     /// For example, `/pkg/app` might be a valid input if your crate name was `app`.
@@ -15,19 +45,265 @@ pub struct RenderOptions {
     /// Used to control whether the Websocket code for code watching is included.
     /// I recommend passing in the result of `env::var("RUST_ENV")`
     #[builder(setter(into), default)]
+    #[serde(default)]
     pub environment: RustEnv,
     /// Provides a way to control the address leptos is served from.
     /// Using an env variable here would allow you to run the same code in dev and prod
     /// Defaults to `127.0.0.1:3000`
     #[builder(setter(into), default=SocketAddr::from(([127,0,0,1], 3000)))]
+    #[serde(default = "default_socket_address")]
     pub socket_address: SocketAddr,
     /// The port the Websocket watcher listens on. Should match the `reload_port` in cargo-leptos(if using).
     /// Defaults to `3001`
     #[builder(default = 3001)]
+    #[serde(default = "default_reload_port")]
     pub reload_port: u32,
+    /// The port the browser should connect to for the autoreload Websocket, if it differs from
+    /// `reload_port`, e.g. because the dev server is behind a reverse proxy or a forwarded
+    /// port in Codespaces or Gitpod. Defaults to `reload_port`.
+    #[builder(setter(strip_option), default)]
+    #[serde(default)]
+    pub reload_external_port: Option<u32>,
+    /// Whether the autoreload Websocket connects with `ws` or `wss`. Use `wss` when the page is
+    /// served over HTTPS, since browsers block insecure Websockets from secure pages.
+    /// Defaults to `ws`
+    #[builder(default)]
+    #[serde(default)]
+    pub reload_ws_protocol: ReloadWSProtocol,
+    /// The host the browser should connect to for the autoreload Websocket, like
+    /// `my-workspace-3001.app.github.dev`. Defaults to the IP of `socket_address`.
+    #[builder(setter(strip_option, into), default)]
+    #[serde(default)]
+    pub reload_host: Option<String>,
+    /// The path to a PEM-encoded TLS certificate chain. If this and `tls_key_path` are both set,
+    /// the integrations can serve `socket_address` over HTTPS directly, which is useful for
+    /// developing features that need a secure context, like service workers or WebAuthn.
+    #[builder(setter(strip_option, into), default)]
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+    /// The path to the PEM-encoded private key for `tls_cert_path`.
+    #[builder(setter(strip_option, into), default)]
+    #[serde(default)]
+    pub tls_key_path: Option<PathBuf>,
+    /// Whether the WASM and JS files have content-hashed file names, as cargo-leptos can emit
+    /// for cache busting. If set, the hashes are read from `hash_file_name` when generating
+    /// `<script>` and `<link>` tags. See [RenderOptions::asset_paths].
+    /// Defaults to `false`
+    #[builder(default)]
+    #[serde(default)]
+    pub hash_files: bool,
+    /// The file the asset hashes are read from, relative to the server executable.
+    /// Defaults to `hash.txt`
+    #[builder(setter(into), default = default_hash_file_name())]
+    #[serde(default = "default_hash_file_name")]
+    pub hash_file_name: String,
+    /// Whether the integrations' static file handlers should serve a precompressed `.br` or
+    /// `.gz` variant of a file, when one exists next to it and the client accepts it.
+    /// Defaults to `false`
+    #[builder(default)]
+    #[serde(default)]
+    pub precompress: bool,
+    /// The `Cache-Control` header the static file handlers send for most files,
+    /// e.g. `public, max-age=3600`. No header is sent if this is not set.
+    #[builder(setter(strip_option, into), default)]
+    #[serde(default)]
+    pub static_cache_control: Option<String>,
+    /// The `Cache-Control` header the static file handlers send for `.wasm` files and their
+    /// JS glue, which can be cached for much longer when `hash_files` is set.
+    /// Falls back to `static_cache_control` if this is not set.
+    #[builder(setter(strip_option, into), default)]
+    #[serde(default)]
+    pub wasm_cache_control: Option<String>,
+    /// How long a server function may run before the integrations give up on it and respond
+    /// with an error, written like `"30s"` or `"500ms"` in config files. See [parse_duration].
+    #[builder(setter(strip_option), default)]
+    #[serde(default, deserialize_with = "units::deserialize_duration")]
+    pub server_fn_timeout: Option<Duration>,
+    /// The largest request body, in bytes, the integrations will pass to a server function,
+    /// written like `"2mb"` in config files. See [parse_size].
+    #[builder(setter(strip_option), default)]
+    #[serde(default, deserialize_with = "units::deserialize_size")]
+    pub max_request_size: Option<u64>,
+    /// The largest `multipart/form-data` request body, in bytes, the integrations will pass to
+    /// a server function, e.g., one that takes file uploads. Such requests are limited by this
+    /// instead of `max_request_size`, which they fall back to if this is not set.
+    #[builder(setter(strip_option), default)]
+    #[serde(default, deserialize_with = "units::deserialize_size")]
+    pub max_upload_size: Option<u64>,
+    /// The largest chunk, in bytes, the integrations will write at once when streaming HTML,
+    /// written like `"16kb"` in config files. Larger fragments are split. See [parse_size].
+    #[builder(setter(strip_option), default)]
+    #[serde(default, deserialize_with = "units::deserialize_size")]
+    pub stream_chunk_size: Option<u64>,
+    /// How the integrations stream the HTML of each `<Suspense/>`, written as `"prioritized"`,
+    /// `"out-of-order"` or `"async"` in config files. Defaults to [SsrMode::Prioritized].
+    #[builder(default)]
+    #[serde(default)]
+    pub ssr_mode: SsrMode,
+    /// How long the integrations wait for the resources of a page rendered with
+    /// [SsrMode::Async] before they send it with the `fallback` of each `<Suspense/>` that is
+    /// still loading, and let the browser load the rest, written like `"2s"` or `"500ms"` in
+    /// config files. See [parse_duration]. Without it, the page waits for every resource.
+    #[builder(setter(strip_option), default)]
+    #[serde(default, deserialize_with = "units::deserialize_duration")]
+    pub ssr_timeout: Option<Duration>,
+    /// Whether the integrations render the app as static HTML with islands, so that only the
+    /// components marked with `#[island]` are hydrated, each on its own, rather than the whole
+    /// app with its `hydrate` function. Defaults to `false`
+    #[builder(default)]
+    #[serde(default)]
+    pub islands: bool,
+    /// Whether the integrations navigate between the pages of the app by fetching each page from
+    /// the server and swapping its `<Routes/>` into the current one, rather than hydrating the
+    /// app and routing in the WASM, for a multi-page app with islands and as little code in the
+    /// browser as possible. Defaults to `false`
+    #[builder(default)]
+    #[serde(default)]
+    pub server_navigation: bool,
+    /// Whether the integrations render the app as a browser without JavaScript gets it, in the
+    /// [RustEnv::DEV] environment only, to check that it still works: no script is run, so that
+    /// links navigate and forms, like an `<ActionForm/>`, post as they would without the WASM,
+    /// and the interactive elements that would do nothing are reported on the console of the
+    /// server. Defaults to `false`
+    #[builder(default)]
+    #[serde(default)]
+    pub no_js_audit: bool,
+    /// Values that are sent to the client as part of its [RuntimeConfig], like an API base URL
+    /// or feature flags. Set in a `client_config` table, or from environment variables with
+    /// e.g. `ConfigLoader::default().env_var("API_BASE_URL", "client_config.api_base_url")`.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub client_config: BTreeMap<String, String>,
+    /// The names of fields from these options to include in the client's [RuntimeConfig],
+    /// like `["environment"]`. Only the fields in [CLIENT_CONFIG_FIELDS] can be listed.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub client_config_fields: Vec<String>,
+    /// Where each of these values was read from. This is filled in when the options are
+    /// loaded with a [ConfigLoader], and empty otherwise.
+    #[builder(default, setter(skip))]
+    #[serde(skip)]
+    pub sources: ConfigSource,
+}
+
+fn default_socket_address() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 3000))
+}
+
+fn default_reload_port() -> u32 {
+    3001
+}
+
+fn default_hash_file_name() -> String {
+    "hash.txt".to_string()
+}
+
+/// Loads [RenderOptions] from the `[package.metadata.leptos]` section of a `Cargo.toml`.
+///
+/// If `path` is `None`, the current directory and its parents are searched for a `Cargo.toml`
+/// with a `[package.metadata.leptos]` section, up to the workspace root. Settings in
+/// `[workspace.metadata.leptos]` apply to every package in the workspace, and the package’s own
+/// settings override them. Use [ConfigLoader::package] to pick a workspace member by name when
+/// running from the workspace root.
+///
+/// Keys can be written in either `snake_case` or the `kebab-case` usual for Cargo metadata.
+/// A `dev` or `prod` sub-table overrides the base keys when running in that [RustEnv], and
+/// environment variables prefixed with `LEPTOS_` override both. Use a [ConfigLoader] to read
+/// other environment variables.
+///
+/// ```toml
+/// [package.metadata.leptos]
+/// pkg-path = "/pkg/app"
+///
+/// [package.metadata.leptos.dev]
+/// socket-address = "127.0.0.1:3000"
+///
+/// [package.metadata.leptos.prod]
+/// socket-address = "0.0.0.0:80"
+/// ```
+pub fn get_configuration(path: Option<&str>) -> Result<RenderOptions, LeptosConfigError> {
+    ConfigLoader::default().load(path)
+}
+
+/// Loads the [RenderOptions] for one of several sites served by the same binary, from the
+/// `[package.metadata.leptos.sites.<site>]` section of a `Cargo.toml`.
+///
+/// The site’s keys override the base `[package.metadata.leptos]` keys, which can be used for
+/// settings that all sites share. Otherwise, this works like [get_configuration].
+///
+/// ```toml
+/// [package.metadata.leptos]
+/// reload-port = 3001
+///
+/// [package.metadata.leptos.sites.main]
+/// pkg-path = "/pkg/main"
+/// socket-address = "127.0.0.1:3000"
+///
+/// [package.metadata.leptos.sites.admin]
+/// pkg-path = "/pkg/admin"
+/// socket-address = "127.0.0.1:3002"
+/// ```
+pub fn get_configuration_for_site(
+    path: Option<&str>,
+    site: &str,
+) -> Result<RenderOptions, LeptosConfigError> {
+    ConfigLoader::default().site(site).load(path)
+}
+
+/// Loads [RenderOptions] from a plain `leptos.toml`, `leptos.json`, or `leptos.yaml` file,
+/// for deployments that don't ship a `Cargo.toml` (like a Docker `scratch` image).
+///
+/// If `path` is `None`, the current directory is searched for the names in [CONFIG_FILE_NAMES].
+/// The format is detected from the file extension. As with [get_configuration], a `dev` or
+/// `prod` table overrides the base keys for that [RustEnv], and values from the file are then
+/// overridden by any environment variables prefixed with `LEPTOS_`, so
+/// `LEPTOS_SOCKET_ADDRESS=0.0.0.0:80` takes precedence over `socket_address` in the file.
+///
+/// ```toml
+/// # leptos.toml
+/// pkg_path = "/pkg/app"
+/// environment = "dev"
+/// socket_address = "127.0.0.1:3000"
+/// reload_port = 3001
+/// ```
+pub fn get_configuration_from_file(path: Option<&str>) -> Result<RenderOptions, LeptosConfigError> {
+    ConfigLoader::default().load_file(path)
 }
 
 impl RenderOptions {
+    /// Returns the certificate and key paths if TLS has been configured, i.e., if both
+    /// `tls_cert_path` and `tls_key_path` are set.
+    pub fn tls_paths(&self) -> Option<(&Path, &Path)> {
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert), Some(key)) => Some((cert.as_path(), key.as_path())),
+            _ => None,
+        }
+    }
+
+    /// The URL the autoreload script connects to, built from `reload_ws_protocol`,
+    /// `reload_host`, and `reload_external_port`, falling back to the IP of `socket_address`
+    /// and `reload_port`.
+    ///
+    /// ```
+    /// # use leptos_config::*;
+    /// let options = RenderOptions::builder()
+    ///     .pkg_path("/pkg/app")
+    ///     .reload_ws_protocol(ReloadWSProtocol::WSS)
+    ///     .reload_host("dev.example.com")
+    ///     .reload_external_port(443)
+    ///     .build();
+    /// assert_eq!(options.reload_url(), "wss://dev.example.com:443/autoreload");
+    /// ```
+    pub fn reload_url(&self) -> String {
+        let protocol = self.reload_ws_protocol.as_str();
+        let host = match &self.reload_host {
+            Some(host) => host.clone(),
+            None => self.socket_address.ip().to_string(),
+        };
+        let port = self.reload_external_port.unwrap_or(self.reload_port);
+        format!("{protocol}://{host}:{port}/autoreload")
+    }
+
     /// Creates a hidden file at ./.leptos_toml so cargo-leptos can monitor settings. We do not read from this file
     /// only write to it, you'll want to change the settings in your main function when you create RenderOptions
     pub fn write_to_file(&self) {
@@ -46,45 +322,186 @@ RenderOptions {{
         fs::write("./.leptos.kdl", options).expect("Unable to write file");
     }
 }
+/// The protocol the autoreload Websocket connects with.
+/// Defaults to WS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReloadWSProtocol {
+    #[default]
+    WS,
+    WSS,
+}
+
+impl ReloadWSProtocol {
+    /// The URL scheme for this protocol, `ws` or `wss`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WS => "ws",
+            Self::WSS => "wss",
+        }
+    }
+}
+
+impl FromStr for ReloadWSProtocol {
+    type Err = String;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "ws" => Ok(Self::WS),
+            "wss" => Ok(Self::WSS),
+            _ => Err(format!(
+                "unknown Websocket protocol {input:?}, expected `ws` or `wss`"
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ReloadWSProtocol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+        input
+            .parse()
+            .map_err(|_| serde::de::Error::unknown_variant(&input, &["ws", "wss"]))
+    }
+}
+
+/// How a server-rendered page streams the HTML of each `<Suspense/>` once its resources
+/// resolve. Defaults to `Prioritized`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SsrMode {
+    /// The HTML is sent in order of the priority of the resources read under each
+    /// `<Suspense/>`, and the initial HTML waits for those that block it, as described for
+    /// `render_to_stream` in `leptos_dom`.
+    #[default]
+    Prioritized,
+    /// The initial HTML is sent right away, and the HTML of each `<Suspense/>` is sent as soon
+    /// as its resources resolve, whatever their priority, as described for
+    /// `render_to_stream_out_of_order` in `leptos_dom`.
+    OutOfOrder,
+    /// Nothing is sent until every resource has resolved, or the `ssr_timeout` has passed, and
+    /// the whole page is then sent at once, as described for `render_to_string_async` in
+    /// `leptos_dom`. This suits crawlers and clients that don't run scripts, and lets the app
+    /// set the status code and headers of the response from any resource.
+    Async,
+}
+
+impl SsrMode {
+    /// The name of this mode in config files, `prioritized`, `out-of-order` or `async`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prioritized => "prioritized",
+            Self::OutOfOrder => "out-of-order",
+            Self::Async => "async",
+        }
+    }
+}
+
+impl FromStr for SsrMode {
+    type Err = String;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().replace('_', "-").as_str() {
+            "prioritized" => Ok(Self::Prioritized),
+            "out-of-order" => Ok(Self::OutOfOrder),
+            "async" => Ok(Self::Async),
+            _ => Err(format!(
+                "unknown SSR mode {input:?}, expected `prioritized`, `out-of-order` or `async`"
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SsrMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(|_| {
+            serde::de::Error::unknown_variant(&input, &["prioritized", "out-of-order", "async"])
+        })
+    }
+}
+
 /// An enum that can be used to define the environment Leptos is running in. Can be passed to RenderOptions.
-/// Setting this to the PROD variant will not include the websockets code for cargo-leptos' watch.
+/// Setting this to the PROD or TEST variant will not include the websockets code for cargo-leptos' watch.
 /// Defaults to PROD
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum RustEnv {
+    #[default]
     PROD,
     DEV,
+    TEST,
 }
 
-impl Default for RustEnv {
-    fn default() -> Self {
-        Self::PROD
+impl RustEnv {
+    /// The name of the config table whose keys override the base config in this environment.
+    pub fn profile_name(&self) -> &'static str {
+        match self {
+            Self::PROD => "prod",
+            Self::DEV => "dev",
+            Self::TEST => "test",
+        }
+    }
+
+    /// Parses an environment name, returning an error for anything other than
+    /// `dev`/`development`, `prod`/`production`, or `test`/`testing` (in any case).
+    ///
+    /// Unlike the [FromStr] and [From] implementations, this neither falls back to `PROD`
+    /// nor panics.
+    pub fn parse_strict(input: &str) -> Result<Self, LeptosConfigError> {
+        Self::from_name(input).ok_or_else(|| LeptosConfigError::UnknownEnv(input.to_string()))
+    }
+
+    fn from_name(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "dev" | "development" => Some(Self::DEV),
+            "prod" | "production" => Some(Self::PROD),
+            "test" | "testing" => Some(Self::TEST),
+            _ => None,
+        }
     }
 }
 
+/// Parses an environment name, falling back to `PROD` if it is not recognized.
+/// Use [RustEnv::parse_strict] to treat unrecognized names as an error.
 impl FromStr for RustEnv {
     type Err = ();
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let sanitized = input.to_lowercase();
-        match sanitized.as_ref() {
-            "dev" => Ok(Self::DEV),
-            "development" => Ok(Self::DEV),
-            "prod" => Ok(Self::PROD),
-            "production" => Ok(Self::PROD),
-            _ => Ok(Self::PROD),
-        }
+        Ok(Self::from_name(input).unwrap_or_default())
     }
 }
 
+impl<'de> Deserialize<'de> for RustEnv {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let input = String::deserialize(deserializer)?;
+        Self::from_name(&input).ok_or_else(|| {
+            serde::de::Error::unknown_variant(
+                &input,
+                &[
+                    "dev",
+                    "development",
+                    "prod",
+                    "production",
+                    "test",
+                    "testing",
+                ],
+            )
+        })
+    }
+}
+
+/// Parses an environment name, panicking if it is not recognized.
+/// Use [RustEnv::parse_strict] to handle unrecognized names as an error.
 impl From<&str> for RustEnv {
     fn from(str: &str) -> Self {
-        let sanitized = str.to_lowercase();
-        match sanitized.as_str() {
-            "dev" => Self::DEV,
-            "development" => Self::DEV,
-            "prod" => Self::PROD,
-            "production" => Self::PROD,
-            _ => {
-                panic!("Environment var is not recognized. Maybe try `dev` or `prod`")
+        match Self::from_name(str) {
+            Some(env) => env,
+            None => {
+                panic!("Environment var is not recognized. Maybe try `dev`, `prod`, or `test`")
             }
         }
     }
@@ -92,18 +509,7 @@ impl From<&str> for RustEnv {
 impl From<&Result<String, VarError>> for RustEnv {
     fn from(input: &Result<String, VarError>) -> Self {
         match input {
-            Ok(str) => {
-                let sanitized = str.to_lowercase();
-                match sanitized.as_ref() {
-                    "dev" => Self::DEV,
-                    "development" => Self::DEV,
-                    "prod" => Self::PROD,
-                    "production" => Self::PROD,
-                    _ => {
-                        panic!("Environment var is not recognized. Maybe try `dev` or `prod`")
-                    }
-                }
-            }
+            Ok(str) => Self::from(str.as_str()),
             Err(_) => Self::PROD,
         }
     }
@@ -0,0 +1,586 @@
+use crate::{validate, ConfigSource, LeptosConfigError, RenderOptions, RustEnv, ValueSource};
+use config::{Config, File, FileFormat};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// The file names [get_configuration_from_file](crate::get_configuration_from_file) looks for,
+/// in order, when no path is given.
+pub const CONFIG_FILE_NAMES: [&str; 4] =
+    ["leptos.toml", "leptos.json", "leptos.yaml", "leptos.yml"];
+
+/// Loads [RenderOptions], with control over how environment variables are read.
+///
+/// [get_configuration](crate::get_configuration) and
+/// [get_configuration_from_file](crate::get_configuration_from_file) use the defaults:
+/// any variable named `LEPTOS_` followed by the upper-cased field name.
+///
+/// Each value is resolved from these sources, with later ones taking precedence:
+/// 1. the field’s default
+/// 2. the base keys in the config file
+/// 3. the `dev` or `prod` profile in the config file
+/// 4. environment variables named with the [env_prefix](ConfigLoader::env_prefix)
+/// 5. environment variables set with [env_var](ConfigLoader::env_var)
+/// 6. command-line arguments, if enabled with [cli_args](ConfigLoader::cli_args)
+///
+/// ```no_run
+/// # use leptos_config::*;
+/// let options = ConfigLoader::new()
+///     .env_prefix("MY_APP")
+///     .env_var("BIND_ADDR", "socket_address")
+///     .load(None)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigLoader {
+    package: Option<String>,
+    site: Option<String>,
+    strict_env: bool,
+    env_prefix: String,
+    env_vars: HashMap<String, String>,
+    args: Option<Vec<String>>,
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self {
+            package: None,
+            site: None,
+            strict_env: false,
+            args: None,
+            env_prefix: "LEPTOS".to_string(),
+            env_vars: HashMap::new(),
+        }
+    }
+}
+
+impl ConfigLoader {
+    /// Creates a loader that reads `LEPTOS_`-prefixed environment variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the prefix for environment variables, which is followed by an `_` and the
+    /// upper-cased field name. With `MY_APP`, `MY_APP_RELOAD_PORT` sets `reload_port`.
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = prefix.into();
+        self
+    }
+
+    /// Reads the field named `field` from the environment variable `var`, whatever its name.
+    pub fn env_var(mut self, var: impl Into<String>, field: impl Into<String>) -> Self {
+        self.env_vars.insert(var.into(), field.into());
+        self
+    }
+
+    /// Selects one of the sites defined under `sites`, whose keys override the base settings.
+    /// See [get_configuration_for_site](crate::get_configuration_for_site).
+    pub fn site(mut self, name: impl Into<String>) -> Self {
+        self.site = Some(name.into());
+        self
+    }
+
+    /// Lists the names of the sites defined under `sites` in a `Cargo.toml`.
+    pub fn site_names(&self, path: Option<&str>) -> Result<Vec<String>, LeptosConfigError> {
+        let files = match path {
+            Some(path) => self.read_manifests(Path::new(path))?,
+            None => self.discover_manifests()?,
+        };
+        let mut names = files
+            .iter()
+            .filter_map(|file| file.settings().ok())
+            .filter_map(|settings| settings.get("sites").cloned())
+            .filter_map(|sites| sites.into_table().ok())
+            .flat_map(|sites| sites.into_keys())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Overlays the settings passed as command-line arguments to this process, like
+    /// `--socket-address 0.0.0.0:80` or `--reload-port=3002`, on top of the file and
+    /// environment variables. Any argument that doesn’t name a [RenderOptions] field is ignored,
+    /// so this can be combined with the app’s own arguments.
+    ///
+    /// Each field can be set with its name in `kebab-case`. `--site-addr` and `--env` are
+    /// accepted as shorthands for `--socket-address` and `--environment`, and boolean fields
+    /// like `--precompress` can be given without a value.
+    pub fn cli_args(self) -> Self {
+        self.args(std::env::args().skip(1))
+    }
+
+    /// Overlays settings from the given command-line arguments, as with [ConfigLoader::cli_args].
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = Some(args.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets whether an unrecognized `environment` is an error. By default, it falls back to
+    /// [RustEnv::PROD], like parsing a [RustEnv] with [FromStr](std::str::FromStr).
+    pub fn strict_env(mut self, strict: bool) -> Self {
+        self.strict_env = strict;
+        self
+    }
+
+    /// Selects the workspace member whose settings should be loaded, by package name.
+    pub fn package(mut self, name: impl Into<String>) -> Self {
+        self.package = Some(name.into());
+        self
+    }
+
+    /// Loads [RenderOptions] from the `[package.metadata.leptos]` section of a `Cargo.toml`.
+    /// See [get_configuration](crate::get_configuration).
+    pub fn load(&self, path: Option<&str>) -> Result<RenderOptions, LeptosConfigError> {
+        let files = match path {
+            Some(path) => self.read_manifests(Path::new(path))?,
+            None => self.discover_manifests()?,
+        };
+        self.resolve(files)
+    }
+
+    /// Walks up from the current directory to find the manifest with the Leptos settings
+    /// for the selected package, along with those of the workspace it belongs to.
+    fn discover_manifests(&self) -> Result<Vec<SourceFile>, LeptosConfigError> {
+        let cwd =
+            std::env::current_dir().map_err(|e| LeptosConfigError::ConfigError(e.to_string()))?;
+        let mut checked = Vec::new();
+        let mut files = Vec::new();
+        for dir in cwd.ancestors() {
+            let path = dir.join("Cargo.toml");
+            if !path.is_file() {
+                continue;
+            }
+            checked.push(path.clone());
+            let manifest = Manifest::read(&path)?;
+            if files.is_empty() && manifest.has_package_settings(self.package.as_deref()) {
+                files.push(manifest.package_file());
+            }
+            if manifest.is_workspace() {
+                files.extend(self.workspace_files(manifest, files.is_empty())?);
+                break;
+            }
+        }
+        if files.is_empty() {
+            Err(LeptosConfigError::ConfigNotFound(checked))
+        } else {
+            Ok(files)
+        }
+    }
+
+    /// Reads the Leptos settings from the manifest at `path`, which may be a package,
+    /// a workspace root, or both.
+    fn read_manifests(&self, path: &Path) -> Result<Vec<SourceFile>, LeptosConfigError> {
+        let manifest = Manifest::read(path)?;
+        let mut files = Vec::new();
+        if manifest.has_package_settings(self.package.as_deref()) {
+            files.push(manifest.package_file());
+        }
+        if manifest.is_workspace() {
+            files.extend(self.workspace_files(manifest, files.is_empty())?);
+        }
+        if files.is_empty() {
+            Err(LeptosConfigError::ConfigError(format!(
+                "{}: missing [package.metadata.leptos] section",
+                path.display()
+            )))
+        } else {
+            Ok(files)
+        }
+    }
+
+    /// Returns the workspace-wide settings, preceded by those of the selected member
+    /// if `find_member` is set.
+    fn workspace_files(
+        &self,
+        workspace: Manifest,
+        find_member: bool,
+    ) -> Result<Vec<SourceFile>, LeptosConfigError> {
+        let mut files = Vec::new();
+        if let (true, Some(package)) = (find_member, &self.package) {
+            let member = workspace
+                .member_manifests()
+                .into_iter()
+                .map(|path| Manifest::read(&path))
+                .find(|manifest| {
+                    manifest
+                        .as_ref()
+                        .map(|manifest| manifest.has_package_settings(Some(package)))
+                        .unwrap_or(false)
+                });
+            match member {
+                Some(member) => files.push(member?.package_file()),
+                None => {
+                    return Err(LeptosConfigError::ConfigError(format!(
+                        "{}: no workspace member named {package:?} with a \
+                         [package.metadata.leptos] section",
+                        workspace.file.path.display()
+                    )))
+                }
+            }
+        }
+        if workspace.config.get_table(WORKSPACE_TABLE).is_ok() {
+            files.push(workspace.workspace_file());
+        }
+        Ok(files)
+    }
+
+    /// Loads [RenderOptions] from a plain `leptos.toml`, `leptos.json`, or `leptos.yaml` file.
+    /// See [get_configuration_from_file](crate::get_configuration_from_file).
+    pub fn load_file(&self, path: Option<&str>) -> Result<RenderOptions, LeptosConfigError> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => find_config_file(Path::new("."))?,
+        };
+        let format =
+            file_format(&path).ok_or_else(|| LeptosConfigError::UnsupportedFormat(path.clone()))?;
+        let text = read_config_file(&path)?;
+        let file = SourceFile {
+            path,
+            text,
+            format,
+            table: String::new(),
+        };
+        self.resolve(vec![file])
+    }
+
+    /// Puts the tables for the selected site, if any, in front of `files`.
+    fn with_site(&self, files: Vec<SourceFile>) -> Result<Vec<SourceFile>, LeptosConfigError> {
+        let site = match &self.site {
+            Some(site) => site,
+            None => return Ok(files),
+        };
+        let site_files = files
+            .iter()
+            .map(|file| SourceFile {
+                table: match file.table.as_str() {
+                    "" => format!("sites.{site}"),
+                    table => format!("{table}.sites.{site}"),
+                },
+                ..file.clone()
+            })
+            .filter(|file| file.settings().is_ok())
+            .collect::<Vec<_>>();
+        if site_files.is_empty() {
+            return Err(LeptosConfigError::ConfigError(format!(
+                "no settings found for the site {site:?}"
+            )));
+        }
+        Ok(site_files.into_iter().chain(files).collect())
+    }
+
+    /// Merges the settings in `files`, then layers the profile for the current environment and
+    /// the environment variables over them, validates them, and deserializes the result.
+    fn resolve(&self, files: Vec<SourceFile>) -> Result<RenderOptions, LeptosConfigError> {
+        let files = self.with_site(files)?;
+        let base = files
+            .iter()
+            .rev()
+            .map(|file| file.settings())
+            .try_fold(Config::builder(), |builder, settings| {
+                Ok::<_, LeptosConfigError>(builder.add_source(config_from_table(settings?)?))
+            })?
+            .build()?;
+
+        let override_fields = self.env_fields().into_iter().chain(self.arg_fields());
+        let mut override_sources = HashMap::new();
+        let mut overrides = Config::builder();
+        for (field, (source, value)) in override_fields {
+            overrides = overrides.set_override(field.as_str(), value)?;
+            override_sources.insert(field, source);
+        }
+        let overrides = overrides.build()?;
+        let environment = overrides
+            .get_string("environment")
+            .or_else(|_| base.get_string("environment"))
+            .ok()
+            .map(|env| env.parse::<RustEnv>().unwrap_or_default())
+            .unwrap_or_default();
+        let profile = base
+            .get_table(environment.profile_name())
+            .unwrap_or_default();
+        let profile = config_from_table(profile)?;
+
+        let layers = validate::Layers {
+            base: &base,
+            profile: &profile,
+            profile_name: environment.profile_name(),
+            strict_env: self.strict_env,
+            overrides: &overrides,
+            override_sources,
+            files: &files,
+        };
+        layers.validate()?;
+
+        let settings = Config::builder()
+            .add_source(base.clone())
+            .add_source(profile.clone())
+            .add_source(overrides.clone())
+            .set_override("environment", environment.profile_name())?
+            .build()?;
+        let mut options = settings.try_deserialize::<RenderOptions>()?;
+
+        let mut sources = ConfigSource::default();
+        for field in ARG_FIELDS.iter().chain(["client_config_fields"].iter()) {
+            sources.insert(*field, layers.origin(field));
+        }
+        for key in options.client_config.keys() {
+            let field = format!("client_config.{key}");
+            sources.insert(field.as_str(), layers.origin(&field));
+        }
+        options.sources = sources;
+        Ok(options)
+    }
+
+    /// Maps each field set in the environment to the variable it was read from and its value.
+    fn env_fields(&self) -> Vec<(String, (ValueSource, String))> {
+        let prefix = format!("{}_", self.env_prefix.to_uppercase());
+        let vars = std::env::vars().collect::<Vec<_>>();
+        let prefixed = vars.iter().filter_map(|(var, value)| {
+            var.to_uppercase().strip_prefix(&prefix).map(|field| {
+                (
+                    field.to_lowercase(),
+                    (ValueSource::Env(var.clone()), value.clone()),
+                )
+            })
+        });
+        let mapped = vars.iter().filter_map(|(var, value)| {
+            self.env_vars.get(var).map(|field| {
+                (
+                    field.clone(),
+                    (ValueSource::Env(var.clone()), value.clone()),
+                )
+            })
+        });
+        // explicit mappings are chained last, so they take precedence
+        prefixed.chain(mapped).collect()
+    }
+
+    /// Maps each field set on the command line to the flag it was read from and its value.
+    fn arg_fields(&self) -> Vec<(String, (ValueSource, String))> {
+        let args = match &self.args {
+            Some(args) => args,
+            None => return Vec::new(),
+        };
+        let mut fields = Vec::new();
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let field = match flag.strip_prefix("--").and_then(arg_field) {
+                Some(field) => field,
+                None => continue,
+            };
+            let value = match inline_value {
+                Some(value) => value,
+                None if BOOL_FIELDS.contains(&field)
+                    && args
+                        .peek()
+                        .map(|next| next.starts_with("--"))
+                        .unwrap_or(true) =>
+                {
+                    "true".to_string()
+                }
+                None => match args.next() {
+                    Some(value) => value.clone(),
+                    None => continue,
+                },
+            };
+            fields.push((
+                field.to_string(),
+                (ValueSource::Arg(flag.to_string()), value),
+            ));
+        }
+        fields
+    }
+}
+
+/// The file a config was read from, kept around to point errors at the right line.
+#[derive(Clone)]
+pub(crate) struct SourceFile {
+    pub path: PathBuf,
+    pub text: String,
+    pub format: FileFormat,
+    /// The TOML table holding the Leptos settings, or an empty string for the root.
+    pub table: String,
+}
+
+impl SourceFile {
+    fn settings(&self) -> Result<config::Map<String, config::Value>, LeptosConfigError> {
+        let config = Config::builder()
+            .add_source(File::from_str(&self.text, self.format))
+            .build()?;
+        if self.table.is_empty() {
+            config.try_deserialize().map_err(|e| e.into())
+        } else {
+            config.get_table(&self.table).map_err(|e| e.into())
+        }
+    }
+}
+
+/// The fields that can be set from the command line.
+const ARG_FIELDS: [&str; 23] = [
+    "pkg_path",
+    "environment",
+    "socket_address",
+    "reload_port",
+    "reload_external_port",
+    "reload_ws_protocol",
+    "reload_host",
+    "tls_cert_path",
+    "tls_key_path",
+    "hash_files",
+    "hash_file_name",
+    "precompress",
+    "static_cache_control",
+    "wasm_cache_control",
+    "server_fn_timeout",
+    "max_request_size",
+    "max_upload_size",
+    "stream_chunk_size",
+    "ssr_mode",
+    "ssr_timeout",
+    "islands",
+    "server_navigation",
+    "no_js_audit",
+];
+
+/// Fields that are set to `true` when their flag is given without a value.
+const BOOL_FIELDS: [&str; 5] = [
+    "hash_files",
+    "precompress",
+    "islands",
+    "server_navigation",
+    "no_js_audit",
+];
+
+fn arg_field(flag: &str) -> Option<&'static str> {
+    match flag {
+        "site-addr" => Some("socket_address"),
+        "env" => Some("environment"),
+        flag => {
+            let field = flag.replace('-', "_");
+            ARG_FIELDS.iter().find(|known| **known == field).copied()
+        }
+    }
+}
+
+const PACKAGE_TABLE: &str = "package.metadata.leptos";
+const WORKSPACE_TABLE: &str = "workspace.metadata.leptos";
+
+/// A parsed `Cargo.toml`.
+struct Manifest {
+    file: SourceFile,
+    config: Config,
+}
+
+impl Manifest {
+    fn read(path: &Path) -> Result<Self, LeptosConfigError> {
+        let text = read_config_file(path)?;
+        let config = Config::builder()
+            .add_source(File::from_str(&text, FileFormat::Toml))
+            .build()
+            .map_err(|e| LeptosConfigError::ConfigError(format!("{}: {e}", path.display())))?;
+        Ok(Self {
+            file: SourceFile {
+                path: path.to_path_buf(),
+                text,
+                format: FileFormat::Toml,
+                table: String::new(),
+            },
+            config,
+        })
+    }
+
+    fn is_workspace(&self) -> bool {
+        self.config.get_table("workspace").is_ok()
+    }
+
+    fn has_package_settings(&self, name: Option<&str>) -> bool {
+        let name_matches = match name {
+            Some(name) => self.config.get_string("package.name").ok().as_deref() == Some(name),
+            None => true,
+        };
+        name_matches && self.config.get_table(PACKAGE_TABLE).is_ok()
+    }
+
+    fn package_file(&self) -> SourceFile {
+        SourceFile {
+            table: PACKAGE_TABLE.to_string(),
+            ..self.file.clone()
+        }
+    }
+
+    fn workspace_file(&self) -> SourceFile {
+        SourceFile {
+            table: WORKSPACE_TABLE.to_string(),
+            ..self.file.clone()
+        }
+    }
+
+    /// The manifests of the workspace members, expanding `dir/*` globs.
+    fn member_manifests(&self) -> Vec<PathBuf> {
+        let root = self.file.path.parent().unwrap_or_else(|| Path::new("."));
+        let members = self
+            .config
+            .get_array("workspace.members")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|member| member.into_string().ok());
+        let mut dirs = Vec::new();
+        for member in members {
+            match member.strip_suffix("/*") {
+                Some(parent) => {
+                    if let Ok(entries) = std::fs::read_dir(root.join(parent)) {
+                        dirs.extend(entries.filter_map(|entry| entry.ok()).map(|e| e.path()));
+                    }
+                }
+                None => dirs.push(root.join(member)),
+            }
+        }
+        dirs.into_iter()
+            .map(|dir| dir.join("Cargo.toml"))
+            .filter(|path| path.is_file())
+            .collect()
+    }
+}
+
+fn config_from_table(
+    table: config::Map<String, config::Value>,
+) -> Result<Config, LeptosConfigError> {
+    table
+        .into_iter()
+        .try_fold(Config::builder(), |builder, (key, value)| {
+            builder.set_default(key.replace('-', "_"), value)
+        })?
+        .build()
+        .map_err(|e| e.into())
+}
+
+fn read_config_file(path: &Path) -> Result<String, LeptosConfigError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| LeptosConfigError::ConfigError(format!("{}: {e}", path.display())))
+}
+
+fn find_config_file(dir: &Path) -> Result<PathBuf, LeptosConfigError> {
+    let candidates = CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .collect::<Vec<_>>();
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(LeptosConfigError::ConfigNotFound(candidates)),
+    }
+}
+
+fn file_format(path: &Path) -> Option<FileFormat> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "toml" => Some(FileFormat::Toml),
+        "json" => Some(FileFormat::Json),
+        "yaml" | "yml" => Some(FileFormat::Yaml),
+        _ => None,
+    }
+}
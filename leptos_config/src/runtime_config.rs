@@ -0,0 +1,136 @@
+use crate::RenderOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `id` of the `<script>` tag the [RuntimeConfig] is embedded in.
+pub const RUNTIME_CONFIG_ELEMENT_ID: &str = "leptos-runtime-config";
+
+/// The [RenderOptions] fields that can be listed in `client_config_fields`. Paths to TLS
+/// files and other server-only settings are deliberately left out.
+pub const CLIENT_CONFIG_FIELDS: [&str; 10] = [
+    "pkg_path",
+    "environment",
+    "socket_address",
+    "reload_port",
+    "hash_files",
+    "precompress",
+    "server_fn_timeout",
+    "max_request_size",
+    "max_upload_size",
+    "stream_chunk_size",
+];
+
+/// Deploy-time settings that are sent from the server to the client, so that the WASM bundle
+/// can read things like an API base URL or feature flags without being recompiled.
+///
+/// The integrations embed it in the `<head>` of each server-rendered page, using
+/// [RuntimeConfig::to_script], and `leptos_meta::use_runtime_config` reads it back in the browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuntimeConfig(BTreeMap<String, String>);
+
+impl RuntimeConfig {
+    /// Creates an empty [RuntimeConfig].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the value for `key`, if it has been set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Sets the value for `key`, replacing any previous value.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    /// Sets the value for `key`, returning the updated config.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Iterates over the keys and values, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Parses a config from the JSON text of its `<script>` tag.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the config into a `<script type="application/json">` tag that can be
+    /// injected into the document head.
+    ///
+    /// ```
+    /// # use leptos_config::RuntimeConfig;
+    /// let config = RuntimeConfig::new().with("api_base_url", "https://api.example.com");
+    /// assert_eq!(
+    ///     config.to_script(),
+    ///     r#"<script id="leptos-runtime-config" type="application/json">{"api_base_url":"https://api.example.com"}</script>"#
+    /// );
+    /// ```
+    pub fn to_script(&self) -> String {
+        // serializing a map of strings can't fail
+        let json = serde_json::to_string(&self.0).unwrap_or_default();
+        // `</script>` inside a value would close the tag early
+        let json = json.replace("</", "<\\/");
+        format!(
+            r#"<script id="{RUNTIME_CONFIG_ELEMENT_ID}" type="application/json">{json}</script>"#
+        )
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for RuntimeConfig
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        RuntimeConfig(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+impl RenderOptions {
+    /// Builds the [RuntimeConfig] that is sent to the client: the fields named in
+    /// `client_config_fields`, followed by the `client_config` values, which take
+    /// precedence if a key appears in both.
+    ///
+    /// Names that are not in [CLIENT_CONFIG_FIELDS] are skipped.
+    pub fn runtime_config(&self) -> RuntimeConfig {
+        let mut config = RuntimeConfig::new();
+        for field in &self.client_config_fields {
+            if let Some(value) = self.client_field(field) {
+                config.insert(field.as_str(), value);
+            }
+        }
+        for (key, value) in &self.client_config {
+            config.insert(key.as_str(), value.as_str());
+        }
+        config
+    }
+
+    fn client_field(&self, field: &str) -> Option<String> {
+        match field {
+            "pkg_path" => Some(self.pkg_path.clone()),
+            "environment" => Some(self.environment.profile_name().to_string()),
+            "socket_address" => Some(self.socket_address.to_string()),
+            "reload_port" => Some(self.reload_port.to_string()),
+            "hash_files" => Some(self.hash_files.to_string()),
+            "precompress" => Some(self.precompress.to_string()),
+            "server_fn_timeout" => self
+                .server_fn_timeout
+                .map(|timeout| format!("{}ms", timeout.as_millis())),
+            "max_request_size" => self.max_request_size.map(|size| size.to_string()),
+            "max_upload_size" => self.max_upload_size.map(|size| size.to_string()),
+            "stream_chunk_size" => self.stream_chunk_size.map(|size| size.to_string()),
+            _ => None,
+        }
+    }
+}
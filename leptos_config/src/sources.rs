@@ -0,0 +1,202 @@
+use crate::{RenderOptions, ValueSource};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Where each field of a loaded [RenderOptions] came from, e.g. that `socket_address` was read
+/// from `LEPTOS_SOCKET_ADDRESS` rather than from `Cargo.toml`.
+///
+/// This is filled in by [ConfigLoader](crate::ConfigLoader), and is empty for options created
+/// with the builder. Printing the options with `{}` includes the source of each value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigSource(BTreeMap<String, ValueSource>);
+
+impl ConfigSource {
+    /// Returns where `field` was set, if the options were loaded from config.
+    /// Values in `client_config` are named like `client_config.api_base_url`.
+    pub fn get(&self, field: &str) -> Option<&ValueSource> {
+        self.0.get(field)
+    }
+
+    /// Iterates over the fields and their sources, in field name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ValueSource)> {
+        self.0
+            .iter()
+            .map(|(field, source)| (field.as_str(), source))
+    }
+
+    pub(crate) fn insert(&mut self, field: impl Into<String>, source: ValueSource) {
+        self.0.insert(field.into(), source);
+    }
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (field, source) in self.iter() {
+            writeln!(f, "{field}: {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A value that should never be written to logs, like a password or signing key.
+///
+/// Its [Debug](fmt::Debug) and [Display](fmt::Display) implementations print `[redacted]`
+/// instead of the value, so it can be kept in config structs that are printed for debugging.
+/// Use [Secret::expose] to read the value itself.
+///
+/// ```
+/// # use leptos_config::Secret;
+/// let key = Secret::new("hunter2".to_string());
+/// assert_eq!(format!("{key:?}"), "Secret([redacted])");
+/// assert_eq!(key.expose(), "hunter2");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wraps a secret value.
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    /// Returns the secret value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([redacted])")
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+/// Prints one `field = value` line per setting, followed by where the value came from if the
+/// options were loaded from config, which is useful to log at startup:
+///
+/// ```text
+/// pkg_path = "/pkg/app" (Cargo.toml:12)
+/// environment = "prod" (env var LEPTOS_ENVIRONMENT)
+/// socket_address = "0.0.0.0:80" (Cargo.toml:17)
+/// reload_port = 3001 (default)
+/// ```
+///
+/// Unset optional fields are left out, and any [Secret] values are redacted.
+impl fmt::Display for RenderOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (field, value) in self.field_values() {
+            write!(f, "{field} = {value}")?;
+            if let Some(source) = self.sources.get(&field) {
+                write!(f, " ({source})")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl RenderOptions {
+    /// Each set field and its value, written the way it would appear in a config file.
+    fn field_values(&self) -> Vec<(String, String)> {
+        fn quoted(value: impl fmt::Display) -> String {
+            format!("{:?}", value.to_string())
+        }
+
+        let mut values = vec![
+            ("pkg_path".to_string(), quoted(&self.pkg_path)),
+            (
+                "environment".to_string(),
+                quoted(self.environment.profile_name()),
+            ),
+            ("socket_address".to_string(), quoted(self.socket_address)),
+            ("reload_port".to_string(), self.reload_port.to_string()),
+        ];
+        let optional = [
+            (
+                "reload_external_port",
+                self.reload_external_port.map(|port| port.to_string()),
+            ),
+            (
+                "reload_ws_protocol",
+                Some(quoted(self.reload_ws_protocol.as_str())),
+            ),
+            ("reload_host", self.reload_host.as_ref().map(quoted)),
+            (
+                "tls_cert_path",
+                self.tls_cert_path.as_ref().map(|p| quoted(p.display())),
+            ),
+            (
+                "tls_key_path",
+                self.tls_key_path.as_ref().map(|p| quoted(p.display())),
+            ),
+            ("hash_files", Some(self.hash_files.to_string())),
+            ("hash_file_name", Some(quoted(&self.hash_file_name))),
+            ("precompress", Some(self.precompress.to_string())),
+            (
+                "static_cache_control",
+                self.static_cache_control.as_ref().map(quoted),
+            ),
+            (
+                "wasm_cache_control",
+                self.wasm_cache_control.as_ref().map(quoted),
+            ),
+            (
+                "server_fn_timeout",
+                self.server_fn_timeout
+                    .map(|timeout| quoted(format!("{}ms", timeout.as_millis()))),
+            ),
+            (
+                "max_request_size",
+                self.max_request_size.map(|s| s.to_string()),
+            ),
+            (
+                "max_upload_size",
+                self.max_upload_size.map(|s| s.to_string()),
+            ),
+            (
+                "stream_chunk_size",
+                self.stream_chunk_size.map(|s| s.to_string()),
+            ),
+            ("ssr_mode", Some(quoted(self.ssr_mode.as_str()))),
+            (
+                "ssr_timeout",
+                self.ssr_timeout
+                    .map(|timeout| quoted(format!("{}ms", timeout.as_millis()))),
+            ),
+            ("islands", Some(self.islands.to_string())),
+            (
+                "server_navigation",
+                Some(self.server_navigation.to_string()),
+            ),
+            ("no_js_audit", Some(self.no_js_audit.to_string())),
+            (
+                "client_config_fields",
+                Some(format!("{:?}", self.client_config_fields)),
+            ),
+        ];
+        values.extend(
+            optional
+                .into_iter()
+                .filter_map(|(field, value)| value.map(|value| (field.to_string(), value))),
+        );
+        values.extend(
+            self.client_config
+                .iter()
+                .map(|(key, value)| (format!("client_config.{key}"), quoted(value))),
+        );
+        values
+    }
+}
@@ -0,0 +1,94 @@
+use crate::RenderOptions;
+use std::path::{Component, Path, PathBuf};
+
+/// A file on disk that a static file handler should send in response to a request,
+/// as resolved by [RenderOptions::static_file].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticFile {
+    /// The file to send, which may be a precompressed variant of the requested file.
+    pub path: PathBuf,
+    /// The `Content-Encoding` to send, if `path` is a precompressed variant.
+    pub content_encoding: Option<&'static str>,
+    /// The `Cache-Control` header to send, if any.
+    pub cache_control: Option<String>,
+}
+
+/// Precompressed variants, in order of preference.
+const ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gzip", "gz")];
+
+impl RenderOptions {
+    /// Resolves a request for `request_path` to a file within `dir`, following the
+    /// `precompress`, `static_cache_control`, and `wasm_cache_control` settings.
+    ///
+    /// `accept_encoding` is the value of the request’s `Accept-Encoding` header. Returns `None`
+    /// if the file does not exist, or if the path tries to escape `dir`.
+    pub fn static_file(
+        &self,
+        dir: impl AsRef<Path>,
+        request_path: &str,
+        accept_encoding: Option<&str>,
+    ) -> Option<StaticFile> {
+        let mut path = dir.as_ref().to_path_buf();
+        for component in Path::new(request_path.trim_start_matches('/')).components() {
+            match component {
+                Component::Normal(segment) => path.push(segment),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        if !path.is_file() {
+            return None;
+        }
+
+        let cache_control = self.cache_control(&path).map(String::from);
+        if self.precompress {
+            let accepted = accept_encoding.unwrap_or_default();
+            for (encoding, extension) in ENCODINGS {
+                let accepts = accepted
+                    .split(',')
+                    .any(|value| value.split(';').next().unwrap_or_default().trim() == encoding);
+                let mut compressed = path.clone().into_os_string();
+                compressed.push(".");
+                compressed.push(extension);
+                let compressed = PathBuf::from(compressed);
+                if accepts && compressed.is_file() {
+                    return Some(StaticFile {
+                        path: compressed,
+                        content_encoding: Some(encoding),
+                        cache_control,
+                    });
+                }
+            }
+        }
+
+        Some(StaticFile {
+            path,
+            content_encoding: None,
+            cache_control,
+        })
+    }
+
+    /// The `Cache-Control` header to send for the file at `path`, if any.
+    ///
+    /// `.wasm` files, and `.js` files named after the last segment of `pkg_path`
+    /// (like `app.js` or `app.4fa2c1.js` for `/pkg/app`), use `wasm_cache_control`.
+    pub fn cache_control(&self, path: &Path) -> Option<&str> {
+        let output_name = self.pkg_path.rsplit('/').next().unwrap_or_default();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let is_wasm_asset = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("wasm") => true,
+            Some("js") => file_name.starts_with(&format!("{output_name}.")),
+            _ => false,
+        };
+        if is_wasm_asset {
+            self.wasm_cache_control
+                .as_deref()
+                .or(self.static_cache_control.as_deref())
+        } else {
+            self.static_cache_control.as_deref()
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{de, Deserialize, Deserializer};
+use std::time::Duration;
+
+/// Parses a human-readable duration like `"30s"`, `"500ms"`, or `"1m30s"`.
+///
+/// The units `ms`, `s`, `m`, `h`, and `d` are supported. A bare number is read as seconds.
+/// ```
+/// # use leptos_config::parse_duration;
+/// # use std::time::Duration;
+/// assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+/// assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+/// assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (number, after_number) = split_number(rest).ok_or_else(|| {
+            format!("invalid duration {input:?}, expected something like \"30s\"")
+        })?;
+        let unit_len = after_number
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(after_number.len());
+        let (unit, after_unit) = after_number.split_at(unit_len);
+        let millis = match unit.trim() {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            unit => {
+                return Err(format!(
+                    "invalid duration {input:?}, unknown unit {unit:?} (expected ms, s, m, h, or d)"
+                ))
+            }
+        };
+        total += Duration::from_millis(number * millis);
+        rest = after_unit.trim_start();
+    }
+    Ok(total)
+}
+
+/// Parses a human-readable size in bytes like `"5mb"`, `"512kb"`, or `"1gb"`.
+///
+/// As with most HTTP body limits, `kb`, `mb`, and `gb` are multiples of 1024.
+/// A bare number is read as bytes.
+/// ```
+/// # use leptos_config::parse_size;
+/// assert_eq!(parse_size("5mb"), Ok(5 * 1024 * 1024));
+/// assert_eq!(parse_size("512 KB"), Ok(512 * 1024));
+/// assert_eq!(parse_size("100"), Ok(100));
+/// ```
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let (number, unit) = split_number(trimmed)
+        .ok_or_else(|| format!("invalid size {input:?}, expected something like \"5mb\""))?;
+    let multiplier = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" | "k" => 1024,
+        "mb" | "m" => 1024 * 1024,
+        "gb" | "g" => 1024 * 1024 * 1024,
+        unit => {
+            return Err(format!(
+                "invalid size {input:?}, unknown unit {unit:?} (expected b, kb, mb, or gb)"
+            ))
+        }
+    };
+    Ok(number * multiplier)
+}
+
+fn split_number(input: &str) -> Option<(u64, &str)> {
+    let digits = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let number = input[..digits].parse().ok()?;
+    Some((number, &input[digits..]))
+}
+
+/// A value that can be written either as a number or as a human-readable string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u64),
+    String(String),
+}
+
+pub(crate) fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<NumberOrString>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(NumberOrString::Number(secs)) => Ok(Some(Duration::from_secs(secs))),
+        Some(NumberOrString::String(input)) => {
+            parse_duration(&input).map(Some).map_err(de::Error::custom)
+        }
+    }
+}
+
+pub(crate) fn deserialize_size<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<NumberOrString>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(NumberOrString::Number(bytes)) => Ok(Some(bytes)),
+        Some(NumberOrString::String(input)) => {
+            parse_size(&input).map(Some).map_err(de::Error::custom)
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{
+    loader::SourceFile, parse_duration, parse_size, InvalidValue, LeptosConfigError,
+    ReloadWSProtocol, RustEnv, SsrMode, ValueSource, CLIENT_CONFIG_FIELDS,
+};
+use config::{Config, FileFormat, Value};
+use std::{collections::HashMap, net::SocketAddr};
+
+/// The layers a config is resolved from, in increasing order of priority.
+pub(crate) struct Layers<'a> {
+    pub base: &'a Config,
+    pub profile: &'a Config,
+    pub profile_name: &'static str,
+    /// Whether an unrecognized `environment` is an error.
+    pub strict_env: bool,
+    /// Values set by environment variables or command-line arguments.
+    pub overrides: &'a Config,
+    /// Maps each field set in `overrides` to the variable or argument it was read from.
+    pub override_sources: HashMap<String, ValueSource>,
+    /// The files the settings were read from, in decreasing order of priority.
+    pub files: &'a [SourceFile],
+}
+
+impl<'a> Layers<'a> {
+    /// Checks every known key, collecting all the invalid values rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), LeptosConfigError> {
+        let mut errors = Vec::new();
+
+        match self.string("pkg_path") {
+            Ok(None) => errors.push(self.invalid("pkg_path", "missing required value".into())),
+            Ok(Some(_)) => {}
+            Err(e) => errors.push(e),
+        }
+
+        if self.strict_env {
+            self.check(&mut errors, "environment", |value| {
+                RustEnv::parse_strict(value).map(|_| ()).map_err(|_| {
+                    format!("unknown environment {value:?}, expected `dev`, `prod`, or `test`")
+                })
+            });
+        }
+        self.check(&mut errors, "socket_address", |value| {
+            value
+                .parse::<SocketAddr>()
+                .map(|_| ())
+                .map_err(|e| format!("{e} (got {value:?})"))
+        });
+        for key in ["reload_port", "reload_external_port"] {
+            self.check(&mut errors, key, |value| {
+                value
+                    .parse::<u32>()
+                    .map(|_| ())
+                    .map_err(|_| format!("expected a port number (got {value:?})"))
+            });
+        }
+        self.check(&mut errors, "reload_ws_protocol", |value| {
+            value.parse::<ReloadWSProtocol>().map(|_| ())
+        });
+        self.check(&mut errors, "ssr_mode", |value| {
+            value.parse::<SsrMode>().map(|_| ())
+        });
+
+        for key in ["server_fn_timeout", "ssr_timeout"] {
+            self.check(&mut errors, key, |value| parse_duration(value).map(|_| ()));
+        }
+        for key in ["max_request_size", "max_upload_size", "stream_chunk_size"] {
+            self.check(&mut errors, key, |value| parse_size(value).map(|_| ()));
+        }
+
+        for key in [
+            "hash_files",
+            "precompress",
+            "islands",
+            "server_navigation",
+            "no_js_audit",
+        ] {
+            if let Some(value) = self.value(key) {
+                if value.into_bool().is_err() {
+                    errors.push(self.invalid(key, "expected `true` or `false`".into()));
+                }
+            }
+        }
+
+        if let Some(value) = self.value("client_config_fields") {
+            match value.into_array() {
+                Ok(fields) => {
+                    for field in fields {
+                        let field = field.into_string().unwrap_or_default();
+                        if !CLIENT_CONFIG_FIELDS.contains(&field.as_str()) {
+                            errors.push(self.invalid(
+                                "client_config_fields",
+                                format!("{field:?} can't be sent to the client"),
+                            ));
+                        }
+                    }
+                }
+                Err(_) => errors.push(self.invalid(
+                    "client_config_fields",
+                    "expected a list of field names".into(),
+                )),
+            }
+        }
+
+        let cert = self.string("tls_cert_path");
+        let key = self.string("tls_key_path");
+        match (cert, key) {
+            (Ok(Some(_)), Ok(None)) => errors.push(self.invalid(
+                "tls_key_path",
+                "must be set along with `tls_cert_path`".into(),
+            )),
+            (Ok(None), Ok(Some(_))) => errors.push(self.invalid(
+                "tls_cert_path",
+                "must be set along with `tls_key_path`".into(),
+            )),
+            (cert, key) => errors.extend(cert.err().into_iter().chain(key.err())),
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(LeptosConfigError::Invalid(errors))
+        }
+    }
+
+    /// Works out which layer the value for `key` comes from.
+    pub fn origin(&self, key: &str) -> ValueSource {
+        if let Some(source) = self.override_sources.get(key) {
+            source.clone()
+        } else if self.profile.get::<Value>(key).is_ok() {
+            self.file_origin(key, Some(self.profile_name))
+        } else if self.base.get::<Value>(key).is_ok() {
+            self.file_origin(key, None)
+        } else {
+            ValueSource::Default
+        }
+    }
+
+    fn value(&self, key: &str) -> Option<Value> {
+        self.overrides
+            .get::<Value>(key)
+            .or_else(|_| self.profile.get::<Value>(key))
+            .or_else(|_| self.base.get::<Value>(key))
+            .ok()
+    }
+
+    fn string(&self, key: &str) -> Result<Option<String>, InvalidValue> {
+        match self.value(key) {
+            Some(value) => value
+                .into_string()
+                .map(Some)
+                .map_err(|_| self.invalid(key, "expected a string".into())),
+            None => Ok(None),
+        }
+    }
+
+    fn check(
+        &self,
+        errors: &mut Vec<InvalidValue>,
+        key: &str,
+        check: impl Fn(&str) -> Result<(), String>,
+    ) {
+        match self.string(key) {
+            Ok(Some(value)) => {
+                if let Err(message) = check(&value) {
+                    errors.push(self.invalid(key, message));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    fn invalid(&self, key: &str, message: String) -> InvalidValue {
+        InvalidValue {
+            key: key.to_string(),
+            message,
+            origin: self.origin(key),
+        }
+    }
+
+    fn file_origin(&self, key: &str, profile: Option<&str>) -> ValueSource {
+        let found = self.files.iter().find_map(|file| {
+            find_line(file, key, profile).map(|line| ValueSource::File {
+                path: file.path.clone(),
+                line: Some(line),
+            })
+        });
+        match (found, self.files.first()) {
+            (Some(origin), _) => origin,
+            (None, Some(file)) => ValueSource::File {
+                path: file.path.clone(),
+                line: None,
+            },
+            (None, None) => ValueSource::Default,
+        }
+    }
+}
+
+/// Finds the 1-based line on which `key` is set, either in the base settings or in `profile`.
+///
+/// TOML files are searched table by table. Other formats are searched from the top, or from
+/// the line that opens the profile.
+fn find_line(file: &SourceFile, key: &str, profile: Option<&str>) -> Option<usize> {
+    let spellings = [key.to_string(), key.replace('_', "-")];
+    let is_toml = matches!(file.format, FileFormat::Toml);
+    let wanted_table = match (file.table.as_str(), profile) {
+        (table, None) => table.to_string(),
+        ("", Some(profile)) => profile.to_string(),
+        (table, Some(profile)) => format!("{table}.{profile}"),
+    };
+
+    let mut current_table = String::new();
+    let mut in_profile = profile.is_none();
+    for (idx, line) in file.text.lines().enumerate() {
+        let line = line.trim_start();
+        if is_toml {
+            if let Some(header) = line.strip_prefix('[') {
+                current_table = header
+                    .split(']')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                continue;
+            }
+            if current_table != wanted_table {
+                continue;
+            }
+        } else if !in_profile {
+            in_profile = profile
+                .map(|profile| sets_key(line, profile))
+                .unwrap_or(true);
+            continue;
+        }
+        if spellings.iter().any(|key| sets_key(line, key)) {
+            return Some(idx + 1);
+        }
+    }
+    None
+}
+
+fn sets_key(line: &str, key: &str) -> bool {
+    let is_quote = |c: char| c == '"' || c == '\'';
+    line.trim_start_matches(is_quote)
+        .strip_prefix(key)
+        .map(|rest| {
+            let rest = rest.trim_start_matches(is_quote).trim_start();
+            rest.starts_with('=') || rest.starts_with(':')
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,141 @@
+use crate::{ConfigLoader, LeptosConfigError, RenderOptions};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Loads [RenderOptions] from `path`, and then watches that file for changes, so that a
+/// long-running server can pick up changes to non-critical settings without a restart.
+///
+/// A `Cargo.toml` is read with [get_configuration](crate::get_configuration), and any other
+/// file with [get_configuration_from_file](crate::get_configuration_from_file). Use
+/// [ConfigLoader::watch] to read other environment variables. The file is checked every
+/// `interval` on a background thread, and each time its contents change, the reloaded options
+/// (or the error that prevented them from loading) are sent to the returned [ConfigWatcher].
+/// The thread stops when the watcher is dropped.
+///
+/// Settings like `socket_address` that are only read at startup will of course not change
+/// anything until the server is restarted.
+///
+/// ```no_run
+/// # use leptos_config::*;
+/// # use std::time::Duration;
+/// let watcher = watch_configuration("leptos.toml", Duration::from_secs(1)).unwrap();
+/// let options = watcher.initial().clone();
+/// std::thread::spawn(move || {
+///     for update in watcher {
+///         match update {
+///             Ok(options) => println!("reloaded config for {}", options.pkg_path),
+///             Err(e) => eprintln!("{e}"),
+///         }
+///     }
+/// });
+/// ```
+pub fn watch_configuration(
+    path: impl AsRef<Path>,
+    interval: Duration,
+) -> Result<ConfigWatcher, LeptosConfigError> {
+    ConfigLoader::default().watch(path, interval)
+}
+
+impl ConfigLoader {
+    /// Loads [RenderOptions] from `path` and watches it for changes.
+    /// See [watch_configuration].
+    pub fn watch(
+        &self,
+        path: impl AsRef<Path>,
+        interval: Duration,
+    ) -> Result<ConfigWatcher, LeptosConfigError> {
+        watch(self.clone(), path.as_ref().to_path_buf(), interval)
+    }
+}
+
+fn watch(
+    loader: ConfigLoader,
+    path: PathBuf,
+    interval: Duration,
+) -> Result<ConfigWatcher, LeptosConfigError> {
+    let mut contents = std::fs::read_to_string(&path).ok();
+    let initial = load(&loader, &path)?;
+
+    let (tx, rx) = mpsc::channel();
+    let stopped = Arc::new(AtomicBool::new(false));
+    thread::spawn({
+        let stopped = Arc::clone(&stopped);
+        move || {
+            while !stopped.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+                let current = std::fs::read_to_string(&path).ok();
+                if current != contents {
+                    contents = current;
+                    if tx.send(load(&loader, &path)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(ConfigWatcher {
+        initial,
+        updates: rx,
+        stopped,
+    })
+}
+
+fn load(loader: &ConfigLoader, path: &Path) -> Result<RenderOptions, LeptosConfigError> {
+    let path_str = path.to_str();
+    if path.file_name().and_then(|name| name.to_str()) == Some("Cargo.toml") {
+        loader.load(path_str)
+    } else {
+        match path_str {
+            Some(path) => loader.load_file(Some(path)),
+            None => Err(LeptosConfigError::ConfigError(format!(
+                "{} is not a valid UTF-8 path",
+                PathBuf::from(path).display()
+            ))),
+        }
+    }
+}
+
+/// Receives updated [RenderOptions] each time a watched config file changes.
+/// Created by [watch_configuration].
+///
+/// Iterating over the watcher blocks until the next change.
+pub struct ConfigWatcher {
+    initial: RenderOptions,
+    updates: Receiver<Result<RenderOptions, LeptosConfigError>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl ConfigWatcher {
+    /// The options that were loaded when the watcher was created.
+    pub fn initial(&self) -> &RenderOptions {
+        &self.initial
+    }
+
+    /// Returns the next update if the file has changed, without blocking.
+    pub fn try_next(&self) -> Option<Result<RenderOptions, LeptosConfigError>> {
+        self.updates.try_recv().ok()
+    }
+}
+
+impl Iterator for ConfigWatcher {
+    type Item = Result<RenderOptions, LeptosConfigError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.updates.recv().ok()
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
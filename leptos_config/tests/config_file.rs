@@ -0,0 +1,733 @@
+use leptos_config::{
+    get_configuration, get_configuration_for_site, get_configuration_from_file,
+    watch_configuration, AssetPaths, ConfigLoader, LeptosConfigError, ReloadWSProtocol,
+    RenderOptions, RuntimeConfig, RustEnv, SsrMode, ValueSource,
+};
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// Held by every test, as they set environment variables and the current directory, which
+/// the process shares while the tests run in parallel.
+fn lock_env() -> MutexGuard<'static, ()> {
+    static ENV: Mutex<()> = Mutex::new(());
+    // a test that failed while holding the lock doesn't leave anything behind for the others
+    ENV.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("leptos_config_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn reads_toml_json_and_yaml() {
+    let _env = lock_env();
+    let toml = write_config(
+        "leptos.toml",
+        r#"
+pkg_path = "/pkg/app"
+environment = "dev"
+socket_address = "0.0.0.0:8080"
+tls_cert_path = "certs/cert.pem"
+tls_key_path = "certs/key.pem"
+"#,
+    );
+    let options = get_configuration_from_file(toml.to_str()).unwrap();
+    assert_eq!(options.pkg_path, "/pkg/app");
+    assert!(matches!(options.environment, RustEnv::DEV));
+    assert_eq!(
+        options.socket_address,
+        "0.0.0.0:8080".parse::<SocketAddr>().unwrap()
+    );
+    assert_eq!(options.reload_port, 3001);
+    assert_eq!(
+        options.tls_paths(),
+        Some((Path::new("certs/cert.pem"), Path::new("certs/key.pem")))
+    );
+
+    let json = write_config(
+        "leptos.json",
+        r#"{ "pkg_path": "/pkg/json", "reload_port": 4001 }"#,
+    );
+    let options = get_configuration_from_file(json.to_str()).unwrap();
+    assert_eq!(options.pkg_path, "/pkg/json");
+    assert!(matches!(options.environment, RustEnv::PROD));
+    assert_eq!(options.reload_port, 4001);
+    assert_eq!(options.tls_paths(), None);
+
+    let yaml = write_config(
+        "leptos.yaml",
+        "pkg_path: /pkg/yaml\nenvironment: production\n",
+    );
+    let options = get_configuration_from_file(yaml.to_str()).unwrap();
+    assert_eq!(options.pkg_path, "/pkg/yaml");
+    assert!(matches!(options.environment, RustEnv::PROD));
+}
+
+#[test]
+fn rejects_unknown_formats() {
+    let _env = lock_env();
+    let ini = write_config("leptos.ini", "pkg_path=/pkg/app");
+    assert!(matches!(
+        get_configuration_from_file(ini.to_str()),
+        Err(LeptosConfigError::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn reads_cargo_metadata_with_profiles() {
+    let _env = lock_env();
+    let cargo_toml = write_config(
+        "Cargo.toml",
+        r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[package.metadata.leptos]
+pkg-path = "/pkg/app"
+environment = "prod"
+reload-port = 4001
+
+[package.metadata.leptos.dev]
+socket-address = "127.0.0.1:3000"
+
+[package.metadata.leptos.prod]
+socket-address = "0.0.0.0:80"
+"#,
+    );
+    let options = get_configuration(cargo_toml.to_str()).unwrap();
+    assert_eq!(options.pkg_path, "/pkg/app");
+    assert_eq!(options.reload_port, 4001);
+    assert_eq!(
+        options.socket_address,
+        "0.0.0.0:80".parse::<SocketAddr>().unwrap()
+    );
+}
+
+#[test]
+fn plain_files_support_profiles() {
+    let _env = lock_env();
+    let yaml = write_config(
+        "profiles.yaml",
+        "pkg_path: /pkg/app\nenvironment: dev\ndev:\n  reload_port: 5001\nprod:\n  reload_port: 6001\n",
+    );
+    let options = get_configuration_from_file(yaml.to_str()).unwrap();
+    assert_eq!(options.reload_port, 5001);
+}
+
+#[test]
+fn reports_every_invalid_value_with_its_line() {
+    let _env = lock_env();
+    let cargo_toml = write_config(
+        "Invalid.toml",
+        r#"[package]
+name = "app"
+
+[package.metadata.leptos]
+pkg-path = "/pkg/app"
+socket-address = "localhost"
+reload-port = "abc"
+tls-cert-path = "cert.pem"
+"#,
+    );
+    let errors = match get_configuration(cargo_toml.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    let summary = errors
+        .iter()
+        .map(|e| (e.key.as_str(), e.origin.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "socket_address",
+                ValueSource::File {
+                    path: cargo_toml.clone(),
+                    line: Some(6)
+                }
+            ),
+            (
+                "reload_port",
+                ValueSource::File {
+                    path: cargo_toml.clone(),
+                    line: Some(7)
+                }
+            ),
+            ("tls_key_path", ValueSource::Default),
+        ]
+    );
+}
+
+#[test]
+fn watches_for_changes() {
+    let _env = lock_env();
+    let path = write_config(
+        "watched.toml",
+        "pkg_path = \"/pkg/app\"\nreload_port = 4001\n",
+    );
+    let mut watcher = watch_configuration(&path, Duration::from_millis(10)).unwrap();
+    assert_eq!(watcher.initial().reload_port, 4001);
+    assert!(watcher.try_next().is_none());
+
+    fs::write(&path, "pkg_path = \"/pkg/app\"\nreload_port = 5001\n").unwrap();
+    let update = watcher.next().unwrap().unwrap();
+    assert_eq!(update.reload_port, 5001);
+}
+
+#[test]
+fn loader_reads_custom_env_vars() {
+    let _env = lock_env();
+    let path = write_config("loader.toml", "pkg_path = \"/pkg/app\"\n");
+    std::env::set_var("CONFIG_TEST_RELOAD_PORT", "4002");
+    std::env::set_var("CONFIG_TEST_ADDR", "0.0.0.0:8000");
+
+    let options = ConfigLoader::new()
+        .env_prefix("CONFIG_TEST")
+        .env_var("CONFIG_TEST_ADDR", "socket_address")
+        .load_file(path.to_str())
+        .unwrap();
+    assert_eq!(options.reload_port, 4002);
+    assert_eq!(
+        options.socket_address,
+        "0.0.0.0:8000".parse::<SocketAddr>().unwrap()
+    );
+
+    std::env::set_var("CONFIG_TEST_ADDR", "nowhere");
+    let errors = match ConfigLoader::new()
+        .env_var("CONFIG_TEST_ADDR", "socket_address")
+        .load_file(path.to_str())
+    {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(
+        errors[0].origin,
+        ValueSource::Env("CONFIG_TEST_ADDR".into())
+    );
+}
+
+#[test]
+fn resolves_hashed_asset_paths() {
+    let _env = lock_env();
+    let hash_file = write_config("hash.txt", "js: 4fa2c1\nwasm: 9e0b1d\ncss: 77aa00\n");
+    let options = RenderOptions::builder().pkg_path("/pkg/app").build();
+    assert_eq!(
+        options.asset_paths().unwrap(),
+        AssetPaths {
+            js: "/pkg/app.js".into(),
+            wasm: "/pkg/app_bg.wasm".into()
+        }
+    );
+
+    let options = RenderOptions::builder()
+        .pkg_path("/pkg/app")
+        .hash_files(true)
+        .hash_file_name(hash_file.to_str().unwrap())
+        .build();
+    assert_eq!(
+        options.asset_paths().unwrap(),
+        AssetPaths {
+            js: "/pkg/app.4fa2c1.js".into(),
+            wasm: "/pkg/app_bg.9e0b1d.wasm".into()
+        }
+    );
+}
+
+#[test]
+fn finds_workspace_member_settings() {
+    let _env = lock_env();
+    let root = write_config(
+        "workspace/Cargo.toml",
+        r#"[workspace]
+members = ["apps/*"]
+
+[workspace.metadata.leptos]
+reload-port = 4003
+socket-address = "0.0.0.0:80"
+"#,
+    );
+    write_config(
+        "workspace/apps/admin/Cargo.toml",
+        r#"[package]
+name = "admin"
+
+[package.metadata.leptos]
+pkg-path = "/pkg/admin"
+socket-address = "0.0.0.0:8080"
+"#,
+    );
+
+    let options = ConfigLoader::new()
+        .package("admin")
+        .load(root.to_str())
+        .unwrap();
+    assert_eq!(options.pkg_path, "/pkg/admin");
+    assert_eq!(options.reload_port, 4003);
+    assert_eq!(
+        options.socket_address,
+        "0.0.0.0:8080".parse::<SocketAddr>().unwrap()
+    );
+
+    assert!(ConfigLoader::new()
+        .package("missing")
+        .load(root.to_str())
+        .is_err());
+}
+
+#[test]
+fn discovers_manifests_upwards() {
+    let _env = lock_env();
+    write_config(
+        "discover/Cargo.toml",
+        r#"[workspace]
+members = ["app"]
+
+[workspace.metadata.leptos]
+reload-port = 4010
+"#,
+    );
+    write_config(
+        "discover/app/Cargo.toml",
+        r#"[package]
+name = "app"
+
+[package.metadata.leptos]
+pkg-path = "/pkg/discovered"
+"#,
+    );
+    let nested = write_config("discover/app/src/bin/main.rs", "fn main() {}\n");
+
+    let cwd = std::env::current_dir().unwrap();
+    std::env::set_current_dir(nested.parent().unwrap()).unwrap();
+    let options = ConfigLoader::new().load(None);
+    std::env::set_current_dir(cwd).unwrap();
+
+    let options = options.unwrap();
+    assert_eq!(options.pkg_path, "/pkg/discovered");
+    assert_eq!(options.reload_port, 4010);
+}
+
+#[test]
+fn resolves_precompressed_static_files() {
+    let _env = lock_env();
+    write_config("site/pkg/app.js", "js");
+    write_config("site/pkg/app.js.br", "br");
+    write_config("site/pkg/app_bg.wasm", "wasm");
+    let site = write_config("site/style.css", "css")
+        .parent()
+        .unwrap()
+        .to_path_buf();
+
+    let options = RenderOptions::builder()
+        .pkg_path("/pkg/app")
+        .precompress(true)
+        .static_cache_control("max-age=60")
+        .wasm_cache_control("max-age=31536000, immutable")
+        .build();
+
+    let js = options
+        .static_file(&site, "/pkg/app.js", Some("gzip, br"))
+        .unwrap();
+    assert_eq!(js.path, site.join("pkg/app.js.br"));
+    assert_eq!(js.content_encoding, Some("br"));
+    assert_eq!(
+        js.cache_control.as_deref(),
+        Some("max-age=31536000, immutable")
+    );
+
+    let js = options
+        .static_file(&site, "/pkg/app.js", Some("gzip"))
+        .unwrap();
+    assert_eq!(js.path, site.join("pkg/app.js"));
+    assert_eq!(js.content_encoding, None);
+
+    let css = options.static_file(&site, "style.css", None).unwrap();
+    assert_eq!(css.cache_control.as_deref(), Some("max-age=60"));
+
+    assert!(options.static_file(&site, "/missing.css", None).is_none());
+    assert!(options
+        .static_file(&site, "/../site/style.css", None)
+        .is_none());
+}
+
+#[test]
+fn reads_named_sites() {
+    let _env = lock_env();
+    let cargo_toml = write_config(
+        "Sites.toml",
+        r#"[package]
+name = "server"
+
+[package.metadata.leptos]
+reload-port = 4004
+
+[package.metadata.leptos.sites.main]
+pkg-path = "/pkg/main"
+
+[package.metadata.leptos.sites.admin]
+pkg-path = "/pkg/admin"
+socket-address = "127.0.0.1:3002"
+"#,
+    );
+    let admin = get_configuration_for_site(cargo_toml.to_str(), "admin").unwrap();
+    assert_eq!(admin.pkg_path, "/pkg/admin");
+    assert_eq!(admin.reload_port, 4004);
+    assert_eq!(
+        admin.socket_address,
+        "127.0.0.1:3002".parse::<SocketAddr>().unwrap()
+    );
+
+    assert_eq!(
+        ConfigLoader::new().site_names(cargo_toml.to_str()).unwrap(),
+        vec!["admin".to_string(), "main".to_string()]
+    );
+    assert!(get_configuration_for_site(cargo_toml.to_str(), "blog").is_err());
+}
+
+#[test]
+fn strict_env_rejects_unknown_environments() {
+    let _env = lock_env();
+    let path = write_config(
+        "staging.toml",
+        "pkg_path = \"/pkg/app\"\nenvironment = \"staging\"\n",
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.environment, RustEnv::PROD);
+
+    let errors = match ConfigLoader::new()
+        .strict_env(true)
+        .load_file(path.to_str())
+    {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "environment");
+
+    let path = write_config(
+        "testing.toml",
+        "pkg_path = \"/pkg/app\"\nenvironment = \"test\"\n",
+    );
+    let options = ConfigLoader::new()
+        .strict_env(true)
+        .load_file(path.to_str())
+        .unwrap();
+    assert_eq!(options.environment, RustEnv::TEST);
+
+    assert_eq!("Testing".parse::<RustEnv>(), Ok(RustEnv::TEST));
+    assert!(matches!(
+        RustEnv::parse_strict("staging"),
+        Err(LeptosConfigError::UnknownEnv(_))
+    ));
+}
+
+#[test]
+fn overlays_command_line_arguments() {
+    let _env = lock_env();
+    let path = write_config("args.toml", "pkg_path = \"/pkg/app\"\nreload_port = 4005\n");
+    let options = ConfigLoader::new()
+        .args([
+            "--verbose",
+            "--site-addr",
+            "0.0.0.0:80",
+            "--reload-port=4006",
+            "--precompress",
+            "--env",
+            "dev",
+        ])
+        .load_file(path.to_str())
+        .unwrap();
+    assert_eq!(
+        options.socket_address,
+        "0.0.0.0:80".parse::<SocketAddr>().unwrap()
+    );
+    assert_eq!(options.reload_port, 4006);
+    assert!(options.precompress);
+    assert_eq!(options.environment, RustEnv::DEV);
+
+    let errors = match ConfigLoader::new()
+        .args(["--reload-port", "abc"])
+        .load_file(path.to_str())
+    {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].origin, ValueSource::Arg("--reload-port".into()));
+}
+
+#[test]
+fn reads_durations_and_sizes() {
+    let _env = lock_env();
+    let path = write_config(
+        "limits.toml",
+        r#"
+pkg_path = "/pkg/app"
+server_fn_timeout = "1m30s"
+max_request_size = "2mb"
+max_upload_size = "50mb"
+stream_chunk_size = 16384
+"#,
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.server_fn_timeout, Some(Duration::from_secs(90)));
+    assert_eq!(options.max_request_size, Some(2 * 1024 * 1024));
+    assert_eq!(options.max_upload_size, Some(50 * 1024 * 1024));
+    assert_eq!(options.stream_chunk_size, Some(16384));
+
+    let path = write_config(
+        "bad_limits.toml",
+        "pkg_path = \"/pkg/app\"\nserver_fn_timeout = \"soon\"\nmax_request_size = \"2 parsecs\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    let keys = errors.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+    assert_eq!(keys, vec!["server_fn_timeout", "max_request_size"]);
+}
+
+#[test]
+fn builds_client_runtime_config() {
+    let _env = lock_env();
+    let path = write_config(
+        "client.toml",
+        r#"
+pkg_path = "/pkg/app"
+environment = "dev"
+client_config_fields = ["environment", "reload_port"]
+
+[client_config]
+api_base_url = "https://api.example.com"
+new_checkout = "true"
+"#,
+    );
+    std::env::set_var("CLIENT_TEST_API_URL", "https://staging.example.com");
+    let options = ConfigLoader::new()
+        .env_var("CLIENT_TEST_API_URL", "client_config.api_base_url")
+        .load_file(path.to_str())
+        .unwrap();
+    let config = options.runtime_config();
+    assert_eq!(config.get("environment"), Some("dev"));
+    assert_eq!(config.get("reload_port"), Some("3001"));
+    assert_eq!(
+        config.get("api_base_url"),
+        Some("https://staging.example.com")
+    );
+    assert_eq!(config.get("new_checkout"), Some("true"));
+    assert_eq!(config.get("pkg_path"), None);
+    assert_eq!(
+        RuntimeConfig::from_json(&script_body(&config.to_script())).unwrap(),
+        config
+    );
+
+    let path = write_config(
+        "bad_client.toml",
+        "pkg_path = \"/pkg/app\"\nclient_config_fields = [\"tls_key_path\"]\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "client_config_fields");
+}
+
+fn script_body(script: &str) -> String {
+    let start = script.find('>').unwrap() + 1;
+    let end = script.rfind("</script>").unwrap();
+    script[start..end].to_string()
+}
+
+#[test]
+fn reports_where_each_value_came_from() {
+    let _env = lock_env();
+    let path = write_config(
+        "sources.toml",
+        "pkg_path = \"/pkg/app\"\nsocket_address = \"127.0.0.1:3000\"\n",
+    );
+    std::env::set_var("SOURCES_TEST_ADDR", "0.0.0.0:9000");
+    let options = ConfigLoader::new()
+        .env_prefix("SOURCES_TEST")
+        .env_var("SOURCES_TEST_ADDR", "socket_address")
+        .load_file(path.to_str())
+        .unwrap();
+
+    assert_eq!(
+        options.sources.get("pkg_path"),
+        Some(&ValueSource::File {
+            path: path.clone(),
+            line: Some(1)
+        })
+    );
+    assert_eq!(
+        options.sources.get("socket_address"),
+        Some(&ValueSource::Env("SOURCES_TEST_ADDR".into()))
+    );
+    assert_eq!(
+        options.sources.get("reload_port"),
+        Some(&ValueSource::Default)
+    );
+
+    let report = options.to_string();
+    assert!(report.contains(r#"socket_address = "0.0.0.0:9000" (env var SOURCES_TEST_ADDR)"#));
+    assert!(report.contains("reload_port = 3001 (default)"));
+    assert!(!report.contains("tls_key_path"));
+}
+
+#[test]
+fn reads_reload_websocket_options() {
+    let _env = lock_env();
+    let path = write_config(
+        "reload.toml",
+        r#"
+pkg_path = "/pkg/app"
+reload_ws_protocol = "wss"
+reload_host = "my-workspace-3001.app.github.dev"
+reload_external_port = 443
+"#,
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.reload_ws_protocol, ReloadWSProtocol::WSS);
+    assert_eq!(options.reload_port, 3001);
+    assert_eq!(
+        options.reload_url(),
+        "wss://my-workspace-3001.app.github.dev:443/autoreload"
+    );
+
+    let path = write_config(
+        "bad_reload.toml",
+        "pkg_path = \"/pkg/app\"\nreload_ws_protocol = \"https\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "reload_ws_protocol");
+}
+
+#[test]
+fn reads_ssr_mode() {
+    let _env = lock_env();
+    let path = write_config(
+        "ssr_mode.toml",
+        "pkg_path = \"/pkg/app\"\nssr-mode = \"out-of-order\"\n",
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.ssr_mode, SsrMode::OutOfOrder);
+
+    let path = write_config("default_ssr_mode.toml", "pkg_path = \"/pkg/app\"\n");
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.ssr_mode, SsrMode::Prioritized);
+
+    let path = write_config(
+        "bad_ssr_mode.toml",
+        "pkg_path = \"/pkg/app\"\nssr_mode = \"in-order\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "ssr_mode");
+}
+
+#[test]
+fn reads_ssr_timeout() {
+    let _env = lock_env();
+    let path = write_config(
+        "ssr_timeout.toml",
+        "pkg_path = \"/pkg/app\"\nssr-mode = \"async\"\nssr-timeout = \"1500ms\"\n",
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert_eq!(options.ssr_mode, SsrMode::Async);
+    assert_eq!(options.ssr_timeout, Some(Duration::from_millis(1500)));
+
+    let path = write_config(
+        "bad_ssr_timeout.toml",
+        "pkg_path = \"/pkg/app\"\nssr_timeout = \"eventually\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "ssr_timeout");
+}
+
+#[test]
+fn reads_islands() {
+    let _env = lock_env();
+    let path = write_config("islands.toml", "pkg_path = \"/pkg/app\"\nislands = true\n");
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert!(options.islands);
+
+    let path = write_config("no_islands.toml", "pkg_path = \"/pkg/app\"\n");
+    let options = ConfigLoader::new()
+        .args(["--islands"])
+        .load_file(path.to_str())
+        .unwrap();
+    assert!(options.islands);
+
+    let path = write_config(
+        "bad_islands.toml",
+        "pkg_path = \"/pkg/app\"\nislands = \"sometimes\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "islands");
+}
+
+#[test]
+fn reads_no_js_audit() {
+    let _env = lock_env();
+    let path = write_config("no_js_audit.toml", "pkg_path = \"/pkg/app\"\n");
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert!(!options.no_js_audit);
+
+    let options = ConfigLoader::new()
+        .args(["--no-js-audit"])
+        .load_file(path.to_str())
+        .unwrap();
+    assert!(options.no_js_audit);
+
+    let path = write_config(
+        "bad_no_js_audit.toml",
+        "pkg_path = \"/pkg/app\"\nno_js_audit = \"mostly\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "no_js_audit");
+}
+
+#[test]
+fn reads_server_navigation() {
+    let _env = lock_env();
+    let path = write_config(
+        "server_navigation.toml",
+        "pkg_path = \"/pkg/app\"\nislands = true\nserver_navigation = true\n",
+    );
+    let options = get_configuration_from_file(path.to_str()).unwrap();
+    assert!(options.islands && options.server_navigation);
+
+    let path = write_config(
+        "bad_server_navigation.toml",
+        "pkg_path = \"/pkg/app\"\nserver_navigation = \"always\"\n",
+    );
+    let errors = match get_configuration_from_file(path.to_str()) {
+        Err(LeptosConfigError::Invalid(errors)) => errors,
+        _ => panic!("expected validation errors"),
+    };
+    assert_eq!(errors[0].key, "server_navigation");
+}
@@ -0,0 +1,183 @@
+use std::{cell::RefCell, error::Error, rc::Rc};
+
+use leptos_dom::{Child, IntoChild};
+use leptos_reactive::{
+    create_rw_signal, provide_context, ErrorBoundaryContext, ErrorHook, Errors, ReadSignal,
+    RwSignal, Scope,
+};
+use typed_builder::TypedBuilder;
+
+/// Props for the [ErrorBoundary](crate::ErrorBoundary) component, which shows a fallback
+/// when an error is rendered in its children.
+#[derive(TypedBuilder)]
+pub struct ErrorBoundaryProps<F, H, E, G>
+where
+    F: Fn(ReadSignal<Errors>, ErrorReset) -> H + 'static,
+    H: IntoChild,
+    E: IntoChild,
+    G: Fn() -> E,
+{
+    /// Will be displayed while any errors are caught, given the errors and an [ErrorReset]
+    /// that clears them and renders the `children` again.
+    pub fallback: F,
+    /// Will be called with each error that is caught, e.g., to report it to an error
+    /// tracking service.
+    #[builder(default, setter(transform = |on_error: impl Fn(&Rc<dyn Error>) + 'static| {
+        Some(Rc::new(on_error) as ErrorHook)
+    }))]
+    pub on_error: Option<ErrorHook>,
+    /// Whether panics while rendering the `children` are caught as
+    /// [RenderPanic](leptos_reactive::RenderPanic) errors and show the `fallback`, instead of
+    /// unwinding past the boundary. See [ErrorBoundaryContext::catch_panics] for the targets
+    /// on which panics can be caught.
+    #[builder(default)]
+    pub catch_panics: bool,
+    /// Will be displayed as long as no errors are caught.
+    pub children: Box<dyn Fn() -> Vec<G>>,
+}
+
+/// If any `Result`s in the `children` of this component render an `Err`, it will show the
+/// `fallback` instead of the `children` until each of those has rendered an `Ok` again, or
+/// until the `fallback` calls [ErrorReset::reset] to render the `children` from scratch.
+///
+/// With `catch_panics`, a panic while rendering the `children`, or while running one of the
+/// reactive functions among them, is caught as an error as well. It remains caught until the
+/// `children` are reset.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_core::*;
+/// # use leptos_macro::*;
+/// # use leptos_dom::*; use leptos::*;
+/// # run_scope(create_runtime(), |cx| {
+/// # if cfg!(not(any(feature = "csr", feature = "hydrate", feature = "ssr"))) {
+/// let (value, set_value) = create_signal(cx, "0".to_string());
+///
+/// let fallback = move |errors: ReadSignal<Errors>, reset: ErrorReset| view! { cx,
+///   <div>
+///     <p>{move || format!("{} error(s)", errors.with(|errors| errors.len()))}</p>
+///     <button on:click=move |_| reset.reset()>"Try again"</button>
+///   </div>
+/// };
+///
+/// view! { cx,
+///   <div>
+///     <ErrorBoundary
+///       fallback=fallback
+///       on_error={|error: &std::rc::Rc<dyn std::error::Error>| log!("reporting {error}")}
+///     >
+///       {move || value.get().parse::<i32>().map(|n| n * 2)}
+///     </ErrorBoundary>
+///   </div>
+/// };
+/// # }
+/// # });
+/// ```
+#[allow(non_snake_case)]
+pub fn ErrorBoundary<F, H, E, G>(
+    cx: Scope,
+    props: ErrorBoundaryProps<F, H, E, G>,
+) -> impl Fn() -> Child
+where
+    F: Fn(ReadSignal<Errors>, ErrorReset) -> H + 'static,
+    H: IntoChild,
+    E: IntoChild,
+    G: Fn() -> E + 'static,
+{
+    let context = ErrorBoundaryContext::new(cx, props.on_error);
+
+    // provide this ErrorBoundaryContext to any results below it
+    provide_context(cx, context.clone());
+
+    let child = (props.children)().swap_remove(0);
+    let fallback = props.fallback;
+    let catch_panics = props.catch_panics;
+    let reset = ErrorReset {
+        generation: create_rw_signal(cx, 0),
+    };
+    let errors = context.errors();
+
+    // the children are only rendered again after a reset, so that the errors they catch
+    // don't cause them to be rendered over and over again
+    let rendered: RefCell<Option<(usize, Child)>> = RefCell::new(None);
+
+    move || {
+        let generation = reset.generation.get();
+        let children = match &*rendered.borrow() {
+            Some((rendered_generation, children)) if *rendered_generation == generation => {
+                Some(children.clone())
+            }
+            _ => None,
+        };
+        let children = children.unwrap_or_else(|| {
+            context.reset();
+            let children = if catch_panics {
+                context
+                    .catch_panics(|| render_children(cx, &child))
+                    .map(|children| catch_child_panics(&context, children))
+                    .unwrap_or(Child::Null)
+            } else {
+                render_children(cx, &child)
+            };
+            *rendered.borrow_mut() = Some((generation, children.clone()));
+            children
+        });
+
+        if errors.with(Errors::is_empty) {
+            children
+        } else {
+            fallback(errors, reset).into_child(cx)
+        }
+    }
+}
+
+/// Wraps a reactive child, so that panics in it are caught whenever it runs again.
+fn catch_child_panics(context: &ErrorBoundaryContext, child: Child) -> Child {
+    match child {
+        Child::Fn(f) => {
+            let context = context.clone();
+            Child::Fn(Rc::new(RefCell::new(move || {
+                let child = context
+                    .catch_panics(|| (f.borrow_mut())())
+                    .unwrap_or(Child::Null);
+                catch_child_panics(&context, child)
+            })))
+        }
+        child => child,
+    }
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn render_children<E, G>(cx: Scope, child: &G) -> Child
+where
+    E: IntoChild,
+    G: Fn() -> E,
+{
+    cx.untrack(|| child().into_child(cx))
+}
+
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+fn render_children<E, G>(cx: Scope, child: &G) -> Child
+where
+    E: IntoChild,
+    G: Fn() -> E,
+{
+    // render the children to HTML right away, so that any errors in them are caught before
+    // deciding whether to show the fallback
+    Child::Text(cx.untrack(|| child().into_child(cx).as_child_string()))
+}
+
+/// A handle passed to the `fallback` of an [ErrorBoundary](crate::ErrorBoundary), which
+/// clears the errors it has caught and renders its `children` again.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorReset {
+    generation: RwSignal<usize>,
+}
+
+impl ErrorReset {
+    /// Clears the caught errors and renders the `children` of the boundary again, e.g., to
+    /// retry after an error that may have been temporary.
+    pub fn reset(&self) {
+        self.generation.update(|generation| *generation += 1);
+    }
+}
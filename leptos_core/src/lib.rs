@@ -3,11 +3,13 @@
 //! This crate contains several utility pieces that depend on multiple crates.
 //! They are all re-exported in the main `leptos` crate.
 
+mod error_boundary;
 mod for_component;
 mod map;
 mod suspense;
 mod transition;
 
+pub use error_boundary::*;
 pub use for_component::*;
 pub use map::*;
 pub use suspense::*;
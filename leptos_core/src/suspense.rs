@@ -2,6 +2,7 @@ use crate as leptos;
 use leptos_dom::{Child, IntoChild};
 use leptos_macro::Props;
 use leptos_reactive::{provide_context, Scope, SuspenseContext};
+use std::time::Duration;
 
 /// Props for the [Suspense](crate::Suspense) component, which shows a fallback
 /// while [Resource](leptos_reactive::Resource)s are being read.
@@ -14,6 +15,15 @@ where
 {
     /// Will be displayed while resources are pending.
     pub fallback: F,
+    /// How long resources must be pending, in milliseconds, before the `fallback` is shown.
+    /// Until then, the previous `children` remain visible, or nothing on the first load, so
+    /// that resources that resolve quickly don't flash the `fallback`.
+    #[builder(default)]
+    pub delay_ms: u64,
+    /// How long the `fallback` remains visible, in milliseconds, once it has been shown,
+    /// even if the resources resolve sooner, so that it doesn't flicker.
+    #[builder(default)]
+    pub min_duration_ms: u64,
     /// Will be displayed once all resources have resolved.
     pub children: Box<dyn Fn() -> Vec<G>>,
 }
@@ -29,6 +39,11 @@ where
 /// those resources are read under the suspense), so you cannot assume that resources have
 /// `Some` value in `children`.
 ///
+/// In the browser, `delay_ms` postpones showing the `fallback` until resources have been
+/// loading for a while, and `min_duration_ms` keeps it visible for a minimum amount of time
+/// once it has been shown. On the server, the `fallback` is always rendered while resources
+/// are pending, and replaced once they have been streamed in.
+///
 /// ```
 /// # use leptos_reactive::*;
 /// # use leptos_core::*;
@@ -44,7 +59,11 @@ where
 ///
 /// view! { cx,
 ///   <div>
-///     <Suspense fallback={"Loading (Suspense Fallback)...".to_string()}>
+///     <Suspense
+///       fallback={"Loading (Suspense Fallback)...".to_string()}
+///       delay_ms=200
+///       min_duration_ms=500
+///     >
 ///       {move || {
 ///           cats.read().map(|data| match data {
 ///             Err(_) => view! { cx,  <pre>"Error"</pre> },
@@ -82,7 +101,14 @@ where
 
     let child = (props.children)().swap_remove(0);
 
-    render_suspense(cx, context, props.fallback, child)
+    render_suspense(
+        cx,
+        context,
+        props.fallback,
+        child,
+        Duration::from_millis(props.delay_ms),
+        Duration::from_millis(props.min_duration_ms),
+    )
 }
 
 #[cfg(any(feature = "csr", feature = "hydrate"))]
@@ -91,18 +117,36 @@ fn render_suspense<'a, F, E, G>(
     context: SuspenseContext,
     fallback: F,
     child: G,
+    delay: Duration,
+    min_duration: Duration,
 ) -> impl Fn() -> Child
 where
     F: IntoChild + Clone,
     E: IntoChild,
     G: Fn() -> E,
 {
-    move || {
-        if context.ready() {
-            (child)().into_child(cx)
-        } else {
-            fallback.clone().into_child(cx)
+    use std::cell::{Cell, RefCell};
+
+    // without any timing, the fallback is shown exactly while resources are pending
+    let fallback_visible = (!delay.is_zero() || !min_duration.is_zero())
+        .then(|| context.fallback_visible(cx, delay, min_duration));
+    let has_rendered_once = Cell::new(false);
+    let prev_child = RefCell::new(Child::Null);
+
+    move || match fallback_visible {
+        None if context.ready() => (child)().into_child(cx),
+        None => fallback.clone().into_child(cx),
+        Some(fallback_visible) if fallback_visible.get() => fallback.clone().into_child(cx),
+        Some(_) if context.ready() => {
+            let current_child = (child)().into_child(cx);
+            // the first render only registers the resources that are read, so it's not kept
+            // around while waiting for them
+            if has_rendered_once.replace(true) {
+                *prev_child.borrow_mut() = current_child.clone();
+            }
+            current_child
         }
+        Some(_) => prev_child.borrow().clone(),
     }
 }
 
@@ -112,6 +156,8 @@ fn render_suspense<'a, F, E, G>(
     context: SuspenseContext,
     fallback: F,
     orig_child: G,
+    delay: Duration,
+    min_duration: Duration,
 ) -> impl Fn() -> Child
 where
     F: IntoChild + Clone,
@@ -121,6 +167,9 @@ where
     use leptos_dom::IntoAttribute;
     use leptos_macro::view;
 
+    // the fallback is replaced by streamed HTML, rather than after a timeout
+    _ = (delay, min_duration);
+
     let initial = {
         // run the child; we'll probably throw this away, but it will register resource reads
         let mut child = orig_child().into_child(cx);
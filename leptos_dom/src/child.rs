@@ -2,7 +2,7 @@ use cfg_if::cfg_if;
 
 use std::{cell::RefCell, rc::Rc};
 
-use leptos_reactive::Scope;
+use leptos_reactive::{report_error, use_context, ErrorBoundaryContext, ErrorLocation, Scope};
 
 use crate::Node;
 
@@ -115,6 +115,41 @@ where
     }
 }
 
+/// Renders the value if the result is [Ok]. An [Err] renders nothing, and is caught by the
+/// nearest [ErrorBoundaryContext], if there is one, so that an
+/// [`ErrorBoundary`](https://docs.rs/leptos_core/latest/leptos_core/fn.ErrorBoundary.html)
+/// can show its fallback instead. Otherwise, it is passed to the hook set with
+/// [set_error_hook](leptos_reactive::set_error_hook).
+impl<T, E> IntoChild for Result<T, E>
+where
+    T: IntoChild,
+    E: std::error::Error + 'static,
+{
+    fn into_child(self, cx: Scope) -> Child {
+        let context = use_context::<ErrorBoundaryContext>(cx);
+        match self {
+            Ok(val) => {
+                if let Some(context) = context {
+                    context.resolve();
+                }
+                val.into_child(cx)
+            }
+            Err(error) => {
+                match context {
+                    Some(context) => context.throw(Rc::new(error)),
+                    None => {
+                        crate::debug_warn!(
+                            "[Result] rendered an error outside of an <ErrorBoundary/>: {error}"
+                        );
+                        report_error(&error, ErrorLocation::Render);
+                    }
+                }
+                Child::Null
+            }
+        }
+    }
+}
+
 impl IntoChild for Vec<Node> {
     fn into_child(self, _cx: Scope) -> Child {
         Child::Nodes(self)
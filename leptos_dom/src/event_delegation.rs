@@ -19,65 +19,77 @@ pub(crate) fn add_event_listener(event_name: &'static str) {
             let handler = move |ev: web_sys::Event| {
                 let target = ev.target();
                 let node = ev.composed_path().get(0);
-                let mut node = if node.is_undefined() || node.is_null() {
+                let node = if node.is_undefined() || node.is_null() {
                     JsValue::from(target)
                 } else {
                     node
                 };
 
-                // TODO reverse Shadow DOM retargetting
+                run_handlers(&key, &ev, node, None);
+            };
+
+            window_event_listener(event_name, handler);
 
-                // TODO simulate currentTarget
+            // register that we've created handler
+            events.insert(event_name);
+        }
+    })
+}
 
-                while !node.is_null() {
-                    let node_is_disabled =
-                        js_sys::Reflect::get(&node, &JsValue::from_str("disabled"))
-                            .unwrap_throw()
-                            .is_truthy();
-                    if !node_is_disabled {
-                        let maybe_handler = js_sys::Reflect::get(&node, &key).unwrap_throw();
-                        if !maybe_handler.is_undefined() {
-                            let f = maybe_handler.unchecked_ref::<js_sys::Function>();
-                            if let Err(e) = f.call1(&node, &ev) {
-                                crate::debug_warn!("{e:#?}");
+/// Runs the delegated handlers for an event, as the events it's delegated to do, on `node` and
+/// then on each of its ancestors, up to `root`, if given, or the document.
+pub(crate) fn run_handlers(
+    key: &JsValue,
+    ev: &web_sys::Event,
+    mut node: JsValue,
+    root: Option<&web_sys::Element>,
+) {
+    // TODO reverse Shadow DOM retargetting
 
-                                #[cfg(not(debug_assertions))]
-                                {
-                                    _ = e;
-                                }
-                            }
+    // TODO simulate currentTarget
 
-                            if ev.cancel_bubble() {
-                                return;
-                            }
-                        }
-                    }
+    while !node.is_null() {
+        let node_is_disabled = js_sys::Reflect::get(&node, &JsValue::from_str("disabled"))
+            .unwrap_throw()
+            .is_truthy();
+        if !node_is_disabled {
+            let maybe_handler = js_sys::Reflect::get(&node, key).unwrap_throw();
+            if !maybe_handler.is_undefined() {
+                let f = maybe_handler.unchecked_ref::<js_sys::Function>();
+                if let Err(e) = f.call1(&node, ev) {
+                    crate::debug_warn!("{e:#?}");
 
-                    // navigate up tree
-                    let host =
-                        js_sys::Reflect::get(&node, &JsValue::from_str("host")).unwrap_throw();
-                    if host.is_truthy() && host != node && host.dyn_ref::<web_sys::Node>().is_some()
-                    {
-                        node = host;
-                    } else if let Some(parent) =
-                        node.unchecked_into::<web_sys::Node>().parent_node()
+                    #[cfg(not(debug_assertions))]
                     {
-                        node = parent.into()
-                    } else {
-                        node = JsValue::null()
+                        _ = e;
                     }
                 }
-            };
 
-            window_event_listener(event_name, handler);
+                if ev.cancel_bubble() {
+                    return;
+                }
+            }
+        }
 
-            // register that we've created handler
-            events.insert(event_name);
+        if let Some(root) = root {
+            if AsRef::<JsValue>::as_ref(root) == &node {
+                return;
+            }
         }
-    })
+
+        // navigate up tree
+        let host = js_sys::Reflect::get(&node, &JsValue::from_str("host")).unwrap_throw();
+        if host.is_truthy() && host != node && host.dyn_ref::<web_sys::Node>().is_some() {
+            node = host;
+        } else if let Some(parent) = node.unchecked_into::<web_sys::Node>().parent_node() {
+            node = parent.into()
+        } else {
+            node = JsValue::null()
+        }
+    }
 }
 
-pub(crate) fn event_delegation_key(event_name: &'static str) -> String {
+pub(crate) fn event_delegation_key(event_name: &str) -> String {
     let mut n = String::from("$$$");
     n.push_str(event_name);
     n
@@ -0,0 +1,98 @@
+//! Events that happen after a server-rendered page is shown but before the part of it they
+//! happen in is hydrated are recorded by a small inline script, sent along with the shell of
+//! the page, and replayed through the delegated event handlers once that part is hydrated, so
+//! that the first click on a slow device isn't swallowed.
+
+/// The events that are recorded until the part of the page they happen in is hydrated.
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+const REPLAYED_EVENTS: [&str; 5] = ["click", "dblclick", "input", "change", "keydown"];
+
+/// The most events that are recorded before the page is hydrated.
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+const MAX_RECORDED_EVENTS: usize = 100;
+
+/// The script that records events until the part of the page they happen in is hydrated: the
+/// island they happen in, if any, or else the whole page.
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+pub(crate) fn recorder_script() -> String {
+    let events = serde_json::to_string(&REPLAYED_EVENTS).unwrap();
+    format!(
+        r#"__LEPTOS_EVENTS = [];
+        __LEPTOS_HYDRATED = new WeakSet();
+        {events}.forEach(function(type) {{
+            document.addEventListener(type, function(ev) {{
+                var target = ev.composedPath()[0] || ev.target;
+                if(!ev.isTrusted || !(target instanceof Element)) return;
+                if(__LEPTOS_HYDRATED.has(target.closest("leptos-island") || document)) return;
+                if(__LEPTOS_EVENTS.length < {MAX_RECORDED_EVENTS}) __LEPTOS_EVENTS.push([ev, target]);
+            }}, true);
+        }});"#
+    )
+}
+
+#[cfg(feature = "hydrate")]
+fn global(name: &str) -> Option<wasm_bindgen::JsValue> {
+    js_sys::Reflect::get(&crate::window(), &wasm_bindgen::JsValue::from_str(name))
+        .ok()
+        .filter(|value| !value.is_undefined())
+}
+
+/// Stops recording the events that happen in `owner`, an island, or the document once the
+/// whole page is hydrated, as they're now handled as they happen.
+#[cfg(feature = "hydrate")]
+pub(crate) fn mark_hydrated(owner: &wasm_bindgen::JsValue) {
+    use wasm_bindgen::JsCast;
+
+    if let Some(hydrated) = global("__LEPTOS_HYDRATED") {
+        hydrated
+            .unchecked_into::<js_sys::WeakSet>()
+            .add(owner.unchecked_ref());
+    }
+}
+
+/// Replays the recorded events that happened under `root`, once it has been hydrated, in the
+/// order they happened. Events in an island under `root` that isn't hydrated yet are kept until
+/// it is.
+///
+/// Only the delegated event handlers between the element an event happened on and `root` are
+/// run again, as the browser has already done whatever else the event does, e.g., toggling a
+/// checkbox.
+#[cfg(feature = "hydrate")]
+pub(crate) fn replay_events(root: &web_sys::Element) {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let (events, hydrated) = match (global("__LEPTOS_EVENTS"), global("__LEPTOS_HYDRATED")) {
+        (Some(events), Some(hydrated)) => (
+            events.unchecked_into::<js_sys::Array>(),
+            hydrated.unchecked_into::<js_sys::WeakSet>(),
+        ),
+        // the page wasn't rendered with the recorder
+        _ => return,
+    };
+
+    let pending = js_sys::Array::new();
+    let mut replayed = Vec::new();
+    for recorded in events.iter() {
+        let recorded = recorded.unchecked_into::<js_sys::Array>();
+        let target = recorded.get(1).unchecked_into::<web_sys::Element>();
+        let owner: JsValue = match target.closest("leptos-island") {
+            Ok(Some(island)) => island.into(),
+            _ => crate::document().into(),
+        };
+        if root.contains(Some(&target)) && hydrated.has(owner.unchecked_ref()) {
+            replayed.push((recorded.get(0).unchecked_into::<web_sys::Event>(), target));
+        } else {
+            pending.push(&recorded);
+        }
+    }
+    _ = js_sys::Reflect::set(
+        &crate::window(),
+        &JsValue::from_str("__LEPTOS_EVENTS"),
+        &pending,
+    );
+
+    for (ev, target) in replayed {
+        let key = JsValue::from_str(&crate::event_delegation::event_delegation_key(&ev.type_()));
+        crate::event_delegation::run_handlers(&key, &ev, target.into(), Some(root));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::Element;
+use cfg_if::cfg_if;
+use leptos_reactive::Scope;
+use serde::Serialize;
+use std::{cell::RefCell, rc::Rc};
+
+/// The islands rendered on a page, which the server integrations provide as context when the
+/// `islands` option of their `RenderOptions` is set, and send to the browser once the page is
+/// rendered, so that each island can be hydrated on its own.
+///
+/// Without an `IslandManifest`, a component marked with `#[island]` renders like any other
+/// component.
+#[derive(Debug, Clone, Default)]
+pub struct IslandManifest {
+    islands: Rc<RefCell<Vec<IslandEntry>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct IslandEntry {
+    id: usize,
+    component: &'static str,
+}
+
+impl IslandManifest {
+    /// Creates an empty manifest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether no island has been rendered.
+    pub fn is_empty(&self) -> bool {
+        self.islands.borrow().is_empty()
+    }
+
+    /// Records an island of the given component, returning its ID on the page.
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    fn add(&self, component: &'static str) -> usize {
+        let mut islands = self.islands.borrow_mut();
+        let id = islands.len();
+        islands.push(IslandEntry { id, component });
+        id
+    }
+
+    /// The manifest of the page, as JSON, and the script that loads the JS and WASM files of the
+    /// app, at the given paths, and hydrates each island that isn't nested in another one, which
+    /// hydrates its own. It's empty if no island has been rendered, so no WASM is loaded at all.
+    /// The script is marked with the [Nonce](crate::Nonce) of the page, if any.
+    pub fn to_script(&self, js: &str, wasm: &str, nonce: Option<&crate::Nonce>) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let manifest = serde_json::to_string(&*self.islands.borrow()).unwrap();
+        let nonce = nonce.map(|nonce| nonce.to_attribute()).unwrap_or_default();
+        format!(
+            r#"<script id="leptos-islands" type="application/json">{manifest}</script>
+                <script type="module"{nonce}>
+                    import init, * as islands from '{js}';
+                    init('{wasm}').then(() => {{
+                        const manifest = JSON.parse(document.getElementById("leptos-islands").textContent);
+                        for (const {{ id, component }} of manifest) {{
+                            const el = document.querySelector(`leptos-island[data-island="${{id}}"]`);
+                            if (el && !el.parentElement.closest("leptos-island")) {{
+                                islands["_island_" + component](el);
+                            }}
+                        }}
+                    }});
+                </script>"#
+        )
+    }
+}
+
+/// When an island is hydrated, set with `#[island(hydrate = "visible")]`, so that widgets
+/// which aren't needed right away, e.g., below the fold, don't hold back the rest of the page.
+///
+/// An island that is hydrated later is still rendered on the server, and its HTML is claimed by
+/// the browser as soon as the page is hydrated, but its component only runs once it's needed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HydrationStrategy {
+    /// Hydrates the island as soon as the page is hydrated.
+    #[default]
+    Load,
+    /// Hydrates the island once the browser is idle, with
+    /// [`requestIdleCallback`](https://developer.mozilla.org/en-US/docs/Web/API/Window/requestIdleCallback).
+    Idle,
+    /// Hydrates the island once any of it is scrolled into view, with an
+    /// [`IntersectionObserver`](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver).
+    Visible,
+    /// Hydrates the island once the user interacts with it: when the pointer enters it, or it
+    /// gets focus, which happens before a click or a key press reaches it.
+    Interaction,
+}
+
+impl HydrationStrategy {
+    /// The name of the strategy, as it's written in `#[island(hydrate = "...")]`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HydrationStrategy::Load => "load",
+            HydrationStrategy::Idle => "idle",
+            HydrationStrategy::Visible => "visible",
+            HydrationStrategy::Interaction => "interaction",
+        }
+    }
+}
+
+impl std::str::FromStr for HydrationStrategy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "load" => Ok(HydrationStrategy::Load),
+            "idle" => Ok(HydrationStrategy::Idle),
+            "visible" => Ok(HydrationStrategy::Visible),
+            "interaction" => Ok(HydrationStrategy::Interaction),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The server-rendered element of the island that [hydrate_island] is hydrating.
+#[cfg(feature = "hydrate")]
+#[derive(Clone)]
+struct IslandRoot(Rc<RefCell<Option<web_sys::HtmlElement>>>);
+
+/// The hydration of an island that waits for its [HydrationStrategy].
+#[cfg(feature = "hydrate")]
+type PendingHydration = Rc<RefCell<Option<Box<dyn FnOnce()>>>>;
+
+// Hidden because this is primarily used by the `island` macro, not by library users.
+#[doc(hidden)]
+pub fn island<P: Serialize + 'static>(
+    cx: Scope,
+    component: &'static str,
+    strategy: HydrationStrategy,
+    props: P,
+    body: impl FnOnce(P) -> Element + 'static,
+) -> Element {
+    cfg_if! {
+        if #[cfg(feature = "hydrate")] {
+            use leptos_reactive::{on_cleanup, use_context};
+            use wasm_bindgen::JsCast;
+
+            _ = component;
+            let in_island = use_context::<IslandRoot>(cx);
+            let root = in_island.as_ref().and_then(|root| root.0.borrow_mut().take());
+            let (el, strategy): (web_sys::Element, _) = match root {
+                // hydrate_island has already waited for the strategy of the island
+                Some(el) => (el.unchecked_into(), HydrationStrategy::Load),
+                // without islands, only an island that is hydrated later is rendered as one
+                None if in_island.is_none() && strategy == HydrationStrategy::Load => {
+                    return body(props)
+                }
+                None => {
+                    thread_local! {
+                        static TEMPLATE: web_sys::HtmlTemplateElement =
+                            crate::create_template("<leptos-island></leptos-island>");
+                    }
+                    (TEMPLATE.with(|template| cx.get_next_element(template)), strategy)
+                }
+            };
+            let id = match el.get_attribute("data-island") {
+                Some(id) => id,
+                // created in the browser, so there's nothing to hydrate
+                None => {
+                    append_island(&el, &body(props));
+                    return el;
+                }
+            };
+
+            if strategy == HydrationStrategy::Load {
+                let inner = cx.with_hydration_prefix(format!("i{id}-"), move || body(props));
+                append_island(&el, &inner);
+                // its events are replayed once whatever it's hydrated with is
+                crate::event_replay::mark_hydrated(&el);
+            } else {
+                // the element is claimed now, but its keys are only claimed once it's hydrated
+                let pending: PendingHydration = Rc::new(RefCell::new(Some(Box::new({
+                    let el = el.clone();
+                    move || {
+                        cx.start_island_hydration(&el);
+                        let inner =
+                            cx.with_hydration_prefix(format!("i{id}-"), move || body(props));
+                        cx.end_hydration();
+                        append_island(&el, &inner);
+                        crate::event_replay::mark_hydrated(&el);
+                        crate::event_replay::replay_events(&el);
+                    }
+                }))));
+                on_cleanup(cx, {
+                    let pending = Rc::clone(&pending);
+                    move || _ = pending.borrow_mut().take()
+                });
+                hydrate_when(strategy, &el, pending);
+            }
+            el
+        } else if #[cfg(feature = "csr")] {
+            _ = (cx, component, strategy);
+            body(props)
+        } else {
+            use leptos_reactive::use_context;
+
+            let manifest = use_context::<IslandManifest>(cx);
+            // without islands, only an island that is hydrated later is rendered as one
+            if manifest.is_none() && strategy == HydrationStrategy::Load {
+                return body(props);
+            }
+            let hk = cx.next_hydration_key();
+            let id = match &manifest {
+                Some(manifest) => manifest.add(component).to_string(),
+                None => hk.clone(),
+            };
+            let data_props = serde_json::to_string(&props).unwrap();
+            let data_props = crate::escape_attr(&data_props);
+            let data_hydrate = match strategy {
+                HydrationStrategy::Load => String::new(),
+                strategy => format!(r#" data-hydrate="{}""#, strategy.as_str()),
+            };
+            // the keys of an island are the same wherever it is on the page
+            let inner = cx.with_hydration_prefix(format!("i{id}-"), move || body(props));
+            format!(
+                r#"<leptos-island data-hk="{hk}" data-island="{id}" data-component="{component}"{data_hydrate} data-props="{data_props}">{inner}</leptos-island>"#
+            )
+        }
+    }
+}
+
+/// Appends the element an island rendered to the island, unless it was hydrated in place.
+#[cfg(feature = "hydrate")]
+fn append_island(el: &web_sys::Element, inner: &web_sys::Element) {
+    use wasm_bindgen::UnwrapThrowExt;
+
+    if inner.parent_node().as_ref() != Some(el.as_ref()) {
+        el.append_child(inner).unwrap_throw();
+    }
+}
+
+/// Runs the pending hydration of an island once its [HydrationStrategy] calls for it.
+#[cfg(feature = "hydrate")]
+fn hydrate_when(strategy: HydrationStrategy, el: &web_sys::Element, pending: PendingHydration) {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let run = {
+        let pending = Rc::clone(&pending);
+        move || {
+            let hydrate = pending.borrow_mut().take();
+            if let Some(hydrate) = hydrate {
+                hydrate();
+            }
+        }
+    };
+
+    match strategy {
+        HydrationStrategy::Load => run(),
+        HydrationStrategy::Idle => crate::request_idle_callback(run),
+        HydrationStrategy::Visible => {
+            let callback = Closure::<dyn Fn(js_sys::Array, web_sys::IntersectionObserver)>::new({
+                let run = run.clone();
+                move |entries: js_sys::Array, observer: web_sys::IntersectionObserver| {
+                    let visible = entries.iter().any(|entry| {
+                        entry
+                            .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                            .is_intersecting()
+                    });
+                    if visible {
+                        observer.disconnect();
+                        run();
+                    }
+                }
+            })
+            .into_js_value();
+            match web_sys::IntersectionObserver::new(callback.unchecked_ref()) {
+                Ok(observer) => observer.observe(el),
+                // e.g., in a browser without IntersectionObserver
+                Err(_) => run(),
+            }
+        }
+        HydrationStrategy::Interaction => {
+            const EVENTS: [&str; 4] = ["pointerover", "pointerdown", "touchstart", "focusin"];
+
+            let listener = Rc::new(RefCell::new(None::<wasm_bindgen::JsValue>));
+            let callback = Closure::<dyn Fn(web_sys::Event)>::new({
+                let el = el.clone();
+                let listener = Rc::clone(&listener);
+                move |_: web_sys::Event| {
+                    if let Some(listener) = listener.borrow_mut().take() {
+                        for event in EVENTS {
+                            _ = el.remove_event_listener_with_callback(
+                                event,
+                                listener.unchecked_ref(),
+                            );
+                        }
+                    }
+                    run();
+                }
+            })
+            .into_js_value();
+            for event in EVENTS {
+                _ = el.add_event_listener_with_callback(event, callback.unchecked_ref());
+            }
+            *listener.borrow_mut() = Some(callback);
+        }
+    }
+}
+
+/// Hydrates a server-rendered island of the given component, reading its props from the
+/// `data-props` attribute of the element, once its [HydrationStrategy] calls for it. This is
+/// called by the function that the `#[island]` macro exports for each island from the WASM
+/// module.
+///
+/// The clicks and inputs that happened in the island before it was hydrated are then replayed
+/// through its event handlers.
+#[cfg(feature = "hydrate")]
+pub fn hydrate_island<P, F>(el: web_sys::HtmlElement, f: F)
+where
+    P: serde::de::DeserializeOwned + 'static,
+    F: FnOnce(Scope, P) -> Element + 'static,
+{
+    use leptos_reactive::{create_runtime, create_scope, provide_context};
+
+    let strategy = el
+        .get_attribute("data-hydrate")
+        .and_then(|strategy| strategy.parse().ok())
+        .unwrap_or_default();
+    let target = el.clone();
+    let pending: PendingHydration = Rc::new(RefCell::new(Some(Box::new(move || {
+        let props = match el
+            .get_attribute("data-props")
+            .and_then(|props| serde_json::from_str::<P>(&props).ok())
+        {
+            Some(props) => props,
+            None => {
+                crate::error!("[hydrate_island] could not read the props of an island");
+                return;
+            }
+        };
+
+        // this is not a leak
+        // the island lives as long as the page
+        let _ = create_scope(create_runtime(), move |cx| {
+            cx.start_island_hydration(&el);
+            let root = el.clone();
+            provide_context(cx, IslandRoot(Rc::new(RefCell::new(Some(el)))));
+            f(cx, props);
+            cx.end_hydration();
+            crate::event_replay::replay_events(&root);
+        });
+    }))));
+    hydrate_when(strategy, &target, pending);
+}
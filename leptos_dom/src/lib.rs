@@ -22,11 +22,16 @@ mod attribute;
 mod child;
 mod class;
 mod event_delegation;
+mod event_replay;
+mod island;
 mod logging;
 mod mount;
+mod no_js_audit;
 mod node_ref;
+mod nonce;
 mod operations;
 mod property;
+mod render_cache;
 
 cfg_if! {
     // can only include this if we're *only* enabling SSR, as it's the lowest-priority feature
@@ -81,11 +86,15 @@ cfg_if! {
 pub use attribute::*;
 pub use child::*;
 pub use class::*;
+pub use island::*;
 pub use logging::*;
 pub use mount::*;
+pub use no_js_audit::*;
 pub use node_ref::*;
+pub use nonce::*;
 pub use operations::*;
 pub use property::*;
+pub use render_cache::*;
 
 pub use js_sys;
 pub use wasm_bindgen;
@@ -96,15 +105,16 @@ pub use wasm_bindgen::UnwrapThrowExt;
 
 // Hidden because this is primarily used by the `view` macro, not by library users.
 #[doc(hidden)]
-pub fn create_component<F, T>(cx: Scope, f: F) -> T
+pub fn create_component<F, T>(cx: Scope, name: &'static str, f: F) -> T
 where
     F: FnOnce() -> T,
 {
     cfg_if! {
         if #[cfg(feature = "csr")] {
+            _ = name;
             cx.untrack(f)
         } else {
-            cx.with_next_context(f)
+            cx.with_next_context(|| cx.with_component_name(name, f))
         }
     }
 }
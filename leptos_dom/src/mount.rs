@@ -84,6 +84,11 @@ where
 /// while reusing the existing DOM nodes, by running the given function beginning with
 /// the parent node.
 ///
+/// Clicks and inputs that happen before the page is hydrated, which are recorded by the script
+/// that [render_to_stream](crate::render_to_stream) sends with the page, are replayed through
+/// their event handlers once it is. Those in an island that is hydrated later are replayed once
+/// that island is hydrated.
+///
 /// ```
 /// // rehydrate a very simple Leptos application
 /// # use leptos_dom::*; use leptos_dom::wasm_bindgen::JsCast;
@@ -108,5 +113,7 @@ where
         cx.start_hydration(&parent);
         (f(cx));
         cx.end_hydration();
+        crate::event_replay::mark_hydrated(&document());
+        crate::event_replay::replay_events(&parent);
     });
 }
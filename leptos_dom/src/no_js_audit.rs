@@ -0,0 +1,120 @@
+use leptos_reactive::{use_context, Scope};
+use std::{cell::RefCell, fmt, rc::Rc};
+
+/// Records the interactive elements of a page that would do nothing without JavaScript while
+/// it's rendered on the server, e.g., a `<button on:click=...>` that isn't in a `<form>`. The
+/// server integrations provide it as context when the `no_js_audit` option is set, and report
+/// what it has recorded once the page has been rendered.
+///
+/// An element is recorded if it has a handler for an event the user causes, like `on:click`
+/// or `on:input`, unless the browser does something with it by itself: a link with an `href`,
+/// a `<form>` with an `action` or a `method`, like an `<ActionForm/>`, or a button or form
+/// control in such a `<form>`. Only the elements of a `view!` in a debug build are recorded.
+///
+/// ```
+/// # cfg_if::cfg_if! { if #[cfg(not(any(feature = "csr", feature = "hydrate")))] {
+/// # use leptos_reactive::*; use leptos_dom::*; use leptos_macro::view;
+/// let audit = NoJsAudit::new();
+/// render_to_string({
+///     let audit = audit.clone();
+///     move |cx| {
+///         provide_context(cx, audit);
+///         view! { cx,
+///             <main>
+///                 <button on:click=|_| ()>"Like"</button>
+///                 <form method="POST" action="/search">
+///                     <input name="q" on:input=|_| ()/>
+///                 </form>
+///                 <a href="/about" on:click=|_| ()>"About"</a>
+///             </main>
+///         }
+///     }
+/// });
+///
+/// let dead = audit.dead_elements();
+/// assert_eq!(dead.len(), 1);
+/// assert_eq!(dead[0].tag, "button");
+/// assert_eq!(dead[0].events, ["click"]);
+/// # }}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NoJsAudit(Rc<RefCell<AuditState>>);
+
+#[derive(Debug, Default)]
+struct AuditState {
+    /// How many `<form>`s that submit without JavaScript the element being rendered is in.
+    forms: usize,
+    dead: Vec<DeadElement>,
+}
+
+/// An element that would do nothing without JavaScript, as recorded by a [NoJsAudit].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadElement {
+    /// The tag name of the element.
+    pub tag: &'static str,
+    /// The events it has handlers for.
+    pub events: Vec<&'static str>,
+    /// The file and line it is written on in its `view!`.
+    pub location: &'static str,
+}
+
+impl fmt::Display for DeadElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.tag)?;
+        for event in &self.events {
+            write!(f, " on:{event}")?;
+        }
+        write!(f, "> at {}", self.location)
+    }
+}
+
+impl NoJsAudit {
+    /// Creates an audit that hasn't recorded anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The elements that have been recorded, each once, in the order they were rendered.
+    pub fn dead_elements(&self) -> Vec<DeadElement> {
+        self.0.borrow().dead.clone()
+    }
+}
+
+// Hidden because this is called by the `view` macro, not by library users.
+#[doc(hidden)]
+pub fn audit_element(
+    cx: Scope,
+    tag: &'static str,
+    events: &[&'static str],
+    submits_form: bool,
+    location: &'static str,
+) {
+    if let Some(audit) = use_context::<NoJsAudit>(cx) {
+        let mut state = audit.0.borrow_mut();
+        if submits_form && state.forms > 0 {
+            return;
+        }
+        let element = DeadElement {
+            tag,
+            events: events.to_vec(),
+            location,
+        };
+        // e.g., a row of a list
+        if !state.dead.contains(&element) {
+            state.dead.push(element);
+        }
+    }
+}
+
+// Hidden because this is called by the `view` macro, not by library users.
+#[doc(hidden)]
+pub fn audit_form(cx: Scope, open: bool) {
+    if let Some(audit) = use_context::<NoJsAudit>(cx) {
+        let mut state = audit.0.borrow_mut();
+        if open {
+            state.forms += 1;
+        } else {
+            state.forms = state.forms.saturating_sub(1);
+        }
+    }
+}
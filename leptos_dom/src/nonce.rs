@@ -0,0 +1,93 @@
+use leptos_reactive::{use_context, Scope};
+
+/// A random value, generated for each response, that marks the inline scripts the server
+/// renders as its own, so that a strict
+/// [Content-Security-Policy](https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP) can allow
+/// them with `script-src 'nonce-...'` instead of `'unsafe-inline'`.
+///
+/// The server integrations provide a new nonce as context for each page they render, and add
+/// it to every inline script they send: the scripts that hydrate the page, stream the data of
+/// its resources, swap in `<Suspense/>` fragments, hydrate islands and reload the page in
+/// development. The app sends the policy itself, as the nonce has to be in its header, and can
+/// read the nonce with [use_nonce] to mark scripts of its own.
+///
+/// ```
+/// # use leptos_dom::*;
+/// let nonce = Nonce::from("abc".to_string());
+/// assert_eq!(nonce.to_attribute(), r#" nonce="abc""#);
+/// assert_eq!(
+///     format!("script-src 'self' {}", nonce.to_source()),
+///     "script-src 'self' 'nonce-abc'"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nonce(String);
+
+impl Nonce {
+    /// Creates a new random nonce.
+    #[cfg(feature = "ssr")]
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// The nonce itself.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The ` nonce="..."` attribute that marks a `<script>` or `<style>` tag, with its leading
+    /// space.
+    pub fn to_attribute(&self) -> String {
+        format!(
+            r#" nonce="{}""#,
+            html_escape::encode_double_quoted_attribute(&self.0)
+        )
+    }
+
+    /// The `'nonce-...'` source that allows the tags marked with this nonce in a
+    /// `Content-Security-Policy` header.
+    pub fn to_source(&self) -> String {
+        format!("'nonce-{}'", self.0)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Default for Nonce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<String> for Nonce {
+    fn from(nonce: String) -> Self {
+        Self(nonce)
+    }
+}
+
+impl std::fmt::Display for Nonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The [Nonce] of the page that is being rendered on the server, which the server integrations
+/// provide as context. Returns `None` in the browser, or when rendering without an integration.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_dom::*;
+/// # use leptos_macro::view;
+/// # create_scope(create_runtime(), |cx| {
+/// #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+/// {
+///     provide_context(cx, Nonce::from("abc".to_string()));
+///     let nonce = use_nonce(cx).map(|nonce| nonce.to_string()).unwrap_or_default();
+///     // an inline script of the app's own
+///     let script: String = view! { cx, <script nonce=nonce>"console.log('hi')"</script> };
+///     assert!(script.contains(r#"nonce="abc""#));
+/// }
+/// # }).dispose();
+/// ```
+pub fn use_nonce(cx: Scope) -> Option<Nonce> {
+    use_context(cx)
+}
@@ -16,7 +16,12 @@ thread_local! {
 ///
 /// This is cached as a thread-local variable, so calling `window()` multiple times
 /// requires only one call out to JavaScript.
+///
+/// There is no `Window` on the server, so this panics when called with the `ssr` feature,
+/// e.g., in an effect created with `create_isomorphic_effect` rather than `create_effect`.
+#[track_caller]
 pub fn window() -> web_sys::Window {
+    assert_not_server("window");
     WINDOW.with(|window| window.clone())
 }
 
@@ -24,10 +29,28 @@ pub fn window() -> web_sys::Window {
 ///
 /// This is cached as a thread-local variable, so calling `window()` multiple times
 /// requires only one call out to JavaScript.
+///
+/// There is no `Document` on the server, so this panics when called with the `ssr` feature,
+/// e.g., in an effect created with `create_isomorphic_effect` rather than `create_effect`.
+#[track_caller]
 pub fn document() -> web_sys::Document {
+    assert_not_server("document");
     DOCUMENT.with(|document| document.clone())
 }
 
+/// Panics with an explanation when a browser API is used on the server, instead of letting
+/// `wasm-bindgen` panic without saying which code touched it.
+#[track_caller]
+fn assert_not_server(api: &str) {
+    if is_server!() {
+        panic!(
+            "tried to access `{api}` while rendering on the server. Browser APIs can only be \
+             used in the browser: use `create_effect`, which doesn't run on the server, instead \
+             of `create_isomorphic_effect`, or check `is_server!()` first."
+        );
+    }
+}
+
 /// Returns the `<body>` elements of the current HTML document, if it exists.
 pub fn body() -> Option<web_sys::HtmlElement> {
     document().body()
@@ -0,0 +1,215 @@
+use crate::Element;
+use cfg_if::cfg_if;
+use leptos_reactive::Scope;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The most views a [MemoryRenderCache] holds; once it's full, the view that was rendered the
+/// longest time ago makes room for the next.
+const MAX_VIEWS: usize = 1024;
+
+/// What the HTML of a view rendered with [cached] is kept by in a [RenderCache].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderCacheKey {
+    /// The path and query of the page the view was rendered on.
+    pub url: String,
+    /// The hydration key of the view, which tells it apart from the other cached views of the
+    /// page.
+    pub position: String,
+    /// The key that was passed to [cached], e.g., the locale the view was rendered in.
+    pub key: String,
+}
+
+/// Keeps the HTML of the views rendered with [cached] on the server, so that it can be sent
+/// again without rendering them, e.g., in a store that is shared between servers. The server
+/// integrations provide it as context with a [RenderCacheContext].
+pub trait RenderCache: Send + Sync + 'static {
+    /// The HTML of the view with the given key, if it's been kept.
+    fn get(&self, key: &RenderCacheKey) -> Option<String>;
+
+    /// Keeps the HTML of the view with the given key.
+    fn insert(&self, key: RenderCacheKey, html: String);
+}
+
+/// A [RenderCache] that keeps the HTML of views in memory, for as long as the server runs or
+/// for a maximum age.
+///
+/// ```
+/// # cfg_if::cfg_if! { if #[cfg(not(any(feature = "csr", feature = "hydrate")))] {
+/// # use leptos_reactive::*; use leptos_dom::*; use leptos_macro::view;
+/// # use std::{cell::Cell, rc::Rc, sync::Arc};
+/// let cache = MemoryRenderCache::default();
+/// let renders = Rc::new(Cell::new(0));
+/// let page = {
+///     let cache = cache.clone();
+///     let renders = Rc::clone(&renders);
+///     move |cx| {
+///         provide_context(cx, RenderCacheContext::new(Arc::new(cache), "/about"));
+///         view! { cx,
+///             <main>
+///                 {cached(cx, "en", move |cx| {
+///                     renders.set(renders.get() + 1);
+///                     view! { cx, <p>"Expensive"</p> }
+///                 })}
+///             </main>
+///         }
+///     }
+/// };
+///
+/// let first = render_to_string(page.clone());
+/// let second = render_to_string(page);
+/// assert_eq!(first, second);
+/// assert_eq!(renders.get(), 1);
+///
+/// cache.clear();
+/// # }}
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryRenderCache {
+    views: Arc<Mutex<HashMap<RenderCacheKey, (String, Instant)>>>,
+    max_age: Option<Duration>,
+}
+
+impl MemoryRenderCache {
+    /// Creates an empty cache, which keeps views for as long as the server runs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty cache, which renders a view again once it's been kept for `max_age`.
+    pub fn with_max_age(max_age: Duration) -> Self {
+        Self {
+            max_age: Some(max_age),
+            ..Self::default()
+        }
+    }
+
+    /// Removes the views of the page at the given path and query, e.g., once its data has
+    /// changed.
+    pub fn invalidate(&self, url: &str) {
+        self.views.lock().unwrap().retain(|key, _| key.url != url);
+    }
+
+    /// Removes every view.
+    pub fn clear(&self) {
+        self.views.lock().unwrap().clear();
+    }
+}
+
+impl RenderCache for MemoryRenderCache {
+    fn get(&self, key: &RenderCacheKey) -> Option<String> {
+        let views = self.views.lock().unwrap();
+        views
+            .get(key)
+            .filter(|(_, rendered_at)| match self.max_age {
+                Some(max_age) => rendered_at.elapsed() < max_age,
+                None => true,
+            })
+            .map(|(html, _)| html.clone())
+    }
+
+    fn insert(&self, key: RenderCacheKey, html: String) {
+        let mut views = self.views.lock().unwrap();
+        if views.len() >= MAX_VIEWS && !views.contains_key(&key) {
+            let oldest = views
+                .iter()
+                .min_by_key(|(_, (_, rendered_at))| *rendered_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                views.remove(&oldest);
+            }
+        }
+        views.insert(key, (html, Instant::now()));
+    }
+}
+
+/// The [RenderCache] of the page being rendered, which the server integrations provide as
+/// context when they're set up with one, along with the URL of the page.
+#[derive(Clone)]
+pub struct RenderCacheContext {
+    // only read while rendering on the server
+    #[cfg_attr(any(feature = "csr", feature = "hydrate"), allow(dead_code))]
+    cache: Arc<dyn RenderCache>,
+    url: String,
+}
+
+impl RenderCacheContext {
+    /// The given cache, for the page at the given path and query.
+    pub fn new(cache: Arc<dyn RenderCache>, url: impl Into<String>) -> Self {
+        Self {
+            cache,
+            url: url.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for RenderCacheContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderCacheContext")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Renders a view whose HTML is kept in the [RenderCache] provided as context, if any, and sent
+/// again from there without rendering the view on the server while it's kept, e.g., for an
+/// expensive but rarely changing part of a page. The HTML is kept by the URL of the page, the
+/// place of the view in it, and `key`, which has to tell apart whatever else the view depends
+/// on, like the locale it's rendered in.
+///
+/// The view is rendered with hydration keys of its own, so that the HTML that is sent again
+/// is hydrated in the browser as if it had just been rendered.
+///
+/// As only the HTML is kept, a view that is sent from the cache doesn't provide context or set
+/// anything else while it's rendered, like the `<title>` of the page, and a view that creates
+/// resources isn't kept at all. It shouldn't show anything that depends on who requests it,
+/// besides what's in its `key`.
+pub fn cached(cx: Scope, key: impl Into<String>, view: impl FnOnce(Scope) -> Element) -> Element {
+    cfg_if! {
+        if #[cfg(feature = "csr")] {
+            _ = key;
+            view(cx)
+        } else if #[cfg(feature = "hydrate")] {
+            _ = key;
+            let position = cx.next_hydration_key();
+            cx.with_hydration_prefix(format!("c{position}-"), move || view(cx))
+        } else {
+            use leptos_reactive::use_context;
+
+            let position = cx.next_hydration_key();
+            let prefix = format!("c{position}-");
+            let cache = use_context::<RenderCacheContext>(cx).map(|context| {
+                let key = RenderCacheKey {
+                    url: context.url,
+                    position,
+                    key: key.into(),
+                };
+                (context.cache, key)
+            });
+            if let Some((cache, key)) = &cache {
+                if let Some(html) = cache.get(key) {
+                    return html;
+                }
+            }
+
+            let resources = cx.all_resources().len();
+            let html = cx.with_hydration_prefix(prefix, move || view(cx));
+            if let Some((cache, key)) = cache {
+                // the resources wouldn't be created when the view is sent from the cache, so
+                // the ones created after it would be out of step with the browser
+                if cx.all_resources().len() == resources {
+                    cache.insert(key, html.clone());
+                } else {
+                    crate::debug_warn!(
+                        "[cached] the view at {:?} creates resources, so it isn't cached",
+                        key.position
+                    );
+                }
+            }
+            html
+        }
+    }
+}
@@ -16,7 +16,12 @@ cfg_if! {
         use leptos_reactive::*;
 
         use crate::Element;
-        use futures::{stream::FuturesUnordered, Stream, StreamExt};
+        use futures::{stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+        use std::{
+            collections::{HashMap, HashSet},
+            future::Future,
+            pin::Pin,
+        };
 
         /// Renders a component to a static HTML string.
         ///
@@ -42,17 +47,142 @@ cfg_if! {
         /// 1) the application shell
         ///   a) HTML for everything that is not under a `<Suspense/>`,
         ///   b) the `fallback` for any `<Suspense/>` component that is not already resolved, and
-        ///   c) JavaScript necessary to receive streaming [Resource](leptos_reactive::Resource) data.
+        ///   c) JavaScript necessary to receive streaming [Resource](leptos_reactive::Resource) data,
+        ///      along with any context provided with
+        ///      [provide_serializable_context](leptos_reactive::provide_serializable_context).
         /// 2) streaming [Resource](leptos_reactive::Resource) data. Resources begin loading on the
         ///    server and are sent down to the browser to resolve. On the browser, if the app sees that
         ///    it is waiting for a resource to resolve from the server, it doesn't run it initially.
         /// 3) HTML fragments to replace each `<Suspense/>` fallback with its actual data as the resources
         ///    read under that `<Suspense/>` resolve.
+        ///
+        /// The fragments are ordered by the [ResourcePriority] of the resources read under each
+        /// `<Suspense/>`: [Blocking](ResourcePriority::Blocking) fragments are sent along with the
+        /// shell, which waits for them, [Deferred](ResourcePriority::Deferred) fragments as soon as
+        /// they resolve, and [Lazy](ResourcePriority::Lazy) fragments once all the others have been
+        /// sent. A fragment nested in another `<Suspense/>` is never sent before the fragment of
+        /// that `<Suspense/>`. The shell also waits for the data of every blocking resource, even
+        /// one that isn't read under a `<Suspense/>`, and sends it along.
+        ///
+        /// To render something in front of the shell once the blocking resources have resolved,
+        /// e.g., the `<head>` of the page, see [render_to_stream_with_prefix].
+        ///
+        /// To send the shell right away, without waiting for any resource, see
+        /// [render_to_stream_out_of_order].
+        ///
+        /// The shell also records the clicks and inputs that happen before the page, or the island
+        /// they happen in, is hydrated, which are then replayed through their event handlers, so
+        /// that they aren't lost on a slow device.
+        ///
+        /// If a [Nonce](crate::Nonce) is provided as context, every inline script is marked with it.
         pub fn render_to_stream(view: impl FnOnce(Scope) -> Element + 'static) -> impl Stream<Item = String> {
+            render_to_stream_with_order(view, |_| String::new(), false, None)
+        }
+
+        /// Renders a component to a stream of HTML strings, like [render_to_stream], but sends
+        /// the application shell right away, with the `fallback` of every `<Suspense/>` that is
+        /// not already resolved, so that slow resources never hold back the first paint.
+        ///
+        /// The HTML of each `<Suspense/>` is then streamed as soon as its resources resolve,
+        /// whatever their [ResourcePriority], in a `<template>` that a small inline script swaps
+        /// in for its `fallback`. A fragment nested in another `<Suspense/>` is still never sent
+        /// before the fragment of that `<Suspense/>`.
+        ///
+        /// As the shell no longer waits for [Blocking](ResourcePriority::Blocking) resources,
+        /// anything they would set while rendering, like the status code of the response, is
+        /// set too late to be sent.
+        pub fn render_to_stream_out_of_order(
+            view: impl FnOnce(Scope) -> Element + 'static,
+        ) -> impl Stream<Item = String> {
+            render_to_stream_with_order(view, |_| String::new(), true, None)
+        }
+
+        /// Renders a component to a string of HTML once all of its resources have resolved,
+        /// with the HTML of every `<Suspense/>` in place of its `fallback`.
+        ///
+        /// The page is rendered like it is by [render_to_stream], but nothing is returned until
+        /// it has been rendered in full, so that it can be sent at once, e.g., to crawlers or
+        /// clients that don't run scripts, and anything that resources set while rendering,
+        /// like the status code of the response, is set by the time it's sent. To stop waiting
+        /// for slow resources, see [render_to_string_async_with_timeout].
+        pub async fn render_to_string_async(view: impl FnOnce(Scope) -> Element + 'static) -> String {
+            render_to_stream_with_order(view, |_| String::new(), false, None)
+                .collect::<String>()
+                .await
+        }
+
+        /// Renders a component to a string of HTML like [render_to_string_async], but only
+        /// waits for its resources until `timeout` has passed, so that one slow upstream call
+        /// can't hold back the whole response.
+        ///
+        /// The page is then returned with the `fallback` of each `<Suspense/>` that is still
+        /// loading, and the browser loads the resources that hadn't resolved by then itself once
+        /// the page is hydrated, rather than waiting for the server to send them.
+        ///
+        /// With the `ssr` feature, the timeout is measured with `tokio`'s timer, so this has to
+        /// run in a `tokio` runtime with time enabled.
+        pub async fn render_to_string_async_with_timeout(
+            view: impl FnOnce(Scope) -> Element + 'static,
+            timeout: std::time::Duration,
+        ) -> String {
+            render_to_stream_with_order(view, |_| String::new(), false, Some(timeout))
+                .collect::<String>()
+                .await
+        }
+
+        /// Renders a component to a stream of HTML strings like [render_to_stream], with the
+        /// HTML that `prefix` renders in front of it, e.g., the `<head>` of the page.
+        ///
+        /// The `prefix` is rendered once the [Blocking](ResourcePriority::Blocking) resources of
+        /// the page, like those created with
+        /// [create_blocking_resource](leptos_reactive::create_blocking_resource), have resolved,
+        /// and the HTML of each `<Suspense/>` that only reads them has been rendered, so that
+        /// anything they set is in the first HTML that is sent, e.g., a `<Title/>` with
+        /// [leptos_meta](https://docs.rs/leptos_meta).
+        pub fn render_to_stream_with_prefix(
+            view: impl FnOnce(Scope) -> Element + 'static,
+            prefix: impl FnOnce(Scope) -> String + 'static,
+        ) -> impl Stream<Item = String> {
+            render_to_stream_with_order(view, prefix, false, None)
+        }
+
+        /// Renders a component to a stream of HTML strings like [render_to_stream_out_of_order],
+        /// with the HTML that `prefix` renders in front of it, e.g., the `<head>` of the page.
+        ///
+        /// As the shell is sent right away, the `prefix` is rendered right after the component.
+        pub fn render_to_stream_out_of_order_with_prefix(
+            view: impl FnOnce(Scope) -> Element + 'static,
+            prefix: impl FnOnce(Scope) -> String + 'static,
+        ) -> impl Stream<Item = String> {
+            render_to_stream_with_order(view, prefix, true, None)
+        }
+
+        /// Renders a component to a string of HTML like [render_to_string_async], or like
+        /// [render_to_string_async_with_timeout] with a `timeout`, with the HTML that `prefix`
+        /// renders in front of it, e.g., the `<head>` of the page. The `prefix` is rendered as
+        /// it is by [render_to_stream_with_prefix].
+        pub async fn render_to_string_async_with_prefix(
+            view: impl FnOnce(Scope) -> Element + 'static,
+            prefix: impl FnOnce(Scope) -> String + 'static,
+            timeout: Option<std::time::Duration>,
+        ) -> String {
+            render_to_stream_with_order(view, prefix, false, timeout)
+                .collect::<String>()
+                .await
+        }
+
+        /// With a `timeout`, stops waiting for resources and fragments once it has passed, and
+        /// leaves those that are still pending for the browser to load.
+        fn render_to_stream_with_order(
+            view: impl FnOnce(Scope) -> Element + 'static,
+            prefix: impl FnOnce(Scope) -> String + 'static,
+            out_of_order: bool,
+            timeout: Option<std::time::Duration>,
+        ) -> impl Stream<Item = String> {
             // create the runtime
             let runtime = create_runtime();
 
-            let ((shell, pending_resources, pending_fragments, serializers), _, disposer) =
+            let ((cx, shell, pending_resources, contexts, nonce, pending_fragments, serializers), _, disposer) =
                 run_scope_undisposed(runtime, {
                     move |cx| {
                         // the actual app body/template code
@@ -63,63 +193,113 @@ cfg_if! {
                         let pending_resources = serde_json::to_string(&resources).unwrap();
 
                         (
+                            cx,
                             shell,
                             pending_resources,
+                            cx.serialized_contexts(),
+                            crate::use_nonce(cx).map(|nonce| nonce.to_attribute()).unwrap_or_default(),
                             cx.pending_fragments(),
                             cx.serialization_resolvers(),
                         )
                     }
                 });
 
-            let fragments = FuturesUnordered::new();
-            for (fragment_id, fut) in pending_fragments {
-                fragments.push(async move { (fragment_id, fut.await) })
+            let fragments = FragmentQueue::new(pending_fragments, out_of_order);
+            let blocking = fragments.blocking;
+            // the shell waits for the data of blocking resources, unless it's sent right away
+            let mut blocking_resources = if out_of_order {
+                HashSet::new()
+            } else {
+                cx.blocking_resources().into_iter().collect::<HashSet<_>>()
+            };
+
+            // resolves once the timeout has passed, if there is one
+            let deadline = match timeout {
+                Some(timeout) => sleep(timeout),
+                None => futures::future::pending().boxed_local(),
             }
+            .shared();
 
-            // resources and fragments
-            let resources_and_fragments = futures::stream::select(
-                // stream data for each Resource as it resolves
-                serializers.map(|(id, json)| {
-                    let id = serde_json::to_string(&id).unwrap();
-                    format!(
-                        r#"<script>
-                                if(__LEPTOS_RESOURCE_RESOLVERS.get({id})) {{
-                                    __LEPTOS_RESOURCE_RESOLVERS.get({id})({json:?})
-                                }} else {{
-                                    __LEPTOS_RESOLVED_RESOURCES.set({id}, {json:?});
-                                }}
-                            </script>"#,
-                    )
-                }),
-                // stream HTML for each <Suspense/> as it resolves
-                fragments.map(|(fragment_id, html)| {
-                    format!(
-                        r#"
-                            <template id="{fragment_id}">{html}</template>
-                            <script>
-                                var frag = document.querySelector(`[data-fragment-id="{fragment_id}"]`);
-                                var tpl = document.getElementById("{fragment_id}");
-                                if(frag) frag.replaceWith(tpl.content.cloneNode(true));
-                            </script>
-                            "#
-                    )
-                })
-            );
+            // the resources that are still pending when the timeout passes are loaded by the browser
+            let hand_off = futures::stream::iter(timeout.map({
+                let nonce = nonce.clone();
+                move |_| format!("<script{nonce}>__LEPTOS_PENDING_RESOURCES = [];</script>")
+            }));
 
             // HTML for the view function and script to store resources
             futures::stream::once(async move {
-                format!(
+                let mut serializers = serializers;
+                // blocking resources are sent along with the shell, and then blocking fragments,
+                // parents first, unless they are still loading when the timeout passes
+                let resolved = {
+                    let nonce = &nonce;
+                    let serializers = &mut serializers;
+                    let blocking = async move {
+                        let mut resources = String::new();
+                        while !blocking_resources.is_empty() {
+                            match serializers.next().await {
+                                Some((id, json)) => {
+                                    blocking_resources.remove(&id);
+                                    resources.push_str(&resource_to_html(id, &json, nonce));
+                                }
+                                None => break,
+                            }
+                        }
+                        let fragments = futures::future::join_all(blocking)
+                            .await
+                            .into_iter()
+                            .map(|(fragment_id, html)| fragment_to_html(&fragment_id, &html, nonce))
+                            .collect::<String>();
+                        resources + &fragments
+                    };
+                    futures::pin_mut!(blocking);
+                    match futures::future::select(blocking, deadline.clone()).await {
+                        futures::future::Either::Left((resolved, _)) => resolved,
+                        futures::future::Either::Right(_) => String::new(),
+                    }
+                };
+                // rendered once blocking resources have resolved, so it can show their data
+                let prefix = prefix(cx);
+                let recorder = crate::event_replay::recorder_script();
+
+                let shell = format!(
                     r#"
-                        {shell}
-                        <script>
+                        {prefix}{shell}
+                        <script{nonce}>
                             __LEPTOS_PENDING_RESOURCES = {pending_resources};
                             __LEPTOS_RESOLVED_RESOURCES = new Map();
                             __LEPTOS_RESOURCE_RESOLVERS = new Map();
+                            __LEPTOS_CONTEXTS = {contexts};
+                            {recorder}
+                            function __LEPTOS_SWAP(id) {{
+                                var frag = document.querySelector(`[data-fragment-id="${{id}}"]`);
+                                var tpl = document.getElementById(id);
+                                if(frag) frag.replaceWith(tpl.content.cloneNode(true));
+                            }}
                         </script>
+                        {resolved}
                     "#
+                );
+
+                // resources and fragments
+                let resources_and_fragments = futures::stream::select(
+                    // stream data for each Resource as it resolves
+                    serializers.map({
+                        let nonce = nonce.clone();
+                        move |(id, json)| resource_to_html(id, &json, &nonce)
+                    }),
+                    // stream HTML for each <Suspense/> as it resolves, in order of priority
+                    fragments
+                        .queue
+                        .into_stream()
+                        .map(move |(fragment_id, html)| fragment_to_html(&fragment_id, &html, &nonce))
                 )
+                .take_until(deadline);
+
+                futures::stream::once(async move { shell }).chain(resources_and_fragments)
             })
-            .chain(resources_and_fragments)
+            .flatten()
+            .chain(hand_off)
             // dispose of Scope and Runtime
             .chain(futures::stream::once(async move {
                 disposer.dispose();
@@ -127,5 +307,160 @@ cfg_if! {
                 Default::default()
             }))
         }
+
+        /// Resolves once `timeout` has passed.
+        fn sleep(timeout: std::time::Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+            cfg_if! {
+                if #[cfg(feature = "ssr")] {
+                    tokio::time::sleep(timeout).boxed_local()
+                } else {
+                    // without tokio, a thread of its own keeps the time
+                    let (tx, rx) = futures::channel::oneshot::channel();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(timeout);
+                        _ = tx.send(());
+                    });
+                    rx.map(|_| ()).boxed_local()
+                }
+            }
+        }
+
+        /// Resolves the [Resource](leptos_reactive::Resource) with the given ID with its data, with
+        /// a script marked with the given ` nonce="..."` attribute, if any.
+        fn resource_to_html(id: ResourceId, json: &str, nonce: &str) -> String {
+            let id = serde_json::to_string(&id).unwrap();
+            format!(
+                r#"<script{nonce}>
+                        if(__LEPTOS_RESOURCE_RESOLVERS.get({id})) {{
+                            __LEPTOS_RESOURCE_RESOLVERS.get({id})({json:?})
+                        }} else {{
+                            __LEPTOS_RESOLVED_RESOURCES.set({id}, {json:?});
+                        }}
+                    </script>"#,
+            )
+        }
+
+        /// Replaces the fallback of a `<Suspense/>` with its resolved HTML, with a script marked
+        /// with the given ` nonce="..."` attribute, if any.
+        fn fragment_to_html(fragment_id: &str, html: &str, nonce: &str) -> String {
+            format!(
+                r#"
+                    <template id="{fragment_id}">{html}</template>
+                    <script{nonce}>__LEPTOS_SWAP("{fragment_id}")</script>
+                    "#
+            )
+        }
+
+        type FragmentFuture = Pin<Box<dyn Future<Output = (String, String)>>>;
+
+        /// The pending fragments of a response, split into the ones that are sent with the shell
+        /// and the ones that are streamed afterwards.
+        struct FragmentQueue {
+            blocking: Vec<FragmentFuture>,
+            queue: StreamedFragments,
+        }
+
+        /// The fragments that are streamed after the shell, in order of priority.
+        struct StreamedFragments {
+            deferred: FuturesUnordered<FragmentFuture>,
+            lazy: Vec<FragmentFuture>,
+            parents: HashMap<String, Option<String>>,
+            sent: HashSet<String>,
+            /// Fragments that have resolved, but whose parents haven't been sent yet.
+            resolved: Vec<(String, String)>,
+        }
+
+        impl FragmentQueue {
+            /// With `out_of_order`, every fragment is streamed as soon as it resolves, regardless of
+            /// its priority.
+            fn new(fragments: HashMap<String, PendingFragment>, out_of_order: bool) -> Self {
+                let parents = fragments
+                    .iter()
+                    .map(|(key, fragment)| (key.clone(), fragment.parent.clone()))
+                    .collect::<HashMap<_, _>>();
+                let priorities = fragments
+                    .iter()
+                    .map(|(key, fragment)| (key.clone(), fragment.priority))
+                    .collect::<HashMap<_, _>>();
+                let ancestors = |key: &String| {
+                    std::iter::successors(parents[key].as_ref(), |parent| parents[*parent].as_ref())
+                };
+
+                let mut blocking = Vec::new();
+                let mut sent = HashSet::new();
+                let deferred = FuturesUnordered::new();
+                let mut lazy = Vec::new();
+                for (key, fragment) in fragments {
+                    let html = fragment.html;
+                    let fut = Box::pin({
+                        let key = key.clone();
+                        async move { (key, html.await) }
+                    }) as FragmentFuture;
+                    // a fragment can only be sent with the shell if the ones it's nested in are
+                    let is_blocking = |key: &String| priorities[key] == ResourcePriority::Blocking;
+                    let is_blocking = is_blocking(&key) && ancestors(&key).all(is_blocking);
+                    if out_of_order {
+                        deferred.push(fut);
+                    } else if is_blocking {
+                        blocking.push((ancestors(&key).count(), fut));
+                        sent.insert(key);
+                    } else if priorities[&key] == ResourcePriority::Lazy {
+                        lazy.push(fut);
+                    } else {
+                        deferred.push(fut);
+                    }
+                }
+                blocking.sort_by_key(|(depth, _)| *depth);
+
+                Self {
+                    blocking: blocking.into_iter().map(|(_, fut)| fut).collect(),
+                    queue: StreamedFragments {
+                        deferred,
+                        lazy,
+                        parents,
+                        sent,
+                        resolved: Vec::new(),
+                    },
+                }
+            }
+        }
+
+        impl StreamedFragments {
+            fn into_stream(self) -> impl Stream<Item = (String, String)> {
+                futures::stream::unfold(self, |mut queue| async move {
+                    let fragment = queue.next().await?;
+                    Some((fragment, queue))
+                })
+            }
+
+            async fn next(&mut self) -> Option<(String, String)> {
+                loop {
+                    let ready = self.resolved.iter().position(|(key, _)| {
+                        self.parents[key]
+                            .as_ref()
+                            .map(|parent| self.sent.contains(parent))
+                            .unwrap_or(true)
+                    });
+                    if let Some(index) = ready {
+                        let fragment = self.resolved.remove(index);
+                        self.sent.insert(fragment.0.clone());
+                        return Some(fragment);
+                    }
+
+                    if let Some(fragment) = self.deferred.next().await {
+                        self.resolved.push(fragment);
+                    } else if !self.lazy.is_empty() {
+                        self.deferred.extend(self.lazy.drain(..));
+                    } else if !self.resolved.is_empty() {
+                        // every fragment has resolved, so any parent that is left was never sent
+                        let fragment = self.resolved.remove(0);
+                        self.sent.insert(fragment.0.clone());
+                        return Some(fragment);
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
     }
 }
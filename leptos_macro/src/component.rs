@@ -73,23 +73,11 @@ impl Parse for InlinePropsBody {
     }
 }
 
-/// Serialize the same way, regardless of flavor
-impl ToTokens for InlinePropsBody {
-    fn to_tokens(&self, out_tokens: &mut TokenStream2) {
-        let Self {
-            vis,
-            ident,
-            generics,
-            inputs,
-            output,
-            where_clause,
-            block,
-            cx_token,
-            attrs,
-            ..
-        } = self;
-
-        let fields = inputs.iter().map(|f| {
+impl InlinePropsBody {
+    /// The fields of the props struct, one for each argument after the [Scope](leptos::Scope).
+    pub fn prop_fields(&self) -> impl Iterator<Item = TokenStream2> + '_ {
+        let vis = &self.vis;
+        self.inputs.iter().map(move |f| {
             let typed_arg = match f {
                 FnArg::Receiver(_) => todo!(),
                 FnArg::Typed(t) => t,
@@ -106,14 +94,38 @@ impl ToTokens for InlinePropsBody {
             } else {
                 quote! { #vis #f }
             }
-        });
+        })
+    }
+
+    /// The names of the fields of the props struct.
+    pub fn prop_names(&self) -> impl Iterator<Item = &Pat> {
+        self.inputs.iter().filter_map(|f| match f {
+            FnArg::Receiver(_) => todo!(),
+            FnArg::Typed(t) => Some(&*t.pat),
+        })
+    }
+}
+
+/// Serialize the same way, regardless of flavor
+impl ToTokens for InlinePropsBody {
+    fn to_tokens(&self, out_tokens: &mut TokenStream2) {
+        let Self {
+            vis,
+            ident,
+            generics,
+            output,
+            where_clause,
+            block,
+            cx_token,
+            attrs,
+            ..
+        } = self;
+
+        let fields = self.prop_fields();
 
         let struct_name = Ident::new(&format!("{}Props", ident), Span::call_site());
 
-        let field_names = inputs.iter().filter_map(|f| match f {
-            FnArg::Receiver(_) => todo!(),
-            FnArg::Typed(t) => Some(&t.pat),
-        });
+        let field_names = self.prop_names();
 
         let first_lifetime = if let Some(GenericParam::Lifetime(lt)) = generics.params.first() {
             Some(lt)
@@ -0,0 +1,148 @@
+use crate::{component::InlinePropsBody, Mode};
+use leptos_dom::HydrationStrategy;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{
+    parse::{Parse, ParseStream},
+    *,
+};
+
+/// A component that is hydrated on its own, with props that are sent from the server.
+pub struct IslandBody {
+    pub args: IslandArgs,
+    pub body: InlinePropsBody,
+}
+
+/// The arguments of the macro, e.g., `#[island(hydrate = "visible")]`.
+#[derive(Default)]
+pub struct IslandArgs {
+    strategy: Option<Ident>,
+}
+
+impl Parse for IslandArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = IslandArgs::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "hydrate" {
+                return Err(Error::new(key.span(), "expected `hydrate`"));
+            }
+            let _: Token![=] = input.parse()?;
+            let value: LitStr = input.parse()?;
+            let strategy = match value.value().parse::<HydrationStrategy>() {
+                Ok(HydrationStrategy::Load) => "Load",
+                Ok(HydrationStrategy::Idle) => "Idle",
+                Ok(HydrationStrategy::Visible) => "Visible",
+                Ok(HydrationStrategy::Interaction) => "Interaction",
+                Err(_) => {
+                    return Err(Error::new(
+                        value.span(),
+                        "expected one of \"load\", \"idle\", \"visible\", or \"interaction\"",
+                    ))
+                }
+            };
+            args.strategy = Some(Ident::new(strategy, value.span()));
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl Parse for IslandBody {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let body: InlinePropsBody = input.parse()?;
+        if !body.generics.params.is_empty() || body.where_clause.is_some() {
+            return Err(Error::new_spanned(
+                &body.generics,
+                "an island can't be generic, as it's hydrated by its name",
+            ));
+        }
+        if let Some(FnArg::Typed(children)) = body.inputs.iter().find(|arg| {
+            matches!(arg, FnArg::Typed(arg) if matches!(&*arg.pat, Pat::Ident(pat) if pat.ident == "children"))
+        }) {
+            return Err(Error::new_spanned(
+                children,
+                "an island can't take children, as its props are sent from the server",
+            ));
+        }
+        Ok(Self {
+            args: IslandArgs::default(),
+            body,
+        })
+    }
+}
+
+impl ToTokens for IslandBody {
+    fn to_tokens(&self, out_tokens: &mut TokenStream2) {
+        let InlinePropsBody {
+            vis,
+            ident,
+            output,
+            block,
+            cx_token,
+            attrs,
+            ..
+        } = &self.body;
+
+        let fields = self.body.prop_fields();
+
+        let struct_name = Ident::new(&format!("{}Props", ident), Span::call_site());
+
+        let field_names = self.body.prop_names();
+
+        let component_name = ident.to_string();
+        let strategy = self
+            .args
+            .strategy
+            .clone()
+            .unwrap_or_else(|| Ident::new("Load", Span::call_site()));
+
+        // the function the browser calls to hydrate each island of this component
+        let hydrate_fn = if Mode::default() == Mode::Hydrate {
+            let export_name = format!("_island_{component_name}");
+            let hydrate_ident = Ident::new(&format!("__island_{component_name}"), ident.span());
+            quote! {
+                #[::leptos::wasm_bindgen::prelude::wasm_bindgen(
+                    wasm_bindgen = ::leptos::wasm_bindgen,
+                    js_name = #export_name
+                )]
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub fn #hydrate_ident(el: ::leptos::web_sys::HtmlElement) {
+                    ::leptos::hydrate_island(el, #ident)
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        out_tokens.append_all(quote! {
+            #[derive(Props, ::serde::Serialize, ::serde::Deserialize)]
+            #[allow(non_camel_case_types)]
+            #vis struct #struct_name
+            {
+                #(#fields),*
+            }
+
+            #[allow(non_snake_case)]
+            #(#attrs)*
+            #vis fn #ident (#cx_token: Scope, props: #struct_name) #output
+            {
+                ::leptos::island(
+                    #cx_token,
+                    #component_name,
+                    ::leptos::HydrationStrategy::#strategy,
+                    props,
+                    move |props| {
+                        let #struct_name { #(#field_names),* } = props;
+                        #block
+                    },
+                )
+            }
+
+            #hydrate_fn
+        });
+    }
+}
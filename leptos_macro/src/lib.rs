@@ -34,8 +34,11 @@ mod params;
 mod view;
 use view::render_view;
 mod component;
+mod island;
 mod props;
 mod server;
+mod store;
+mod typed_route;
 
 /// The `view` macro uses RSX (like JSX, but Rust!) It follows most of the
 /// same rules as HTML, with the following differences:
@@ -352,6 +355,83 @@ pub fn component(_args: proc_macro::TokenStream, s: TokenStream) -> TokenStream
     }
 }
 
+/// Marks a component as an island: a part of the page that is interactive in the browser, while
+/// the rest of the page stays the static HTML that was rendered on the server. It takes the same
+/// arguments as a [component](macro@component), and is used the same way in the `view` macro.
+///
+/// When the `islands` option of [RenderOptions](leptos_config::RenderOptions) is set, the
+/// server integrations render each island in a `<leptos-island>` element, with its props
+/// serialized into it, and list the islands of the page in a manifest that is sent at the end
+/// of it. The browser then loads the WASM module of the app, if the page has any island, and
+/// hydrates each island on its own, by a function the macro exports from the module when the
+/// `hydrate` feature is enabled. As nothing else is hydrated, the `hydrate` function of the
+/// app isn't called, so the code of every other component is left out of the WASM binary.
+///
+/// ```
+/// # use leptos::*; use serde::{Serialize, Deserialize};
+/// #[island]
+/// fn Counter(cx: Scope, initial: i32) -> Element {
+///     let (count, set_count) = create_signal(cx, initial);
+///     view! {
+///         cx,
+///         <button on:click=move |_| set_count.update(|n| *n += 1)>{move || count.get()}</button>
+///     }
+/// }
+///
+/// #[component]
+/// fn Article(cx: Scope) -> Element {
+///     // only the counter is hydrated
+///     view! {
+///         cx,
+///         <article>
+///             <h1>"A static article"</h1>
+///             <Counter initial=3/>
+///         </article>
+///     }
+/// }
+/// ```
+///
+/// Note the following:
+/// - **Props must implement [serde::Serialize] and [serde::Deserialize].** They are sent from
+///   the server to the browser, so the crate needs to depend on `serde`.
+/// - **Islands can't be generic or take `children`,** as they are hydrated by their name, from
+///   nothing but their props.
+/// - **Resources read in an island load again in the browser,** as the data the server sent
+///   for the page isn't hydrated along with it.
+/// - An island can be nested in another one, which hydrates it along with itself. A component
+///   that isn't an island is only interactive when it's used in one.
+///
+/// An island can also be hydrated later than the rest of the page, with a
+/// [HydrationStrategy](leptos_dom::HydrationStrategy): `#[island(hydrate = "idle")]` once the
+/// browser is idle, `#[island(hydrate = "visible")]` once it's scrolled into view, or
+/// `#[island(hydrate = "interaction")]` once the pointer enters it or it gets focus. This also
+/// works without the `islands` option, in an app that is hydrated as a whole: such an island is
+/// then rendered in a `<leptos-island>` element, which is claimed along with the rest of the
+/// page, while its component only runs once it's needed.
+///
+/// ```
+/// # use leptos::*; use serde::{Serialize, Deserialize};
+/// #[island(hydrate = "visible")]
+/// fn Comments(cx: Scope, post_id: u32) -> Element {
+///     let (open, set_open) = create_signal(cx, false);
+///     view! {
+///         cx,
+///         <section>
+///             <button on:click=move |_| set_open.update(|open| *open = !*open)>"Comments"</button>
+///             <p>{move || open.get().then(|| format!("The comments on post {post_id}"))}</p>
+///         </section>
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn island(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as island::IslandArgs);
+    match syn::parse::<island::IslandBody>(s) {
+        Err(e) => e.to_compile_error().into(),
+        Ok(s) => island::IslandBody { args, ..s }.to_token_stream().into(),
+    }
+}
+
 /// Declares that a function is a [server function](leptos::leptos_server). This means that 
 /// its body will only run on the server, i.e., when the `ssr` feature is enabled.
 ///
@@ -367,6 +447,63 @@ pub fn component(_args: proc_macro::TokenStream, s: TokenStream) -> TokenStream
 ///   serialization) or `"Url"` (specifying that it should be use a URL-encoded form-data string).
 ///   Defaults to `"Url"`. If you want to use this server function to power an 
 ///   [ActionForm](leptos_router::ActionForm) the encoding must be `"Url"`.
+///   A function that takes a [MultipartData](leptos_server::MultipartData) or an
+///   [UploadedFile](leptos_server::UploadedFile) always uses `"Multipart"`, which sends
+///   `multipart/form-data`, so that it can power an `ActionForm` that uploads files.
+///
+/// The encoding can also be set with a named argument after these, e.g.,
+/// `#[server(MyServerFn, "/api", encoding = "msgpack")]`. Besides `"url"` and `"cbor"`, this
+/// accepts `"json"`, `"msgpack"` (the compact binary [MessagePack](https://msgpack.org/) format),
+/// or the name of any type that implements [Codec](leptos_server::Codec), so that, e.g.,
+/// Protobuf or Postcard can be plugged in: `encoding = MyCodec`.
+///
+/// A function that only reads data can be called with a `GET` request instead of a `POST`,
+/// with its URL-encoded arguments in the query string, so that browsers and CDNs can cache the
+/// response: `#[server(GetPosts, "/api", method = "GET", cache = "max-age=60")]`. The optional
+/// `cache` value is sent as the `Cache-Control` header of a successful response.
+///
+/// Server functions that aren't called with `GET` are protected against cross-site request
+/// forgery: the server integrations only run them if the request sends back the
+/// [CsrfToken](leptos_server::CsrfToken) they issued when rendering the page, which the client
+/// and `ActionForm` do for you. A function that other sites or services should be able to call
+/// can opt out with `#[server(Webhook, "/api", csrf = false)]`.
+///
+/// Access to a function can be restricted with `#[server(DeleteUser, "/api", guard = admin_only)]`,
+/// where `admin_only` is an async function that takes the [Scope](leptos::Scope) and the
+/// [RequestParts](leptos_server::RequestParts) of the call and returns
+/// `Result<(), ServerFnError>`. Guards run on the server before the arguments are
+/// deserialized, so an error rejects the call without doing any work for it; several guards
+/// can be listed with `guard = [logged_in, admin_only]`. See
+/// [ServerFnGuard](leptos_server::ServerFnGuard).
+///
+/// A function can set its own limits, which take the place of the ones the server integration
+/// is configured with: `#[server(Import, "/api", timeout = "10s", max_body = "2mb")]`. A call
+/// that runs longer fails with [ServerFnError::Timeout](leptos_server::ServerFnError::Timeout),
+/// and a larger request with
+/// [ServerFnError::PayloadTooLarge](leptos_server::ServerFnError::PayloadTooLarge), which the
+/// client receives like any other error of the function.
+///
+/// By default, the path a function is called at is derived from its source file and name, so it
+/// changes when the code is moved, which breaks clients that loaded an earlier build of the app
+/// mid-deploy. A stable path can be set with `endpoint`, optionally under a `version` segment:
+/// `#[server(AddTodo, "/api", endpoint = "todos/add", version = "v2")]` is called at
+/// `/api/v2/todos/add`. The paths it had before can be listed with `previous = ["todos/add"]`,
+/// so that the server keeps accepting calls from clients of the previous build during a
+/// rolling deploy; see [register_server_fn_alias](leptos_server::register_server_fn_alias)
+/// for paths that are only known at runtime.
+///
+/// Besides the function itself, the macro generates an async `call` function on its type that
+/// takes a [ServerFnClient](leptos_server::ServerFnClient), e.g.,
+/// `ReadPosts::call(&client, 3, query)`, so that any Rust code, like a native app, can call
+/// the server function at a configurable base URL.
+///
+/// With the `local` feature of `leptos`, e.g., in a desktop or Tauri build of the app, the
+/// function isn't called over HTTP at all: the client runs its body in the same process, like
+/// any other async function, so the same code runs as a web app and as a native one. As no
+/// request is made, limits don't apply, and giving the function `middleware`, a `guard` or
+/// `csrf` is a compile error rather than a check that silently never runs. The [Scope](leptos::Scope)
+/// it's given is the one of the caller, which holds no HTTP request; the dependencies its
+/// body uses need to be available in that build.
 ///
 /// The server function itself can take any number of arguments, each of which should be serializable 
 /// and deserializable with `serde`. Optionally, its first argument can be a Leptos [Scope](leptos::Scope),
@@ -391,9 +528,13 @@ pub fn component(_args: proc_macro::TokenStream, s: TokenStream) -> TokenStream
 /// - **Server functions must return `Result<T, ServerFnError>`.** Even if the work being done
 ///   inside the function body can’t fail, the processes of serialization/deserialization and the
 ///   network call are fallible.
+///   Instead of [ServerFnError](leptos_server::ServerFnError), the error can be any type that implements
+///   [ServerFnErrorKind](leptos_server::ServerFnErrorKind), which is preserved across the wire.
 /// - **Return types must be [Serializable](leptos_reactive::Serializable).**
 ///   This should be fairly obvious: we have to serialize arguments to send them to the server, and we
 ///   need to deserialize the result to return it to the client.
+///   A function can return `Result<impl Stream<Item = T>, _>` as well, which streams each `T` to
+///   the client as it is produced, where it arrives as a [ServerStream](leptos_server::ServerStream).
 /// - **Arguments must be implement [serde::Serialize].** They are serialized as an `application/x-www-form-urlencoded`
 ///   form data using [`serde_urlencoded`](https://docs.rs/serde_urlencoded/latest/serde_urlencoded/) or as `application/cbor`
 ///   using [`cbor`](https://docs.rs/cbor/latest/cbor/).
@@ -424,6 +565,81 @@ pub fn params_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     params::impl_params(&ast)
 }
 
+/// Derives [TypedRoute](leptos_router::TypedRoute) for a struct whose fields are the params of
+/// the path given with `#[route(path = "...")]`, and [Params](leptos_router::Params) to parse
+/// them, so that the path a [Route](leptos_router::Route) is matched at, the params that
+/// [use_params](leptos_router::use_params) reads, and the links built with
+/// [to_href](leptos_router::TypedRoute::to_href) all come from the same declaration.
+///
+/// The path is checked against the fields at compile time: each `:param` and `*wildcard`
+/// needs a field of the same name, and each field a param. A field of an optional `:param?`
+/// is an `Option`. Fields are parsed with [FromStr](std::str::FromStr) and written into links
+/// with [ToString].
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[derive(TypedRoute, Debug, Clone, PartialEq)]
+/// #[route(path = "/users/:id/posts/:post")]
+/// struct PostRoute {
+///     id: u32,
+///     post: String,
+/// }
+///
+/// let route = PostRoute { id: 5, post: "hello world".to_string() };
+/// assert_eq!(route.to_href(), "/users/5/posts/hello%20world");
+/// assert_eq!(PostRoute::from_map(&route.to_params()), Ok(route));
+/// ```
+#[proc_macro_derive(TypedRoute, attributes(route))]
+pub fn derive_typed_route(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    typed_route::impl_typed_route(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derives accessors that let each field of a struct held in a [Store](leptos_reactive::Store)
+/// be read and written independently, as a [StoreField](leptos_reactive::StoreField).
+///
+/// For a struct named `User`, this generates a `UserStoreFields` trait with one method per
+/// field, implemented for `Store<User>` and for any `StoreField` that holds a `User`, so fields
+/// of nested structs that also derive `Store` can be chained. Bring the trait into scope to
+/// use the accessors.
+///
+/// ```
+/// # use leptos::*;
+/// #[derive(Store, Clone, Default)]
+/// struct User {
+///     name: String,
+///     email: String,
+/// }
+///
+/// #[derive(Store, Clone, Default)]
+/// struct Form {
+///     user: User,
+///     accepted_terms: bool,
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// let form = create_store(cx, Form::default());
+///
+/// // only notifies effects that read the name, the user, or the whole form
+/// form.user().name().set("Alice".to_string());
+/// assert_eq!(form.user().name().get(), "Alice");
+/// assert_eq!(form.user().email().get(), "");
+/// assert!(!form.accepted_terms().get());
+/// # });
+/// ```
+#[proc_macro_derive(Store)]
+pub fn store_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    store::impl_store(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 pub(crate) fn is_component_node(node: &NodeElement) -> bool {
     let name = node.name.to_string();
     let first_char = name.chars().next();
@@ -1,5 +1,6 @@
+use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned};
-use syn::spanned::Spanned;
+use syn::{punctuated::Punctuated, spanned::Spanned, token::Comma, Field, Ident};
 
 pub fn impl_params(ast: &syn::DeriveInput) -> proc_macro::TokenStream {
     let name = &ast.ident;
@@ -9,32 +10,39 @@ pub fn impl_params(ast: &syn::DeriveInput) -> proc_macro::TokenStream {
         ..
     }) = ast.data
     {
-        fields
-            .named
-            .iter()
-            .map(|field| {
-				let field_name_string = &field.ident.as_ref().unwrap().to_string();
-				let ident = &field.ident;
-				let ty = &field.ty;
-				let span = field.span().unwrap();
-
-				quote_spanned! {
-					span.into() => #ident: <#ty>::into_param(map.get(#field_name_string).map(|n| n.as_str()), #field_name_string)?
-				}
-			})
-            .collect()
+        Some(&fields.named)
     } else {
-        vec![]
+        None
     };
 
-    let gen = quote! {
-        impl Params for #name {
-            fn from_map(map: &::leptos_router::ParamsMap) -> Result<Self, ::leptos_router::RouterError> {
+    params_impl(name, fields).into()
+}
+
+/// Implements `Params` for a struct by parsing each of its fields from the param of the same
+/// name.
+pub(crate) fn params_impl(name: &Ident, fields: Option<&Punctuated<Field, Comma>>) -> TokenStream {
+    let fields = fields
+        .into_iter()
+        .flatten()
+        .map(|field| {
+            let field_name_string = &field.ident.as_ref().unwrap().to_string();
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let span = field.span();
+
+            quote_spanned! {
+                span => #ident: <#ty as ::leptos_router::IntoParam>::into_param(map.get(#field_name_string).map(|n| n.as_str()), #field_name_string)?
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl ::leptos_router::Params for #name {
+            fn from_map(map: &::leptos_router::ParamsMap) -> Result<Self, ::leptos_router::ParamsError> {
                 Ok(Self {
                     #(#fields,)*
                 })
             }
         }
-    };
-    gen.into()
+    }
 }
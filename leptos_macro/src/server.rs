@@ -23,20 +23,158 @@ fn fn_arg_is_cx(f: &syn::FnArg) -> bool {
     }
 }
 
+fn fn_arg_is_upload(f: &syn::FnArg) -> bool {
+    if let FnArg::Typed(t) = f {
+        if let Type::Path(path) = &*t.ty {
+            path.path.segments.last().map_or(false, |segment| {
+                segment.ident == "MultipartData" || segment.ident == "UploadedFile"
+            })
+        } else {
+            false
+        }
+    } else {
+        false
+    }
+}
+
+// with the `local` feature, the body is called directly rather than over HTTP, so nothing would
+// run the middleware, guards or CSRF check
+fn check_local(args: &ServerFnName, local: bool) -> Result<()> {
+    if !local {
+        return Ok(());
+    }
+    if let Some(middleware) = args.middleware.first() {
+        return Err(Error::new_spanned(
+            middleware,
+            "`middleware` can't be used with the `local` feature, which calls the function directly",
+        ));
+    }
+    if let Some(guard) = args.guards.first() {
+        return Err(Error::new_spanned(
+            guard,
+            "`guard` can't be used with the `local` feature, which calls the function directly",
+        ));
+    }
+    if let Some(csrf) = &args.csrf_attr {
+        return Err(Error::new_spanned(
+            csrf,
+            "`csrf` can't be used with the `local` feature, which calls the function directly",
+        ));
+    }
+    Ok(())
+}
+
 pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Result<TokenStream2> {
+    let args = syn::parse::<ServerFnName>(args)?;
+    check_local(&args, cfg!(feature = "local"))?;
     let ServerFnName {
         struct_name,
         prefix,
         encoding,
+        codec,
+        middleware,
+        guards,
+        method,
+        cache,
+        csrf,
+        timeout,
+        max_body,
+        endpoint,
+        version,
+        previous,
         ..
-    } = syn::parse::<ServerFnName>(args)?;
+    } = args;
     let prefix = prefix.unwrap_or_else(|| Literal::string(""));
-    let encoding = match encoding {
-        Encoding::Cbor => quote! { ::leptos::Encoding::Cbor },
-        Encoding::Url => quote! { ::leptos::Encoding::Url },
-    };
 
     let body = syn::parse::<ServerFnBody>(s.into())?;
+
+    // a function that takes a file is called with a multipart/form-data request
+    let takes_upload = body.inputs.iter().any(fn_arg_is_upload);
+    let encoding = if takes_upload {
+        if let Some(codec) = &codec {
+            return Err(Error::new_spanned(
+                codec,
+                "server functions that take uploads are always called with multipart/form-data",
+            ));
+        }
+        Encoding::Multipart
+    } else {
+        encoding
+    };
+    // the arguments of a GET request are sent in its query string
+    let is_get = method
+        .as_ref()
+        .map_or(false, |method| method.value().eq_ignore_ascii_case("GET"));
+    if is_get && (codec.is_some() || encoding != Encoding::Url) {
+        return Err(Error::new_spanned(
+            method,
+            "server functions called with GET must use the \"url\" encoding",
+        ));
+    }
+    if let Some(cache) = &cache {
+        if !is_get {
+            return Err(Error::new_spanned(
+                cache,
+                "only server functions called with `method = \"GET\"` can be cached",
+            ));
+        }
+    }
+    let method_fn = if is_get {
+        quote! {
+            fn method() -> ::leptos::HttpMethod {
+                ::leptos::HttpMethod::Get
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let cache_fn = match cache {
+        Some(cache) => quote! {
+            fn cache_control() -> Option<&'static str> {
+                Some(#cache)
+            }
+        },
+        None => quote! {},
+    };
+    let csrf_fn = if csrf {
+        quote! {}
+    } else {
+        quote! {
+            fn csrf() -> bool {
+                false
+            }
+        }
+    };
+    let timeout_fn = match timeout {
+        Some(millis) => quote! {
+            fn timeout() -> Option<::std::time::Duration> {
+                Some(::std::time::Duration::from_millis(#millis))
+            }
+        },
+        None => quote! {},
+    };
+    let max_body_fn = match max_body {
+        Some(size) => quote! {
+            fn max_body_size() -> Option<u64> {
+                Some(#size)
+            }
+        },
+        None => quote! {},
+    };
+
+    let encoding = match (&codec, encoding) {
+        (Some(codec), _) => {
+            quote! { ::leptos::Encoding::Codec(<#codec as ::leptos::Codec>::CONTENT_TYPE) }
+        }
+        (None, Encoding::Cbor) => quote! { ::leptos::Encoding::Cbor },
+        (None, Encoding::Multipart) => quote! { ::leptos::Encoding::Multipart },
+        (None, _) => quote! { ::leptos::Encoding::Url },
+    };
+    let codec = match codec {
+        Some(codec) => quote! { #codec },
+        None => quote! { ::leptos::Json },
+    };
+
     let fn_name = &body.ident;
     let fn_name_as_str = body.ident.to_string();
     let vis = body.vis;
@@ -54,6 +192,24 @@ pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Resu
             let url = fn_name_as_str;
         }
     }
+    // a path given with `endpoint` doesn't change when the code is moved or rebuilt
+    let url = match &endpoint {
+        Some(endpoint) => endpoint.value(),
+        None => url,
+    };
+    let url = match &version {
+        Some(version) => format!("{}/{url}", version.value()),
+        None => url,
+    };
+    let previous_fn = if previous.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn previous_urls() -> &'static [&'static str] {
+                &[#(#previous),*]
+            }
+        }
+    };
 
     let fields = body.inputs.iter().filter(|f| !fn_arg_is_cx(f)).map(|f| {
         let typed_arg = match f {
@@ -105,6 +261,29 @@ pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Resu
         }
     });
 
+    let multipart_fns = if takes_upload {
+        let field_names = field_names.clone();
+        let field_names_2 = field_names.clone();
+        let field_names_3 = field_names.clone();
+        let field_name_strs = field_names.clone().map(|name| quote! { #name }.to_string());
+        let field_name_strs_2 = field_name_strs.clone();
+        quote! {
+            #[cfg(feature = "ssr")]
+            fn from_multipart(mut data: ::leptos::MultipartData) -> Result<Self, ::leptos::ServerFnError> {
+                #(let #field_names = ::leptos::MultipartArg::from_multipart(&mut data, #field_name_strs)?;)*
+                Ok(Self { #(#field_names_2),* })
+            }
+
+            fn into_multipart(self) -> Result<::leptos::MultipartData, ::leptos::ServerFnError> {
+                let mut data = ::leptos::MultipartData::new();
+                #(::leptos::MultipartArg::into_multipart(self.#field_names_3, &mut data, #field_name_strs_2);)*
+                Ok(data)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let field_names_2 = field_names.clone();
     let field_names_3 = field_names.clone();
     let field_names_4 = field_names.clone();
@@ -113,18 +292,128 @@ pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Resu
     let output_arrow = body.output_arrow;
     let return_ty = body.return_ty;
 
-    let output_ty = if let syn::Type::Path(pat) = &return_ty {
+    let (output_ty, error_ty) = if let syn::Type::Path(pat) = &return_ty {
         if pat.path.segments[0].ident == "Result" {
             if let PathArguments::AngleBracketed(args) = &pat.path.segments[0].arguments {
-                &args.args[0]
+                if args.args.len() != 2 {
+                    panic!("server functions should return Result<T, E>");
+                }
+                (&args.args[0], &args.args[1])
             } else {
-                panic!("server functions should return Result<T, ServerFnError>");
+                panic!("server functions should return Result<T, E>");
             }
         } else {
-            panic!("server functions should return Result<T, ServerFnError>");
+            panic!("server functions should return Result<T, E>");
+        }
+    } else {
+        panic!("server functions should return Result<T, E>");
+    };
+
+    // a function that returns `impl Stream<Item = T>` streams its values to the client
+    let stream_item_ty = stream_item_ty(output_ty);
+
+    // the signature describes the function in the registry and the OpenAPI spec
+    let signature_args = body.inputs.iter().filter_map(|f| match f {
+        FnArg::Typed(t) if !fn_arg_is_cx(f) => {
+            let pat = &t.pat;
+            let ty = &t.ty;
+            let name = quote! { #pat }.to_string();
+            let ty = type_name(quote! { #ty });
+            Some(quote! { (#name, #ty) })
+        }
+        _ => None,
+    });
+    let signature_output = match stream_item_ty {
+        Some(item_ty) => type_name(quote! { #item_ty }),
+        None => type_name(quote! { #output_ty }),
+    };
+    let signature_error = type_name(quote! { #error_ty });
+    let streaming = stream_item_ty.is_some();
+
+    // with the `schemars` feature, the OpenAPI spec describes the types with their JSON schemas
+    let schemas_fn = if cfg!(feature = "schemars") {
+        let schema_args = body.inputs.iter().filter_map(|f| match f {
+            FnArg::Typed(t) if !fn_arg_is_cx(f) && !fn_arg_is_upload(f) => {
+                let pat = &t.pat;
+                let ty = &t.ty;
+                let name = quote! { #pat }.to_string();
+                Some(quote! { (#name, generator.subschema_for::<#ty>()) })
+            }
+            _ => None,
+        });
+        let schema_output = match stream_item_ty {
+            Some(item_ty) => quote! { #item_ty },
+            None => quote! { #output_ty },
+        };
+        quote! {
+            fn schemas(generator: &mut ::leptos::schemars::gen::SchemaGenerator) -> Option<::leptos::ServerFnSchemas> {
+                Some(::leptos::ServerFnSchemas {
+                    args: vec![#(#schema_args),*],
+                    output: generator.subschema_for::<#schema_output>(),
+                    error: generator.subschema_for::<#error_ty>(),
+                })
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let (output_ty, call_server_fn, into_output) = match stream_item_ty {
+        Some(item_ty) => (
+            quote! { ::leptos::ServerStream<#item_ty> },
+            quote! { ::leptos::call_server_fn_stream },
+            quote! { .map(::leptos::ServerStream::new) },
+        ),
+        None => (
+            quote! { #output_ty },
+            quote! { ::leptos::call_server_fn },
+            quote! {},
+        ),
+    };
+
+    // functions that stream their values can't be called with a `ServerFnClient`
+    let client_fn = if stream_item_ty.is_none() {
+        let client_args = body.inputs.iter().filter(|f| !fn_arg_is_cx(f));
+        let field_names = field_names.clone();
+        quote! {
+            impl #struct_name {
+                /// Calls the server function with the given client, which can be used from any
+                /// Rust code, e.g., from a native app.
+                #vis async fn call(client: &::leptos::ServerFnClient, #(#client_args),*) -> #return_ty {
+                    client.call(#struct_name { #(#field_names),* }).await
+                }
+            }
         }
     } else {
-        panic!("server functions should return Result<T, ServerFnError>");
+        quote! {}
+    };
+
+    // a desktop build runs the body in the same process, rather than calling the server over HTTP
+    let client_body = if cfg!(feature = "local") {
+        quote! { #block }
+    } else {
+        quote! {
+            let prefix = #struct_name::prefix().to_string();
+            let url = prefix + "/" + #struct_name::url();
+            #call_server_fn(&url, #struct_name { #(#field_names_5),* }, #encoding).await
+        }
+    };
+
+    // guards run after the other middleware
+    let middleware_fn = if middleware.is_empty() && guards.is_empty() {
+        quote! {}
+    } else {
+        let middleware = middleware.iter();
+        let guards = guards.iter();
+        quote! {
+            #[cfg(feature = "ssr")]
+            fn middleware() -> Vec<::std::sync::Arc<dyn ::leptos::ServerFnMiddleware>> {
+                vec![
+                    #(::std::sync::Arc::new(#middleware),)*
+                    #(::std::sync::Arc::new(::leptos::ServerFnGuard::new(#guards)),)*
+                ]
+            }
+        }
     };
 
     Ok(quote::quote! {
@@ -135,6 +424,8 @@ pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Resu
 
         impl ServerFn for #struct_name {
             type Output = #output_ty;
+            type Error = #error_ty;
+            type Codec = #codec;
 
             fn prefix() -> &'static str {
                 #prefix
@@ -148,39 +439,125 @@ pub fn server_macro_impl(args: proc_macro::TokenStream, s: TokenStream2) -> Resu
                 #encoding
             }
 
+            #method_fn
+
+            #cache_fn
+
+            #csrf_fn
+
+            #timeout_fn
+
+            #max_body_fn
+
+            #previous_fn
+
+            fn signature() -> ::leptos::ServerFnSignature {
+                ::leptos::ServerFnSignature {
+                    args: &[#(#signature_args),*],
+                    output: #signature_output,
+                    error: #signature_error,
+                    streaming: #streaming,
+                }
+            }
+
+            #schemas_fn
+
+            #middleware_fn
+
+            #multipart_fns
+
             #[cfg(feature = "ssr")]
-            fn call_fn(self, cx: ::leptos::Scope) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Output, ::leptos::ServerFnError>>>> {
+            fn call_fn(self, cx: ::leptos::Scope) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Output, Self::Error>>>> {
                 let #struct_name { #(#field_names),* } = self;
                 #cx_assign_statement;
-                Box::pin(async move { #fn_name( #cx_fn_arg #(#field_names_2),*).await })
+                Box::pin(async move { #fn_name( #cx_fn_arg #(#field_names_2),*).await #into_output })
             }
 
             #[cfg(not(feature = "ssr"))]
-            fn call_fn_client(self, cx: ::leptos::Scope) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Output, ::leptos::ServerFnError>>>> {
+            fn call_fn_client(self, cx: ::leptos::Scope) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Output, Self::Error>>>> {
                 let #struct_name { #(#field_names_3),* } = self;
-                Box::pin(async move { #fn_name( #cx_fn_arg #(#field_names_4),*).await })
+                Box::pin(async move { #fn_name( #cx_fn_arg #(#field_names_4),*).await #into_output })
             }
         }
 
+        #client_fn
+
         #[cfg(feature = "ssr")]
         #vis async fn #fn_name(#(#fn_args),*) #output_arrow #return_ty {
             #block
         }
         #[cfg(not(feature = "ssr"))]
         #vis async fn #fn_name(#(#fn_args_2),*) #output_arrow #return_ty {
-            let prefix = #struct_name::prefix().to_string();
-            let url = prefix + "/" + #struct_name::url();
-            ::leptos::call_server_fn(&url, #struct_name { #(#field_names_5),* }, #encoding).await
+            #client_body
         }
     })
 }
 
+/// Writes a type the way it's written in code, e.g., `Vec<String>` rather than the
+/// `Vec < String >` that the tokens are printed as.
+fn type_name(ty: TokenStream2) -> String {
+    let printed = ty.to_string();
+    let chars = printed.chars().collect::<Vec<_>>();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(index, c)| {
+            // only the spaces between two words, like in `dyn Trait`, are kept
+            *c != ' '
+                || (index > 0
+                    && chars.get(index - 1).map_or(false, is_word)
+                    && chars.get(index + 1).map_or(false, is_word))
+        })
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Returns `T` if the given output type of a server function is `impl Stream<Item = T>`.
+fn stream_item_ty(output_ty: &GenericArgument) -> Option<&Type> {
+    if let GenericArgument::Type(Type::ImplTrait(impl_trait)) = output_ty {
+        impl_trait.bounds.iter().find_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => {
+                let segment = bound.path.segments.last()?;
+                if segment.ident != "Stream" {
+                    return None;
+                }
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    args.args.iter().find_map(|arg| match arg {
+                        GenericArgument::Binding(binding) if binding.ident == "Item" => {
+                            Some(&binding.ty)
+                        }
+                        _ => None,
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    } else {
+        None
+    }
+}
+
 pub struct ServerFnName {
     struct_name: Ident,
     _comma: Option<Token![,]>,
     prefix: Option<Literal>,
     _comma2: Option<Token![,]>,
     encoding: Encoding,
+    codec: Option<Type>,
+    middleware: Vec<Expr>,
+    guards: Vec<Expr>,
+    method: Option<LitStr>,
+    cache: Option<LitStr>,
+    csrf: bool,
+    csrf_attr: Option<LitBool>,
+    timeout: Option<u64>,
+    max_body: Option<u64>,
+    endpoint: Option<LitStr>,
+    version: Option<LitStr>,
+    previous: Vec<LitStr>,
 }
 
 impl Parse for ServerFnName {
@@ -189,7 +566,120 @@ impl Parse for ServerFnName {
         let _comma = input.parse()?;
         let prefix = input.parse()?;
         let _comma2 = input.parse()?;
-        let encoding = input.parse().unwrap_or(Encoding::Url);
+        let mut encoding = input.parse().unwrap_or(Encoding::Url);
+        let mut codec = None;
+        let mut middleware = Vec::new();
+        let mut guards = Vec::new();
+        let mut method = None;
+        let mut cache = None;
+        let mut csrf = true;
+        let mut csrf_attr = None;
+        let mut timeout = None;
+        let mut max_body = None;
+        let mut endpoint = None;
+        let mut version = None;
+        let mut previous = Vec::new();
+
+        // named arguments like `encoding = ...` and `middleware = [A, B]` can follow any of
+        // the other arguments
+        while !input.is_empty() {
+            input.parse::<Option<Token![,]>>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if name == "middleware" {
+                let content;
+                bracketed!(content in input);
+                middleware = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            } else if name == "guard" {
+                // either a single guard or a list of them, which run in order
+                if input.peek(token::Bracket) {
+                    let content;
+                    bracketed!(content in input);
+                    guards.extend(Punctuated::<Expr, Token![,]>::parse_terminated(&content)?);
+                } else {
+                    guards.push(input.parse()?);
+                }
+            } else if name == "encoding" {
+                if input.peek(LitStr) {
+                    let name: LitStr = input.parse()?;
+                    match name.value().as_str() {
+                        "url" => encoding = Encoding::Url,
+                        "cbor" => encoding = Encoding::Cbor,
+                        "json" => codec = Some(parse_quote!(::leptos::Json)),
+                        "msgpack" => codec = Some(parse_quote!(::leptos::MsgPack)),
+                        _ => {
+                            return Err(Error::new(
+                                name.span(),
+                                "expected \"url\", \"cbor\", \"json\", \"msgpack\" \
+                                 or a type that implements `Codec`",
+                            ))
+                        }
+                    }
+                } else {
+                    codec = Some(input.parse()?);
+                }
+            } else if name == "method" {
+                let name: LitStr = input.parse()?;
+                if !["GET", "POST"].contains(&name.value().to_uppercase().as_str()) {
+                    return Err(Error::new(name.span(), "expected \"GET\" or \"POST\""));
+                }
+                method = Some(name);
+            } else if name == "cache" {
+                cache = Some(input.parse()?);
+            } else if name == "csrf" {
+                let value: LitBool = input.parse()?;
+                csrf = value.value;
+                csrf_attr = Some(value);
+            } else if name == "timeout" {
+                // checked here, so that a typo fails to compile rather than at runtime
+                let value: LitStr = input.parse()?;
+                let duration = leptos_config::parse_duration(&value.value())
+                    .map_err(|e| Error::new(value.span(), e))?;
+                timeout = Some(duration.as_millis() as u64);
+            } else if name == "max_body" {
+                let value: LitStr = input.parse()?;
+                let size = leptos_config::parse_size(&value.value())
+                    .map_err(|e| Error::new(value.span(), e))?;
+                max_body = Some(size);
+            } else if name == "endpoint" {
+                let value: LitStr = input.parse()?;
+                let path = value.value();
+                if path.is_empty() || path.starts_with('/') || path.ends_with('/') {
+                    return Err(Error::new(
+                        value.span(),
+                        "expected a path relative to the prefix, like \"todos/add\"",
+                    ));
+                }
+                endpoint = Some(value);
+            } else if name == "version" {
+                let value: LitStr = input.parse()?;
+                if value.value().is_empty() || value.value().contains('/') {
+                    return Err(Error::new(
+                        value.span(),
+                        "expected a single path segment, like \"v2\"",
+                    ));
+                }
+                version = Some(value);
+            } else if name == "previous" {
+                // the paths the function was reached at before, e.g., by the previous build
+                let content;
+                bracketed!(content in input);
+                previous = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            } else {
+                return Err(Error::new(
+                    name.span(),
+                    "expected `encoding`, `middleware`, `guard`, `method`, `cache`, `csrf`, \
+                     `timeout`, `max_body`, `endpoint`, `version` or `previous`",
+                ));
+            }
+        }
 
         Ok(Self {
             struct_name,
@@ -197,6 +687,18 @@ impl Parse for ServerFnName {
             prefix,
             _comma2,
             encoding,
+            codec,
+            middleware,
+            guards,
+            method,
+            cache,
+            csrf,
+            csrf_attr,
+            timeout,
+            max_body,
+            endpoint,
+            version,
+            previous,
         })
     }
 }
@@ -252,3 +754,49 @@ impl Parse for ServerFnBody {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(args: &str, local: bool) -> Result<()> {
+        check_local(&syn::parse_str::<ServerFnName>(args)?, local)
+    }
+
+    #[test]
+    fn local_allows_plain_server_fns() {
+        assert!(check("AddTodo, \"/api\"", true).is_ok());
+        assert!(check("AddTodo, \"/api\", method = \"GET\"", true).is_ok());
+    }
+
+    #[test]
+    fn local_rejects_middleware() {
+        let err = check("AddTodo, \"/api\", middleware = [Logger]", true).unwrap_err();
+        assert!(err.to_string().contains("`middleware`"));
+        assert!(check("AddTodo, \"/api\", middleware = [Logger]", false).is_ok());
+    }
+
+    #[test]
+    fn local_rejects_guards() {
+        let err = check("AddTodo, \"/api\", guard = require_user", true).unwrap_err();
+        assert!(err.to_string().contains("`guard`"));
+        let err = check("AddTodo, \"/api\", guard = [a, b]", true).unwrap_err();
+        assert!(err.to_string().contains("`guard`"));
+        assert!(check("AddTodo, \"/api\", guard = require_user", false).is_ok());
+    }
+
+    #[test]
+    fn local_rejects_csrf() {
+        let err = check("AddTodo, \"/api\", csrf = false", true).unwrap_err();
+        assert!(err.to_string().contains("`csrf`"));
+        assert!(check("AddTodo, \"/api\", csrf = false", false).is_ok());
+    }
+
+    #[test]
+    fn local_error_becomes_compile_error() {
+        let err = check("AddTodo, \"/api\", middleware = [Logger]", true).unwrap_err();
+        let expanded = err.to_compile_error().to_string();
+        assert!(expanded.contains("compile_error"));
+        assert!(expanded.contains("`local` feature"));
+    }
+}
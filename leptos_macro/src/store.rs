@@ -0,0 +1,74 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DataStruct, DeriveInput, Error, Fields, Result};
+
+pub fn impl_store(ast: &DeriveInput) -> Result<TokenStream> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &ast.generics,
+            "#[derive(Store)] does not support generic structs",
+        ));
+    }
+
+    let fields = match &ast.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "#[derive(Store)] can only be used on structs with named fields",
+            ))
+        }
+    };
+
+    let trait_name = format_ident!("{}StoreFields", name);
+    let docs = format!(
+        "Accessors for the fields of a [`{name}`] held in a [`Store`](::leptos::Store), generated by `#[derive(Store)]`."
+    );
+
+    let (signatures, methods): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            let ty = &field.ty;
+            let field_name = ident.to_string();
+            let signature = quote! {
+                fn #ident(&self) -> ::leptos::StoreField<Root, #ty>
+            };
+            let method = quote! {
+                #signature {
+                    ::leptos::StorePath::field(
+                        self,
+                        #field_name,
+                        |value: &#name| &value.#ident,
+                        |value: &mut #name| &mut value.#ident,
+                    )
+                }
+            };
+            (signature, method)
+        })
+        .unzip();
+
+    Ok(quote! {
+        #[doc = #docs]
+        #vis trait #trait_name<Root> {
+            #(
+                #[allow(missing_docs)]
+                #signatures;
+            )*
+        }
+
+        impl<Root, S> #trait_name<Root> for S
+        where
+            Root: 'static,
+            S: ::leptos::StorePath<Root, #name>,
+        {
+            #(#methods)*
+        }
+    })
+}
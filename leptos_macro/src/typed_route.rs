@@ -0,0 +1,187 @@
+use crate::params::params_impl;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    Data, DataStruct, DeriveInput, Error, Fields, Lit, Meta, NestedMeta, PathArguments, Result,
+    Type,
+};
+
+pub fn impl_typed_route(ast: &DeriveInput) -> Result<TokenStream> {
+    let name = &ast.ident;
+
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &ast.generics,
+            "#[derive(TypedRoute)] does not support generic structs",
+        ));
+    }
+
+    let fields = match &ast.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => Some(&fields.named),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => None,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "#[derive(TypedRoute)] can only be used on structs with named fields",
+            ))
+        }
+    };
+
+    let path = route_path(ast)?;
+    let params = path_params(&path.value());
+
+    // every param of the path is a field, and every field a param, so that neither the params
+    // that are parsed nor the links that are built can miss one
+    for field in fields.into_iter().flatten() {
+        let ident = field.ident.as_ref().unwrap();
+        let field_name = ident.to_string();
+        match params.iter().find(|param| param.name == field_name) {
+            None => {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("the path `{}` has no param `{field_name}`", path.value()),
+                ))
+            }
+            Some(param) if param.optional && !is_option(&field.ty) => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    format!(
+                        "the param `{field_name}` is optional, so its type should be an `Option`"
+                    ),
+                ))
+            }
+            Some(param) if !param.optional && is_option(&field.ty) => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    format!(
+                        "the param `{field_name}` is required, so its type shouldn't be an \
+                         `Option`; mark it `:{field_name}?` to make it optional"
+                    ),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    for param in &params {
+        let has_field = fields
+            .into_iter()
+            .flatten()
+            .any(|field| field.ident.as_ref().unwrap() == param.name.as_str());
+        if !has_field {
+            return Err(Error::new_spanned(
+                &path,
+                format!("`{name}` has no field for the param `{}`", param.name),
+            ));
+        }
+    }
+
+    let inserts = fields.into_iter().flatten().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let field_name = ident.to_string();
+        if is_option(&field.ty) {
+            quote! {
+                if let Some(value) = &self.#ident {
+                    map.insert(#field_name.to_string(), ::std::string::ToString::to_string(value));
+                }
+            }
+        } else {
+            quote! {
+                map.insert(#field_name.to_string(), ::std::string::ToString::to_string(&self.#ident));
+            }
+        }
+    });
+
+    let params_impl = params_impl(name, fields);
+
+    Ok(quote! {
+        #params_impl
+
+        impl ::leptos_router::TypedRoute for #name {
+            const PATH: &'static str = #path;
+
+            fn to_params(&self) -> ::leptos_router::ParamsMap {
+                #[allow(unused_mut)]
+                let mut map = ::leptos_router::ParamsMap::new();
+                #(#inserts)*
+                map
+            }
+        }
+    })
+}
+
+/// Reads the path from the `#[route(path = "...")]` attribute.
+fn route_path(ast: &DeriveInput) -> Result<syn::LitStr> {
+    for attr in ast.attrs.iter().filter(|attr| attr.path.is_ident("route")) {
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(pair)) = nested {
+                    if pair.path.is_ident("path") {
+                        return match pair.lit {
+                            Lit::Str(path) => Ok(path),
+                            lit => Err(Error::new_spanned(lit, "the path should be a string")),
+                        };
+                    }
+                }
+            }
+        }
+    }
+    Err(Error::new(
+        Span::call_site(),
+        "#[derive(TypedRoute)] needs a path, e.g., #[route(path = \"/users/:id\")]",
+    ))
+}
+
+struct PathParam {
+    name: String,
+    optional: bool,
+}
+
+/// Finds the params in a path pattern: `:name`, `:name?` and `*name`, where a param can be
+/// followed by the pattern its value must match, e.g., `:name(\d+)`.
+fn path_params(path: &str) -> Vec<PathParam> {
+    path.split('/')
+        .filter_map(|segment| {
+            if let Some(param) = segment.strip_prefix(':') {
+                let (param, optional) = match param.strip_suffix('?') {
+                    Some(param) => (param, true),
+                    None => (param, false),
+                };
+                let name = match param.split_once('(') {
+                    Some((name, constraint)) if constraint.ends_with(')') => name,
+                    _ => param,
+                };
+                Some(PathParam {
+                    name: name.to_string(),
+                    optional,
+                })
+            } else {
+                // a wildcard matches an empty rest of the path, so it's always there
+                segment
+                    .strip_prefix('*')
+                    .filter(|name| !name.is_empty())
+                    .map(|name| PathParam {
+                        name: name.to_string(),
+                        optional: false,
+                    })
+            }
+        })
+        .collect()
+}
+
+fn is_option(ty: &Type) -> bool {
+    if let Type::Path(path) = ty {
+        matches!(
+            path.path.segments.last(),
+            Some(segment) if segment.ident == "Option"
+                && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+        )
+    } else {
+        false
+    }
+}
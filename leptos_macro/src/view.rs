@@ -21,6 +21,25 @@ const NON_BUBBLING_EVENTS: [&str; 11] = [
     "loadend",
 ];
 
+// events the user causes, which an element only responds to without JavaScript if the browser
+// does something with it by itself, e.g., follows a link
+const INTERACTIVE_EVENTS: [&str; 14] = [
+    "click",
+    "dblclick",
+    "auxclick",
+    "contextmenu",
+    "input",
+    "change",
+    "submit",
+    "keydown",
+    "keyup",
+    "keypress",
+    "pointerdown",
+    "pointerup",
+    "mousedown",
+    "mouseup",
+];
+
 lazy_static::lazy_static! {
     // Specialized event type
     // https://github.com/yewstack/yew/blob/d422b533ea19a09cddf9b31ecd6cd5e5ce35ce3f/packages/yew/src/html/listener/events.rs
@@ -298,6 +317,32 @@ fn element_to_tokens(
         });
     }
 
+    // for SSR in debug builds: record an element that would do nothing without JavaScript
+    if mode == Mode::Ssr {
+        let events = attributes(node)
+            .filter_map(|attr| {
+                let name = attr.key.to_string();
+                let event = name.strip_prefix("on:")?;
+                INTERACTIVE_EVENTS.contains(&event).then(|| event.to_string())
+            })
+            .collect::<Vec<_>>();
+        let behavior = no_js_behavior(node);
+        if !events.is_empty() && behavior != NoJsBehavior::Native {
+            let submits_form = behavior == NoJsBehavior::SubmitsForm;
+            expressions.push(quote_spanned! {
+                span => if cfg!(debug_assertions) {
+                    leptos_dom::audit_element(
+                        #cx,
+                        #name_str,
+                        &[#(#events),*],
+                        #submits_form,
+                        concat!(file!(), ":", line!()),
+                    )
+                }
+            });
+        }
+    }
+
     // for SSR: merge all class: attributes and class attribute
     if mode == Mode::Ssr {
         let class_attr = attributes(node)
@@ -431,6 +476,15 @@ fn element_to_tokens(
         template.push('>');
     }
 
+    // for SSR in debug builds: buttons in a form that submits without JavaScript aren't dead
+    let is_native_form =
+        mode == Mode::Ssr && name_str == "form" && no_js_behavior(node) == NoJsBehavior::Native;
+    if is_native_form {
+        expressions.push(quote_spanned! {
+            span => if cfg!(debug_assertions) { leptos_dom::audit_form(#cx, true) }
+        });
+    }
+
     // iterate over children
     let mut prev_sib = prev_sib;
     let multi = !node.children.is_empty();
@@ -467,7 +521,12 @@ fn element_to_tokens(
             leptos_buffer.push_str("</");
             leptos_buffer.push_str(#name_str);
             leptos_buffer.push('>');
-        })
+        });
+        if is_native_form {
+            expressions.push(quote_spanned! {
+                span => if cfg!(debug_assertions) { leptos_dom::audit_form(#cx, false) }
+            });
+        }
     } else {
         template.push_str("</");
         template.push_str(&name_str);
@@ -477,6 +536,32 @@ fn element_to_tokens(
     this_el_ident
 }
 
+/// What the browser does with an element by itself, without JavaScript.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NoJsBehavior {
+    /// Follows a link or submits a form.
+    Native,
+    /// Submits the `<form>` it's in, or is sent along with it, if it's in one.
+    SubmitsForm,
+    Nothing,
+}
+
+fn no_js_behavior(node: &NodeElement) -> NoJsBehavior {
+    let has_attribute = |name: &str| attributes(node).any(|attr| attr.key.to_string() == name);
+    let input_type = attributes(node)
+        .find(|attr| attr.key.to_string() == "type")
+        .and_then(|attr| attr.value.as_ref())
+        .and_then(|value| String::try_from(value).ok());
+
+    match node.name.to_string().as_str() {
+        "a" | "area" if has_attribute("href") => NoJsBehavior::Native,
+        "form" if has_attribute("action") || has_attribute("method") => NoJsBehavior::Native,
+        "button" | "input" if input_type.as_deref() == Some("button") => NoJsBehavior::Nothing,
+        "button" | "input" | "select" | "textarea" => NoJsBehavior::SubmitsForm,
+        _ => NoJsBehavior::Nothing,
+    }
+}
+
 fn next_sibling_node(children: &[Node], idx: usize, next_el_id: &mut usize) -> Option<Ident> {
     if children.len() <= idx {
         None
@@ -1036,6 +1121,7 @@ fn create_component(cx: &Ident, node: &NodeElement, mode: Mode) -> TokenStream {
     let component_name = ident_from_tag_name(&node.name);
     let span = node.name.span();
     let component_props_name = Ident::new(&format!("{component_name}Props"), span);
+    let debug_name = component_name.to_string();
 
     let children = if node.children.is_empty() {
         quote! {}
@@ -1117,7 +1203,7 @@ fn create_component(cx: &Ident, node: &NodeElement, mode: Mode) -> TokenStream {
 
     if other_attrs.peek().is_none() {
         quote_spanned! {
-            span => create_component(#cx, move || {
+            span => create_component(#cx, #debug_name, move || {
                 #component_name(
                     #cx,
                     #component_props_name::builder()
@@ -1129,7 +1215,7 @@ fn create_component(cx: &Ident, node: &NodeElement, mode: Mode) -> TokenStream {
         }
     } else {
         quote_spanned! {
-            span => create_component(#cx, move || {
+            span => create_component(#cx, #debug_name, move || {
                 let #component_name = #component_name(
                     #cx,
                     #component_props_name::builder()
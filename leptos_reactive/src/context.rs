@@ -1,9 +1,13 @@
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
 };
 
-use crate::{runtime::with_runtime, Scope};
+use cfg_if::cfg_if;
+
+use crate::{runtime::with_runtime, Scope, Serializable};
 
 /// Provides a context value of type `T` to the current reactive [Scope](crate::Scope)
 /// and all of its descendants. This can be consumed using [use_context](crate::use_context).
@@ -47,7 +51,13 @@ pub fn provide_context<T>(cx: Scope, value: T)
 where
     T: Clone + 'static,
 {
-    let id = value.type_id();
+    insert_context(cx, ContextId::Type(value.type_id()), value);
+}
+
+fn insert_context<T>(cx: Scope, id: ContextId, value: T)
+where
+    T: Clone + 'static,
+{
     with_runtime(cx.runtime, |runtime| {
         let mut contexts = runtime.scope_contexts.borrow_mut();
         let context = contexts.entry(cx.id).unwrap().or_insert_with(HashMap::new);
@@ -99,7 +109,13 @@ pub fn use_context<T>(cx: Scope) -> Option<T>
 where
     T: Clone + 'static,
 {
-    let id = TypeId::of::<T>();
+    find_context(cx, ContextId::Type(TypeId::of::<T>()))
+}
+
+fn find_context<T>(cx: Scope, id: ContextId) -> Option<T>
+where
+    T: Clone + 'static,
+{
     with_runtime(cx.runtime, |runtime| {
         let local_value = {
             let contexts = runtime.scope_contexts.borrow();
@@ -115,11 +131,206 @@ where
                 .borrow()
                 .get(cx.id)
                 .and_then(|parent| {
-                    use_context::<T>(Scope {
-                        runtime: cx.runtime,
-                        id: *parent,
-                    })
+                    find_context::<T>(
+                        Scope {
+                            runtime: cx.runtime,
+                            id: *parent,
+                        },
+                        id,
+                    )
                 }),
         }
     })
 }
+
+/// Extracts a context value of type `T` like [use_context], or returns `default()` if no
+/// [Scope](crate::Scope) above this one has provided one.
+///
+/// This lets a component work without requiring every app that uses it to provide the
+/// context, e.g., falling back to a default theme. The default is not provided to other
+/// scopes: call [provide_context] with it to share it.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// #[derive(Clone, Debug, Default, PartialEq)]
+/// struct Theme {
+///   dark: bool
+/// }
+///
+/// assert_eq!(use_context_with_default(cx, Theme::default), Theme { dark: false });
+///
+/// provide_context(cx, Theme { dark: true });
+/// assert_eq!(use_context_with_default(cx, Theme::default), Theme { dark: true });
+/// # }).dispose();
+/// ```
+pub fn use_context_with_default<T>(cx: Scope, default: impl FnOnce() -> T) -> T
+where
+    T: Clone + 'static,
+{
+    use_context(cx).unwrap_or_else(default)
+}
+
+/// Provides a context value of type `T` like [provide_context], and sends it from the server to
+/// the browser along with the rendered page, so that data the server already has, like the
+/// session of the user or their preferences, is available while hydrating without fetching it
+/// again with a [Resource](crate::Resource).
+///
+/// On the server, `value` is called, and its result is serialized into the page. In the browser,
+/// while hydrating, the value sent by the server is provided instead, and `value` is only called
+/// if there is none, e.g., when rendering in the browser alone. Values are identified by their
+/// type, so a type should only be provided this way once on a page.
+///
+/// The value is sent along with the application shell, so it should be provided outside of any
+/// `<Suspense/>` that is streamed later, typically at the root of the app.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use serde::{Serialize, Deserialize};
+/// # create_scope(create_runtime(), |cx| {
+/// #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// struct Session {
+///   user: Option<String>
+/// }
+///
+/// provide_serializable_context(cx, || Session { user: Some("Greg".to_string()) });
+///
+/// cx.child_scope(|cx| {
+///   let session = use_context::<Session>(cx).unwrap();
+///   assert_eq!(session.user.as_deref(), Some("Greg"));
+/// });
+/// # }).dispose();
+/// ```
+pub fn provide_serializable_context<T>(cx: Scope, value: impl FnOnce() -> T)
+where
+    T: Serializable + Clone + 'static,
+{
+    let key = std::any::type_name::<T>();
+
+    cfg_if! {
+        if #[cfg(feature = "hydrate")] {
+            let sent = with_runtime(cx.runtime, |runtime| {
+                runtime
+                    .shared_context
+                    .borrow()
+                    .as_ref()
+                    .and_then(|context| context.serialized_contexts.get(key).cloned())
+            });
+            let value = match sent.map(|json| T::from_json(&json)) {
+                Some(Ok(sent)) => sent,
+                Some(Err(e)) => {
+                    crate::report_error(&e, crate::ErrorLocation::Hydration);
+                    value()
+                }
+                None => value(),
+            };
+        } else if #[cfg(feature = "csr")] {
+            _ = key;
+            let value = value();
+        } else {
+            let value = value();
+            match value.to_json() {
+                Ok(json) => with_runtime(cx.runtime, |runtime| {
+                    runtime
+                        .shared_context
+                        .borrow_mut()
+                        .get_or_insert_with(Default::default)
+                        .serialized_contexts
+                        .insert(key.to_string(), json);
+                }),
+                Err(e) => crate::report_error(&e, crate::ErrorLocation::Render),
+            }
+        }
+    }
+
+    provide_context(cx, value);
+}
+
+/// A key that identifies a context value of type `T`, for providing several contexts of the
+/// same type without wrapping each of them in its own type.
+///
+/// Keys are compared by their name and type, so two keys with the same name and type
+/// refer to the same context. They are typically declared as constants, and used with
+/// [provide_keyed_context] and [use_keyed_context].
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// const HEADER_THEME: ContextKey<String> = ContextKey::new("header_theme");
+/// const FOOTER_THEME: ContextKey<String> = ContextKey::new("footer_theme");
+///
+/// provide_keyed_context(cx, HEADER_THEME, "dark".to_string());
+/// provide_keyed_context(cx, FOOTER_THEME, "light".to_string());
+///
+/// cx.child_scope(|cx| {
+///   assert_eq!(use_keyed_context(cx, HEADER_THEME).as_deref(), Some("dark"));
+///   assert_eq!(use_keyed_context(cx, FOOTER_THEME).as_deref(), Some("light"));
+///   // keyed contexts are separate from the one for the type itself
+///   assert_eq!(use_context::<String>(cx), None);
+/// });
+/// # }).dispose();
+/// ```
+pub struct ContextKey<T> {
+    name: &'static str,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T> ContextKey<T> {
+    /// Creates a key with the given name.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ty: PhantomData,
+        }
+    }
+
+    /// The name the key was created with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for ContextKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ContextKey<T> {}
+
+impl<T> Debug for ContextKey<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ContextKey")
+            .field(&self.name)
+            .field(&std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+/// Provides a context value to the current reactive [Scope](crate::Scope) and all of its
+/// descendants under the given [ContextKey], like [provide_context]. It can be consumed
+/// using [use_keyed_context].
+pub fn provide_keyed_context<T>(cx: Scope, key: ContextKey<T>, value: T)
+where
+    T: Clone + 'static,
+{
+    insert_context(cx, ContextId::Key(TypeId::of::<T>(), key.name), value);
+}
+
+/// Extracts the context value that was provided under the given [ContextKey] with
+/// [provide_keyed_context], by traversing the reactive system upwards like [use_context].
+pub fn use_keyed_context<T>(cx: Scope, key: ContextKey<T>) -> Option<T>
+where
+    T: Clone + 'static,
+{
+    find_context(cx, ContextId::Key(TypeId::of::<T>(), key.name))
+}
+
+/// Identifies a context value within a [Scope](crate::Scope).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ContextId {
+    /// Provided with [provide_context], identified by its type.
+    Type(TypeId),
+    /// Provided with [provide_keyed_context], identified by its type and the key's name.
+    Key(TypeId, &'static str),
+}
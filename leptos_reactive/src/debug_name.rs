@@ -0,0 +1,38 @@
+use std::{fmt, panic::Location};
+
+/// A name given to a signal, memo, or effect to make it easier to identify while debugging,
+/// along with the place in the source code where it was created.
+///
+/// Names are given with [create_signal_named](crate::create_signal_named),
+/// [create_memo_named](crate::create_memo_named), and similar functions, and are included
+/// in warnings and panic messages, e.g., when a signal is read after it has been disposed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugName {
+    /// The name given when the node was created.
+    pub name: &'static str,
+    /// Where the node was created.
+    pub location: &'static Location<'static>,
+}
+
+impl DebugName {
+    #[track_caller]
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            location: Location::caller(),
+        }
+    }
+}
+
+/// Formats a name to follow a type in a message, e.g., "Signal<i32> `count` (created at ...)",
+/// or nothing if there isn't one.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+pub(crate) fn name_suffix(name: Option<DebugName>) -> String {
+    name.map(|name| format!(" {name}")).unwrap_or_default()
+}
+
+impl fmt::Display for DebugName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` (created at {})", self.name, self.location)
+    }
+}
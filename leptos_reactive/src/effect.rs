@@ -1,8 +1,9 @@
 use crate::runtime::{with_runtime, RuntimeId};
-use crate::{debug_warn, Runtime, Scope, ScopeProperty};
+use crate::{debug_warn, on_cleanup, DebugName, Runtime, Scope, ScopeProperty};
 use cfg_if::cfg_if;
 use std::cell::RefCell;
 use std::fmt::Debug;
+use std::rc::Rc;
 
 /// Effects run a certain chunk of code whenever the signals they depend on change.
 /// `create_effect` immediately runs the given function once, tracks its dependence
@@ -19,7 +20,8 @@ use std::fmt::Debug;
 ///
 /// By default, effects **do not run on the server**. This means you can call browser-specific
 /// APIs within the effect function without causing issues. If you need an effect to run on
-/// the server, use [create_isomorphic_effect].
+/// the server, use [create_isomorphic_effect], or [create_server_effect] for one that only
+/// runs on the server.
 /// ```
 /// # use leptos_reactive::*;
 /// # use log::*;
@@ -64,6 +66,12 @@ where
 
 /// Creates an effect; unlike effects created by [create_effect], isomorphic effects will run on
 /// the server as well as the client.
+///
+/// Isomorphic effects must not touch browser APIs like `window` or `document`, which don't
+/// exist while rendering on the server, and shouldn't change state that affects what is
+/// rendered, or the HTML rendered on the server won't match what the client hydrates. Use
+/// [create_effect] for effects that need the browser, and [create_server_effect] for effects
+/// that only make sense on the server.
 /// ```
 /// # use leptos_reactive::*;
 /// # use log::*;
@@ -92,10 +100,188 @@ pub fn create_isomorphic_effect<T>(cx: Scope, f: impl Fn(Option<T>) -> T + 'stat
 where
     T: Debug + 'static,
 {
-    let e = cx.runtime.create_effect(f);
+    let e = cx.runtime.create_effect(f, None);
     cx.with_scope_property(|prop| prop.push(ScopeProperty::Effect(e)))
 }
 
+/// Creates an effect that **only runs on the server**, the counterpart of [create_effect],
+/// which only runs in the browser.
+///
+/// This is useful for side effects of rendering that only make sense on the server, like
+/// logging a request or setting a response header, which would otherwise be compiled into
+/// the client and run again during hydration.
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (page, set_page) = create_signal(cx, "/");
+///
+/// create_server_effect(cx, move |_| {
+///   // only logged while rendering on the server
+///   log::info!("rendering {}", page.get());
+/// });
+/// # }).dispose();
+/// ```
+pub fn create_server_effect<T>(cx: Scope, f: impl Fn(Option<T>) -> T + 'static)
+where
+    T: Debug + 'static,
+{
+    cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            create_isomorphic_effect(cx, f);
+        } else {
+            // clear warnings
+            _ = cx;
+            _ = f;
+        }
+    }
+}
+
+/// Creates an effect like [create_effect], whose function can return a cleanup function.
+///
+/// The cleanup function runs before the effect runs again, and when its [Scope] is disposed,
+/// so that anything the effect set up, like an event listener or a subscription, can be torn
+/// down instead of leaking each time the effect re-runs. Return `None` to skip the cleanup.
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::{cell::RefCell, rc::Rc};
+/// # create_scope(create_runtime(), |cx| {
+/// let (topic, set_topic) = create_signal(cx, "news");
+/// let log = Rc::new(RefCell::new(Vec::new()));
+///
+/// create_effect_with_cleanup(cx, {
+///   let log = Rc::clone(&log);
+///   move || {
+///     let topic = topic.get();
+///     log.borrow_mut().push(format!("subscribe {topic}"));
+///     let log = Rc::clone(&log);
+///     Some(move || log.borrow_mut().push(format!("unsubscribe {topic}")))
+///   }
+/// });
+///
+/// set_topic.set("sports");
+/// # if !cfg!(feature = "ssr") {
+/// assert_eq!(
+///   *log.borrow(),
+///   ["subscribe news", "unsubscribe news", "subscribe sports"]
+/// );
+/// # }
+/// # }).dispose();
+/// ```
+pub fn create_effect_with_cleanup<C>(cx: Scope, f: impl Fn() -> Option<C> + 'static)
+where
+    C: FnOnce() + 'static,
+{
+    let cleanup: Rc<RefCell<Option<C>>> = Default::default();
+    let run_cleanup = {
+        let cleanup = Rc::clone(&cleanup);
+        move || {
+            // take it out first, in case the cleanup function causes the effect to run
+            let prev = cleanup.borrow_mut().take();
+            if let Some(prev) = prev {
+                prev();
+            }
+        }
+    };
+
+    on_cleanup(cx, run_cleanup.clone());
+    create_effect(cx, move |_| {
+        run_cleanup();
+        let next = f();
+        *cleanup.borrow_mut() = next;
+    });
+}
+
+/// Creates an effect like [create_effect], giving it a name that is included, along with the
+/// place it was created, in warnings about it.
+#[track_caller]
+pub fn create_effect_named<T>(cx: Scope, name: &'static str, f: impl Fn(Option<T>) -> T + 'static)
+where
+    T: Debug + 'static,
+{
+    cfg_if! {
+        if #[cfg(not(feature = "ssr"))] {
+            create_isomorphic_effect_named(cx, name, f);
+        } else {
+            // clear warnings
+            _ = cx;
+            _ = name;
+            _ = f;
+        }
+    }
+}
+
+/// Creates an isomorphic effect like [create_isomorphic_effect], giving it a name that is
+/// included, along with the place it was created, in warnings about it.
+#[track_caller]
+pub fn create_isomorphic_effect_named<T>(
+    cx: Scope,
+    name: &'static str,
+    f: impl Fn(Option<T>) -> T + 'static,
+) where
+    T: Debug + 'static,
+{
+    let e = cx.runtime.create_effect(f, Some(DebugName::new(name)));
+    cx.with_scope_property(|prop| prop.push(ScopeProperty::Effect(e)))
+}
+
+/// Creates an effect like [create_effect], which runs with the given [EffectPriority] when
+/// the signals it depends on change.
+///
+/// Lower-priority effects, like logging or analytics, run after the render effects that
+/// update the DOM instead of competing with them. The first run, which subscribes the
+/// effect to its dependencies, happens immediately, as with any other effect. Like an
+/// effect created with [create_effect], it doesn't run on the server.
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (page, set_page) = create_signal(cx, "/");
+///
+/// create_effect_with_priority(cx, EffectPriority::Idle, move |_| {
+///   // runs once the browser is idle, after the DOM has been updated
+///   log::debug!("page view: {}", page.get());
+/// });
+///
+/// set_page.set("/about");
+/// # }).dispose();
+/// ```
+pub fn create_effect_with_priority<T>(
+    cx: Scope,
+    priority: EffectPriority,
+    f: impl Fn(Option<T>) -> T + 'static,
+) where
+    T: Debug + 'static,
+{
+    cfg_if! {
+        if #[cfg(not(feature = "ssr"))] {
+            let e = cx.runtime.create_effect(f, None);
+            with_runtime(cx.runtime, |runtime| {
+                runtime.effect_priorities.borrow_mut().insert(e, priority);
+            });
+            cx.with_scope_property(|prop| prop.push(ScopeProperty::Effect(e)))
+        } else {
+            // clear warnings
+            _ = cx;
+            _ = priority;
+            _ = f;
+        }
+    }
+}
+
+/// When an effect created with [create_effect_with_priority] runs after the signals it
+/// depends on change, relative to other effects. As these effects don't run on the server,
+/// their priority only matters in the browser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EffectPriority {
+    /// Runs immediately, like every other effect. This is the default.
+    #[default]
+    Render,
+    /// Runs in a microtask, after the current render effects have run but before the
+    /// browser paints.
+    UserBlocking,
+    /// Runs when the browser is idle, using `requestIdleCallback` where it is available.
+    Idle,
+}
+
 #[doc(hidden)]
 pub fn create_render_effect<T>(cx: Scope, f: impl Fn(Option<T>) -> T + 'static)
 where
@@ -105,8 +291,8 @@ where
 }
 
 slotmap::new_key_type! {
-    /// Unique ID assigned to an [Effect](crate::Effect).
-    pub(crate) struct EffectId;
+    /// Unique ID assigned to an effect.
+    pub struct EffectId;
 }
 
 pub(crate) struct Effect<T, F>
@@ -157,7 +343,10 @@ impl EffectId {
             if let Some(effect) = effect {
                 effect.run(*self, runtime_id);
             } else {
-                debug_warn!("[Effect] Trying to run an Effect that has been disposed. This is probably either a logic error in a component that creates and disposes of scopes, or a Resource resolving after its scope has been dropped without having been cleaned up.")
+                debug_warn!(
+                    "[Effect] Trying to run an Effect{} that has been disposed. This is probably either a logic error in a component that creates and disposes of scopes, or a Resource resolving after its scope has been dropped without having been cleaned up.",
+                    crate::debug_name::name_suffix(runtime.effect_name(*self))
+                )
             }
         })
     }
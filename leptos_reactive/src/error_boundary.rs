@@ -0,0 +1,228 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    panic,
+    rc::Rc,
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    create_rw_signal, runtime::with_runtime, EffectId, ReadSignal, RwSignal, Scope,
+    UntrackedGettableSignal,
+};
+
+/// A callback that is notified of each error caught by an [ErrorBoundaryContext].
+pub type ErrorHook = Rc<dyn Fn(&Rc<dyn Error>)>;
+
+/// The errors that have been rendered under an
+/// [`ErrorBoundary`](https://docs.rs/leptos_core/latest/leptos_core/fn.ErrorBoundary.html).
+#[derive(Clone, Default)]
+pub struct Errors(Vec<(Option<EffectId>, Rc<dyn Error>)>);
+
+impl Errors {
+    /// Whether no errors have been rendered.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The number of errors that have been rendered.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterates over the errors, in the order in which they were rendered.
+    pub fn iter(&self) -> impl Iterator<Item = &Rc<dyn Error>> {
+        self.0.iter().map(|(_, error)| error)
+    }
+}
+
+impl Debug for Errors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(_, error)| error.to_string()))
+            .finish()
+    }
+}
+
+/// Collects the errors that are rendered under an error boundary, i.e., within an
+/// [`ErrorBoundary`](https://docs.rs/leptos_core/latest/leptos_core/fn.ErrorBoundary.html)
+/// component, so that it can show a fallback instead of its children.
+///
+/// Each error is tracked along with the effect that rendered it, so that it is removed again
+/// once that effect renders a value instead.
+#[derive(Clone)]
+pub struct ErrorBoundaryContext {
+    cx: Scope,
+    errors: RwSignal<Errors>,
+    on_error: Option<ErrorHook>,
+}
+
+impl ErrorBoundaryContext {
+    /// Creates an empty error boundary context, which passes each error it catches to
+    /// `on_error`, e.g., to report it to an error tracking service.
+    pub fn new(cx: Scope, on_error: Option<ErrorHook>) -> Self {
+        Self {
+            cx,
+            errors: create_rw_signal(cx, Errors::default()),
+            on_error,
+        }
+    }
+
+    /// The errors that are currently caught by this boundary.
+    pub fn errors(&self) -> ReadSignal<Errors> {
+        self.errors.read_only()
+    }
+
+    /// Adds an error rendered by the running effect, replacing any error it rendered before.
+    pub fn throw(&self, error: Rc<dyn Error>) {
+        let key = self.observer();
+        if let Some(on_error) = &self.on_error {
+            on_error(&error);
+        }
+        self.errors.update(|errors| {
+            if key.is_some() {
+                errors.0.retain(|(id, _)| *id != key);
+            }
+            errors.0.push((key, error));
+        });
+    }
+
+    /// Removes the error rendered by the running effect, if there is one.
+    pub fn resolve(&self) {
+        let key = self.observer();
+        if key.is_some()
+            && self
+                .errors
+                .with_untracked(|errors| errors.0.iter().any(|(id, _)| *id == key))
+        {
+            self.errors
+                .update(|errors| errors.0.retain(|(id, _)| *id != key));
+        }
+    }
+
+    /// Runs `f`, which renders part of the children of the boundary, and catches a panic in it
+    /// as a [RenderPanic] error instead of letting it unwind past the boundary. Returns
+    /// [Option::None] if `f` panicked.
+    ///
+    /// Panics can only be caught if the target unwinds on panic; `wasm32` targets abort by
+    /// default. There, the panic is still passed to the `on_error` callback as a
+    /// [RenderPanic] before the instance aborts, so that it can be reported.
+    pub fn catch_panics<T>(&self, f: impl FnOnce() -> T) -> Option<T> {
+        cfg_if! {
+            if #[cfg(panic = "unwind")] {
+                let observer = self.observer();
+                match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+                    Ok(value) => Some(value),
+                    Err(payload) => {
+                        // the panicking effect didn't get to restore the previous observer
+                        with_runtime(self.cx.runtime, |runtime| runtime.observer.set(observer));
+                        self.throw(Rc::new(RenderPanic {
+                            message: panic_message(&*payload),
+                            aborted: false,
+                        }));
+                        None
+                    }
+                }
+            } else {
+                install_abort_hook();
+                RENDERING.with(|rendering| rendering.borrow_mut().push(self.clone()));
+                let value = f();
+                RENDERING.with(|rendering| rendering.borrow_mut().pop());
+                Some(value)
+            }
+        }
+    }
+
+    /// Removes all the errors, e.g., before the children of the boundary are rendered again.
+    pub fn reset(&self) {
+        if !self.errors.with_untracked(Errors::is_empty) {
+            self.errors.update(|errors| errors.0.clear());
+        }
+    }
+
+    fn observer(&self) -> Option<EffectId> {
+        with_runtime(self.cx.runtime, |runtime| runtime.observer.get())
+    }
+}
+
+impl Debug for ErrorBoundaryContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorBoundaryContext")
+            .field("errors", &self.errors)
+            .field("on_error", &self.on_error.is_some())
+            .finish()
+    }
+}
+
+/// An error caught by [ErrorBoundaryContext::catch_panics] when rendering panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderPanic {
+    /// The message the panic was raised with.
+    pub message: String,
+    /// Whether the panic couldn't be caught, as the target aborts on panic, so that rendering
+    /// was aborted instead.
+    pub aborted: bool,
+}
+
+impl Display for RenderPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.aborted {
+            write!(f, "render aborted: {}", self.message)
+        } else {
+            write!(f, "render panicked: {}", self.message)
+        }
+    }
+}
+
+impl Error for RenderPanic {}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+cfg_if! {
+    if #[cfg(not(panic = "unwind"))] {
+        use std::cell::{Cell, RefCell};
+
+        thread_local! {
+            /// The boundaries whose children are being rendered, innermost last.
+            static RENDERING: RefCell<Vec<ErrorBoundaryContext>> = RefCell::new(Vec::new());
+            static ABORT_HOOK_INSTALLED: Cell<bool> = Cell::new(false);
+        }
+
+        /// Installs a panic hook that reports a panic to the innermost boundary that is
+        /// rendering, if any, before the previous hook runs and the instance aborts.
+        fn install_abort_hook() {
+            if ABORT_HOOK_INSTALLED.with(|installed| installed.replace(true)) {
+                return;
+            }
+            let prev_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let boundary = RENDERING
+                    .try_with(|rendering| {
+                        rendering
+                            .try_borrow()
+                            .ok()
+                            .and_then(|rendering| rendering.last().cloned())
+                    })
+                    .ok()
+                    .flatten();
+                if let Some(on_error) = boundary.and_then(|boundary| boundary.on_error) {
+                    let error: Rc<dyn Error> = Rc::new(RenderPanic {
+                        message: panic_message(info.payload()),
+                        aborted: true,
+                    });
+                    on_error(&error);
+                }
+                prev_hook(info);
+            }));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+type ErrorHookFn = Arc<dyn Fn(&dyn Error, &ErrorLocation) + Send + Sync>;
+
+static ERROR_HOOK: RwLock<Option<ErrorHookFn>> = RwLock::new(None);
+
+/// Where an error that was passed to the hook set with [set_error_hook] came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorLocation {
+    /// An error that was rendered outside of any
+    /// [`ErrorBoundary`](https://docs.rs/leptos_core/latest/leptos_core/fn.ErrorBoundary.html).
+    Render,
+    /// A call to the server function at the given path that failed.
+    ServerFn(String),
+    /// A failure to hydrate what was rendered on the server, e.g., a resource whose value
+    /// couldn't be deserialized.
+    Hydration,
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorLocation::Render => write!(f, "render"),
+            ErrorLocation::ServerFn(path) => write!(f, "server function {path}"),
+            ErrorLocation::Hydration => write!(f, "hydration"),
+        }
+    }
+}
+
+/// Sets a hook that is called with every error that isn't handled by the app itself: errors
+/// rendered outside of all error boundaries, failed server function calls, and hydration
+/// errors. This gives an app a single place to report errors to a telemetry service.
+///
+/// The hook replaces any hook that was set before, and is shared by all threads and reactive
+/// runtimes, so that it only has to be set once on the server as well.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// set_error_hook(|error, location| {
+///     eprintln!("unhandled error in {location}: {error}");
+/// });
+/// ```
+pub fn set_error_hook(hook: impl Fn(&dyn Error, &ErrorLocation) + Send + Sync + 'static) {
+    *ERROR_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Removes the hook set with [set_error_hook], if there is one.
+pub fn clear_error_hook() {
+    *ERROR_HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Passes an unhandled error to the hook set with [set_error_hook], if there is one. This is
+/// called by the framework, but can be used to report errors that the app doesn't handle
+/// itself as well.
+pub fn report_error(error: &dyn Error, location: ErrorLocation) {
+    // the hook is called without holding the lock, so that it can report errors itself
+    let hook = ERROR_HOOK.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(hook) = hook {
+        hook(error, &location);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{runtime::with_runtime, DebugName, EffectId, Scope, ScopeId, ScopeProperty, SignalId};
+
+/// Returns a snapshot of every live signal, memo, and effect in the runtime `cx` belongs to,
+/// and of which of them depend on each other.
+///
+/// This is meant for devtools and for tracking down leaks: a node that is still alive but has
+/// no [GraphNode::owner] will never be disposed. Only available with the `debug-graph` feature,
+/// which records the extra information needed to build the graph.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (count, set_count) = create_signal(cx, 0);
+/// let double = create_memo(cx, move |_| count.get() * 2);
+/// create_isomorphic_effect(cx, move |_| {
+///     _ = double.get();
+/// });
+///
+/// let graph = reactive_graph(cx);
+/// let count_node = graph
+///     .nodes
+///     .iter()
+///     .find(|node| node.kind == NodeKind::Signal)
+///     .unwrap();
+/// assert_eq!(count_node.type_name, "i32");
+///
+/// // `count` notifies `double`, which notifies the effect
+/// let memo = &graph.dependents(count_node.id)[0];
+/// assert_eq!(graph.node(*memo).unwrap().kind, NodeKind::Memo);
+/// assert_eq!(graph.node(graph.dependents(*memo)[0]).unwrap().kind, NodeKind::Effect);
+/// # }).dispose();
+/// ```
+pub fn reactive_graph(cx: Scope) -> ReactiveGraph {
+    with_runtime(cx.runtime, |runtime| {
+        let mut owners = HashMap::new();
+        for (scope_id, properties) in runtime.scopes.borrow().iter() {
+            for property in properties.borrow().iter() {
+                let node = match property {
+                    ScopeProperty::Signal(id) => NodeId::Signal(*id),
+                    ScopeProperty::Effect(id) => NodeId::Effect(*id),
+                    ScopeProperty::Resource(_) => continue,
+                };
+                owners.insert(node, scope_id);
+            }
+        }
+
+        let memos = runtime.memos.borrow();
+        let memo_signals = memos.values().copied().collect::<HashSet<_>>();
+        // a memo's effect and signal are shown as a single node, named after the signal
+        let node_for_effect = |id: EffectId| match memos.get(id) {
+            Some(signal) => NodeId::Signal(*signal),
+            None => NodeId::Effect(id),
+        };
+
+        let signal_types = runtime.signal_types.borrow();
+        let mut nodes = runtime
+            .signals
+            .borrow()
+            .keys()
+            .map(|id| {
+                let node = NodeId::Signal(id);
+                GraphNode {
+                    id: node,
+                    kind: if memo_signals.contains(&id) {
+                        NodeKind::Memo
+                    } else {
+                        NodeKind::Signal
+                    },
+                    type_name: signal_types.get(id).copied().unwrap_or("unknown"),
+                    name: runtime.signal_name(id),
+                    owner: owners.get(&node).copied(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let effect_types = runtime.effect_types.borrow();
+        let effects = runtime.effects.borrow();
+        nodes.extend(
+            effects
+                .keys()
+                .filter(|id| !memos.contains_key(*id))
+                .map(|id| {
+                    let node = NodeId::Effect(id);
+                    GraphNode {
+                        id: node,
+                        kind: NodeKind::Effect,
+                        type_name: effect_types.get(id).copied().unwrap_or("unknown"),
+                        name: runtime.effect_name(id),
+                        owner: owners.get(&node).copied(),
+                    }
+                }),
+        );
+
+        let signals = runtime.signals.borrow();
+        let mut edges = Vec::new();
+        for (signal, subscribers) in runtime.signal_subscribers.borrow().iter() {
+            if !signals.contains_key(signal) {
+                continue;
+            }
+            for effect in subscribers.borrow().iter() {
+                if effects.contains_key(*effect) {
+                    edges.push(GraphEdge {
+                        source: NodeId::Signal(signal),
+                        target: node_for_effect(*effect),
+                    });
+                }
+            }
+        }
+
+        ReactiveGraph { nodes, edges }
+    })
+}
+
+/// A snapshot of the reactive graph, returned by [reactive_graph].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactiveGraph {
+    /// Every live signal, memo, and effect.
+    pub nodes: Vec<GraphNode>,
+    /// Every subscription, from the node that is read to the node that reads it.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ReactiveGraph {
+    /// Returns the node with the given ID, if it is alive.
+    pub fn node(&self, id: NodeId) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the nodes that `id` reads, and will re-run when they change.
+    pub fn dependencies(&self, id: NodeId) -> Vec<NodeId> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.target == id)
+            .map(|edge| edge.source)
+            .collect()
+    }
+
+    /// Returns the nodes that read `id`, and will re-run when it changes.
+    pub fn dependents(&self, id: NodeId) -> Vec<NodeId> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.source == id)
+            .map(|edge| edge.target)
+            .collect()
+    }
+
+    /// Returns the nodes that don't belong to any [Scope], which are never disposed.
+    pub fn unowned(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.iter().filter(|node| node.owner.is_none())
+    }
+}
+
+/// A signal, memo, or effect in a [ReactiveGraph].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// Identifies the node.
+    pub id: NodeId,
+    /// Whether the node is a signal, memo, or effect.
+    pub kind: NodeKind,
+    /// The type of the value the node holds or returns.
+    pub type_name: &'static str,
+    /// The name given to the node with [create_signal_named](crate::create_signal_named)
+    /// or a similar function, if any.
+    pub name: Option<DebugName>,
+    /// The [Scope] the node will be disposed with, if any.
+    pub owner: Option<ScopeId>,
+}
+
+/// A subscription in a [ReactiveGraph]: `target` reads `source`, and re-runs when it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphEdge {
+    /// The node that is read.
+    pub source: NodeId,
+    /// The node that reads it.
+    pub target: NodeId,
+}
+
+/// Identifies a node in a [ReactiveGraph]. Memos are identified by the signal that holds
+/// their value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeId {
+    /// A signal or memo.
+    Signal(SignalId),
+    /// An effect.
+    Effect(EffectId),
+}
+
+/// The kind of a node in a [ReactiveGraph].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// A signal, created with [create_signal](crate::create_signal) or similar.
+    Signal,
+    /// A memo, created with [create_memo](crate::create_memo).
+    Memo,
+    /// An effect, created with [create_effect](crate::create_effect) or similar.
+    Effect,
+}
@@ -0,0 +1,250 @@
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::{
+    create_rw_signal, with_runtime, RwSignal, Scope, UntrackedGettableSignal,
+    UntrackedSettableSignal,
+};
+
+/// Creates a [HistorySignal], a signal that remembers its previous values so that changes can
+/// be undone and redone, as in a text editor or drawing app.
+///
+/// Every write records the value it replaces, keeping at most `capacity` previous values; the
+/// oldest is forgotten once there are more. Writing after an undo discards the values that
+/// could have been redone.
+///
+/// All the writes made inside one [Scope::batch] are recorded as a single change, so they are
+/// undone together. Untracked writes are recorded too, so that undoing always restores the
+/// value that was there before.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let text = create_history_signal(cx, String::new(), 100);
+/// text.set("Hello".to_string());
+/// text.update(|text| text.push_str(", world"));
+/// assert_eq!(text.get(), "Hello, world");
+///
+/// text.undo();
+/// assert_eq!(text.get(), "Hello");
+/// text.undo();
+/// assert_eq!(text.get(), "");
+/// assert!(!text.can_undo());
+///
+/// text.redo();
+/// assert_eq!(text.get(), "Hello");
+///
+/// // both writes are undone together
+/// cx.batch(|| {
+///     text.set("Goodbye".to_string());
+///     text.update(|text| text.push('!'));
+/// });
+/// text.undo();
+/// assert_eq!(text.get(), "Hello");
+/// # }).dispose();
+/// ```
+pub fn create_history_signal<T>(cx: Scope, value: T, capacity: usize) -> HistorySignal<T>
+where
+    T: Clone + 'static,
+{
+    HistorySignal {
+        cx,
+        value: create_rw_signal(cx, value),
+        history: create_rw_signal(
+            cx,
+            History {
+                past: VecDeque::new(),
+                future: Vec::new(),
+                batch: None,
+            },
+        ),
+        capacity,
+    }
+}
+
+/// A signal that records its previous values so that changes can be undone and redone.
+/// Created with [create_history_signal].
+pub struct HistorySignal<T>
+where
+    T: 'static,
+{
+    cx: Scope,
+    value: RwSignal<T>,
+    history: RwSignal<History<T>>,
+    capacity: usize,
+}
+
+struct History<T> {
+    past: VecDeque<T>,
+    future: Vec<T>,
+    /// The batch the latest change was recorded in, if it was made inside one.
+    batch: Option<usize>,
+}
+
+impl<T> HistorySignal<T>
+where
+    T: Clone + 'static,
+{
+    /// Clones and returns the current value, subscribing to changes to it.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Applies a function to the current value, subscribing to changes to it.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.value.with(f)
+    }
+
+    /// Replaces the value, recording the previous one.
+    pub fn set(&self, value: T) {
+        self.record(true);
+        self.value.set(value);
+    }
+
+    /// Mutates the value in place, recording the previous one.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.record(true);
+        self.value.update(f);
+    }
+
+    /// Restores the value before the latest change, if there is one. Returns whether
+    /// anything was undone.
+    pub fn undo(&self) -> bool {
+        let mut prev = None;
+        self.history.update(|history| {
+            prev = history.past.pop_back();
+            if prev.is_some() {
+                history.future.push(self.value.get_untracked());
+                history.batch = None;
+            }
+        });
+        match prev {
+            Some(prev) => {
+                self.value.set(prev);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restores the value that was last undone, if nothing has been written since. Returns
+    /// whether anything was redone.
+    pub fn redo(&self) -> bool {
+        let mut next = None;
+        self.history.update(|history| {
+            next = history.future.pop();
+            if next.is_some() {
+                history.past.push_back(self.value.get_untracked());
+                history.batch = None;
+            }
+        });
+        match next {
+            Some(next) => {
+                self.value.set(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether there is a change to undo, subscribing to changes to the history.
+    pub fn can_undo(&self) -> bool {
+        self.history.with(|history| !history.past.is_empty())
+    }
+
+    /// Returns whether there is a change to redo, subscribing to changes to the history.
+    pub fn can_redo(&self) -> bool {
+        self.history.with(|history| !history.future.is_empty())
+    }
+
+    /// Forgets all the recorded values, keeping the current one.
+    pub fn clear_history(&self) {
+        self.history.update(|history| {
+            history.past.clear();
+            history.future.clear();
+            history.batch = None;
+        });
+    }
+
+    /// Records the current value before it is written, unless it has already been recorded
+    /// for the batch this write is part of. Untracked writes don't notify the subscribers of
+    /// [HistorySignal::can_undo] or [HistorySignal::can_redo] either.
+    fn record(&self, notify: bool) {
+        let batch = with_runtime(self.cx.runtime, |runtime| {
+            runtime.batching.get().then(|| runtime.batch_count.get())
+        });
+        let already_recorded = batch.is_some()
+            && self
+                .history
+                .with_untracked(|history| history.batch == batch);
+        if already_recorded {
+            return;
+        }
+
+        let current = self.value.get_untracked();
+        let capacity = self.capacity;
+        let record = |history: &mut History<T>| {
+            history.batch = batch;
+            history.future.clear();
+            history.past.push_back(current);
+            while history.past.len() > capacity {
+                history.past.pop_front();
+            }
+        };
+        if notify {
+            self.history.update(record);
+        } else {
+            self.history.update_untracked(record);
+        }
+    }
+}
+
+impl<T> UntrackedGettableSignal<T> for HistorySignal<T>
+where
+    T: Clone,
+{
+    fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.get_untracked()
+    }
+
+    fn with_untracked<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        self.value.with_untracked(f)
+    }
+}
+
+impl<T> UntrackedSettableSignal<T> for HistorySignal<T>
+where
+    T: Clone,
+{
+    fn set_untracked(&self, new_value: T) {
+        self.record(false);
+        self.value.set_untracked(new_value);
+    }
+
+    fn update_untracked(&self, f: impl FnOnce(&mut T)) {
+        self.record(false);
+        self.value.update_untracked(f);
+    }
+}
+
+impl<T> Clone for HistorySignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for HistorySignal<T> {}
+
+impl<T> Debug for HistorySignal<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistorySignal")
+            .field("value", &self.value)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
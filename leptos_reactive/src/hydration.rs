@@ -1,9 +1,5 @@
-use crate::ResourceId;
-use std::{
-    collections::{HashMap, HashSet},
-    future::Future,
-    pin::Pin,
-};
+use crate::{PinnedFuture, ResourceId, SuspenseContext};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct SharedContext {
@@ -13,7 +9,13 @@ pub struct SharedContext {
     pub registry: HashMap<String, web_sys::Element>,
     pub pending_resources: HashSet<ResourceId>,
     pub resolved_resources: HashMap<ResourceId, String>,
-    pub pending_fragments: HashMap<String, Pin<Box<dyn Future<Output = String>>>>,
+    pub pending_fragments: HashMap<String, (SuspenseContext, PinnedFuture<String>)>,
+    /// The JSON of each context provided with
+    /// [provide_serializable_context](crate::provide_serializable_context), by type name.
+    pub serialized_contexts: HashMap<String, String>,
+    /// The components being hydrated, innermost last, which mismatches are reported for.
+    #[cfg(all(feature = "hydrate", debug_assertions))]
+    pub components: Vec<&'static str>,
 }
 
 impl std::fmt::Debug for SharedContext {
@@ -30,6 +32,7 @@ impl PartialEq for SharedContext {
             && self.registry == other.registry
             && self.pending_resources == other.pending_resources
             && self.resolved_resources == other.resolved_resources
+            && self.serialized_contexts == other.serialized_contexts
     }
 }
 
@@ -56,6 +59,15 @@ impl SharedContext {
         let resolved_resources =
             serde_wasm_bindgen::from_value(resolved_resources).unwrap_or_default();
 
+        let serialized_contexts = js_sys::Reflect::get(
+            &web_sys::window().unwrap(),
+            &wasm_bindgen::JsValue::from_str("__LEPTOS_CONTEXTS"),
+        )
+        .unwrap_or(wasm_bindgen::JsValue::NULL);
+
+        let serialized_contexts =
+            serde_wasm_bindgen::from_value(serialized_contexts).unwrap_or_default();
+
         Self {
             completed: Default::default(),
             events: Default::default(),
@@ -67,6 +79,23 @@ impl SharedContext {
             pending_resources,
             resolved_resources,
             pending_fragments: Default::default(),
+            serialized_contexts,
+            #[cfg(debug_assertions)]
+            components: Default::default(),
+        }
+    }
+
+    /// Hydrates an island of a page on its own, with none of the resources the server sent,
+    /// since those were created for the whole page rather than for the island.
+    #[cfg(feature = "hydrate")]
+    pub fn new_for_island(registry: HashMap<String, web_sys::Element>) -> Self {
+        Self {
+            context: Some(HydrationContext {
+                id: "".into(),
+                count: -1,
+            }),
+            registry,
+            ..Default::default()
         }
     }
 
@@ -100,6 +129,14 @@ pub struct HydrationContext {
 }
 
 impl HydrationContext {
+    /// A context whose keys start from `{prefix}0`.
+    pub fn with_prefix(prefix: String) -> Self {
+        Self {
+            id: prefix,
+            count: 0,
+        }
+    }
+
     pub fn next_hydration_context(&mut self) -> HydrationContext {
         self.count += 1;
         HydrationContext {
@@ -108,3 +145,199 @@ impl HydrationContext {
         }
     }
 }
+
+/// A difference between the HTML the server rendered and the view the browser hydrates it with,
+/// e.g., an element the server didn't render, or one of another type. The events and reactive
+/// updates of the view may not be wired up to the page where they differ, so in debug builds,
+/// each one is logged as an error while the page is hydrated.
+///
+/// ```
+/// # use leptos_reactive::HydrationMismatch;
+/// let mismatch = HydrationMismatch {
+///     component: Some("Counter"),
+///     key: Some("0-2-0".to_string()),
+///     expected: "<button>".to_string(),
+///     found: Some(r#"<span data-hk="0-2-0">"#.to_string()),
+///     context: Some(r#"<div><span data-hk="0-2-0">1 234</span></div>"#.to_string()),
+/// };
+/// let report = mismatch.to_string();
+/// assert!(report.starts_with(r#"Hydration mismatch in <Counter/> at data-hk="0-2-0""#));
+/// assert!(report.contains("expected: <button>"));
+/// assert!(report.contains(r#"found:    <span data-hk="0-2-0">"#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HydrationMismatch {
+    /// The innermost component being hydrated, if any.
+    pub component: Option<&'static str>,
+    /// The hydration key of the element, if the mismatch is about an element.
+    pub key: Option<String>,
+    /// The node the view expected, e.g., `<button>` or `<!--#-->`.
+    pub expected: String,
+    /// The node that was found instead, if any.
+    pub found: Option<String>,
+    /// The HTML around the node that was found, or else around where it was expected.
+    pub context: Option<String>,
+}
+
+impl std::fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Hydration mismatch")?;
+        if let Some(component) = self.component {
+            write!(f, " in <{component}/>")?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, " at data-hk=\"{key}\"")?;
+        }
+        writeln!(f, ":")?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        writeln!(
+            f,
+            "  found:    {}",
+            self.found.as_deref().unwrap_or("nothing")
+        )?;
+        if let Some(context) = &self.context {
+            writeln!(f, "  around:   {context}")?;
+        }
+        writeln!(
+            f,
+            "\nThe server rendered different HTML than the browser, which usually comes from"
+        )?;
+        writeln!(
+            f,
+            "  - IDs, random numbers or timestamps that differ between the server and the browser,"
+        )?;
+        writeln!(
+            f,
+            "  - formatting that depends on the locale or time zone, like of dates or numbers,"
+        )?;
+        writeln!(
+            f,
+            "  - reading browser-only state, like `window` or local storage, while rendering,"
+        )?;
+        writeln!(
+            f,
+            "  - invalid HTML the browser fixes while parsing, like a <div> in a <p>."
+        )?;
+        write!(
+            f,
+            "Events and reactive updates in this part of the page may not work."
+        )
+    }
+}
+
+#[cfg(all(feature = "hydrate", debug_assertions))]
+impl SharedContext {
+    /// Reports an element that was claimed from the server-rendered HTML, if it's of another
+    /// type than the one in the template, or that is missing from it.
+    pub(crate) fn check_element(
+        &self,
+        key: &str,
+        template: &web_sys::Element,
+        found: Option<&web_sys::Element>,
+    ) {
+        use wasm_bindgen::JsCast;
+
+        let expected = template
+            .unchecked_ref::<web_sys::HtmlTemplateElement>()
+            .content()
+            .first_element_child();
+        let matches = match (&expected, found) {
+            (Some(expected), Some(found)) => {
+                expected.tag_name().eq_ignore_ascii_case(&found.tag_name())
+            }
+            _ => false,
+        };
+        if !matches {
+            let mismatch = HydrationMismatch {
+                component: self.components.last().copied(),
+                key: Some(key.to_string()),
+                expected: expected
+                    .map(|el| describe_node(&el))
+                    .unwrap_or_else(|| "an element".to_string()),
+                found: found.map(|el| describe_node(el)),
+                context: found.and_then(|el| surrounding_html(el)),
+            };
+            log::error!("{mismatch}");
+        }
+    }
+
+    /// Reports a node that should start a component or a block, but doesn't.
+    pub(crate) fn check_marker(&self, start: &web_sys::Node, found_end: bool) {
+        let is_start = start.node_type() == 8 && start.node_value().as_deref() == Some("#");
+        if !is_start || !found_end {
+            let mismatch = HydrationMismatch {
+                component: self.components.last().copied(),
+                key: None,
+                expected: if is_start {
+                    "<!--/--> at the end of a component or block".to_string()
+                } else {
+                    "<!--#--> at the start of a component or block".to_string()
+                },
+                found: Some(describe_node(start)),
+                context: surrounding_html(start),
+            };
+            log::error!("{mismatch}");
+        }
+    }
+}
+
+/// A short description of a node: the opening tag of an element, or the content of a text or
+/// comment node.
+#[cfg(all(feature = "hydrate", debug_assertions))]
+fn describe_node(node: &web_sys::Node) -> String {
+    use wasm_bindgen::JsCast;
+
+    match node.node_type() {
+        1 => {
+            let html = node.unchecked_ref::<web_sys::Element>().outer_html();
+            let end = html.find('>').map(|end| end + 1).unwrap_or(html.len());
+            truncate(&html[..end], 120)
+        }
+        3 => format!(
+            "text {:?}",
+            truncate(&node.node_value().unwrap_or_default(), 120)
+        ),
+        8 => format!("<!--{}-->", node.node_value().unwrap_or_default()),
+        _ => node.node_name(),
+    }
+}
+
+/// The HTML of the parent of a node, cut down to the part around the node.
+#[cfg(all(feature = "hydrate", debug_assertions))]
+fn surrounding_html(node: &web_sys::Node) -> Option<String> {
+    use wasm_bindgen::JsCast;
+
+    const AROUND: usize = 120;
+
+    let parent = node.parent_element()?;
+    let html = parent.outer_html();
+    let own = match node.node_type() {
+        1 => node.unchecked_ref::<web_sys::Element>().outer_html(),
+        8 => format!("<!--{}-->", node.node_value().unwrap_or_default()),
+        _ => node.text_content().unwrap_or_default(),
+    };
+    let chars = html.chars().collect::<Vec<_>>();
+    let at = html
+        .find(&own)
+        .map(|at| html[..at].chars().count())
+        .unwrap_or(0);
+    let start = at.saturating_sub(AROUND);
+    let end = (at + own.chars().count().min(AROUND) + AROUND).min(chars.len());
+    let mut context = chars[start..end].iter().collect::<String>();
+    if start > 0 {
+        context.insert(0, '…');
+    }
+    if end < chars.len() {
+        context.push('…');
+    }
+    Some(context)
+}
+
+#[cfg(all(feature = "hydrate", debug_assertions))]
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
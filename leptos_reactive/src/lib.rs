@@ -66,39 +66,74 @@
 //! ```
 
 mod context;
+mod debug_name;
 mod effect;
+mod error_boundary;
+mod error_hook;
+#[cfg(feature = "debug-graph")]
+mod graph;
+mod history;
 mod hydration;
 mod memo;
+mod paginated;
+mod rate_limit;
 
 mod resource;
+mod resource_cache;
 mod runtime;
 mod scope;
 mod selector;
 mod serialization;
 mod signal;
+mod signal_map;
 mod signal_wrappers_read;
 mod signal_wrappers_write;
+mod slice;
+mod snapshot;
 mod spawn;
+mod stats;
+mod store;
 mod suspense;
+mod timer;
+mod transition;
+mod watch;
 
 pub use context::*;
+pub use debug_name::*;
 pub use effect::*;
+pub use error_boundary::*;
+pub use error_hook::*;
+#[cfg(feature = "debug-graph")]
+pub use graph::*;
+pub use history::*;
+pub use hydration::HydrationMismatch;
 pub use memo::*;
+pub use paginated::*;
+pub use rate_limit::*;
 pub use resource::*;
+pub use resource_cache::*;
 use runtime::*;
 pub use runtime::{create_runtime, RuntimeId};
 pub use scope::*;
 pub use selector::*;
 pub use serialization::*;
 pub use signal::*;
+pub use signal_map::*;
 pub use signal_wrappers_read::*;
 pub use signal_wrappers_write::*;
+pub use slice::*;
+pub use snapshot::*;
 pub use spawn::*;
+pub use stats::*;
+pub use store::*;
 pub use suspense::*;
+pub use timer::*;
+pub use transition::*;
+pub use watch::*;
 
 /// Trait implemented for all signal types which you can `get` a value
 /// from, such as [`ReadSignal`],
-/// [`Memo`], etc., which allows getting the inner value without
+/// [`Memo`], [`Resource`], etc., which allows getting the inner value without
 /// subscribing to the current scope.
 pub trait UntrackedGettableSignal<T> {
     /// Gets the signal's value without creating a dependency on the
@@ -113,7 +148,7 @@ pub trait UntrackedGettableSignal<T> {
 }
 
 /// Trait implemented for all signal types which you can `set` the inner
-/// value, such as [`WriteSignal`] and [`RwSignal`], which allows setting
+/// value, such as [`WriteSignal`], [`RwSignal`], [`Resource`], and [`HistorySignal`], which allows setting
 /// the inner value without causing effects which depend on the signal
 /// from being run.
 pub trait UntrackedSettableSignal<T> {
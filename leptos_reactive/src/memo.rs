@@ -1,4 +1,4 @@
-use crate::{ReadSignal, Scope, SignalError, UntrackedGettableSignal};
+use crate::{DebugName, ReadSignal, Scope, ScopeProperty, SignalError, UntrackedGettableSignal};
 use std::fmt::Debug;
 
 /// Creates an efficient derived reactive value based on other reactive values.
@@ -58,7 +58,28 @@ pub fn create_memo<T>(cx: Scope, f: impl Fn(Option<&T>) -> T + 'static) -> Memo<
 where
     T: PartialEq + Debug + 'static,
 {
-    cx.runtime.create_memo(f)
+    let (memo, effect) = cx.runtime.create_memo(f);
+    cx.with_scope_property(|prop| {
+        prop.push(ScopeProperty::Signal(memo.0.id));
+        prop.push(ScopeProperty::Effect(effect));
+    });
+    memo
+}
+
+/// Creates a memo like [create_memo], giving it a name that is included, along with the
+/// place it was created, in warnings and panic messages about it.
+#[track_caller]
+pub fn create_memo_named<T>(
+    cx: Scope,
+    name: &'static str,
+    f: impl Fn(Option<&T>) -> T + 'static,
+) -> Memo<T>
+where
+    T: PartialEq + Debug + 'static,
+{
+    let memo = create_memo(cx, f);
+    memo.0.id.set_name(cx.runtime, DebugName::new(name));
+    memo
 }
 
 /// An efficient derived reactive value based on other reactive values.
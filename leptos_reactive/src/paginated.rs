@@ -0,0 +1,291 @@
+use std::{fmt::Debug, future::Future};
+
+use crate::{
+    create_local_resource, create_resource, create_rw_signal, ReadSignal, Resource, RwSignal,
+    Scope, Serializable, UntrackedGettableSignal,
+};
+
+/// Creates a [PaginatedResource], which loads one numbered page at a time, starting with
+/// page `0`, and loads another page whenever [PaginatedResource::set_page] or one of the
+/// other navigation methods changes the page.
+///
+/// The resource behaves like any other [Resource]: reading it inside `<Suspense/>` shows the
+/// fallback while a page loads, and its value is streamed from the server.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// async fn fetch_posts(page: usize) -> Vec<String> {
+///   // pretend we're fetching ten posts
+///   (page * 10..page * 10 + 10).map(|n| format!("post {n}")).collect()
+/// }
+///
+/// # // like `create_resource`, this needs a browser or a Tokio runtime to run the Future
+/// # if false {
+/// let posts = create_paginated_resource(cx, fetch_posts);
+/// posts.next_page();
+/// assert_eq!(posts.page(), 1);
+/// # }
+/// # }).dispose();
+/// ```
+pub fn create_paginated_resource<T, Fu>(
+    cx: Scope,
+    fetcher: impl Fn(usize) -> Fu + 'static,
+) -> PaginatedResource<T>
+where
+    T: Debug + Clone + Serializable + 'static,
+    Fu: Future<Output = T> + 'static,
+{
+    let page = create_rw_signal(cx, 0);
+    let resource = create_resource(cx, move || page.get(), fetcher);
+    PaginatedResource { page, resource }
+}
+
+/// A [Resource] that loads one numbered page at a time, created with
+/// [create_paginated_resource].
+#[derive(Debug, PartialEq, Eq)]
+pub struct PaginatedResource<T>
+where
+    T: Debug + 'static,
+{
+    page: RwSignal<usize>,
+    resource: Resource<usize, T>,
+}
+
+impl<T> PaginatedResource<T>
+where
+    T: Debug + Clone + 'static,
+{
+    /// Clones and returns the current page, or [Option::None] while it is loading for the
+    /// first time. Subscribes the running effect to the resource, like [Resource::read].
+    pub fn read(&self) -> Option<T> {
+        self.resource.read()
+    }
+
+    /// Applies a function to the current page, if it has loaded, like [Resource::with].
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.resource.with(f)
+    }
+
+    /// Returns a signal that indicates whether a page is currently loading.
+    pub fn loading(&self) -> ReadSignal<bool> {
+        self.resource.loading()
+    }
+
+    /// Returns the number of the current page, starting from `0`, and subscribes the running
+    /// effect to it.
+    pub fn page(&self) -> usize {
+        self.page.get()
+    }
+
+    /// Loads the page with the given number.
+    pub fn set_page(&self, page: usize) {
+        if self.page.get_untracked() != page {
+            self.page.set(page);
+        }
+    }
+
+    /// Loads the page after the current one.
+    pub fn next_page(&self) {
+        self.page.update(|page| *page += 1);
+    }
+
+    /// Loads the page before the current one, unless the current page is the first one.
+    pub fn prev_page(&self) {
+        self.set_page(self.page.get_untracked().saturating_sub(1));
+    }
+
+    /// Returns the underlying [Resource], which is keyed by the page number.
+    pub fn resource(&self) -> Resource<usize, T> {
+        self.resource
+    }
+}
+
+impl<T> Clone for PaginatedResource<T>
+where
+    T: Debug + Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PaginatedResource<T> where T: Debug + Clone + 'static {}
+
+/// One page loaded by an [InfiniteResource]: its items, and the cursor that loads the
+/// next page, or [Option::None] if it is the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfinitePage<T, C> {
+    /// The items on this page.
+    pub items: Vec<T>,
+    /// The cursor passed to the fetcher to load the next page, if there is one.
+    pub next_cursor: Option<C>,
+}
+
+/// Creates an [InfiniteResource], which accumulates the items of consecutive pages, as in
+/// an infinitely scrolling list.
+///
+/// The `fetcher` is called with [Option::None] to load the first page, and with the
+/// [InfinitePage::next_cursor] of the last loaded page each time
+/// [InfiniteResource::load_more] is called, until a page has no next cursor. Each page's
+/// items are appended to the ones already loaded.
+///
+/// Like a [Resource], reading the items inside `<Suspense/>` shows the fallback while a page
+/// loads; use `<Transition/>` to keep the loaded items visible while more are loaded. As the
+/// items are accumulated in the browser, this is a local resource: it isn't loaded on the
+/// server.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// async fn fetch_feed(cursor: Option<usize>) -> InfinitePage<String, usize> {
+///   // pretend we're fetching five posts at a time, from a feed of twelve
+///   let start = cursor.unwrap_or(0);
+///   let end = (start + 5).min(12);
+///   InfinitePage {
+///     items: (start..end).map(|n| format!("post {n}")).collect(),
+///     next_cursor: (end < 12).then_some(end),
+///   }
+/// }
+///
+/// let feed = create_infinite_resource(cx, fetch_feed);
+/// assert_eq!(feed.with_items(|items| items.len()), 5);
+///
+/// feed.load_more();
+/// feed.load_more();
+/// assert_eq!(feed.with_items(|items| items.len()), 12);
+/// assert!(!feed.has_more());
+/// # }).dispose();
+/// ```
+pub fn create_infinite_resource<T, C, Fu>(
+    cx: Scope,
+    fetcher: impl Fn(Option<C>) -> Fu + 'static,
+) -> InfiniteResource<T, C>
+where
+    T: 'static,
+    C: PartialEq + Debug + Clone + 'static,
+    Fu: Future<Output = InfinitePage<T, C>> + 'static,
+{
+    let items = create_rw_signal(cx, Vec::new());
+    let next_cursor = create_rw_signal(cx, None);
+    let exhausted = create_rw_signal(cx, false);
+    // the generation changes on reset, so that the first page is loaded again even if the
+    // cursor is the same
+    let source = create_rw_signal(cx, (0_usize, None));
+
+    let resource = create_local_resource(
+        cx,
+        move || source.get(),
+        move |(_, cursor): (usize, Option<C>)| {
+            let page = fetcher(cursor);
+            async move {
+                // resources drop outdated loads, so a page that is still loading when the
+                // resource is reset is never appended
+                let page = page.await;
+                items.update(|items| items.extend(page.items));
+                exhausted.set(page.next_cursor.is_none());
+                next_cursor.set(page.next_cursor);
+            }
+        },
+    );
+
+    InfiniteResource {
+        items,
+        next_cursor,
+        exhausted,
+        source,
+        resource,
+    }
+}
+
+/// A resource that accumulates the items of consecutive pages, created with
+/// [create_infinite_resource].
+pub struct InfiniteResource<T, C>
+where
+    T: 'static,
+    C: Debug + 'static,
+{
+    items: RwSignal<Vec<T>>,
+    next_cursor: RwSignal<Option<C>>,
+    exhausted: RwSignal<bool>,
+    source: RwSignal<(usize, Option<C>)>,
+    resource: Resource<(usize, Option<C>), ()>,
+}
+
+impl<T, C> InfiniteResource<T, C>
+where
+    T: 'static,
+    C: PartialEq + Debug + Clone + 'static,
+{
+    /// Applies a function to the items loaded so far, and subscribes the running effect to
+    /// them. Inside `<Suspense/>`, this also registers the resource with it.
+    pub fn with_items<U>(&self, f: impl FnOnce(&[T]) -> U) -> U {
+        _ = self.resource.with(|_| ());
+        self.items.with(|items| f(items))
+    }
+
+    /// Clones and returns the items loaded so far, and subscribes the running effect to them.
+    pub fn items(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.with_items(|items| items.to_vec())
+    }
+
+    /// Returns a signal that indicates whether a page is currently loading.
+    pub fn loading(&self) -> ReadSignal<bool> {
+        self.resource.loading()
+    }
+
+    /// Returns whether there are more pages to load, and subscribes the running effect to it.
+    pub fn has_more(&self) -> bool {
+        !self.exhausted.get()
+    }
+
+    /// Loads the next page and appends its items, unless a page is already loading or the
+    /// last page has been loaded.
+    pub fn load_more(&self) {
+        if self.resource.loading().get_untracked() {
+            return;
+        }
+        if let Some(cursor) = self.next_cursor.get_untracked() {
+            self.source.update(|(_, current)| *current = Some(cursor));
+        }
+    }
+
+    /// Discards the items loaded so far, and loads the first page again.
+    pub fn reset(&self) {
+        self.items.update(Vec::clear);
+        self.next_cursor.set(None);
+        self.exhausted.set(false);
+        self.source.update(|(generation, cursor)| {
+            *generation += 1;
+            *cursor = None;
+        });
+    }
+}
+
+impl<T, C> Clone for InfiniteResource<T, C>
+where
+    C: Debug + Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, C> Copy for InfiniteResource<T, C> where C: Debug + Clone + 'static {}
+
+impl<T, C> Debug for InfiniteResource<T, C>
+where
+    T: Debug,
+    C: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InfiniteResource")
+            .field("items", &self.items)
+            .field("next_cursor", &self.next_cursor)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
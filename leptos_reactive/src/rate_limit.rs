@@ -0,0 +1,167 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use crate::{
+    create_isomorphic_effect, create_signal, on_cleanup, spawn::set_timeout, ReadSignal, Scope,
+    WriteSignal,
+};
+
+/// Creates a signal that follows `source`, but only updates once `source` has stopped
+/// changing for `delay`. Each change restarts the wait, so a burst of changes (like keystrokes
+/// in a search box) produces a single update with the last value.
+///
+/// This is useful for inputs that drive expensive effects or resources, which would otherwise
+/// re-run on every change.
+///
+/// In the browser, the delay is measured with `setTimeout`. On the server, where nothing is
+/// redrawn, the signal follows `source` without any delay.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # create_scope(create_runtime(), |cx| {
+/// let (query, set_query) = create_signal(cx, String::new());
+/// let debounced = signal_debounced(cx, move || query.get(), Duration::from_millis(300));
+///
+/// // fetches only once typing has paused for 300ms
+/// let results = create_resource(cx, move || debounced.get(), |query| async move {
+///     format!("results for {query:?}")
+/// });
+///
+/// set_query.set("leptos".to_string());
+/// assert_eq!(debounced.get(), "leptos");
+/// # }).dispose();
+/// ```
+pub fn signal_debounced<T>(
+    cx: Scope,
+    source: impl Fn() -> T + 'static,
+    delay: Duration,
+) -> ReadSignal<T>
+where
+    T: 'static,
+{
+    let (value, set_value) = create_signal(cx, cx.untrack(&source));
+    // incremented on each change, so that only the latest pending timeout writes its value
+    let generation = Rc::new(Cell::new(0_usize));
+
+    on_cleanup(cx, {
+        let generation = Rc::clone(&generation);
+        move || generation.set(generation.get().wrapping_add(1))
+    });
+
+    create_isomorphic_effect(cx, move |prev: Option<()>| {
+        let next = source();
+        if prev.is_none() {
+            return;
+        }
+        let current = generation.get().wrapping_add(1);
+        generation.set(current);
+        set_timeout(
+            {
+                let generation = Rc::clone(&generation);
+                move || {
+                    if generation.get() == current {
+                        set_value.set(next);
+                    }
+                }
+            },
+            delay,
+        );
+    });
+
+    value
+}
+
+/// Creates a signal that follows `source`, but updates at most once per `interval`.
+///
+/// The first change is passed through immediately. Further changes during the following
+/// `interval` are held back, and the latest of them is passed through when it ends, so the
+/// signal always ends up with the last value of `source`.
+///
+/// This is useful for sources that change continuously, like scroll or pointer positions.
+///
+/// In the browser, the interval is measured with `setTimeout`. On the server, where nothing is
+/// redrawn, the signal follows `source` without any delay.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # create_scope(create_runtime(), |cx| {
+/// let (scroll_y, set_scroll_y) = create_signal(cx, 0);
+/// let throttled = signal_throttled(cx, move || scroll_y.get(), Duration::from_millis(100));
+///
+/// set_scroll_y.set(120);
+/// // the first change is passed through immediately
+/// assert_eq!(throttled.get(), 120);
+/// # }).dispose();
+/// ```
+pub fn signal_throttled<T>(
+    cx: Scope,
+    source: impl Fn() -> T + 'static,
+    interval: Duration,
+) -> ReadSignal<T>
+where
+    T: 'static,
+{
+    let (value, set_value) = create_signal(cx, cx.untrack(&source));
+    let state = Rc::new(ThrottleState {
+        waiting: Cell::new(false),
+        disposed: Cell::new(false),
+        pending: RefCell::new(None),
+    });
+
+    on_cleanup(cx, {
+        let state = Rc::clone(&state);
+        move || state.disposed.set(true)
+    });
+
+    create_isomorphic_effect(cx, move |prev: Option<()>| {
+        let next = source();
+        if prev.is_none() {
+            return;
+        }
+        if state.waiting.get() {
+            *state.pending.borrow_mut() = Some(next);
+        } else {
+            set_value.set(next);
+            start_throttle_interval(Rc::clone(&state), set_value, interval);
+        }
+    });
+
+    value
+}
+
+struct ThrottleState<T> {
+    waiting: Cell<bool>,
+    disposed: Cell<bool>,
+    pending: RefCell<Option<T>>,
+}
+
+/// Holds back changes for `interval`, then passes through the latest one, if any, and starts
+/// another interval for it.
+fn start_throttle_interval<T>(
+    state: Rc<ThrottleState<T>>,
+    set_value: WriteSignal<T>,
+    interval: Duration,
+) where
+    T: 'static,
+{
+    state.waiting.set(true);
+    set_timeout(
+        move || {
+            state.waiting.set(false);
+            if state.disposed.get() {
+                return;
+            }
+            let pending = state.pending.borrow_mut().take();
+            if let Some(next) = pending {
+                set_value.set(next);
+                start_throttle_interval(state, set_value, interval);
+            }
+        },
+        interval,
+    );
+}
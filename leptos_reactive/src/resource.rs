@@ -9,12 +9,16 @@ use std::{
     rc::Rc,
 };
 
+use futures::future::{AbortHandle, Abortable};
+
 use crate::{
-    create_effect, create_isomorphic_effect, create_memo, create_signal, queue_microtask,
+    create_effect, create_isomorphic_effect, create_memo, create_signal, on_cleanup,
+    queue_microtask,
     runtime::{with_runtime, RuntimeId},
-    serialization::Serializable,
+    serialization::{Serializable, SerializationError, Serializer},
     spawn::spawn_local,
-    use_context, Memo, ReadSignal, Scope, ScopeProperty, SuspenseContext, WriteSignal,
+    use_context, Memo, ReadSignal, Scope, ScopeProperty, SuspenseContext, UntrackedGettableSignal,
+    UntrackedSettableSignal, WriteSignal,
 };
 
 /// Creates [Resource](crate::Resource), which is a signal that reflects the
@@ -24,6 +28,9 @@ use crate::{
 /// Takes a `fetcher` function that generates a [Future] when called and a
 /// `source` signal that provides the argument for the `fetcher`. Whenever the
 /// value of the `source` changes, a new [Future] will be created and run.
+/// If the previous [Future] hasn't resolved yet, it is dropped, so responses
+/// can't arrive out of order; while the new one loads, [Resource::is_stale]
+/// returns `true`.
 ///
 /// When server-side rendering is used, the server will handle running the
 /// [Future] and will stream the result to the client. This process requires the
@@ -94,11 +101,103 @@ where
     S: PartialEq + Debug + Clone + 'static,
     T: Debug + Serializable + 'static,
     Fu: Future<Output = T> + 'static,
+{
+    create_encoded_resource(cx, source, fetcher, initial_value, T::to_json, T::from_json)
+}
+
+/// Creates a [Resource](crate::Resource) like [create_resource], whose value is sent from the
+/// server to the browser with the given [Serializer] rather than as [Serializable] JSON, e.g.,
+/// as compact [BincodeSerializer](crate::BincodeSerializer) for a large numeric dataset.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// async fn fetch_samples(sensor: u32) -> Vec<f64> {
+///   vec![sensor as f64; 1024]
+/// }
+///
+/// let (sensor, set_sensor) = create_signal(cx, 1);
+///
+/// # if false {
+/// let samples = create_resource_with_serializer(cx, BincodeSerializer, sensor, fetch_samples);
+/// # }
+/// # }).dispose();
+/// ```
+pub fn create_resource_with_serializer<Ser, S, T, Fu>(
+    cx: Scope,
+    serializer: Ser,
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fu + 'static,
+) -> Resource<S, T>
+where
+    Ser: Serializer,
+    S: PartialEq + Debug + Clone + 'static,
+    T: Debug + serde::Serialize + serde::de::DeserializeOwned + 'static,
+    Fu: Future<Output = T> + 'static,
+{
+    _ = serializer;
+    create_encoded_resource(cx, source, fetcher, None, Ser::encode, Ser::decode)
+}
+
+/// Creates a [Resource](crate::Resource) like [create_resource], with the
+/// [Blocking](ResourcePriority::Blocking) priority: when a page is streamed in order with
+/// `render_to_stream`, its data has resolved before anything is sent, so that whatever depends
+/// on it is in the first HTML the browser receives, e.g., the `<title>` and `og:` meta tags of
+/// an article for search engines and link previews.
+///
+/// The other resources of the page are still streamed as they resolve, in the same response.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// # if false {
+/// # async fn fetch_article(id: u32) -> String { todo!() }
+/// # async fn fetch_comments(id: u32) -> Vec<String> { todo!() }
+/// let (id, _) = create_signal(cx, 1);
+/// // the page isn't sent before the article has loaded
+/// let article = create_blocking_resource(cx, id, fetch_article);
+/// // the comments are streamed once they have loaded
+/// let comments = create_resource(cx, id, fetch_comments);
+/// # }
+/// # }).dispose();
+/// ```
+pub fn create_blocking_resource<S, T, Fu>(
+    cx: Scope,
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fu + 'static,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + 'static,
+    T: Debug + Serializable + 'static,
+    Fu: Future<Output = T> + 'static,
+{
+    create_resource(cx, source, fetcher).with_priority(ResourcePriority::Blocking)
+}
+
+/// Encodes the value of a resource as the text it is sent from the server in.
+type Encode<T> = fn(&T) -> Result<String, SerializationError>;
+
+/// Decodes the value of a resource that was encoded with an [Encode] function.
+type Decode<T> = fn(&str) -> Result<T, SerializationError>;
+
+fn create_encoded_resource<S, T, Fu>(
+    cx: Scope,
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fu + 'static,
+    initial_value: Option<T>,
+    encode: Encode<T>,
+    decode: Decode<T>,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + 'static,
+    T: Debug + 'static,
+    Fu: Future<Output = T> + 'static,
 {
     let resolved = initial_value.is_some();
     let (value, set_value) = create_signal(cx, initial_value);
 
     let (loading, set_loading) = create_signal(cx, false);
+    let (stale, set_stale) = create_signal(cx, false);
 
     let fetcher = Rc::new(move |s| Box::pin(fetcher(s)) as Pin<Box<dyn Future<Output = T>>>);
     let source = create_memo(cx, move |_| source());
@@ -109,21 +208,32 @@ where
         set_value,
         loading,
         set_loading,
+        stale,
+        set_stale,
         source,
         fetcher,
         resolved: Rc::new(Cell::new(resolved)),
         scheduled: Rc::new(Cell::new(false)),
+        in_flight: Default::default(),
+        priority: Default::default(),
         suspense_contexts: Default::default(),
     });
+    on_cleanup(cx, {
+        let r = Rc::clone(&r);
+        move || r.abort_in_flight()
+    });
 
     let id = with_runtime(cx.runtime, |runtime| {
-        runtime.create_serializable_resource(Rc::clone(&r))
+        runtime.create_serializable_resource(Rc::new(EncodedResource {
+            state: Rc::clone(&r),
+            encode,
+        }))
     });
 
     create_isomorphic_effect(cx, {
         let r = Rc::clone(&r);
         move |_| {
-            load_resource(cx, id, r.clone());
+            load_resource(cx, id, r.clone(), decode);
         }
     });
 
@@ -203,6 +313,7 @@ where
     let (value, set_value) = create_signal(cx, initial_value);
 
     let (loading, set_loading) = create_signal(cx, false);
+    let (stale, set_stale) = create_signal(cx, false);
 
     let fetcher = Rc::new(move |s| Box::pin(fetcher(s)) as Pin<Box<dyn Future<Output = T>>>);
     let source = create_memo(cx, move |_| source());
@@ -213,12 +324,20 @@ where
         set_value,
         loading,
         set_loading,
+        stale,
+        set_stale,
         source,
         fetcher,
         resolved: Rc::new(Cell::new(resolved)),
         scheduled: Rc::new(Cell::new(false)),
+        in_flight: Default::default(),
+        priority: Default::default(),
         suspense_contexts: Default::default(),
     });
+    on_cleanup(cx, {
+        let r = Rc::clone(&r);
+        move || r.abort_in_flight()
+    });
 
     let id = with_runtime(cx.runtime, |runtime| {
         runtime.create_unserializable_resource(Rc::clone(&r))
@@ -242,7 +361,7 @@ where
 }
 
 #[cfg(not(feature = "hydrate"))]
-fn load_resource<S, T>(_cx: Scope, _id: ResourceId, r: Rc<ResourceState<S, T>>)
+fn load_resource<S, T>(_cx: Scope, _id: ResourceId, r: Rc<ResourceState<S, T>>, _decode: Decode<T>)
 where
     S: PartialEq + Debug + Clone + 'static,
     T: Debug + 'static,
@@ -251,11 +370,12 @@ where
 }
 
 #[cfg(feature = "hydrate")]
-fn load_resource<S, T>(cx: Scope, id: ResourceId, r: Rc<ResourceState<S, T>>)
+fn load_resource<S, T>(cx: Scope, id: ResourceId, r: Rc<ResourceState<S, T>>, decode: Decode<T>)
 where
     S: PartialEq + Debug + Clone + 'static,
-    T: Debug + Serializable + 'static,
+    T: Debug + 'static,
 {
+    use crate::{report_error, ErrorLocation};
     use wasm_bindgen::{JsCast, UnwrapThrowExt};
 
     with_runtime(cx.runtime, |runtime| {
@@ -266,7 +386,9 @@ where
                 context.pending_resources.remove(&id); // no longer pending
                 r.resolved.set(true);
 
-                let res = T::from_json(&data).expect_throw("could not deserialize Resource JSON");
+                let res = decode(&data)
+                    .map_err(|e| report_error(&e, ErrorLocation::Hydration))
+                    .expect_throw("could not deserialize Resource JSON");
                 r.set_value.update(|n| *n = Some(res));
                 r.set_loading.update(|n| *n = false);
 
@@ -283,8 +405,9 @@ where
                     let set_value = r.set_value;
                     let set_loading = r.set_loading;
                     move |res: String| {
-                        let res =
-                            T::from_json(&res).expect_throw("could not deserialize Resource JSON");
+                        let res = decode(&res)
+                            .map_err(|e| report_error(&e, ErrorLocation::Hydration))
+                            .expect_throw("could not deserialize Resource JSON");
                         resolved.set(true);
                         set_value.update(|n| *n = Some(res));
                         set_loading.update(|n| *n = false);
@@ -317,6 +440,57 @@ where
     })
 }
 
+impl<S, T> UntrackedGettableSignal<Option<T>> for Resource<S, T>
+where
+    S: Debug + Clone + 'static,
+    T: Debug + 'static,
+{
+    /// Clones and returns the current value of the resource, without subscribing the
+    /// running effect or registering the read with any `<Suspense/>`.
+    fn get_untracked(&self) -> Option<T>
+    where
+        Option<T>: Clone,
+    {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.value.get_untracked()
+            })
+        })
+    }
+
+    fn with_untracked<O>(&self, f: impl FnOnce(&Option<T>) -> O) -> O {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.value.with_untracked(f)
+            })
+        })
+    }
+}
+
+impl<S, T> UntrackedSettableSignal<Option<T>> for Resource<S, T>
+where
+    S: Debug + Clone + 'static,
+    T: Debug + 'static,
+{
+    /// Replaces the current value of the resource, e.g. for an optimistic update,
+    /// without notifying anything that has read it.
+    fn set_untracked(&self, new_value: Option<T>) {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.set_value.set_untracked(new_value)
+            })
+        })
+    }
+
+    fn update_untracked(&self, f: impl FnOnce(&mut Option<T>)) {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.set_value.update_untracked(f)
+            })
+        })
+    }
+}
+
 impl<S, T> Resource<S, T>
 where
     S: Debug + Clone + 'static,
@@ -357,6 +531,54 @@ where
         })
     }
 
+    /// Returns whether the resource is showing a value loaded for a previous source, while
+    /// the value for the current one is loading, and subscribes the running effect to it.
+    ///
+    /// This is useful to dim or mark outdated results, e.g., while the results for a new
+    /// search query are loading, instead of replacing them with a loading indicator.
+    pub fn is_stale(&self) -> bool {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.stale.get()
+            })
+        })
+    }
+
+    /// Sets the [ResourcePriority] of the resource, which determines when the HTML of the
+    /// `<Suspense/>` it is read under is sent while streaming a server-rendered response.
+    ///
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(create_runtime(), |cx| {
+    /// # if false {
+    /// # async fn fetch_article(id: u32) -> String { todo!() }
+    /// # async fn fetch_comments(id: u32) -> Vec<String> { todo!() }
+    /// let (id, _) = create_signal(cx, 1);
+    /// // the article is sent along with the rest of the page
+    /// let article = create_resource(cx, id, fetch_article).with_priority(ResourcePriority::Blocking);
+    /// // the comments below it are sent once everything else has been sent
+    /// let comments = create_resource(cx, id, fetch_comments).with_priority(ResourcePriority::Lazy);
+    /// # }
+    /// # }).dispose();
+    /// ```
+    pub fn with_priority(self, priority: ResourcePriority) -> Self {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.priority.set(priority)
+            })
+        });
+        self
+    }
+
+    /// Returns the [ResourcePriority] of the resource.
+    pub fn priority(&self) -> ResourcePriority {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                resource.priority.get()
+            })
+        })
+    }
+
     /// Re-runs the async function with the current source data.
     pub fn refetch(&self) {
         with_runtime(self.runtime, |runtime| {
@@ -364,6 +586,21 @@ where
         });
     }
 
+    /// Replaces the value with one that was loaded for `source`, unless the source has
+    /// changed since, notifying anything that has read it.
+    pub(crate) fn set_if_source_is(&self, source: &S, value: T)
+    where
+        S: PartialEq,
+    {
+        with_runtime(self.runtime, |runtime| {
+            runtime.resource(self.id, |resource: &ResourceState<S, T>| {
+                if resource.source.with_untracked(|current| current == source) {
+                    resource.set_value.set(Some(value));
+                }
+            })
+        })
+    }
+
     /// Returns a [std::future::Future] that will resolve when the resource has loaded,
     /// yield its [ResourceId] and a JSON string.
     #[cfg(any(feature = "ssr", doc))]
@@ -380,6 +617,24 @@ where
     }
 }
 
+/// When the HTML of a `<Suspense/>` that reads a [Resource] is sent while streaming a
+/// server-rendered response with `render_to_stream`. A `<Suspense/>` takes the highest
+/// priority of the resources read under it, and a `<Suspense/>` nested in another one is
+/// never sent before it.
+///
+/// Priorities are ordered from lowest to highest, and only affect server rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ResourcePriority {
+    /// Sent only after all the other `<Suspense/>` fragments, e.g., for panels below the fold.
+    Lazy,
+    /// Sent as soon as its resources resolve. This is the default.
+    #[default]
+    Deferred,
+    /// Sent along with the initial HTML, which waits for its resources to resolve, e.g.,
+    /// for the main content of a page. See [create_blocking_resource].
+    Blocking,
+}
+
 /// A signal that reflects the
 /// current state of an asynchronous task, allowing you to integrate `async`
 /// [Future]s into the synchronous reactive system.
@@ -509,11 +764,16 @@ where
     set_value: WriteSignal<Option<T>>,
     pub loading: ReadSignal<bool>,
     set_loading: WriteSignal<bool>,
+    stale: ReadSignal<bool>,
+    set_stale: WriteSignal<bool>,
     source: Memo<S>,
     #[allow(clippy::type_complexity)]
     fetcher: Rc<dyn Fn(S) -> Pin<Box<dyn Future<Output = T>>>>,
     resolved: Rc<Cell<bool>>,
     scheduled: Rc<Cell<bool>>,
+    /// Aborts the [Future] that is currently loading, if any.
+    in_flight: Rc<RefCell<Option<AbortHandle>>>,
+    priority: Rc<Cell<ResourcePriority>>,
     suspense_contexts: Rc<RefCell<HashSet<SuspenseContext>>>,
 }
 
@@ -538,6 +798,10 @@ where
             .ok()?
             .flatten();
 
+        if let Some(s) = &suspense_cx {
+            s.raise_priority(self.priority.get());
+        }
+
         let suspense_contexts = self.suspense_contexts.clone();
         let has_value = v.is_some();
 
@@ -585,7 +849,16 @@ where
                 }
             });
 
+            // the previous Future is dropped rather than left to resolve after this one,
+            // which would overwrite the newer value with an outdated one
+            self.abort_in_flight();
+            let (abort_handle, abort_registration) = AbortHandle::new_pair();
+            *self.in_flight.borrow_mut() = Some(abort_handle);
+
             self.set_loading.update(|n| *n = true);
+            if self.resolved.get() {
+                self.set_stale.update(|n| *n = true);
+            }
 
             // increment counter everywhere it's read
             let suspense_contexts = self.suspense_contexts.clone();
@@ -594,34 +867,53 @@ where
                 suspense_context.increment();
             }
 
+            // and in any transition that is being started, for this load only
+            let transitions = with_runtime(self.scope.runtime, |runtime| {
+                runtime.transitions.borrow().clone()
+            });
+            for transition in &transitions {
+                transition.increment();
+            }
+
             // run the Future
             spawn_local({
                 let resolved = self.resolved.clone();
                 let set_value = self.set_value;
                 let set_loading = self.set_loading;
+                let set_stale = self.set_stale;
                 async move {
-                    let res = fut.await;
+                    let res = Abortable::new(fut, abort_registration).await;
 
-                    resolved.set(true);
+                    if let Ok(res) = res {
+                        resolved.set(true);
 
-                    set_value.update(|n| *n = Some(res));
-                    set_loading.update(|n| *n = false);
+                        set_value.update(|n| *n = Some(res));
+                        set_loading.update(|n| *n = false);
+                        set_stale.update(|n| *n = false);
+                    }
 
                     for suspense_context in suspense_contexts.borrow().iter() {
                         suspense_context.decrement();
                     }
+                    for transition in &transitions {
+                        transition.decrement();
+                    }
                 }
             })
         });
     }
 
+    fn abort_in_flight(&self) {
+        if let Some(abort_handle) = self.in_flight.borrow_mut().take() {
+            abort_handle.abort();
+        }
+    }
+
     pub fn resource_to_serialization_resolver(
         &self,
         id: ResourceId,
-    ) -> std::pin::Pin<Box<dyn futures::Future<Output = (ResourceId, String)>>>
-    where
-        T: Serializable,
-    {
+        encode: Encode<T>,
+    ) -> std::pin::Pin<Box<dyn futures::Future<Output = (ResourceId, String)>>> {
         use futures::StreamExt;
 
         let (tx, mut rx) = futures::channel::mpsc::channel(1);
@@ -633,7 +925,7 @@ where
                     let mut tx = tx.clone();
                     move |value| {
                         if let Some(value) = value.as_ref() {
-                            tx.try_send((id, value.to_json().expect("could not serialize Resource")))
+                            tx.try_send((id, encode(value).expect("could not serialize Resource")))
                                 .expect("failed while trying to write to Resource serializer");
                         }
                     }
@@ -658,6 +950,8 @@ pub(crate) trait SerializableResource {
         &self,
         id: ResourceId,
     ) -> Pin<Box<dyn Future<Output = (ResourceId, String)>>>;
+
+    fn priority(&self) -> ResourcePriority;
 }
 
 impl<S, T> SerializableResource for ResourceState<S, T>
@@ -673,9 +967,47 @@ where
         &self,
         id: ResourceId,
     ) -> Pin<Box<dyn Future<Output = (ResourceId, String)>>> {
-        let fut = self.resource_to_serialization_resolver(id);
+        let fut = self.resource_to_serialization_resolver(id, T::to_json);
         Box::pin(fut)
     }
+
+    fn priority(&self) -> ResourcePriority {
+        self.priority.get()
+    }
+}
+
+/// A resource whose value is sent with the given [Encode] function.
+struct EncodedResource<S, T>
+where
+    S: 'static,
+    T: Debug + 'static,
+{
+    state: Rc<ResourceState<S, T>>,
+    encode: Encode<T>,
+}
+
+impl<S, T> SerializableResource for EncodedResource<S, T>
+where
+    S: Debug + Clone,
+    T: Debug,
+{
+    fn as_any(&self) -> &dyn Any {
+        &*self.state
+    }
+
+    fn to_serialization_resolver(
+        &self,
+        id: ResourceId,
+    ) -> Pin<Box<dyn Future<Output = (ResourceId, String)>>> {
+        let fut = self
+            .state
+            .resource_to_serialization_resolver(id, self.encode);
+        Box::pin(fut)
+    }
+
+    fn priority(&self) -> ResourcePriority {
+        self.state.priority.get()
+    }
 }
 
 pub(crate) trait UnserializableResource {
@@ -0,0 +1,194 @@
+use std::{
+    any::Any, cell::Cell, collections::HashMap, fmt::Debug, future::Future, pin::Pin, rc::Rc,
+    time::Duration,
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    create_resource_with_initial_value, on_cleanup, runtime::with_runtime, Resource, Scope,
+    Serializable,
+};
+
+/// Creates a [Resource] like [create_resource](crate::create_resource), whose values are cached in the reactive
+/// runtime under `key` and the serialized value of the `source`, so that other resources
+/// created with the same `key`, like the one on a page the user navigates back to, can reuse
+/// them instead of fetching them again.
+///
+/// - A cached value younger than `ttl` is used as it is, without running the `fetcher`.
+/// - An older cached value is used right away, while the `fetcher` runs in the background
+///   (“stale-while-revalidate”); the resource is updated once the fresh value arrives,
+///   and [Resource::is_stale] returns `true` until then.
+/// - Without a cached value, the resource loads as usual.
+///
+/// Use [invalidate_resource_cache] to discard the values cached under a `key`, e.g., after a
+/// mutation, which also refetches any live resource that uses it.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # create_scope(create_runtime(), |cx| {
+/// async fn fetch_user(id: u32) -> String {
+///   // pretend we're fetching the user
+///   format!("user {id}")
+/// }
+///
+/// let (user_id, set_user_id) = create_signal(cx, 1);
+///
+/// # // like `create_resource`, this needs a browser or a Tokio runtime to run the Future
+/// # if false {
+/// // navigating back to user 1 within a minute won't fetch it again
+/// let user = create_cached_resource(cx, "user", user_id, fetch_user, Duration::from_secs(60));
+///
+/// // after the user has been edited
+/// invalidate_resource_cache(cx, "user");
+/// # }
+/// # }).dispose();
+/// ```
+pub fn create_cached_resource<S, T, Fu>(
+    cx: Scope,
+    key: &'static str,
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fu + 'static,
+    ttl: Duration,
+) -> Resource<S, T>
+where
+    S: PartialEq + Debug + Clone + Serializable + 'static,
+    T: Debug + Clone + Serializable + 'static,
+    Fu: Future<Output = T> + 'static,
+{
+    let entry_key = move |source: &S| {
+        (
+            key,
+            source
+                .to_json()
+                .expect("could not serialize resource cache key"),
+        )
+    };
+    // a cached value is shown right away, even if it's outdated and will be fetched again
+    let initial_value =
+        cached_value::<T>(cx, &entry_key(&cx.untrack(&source)), ttl).map(|(value, _)| value);
+    // the fetcher needs the resource to show outdated values when the source changes, so
+    // it is filled in once the resource has been created
+    let this: Rc<Cell<Option<Resource<S, T>>>> = Default::default();
+
+    let resource = create_resource_with_initial_value(
+        cx,
+        source,
+        {
+            let this = Rc::clone(&this);
+            move |source: S| -> Pin<Box<dyn Future<Output = T>>> {
+                let entry_key = entry_key(&source);
+                if let Some((value, fresh)) = cached_value::<T>(cx, &entry_key, ttl) {
+                    if fresh {
+                        return Box::pin(async move { value });
+                    }
+                    if let Some(resource) = this.get() {
+                        resource.set_if_source_is(&source, value);
+                    }
+                }
+
+                let fut = fetcher(source);
+                Box::pin(async move {
+                    let value = fut.await;
+                    store_value(cx, entry_key, value.clone());
+                    value
+                })
+            }
+        },
+        initial_value,
+    );
+    this.set(Some(resource));
+
+    let subscription = with_runtime(cx.runtime, |runtime| {
+        let mut cache = runtime.resource_cache.borrow_mut();
+        let id = cache.next_id;
+        cache.next_id += 1;
+        cache
+            .refetchers
+            .entry(key)
+            .or_default()
+            .push((id, Rc::new(move || resource.refetch())));
+        id
+    });
+    on_cleanup(cx, move || {
+        with_runtime(cx.runtime, |runtime| {
+            let mut cache = runtime.resource_cache.borrow_mut();
+            if let Some(refetchers) = cache.refetchers.get_mut(key) {
+                refetchers.retain(|(id, _)| *id != subscription);
+            }
+        })
+    });
+
+    resource
+}
+
+/// Discards all the values cached under `key` by [create_cached_resource], and refetches
+/// the live resources that were created with that `key`.
+pub fn invalidate_resource_cache(cx: Scope, key: &str) {
+    let refetchers = with_runtime(cx.runtime, |runtime| {
+        let mut cache = runtime.resource_cache.borrow_mut();
+        cache.entries.retain(|(entry_key, _), _| *entry_key != key);
+        cache.refetchers.get(key).cloned().unwrap_or_default()
+    });
+    // refetched outside the borrow, as a refetch may read or write the cache
+    for (_, refetch) in refetchers {
+        refetch();
+    }
+}
+
+/// The values cached by [create_cached_resource] in a reactive runtime.
+#[derive(Default)]
+pub(crate) struct ResourceCache {
+    entries: HashMap<(&'static str, String), CacheEntry>,
+    #[allow(clippy::type_complexity)]
+    refetchers: HashMap<&'static str, Vec<(usize, Rc<dyn Fn()>)>>,
+    next_id: usize,
+}
+
+struct CacheEntry {
+    value: Box<dyn Any>,
+    fetched_at: Duration,
+}
+
+/// Returns the value cached under `key`, if any, and whether it is younger than `ttl`.
+fn cached_value<T>(cx: Scope, key: &(&'static str, String), ttl: Duration) -> Option<(T, bool)>
+where
+    T: Clone + 'static,
+{
+    with_runtime(cx.runtime, |runtime| {
+        let cache = runtime.resource_cache.borrow();
+        let entry = cache.entries.get(key)?;
+        let value = entry.value.downcast_ref::<T>()?.clone();
+        let fresh = now().saturating_sub(entry.fetched_at) < ttl;
+        Some((value, fresh))
+    })
+}
+
+fn store_value<T>(cx: Scope, key: (&'static str, String), value: T)
+where
+    T: 'static,
+{
+    with_runtime(cx.runtime, |runtime| {
+        runtime.resource_cache.borrow_mut().entries.insert(
+            key,
+            CacheEntry {
+                value: Box::new(value),
+                fetched_at: now(),
+            },
+        );
+    })
+}
+
+/// The current time, as a duration since the Unix epoch.
+fn now() -> Duration {
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+        } else {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+        }
+    }
+}
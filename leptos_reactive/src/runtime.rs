@@ -1,13 +1,15 @@
 use crate::{
-    hydration::SharedContext, serialization::Serializable, AnyEffect, AnyResource, Effect,
-    EffectId, Memo, ReadSignal, ResourceId, ResourceState, RwSignal, Scope, ScopeDisposer, ScopeId,
-    ScopeProperty, SignalId, WriteSignal,
+    context::ContextId, hydration::SharedContext, queue_microtask, resource_cache::ResourceCache,
+    spawn::queue_idle_task, AnyEffect, AnyResource, DebugName, Effect, EffectId, EffectPriority,
+    Memo, ReadSignal, ResourceId, ResourcePriority, ResourceState, RwSignal, Scope, ScopeDisposer,
+    ScopeId, ScopeProperty, SerializableResource, SignalId, SuspenseContext, WriteSignal,
 };
 use cfg_if::cfg_if;
 use futures::stream::FuturesUnordered;
+use indexmap::IndexSet;
 use slotmap::{SecondaryMap, SlotMap, SparseSecondaryMap};
 use std::{
-    any::{Any, TypeId},
+    any::Any,
     cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
     fmt::Debug,
@@ -125,10 +127,16 @@ impl RuntimeId {
         T: Any + 'static,
     {
         let id = with_runtime(self, |runtime| {
-            runtime
+            let id = runtime
                 .signals
                 .borrow_mut()
-                .insert(Rc::new(RefCell::new(value)))
+                .insert(Rc::new(RefCell::new(value)));
+            #[cfg(feature = "debug-graph")]
+            runtime
+                .signal_types
+                .borrow_mut()
+                .insert(id, std::any::type_name::<T>());
+            id
         });
         (
             ReadSignal {
@@ -149,10 +157,16 @@ impl RuntimeId {
         T: Any + 'static,
     {
         let id = with_runtime(self, |runtime| {
-            runtime
+            let id = runtime
                 .signals
                 .borrow_mut()
-                .insert(Rc::new(RefCell::new(value)))
+                .insert(Rc::new(RefCell::new(value)));
+            #[cfg(feature = "debug-graph")]
+            runtime
+                .signal_types
+                .borrow_mut()
+                .insert(id, std::any::type_name::<T>());
+            id
         });
         RwSignal {
             runtime: self,
@@ -161,7 +175,11 @@ impl RuntimeId {
         }
     }
 
-    pub(crate) fn create_effect<T>(self, f: impl Fn(Option<T>) -> T + 'static) -> EffectId
+    pub(crate) fn create_effect<T>(
+        self,
+        f: impl Fn(Option<T>) -> T + 'static,
+        name: Option<DebugName>,
+    ) -> EffectId
     where
         T: Any + 'static,
     {
@@ -171,30 +189,52 @@ impl RuntimeId {
                 value: RefCell::new(None),
             };
             let id = { runtime.effects.borrow_mut().insert(Rc::new(effect)) };
+            if let Some(name) = name {
+                runtime.effect_names.borrow_mut().insert(id, name);
+            }
+            #[cfg(feature = "debug-graph")]
+            runtime
+                .effect_types
+                .borrow_mut()
+                .insert(id, std::any::type_name::<T>());
             id.run::<T>(self);
             id
         })
     }
 
-    pub(crate) fn create_memo<T>(self, f: impl Fn(Option<&T>) -> T + 'static) -> Memo<T>
+    /// Creates a memo, returning it along with the effect that computes it, which should be
+    /// disposed along with it.
+    pub(crate) fn create_memo<T>(self, f: impl Fn(Option<&T>) -> T + 'static) -> (Memo<T>, EffectId)
     where
         T: PartialEq + Any + 'static,
     {
         let (read, write) = self.create_signal(None);
 
-        self.create_effect(move |_| {
-            let (new, changed) = read.with_no_subscription(|p| {
-                let new = f(p.as_ref());
-                let changed = Some(&new) != p.as_ref();
-                (new, changed)
-            });
+        let effect = self.create_effect(
+            move |_| {
+                let (new, changed) = read.with_no_subscription(|p| {
+                    let new = f(p.as_ref());
+                    let changed = Some(&new) != p.as_ref();
+                    (new, changed)
+                });
 
-            if changed {
-                write.update(|n| *n = Some(new));
-            }
+                if changed {
+                    write.update(|n| *n = Some(new));
+                }
+            },
+            None,
+        );
+
+        with_runtime(self, |runtime| {
+            runtime.memos.borrow_mut().insert(effect, read.id);
+            #[cfg(feature = "debug-graph")]
+            runtime
+                .signal_types
+                .borrow_mut()
+                .insert(read.id, std::any::type_name::<T>());
         });
 
-        Memo(read)
+        (Memo(read), effect)
     }
 }
 
@@ -206,7 +246,10 @@ pub(crate) struct Runtime {
     pub scope_parents: RefCell<SparseSecondaryMap<ScopeId, ScopeId>>,
     pub scope_children: RefCell<SparseSecondaryMap<ScopeId, Vec<ScopeId>>>,
     #[allow(clippy::type_complexity)]
-    pub scope_contexts: RefCell<SparseSecondaryMap<ScopeId, HashMap<TypeId, Box<dyn Any>>>>,
+    pub scope_contexts: RefCell<SparseSecondaryMap<ScopeId, HashMap<ContextId, Box<dyn Any>>>>,
+    pub resource_cache: RefCell<ResourceCache>,
+    /// The suspense contexts of the transitions that are currently being started.
+    pub transitions: RefCell<Vec<SuspenseContext>>,
     #[allow(clippy::type_complexity)]
     pub scope_cleanups: RefCell<SparseSecondaryMap<ScopeId, Vec<Box<dyn FnOnce()>>>>,
     pub signals: RefCell<SlotMap<SignalId, Rc<RefCell<dyn Any>>>>,
@@ -214,6 +257,20 @@ pub(crate) struct Runtime {
     pub effects: RefCell<SlotMap<EffectId, Rc<dyn AnyEffect>>>,
     pub effect_sources: RefCell<SecondaryMap<EffectId, RefCell<HashSet<SignalId>>>>,
     pub resources: RefCell<SlotMap<ResourceId, AnyResource>>,
+    pub batching: Cell<bool>,
+    pub batch_count: Cell<usize>,
+    pub pending_effects: RefCell<IndexSet<EffectId>>,
+    pub signal_names: RefCell<SecondaryMap<SignalId, DebugName>>,
+    pub effect_names: RefCell<SecondaryMap<EffectId, DebugName>>,
+    pub effect_priorities: RefCell<SecondaryMap<EffectId, EffectPriority>>,
+    pub user_blocking_effects: RefCell<IndexSet<EffectId>>,
+    pub idle_effects: RefCell<IndexSet<EffectId>>,
+    #[cfg(feature = "debug-graph")]
+    pub signal_types: RefCell<SecondaryMap<SignalId, &'static str>>,
+    #[cfg(feature = "debug-graph")]
+    pub effect_types: RefCell<SecondaryMap<EffectId, &'static str>>,
+    /// The effect that computes each memo, and the signal that holds its value.
+    pub memos: RefCell<SecondaryMap<EffectId, SignalId>>,
 }
 
 impl Debug for Runtime {
@@ -228,6 +285,9 @@ impl Debug for Runtime {
             .field("signal_subscribers", &self.signal_subscribers)
             .field("effects", &self.effects.borrow().len())
             .field("effect_sources", &self.effect_sources)
+            .field("batching", &self.batching)
+            .field("batch_count", &self.batch_count)
+            .field("pending_effects", &self.pending_effects)
             .finish()
     }
 }
@@ -237,6 +297,141 @@ impl Runtime {
         Self::default()
     }
 
+    /// The name given to a signal, if any. Names outlive the signal, so that reading it
+    /// after it has been disposed can be reported by name.
+    pub(crate) fn signal_name(&self, id: SignalId) -> Option<DebugName> {
+        self.signal_names.borrow().get(id).copied()
+    }
+
+    /// The name given to an effect, if any.
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    pub(crate) fn effect_name(&self, id: EffectId) -> Option<DebugName> {
+        self.effect_names.borrow().get(id).copied()
+    }
+
+    /// Ends the outermost batch, bringing the memos it deferred up to date before running
+    /// the other effects, so that each of those runs once and sees the new values of the memos.
+    pub(crate) fn end_batch(&self, runtime_id: RuntimeId) {
+        // the memos run while still batching, so that the effects they notify are deferred too
+        while let Some(memo) = self.next_pending_memo() {
+            self.pending_effects.borrow_mut().shift_remove(&memo);
+            self.run_effect(runtime_id, memo);
+        }
+        self.batching.set(false);
+        let pending = self.pending_effects.take();
+        self.run_effects(runtime_id, pending);
+    }
+
+    /// The next deferred memo to run: one that doesn't read another deferred memo.
+    fn next_pending_memo(&self) -> Option<EffectId> {
+        let memos = self.memos.borrow();
+        let pending_memos = self
+            .pending_effects
+            .borrow()
+            .iter()
+            .copied()
+            .filter(|id| memos.contains_key(*id))
+            .collect::<Vec<_>>();
+        let sources = self.effect_sources.borrow();
+        let reads_pending_memo = |id: EffectId| {
+            if let Some(sources) = sources.get(id) {
+                let sources = sources.borrow();
+                pending_memos
+                    .iter()
+                    .filter(|other| **other != id)
+                    .filter_map(|other| memos.get(*other))
+                    .any(|signal| sources.contains(signal))
+            } else {
+                false
+            }
+        };
+        pending_memos
+            .iter()
+            .copied()
+            .find(|id| !reads_pending_memo(*id))
+            // memos can't depend on each other in a cycle, but if they did, one goes first
+            .or_else(|| pending_memos.first().copied())
+    }
+
+    pub(crate) fn run_effects(
+        &self,
+        runtime_id: RuntimeId,
+        effect_ids: impl IntoIterator<Item = EffectId>,
+    ) {
+        // render effects run right away; the others are queued to run after them
+        let mut deferred = Vec::new();
+        for id in effect_ids {
+            let priority = self
+                .effect_priorities
+                .borrow()
+                .get(id)
+                .copied()
+                .unwrap_or_default();
+            match priority {
+                EffectPriority::Render => self.run_effect(runtime_id, id),
+                _ => deferred.push((priority, id)),
+            }
+        }
+        deferred.sort_by_key(|(priority, _)| *priority);
+        for (priority, id) in deferred {
+            self.schedule_effect(runtime_id, id, priority);
+        }
+    }
+
+    fn run_effect(&self, runtime_id: RuntimeId, id: EffectId) {
+        let effect = {
+            let effects = self.effects.borrow();
+            effects.get(id).cloned()
+        };
+        if let Some(effect) = effect {
+            effect.run(id, runtime_id);
+        }
+    }
+
+    /// The queue effects with this priority wait in, or `None` for render effects,
+    /// which run immediately.
+    fn effect_queue(&self, priority: EffectPriority) -> Option<&RefCell<IndexSet<EffectId>>> {
+        match priority {
+            EffectPriority::Render => None,
+            EffectPriority::UserBlocking => Some(&self.user_blocking_effects),
+            EffectPriority::Idle => Some(&self.idle_effects),
+        }
+    }
+
+    /// Adds an effect to the queue for its priority, scheduling the queue to be flushed
+    /// if it was empty: in a microtask for user-blocking effects, and when the browser is
+    /// idle for idle effects.
+    fn schedule_effect(&self, runtime_id: RuntimeId, id: EffectId, priority: EffectPriority) {
+        let queue = match self.effect_queue(priority) {
+            Some(queue) => queue,
+            None => return self.run_effect(runtime_id, id),
+        };
+        let needs_flush = {
+            let mut queue = queue.borrow_mut();
+            // an effect that is already queued runs once
+            if !queue.insert(id) {
+                return;
+            }
+            queue.len() == 1
+        };
+        if needs_flush {
+            let flush = move || {
+                with_runtime(runtime_id, |runtime| {
+                    if let Some(queue) = runtime.effect_queue(priority) {
+                        let ids = queue.take();
+                        for id in ids {
+                            runtime.run_effect(runtime_id, id);
+                        }
+                    }
+                })
+            };
+            match priority {
+                EffectPriority::Idle => queue_idle_task(flush),
+                _ => queue_microtask(flush),
+            }
+        }
+    }
+
     pub(crate) fn create_unserializable_resource<S, T>(
         &self,
         state: Rc<ResourceState<S, T>>,
@@ -250,14 +445,10 @@ impl Runtime {
             .insert(AnyResource::Unserializable(state))
     }
 
-    pub(crate) fn create_serializable_resource<S, T>(
+    pub(crate) fn create_serializable_resource(
         &self,
-        state: Rc<ResourceState<S, T>>,
-    ) -> ResourceId
-    where
-        S: Debug + Clone + 'static,
-        T: Debug + Serializable + 'static,
-    {
+        state: Rc<dyn SerializableResource>,
+    ) -> ResourceId {
         self.resources
             .borrow_mut()
             .insert(AnyResource::Serializable(state))
@@ -265,24 +456,16 @@ impl Runtime {
 
     #[cfg(feature = "hydrate")]
     pub fn start_hydration(&self, element: &web_sys::Element) {
-        use wasm_bindgen::{JsCast, UnwrapThrowExt};
-
-        // gather hydratable elements
-        let mut registry = HashMap::new();
-        if let Ok(templates) = element.query_selector_all("*[data-hk]") {
-            for i in 0..templates.length() {
-                let node = templates
-                    .item(i)
-                    .unwrap_throw() // ok to unwrap; we already have the index, so this can't fail
-                    .unchecked_into::<web_sys::Element>();
-                let key = node.get_attribute("data-hk").unwrap_throw();
-                registry.insert(key, node);
-            }
-        }
-
+        let registry = hydration_registry(element);
         *self.shared_context.borrow_mut() = Some(SharedContext::new_with_registry(registry));
     }
 
+    #[cfg(feature = "hydrate")]
+    pub fn start_island_hydration(&self, element: &web_sys::Element) {
+        let registry = hydration_registry(element);
+        *self.shared_context.borrow_mut() = Some(SharedContext::new_for_island(registry));
+    }
+
     #[cfg(feature = "hydrate")]
     pub fn end_hydration(&self) {
         if let Some(ref mut sc) = *self.shared_context.borrow_mut() {
@@ -331,6 +514,21 @@ impl Runtime {
             .collect()
     }
 
+    pub(crate) fn blocking_resources(&self) -> Vec<ResourceId> {
+        self.resources
+            .borrow()
+            .iter()
+            .filter_map(|(id, resource)| match resource {
+                AnyResource::Serializable(resource)
+                    if resource.priority() == ResourcePriority::Blocking =>
+                {
+                    Some(id)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub(crate) fn serialization_resolvers(
         &self,
     ) -> FuturesUnordered<PinnedFuture<(ResourceId, String)>> {
@@ -357,3 +555,22 @@ impl std::hash::Hash for Runtime {
         std::ptr::hash(&self, state);
     }
 }
+
+/// Gathers the server-rendered elements under `element` that can be hydrated, by their keys.
+#[cfg(feature = "hydrate")]
+fn hydration_registry(element: &web_sys::Element) -> HashMap<String, web_sys::Element> {
+    use wasm_bindgen::{JsCast, UnwrapThrowExt};
+
+    let mut registry = HashMap::new();
+    if let Ok(templates) = element.query_selector_all("*[data-hk]") {
+        for i in 0..templates.length() {
+            let node = templates
+                .item(i)
+                .unwrap_throw() // ok to unwrap; we already have the index, so this can't fail
+                .unchecked_into::<web_sys::Element>();
+            let key = node.get_attribute("data-hk").unwrap_throw();
+            registry.insert(key, node);
+        }
+    }
+    registry
+}
@@ -1,8 +1,11 @@
 use cfg_if::cfg_if;
 
 use crate::runtime::{with_runtime, RuntimeId};
-use crate::{hydration::SharedContext, EffectId, ResourceId, SignalId};
-use crate::{PinnedFuture, SuspenseContext};
+use crate::{
+    hydration::{HydrationContext, SharedContext},
+    EffectId, ResourceId, SignalId,
+};
+use crate::{PinnedFuture, ResourcePriority, SuspenseContext};
 use futures::stream::FuturesUnordered;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -151,6 +154,75 @@ impl Scope {
             untracked_result
         })
     }
+
+    /// Runs the given function, deferring any effects triggered by the signals it updates
+    /// until it has finished. The memos that depend on those signals are then brought up to
+    /// date first, so each effect runs once, even if several of the signals and memos it
+    /// depends on have changed, and sees all of their new values.
+    ///
+    /// Memos are effects too, so a memo read inside the batch will not reflect the updates
+    /// made inside it until the batch is finished. Batches can be nested; effects run when
+    /// the outermost batch finishes. If it panics, the batch ends without running the
+    /// effects it deferred.
+    ///
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # use std::{cell::Cell, rc::Rc};
+    /// # run_scope(create_runtime(), |cx| {
+    /// let (first, set_first) = create_signal(cx, "Bob");
+    /// let (last, set_last) = create_signal(cx, "Smith");
+    /// let runs = Rc::new(Cell::new(0));
+    ///
+    /// create_isomorphic_effect(cx, {
+    ///     let runs = Rc::clone(&runs);
+    ///     move |_| {
+    ///         _ = format!("{} {}", first(), last());
+    ///         runs.set(runs.get() + 1);
+    ///     }
+    /// });
+    /// assert_eq!(runs.get(), 1);
+    ///
+    /// cx.batch(|| {
+    ///     set_first("Alice");
+    ///     set_last("Jones");
+    /// });
+    /// // the effect ran once for both updates
+    /// assert_eq!(runs.get(), 2);
+    /// # });
+    /// ```
+    pub fn batch<T>(&self, f: impl FnOnce() -> T) -> T {
+        let runtime_id = self.runtime;
+        let nested = with_runtime(runtime_id, |runtime| {
+            let nested = runtime.batching.replace(true);
+            if !nested {
+                runtime
+                    .batch_count
+                    .set(runtime.batch_count.get().wrapping_add(1));
+            }
+            nested
+        });
+        // the outermost batch ends even if `f` panics, so that effects aren't deferred forever
+        let _guard = (!nested).then(|| BatchGuard(runtime_id));
+        f()
+    }
+}
+
+/// Ends the outermost batch of a runtime when it's dropped, and runs the effects the batch
+/// deferred, unless it's dropped by a panic.
+struct BatchGuard(RuntimeId);
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        with_runtime(self.0, |runtime| {
+            // an effect that panicked too would abort the process while unwinding
+            if std::thread::panicking() {
+                runtime.batching.set(false);
+                runtime.pending_effects.take();
+            } else {
+                runtime.end_batch(self.0);
+            }
+        });
+    }
 }
 
 // Internals
@@ -295,6 +367,14 @@ impl Scope {
                 })
             }
 
+            /// `hydrate` only: Begins hydrating an island of a page on its own, without any of
+            /// the resources the server sent for the page.
+            pub fn start_island_hydration(&self, element: &web_sys::Element) {
+                with_runtime(self.runtime, |runtime| {
+                    runtime.start_island_hydration(element);
+                })
+            }
+
             /// `hydrate` only: Ends the hydration process.
             pub fn end_hydration(&self) {
                 with_runtime(self.runtime, |runtime| {
@@ -327,6 +407,9 @@ impl Scope {
 
                             //log::debug!("(hy) searching for {key}");
 
+                            #[cfg(debug_assertions)]
+                            shared_context.check_element(&key, template, node.as_ref());
+
                             if let Some(node) = node {
                                 //log::debug!("(hy) found {key}");
                                 shared_context.completed.push(node.clone());
@@ -363,6 +446,8 @@ impl Scope {
                 .map(|sc| sc.context.as_ref())
                 .is_some()
             {
+                #[cfg(all(feature = "hydrate", debug_assertions))]
+                let first = start.clone();
                 while let Some(curr) = end {
                     start = curr.clone();
                     if curr.node_type() == 8 {
@@ -373,6 +458,10 @@ impl Scope {
                         } else if v == Some("/".to_string()) {
                             count -= 1;
                             if count == 0 {
+                                #[cfg(all(feature = "hydrate", debug_assertions))]
+                                if let Some(ref sc) = *runtime.shared_context.borrow() {
+                                    sc.check_marker(&first, true);
+                                }
                                 current.push(curr.clone());
                                 return (curr, current);
                             }
@@ -381,12 +470,49 @@ impl Scope {
                     current.push(curr.clone());
                     end = curr.next_sibling();
                 }
+
+                #[cfg(all(feature = "hydrate", debug_assertions))]
+                if let Some(ref sc) = *runtime.shared_context.borrow() {
+                    sc.check_marker(&first, false);
+                }
             }
 
             (start, current)
         })
     }
 
+    /// Runs the given function as the component with the given name, which any hydration
+    /// mismatch found while it runs is reported for, in debug builds. See [HydrationMismatch](crate::HydrationMismatch).
+    pub fn with_component_name<T>(&self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        cfg_if! {
+            if #[cfg(all(feature = "hydrate", debug_assertions))] {
+                let hydrating = with_runtime(self.runtime, |runtime| {
+                    match *runtime.shared_context.borrow_mut() {
+                        Some(ref mut sc) if sc.context.is_some() => {
+                            sc.components.push(name);
+                            true
+                        }
+                        _ => false,
+                    }
+                });
+
+                let res = f();
+
+                if hydrating {
+                    with_runtime(self.runtime, |runtime| {
+                        if let Some(ref mut sc) = *runtime.shared_context.borrow_mut() {
+                            sc.components.pop();
+                        }
+                    });
+                }
+                res
+            } else {
+                _ = name;
+                f()
+            }
+        }
+    }
+
     /// On either the server side or the browser side, generates the next key in the hydration process.
     pub fn next_hydration_key(&self) -> String {
         with_runtime(self.runtime, |runtime| {
@@ -437,11 +563,53 @@ impl Scope {
         })
     }
 
+    /// Runs the given function with hydration keys of its own, which start from `{prefix}0`,
+    /// rather than following on from the keys around it, so that a part of the page can be
+    /// hydrated on its own with the same keys it was rendered with, wherever it is.
+    pub fn with_hydration_prefix<T>(&self, prefix: String, f: impl FnOnce() -> T) -> T {
+        with_runtime(self.runtime, |runtime| {
+            let prev = {
+                let mut sc = runtime.shared_context.borrow_mut();
+                let sc = sc.get_or_insert_with(SharedContext::default);
+                sc.context.replace(HydrationContext::with_prefix(prefix))
+            };
+
+            let res = self.untrack(f);
+
+            if let Some(ref mut sc) = *runtime.shared_context.borrow_mut() {
+                sc.context = prev;
+            }
+            res
+        })
+    }
+
     /// Returns IDs for all [Resource](crate::Resource)s found on any scope.
     pub fn all_resources(&self) -> Vec<ResourceId> {
         with_runtime(self.runtime, |runtime| runtime.all_resources())
     }
 
+    /// Returns IDs for the [Resource](crate::Resource)s with the
+    /// [Blocking](crate::ResourcePriority::Blocking) priority, which a page that is streamed in
+    /// order waits for before it's sent.
+    pub fn blocking_resources(&self) -> Vec<ResourceId> {
+        with_runtime(self.runtime, |runtime| runtime.blocking_resources())
+    }
+
+    /// The contexts provided with [provide_serializable_context](crate::provide_serializable_context),
+    /// as a JSON object of their JSON by type name, which can be embedded in a `<script>`.
+    pub fn serialized_contexts(&self) -> String {
+        with_runtime(self.runtime, |runtime| {
+            let json = runtime
+                .shared_context
+                .borrow()
+                .as_ref()
+                .map(|context| serde_json::to_string(&context.serialized_contexts).unwrap())
+                .unwrap_or_else(|| String::from("{}"));
+            // keeps a `</script>` in a value from closing the script it's embedded in
+            json.replace('<', "\\u003c")
+        })
+    }
+
     /// The current key for an HTML fragment created by server-rendering a `<Suspense/>` component.
     pub fn current_fragment_key(&self) -> String {
         with_runtime(self.runtime, |runtime| {
@@ -483,20 +651,38 @@ impl Scope {
 
                 shared_context.pending_fragments.insert(
                     key.to_string(),
-                    Box::pin(async move {
-                        rx.next().await;
-                        resolver()
-                    }),
+                    (
+                        context,
+                        Box::pin(async move {
+                            rx.next().await;
+                            resolver()
+                        }),
+                    ),
                 );
             }
         })
     }
 
     /// The set of all HTML fragments current pending, by their keys (see [Self::current_fragment_key]).
-    pub fn pending_fragments(&self) -> HashMap<String, Pin<Box<dyn Future<Output = String>>>> {
+    pub fn pending_fragments(&self) -> HashMap<String, PendingFragment> {
         with_runtime(self.runtime, |runtime| {
             if let Some(ref mut shared_context) = *runtime.shared_context.borrow_mut() {
-                std::mem::take(&mut shared_context.pending_fragments)
+                let fragments = std::mem::take(&mut shared_context.pending_fragments);
+                let keys = fragments
+                    .iter()
+                    .map(|(key, (context, _))| (context.id(), key.clone()))
+                    .collect::<HashMap<_, _>>();
+                fragments
+                    .into_iter()
+                    .map(|(key, (context, html))| {
+                        let fragment = PendingFragment {
+                            priority: context.priority(),
+                            parent: context.parent.and_then(|parent| keys.get(&parent).cloned()),
+                            html,
+                        };
+                        (key, fragment)
+                    })
+                    .collect()
             } else {
                 HashMap::new()
             }
@@ -504,6 +690,26 @@ impl Scope {
     }
 }
 
+/// The HTML of a `<Suspense/>` that is rendered on the server once its resources have
+/// resolved, returned by [Scope::pending_fragments].
+pub struct PendingFragment {
+    /// The highest priority of the resources read under the `<Suspense/>`.
+    pub priority: ResourcePriority,
+    /// The key of the pending fragment of the `<Suspense/>` this one is nested in, if any.
+    pub parent: Option<String>,
+    /// Resolves to the HTML once all of the resources have resolved.
+    pub html: Pin<Box<dyn Future<Output = String>>>,
+}
+
+impl Debug for PendingFragment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingFragment")
+            .field("priority", &self.priority)
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
 impl Debug for ScopeDisposer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("ScopeDisposer").finish()
@@ -45,7 +45,36 @@ pub fn create_selector<T>(
 where
     T: PartialEq + Eq + Debug + Clone + Hash + 'static,
 {
-    create_selector_with_fn(cx, source, |a, b| a == b)
+    #[allow(clippy::type_complexity)]
+    let subs: Rc<RefCell<HashMap<T, (ReadSignal<bool>, WriteSignal<bool>)>>> =
+        Rc::new(RefCell::new(HashMap::new()));
+    let v = Rc::new(RefCell::new(None));
+
+    create_isomorphic_effect(cx, {
+        let subs = Rc::clone(&subs);
+        let v = Rc::clone(&v);
+        move |prev: Option<T>| {
+            let next_value = source();
+            *v.borrow_mut() = Some(next_value.clone());
+            if prev.as_ref() != Some(&next_value) {
+                // only the previously-selected and newly-selected keys can have changed,
+                // so look them up directly rather than checking every key
+                let changed = {
+                    let subs = subs.borrow();
+                    prev.iter()
+                        .chain(Some(&next_value))
+                        .filter_map(|key| subs.get(key).map(|(_, set)| *set))
+                        .collect::<Vec<_>>()
+                };
+                for set in changed {
+                    set.update(|n| *n = true);
+                }
+            }
+            next_value
+        }
+    });
+
+    selector_reader(cx, subs, v, |a, b| a == b)
 }
 
 /// Creates a conditional signal that only notifies subscribers when a change
@@ -86,11 +115,28 @@ where
         }
     });
 
+    selector_reader(cx, subs, v, f)
+}
+
+/// Returns a function that subscribes to the signal for a key, creating it if needed, and
+/// checks the key against the current value.
+#[allow(clippy::type_complexity)]
+fn selector_reader<T>(
+    cx: Scope,
+    subs: Rc<RefCell<HashMap<T, (ReadSignal<bool>, WriteSignal<bool>)>>>,
+    v: Rc<RefCell<Option<T>>>,
+    f: impl Fn(&T, &T) -> bool + Clone + 'static,
+) -> impl Fn(T) -> bool + Clone
+where
+    T: PartialEq + Eq + Debug + Clone + Hash + 'static,
+{
     move |key| {
-        let mut subs = subs.borrow_mut();
-        let (read, _) = subs
-            .entry(key.clone())
-            .or_insert_with(|| create_signal(cx, false));
+        let read = {
+            let mut subs = subs.borrow_mut();
+            subs.entry(key.clone())
+                .or_insert_with(|| create_signal(cx, false))
+                .0
+        };
         _ = read.try_with(|n| *n);
         f(&key, v.borrow().as_ref().unwrap())
     }
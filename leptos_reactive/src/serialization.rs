@@ -97,3 +97,107 @@ cfg_if! {
         }
     }
 }
+
+/// A format that the value of a [Resource](crate::Resource) is sent from the server to the
+/// browser in, for a resource created with
+/// [create_resource_with_serializer](crate::create_resource_with_serializer).
+///
+/// Resources are sent as JSON by default, with [Serializable]. Binary formats like
+/// [BincodeSerializer] and [CborSerializer] are much more compact for large numeric data, like
+/// charts or embeddings, and are sent as base64 text. Any type that implements this trait can
+/// be used:
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use serde::{de::DeserializeOwned, Serialize};
+/// pub struct Postcard;
+///
+/// impl Serializer for Postcard {
+///     fn encode<T: Serialize>(value: &T) -> Result<String, SerializationError> {
+///         // e.g., base64 of `postcard::to_allocvec(value)`
+///         # unimplemented!()
+///     }
+///
+///     fn decode<T: DeserializeOwned>(data: &str) -> Result<T, SerializationError> {
+///         // e.g., `postcard::from_bytes` of the base64-decoded data
+///         # unimplemented!()
+///     }
+/// }
+/// ```
+pub trait Serializer: 'static {
+    /// Encodes a value as the text it is sent in.
+    fn encode<T: serde::Serialize>(value: &T) -> Result<String, SerializationError>;
+
+    /// Decodes a value that was encoded with [Serializer::encode].
+    fn decode<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, SerializationError>;
+}
+
+/// Sends values as JSON, with `serde_json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn encode<T: serde::Serialize>(value: &T) -> Result<String, SerializationError> {
+        serde_json::to_string(value).map_err(|e| SerializationError::Serialize(Rc::new(e)))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, SerializationError> {
+        serde_json::from_str(data).map_err(|e| SerializationError::Deserialize(Rc::new(e)))
+    }
+}
+
+/// Sends values as base64-encoded [bincode](https://github.com/bincode-org/bincode).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BincodeSerializer;
+
+impl Serializer for BincodeSerializer {
+    fn encode<T: serde::Serialize>(value: &T) -> Result<String, SerializationError> {
+        let bytes =
+            bincode::serialize(value).map_err(|e| SerializationError::Serialize(Rc::new(e)))?;
+        Ok(base64::encode(bytes))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, SerializationError> {
+        let bytes =
+            base64::decode(data).map_err(|e| SerializationError::Deserialize(Rc::new(e)))?;
+        bincode::deserialize(&bytes).map_err(|e| SerializationError::Deserialize(Rc::new(e)))
+    }
+}
+
+/// Sends values as base64-encoded [CBOR](https://cbor.io/).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CborSerializer;
+
+impl Serializer for CborSerializer {
+    fn encode<T: serde::Serialize>(value: &T) -> Result<String, SerializationError> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes)
+            .map_err(|e| SerializationError::Serialize(Rc::new(e)))?;
+        Ok(base64::encode(bytes))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, SerializationError> {
+        let bytes =
+            base64::decode(data).map_err(|e| SerializationError::Deserialize(Rc::new(e)))?;
+        ciborium::de::from_reader(bytes.as_slice())
+            .map_err(|e| SerializationError::Deserialize(Rc::new(e)))
+    }
+}
+
+/// Sends values as base64-encoded [MessagePack](https://msgpack.org/).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MsgPackSerializer;
+
+impl Serializer for MsgPackSerializer {
+    fn encode<T: serde::Serialize>(value: &T) -> Result<String, SerializationError> {
+        let bytes = rmp_serde::to_vec_named(value)
+            .map_err(|e| SerializationError::Serialize(Rc::new(e)))?;
+        Ok(base64::encode(bytes))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(data: &str) -> Result<T, SerializationError> {
+        let bytes =
+            base64::decode(data).map_err(|e| SerializationError::Deserialize(Rc::new(e)))?;
+        rmp_serde::from_slice(&bytes).map_err(|e| SerializationError::Deserialize(Rc::new(e)))
+    }
+}
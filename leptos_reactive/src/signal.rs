@@ -1,7 +1,8 @@
 use crate::{
     debug_warn,
     runtime::{with_runtime, RuntimeId},
-    spawn_local, Runtime, Scope, ScopeProperty, UntrackedGettableSignal, UntrackedSettableSignal,
+    spawn_local, DebugName, Runtime, Scope, ScopeProperty, UntrackedGettableSignal,
+    UntrackedSettableSignal,
 };
 use futures::Stream;
 use std::{fmt::Debug, marker::PhantomData};
@@ -51,6 +52,34 @@ pub fn create_signal<T>(cx: Scope, value: T) -> (ReadSignal<T>, WriteSignal<T>)
     s
 }
 
+/// Creates a signal like [create_signal], giving it a name that is included, along with the
+/// place it was created, in warnings and panic messages about it.
+///
+/// ```should_panic
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let mut count = None;
+/// cx.child_scope(|cx| {
+///     count = Some(create_signal_named(cx, "count", 0).0);
+/// })
+/// .dispose();
+///
+/// // panics with "tried to access signal `count` (created at src/app.rs:5:18)
+/// // after it had been disposed"
+/// count.unwrap().get();
+/// # }).dispose();
+/// ```
+#[track_caller]
+pub fn create_signal_named<T>(
+    cx: Scope,
+    name: &'static str,
+    value: T,
+) -> (ReadSignal<T>, WriteSignal<T>) {
+    let s = create_signal(cx, value);
+    s.0.id.set_name(s.0.runtime, DebugName::new(name));
+    s
+}
+
 /// Creates a signal that always contains the most recent value emitted by a [Stream].
 /// If the stream has not yet emitted a value since the signal was created, the signal's
 /// value will be `None`.
@@ -206,9 +235,16 @@ where
         let id = self.id;
         let runtime = self.runtime;
         // TODO: because it's not attached to a scope, this effect will leak if the scope is disposed
-        runtime.create_effect(move |_| {
-            _ = tx.unbounded_send(id.with(runtime, T::clone));
-        });
+        debug_warn!(
+            "[ReadSignal::to_stream] The stream for this Signal<{}> is fed by an effect that doesn’t belong to any Scope, so it will not be disposed until the runtime is. Prefer reading the signal in an effect created with a Scope.",
+            std::any::type_name::<T>()
+        );
+        runtime.create_effect(
+            move |_| {
+                _ = tx.unbounded_send(id.with(runtime, T::clone));
+            },
+            None,
+        );
         rx
     }
 }
@@ -437,6 +473,15 @@ pub fn create_rw_signal<T>(cx: Scope, value: T) -> RwSignal<T> {
     s
 }
 
+/// Creates a signal like [create_rw_signal], giving it a name that is included, along with
+/// the place it was created, in warnings and panic messages about it.
+#[track_caller]
+pub fn create_rw_signal_named<T>(cx: Scope, name: &'static str, value: T) -> RwSignal<T> {
+    let s = create_rw_signal(cx, value);
+    s.id.set_name(s.runtime, DebugName::new(name));
+    s
+}
+
 /// A signal that combines the getter and setter into one value, rather than
 /// separating them into a [ReadSignal] and a [WriteSignal]. You may prefer this
 /// its style, or it may be easier to pass around in a context or as a function argument.
@@ -715,13 +760,27 @@ slotmap::new_key_type! {
 
 #[derive(Debug, Error)]
 pub(crate) enum SignalError {
-    #[error("tried to access a signal that had been disposed")]
-    Disposed,
+    #[error("tried to access {} after it had been disposed", describe_signal(.0))]
+    Disposed(Option<DebugName>),
     #[error("error casting signal to type {0}")]
     Type(&'static str),
 }
 
+/// Describes a signal by its name, if it has one.
+pub(crate) fn describe_signal(name: &Option<DebugName>) -> String {
+    match name {
+        Some(name) => format!("signal {name}"),
+        None => "a signal".to_string(),
+    }
+}
+
 impl SignalId {
+    pub(crate) fn set_name(&self, runtime: RuntimeId, name: DebugName) {
+        with_runtime(runtime, |runtime| {
+            runtime.signal_names.borrow_mut().insert(*self, name);
+        })
+    }
+
     pub(crate) fn subscribe(&self, runtime: &Runtime) {
         // add subscriber
         if let Some(observer) = runtime.observer.get() {
@@ -743,7 +802,11 @@ impl SignalId {
         // get the value
         let value = {
             let signals = runtime.signals.borrow();
-            match signals.get(*self).cloned().ok_or(SignalError::Disposed) {
+            match signals
+                .get(*self)
+                .cloned()
+                .ok_or_else(|| SignalError::Disposed(runtime.signal_name(*self)))
+            {
                 Ok(s) => Ok(s),
                 Err(e) => {
                     debug_warn!("[Signal::try_with] {e}");
@@ -753,8 +816,9 @@ impl SignalId {
         }?;
         let value = value.try_borrow().unwrap_or_else(|e| {
             debug_warn!(
-                "Signal::try_with_no_subscription failed on Signal<{}>. It seems you're trying to read the value of a signal within an effect caused by updating the signal.",
-                std::any::type_name::<T>()
+                "Signal::try_with_no_subscription failed on Signal<{}>{}. It seems you're trying to read the value of a signal within an effect caused by updating the signal.",
+                std::any::type_name::<T>(),
+                crate::debug_name::name_suffix(runtime.signal_name(*self))
             );
             panic!("{e}");
         });
@@ -786,7 +850,8 @@ impl SignalId {
         T: 'static,
     {
         with_runtime(runtime, |runtime| {
-            self.try_with_no_subscription(runtime, f).unwrap()
+            self.try_with_no_subscription(runtime, f)
+                .unwrap_or_else(|e| panic!("{e}"))
         })
     }
 
@@ -794,7 +859,9 @@ impl SignalId {
     where
         T: 'static,
     {
-        with_runtime(runtime, |runtime| self.try_with(runtime, f).unwrap())
+        with_runtime(runtime, |runtime| {
+            self.try_with(runtime, f).unwrap_or_else(|e| panic!("{e}"))
+        })
     }
 
     fn update_value<T>(&self, runtime: RuntimeId, f: impl FnOnce(&mut T)) -> bool
@@ -820,8 +887,9 @@ impl SignalId {
                 }
             } else {
                 debug_warn!(
-                    "[Signal::update] You’re trying to update a Signal<{}> that has already been disposed of. This is probably either a logic error in a component that creates and disposes of scopes, or a Resource resolving after its scope has been dropped without having been cleaned up.",
-                    std::any::type_name::<T>()
+                    "[Signal::update] You’re trying to update a Signal<{}>{} that has already been disposed of. This is probably either a logic error in a component that creates and disposes of scopes, or a Resource resolving after its scope has been dropped without having been cleaned up.",
+                    std::any::type_name::<T>(),
+                    crate::debug_name::name_suffix(runtime.signal_name(*self))
                 );
                 false
             }
@@ -844,14 +912,11 @@ impl SignalId {
                     subs.map(|subs| subs.borrow().clone())
                 };
                 if let Some(subs) = subs {
-                    for sub in subs {
-                        let effect = {
-                            let effects = runtime.effects.borrow();
-                            effects.get(sub).cloned()
-                        };
-                        if let Some(effect) = effect {
-                            effect.run(sub, runtime_id);
-                        }
+                    if runtime.batching.get() {
+                        // defer the effects until the batch finishes, running each once
+                        runtime.pending_effects.borrow_mut().extend(subs);
+                    } else {
+                        runtime.run_effects(runtime_id, subs);
                     }
                 }
             }
@@ -0,0 +1,188 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, hash::Hash, rc::Rc};
+
+use crate::{create_rw_signal, RwSignal, Scope, UntrackedGettableSignal};
+
+/// Creates a reactive keyed collection, in which each key can be tracked independently.
+///
+/// Reading a key with [SignalMap::get] or [SignalMap::with] subscribes only to that key, so
+/// inserting, updating, or removing one entry only notifies the effects that read it. Reading
+/// [SignalMap::keys] or [SignalMap::len] subscribes to the set of keys, which only changes when
+/// an entry is added or removed.
+///
+/// This makes large keyed lists cheaper to keep up to date than a single signal holding a `Vec`,
+/// which notifies every subscriber whenever any item changes.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// # create_scope(create_runtime(), |cx| {
+/// let todos = create_signal_map(cx);
+/// todos.insert(1, "Buy milk".to_string());
+/// todos.insert(2, "Walk the dog".to_string());
+///
+/// let runs = Rc::new(Cell::new(0));
+/// create_isomorphic_effect(cx, {
+///     let todos = todos.clone();
+///     let runs = Rc::clone(&runs);
+///     move |_| {
+///         _ = todos.get(&1);
+///         runs.set(runs.get() + 1);
+///     }
+/// });
+/// assert_eq!(runs.get(), 1);
+///
+/// // updating another key doesn't notify the effect
+/// todos.update(&2, |todo| todo.push_str(" twice"));
+/// assert_eq!(runs.get(), 1);
+///
+/// // but updating the key it read does
+/// todos.insert(1, "Buy oat milk".to_string());
+/// assert_eq!(runs.get(), 2);
+/// assert_eq!(todos.len(), 2);
+/// # }).dispose();
+/// ```
+pub fn create_signal_map<K, V>(cx: Scope) -> SignalMap<K, V>
+where
+    K: Eq + Hash + Clone + 'static,
+    V: 'static,
+{
+    SignalMap {
+        cx,
+        entries: Default::default(),
+        keys: create_rw_signal(cx, Vec::new()),
+    }
+}
+
+/// A reactive keyed collection, created with [create_signal_map].
+///
+/// Each key has its own signal, which is created the first time the key is read or inserted
+/// and lives as long as the [Scope] the map was created in, so that an effect that read a key
+/// before it was inserted is notified when it is.
+pub struct SignalMap<K, V>
+where
+    K: 'static,
+    V: 'static,
+{
+    cx: Scope,
+    entries: Rc<RefCell<HashMap<K, RwSignal<Option<V>>>>>,
+    keys: RwSignal<Vec<K>>,
+}
+
+impl<K, V> SignalMap<K, V>
+where
+    K: Eq + Hash + Clone + 'static,
+    V: 'static,
+{
+    /// Clones and returns the value for `key`, subscribing to changes to that key only.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.entry(key).get()
+    }
+
+    /// Applies a function to the value for `key`, subscribing to changes to that key only.
+    /// Returns [Option::None] if the key is not present.
+    pub fn with<U>(&self, key: &K, f: impl FnOnce(&V) -> U) -> Option<U> {
+        self.entry(key).with(|value| value.as_ref().map(f))
+    }
+
+    /// Returns whether `key` is present, subscribing to changes to that key only.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entry(key).with(Option::is_some)
+    }
+
+    /// Inserts a value, notifying the subscribers of `key` and, if the key is new, the
+    /// subscribers of [SignalMap::keys] and [SignalMap::len]. Returns the previous value.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let entry = self.entry(&key);
+        let mut prev = None;
+        entry.update(|current| prev = current.replace(value));
+        if prev.is_none() {
+            self.keys.update(|keys| keys.push(key));
+        }
+        prev
+    }
+
+    /// Removes a value, notifying the subscribers of `key`, [SignalMap::keys], and
+    /// [SignalMap::len]. Returns the removed value, or [Option::None] if there was none.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let entry = self.entries.borrow().get(key).copied()?;
+        if entry.with_untracked(Option::is_none) {
+            return None;
+        }
+        let mut prev = None;
+        entry.update(|current| prev = current.take());
+        self.keys.update(|keys| keys.retain(|k| k != key));
+        prev
+    }
+
+    /// Mutates the value for `key` in place, notifying the subscribers of that key only.
+    /// Does nothing if the key is not present.
+    pub fn update(&self, key: &K, f: impl FnOnce(&mut V)) {
+        let entry = self.entries.borrow().get(key).copied();
+        if let Some(entry) = entry {
+            if entry.with_untracked(Option::is_some) {
+                entry.update(|value| {
+                    if let Some(value) = value {
+                        f(value)
+                    }
+                });
+            }
+        }
+    }
+
+    /// Returns the keys in the order they were inserted, subscribing to entries being
+    /// added or removed but not to changes to their values.
+    pub fn keys(&self) -> Vec<K> {
+        self.keys.get()
+    }
+
+    /// Returns the number of entries, subscribing to entries being added or removed.
+    pub fn len(&self) -> usize {
+        self.keys.with(Vec::len)
+    }
+
+    /// Returns whether the map is empty, subscribing to entries being added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.keys.with(Vec::is_empty)
+    }
+
+    /// Removes every entry, notifying the subscribers of each key that was present.
+    pub fn clear(&self) {
+        for key in self.keys.get_untracked() {
+            self.remove(&key);
+        }
+    }
+
+    /// The signal for `key`, created if it doesn't exist yet.
+    fn entry(&self, key: &K) -> RwSignal<Option<V>> {
+        let cx = self.cx;
+        *self
+            .entries
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| create_rw_signal(cx, None))
+    }
+}
+
+impl<K, V> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            cx: self.cx,
+            entries: Rc::clone(&self.entries),
+            keys: self.keys,
+        }
+    }
+}
+
+impl<K, V> Debug for SignalMap<K, V>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignalMap")
+            .field("keys", &self.keys)
+            .finish()
+    }
+}
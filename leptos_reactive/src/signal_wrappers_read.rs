@@ -28,11 +28,17 @@ use crate::{Memo, ReadSignal, RwSignal, Scope, UntrackedGettableSignal};
 /// assert_eq!(above_3(&memoized_double_count.into()), true);
 /// # });
 /// ```
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Signal<T>(SignalTypes<T>)
 where
     T: 'static;
 
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
 /// Please note that using `Signal::with_untracked` still clones the inner value,
 /// so there's no benefit to using it as opposed to calling
 /// `Signal::get_untracked`.
@@ -160,6 +166,55 @@ where
             SignalTypes::DerivedSignal(_, s) => s(),
         }
     }
+
+    /// Creates a derived signal that applies `f` to the value of this one.
+    ///
+    /// Like any derived signal, `f` runs each time the new signal is read. If it is
+    /// expensive, or the result is read in many places, use a [Memo](crate::Memo) instead.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(create_runtime(), |cx| {
+    /// let (name, set_name) = create_signal(cx, "Alice".to_string());
+    /// let name = Signal::from(name);
+    /// let len = name.map(cx, |name| name.len());
+    ///
+    /// assert_eq!(len(), 5);
+    /// set_name("Bob".to_string());
+    /// assert_eq!(len(), 3);
+    /// # }).dispose();
+    /// ```
+    pub fn map<U>(&self, cx: Scope, f: impl Fn(&T) -> U + 'static) -> Signal<U>
+    where
+        U: 'static,
+    {
+        let this = self.clone();
+        Signal::derive(cx, move || this.with(&f))
+    }
+
+    /// Creates a derived signal that pairs the value of this signal with the value of `other`.
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(create_runtime(), |cx| {
+    /// let (first, set_first) = create_signal(cx, "Ada".to_string());
+    /// let (last, _) = create_signal(cx, "Lovelace".to_string());
+    /// let full_name = Signal::from(first)
+    ///     .zip(cx, last)
+    ///     .map(cx, |(first, last)| format!("{first} {last}"));
+    ///
+    /// assert_eq!(full_name(), "Ada Lovelace");
+    /// set_first("Augusta".to_string());
+    /// assert_eq!(full_name(), "Augusta Lovelace");
+    /// # }).dispose();
+    /// ```
+    pub fn zip<U>(&self, cx: Scope, other: impl IntoSignal<U>) -> Signal<(T, U)>
+    where
+        T: Clone,
+        U: Clone + 'static,
+    {
+        let this = self.clone();
+        let other = other.into_signal(cx);
+        Signal::derive(cx, move || (this.get(), other.get()))
+    }
 }
 
 impl<T> From<ReadSignal<T>> for Signal<T> {
@@ -180,7 +235,90 @@ impl<T> From<Memo<T>> for Signal<T> {
     }
 }
 
-#[derive(Clone)]
+/// Converts anything that can be read reactively into a [Signal]: a [ReadSignal],
+/// [RwSignal], [Memo], [Signal], [MaybeSignal], or a closure that reads other signals.
+///
+/// Taking an `impl IntoSignal<T>` lets a function or component accept all of these with a
+/// single argument. Plain values can be passed as a [MaybeSignal], e.g., `MaybeSignal::from(5)`.
+///
+/// Without the `stable` feature, signals can be called like closures, so they are converted
+/// by wrapping them in a derived signal.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// fn is_even(cx: Scope, value: impl IntoSignal<i32>) -> Signal<bool> {
+///     value.into_signal(cx).map(cx, |value| value % 2 == 0)
+/// }
+///
+/// let (count, set_count) = create_signal(cx, 2);
+/// let double = create_memo(cx, move |_| count() * 2);
+///
+/// assert!(is_even(cx, count)());
+/// assert!(is_even(cx, double)());
+/// assert!(!is_even(cx, move || count() + 1)());
+/// assert!(!is_even(cx, MaybeSignal::from(7))());
+/// # }).dispose();
+/// ```
+pub trait IntoSignal<T>
+where
+    T: 'static,
+{
+    /// Converts `self` into a [Signal]. Closures become derived signals that belong to `cx`.
+    fn into_signal(self, cx: Scope) -> Signal<T>;
+}
+
+impl<T, F> IntoSignal<T> for F
+where
+    T: 'static,
+    F: Fn() -> T + 'static,
+{
+    fn into_signal(self, cx: Scope) -> Signal<T> {
+        Signal::derive(cx, self)
+    }
+}
+
+#[cfg(feature = "stable")]
+impl<T> IntoSignal<T> for Signal<T> {
+    fn into_signal(self, _cx: Scope) -> Signal<T> {
+        self
+    }
+}
+
+#[cfg(feature = "stable")]
+impl<T> IntoSignal<T> for ReadSignal<T> {
+    fn into_signal(self, _cx: Scope) -> Signal<T> {
+        self.into()
+    }
+}
+
+#[cfg(feature = "stable")]
+impl<T> IntoSignal<T> for RwSignal<T> {
+    fn into_signal(self, _cx: Scope) -> Signal<T> {
+        self.into()
+    }
+}
+
+#[cfg(feature = "stable")]
+impl<T> IntoSignal<T> for Memo<T> {
+    fn into_signal(self, _cx: Scope) -> Signal<T> {
+        self.into()
+    }
+}
+
+#[cfg(feature = "stable")]
+impl<T> IntoSignal<T> for MaybeSignal<T>
+where
+    T: Clone,
+{
+    fn into_signal(self, cx: Scope) -> Signal<T> {
+        match self {
+            Self::Static(value) => Signal::derive(cx, move || value.clone()),
+            Self::Dynamic(signal) => signal,
+        }
+    }
+}
+
 enum SignalTypes<T>
 where
     T: 'static,
@@ -190,6 +328,16 @@ where
     DerivedSignal(Scope, Rc<dyn Fn() -> T>),
 }
 
+impl<T> Clone for SignalTypes<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::ReadSignal(signal) => Self::ReadSignal(*signal),
+            Self::Memo(memo) => Self::Memo(*memo),
+            Self::DerivedSignal(cx, f) => Self::DerivedSignal(*cx, Rc::clone(f)),
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for SignalTypes<T>
 where
     T: std::fmt::Debug,
@@ -407,6 +555,30 @@ where
             Self::Dynamic(signal) => signal.get(),
         }
     }
+
+    /// Applies `f` to the value: a static value is mapped right away, and a signal is mapped
+    /// with [Signal::map].
+    /// ```
+    /// # use leptos_reactive::*;
+    /// # create_scope(create_runtime(), |cx| {
+    /// let (count, set_count) = create_signal(cx, 2);
+    /// let label = |value: MaybeSignal<i32>| value.map(cx, |n| format!("{n} items"));
+    ///
+    /// assert_eq!(label(3.into()).get(), "3 items");
+    /// let dynamic = label(count.into());
+    /// set_count(4);
+    /// assert_eq!(dynamic.get(), "4 items");
+    /// # }).dispose();
+    /// ```
+    pub fn map<U>(&self, cx: Scope, f: impl Fn(&T) -> U + 'static) -> MaybeSignal<U>
+    where
+        U: 'static,
+    {
+        match &self {
+            Self::Static(value) => MaybeSignal::Static(f(value)),
+            Self::Dynamic(signal) => MaybeSignal::Dynamic(signal.map(cx, f)),
+        }
+    }
 }
 
 impl<T> From<T> for MaybeSignal<T> {
@@ -433,6 +605,12 @@ impl<T> From<Memo<T>> for MaybeSignal<T> {
     }
 }
 
+impl<T> From<Signal<T>> for MaybeSignal<T> {
+    fn from(value: Signal<T>) -> Self {
+        Self::Dynamic(value)
+    }
+}
+
 #[cfg(not(feature = "stable"))]
 impl<T> FnOnce<()> for MaybeSignal<T>
 where
@@ -0,0 +1,71 @@
+use crate::{
+    create_isomorphic_effect, create_signal, ReadSignal, RwSignal, Scope, SignalSetter,
+    UntrackedGettableSignal,
+};
+
+/// Derives a two-way slice of a [RwSignal]: a [ReadSignal] holding one part of its value,
+/// and a [SignalSetter] that writes that part back into it.
+///
+/// The slice only notifies its subscribers when the part returned by `getter` actually
+/// changes, so writing to other parts of the signal doesn't re-run effects that read the
+/// slice. `setter` receives the whole value and the new part, and updates the signal.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// # create_scope(create_runtime(), |cx| {
+/// #[derive(Clone, Default)]
+/// struct State {
+///     name: String,
+///     count: i32,
+/// }
+///
+/// let state = create_rw_signal(cx, State::default());
+/// let (count, set_count) = create_slice(
+///     cx,
+///     state,
+///     |state| state.count,
+///     |state, count| state.count = count,
+/// );
+///
+/// let runs = Rc::new(Cell::new(0));
+/// create_isomorphic_effect(cx, {
+///     let runs = Rc::clone(&runs);
+///     move |_| {
+///         _ = count.get();
+///         runs.set(runs.get() + 1);
+///     }
+/// });
+/// assert_eq!(runs.get(), 1);
+///
+/// // writing another field doesn't notify the slice
+/// state.update(|state| state.name = "Alice".to_string());
+/// assert_eq!(runs.get(), 1);
+///
+/// set_count.set(2);
+/// assert_eq!(runs.get(), 2);
+/// assert_eq!(state.with(|state| state.count), 2);
+/// # }).dispose();
+/// ```
+pub fn create_slice<T, O>(
+    cx: Scope,
+    signal: RwSignal<T>,
+    getter: impl Fn(&T) -> O + 'static,
+    setter: impl Fn(&mut T, O) + 'static,
+) -> (ReadSignal<O>, SignalSetter<O>)
+where
+    T: 'static,
+    O: PartialEq + 'static,
+{
+    let (slice, set_slice) = create_signal(cx, signal.with_untracked(&getter));
+
+    create_isomorphic_effect(cx, move |_| {
+        let next = signal.with(&getter);
+        if slice.with_untracked(|current| *current != next) {
+            set_slice.set(next);
+        }
+    });
+
+    let set = SignalSetter::map(cx, move |value| signal.update(|whole| setter(whole, value)));
+    (slice, set)
+}
@@ -0,0 +1,250 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    rc::Rc,
+};
+
+use cfg_if::cfg_if;
+
+use crate::{
+    debug_warn, on_cleanup, ReadSignal, RwSignal, Scope, Serializable, SerializationError,
+    UntrackedGettableSignal, WriteSignal,
+};
+
+/// A registry of signals whose values can be saved all at once and restored later, e.g., to
+/// keep the state of an app in `sessionStorage` across a reload or a hot-reload in development.
+///
+/// Signals are added with [StateSnapshot::register] under a key that identifies them across
+/// reloads. [StateSnapshot::save] serializes the current value of each of them, and
+/// [StateSnapshot::restore] sets each of them back to its saved value. A saved value whose
+/// signal hasn't been registered yet is kept, and is restored as soon as a signal is
+/// registered under its key, so the snapshot can be restored when the app starts, before the
+/// components that create the signals have run.
+///
+/// Values are serialized with [Serializable], so the types must support the serialization
+/// crate selected by this crate's features.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let snapshot = StateSnapshot::new();
+///
+/// let (count, set_count) = create_signal(cx, 0);
+/// let name = create_rw_signal(cx, String::from("Alice"));
+/// snapshot.register(cx, "count", count, set_count);
+/// snapshot.register_rw(cx, "name", name);
+///
+/// set_count.set(3);
+/// let saved = snapshot.save().unwrap();
+///
+/// // e.g., after a reload
+/// let restored = StateSnapshot::new();
+/// restored.restore(&saved).unwrap();
+/// let (count, set_count) = create_signal(cx, 0);
+/// restored.register(cx, "count", count, set_count);
+/// assert_eq!(count.get(), 3);
+/// # }).dispose();
+/// ```
+#[derive(Clone, Default)]
+pub struct StateSnapshot {
+    inner: Rc<RefCell<SnapshotState>>,
+}
+
+#[derive(Default)]
+struct SnapshotState {
+    entries: HashMap<String, SnapshotEntry>,
+    /// Saved values that have been restored before their signals were registered.
+    pending: HashMap<String, String>,
+    next_id: usize,
+}
+
+type SaveFn = dyn Fn() -> Result<String, SerializationError>;
+type RestoreFn = dyn Fn(&str) -> Result<(), SerializationError>;
+
+struct SnapshotEntry {
+    /// Distinguishes this registration from a later one under the same key.
+    id: usize,
+    save: Rc<SaveFn>,
+    restore: Rc<RestoreFn>,
+}
+
+impl StateSnapshot {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a signal to the snapshot under `key`, replacing any signal that was already
+    /// registered under it. The signal is removed again when `cx` is disposed.
+    ///
+    /// If a value for `key` has been restored but not yet applied, the signal is set to it.
+    pub fn register<T>(
+        &self,
+        cx: Scope,
+        key: impl Into<String>,
+        read: ReadSignal<T>,
+        write: WriteSignal<T>,
+    ) where
+        T: Serializable + 'static,
+    {
+        self.insert(
+            cx,
+            key.into(),
+            Rc::new(move || read.with_untracked(|value| value.to_json())),
+            Rc::new(move |json| {
+                write.set(T::from_json(json)?);
+                Ok(())
+            }),
+        );
+    }
+
+    /// Adds an [RwSignal] to the snapshot under `key`, like [StateSnapshot::register].
+    pub fn register_rw<T>(&self, cx: Scope, key: impl Into<String>, signal: RwSignal<T>)
+    where
+        T: Serializable + 'static,
+    {
+        self.register(cx, key, signal.read_only(), signal.write_only());
+    }
+
+    fn insert(&self, cx: Scope, key: String, save: Rc<SaveFn>, restore: Rc<RestoreFn>) {
+        let pending = {
+            let mut state = self.inner.borrow_mut();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.entries.insert(
+                key.clone(),
+                SnapshotEntry {
+                    id,
+                    save,
+                    restore: Rc::clone(&restore),
+                },
+            );
+
+            on_cleanup(cx, {
+                let inner = Rc::clone(&self.inner);
+                let key = key.clone();
+                move || {
+                    let mut state = inner.borrow_mut();
+                    if state.entries.get(&key).map(|entry| entry.id) == Some(id) {
+                        state.entries.remove(&key);
+                    }
+                }
+            });
+
+            state.pending.remove(&key)
+        };
+
+        if let Some(json) = pending {
+            #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+            if let Err(e) = restore(&json) {
+                debug_warn!("[StateSnapshot::register] could not restore {key:?}: {e}");
+            }
+        }
+    }
+
+    /// Serializes the current values of all the registered signals, without subscribing to
+    /// them. Values that have been restored but not applied yet are included as well, so
+    /// saving doesn't lose the state of signals that haven't been created yet.
+    pub fn save(&self) -> Result<String, SerializationError> {
+        let state = self.inner.borrow();
+        let mut values = state
+            .pending
+            .iter()
+            .map(|(key, json)| (key.clone(), json.clone()))
+            .collect::<BTreeMap<_, _>>();
+        for (key, entry) in state.entries.iter() {
+            values.insert(key.clone(), (entry.save)()?);
+        }
+        serde_json::to_string(&values).map_err(|e| SerializationError::Serialize(Rc::new(e)))
+    }
+
+    /// Sets the registered signals to the values in `snapshot`, which was returned by
+    /// [StateSnapshot::save]. Values for keys that haven't been registered yet are kept until
+    /// they are; registered signals that are missing from `snapshot` keep their values.
+    pub fn restore(&self, snapshot: &str) -> Result<(), SerializationError> {
+        let values: BTreeMap<String, String> = serde_json::from_str(snapshot)
+            .map_err(|e| SerializationError::Deserialize(Rc::new(e)))?;
+
+        let mut to_restore = Vec::new();
+        {
+            let mut state = self.inner.borrow_mut();
+            for (key, json) in values {
+                if state.entries.contains_key(&key) {
+                    to_restore.push((key, json));
+                } else {
+                    state.pending.insert(key, json);
+                }
+            }
+        }
+
+        // signals are set without holding the borrow, as setting them runs effects that may
+        // register more signals
+        for (key, json) in to_restore {
+            let restore = self
+                .inner
+                .borrow()
+                .entries
+                .get(&key)
+                .map(|entry| Rc::clone(&entry.restore));
+            if let Some(restore) = restore {
+                restore(&json)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the snapshot to the browser's `sessionStorage` under `key`.
+    ///
+    /// Does nothing on the server, or if `sessionStorage` is not available.
+    pub fn save_to_session_storage(&self, key: &str) -> Result<(), SerializationError> {
+        let snapshot = self.save()?;
+        cfg_if! {
+            if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+                if let Some(storage) = session_storage() {
+                    _ = storage.set_item(key, &snapshot);
+                }
+            } else {
+                _ = (key, snapshot);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the snapshot that was saved under `key` in the browser's `sessionStorage`
+    /// with [StateSnapshot::save_to_session_storage], if there is one.
+    ///
+    /// Does nothing on the server, or if `sessionStorage` is not available.
+    pub fn restore_from_session_storage(&self, key: &str) -> Result<(), SerializationError> {
+        cfg_if! {
+            if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+                let snapshot =
+                    session_storage().and_then(|storage| storage.get_item(key).ok().flatten());
+                match snapshot {
+                    Some(snapshot) => self.restore(&snapshot),
+                    None => Ok(()),
+                }
+            } else {
+                _ = key;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn session_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok().flatten()
+}
+
+impl Debug for StateSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.inner.borrow();
+        let mut keys = state.entries.keys().collect::<Vec<_>>();
+        keys.sort();
+        f.debug_struct("StateSnapshot")
+            .field("keys", &keys)
+            .field("pending", &state.pending.len())
+            .finish()
+    }
+}
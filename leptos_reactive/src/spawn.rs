@@ -1,5 +1,7 @@
+use crate::{on_cleanup, Scope};
 use cfg_if::cfg_if;
-use std::future::Future;
+use futures::future::{AbortHandle, Abortable};
+use std::{future::Future, time::Duration};
 
 cfg_if! {
     if #[cfg(any(feature = "csr", feature = "hydrate"))] {
@@ -16,6 +18,13 @@ cfg_if! {
         extern "C" {
             fn microtask(task: wasm_bindgen::JsValue);
         }
+
+        #[wasm_bindgen::prelude::wasm_bindgen(
+            inline_js = "export function idle(f) { if (window.requestIdleCallback) { requestIdleCallback(f); } else { setTimeout(f, 0); } }"
+        )]
+        extern "C" {
+            fn idle(task: wasm_bindgen::JsValue);
+        }
     } else {
         /// Exposes the [queueMicrotask](https://developer.mozilla.org/en-US/docs/Web/API/queueMicrotask) method
         /// in the browser, and simply runs the given function when on the server.
@@ -26,6 +35,42 @@ cfg_if! {
     }
 }
 
+/// Runs the given function when the browser is idle, using
+/// [requestIdleCallback](https://developer.mozilla.org/en-US/docs/Web/API/Window/requestIdleCallback)
+/// where it is supported and `setTimeout` elsewhere, and simply runs it immediately when on
+/// the server.
+pub(crate) fn queue_idle_task(task: impl FnOnce() + 'static) {
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            idle(wasm_bindgen::closure::Closure::once_into_js(task));
+        } else {
+            task();
+        }
+    }
+}
+
+/// Runs the given function after `delay` using
+/// [setTimeout](https://developer.mozilla.org/en-US/docs/Web/API/setTimeout) in the browser,
+/// and simply runs it immediately when on the server, where nothing is waiting to be redrawn.
+pub(crate) fn set_timeout(task: impl FnOnce() + 'static, delay: Duration) {
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            use wasm_bindgen::JsCast;
+
+            let task = wasm_bindgen::closure::Closure::once_into_js(task);
+            _ = web_sys::window()
+                .expect("there to be a window")
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    task.unchecked_ref(),
+                    delay.as_millis().try_into().unwrap_or(i32::MAX),
+                );
+        } else {
+            _ = delay;
+            task();
+        }
+    }
+}
+
 /// Spawns and runs a thread-local [std::future::Future] in a platform-independent way.
 ///
 /// This can be used to interface with any `async` code.
@@ -46,3 +91,33 @@ where
         }
     }
 }
+
+/// Spawns a thread-local [std::future::Future] like [spawn_local], but ties it to a [Scope]:
+/// if the scope is disposed before the future has finished, the future is dropped at its next
+/// `.await` point instead of running to completion.
+///
+/// This prevents a future that outlives its component, like a slow request, from reading or
+/// writing signals that have already been disposed.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (data, set_data) = create_signal(cx, None);
+///
+/// spawn_local_scoped(cx, async move {
+///     let response = async { "fetched".to_string() }.await;
+///     // if `cx` was disposed while waiting, this never runs
+///     set_data.set(Some(response));
+/// });
+/// # }).dispose();
+/// ```
+pub fn spawn_local_scoped<F>(cx: Scope, fut: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    on_cleanup(cx, move || abort_handle.abort());
+    spawn_local(async move {
+        _ = Abortable::new(fut, abort_registration).await;
+    });
+}
@@ -0,0 +1,173 @@
+use std::{cell::RefCell, collections::HashSet};
+
+use slotmap::{Key, SecondaryMap, SparseSecondaryMap};
+
+use crate::{runtime::with_runtime, EffectId, Scope, ScopeId, ScopeProperty, SignalId};
+
+/// Counts the live nodes in the reactive runtime `cx` belongs to, by type.
+///
+/// Logging this periodically is an easy way to notice a leak in a long-lived app: counts that
+/// keep growing as the user navigates back and forth usually mean something is never disposed.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (count, _) = create_signal(cx, 0);
+/// let double = create_memo(cx, move |_| count.get() * 2);
+/// create_isomorphic_effect(cx, move |_| double.get());
+///
+/// let stats = runtime_stats(cx);
+/// assert_eq!(stats.signals, 1);
+/// assert_eq!(stats.memos, 1);
+/// assert_eq!(stats.effects, 1);
+/// assert_eq!(stats.unowned, 0);
+/// # }).dispose();
+/// ```
+pub fn runtime_stats(cx: Scope) -> RuntimeStats {
+    with_runtime(cx.runtime, |runtime| {
+        let scopes = runtime.scopes.borrow();
+        let mut owned_signals = HashSet::new();
+        let mut owned_effects = HashSet::new();
+        for (_, properties) in scopes.iter() {
+            for property in properties.borrow().iter() {
+                match property {
+                    ScopeProperty::Signal(id) => {
+                        owned_signals.insert(*id);
+                    }
+                    ScopeProperty::Effect(id) => {
+                        owned_effects.insert(*id);
+                    }
+                    ScopeProperty::Resource(_) => {}
+                }
+            }
+        }
+
+        let signals = runtime.signals.borrow();
+        let effects = runtime.effects.borrow();
+        let memos = runtime
+            .memos
+            .borrow()
+            .iter()
+            .filter(|(effect, signal)| {
+                effects.contains_key(*effect) && signals.contains_key(**signal)
+            })
+            .count();
+        let unowned = signals
+            .keys()
+            .filter(|id| !owned_signals.contains(id))
+            .count()
+            + effects
+                .keys()
+                .filter(|id| !owned_effects.contains(id))
+                .count();
+
+        RuntimeStats {
+            scopes: scopes.len(),
+            signals: signals.len() - memos,
+            memos,
+            effects: effects.len() - memos,
+            resources: runtime.resources.borrow().len(),
+            unowned,
+        }
+    })
+}
+
+/// Reclaims the bookkeeping that the reactive runtime `cx` belongs to keeps about nodes that
+/// have been disposed, like subscriptions of disposed effects, the contexts of disposed scopes,
+/// and the names given with [create_signal_named](crate::create_signal_named).
+///
+/// Disposing a [Scope] frees its signals and effects, but some of this bookkeeping is only
+/// removed lazily, so a long-lived app that creates and disposes many scopes can call this
+/// now and then, e.g., after navigating. Returns the number of entries that were removed.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// let (count, _) = create_signal(cx, 0);
+/// cx.child_scope(|cx| {
+///     create_isomorphic_effect(cx, move |_| count.get());
+/// })
+/// .dispose();
+///
+/// // `count` still lists the disposed effect as a subscriber
+/// assert!(compact_runtime(cx) > 0);
+/// assert_eq!(compact_runtime(cx), 0);
+/// # }).dispose();
+/// ```
+pub fn compact_runtime(cx: Scope) -> usize {
+    with_runtime(cx.runtime, |runtime| {
+        let signals = runtime.signals.borrow();
+        let effects = runtime.effects.borrow();
+        let scopes = runtime.scopes.borrow();
+        let is_signal = |id: SignalId| signals.contains_key(id);
+        let is_effect = |id: EffectId| effects.contains_key(id);
+        let is_scope = |id: ScopeId| scopes.contains_key(id);
+        let mut removed = 0;
+
+        for subscribers in runtime.signal_subscribers.borrow_mut().values_mut() {
+            let subscribers = subscribers.get_mut();
+            let before = subscribers.len();
+            subscribers.retain(|effect| is_effect(*effect));
+            removed += before - subscribers.len();
+        }
+        for children in runtime.scope_children.borrow_mut().values_mut() {
+            let before = children.len();
+            children.retain(|child| is_scope(*child));
+            removed += before - children.len();
+        }
+
+        removed += retain(&runtime.signal_subscribers, is_signal);
+        removed += retain(&runtime.effect_sources, is_effect);
+        removed += retain(&runtime.signal_names, is_signal);
+        removed += retain(&runtime.effect_names, is_effect);
+        removed += retain(&runtime.effect_priorities, is_effect);
+        removed += retain(&runtime.memos, is_effect);
+        #[cfg(feature = "debug-graph")]
+        {
+            removed += retain(&runtime.signal_types, is_signal);
+            removed += retain(&runtime.effect_types, is_effect);
+        }
+        removed += retain_sparse(&runtime.scope_parents, is_scope);
+        removed += retain_sparse(&runtime.scope_contexts, is_scope);
+        removed += retain_sparse(&runtime.scope_children, is_scope);
+        removed += retain_sparse(&runtime.scope_cleanups, is_scope);
+
+        removed
+    })
+}
+
+/// Removes the entries for keys that are no longer live, returning how many there were.
+fn retain<K: Key, V>(map: &RefCell<SecondaryMap<K, V>>, live: impl Fn(K) -> bool) -> usize {
+    let mut map = map.borrow_mut();
+    let before = map.len();
+    map.retain(|id, _| live(id));
+    before - map.len()
+}
+
+fn retain_sparse<K: Key, V>(
+    map: &RefCell<SparseSecondaryMap<K, V>>,
+    live: impl Fn(K) -> bool,
+) -> usize {
+    let mut map = map.borrow_mut();
+    let before = map.len();
+    map.retain(|id, _| live(id));
+    before - map.len()
+}
+
+/// The number of live nodes in a reactive runtime, returned by [runtime_stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeStats {
+    /// Scopes that have not been disposed.
+    pub scopes: usize,
+    /// Signals, not counting the ones that hold the values of memos.
+    pub signals: usize,
+    /// Memos.
+    pub memos: usize,
+    /// Effects, not counting the ones that compute memos.
+    pub effects: usize,
+    /// Resources.
+    pub resources: usize,
+    /// Signals and effects that don't belong to any [Scope], so will only be disposed
+    /// along with the runtime.
+    pub unowned: usize,
+}
@@ -0,0 +1,282 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc};
+
+use crate::{create_rw_signal, RwSignal, Scope};
+
+/// Creates a [Store], a reactive container for a struct in which each field can be
+/// tracked independently.
+///
+/// Reading a whole [Store] with [Store::get] or [Store::with] works like reading a signal.
+/// The fields of a struct that derives `Store` can also be read individually, as
+/// [StoreField]s, which only notify their subscribers when that field (or a field nested
+/// inside it, or the whole struct) is written. Updating `store.user().name()` doesn’t wake
+/// effects that only read `store.user().email()`.
+///
+/// Fields can also be accessed without the derive macro, with [StorePath::field]:
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// # create_scope(create_runtime(), |cx| {
+/// #[derive(Clone, Default)]
+/// struct Form {
+///     name: String,
+///     email: String,
+/// }
+///
+/// let form = create_store(cx, Form::default());
+/// let name = form.field("name", |f| &f.name, |f| &mut f.name);
+/// let email = form.field("email", |f| &f.email, |f| &mut f.email);
+///
+/// let runs = Rc::new(Cell::new(0));
+/// create_isomorphic_effect(cx, {
+///     let name = name.clone();
+///     let runs = Rc::clone(&runs);
+///     move |_| {
+///         _ = name.get();
+///         runs.set(runs.get() + 1);
+///     }
+/// });
+/// assert_eq!(runs.get(), 1);
+///
+/// // writing another field doesn't notify the effect
+/// email.set("alice@example.com".to_string());
+/// assert_eq!(runs.get(), 1);
+///
+/// name.set("Alice".to_string());
+/// assert_eq!(runs.get(), 2);
+/// assert_eq!(form.with(|f| f.name.clone()), "Alice");
+/// # }).dispose();
+/// ```
+pub fn create_store<T>(cx: Scope, value: T) -> Store<T>
+where
+    T: 'static,
+{
+    Store {
+        cx,
+        value: Rc::new(RefCell::new(value)),
+        triggers: Default::default(),
+    }
+}
+
+/// A reactive container for a struct, whose fields can be tracked independently.
+/// Created with [create_store].
+pub struct Store<T>
+where
+    T: 'static,
+{
+    cx: Scope,
+    value: Rc<RefCell<T>>,
+    triggers: Rc<RefCell<HashMap<String, RwSignal<()>>>>,
+}
+
+/// A part of a [Store], which only notifies its subscribers when it is written to, or when a
+/// field that contains it or one it contains is written to.
+///
+/// Created with [StorePath::field], or with the accessors generated by `#[derive(Store)]`.
+pub struct StoreField<Root, T>
+where
+    Root: 'static,
+    T: 'static,
+{
+    store: Store<Root>,
+    path: String,
+    read: Rc<dyn Fn(&Root) -> &T>,
+    write: Rc<dyn Fn(&mut Root) -> &mut T>,
+}
+
+/// Implemented by [Store] and [StoreField], so that fields can be accessed at any depth.
+///
+/// `#[derive(Store)]` generates a trait that calls [StorePath::field] for each field of a
+/// struct, and implements it for everything that implements `StorePath<Root, ThatStruct>`.
+pub trait StorePath<Root, T> {
+    /// Returns a [StoreField] for a field of this value, named `name`, that can be
+    /// tracked independently from the other fields.
+    fn field<F>(
+        &self,
+        name: &str,
+        read: fn(&T) -> &F,
+        write: fn(&mut T) -> &mut F,
+    ) -> StoreField<Root, F>
+    where
+        F: 'static;
+}
+
+impl<T> Store<T>
+where
+    T: 'static,
+{
+    /// Clones and returns the whole value, subscribing to changes to any field.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Applies a function to the whole value, subscribing to changes to any field.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.track("");
+        f(&self.value.borrow())
+    }
+
+    /// Replaces the whole value, notifying the subscribers of every field.
+    pub fn set(&self, value: T) {
+        self.update(|n| *n = value);
+    }
+
+    /// Mutates the whole value in place, notifying the subscribers of every field.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.borrow_mut());
+        self.notify("");
+    }
+
+    fn track(&self, path: &str) {
+        let cx = self.cx;
+        let trigger = *self
+            .triggers
+            .borrow_mut()
+            .entry(path.to_string())
+            .or_insert_with(|| create_rw_signal(cx, ()));
+        trigger.with(|_| ());
+    }
+
+    /// Notifies the subscribers of `path`, of the fields that contain it, and of the
+    /// fields it contains.
+    fn notify(&self, path: &str) {
+        let prefix = format!("{path}.");
+        let triggers = self
+            .triggers
+            .borrow()
+            .iter()
+            .filter(|(key, _)| {
+                path.is_empty()
+                    || key.is_empty()
+                    || *key == path
+                    || key.starts_with(&prefix)
+                    || path.starts_with(&format!("{key}."))
+            })
+            .map(|(_, trigger)| *trigger)
+            .collect::<Vec<_>>();
+        self.cx.batch(|| {
+            for trigger in triggers {
+                trigger.update(|_| ());
+            }
+        });
+    }
+}
+
+impl<Root, T> StoreField<Root, T>
+where
+    Root: 'static,
+    T: 'static,
+{
+    /// Clones and returns the value of this field, subscribing to changes to it.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Applies a function to the value of this field, subscribing to changes to it.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.store.track(&self.path);
+        f((self.read)(&self.store.value.borrow()))
+    }
+
+    /// Replaces the value of this field, notifying its subscribers.
+    pub fn set(&self, value: T) {
+        self.update(|n| *n = value);
+    }
+
+    /// Mutates the value of this field in place, notifying its subscribers.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f((self.write)(&mut self.store.value.borrow_mut()));
+        self.store.notify(&self.path);
+    }
+}
+
+impl<T> StorePath<T, T> for Store<T>
+where
+    T: 'static,
+{
+    fn field<F>(
+        &self,
+        name: &str,
+        read: fn(&T) -> &F,
+        write: fn(&mut T) -> &mut F,
+    ) -> StoreField<T, F>
+    where
+        F: 'static,
+    {
+        StoreField {
+            store: self.clone(),
+            path: name.to_string(),
+            read: Rc::new(read),
+            write: Rc::new(write),
+        }
+    }
+}
+
+impl<Root, T> StorePath<Root, T> for StoreField<Root, T>
+where
+    Root: 'static,
+    T: 'static,
+{
+    fn field<F>(
+        &self,
+        name: &str,
+        read: fn(&T) -> &F,
+        write: fn(&mut T) -> &mut F,
+    ) -> StoreField<Root, F>
+    where
+        F: 'static,
+    {
+        let parent_read = Rc::clone(&self.read);
+        let parent_write = Rc::clone(&self.write);
+        StoreField {
+            store: self.store.clone(),
+            path: format!("{}.{name}", self.path),
+            read: Rc::new(move |root| read(parent_read(root))),
+            write: Rc::new(move |root| write(parent_write(root))),
+        }
+    }
+}
+
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cx: self.cx,
+            value: Rc::clone(&self.value),
+            triggers: Rc::clone(&self.triggers),
+        }
+    }
+}
+
+impl<Root, T> Clone for StoreField<Root, T> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            path: self.path.clone(),
+            read: Rc::clone(&self.read),
+            write: Rc::clone(&self.write),
+        }
+    }
+}
+
+impl<T> Debug for Store<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store").field("value", &self.value).finish()
+    }
+}
+
+impl<Root, T> Debug for StoreField<Root, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreField")
+            .field("path", &self.path)
+            .finish()
+    }
+}
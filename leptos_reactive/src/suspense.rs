@@ -1,4 +1,14 @@
-use crate::{create_signal, spawn::queue_microtask, ReadSignal, Scope, WriteSignal};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use crate::{
+    create_effect, create_rw_signal, create_signal, create_timeout, spawn::queue_microtask,
+    use_context, ReadSignal, ResourcePriority, RwSignal, Scope, SignalId, TimerHandle,
+    UntrackedGettableSignal, WriteSignal,
+};
 
 /// Tracks [Resource](crate::Resource)s that are read under a suspense context,
 /// i.e., within a [`Suspense`](https://docs.rs/leptos_core/latest/leptos_core/fn.Suspense.html) component.
@@ -7,6 +17,9 @@ pub struct SuspenseContext {
     /// The number of resources that are currently pending.
     pub pending_resources: ReadSignal<usize>,
     set_pending_resources: WriteSignal<usize>,
+    priority: RwSignal<ResourcePriority>,
+    /// Identifies the suspense context this one is nested in, if any.
+    pub(crate) parent: Option<SignalId>,
 }
 
 impl std::hash::Hash for SuspenseContext {
@@ -24,12 +37,31 @@ impl PartialEq for SuspenseContext {
 impl Eq for SuspenseContext {}
 
 impl SuspenseContext {
-    /// Creates an empty suspense context.
+    /// Creates an empty suspense context, nested in the suspense context provided to `cx`,
+    /// if there is one.
     pub fn new(cx: Scope) -> Self {
+        let parent = use_context::<SuspenseContext>(cx).map(|parent| parent.id());
         let (pending_resources, set_pending_resources) = create_signal(cx, 0);
         Self {
             pending_resources,
             set_pending_resources,
+            priority: create_rw_signal(cx, ResourcePriority::Lazy),
+            parent,
+        }
+    }
+
+    pub(crate) fn id(&self) -> SignalId {
+        self.pending_resources.id
+    }
+
+    /// The highest [ResourcePriority] of the resources that have been read under this context.
+    pub fn priority(&self) -> ResourcePriority {
+        self.priority.get_untracked()
+    }
+
+    pub(crate) fn raise_priority(&self, priority: ResourcePriority) {
+        if self.priority.get_untracked() < priority {
+            self.priority.set(priority);
         }
     }
 
@@ -57,4 +89,76 @@ impl SuspenseContext {
             .try_with(|n| *n == 0)
             .unwrap_or(false)
     }
+
+    /// Returns a signal that indicates whether a fallback should be shown for the pending
+    /// resources, so that a fallback is neither flashed for resources that resolve quickly
+    /// nor hidden again right after it appears.
+    ///
+    /// The signal only becomes `true` once resources have been pending for `delay`, and then
+    /// stays `true` for at least `min_duration`, even if they resolve sooner. With zero
+    /// durations, it is `true` exactly while resources are pending.
+    ///
+    /// The timing is only applied in the browser; on the server, the signal is never `true`.
+    pub fn fallback_visible(
+        &self,
+        cx: Scope,
+        delay: Duration,
+        min_duration: Duration,
+    ) -> ReadSignal<bool> {
+        let context = *self;
+        let (visible, set_visible) = create_signal(cx, false);
+        let delay_timer: Rc<RefCell<Option<TimerHandle>>> = Default::default();
+        let min_elapsed = Rc::new(Cell::new(true));
+
+        let show = Rc::new({
+            let min_elapsed = Rc::clone(&min_elapsed);
+            move || {
+                set_visible.set(true);
+                if min_duration.is_zero() {
+                    return;
+                }
+                min_elapsed.set(false);
+                let min_elapsed = Rc::clone(&min_elapsed);
+                create_timeout(cx, min_duration, move || {
+                    min_elapsed.set(true);
+                    if context.ready() {
+                        set_visible.set(false);
+                    }
+                });
+            }
+        });
+
+        create_effect(cx, move |_| {
+            if context.pending_resources.get() > 0 {
+                if visible.get_untracked() || delay_timer.borrow().is_some() {
+                    return;
+                }
+                if delay.is_zero() {
+                    show();
+                } else {
+                    let timer = create_timeout(cx, delay, {
+                        let delay_timer = Rc::clone(&delay_timer);
+                        let show = Rc::clone(&show);
+                        move || {
+                            delay_timer.borrow_mut().take();
+                            if !context.ready() {
+                                show();
+                            }
+                        }
+                    });
+                    *delay_timer.borrow_mut() = Some(timer);
+                }
+            } else {
+                if let Some(timer) = delay_timer.borrow_mut().take() {
+                    timer.cancel();
+                }
+                // otherwise, the fallback is hidden once it has been shown for long enough
+                if visible.get_untracked() && min_elapsed.get() {
+                    set_visible.set(false);
+                }
+            }
+        });
+
+        visible
+    }
 }
@@ -0,0 +1,195 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc, time::Duration};
+
+use cfg_if::cfg_if;
+
+use crate::{on_cleanup, Scope};
+
+cfg_if! {
+    if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+        use std::cell::RefCell;
+        use wasm_bindgen::{closure::Closure, JsCast};
+    }
+}
+
+/// Runs `callback` once after `delay`, using
+/// [setTimeout](https://developer.mozilla.org/en-US/docs/Web/API/setTimeout) in the browser.
+///
+/// The timeout is cancelled when `cx` is disposed, so a component can schedule work without
+/// it running after the component has been removed. It can also be cancelled earlier with the
+/// returned [TimerHandle].
+///
+/// On the server, where nothing is rendered after the response is sent, this does nothing.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # create_scope(create_runtime(), |cx| {
+/// let (visible, set_visible) = create_signal(cx, true);
+///
+/// // hide a notification after five seconds, unless it is dismissed first
+/// let timeout = create_timeout(cx, Duration::from_secs(5), move || set_visible.set(false));
+/// let dismiss = move || {
+///     timeout.cancel();
+///     set_visible.set(false);
+/// };
+/// # }).dispose();
+/// ```
+pub fn create_timeout(
+    cx: Scope,
+    delay: Duration,
+    callback: impl FnOnce() + 'static,
+) -> TimerHandle {
+    let handle = TimerHandle::new(cx);
+
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            let mut callback = Some(callback);
+            let state = Rc::clone(&handle.0);
+            let closure = Closure::<dyn FnMut()>::new(move || {
+                if !state.cancelled.replace(true) {
+                    if let Some(callback) = callback.take() {
+                        callback();
+                    }
+                }
+            });
+            let id = web_sys::window()
+                .expect("there to be a window")
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    millis(delay),
+                )
+                .expect("could not set timeout");
+            *handle.0.timer.borrow_mut() = Some(BrowserTimer {
+                id,
+                is_interval: false,
+                _closure: closure,
+            });
+        } else {
+            _ = (delay, callback);
+        }
+    }
+
+    handle
+}
+
+/// Runs `callback` every `interval`, using
+/// [setInterval](https://developer.mozilla.org/en-US/docs/Web/API/setInterval) in the browser,
+/// until `cx` is disposed or the returned [TimerHandle] is cancelled.
+///
+/// This is useful for clocks and for polling, which would otherwise keep running after the
+/// component that started them has been removed.
+///
+/// On the server, where nothing is rendered after the response is sent, this does nothing.
+///
+/// ```no_run
+/// # use leptos_reactive::*;
+/// # use std::time::Duration;
+/// # create_scope(create_runtime(), |cx| {
+/// let (seconds, set_seconds) = create_signal(cx, 0);
+///
+/// create_interval(cx, Duration::from_secs(1), move || {
+///     set_seconds.update(|seconds| *seconds += 1)
+/// });
+/// # }).dispose();
+/// ```
+pub fn create_interval(
+    cx: Scope,
+    interval: Duration,
+    callback: impl Fn() + 'static,
+) -> TimerHandle {
+    let handle = TimerHandle::new(cx);
+
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            let state = Rc::clone(&handle.0);
+            let closure = Closure::<dyn FnMut()>::new(move || {
+                if !state.cancelled.get() {
+                    callback();
+                }
+            });
+            let id = web_sys::window()
+                .expect("there to be a window")
+                .set_interval_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    millis(interval),
+                )
+                .expect("could not set interval");
+            *handle.0.timer.borrow_mut() = Some(BrowserTimer {
+                id,
+                is_interval: true,
+                _closure: closure,
+            });
+        } else {
+            _ = (interval, callback);
+        }
+    }
+
+    handle
+}
+
+/// A handle to a timer created with [create_timeout] or [create_interval], which can be used
+/// to cancel it before its [Scope] is disposed.
+#[derive(Clone)]
+pub struct TimerHandle(Rc<TimerState>);
+
+struct TimerState {
+    cancelled: Cell<bool>,
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    timer: RefCell<Option<BrowserTimer>>,
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+struct BrowserTimer {
+    id: i32,
+    is_interval: bool,
+    /// Kept alive until the timer is cancelled, as the browser calls it.
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl TimerHandle {
+    fn new(cx: Scope) -> Self {
+        let handle = Self(Rc::new(TimerState {
+            cancelled: Cell::new(false),
+            #[cfg(any(feature = "csr", feature = "hydrate"))]
+            timer: RefCell::new(None),
+        }));
+        on_cleanup(cx, {
+            let handle = handle.clone();
+            move || handle.cancel()
+        });
+        handle
+    }
+
+    /// Stops the timer, so that its callback won't run again. Does nothing if it has already
+    /// been cancelled, or if a timeout has already run.
+    pub fn cancel(&self) {
+        self.0.cancelled.set(true);
+
+        #[cfg(any(feature = "csr", feature = "hydrate"))]
+        if let Some(timer) = self.0.timer.borrow_mut().take() {
+            if let Some(window) = web_sys::window() {
+                if timer.is_interval {
+                    window.clear_interval_with_handle(timer.id);
+                } else {
+                    window.clear_timeout_with_handle(timer.id);
+                }
+            }
+            // the callback may be the one cancelling its own timer, so it can't be dropped
+            // while it's running
+            crate::queue_microtask(move || drop(timer));
+        }
+    }
+}
+
+impl Debug for TimerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimerHandle")
+            .field("cancelled", &self.0.cancelled.get())
+            .finish()
+    }
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn millis(duration: Duration) -> i32 {
+    duration.as_millis().try_into().unwrap_or(i32::MAX)
+}
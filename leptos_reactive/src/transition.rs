@@ -0,0 +1,80 @@
+use crate::{create_memo, runtime::with_runtime, Memo, Scope, SuspenseContext};
+
+/// Creates a [Transition], which tracks the [Resource](crate::Resource)s that start loading
+/// because of the updates made in [Transition::start], so that the UI can show that an
+/// operation is in progress while it keeps displaying the previous data, like React's
+/// `useTransition`.
+///
+/// Each transition tracks its own operations, so a navigation and a refetch that are
+/// wrapped in different transitions have separate [Transition::pending] signals. To keep
+/// the previous `children` visible while their resources load, read the resources under a
+/// [`Transition`](https://docs.rs/leptos_core/latest/leptos_core/fn.Transition.html) component
+/// rather than a `<Suspense/>`.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # create_scope(create_runtime(), |cx| {
+/// async fn fetch_user(id: u32) -> String {
+///   // pretend we're fetching the user
+///   format!("user {id}")
+/// }
+///
+/// let (user_id, set_user_id) = create_signal(cx, 1);
+/// # if false {
+/// let user = create_resource(cx, user_id, fetch_user);
+/// # }
+///
+/// let transition = create_transition(cx);
+/// let pending = transition.pending();
+///
+/// // `pending` is `true` until the user has been loaded
+/// transition.start(move || set_user_id.set(2));
+/// # }).dispose();
+/// ```
+pub fn create_transition(cx: Scope) -> Transition {
+    let context = SuspenseContext::new(cx);
+    let pending = create_memo(cx, move |_| !context.ready());
+    Transition {
+        cx,
+        context,
+        pending,
+    }
+}
+
+/// Creates a [Transition] and immediately runs `f` in it, like [Transition::start]. The
+/// returned [Transition] tracks only the operations started by `f`.
+pub fn start_transition(cx: Scope, f: impl FnOnce()) -> Transition {
+    let transition = create_transition(cx);
+    transition.start(f);
+    transition
+}
+
+/// Tracks the [Resource](crate::Resource)s that start loading because of the updates made in
+/// [Transition::start], created with [create_transition].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    cx: Scope,
+    context: SuspenseContext,
+    pending: Memo<bool>,
+}
+
+impl Transition {
+    /// Runs `f`, and adds any [Resource](crate::Resource) that starts loading or is refetched
+    /// while it runs, e.g., because `f` sets a signal that is the source of a resource, or
+    /// because the new route it navigates to creates resources, to this transition.
+    pub fn start(&self, f: impl FnOnce()) {
+        with_runtime(self.cx.runtime, |runtime| {
+            runtime.transitions.borrow_mut().push(self.context)
+        });
+        f();
+        with_runtime(self.cx.runtime, |runtime| {
+            runtime.transitions.borrow_mut().pop()
+        });
+    }
+
+    /// Returns a signal that indicates whether any of the resources that were started in this
+    /// transition are still loading.
+    pub fn pending(&self) -> Memo<bool> {
+        self.pending
+    }
+}
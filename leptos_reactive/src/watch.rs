@@ -0,0 +1,87 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use crate::{create_effect, Scope, ScopeDisposer};
+
+/// Runs `callback` whenever the value returned by `deps` changes, passing it the new value
+/// and the previous one.
+///
+/// Unlike an effect, the callback is not tracked: only the signals read in `deps` cause it
+/// to run again. If `immediate` is `true`, the callback also runs right away, with no previous
+/// value; otherwise it first runs after the first change.
+///
+/// The returned [WatchHandle] can be used to stop watching before `cx` is disposed. Like
+/// [create_effect], this does not run on the server.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use std::{cell::RefCell, rc::Rc};
+/// # create_scope(create_runtime(), |cx| {
+/// let (count, set_count) = create_signal(cx, 0);
+/// let log = Rc::new(RefCell::new(Vec::new()));
+///
+/// let handle = watch(
+///     cx,
+///     move || count.get(),
+///     {
+///         let log = Rc::clone(&log);
+///         move |current, previous| log.borrow_mut().push((*current, previous.copied()))
+///     },
+///     false,
+/// );
+///
+/// set_count.set(1);
+/// set_count.set(2);
+/// handle.stop();
+/// set_count.set(3);
+///
+/// # if !cfg!(feature = "ssr") {
+/// assert_eq!(*log.borrow(), [(1, Some(0)), (2, Some(1))]);
+/// # }
+/// # }).dispose();
+/// ```
+pub fn watch<W>(
+    cx: Scope,
+    deps: impl Fn() -> W + 'static,
+    callback: impl Fn(&W, Option<&W>) + 'static,
+    immediate: bool,
+) -> WatchHandle
+where
+    W: 'static,
+{
+    let (_, disposer) = cx.run_child_scope(|cx| {
+        let prev: Rc<RefCell<Option<W>>> = Default::default();
+        create_effect(cx, move |first_run: Option<()>| {
+            let current = deps();
+            let prev_value = prev.borrow_mut().take();
+            if first_run.is_some() || immediate {
+                cx.untrack(|| callback(&current, prev_value.as_ref()));
+            }
+            *prev.borrow_mut() = Some(current);
+        });
+    });
+
+    WatchHandle(Rc::new(RefCell::new(Some(disposer))))
+}
+
+/// Stops a [watch] when [WatchHandle::stop] is called. Dropping the handle does not stop
+/// watching; that happens when the [Scope] it was created in is disposed.
+#[derive(Clone)]
+pub struct WatchHandle(Rc<RefCell<Option<ScopeDisposer>>>);
+
+impl WatchHandle {
+    /// Stops watching, so the callback will not run again.
+    pub fn stop(&self) {
+        let disposer = self.0.borrow_mut().take();
+        if let Some(disposer) = disposer {
+            disposer.dispose();
+        }
+    }
+}
+
+impl Debug for WatchHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchHandle")
+            .field("stopped", &self.0.borrow().is_none())
+            .finish()
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(not(feature = "stable"))]
+use leptos_reactive::{
+    create_isomorphic_effect, create_memo, create_runtime, create_scope, create_signal,
+};
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn batch_runs_effects_once() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let (b, set_b) = create_signal(cx, 2);
+        let (c, set_c) = create_signal(cx, 3);
+        let sum = create_memo(cx, move |_| a() + b() + c());
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        create_isomorphic_effect(cx, {
+            let log = log.clone();
+            move |_| {
+                log.borrow_mut().push(sum());
+            }
+        });
+
+        assert_eq!(*log.borrow(), vec![6]);
+
+        cx.batch(|| {
+            set_a(10);
+            set_b(20);
+            // nested batches are flushed by the outermost one
+            cx.batch(|| set_c(30));
+            assert_eq!(*log.borrow(), vec![6]);
+        });
+
+        assert_eq!(sum(), 60);
+        assert_eq!(*log.borrow(), vec![6, 60]);
+
+        // outside a batch, every update runs its effects
+        set_a(1);
+        set_b(2);
+        assert_eq!(*log.borrow(), vec![6, 60, 51, 33]);
+    })
+    .dispose()
+}
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn batch_ends_when_it_panics() {
+    use std::cell::Cell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let runs = Rc::new(Cell::new(0));
+
+        create_isomorphic_effect(cx, {
+            let runs = runs.clone();
+            move |_| {
+                a();
+                runs.set(runs.get() + 1);
+            }
+        });
+        assert_eq!(runs.get(), 1);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cx.batch(|| {
+                set_a(2);
+                panic!("failed halfway through the batch");
+            })
+        }));
+        assert!(result.is_err());
+        // the effects the batch deferred are dropped
+        assert_eq!(runs.get(), 1);
+
+        // and later updates aren't deferred
+        set_a(3);
+        assert_eq!(runs.get(), 2);
+    })
+    .dispose()
+}
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn batch_that_panics_doesnt_run_effects_while_unwinding() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 0);
+
+        create_isomorphic_effect(cx, move |_| {
+            if a() == 1 {
+                panic!("the effect fails too");
+            }
+        });
+
+        // a second panic while unwinding would abort the process
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cx.batch(|| {
+                set_a(1);
+                panic!("failed halfway through the batch");
+            })
+        }));
+        assert!(result.is_err());
+        set_a(2);
+    })
+    .dispose()
+}
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn batch_updates_memos_before_the_effects_that_read_them() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let (b, set_b) = create_signal(cx, 0);
+        let double = create_memo(cx, move |_| b() * 2);
+        let quadruple = create_memo(cx, move |_| double() * 2);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        create_isomorphic_effect(cx, {
+            let log = log.clone();
+            move |_| log.borrow_mut().push((a(), double(), quadruple()))
+        });
+        assert_eq!(*log.borrow(), vec![(0, 0, 0)]);
+
+        cx.batch(|| {
+            set_a(1);
+            set_b(1);
+        });
+        // the effect ran once, and saw the new values of both memos
+        assert_eq!(*log.borrow(), vec![(0, 0, 0), (1, 2, 4)]);
+    })
+    .dispose()
+}
@@ -0,0 +1,86 @@
+use leptos_reactive::{
+    create_runtime, create_scope, provide_context, provide_keyed_context,
+    provide_serializable_context, use_context, use_context_with_default, use_keyed_context,
+    ContextKey,
+};
+
+#[test]
+fn use_context_with_default_falls_back_without_providing() {
+    create_scope(create_runtime(), |cx| {
+        assert_eq!(use_context_with_default(cx, || 1_u32), 1);
+        // the default isn't provided
+        assert_eq!(use_context::<u32>(cx), None);
+
+        cx.child_scope(|child| {
+            provide_context(child, 2_u32);
+            assert_eq!(use_context_with_default(child, || 1_u32), 2);
+        });
+    })
+    .dispose()
+}
+
+#[test]
+fn keyed_contexts_of_the_same_type_are_separate() {
+    const PRIMARY: ContextKey<String> = ContextKey::new("primary");
+    const ACCENT: ContextKey<String> = ContextKey::new("accent");
+
+    create_scope(create_runtime(), |cx| {
+        provide_context(cx, "plain".to_string());
+        provide_keyed_context(cx, PRIMARY, "blue".to_string());
+        provide_keyed_context(cx, ACCENT, "orange".to_string());
+
+        cx.child_scope(|child| {
+            // a nested scope can override one key without affecting the others
+            provide_keyed_context(child, ACCENT, "green".to_string());
+
+            assert_eq!(use_keyed_context(child, PRIMARY).as_deref(), Some("blue"));
+            assert_eq!(use_keyed_context(child, ACCENT).as_deref(), Some("green"));
+            assert_eq!(use_context::<String>(child).as_deref(), Some("plain"));
+        });
+
+        assert_eq!(use_keyed_context(cx, ACCENT).as_deref(), Some("orange"));
+        assert_eq!(
+            use_keyed_context(cx, ContextKey::<String>::new("missing")),
+            None
+        );
+        // keys with the same name but a different type don't match
+        assert_eq!(
+            use_keyed_context(cx, ContextKey::<u32>::new("primary")),
+            None
+        );
+    })
+    .dispose()
+}
+
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+#[test]
+fn serializable_contexts_are_sent_by_type_name() {
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Session {
+        user: String,
+    }
+
+    create_scope(create_runtime(), |cx| {
+        assert_eq!(cx.serialized_contexts(), "{}");
+
+        provide_serializable_context(cx, || Session {
+            user: "</script>".to_string(),
+        });
+        assert_eq!(
+            use_context::<Session>(cx),
+            Some(Session {
+                user: "</script>".to_string()
+            })
+        );
+
+        let contexts: std::collections::HashMap<String, String> =
+            serde_json::from_str(&cx.serialized_contexts()).unwrap();
+        assert_eq!(
+            contexts[std::any::type_name::<Session>()],
+            r#"{"user":"</script>"}"#
+        );
+        // a value can't close the script it's embedded in
+        assert!(!cx.serialized_contexts().contains("</script>"));
+    })
+    .dispose()
+}
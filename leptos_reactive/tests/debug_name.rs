@@ -0,0 +1,66 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use leptos_reactive::{
+    create_memo_named, create_runtime, create_rw_signal_named, create_scope, create_signal,
+    create_signal_named,
+};
+
+#[test]
+fn disposed_signal_panic_includes_name_and_location() {
+    create_scope(create_runtime(), |cx| {
+        let mut count = None;
+        let line = line!() + 2;
+        cx.child_scope(|cx| {
+            count = Some(create_signal_named(cx, "count", 0).0);
+        })
+        .dispose();
+
+        let count = count.unwrap();
+        let err = panic::catch_unwind(AssertUnwindSafe(|| count.get())).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains("signal `count`"), "{message}");
+        assert!(
+            message.contains(&format!("tests/debug_name.rs:{line}")),
+            "{message}"
+        );
+    })
+    .dispose()
+}
+
+#[test]
+fn named_memos_and_rw_signals_are_reported() {
+    create_scope(create_runtime(), |cx| {
+        let mut nodes = None;
+        cx.child_scope(|cx| {
+            let (a, _) = create_signal(cx, 1);
+            let doubled = create_memo_named(cx, "doubled", move |_| a.get() * 2);
+            let total = create_rw_signal_named(cx, "total", 0);
+            nodes = Some((doubled, total));
+        })
+        .dispose();
+
+        let (_, total) = nodes.unwrap();
+        let err = panic::catch_unwind(AssertUnwindSafe(|| total.get())).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains("signal `total`"), "{message}");
+    })
+    .dispose()
+}
+
+#[test]
+fn unnamed_signal_panic_is_unchanged() {
+    create_scope(create_runtime(), |cx| {
+        let mut count = None;
+        cx.child_scope(|cx| count = Some(create_signal(cx, 0).0))
+            .dispose();
+
+        let count = count.unwrap();
+        let err = panic::catch_unwind(AssertUnwindSafe(|| count.get())).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            message,
+            "tried to access a signal after it had been disposed"
+        );
+    })
+    .dispose()
+}
@@ -1,7 +1,6 @@
+use leptos_reactive::{create_isomorphic_effect, create_runtime, create_scope};
 #[cfg(not(feature = "stable"))]
-use leptos_reactive::{
-    create_isomorphic_effect, create_memo, create_runtime, create_scope, create_signal,
-};
+use leptos_reactive::{create_memo, create_signal};
 
 #[cfg(not(feature = "stable"))]
 #[test]
@@ -93,3 +92,100 @@ fn untrack_mutes_effect() {
     })
     .dispose()
 }
+
+#[cfg(not(any(feature = "ssr", feature = "csr", feature = "hydrate")))]
+#[test]
+fn effects_run_in_priority_order() {
+    use leptos_reactive::{create_effect_with_priority, create_signal, EffectPriority};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        for (name, priority) in [
+            ("idle", EffectPriority::Idle),
+            ("user-blocking", EffectPriority::UserBlocking),
+            ("render", EffectPriority::Render),
+        ] {
+            create_effect_with_priority(cx, priority, {
+                let log = log.clone();
+                move |_| log.borrow_mut().push((name, a.get()))
+            });
+        }
+        log.borrow_mut().clear();
+
+        set_a.set(1);
+        assert_eq!(
+            *log.borrow(),
+            vec![("render", 1), ("user-blocking", 1), ("idle", 1)]
+        );
+    })
+    .dispose()
+}
+
+#[cfg(not(any(feature = "ssr", feature = "stable")))]
+#[test]
+fn effect_cleanup_runs_before_rerun_and_on_dispose() {
+    use leptos_reactive::create_effect_with_cleanup;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    create_scope(create_runtime(), {
+        let log = log.clone();
+        move |cx| {
+            let (a, set_a) = create_signal(cx, 0);
+
+            create_effect_with_cleanup(cx, {
+                let log = log.clone();
+                move || {
+                    let value = a();
+                    log.borrow_mut().push(format!("run {value}"));
+                    let log = log.clone();
+                    (value % 2 == 0)
+                        .then_some(move || log.borrow_mut().push(format!("cleanup {value}")))
+                }
+            });
+
+            set_a(1);
+            set_a(2);
+        }
+    })
+    .dispose();
+
+    assert_eq!(
+        *log.borrow(),
+        vec!["run 0", "cleanup 0", "run 1", "run 2", "cleanup 2"]
+    );
+}
+
+#[test]
+fn server_and_client_effects_run_on_their_own_side() {
+    use leptos_reactive::{create_effect, create_server_effect};
+    use std::{cell::RefCell, rc::Rc};
+
+    let runs = Rc::new(RefCell::new(Vec::new()));
+    create_scope(create_runtime(), {
+        let runs = Rc::clone(&runs);
+        move |cx| {
+            create_effect(cx, {
+                let runs = Rc::clone(&runs);
+                move |_| runs.borrow_mut().push("client")
+            });
+            create_server_effect(cx, {
+                let runs = Rc::clone(&runs);
+                move |_| runs.borrow_mut().push("server")
+            });
+            create_isomorphic_effect(cx, move |_| runs.borrow_mut().push("isomorphic"));
+        }
+    })
+    .dispose();
+
+    if cfg!(feature = "ssr") {
+        assert_eq!(*runs.borrow(), ["server", "isomorphic"]);
+    } else {
+        assert_eq!(*runs.borrow(), ["client", "isomorphic"]);
+    }
+}
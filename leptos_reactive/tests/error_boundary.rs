@@ -0,0 +1,94 @@
+use std::{cell::Cell, fmt, rc::Rc};
+
+use leptos_reactive::*;
+
+#[derive(Debug)]
+struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not parse {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[test]
+fn error_boundary_tracks_errors_by_effect() {
+    create_scope(create_runtime(), |cx| {
+        let reported = Rc::new(Cell::new(0));
+        let context = ErrorBoundaryContext::new(
+            cx,
+            Some(Rc::new({
+                let reported = Rc::clone(&reported);
+                move |_: &Rc<dyn std::error::Error>| reported.set(reported.get() + 1)
+            })),
+        );
+        let errors = context.errors();
+
+        let (input, set_input) = create_signal(cx, "1".to_string());
+        create_isomorphic_effect(cx, {
+            let context = context.clone();
+            move |_| match input.get().parse::<i32>() {
+                Ok(_) => context.resolve(),
+                Err(_) => context.throw(Rc::new(ParseError(input.get()))),
+            }
+        });
+        assert!(errors.with(Errors::is_empty));
+
+        set_input.set("a".into());
+        set_input.set("b".into());
+        // the second error replaces the first, as both were rendered by the same effect
+        assert_eq!(errors.with(Errors::len), 1);
+        assert_eq!(
+            errors.with(|errors| errors.iter().next().unwrap().to_string()),
+            "could not parse \"b\""
+        );
+        assert_eq!(reported.get(), 2);
+
+        set_input.set("2".into());
+        assert!(errors.with(Errors::is_empty));
+
+        set_input.set("c".into());
+        context.reset();
+        assert!(errors.with(Errors::is_empty));
+        assert_eq!(reported.get(), 3);
+    })
+    .dispose()
+}
+
+#[test]
+fn error_boundary_catches_panics() {
+    create_scope(create_runtime(), |cx| {
+        let context = ErrorBoundaryContext::new(cx, None);
+        let errors = context.errors();
+
+        assert_eq!(context.catch_panics(|| 1), Some(1));
+        assert!(errors.with(Errors::is_empty));
+
+        let (count, set_count) = create_signal(cx, 0);
+        create_isomorphic_effect(cx, {
+            let context = context.clone();
+            move |_| {
+                let count = count.get();
+                context.catch_panics(|| {
+                    if count == 1 {
+                        panic!("count is {count}");
+                    }
+                });
+            }
+        });
+
+        set_count.set(1);
+        assert_eq!(
+            errors.with(|errors| errors.iter().map(ToString::to_string).collect::<Vec<_>>()),
+            vec!["render panicked: count is 1".to_string()]
+        );
+
+        // the panic is caught, so the effect keeps running
+        context.reset();
+        set_count.set(2);
+        assert!(errors.with(Errors::is_empty));
+    })
+    .dispose()
+}
@@ -0,0 +1,44 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use leptos_reactive::*;
+
+#[derive(Debug)]
+struct TestError;
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "test error")
+    }
+}
+
+impl std::error::Error for TestError {}
+
+#[test]
+fn error_hook_receives_reported_errors() {
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    set_error_hook({
+        let reported = Arc::clone(&reported);
+        move |error, location| {
+            reported
+                .lock()
+                .unwrap()
+                .push(format!("{location}: {error}"))
+        }
+    });
+
+    report_error(&TestError, ErrorLocation::Render);
+    report_error(&TestError, ErrorLocation::ServerFn("/api/add_todo".into()));
+    clear_error_hook();
+    report_error(&TestError, ErrorLocation::Hydration);
+
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![
+            "render: test error".to_string(),
+            "server function /api/add_todo: test error".to_string()
+        ]
+    );
+}
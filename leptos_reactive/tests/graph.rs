@@ -0,0 +1,55 @@
+#![cfg(feature = "debug-graph")]
+
+use leptos_reactive::{
+    create_isomorphic_effect, create_memo, create_runtime, create_scope, create_signal,
+    reactive_graph, NodeKind,
+};
+
+#[test]
+fn graph_lists_nodes_and_edges() {
+    create_scope(create_runtime(), |cx| {
+        let (first, _) = create_signal(cx, "Alice".to_string());
+        let (last, _) = create_signal(cx, "Smith".to_string());
+        let full = create_memo(cx, move |_| format!("{} {}", first.get(), last.get()));
+        create_isomorphic_effect(cx, move |_| full.with(String::len));
+
+        let graph = reactive_graph(cx);
+        let kinds = |kind| graph.nodes.iter().filter(|n| n.kind == kind).count();
+        assert_eq!(kinds(NodeKind::Signal), 2);
+        assert_eq!(kinds(NodeKind::Memo), 1);
+        assert_eq!(kinds(NodeKind::Effect), 1);
+
+        let memo = graph
+            .nodes
+            .iter()
+            .find(|n| n.kind == NodeKind::Memo)
+            .unwrap();
+        assert_eq!(memo.type_name, "alloc::string::String");
+        assert_eq!(graph.dependencies(memo.id).len(), 2);
+
+        let effect = graph.dependents(memo.id);
+        assert_eq!(effect.len(), 1);
+        assert_eq!(graph.node(effect[0]).unwrap().type_name, "usize");
+    })
+    .dispose()
+}
+
+#[test]
+fn graph_forgets_disposed_nodes() {
+    create_scope(create_runtime(), |cx| {
+        let child = cx.child_scope(|cx| {
+            let (count, _) = create_signal(cx, 0);
+            create_isomorphic_effect(cx, move |_| count.get());
+        });
+        let graph = reactive_graph(cx);
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.nodes.iter().all(|n| n.owner.is_some()));
+        assert_eq!(graph.edges.len(), 1);
+
+        child.dispose();
+        let graph = reactive_graph(cx);
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    })
+    .dispose()
+}
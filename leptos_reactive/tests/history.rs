@@ -0,0 +1,97 @@
+use std::{cell::Cell, rc::Rc};
+
+use leptos_reactive::{
+    create_history_signal, create_isomorphic_effect, create_runtime, create_scope,
+    UntrackedSettableSignal,
+};
+
+#[test]
+fn history_signal_keeps_at_most_capacity_values() {
+    create_scope(create_runtime(), |cx| {
+        let count = create_history_signal(cx, 0, 2);
+        for n in 1..=4 {
+            count.set(n);
+        }
+
+        assert!(count.undo());
+        assert!(count.undo());
+        assert_eq!(count.get(), 2);
+        // older values were forgotten
+        assert!(!count.undo());
+        assert_eq!(count.get(), 2);
+    })
+    .dispose()
+}
+
+#[test]
+fn history_signal_write_after_undo_discards_redo() {
+    create_scope(create_runtime(), |cx| {
+        let count = create_history_signal(cx, 0, 10);
+        count.set(1);
+        count.set(2);
+        count.undo();
+        assert!(count.can_redo());
+
+        count.set(3);
+        assert!(!count.can_redo());
+        assert!(!count.redo());
+        count.undo();
+        assert_eq!(count.get(), 1);
+    })
+    .dispose()
+}
+
+#[test]
+fn history_signal_batches_and_untracked_writes() {
+    create_scope(create_runtime(), |cx| {
+        let count = create_history_signal(cx, 0, 10);
+        let runs = Rc::new(Cell::new(0));
+        create_isomorphic_effect(cx, {
+            let runs = Rc::clone(&runs);
+            move |_| {
+                _ = count.get();
+                runs.set(runs.get() + 1);
+            }
+        });
+
+        cx.batch(|| {
+            count.set(1);
+            count.set(2);
+        });
+        // a separate batch is a separate change
+        cx.batch(|| count.set(3));
+
+        count.set_untracked(4);
+        assert_eq!(runs.get(), 3);
+
+        count.undo();
+        assert_eq!(count.get(), 3);
+        count.undo();
+        assert_eq!(count.get(), 2);
+        count.undo();
+        assert_eq!(count.get(), 0);
+        assert_eq!(runs.get(), 6);
+    })
+    .dispose()
+}
+
+#[test]
+fn history_signal_can_undo_is_reactive() {
+    create_scope(create_runtime(), |cx| {
+        let text = create_history_signal(cx, String::new(), 10);
+        let can_undo = Rc::new(Cell::new(false));
+        create_isomorphic_effect(cx, {
+            let can_undo = Rc::clone(&can_undo);
+            move |_| can_undo.set(text.can_undo())
+        });
+        assert!(!can_undo.get());
+
+        text.set("a".to_string());
+        assert!(can_undo.get());
+
+        text.clear_history();
+        assert!(!can_undo.get());
+        assert_eq!(text.get(), "a");
+    })
+    .dispose()
+}
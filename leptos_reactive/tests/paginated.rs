@@ -0,0 +1,93 @@
+#[cfg(not(any(feature = "csr", feature = "hydrate", feature = "ssr")))]
+#[test]
+fn infinite_resource_appends_pages_until_exhausted() {
+    use leptos_reactive::{create_infinite_resource, create_runtime, create_scope, InfinitePage};
+    use std::{cell::RefCell, rc::Rc};
+
+    create_scope(create_runtime(), |cx| {
+        let requested = Rc::new(RefCell::new(Vec::new()));
+        let feed = create_infinite_resource(cx, {
+            let requested = Rc::clone(&requested);
+            move |cursor: Option<u32>| {
+                requested.borrow_mut().push(cursor);
+                let start = cursor.unwrap_or(0);
+                async move {
+                    InfinitePage {
+                        items: vec![start, start + 1],
+                        next_cursor: (start < 4).then_some(start + 2),
+                    }
+                }
+            }
+        });
+
+        assert_eq!(feed.items(), [0, 1]);
+        assert!(feed.has_more());
+
+        feed.load_more();
+        feed.load_more();
+        assert_eq!(feed.items(), [0, 1, 2, 3, 4, 5]);
+        assert!(!feed.has_more());
+
+        // nothing left to load
+        feed.load_more();
+        assert_eq!(*requested.borrow(), [None, Some(2), Some(4)]);
+
+        feed.reset();
+        assert_eq!(feed.items(), [0, 1]);
+        assert!(feed.has_more());
+        assert_eq!(requested.borrow().len(), 4);
+    })
+    .dispose()
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn paginated_resource_loads_the_current_page() {
+    use leptos_reactive::{
+        create_paginated_resource, create_runtime, create_scope, UntrackedGettableSignal,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let settle = || async {
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+        };
+        let scope = Rc::new(Cell::new(None));
+        let disposer = create_scope(create_runtime(), {
+            let scope = Rc::clone(&scope);
+            move |cx| scope.set(Some(cx))
+        });
+        let cx = scope.get().unwrap();
+
+        let posts =
+            create_paginated_resource(cx, |page| async move { format!("posts on page {page}") });
+        settle().await;
+        assert_eq!(posts.read().as_deref(), Some("posts on page 0"));
+
+        posts.next_page();
+        posts.next_page();
+        settle().await;
+        assert_eq!(posts.page(), 2);
+        assert_eq!(
+            posts.resource().get_untracked().as_deref(),
+            Some("posts on page 2")
+        );
+
+        posts.prev_page();
+        settle().await;
+        assert_eq!(posts.read().as_deref(), Some("posts on page 1"));
+        posts.set_page(0);
+        posts.prev_page();
+        settle().await;
+        assert_eq!(posts.page(), 0);
+        assert_eq!(posts.read().as_deref(), Some("posts on page 0"));
+
+        disposer.dispose();
+    });
+}
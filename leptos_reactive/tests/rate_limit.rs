@@ -0,0 +1,53 @@
+// the timers of the browser can't run in a native test
+#![cfg(not(any(feature = "csr", feature = "hydrate")))]
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use leptos_reactive::{
+    create_isomorphic_effect, create_runtime, create_scope, create_signal, signal_debounced,
+    signal_throttled,
+};
+
+// off the browser there are no timers, so both signals follow their source immediately
+
+#[test]
+fn debounced_signal_follows_source_on_server() {
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        let debounced = signal_debounced(cx, move || a.get() * 10, Duration::from_millis(50));
+        assert_eq!(debounced.get(), 10);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        create_isomorphic_effect(cx, {
+            let log = Rc::clone(&log);
+            move |_| log.borrow_mut().push(debounced.get())
+        });
+
+        set_a.set(2);
+        set_a.set(3);
+        assert_eq!(debounced.get(), 30);
+        assert_eq!(*log.borrow(), vec![10, 20, 30]);
+    })
+    .dispose()
+}
+
+#[test]
+fn throttled_signal_follows_source_on_server() {
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, "a".to_string());
+        let throttled = signal_throttled(cx, move || a.get(), Duration::from_millis(50));
+        assert_eq!(throttled.get(), "a");
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        create_isomorphic_effect(cx, {
+            let log = Rc::clone(&log);
+            move |_| log.borrow_mut().push(throttled.get())
+        });
+
+        set_a.set("b".to_string());
+        set_a.set("c".to_string());
+        assert_eq!(throttled.get(), "c");
+        assert_eq!(*log.borrow(), vec!["a", "b", "c"]);
+    })
+    .dispose()
+}
@@ -0,0 +1,151 @@
+#[cfg(feature = "ssr")]
+#[test]
+fn resource_drops_outdated_loads() {
+    use futures::channel::oneshot;
+    use leptos_reactive::{
+        create_resource, create_runtime, create_scope, create_signal, UntrackedGettableSignal,
+    };
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let (tx, rx) = oneshot::channel();
+        let disposer = create_scope(create_runtime(), move |cx| {
+            // each load waits until the test sends its response
+            let responses = Rc::new(RefCell::new(HashMap::new()));
+            let (id, set_id) = create_signal(cx, 1);
+            let resource = create_resource(cx, move || id.get(), {
+                let responses = Rc::clone(&responses);
+                move |id: u32| {
+                    let (send, recv) = oneshot::channel::<String>();
+                    responses.borrow_mut().insert(id, send);
+                    async move { recv.await.unwrap_or_default() }
+                }
+            });
+            _ = tx.send((resource, set_id, responses));
+        });
+        let (resource, set_id, responses) = rx.await.unwrap();
+        let respond = |id: u32| {
+            let send = responses.borrow_mut().remove(&id).unwrap();
+            _ = send.send(format!("response {id}"));
+        };
+        let settle = || async {
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+        };
+
+        settle().await;
+        respond(1);
+        settle().await;
+        assert_eq!(resource.get_untracked().as_deref(), Some("response 1"));
+        assert!(!resource.is_stale());
+
+        set_id.set(2);
+        settle().await;
+        assert!(resource.is_stale());
+        set_id.set(3);
+        settle().await;
+
+        // the response for 2 arrives after 3 was requested, and is ignored
+        respond(2);
+        settle().await;
+        assert_eq!(resource.get_untracked().as_deref(), Some("response 1"));
+        assert!(resource.is_stale());
+
+        respond(3);
+        settle().await;
+        assert_eq!(resource.get_untracked().as_deref(), Some("response 3"));
+        assert!(!resource.is_stale());
+
+        disposer.dispose();
+    });
+}
+
+#[cfg(not(any(feature = "csr", feature = "hydrate", feature = "ssr")))]
+#[test]
+fn suspense_takes_highest_resource_priority() {
+    use leptos_reactive::{
+        create_resource, create_runtime, create_scope, provide_context, ResourcePriority,
+        SuspenseContext,
+    };
+
+    let disposer = create_scope(create_runtime(), |cx| {
+        let context = SuspenseContext::new(cx);
+        provide_context(cx, context);
+
+        let lazy =
+            create_resource(cx, || (), |_| async { 1 }).with_priority(ResourcePriority::Lazy);
+        let blocking =
+            create_resource(cx, || (), |_| async { 2 }).with_priority(ResourcePriority::Blocking);
+        assert_eq!(blocking.priority(), ResourcePriority::Blocking);
+
+        lazy.read();
+        assert_eq!(context.priority(), ResourcePriority::Lazy);
+        blocking.read();
+        lazy.read();
+        assert_eq!(context.priority(), ResourcePriority::Blocking);
+    });
+    disposer.dispose();
+}
+
+#[cfg(not(any(feature = "csr", feature = "hydrate", feature = "ssr")))]
+#[test]
+fn blocking_resources_are_listed_by_the_scope() {
+    use leptos_reactive::{
+        create_blocking_resource, create_resource, create_runtime, create_scope, ResourcePriority,
+    };
+
+    let disposer = create_scope(create_runtime(), |cx| {
+        let blocking = create_blocking_resource(cx, || (), |_| async { 1 });
+        let deferred = create_resource(cx, || (), |_| async { 2 });
+        assert_eq!(blocking.priority(), ResourcePriority::Blocking);
+        assert_eq!(deferred.priority(), ResourcePriority::Deferred);
+
+        let lazy = create_resource(cx, || (), |_| async { 3 });
+        assert_eq!(cx.blocking_resources().len(), 1);
+        lazy.with_priority(ResourcePriority::Blocking);
+        assert_eq!(cx.blocking_resources().len(), 2);
+    });
+    disposer.dispose();
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn resource_is_sent_with_its_serializer() {
+    use futures::StreamExt;
+    use leptos_reactive::{
+        create_resource_with_serializer, create_runtime, create_scope, BincodeSerializer,
+        Serializer,
+    };
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let runtime = create_runtime();
+        let (tx, rx) = futures::channel::oneshot::channel();
+        let disposer = create_scope(runtime, move |cx| {
+            create_resource_with_serializer(
+                cx,
+                BincodeSerializer,
+                || (),
+                |_| async { vec![0.5_f64, 1.5, 2.5] },
+            );
+            _ = tx.send(cx.serialization_resolvers());
+        });
+        let mut resolvers = rx.await.unwrap();
+        let (_, data) = resolvers.next().await.unwrap();
+        // sent as base64 rather than JSON
+        assert!(!data.starts_with('['));
+        assert_eq!(
+            BincodeSerializer::decode::<Vec<f64>>(&data).unwrap(),
+            vec![0.5, 1.5, 2.5]
+        );
+        disposer.dispose();
+    })
+}
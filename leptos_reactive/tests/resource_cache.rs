@@ -0,0 +1,63 @@
+#[cfg(feature = "ssr")]
+#[test]
+fn cached_resources_reuse_and_revalidate_values() {
+    use leptos_reactive::{
+        create_cached_resource, create_runtime, create_scope, invalidate_resource_cache,
+        UntrackedGettableSignal,
+    };
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let settle = || async {
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+        };
+        let fetches = Rc::new(Cell::new(0));
+        let fetcher = {
+            let fetches = Rc::clone(&fetches);
+            move |id: u32| {
+                fetches.set(fetches.get() + 1);
+                let fetch = fetches.get();
+                async move { format!("user {id}, fetch {fetch}") }
+            }
+        };
+
+        let scope = Rc::new(Cell::new(None));
+        let disposer = create_scope(create_runtime(), {
+            let scope = Rc::clone(&scope);
+            move |cx| scope.set(Some(cx))
+        });
+        let cx = scope.get().unwrap();
+        let user = |ttl| create_cached_resource(cx, "user", || 1, fetcher.clone(), ttl);
+
+        let first = user(Duration::MAX);
+        settle().await;
+        assert_eq!(first.get_untracked().as_deref(), Some("user 1, fetch 1"));
+
+        // a fresh value is reused without fetching
+        let second = user(Duration::MAX);
+        settle().await;
+        assert_eq!(second.get_untracked().as_deref(), Some("user 1, fetch 1"));
+        assert_eq!(fetches.get(), 1);
+
+        // an outdated value is shown, then replaced once it has been fetched again
+        let third = user(Duration::ZERO);
+        settle().await;
+        assert_eq!(fetches.get(), 2);
+        assert_eq!(third.get_untracked().as_deref(), Some("user 1, fetch 2"));
+
+        // invalidating refetches every live resource with the key
+        invalidate_resource_cache(cx, "user");
+        settle().await;
+        assert_eq!(fetches.get(), 5);
+        assert!(first.get_untracked().unwrap().starts_with("user 1, fetch"));
+        assert_ne!(first.get_untracked().as_deref(), Some("user 1, fetch 1"));
+
+        disposer.dispose();
+    });
+}
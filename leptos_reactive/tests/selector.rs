@@ -0,0 +1,41 @@
+#[cfg(not(feature = "stable"))]
+use leptos_reactive::{
+    create_isomorphic_effect, create_runtime, create_scope, create_selector, create_signal,
+};
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn selector_only_notifies_changed_rows() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (selected, set_selected) = create_signal(cx, 0);
+        let is_selected = create_selector(cx, selected);
+
+        // count how many times each row's effect runs
+        let runs = Rc::new(RefCell::new(vec![0; 1000]));
+        for row in 0..1000 {
+            create_isomorphic_effect(cx, {
+                let is_selected = is_selected.clone();
+                let runs = runs.clone();
+                move |_| {
+                    _ = is_selected(row);
+                    runs.borrow_mut()[row] += 1;
+                }
+            });
+        }
+        runs.borrow_mut().iter_mut().for_each(|n| *n = 0);
+
+        set_selected(500);
+        set_selected(999);
+
+        let runs = runs.borrow();
+        let rerun = (0..1000).filter(|row| runs[*row] > 0).collect::<Vec<_>>();
+        assert_eq!(rerun, vec![0, 500, 999]);
+        assert_eq!(runs[500], 2);
+        assert!(is_selected(999));
+        assert!(!is_selected(500));
+    })
+    .dispose()
+}
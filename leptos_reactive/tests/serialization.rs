@@ -0,0 +1,42 @@
+use leptos_reactive::{
+    BincodeSerializer, CborSerializer, JsonSerializer, MsgPackSerializer, Serializer,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Series {
+    name: String,
+    points: Vec<(f64, f64)>,
+}
+
+fn round_trip<Ser: Serializer>() -> String {
+    let series = Series {
+        name: "temperature".to_string(),
+        points: vec![(0.0, 21.5), (1.0, 22.25)],
+    };
+    let data = Ser::encode(&series).unwrap();
+    assert_eq!(Ser::decode::<Series>(&data).unwrap(), series);
+    data
+}
+
+#[test]
+fn serializers_round_trip() {
+    assert!(round_trip::<JsonSerializer>().starts_with('{'));
+    // binary formats are sent as base64 text
+    for data in [
+        round_trip::<BincodeSerializer>(),
+        round_trip::<CborSerializer>(),
+        round_trip::<MsgPackSerializer>(),
+    ] {
+        assert!(data
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=')));
+    }
+}
+
+#[test]
+fn serializers_reject_invalid_data() {
+    assert!(JsonSerializer::decode::<Series>("not json").is_err());
+    assert!(BincodeSerializer::decode::<Series>("not base64!").is_err());
+    assert!(CborSerializer::decode::<Series>("AAAA").is_err());
+}
@@ -0,0 +1,54 @@
+#[cfg(not(feature = "stable"))]
+use leptos_reactive::{create_isomorphic_effect, create_runtime, create_scope, create_signal_map};
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn signal_map_notifies_per_key() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let rows = create_signal_map(cx);
+        rows.insert("a", 1);
+
+        // simulate an arbitrary side effect for a key, and for the length
+        let b = Rc::new(RefCell::new(Vec::new()));
+        let len = Rc::new(RefCell::new(Vec::new()));
+
+        create_isomorphic_effect(cx, {
+            let rows = rows.clone();
+            let b = b.clone();
+            move |_| b.borrow_mut().push(rows.get(&"b"))
+        });
+        create_isomorphic_effect(cx, {
+            let rows = rows.clone();
+            let len = len.clone();
+            move |_| len.borrow_mut().push(rows.len())
+        });
+
+        assert_eq!(*b.borrow(), vec![None]);
+        assert_eq!(*len.borrow(), vec![1]);
+
+        // inserting a key that was read before it existed notifies its readers
+        rows.insert("b", 2);
+        assert_eq!(*b.borrow(), vec![None, Some(2)]);
+        assert_eq!(*len.borrow(), vec![1, 2]);
+
+        // updating a value doesn't change the length
+        rows.update(&"a", |n| *n += 10);
+        rows.update(&"b", |n| *n += 10);
+        assert_eq!(*b.borrow(), vec![None, Some(2), Some(12)]);
+        assert_eq!(*len.borrow(), vec![1, 2]);
+
+        assert_eq!(rows.remove(&"a"), Some(11));
+        assert_eq!(rows.remove(&"a"), None);
+        assert_eq!(*b.borrow(), vec![None, Some(2), Some(12)]);
+        assert_eq!(*len.borrow(), vec![1, 2, 1]);
+        assert_eq!(rows.keys(), vec!["b"]);
+
+        rows.clear();
+        assert_eq!(*b.borrow(), vec![None, Some(2), Some(12), None]);
+        assert!(rows.is_empty());
+    })
+    .dispose()
+}
@@ -0,0 +1,52 @@
+use leptos_reactive::{
+    create_memo, create_runtime, create_rw_signal, create_scope, create_signal, IntoSignal,
+    MaybeSignal, Scope, Signal,
+};
+
+fn describe(cx: Scope, count: impl IntoSignal<usize>) -> Signal<String> {
+    count
+        .into_signal(cx)
+        .map(cx, |count| format!("{count} item(s)"))
+}
+
+#[test]
+fn into_signal_accepts_every_readable_kind() {
+    create_scope(create_runtime(), |cx| {
+        let (count, set_count) = create_signal(cx, 1);
+        let rw = create_rw_signal(cx, 2);
+        let double = create_memo(cx, move |_| count.get() * 2);
+
+        let from_signal = describe(cx, count);
+        let from_rw = describe(cx, rw);
+        let from_memo = describe(cx, double);
+        let from_closure = describe(cx, move || count.get() + 10);
+        let from_value = describe(cx, MaybeSignal::from(5));
+
+        assert_eq!(from_signal.get(), "1 item(s)");
+        assert_eq!(from_rw.get(), "2 item(s)");
+        assert_eq!(from_memo.get(), "2 item(s)");
+        assert_eq!(from_closure.get(), "11 item(s)");
+        assert_eq!(from_value.get(), "5 item(s)");
+
+        set_count.set(3);
+        assert_eq!(from_signal.get(), "3 item(s)");
+        assert_eq!(from_memo.get(), "6 item(s)");
+        assert_eq!(from_closure.get(), "13 item(s)");
+    })
+    .dispose()
+}
+
+#[test]
+fn maybe_signal_map_keeps_static_values_static() {
+    create_scope(create_runtime(), |cx| {
+        let value = MaybeSignal::from(2).map(cx, |n| n * 10);
+        assert_eq!(value, MaybeSignal::Static(20));
+
+        let (count, set_count) = create_signal(cx, 2);
+        let value = MaybeSignal::from(Signal::from(count)).map(cx, |n| n * 10);
+        assert!(matches!(value, MaybeSignal::Dynamic(_)));
+        set_count.set(3);
+        assert_eq!(value.get(), 30);
+    })
+    .dispose()
+}
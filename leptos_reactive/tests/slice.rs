@@ -0,0 +1,53 @@
+use std::{cell::RefCell, rc::Rc};
+
+use leptos_reactive::{
+    create_isomorphic_effect, create_runtime, create_rw_signal, create_scope, create_slice,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Form {
+    name: String,
+    tags: Vec<String>,
+}
+
+#[test]
+fn slices_only_notify_when_their_part_changes() {
+    create_scope(create_runtime(), |cx| {
+        let form = create_rw_signal(
+            cx,
+            Form {
+                name: "Alice".to_string(),
+                tags: vec![],
+            },
+        );
+        let (name, set_name) = create_slice(
+            cx,
+            form,
+            |form| form.name.clone(),
+            |form, name| form.name = name,
+        );
+        let (tag_count, _) = create_slice(cx, form, |form| form.tags.len(), |_, _| {});
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        create_isomorphic_effect(cx, {
+            let log = Rc::clone(&log);
+            move |_| log.borrow_mut().push(format!("name {}", name.get()))
+        });
+        create_isomorphic_effect(cx, {
+            let log = Rc::clone(&log);
+            move |_| log.borrow_mut().push(format!("tags {}", tag_count.get()))
+        });
+
+        form.update(|form| form.tags.push("new".to_string()));
+        set_name.set("Bob".to_string());
+        // setting the same value doesn't notify
+        set_name.set("Bob".to_string());
+
+        assert_eq!(
+            *log.borrow(),
+            vec!["name Alice", "tags 0", "tags 1", "name Bob"]
+        );
+        assert_eq!(form.with(|form| form.name.clone()), "Bob");
+    })
+    .dispose()
+}
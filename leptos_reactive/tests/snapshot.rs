@@ -0,0 +1,65 @@
+use leptos_reactive::{
+    create_runtime, create_rw_signal, create_scope, create_signal, StateSnapshot,
+};
+
+#[test]
+fn snapshot_restores_registered_signals() {
+    create_scope(create_runtime(), |cx| {
+        let snapshot = StateSnapshot::new();
+        let (count, set_count) = create_signal(cx, 1);
+        let names = create_rw_signal(cx, vec!["Alice".to_string()]);
+        snapshot.register(cx, "count", count, set_count);
+        snapshot.register_rw(cx, "names", names);
+
+        let saved = snapshot.save().unwrap();
+        set_count.set(5);
+        names.update(|names| names.push("Bob".to_string()));
+
+        snapshot.restore(&saved).unwrap();
+        assert_eq!(count.get(), 1);
+        assert_eq!(names.get(), vec!["Alice".to_string()]);
+
+        assert!(snapshot.restore("not json").is_err());
+    })
+    .dispose()
+}
+
+#[test]
+fn snapshot_keeps_values_until_registered() {
+    create_scope(create_runtime(), |cx| {
+        let saved = {
+            let snapshot = StateSnapshot::new();
+            let (count, set_count) = create_signal(cx, 42);
+            snapshot.register(cx, "count", count, set_count);
+            snapshot.save().unwrap()
+        };
+
+        let snapshot = StateSnapshot::new();
+        snapshot.restore(&saved).unwrap();
+        // the value not applied yet is saved again
+        assert_eq!(snapshot.save().unwrap(), saved);
+
+        let (count, set_count) = create_signal(cx, 0);
+        snapshot.register(cx, "count", count, set_count);
+        assert_eq!(count.get(), 42);
+    })
+    .dispose()
+}
+
+#[test]
+fn snapshot_forgets_signals_of_disposed_scopes() {
+    create_scope(create_runtime(), |cx| {
+        let snapshot = StateSnapshot::new();
+        cx.child_scope({
+            let snapshot = snapshot.clone();
+            move |cx| {
+                let value = create_rw_signal(cx, "temporary".to_string());
+                snapshot.register_rw(cx, "value", value);
+            }
+        })
+        .dispose();
+
+        assert_eq!(snapshot.save().unwrap(), "{}");
+    })
+    .dispose()
+}
@@ -0,0 +1,49 @@
+// without a server runtime, a future is run to completion as it's spawned, but in the browser
+// it's spawned with APIs that a native test can't use
+#[cfg(not(any(feature = "ssr", feature = "csr", feature = "hydrate")))]
+#[test]
+fn spawn_local_scoped_runs_future() {
+    use leptos_reactive::{create_runtime, create_scope, create_signal, spawn_local_scoped};
+
+    create_scope(create_runtime(), |cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        spawn_local_scoped(cx, async move {
+            set_count.set(1);
+        });
+        assert_eq!(count.get(), 1);
+    })
+    .dispose()
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn spawn_local_scoped_cancels_future_on_dispose() {
+    use leptos_reactive::{create_runtime, create_scope, spawn_local_scoped};
+    use std::{cell::Cell, rc::Rc};
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let finished = Rc::new(Cell::new(false));
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+
+        let disposer = create_scope(create_runtime(), {
+            let finished = Rc::clone(&finished);
+            move |cx| {
+                spawn_local_scoped(cx, async move {
+                    _ = rx.await;
+                    finished.set(true);
+                });
+            }
+        });
+        tokio::task::yield_now().await;
+        disposer.dispose();
+
+        _ = tx.send(());
+        tokio::task::yield_now().await;
+        tokio::task::yield_now().await;
+        assert!(!finished.get());
+    });
+}
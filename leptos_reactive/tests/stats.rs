@@ -0,0 +1,59 @@
+use leptos_reactive::{
+    compact_runtime, create_isomorphic_effect, create_memo, create_runtime, create_scope,
+    create_signal, runtime_stats, RuntimeStats,
+};
+
+#[test]
+fn memos_are_disposed_with_their_scope() {
+    create_scope(create_runtime(), |cx| {
+        let (count, _) = create_signal(cx, 0);
+        let child = cx.child_scope(|cx| {
+            let double = create_memo(cx, move |_| count.get() * 2);
+            create_isomorphic_effect(cx, move |_| double.get());
+        });
+        assert_eq!(
+            runtime_stats(cx),
+            RuntimeStats {
+                scopes: 2,
+                signals: 1,
+                memos: 1,
+                effects: 1,
+                resources: 0,
+                unowned: 0,
+            }
+        );
+
+        child.dispose();
+        assert_eq!(
+            runtime_stats(cx),
+            RuntimeStats {
+                scopes: 1,
+                signals: 1,
+                ..Default::default()
+            }
+        );
+    })
+    .dispose()
+}
+
+#[test]
+fn compaction_removes_stale_subscriptions() {
+    create_scope(create_runtime(), |cx| {
+        let (count, set_count) = create_signal(cx, 0);
+        for _ in 0..10 {
+            cx.child_scope(|cx| {
+                create_isomorphic_effect(cx, move |_| count.get());
+            })
+            .dispose();
+        }
+
+        // at least one stale subscription per disposed effect
+        assert!(compact_runtime(cx) >= 10);
+        assert_eq!(compact_runtime(cx), 0);
+
+        // the signal still works after compaction
+        set_count.set(1);
+        assert_eq!(count.get(), 1);
+    })
+    .dispose()
+}
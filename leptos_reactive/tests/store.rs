@@ -0,0 +1,85 @@
+#[cfg(not(feature = "stable"))]
+use leptos_reactive::{
+    create_isomorphic_effect, create_runtime, create_scope, create_store, StorePath,
+};
+
+#[cfg(not(feature = "stable"))]
+#[test]
+fn store_fields_are_tracked_independently() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct User {
+        name: String,
+        email: String,
+    }
+
+    #[derive(Clone, Default)]
+    struct Form {
+        user: User,
+        accepted_terms: bool,
+    }
+
+    create_scope(create_runtime(), |cx| {
+        let form = create_store(cx, Form::default());
+        let user = form.field("user", |f| &f.user, |f| &mut f.user);
+        let name = user.field("name", |u| &u.name, |u| &mut u.name);
+        let email = user.field("email", |u| &u.email, |u| &mut u.email);
+        let terms = form.field(
+            "accepted_terms",
+            |f| &f.accepted_terms,
+            |f| &mut f.accepted_terms,
+        );
+
+        // record which parts of the form each effect has seen
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let track = |label: &'static str, read: Box<dyn Fn()>| {
+            let log = log.clone();
+            create_isomorphic_effect(cx, move |_| {
+                read();
+                log.borrow_mut().push(label);
+            });
+        };
+        track(
+            "name",
+            Box::new({
+                let name = name.clone();
+                move || _ = name.get()
+            }),
+        );
+        track(
+            "user",
+            Box::new({
+                let user = user.clone();
+                move || _ = user.with(|u| u.email.len())
+            }),
+        );
+        track(
+            "form",
+            Box::new({
+                let form = form.clone();
+                move || _ = form.with(|f| f.accepted_terms)
+            }),
+        );
+        let seen = || {
+            let mut seen = log.take();
+            seen.sort();
+            seen
+        };
+        seen();
+
+        // a field notifies itself and the fields that contain it
+        email.set("alice@example.com".to_string());
+        assert_eq!(seen(), vec!["form", "user"]);
+
+        terms.set(true);
+        assert_eq!(seen(), vec!["form"]);
+
+        // replacing a field notifies the fields it contains
+        user.set(User::default());
+        assert_eq!(seen(), vec!["form", "name", "user"]);
+        assert_eq!(email.get(), "");
+    })
+    .dispose()
+}
@@ -0,0 +1,24 @@
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+#[test]
+fn timers_do_nothing_on_the_server() {
+    use leptos_reactive::{create_interval, create_runtime, create_scope, create_timeout};
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    let runs = Rc::new(Cell::new(0));
+    create_scope(create_runtime(), {
+        let runs = Rc::clone(&runs);
+        move |cx| {
+            create_timeout(cx, Duration::ZERO, {
+                let runs = Rc::clone(&runs);
+                move || runs.set(runs.get() + 1)
+            });
+            let interval = create_interval(cx, Duration::ZERO, move || runs.set(runs.get() + 1));
+
+            interval.cancel();
+            interval.cancel();
+        }
+    })
+    .dispose();
+
+    assert_eq!(runs.get(), 0);
+}
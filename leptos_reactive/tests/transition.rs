@@ -0,0 +1,63 @@
+#[cfg(feature = "ssr")]
+#[test]
+fn transition_tracks_loads_started_in_it() {
+    use futures::channel::oneshot;
+    use leptos_reactive::{
+        create_resource, create_runtime, create_scope, create_signal, create_transition,
+        UntrackedGettableSignal,
+    };
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    local.block_on(&rt, async {
+        let (tx, rx) = oneshot::channel();
+        let disposer = create_scope(create_runtime(), move |cx| {
+            // each load waits until the test sends its response
+            let responses = Rc::new(RefCell::new(HashMap::new()));
+            let (id, set_id) = create_signal(cx, 1);
+            let resource = create_resource(cx, move || id.get(), {
+                let responses = Rc::clone(&responses);
+                move |id: u32| {
+                    let (send, recv) = oneshot::channel::<u32>();
+                    responses.borrow_mut().insert(id, send);
+                    async move { recv.await.unwrap_or_default() }
+                }
+            });
+            let navigation = create_transition(cx);
+            let other = create_transition(cx);
+            _ = tx.send((resource, set_id, responses, navigation, other));
+        });
+        let (resource, set_id, responses, navigation, other) = rx.await.unwrap();
+        let respond = |id: u32| {
+            let send = responses.borrow_mut().remove(&id).unwrap();
+            _ = send.send(id);
+        };
+        let settle = || async {
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+        };
+
+        // the initial load wasn't started in a transition
+        settle().await;
+        assert!(!navigation.pending().get());
+        respond(1);
+        settle().await;
+
+        navigation.start(move || set_id.set(2));
+        settle().await;
+        assert!(navigation.pending().get());
+        assert!(!other.pending().get());
+        assert_eq!(resource.get_untracked(), Some(1));
+
+        respond(2);
+        settle().await;
+        assert!(!navigation.pending().get());
+        assert_eq!(resource.get_untracked(), Some(2));
+
+        disposer.dispose();
+    });
+}
@@ -77,3 +77,44 @@ fn untracked_get_doesnt_trigger_effect() {
     })
     .dispose()
 }
+
+// outside the server, resources are loaded with the browser's APIs
+#[cfg(any(feature = "ssr", target_arch = "wasm32"))]
+#[test]
+fn untracked_resource_access_doesnt_trigger_effect() {
+    use leptos_reactive::create_local_resource;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    create_scope(create_runtime(), |cx| {
+        let (a, set_a) = create_signal(cx, 1);
+        // a local resource isn't fetched on the server, so the value is set here as it would
+        // be once the fetcher has run
+        let user = create_local_resource(cx, || (), |_| async { "Bob".to_string() });
+        user.set_untracked(Some("Bob".to_string()));
+
+        // simulate an arbitrary side effect
+        let b = Rc::new(RefCell::new(String::new()));
+
+        create_isomorphic_effect(cx, {
+            let b = b.clone();
+            move |_| {
+                let name = user.with_untracked(|name| name.clone().unwrap_or_default());
+                *b.borrow_mut() = format!("{name} has {} messages", a());
+            }
+        });
+
+        assert_eq!(b.borrow().as_str(), "Bob has 1 messages");
+
+        user.set_untracked(Some("Carol".to_string()));
+
+        assert_eq!(b.borrow().as_str(), "Bob has 1 messages");
+        assert_eq!(user.get_untracked(), Some("Carol".to_string()));
+
+        user.update_untracked(|name| *name = Some("Dave".to_string()));
+        set_a.set(2);
+
+        assert_eq!(b.borrow().as_str(), "Dave has 2 messages");
+    })
+    .dispose()
+}
@@ -0,0 +1,35 @@
+#[cfg(not(feature = "ssr"))]
+#[test]
+fn watch_passes_previous_value_and_ignores_callback_reads() {
+    use leptos_reactive::{create_runtime, create_scope, create_signal, watch};
+    use std::{cell::RefCell, rc::Rc};
+
+    create_scope(create_runtime(), |cx| {
+        let (name, set_name) = create_signal(cx, "Alice".to_string());
+        let (other, set_other) = create_signal(cx, 0);
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        watch(
+            cx,
+            move || name.get(),
+            {
+                let log = Rc::clone(&log);
+                move |current: &String, previous: Option<&String>| {
+                    // reading a signal in the callback doesn't subscribe to it
+                    _ = other.get();
+                    log.borrow_mut().push(format!("{previous:?} -> {current}"));
+                }
+            },
+            true,
+        );
+
+        set_other.set(1);
+        set_name.set("Bob".to_string());
+
+        assert_eq!(
+            *log.borrow(),
+            vec!["None -> Alice", "Some(\"Alice\") -> Bob"]
+        );
+    })
+    .dispose()
+}
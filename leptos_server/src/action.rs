@@ -1,6 +1,14 @@
-use crate::{ServerFn, ServerFnError};
+use crate::ServerFn;
 use leptos_reactive::{create_rw_signal, spawn_local, ReadSignal, RwSignal, Scope};
-use std::{future::Future, pin::Pin, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashSet},
+    future::Future,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    rc::Rc,
+    time::Duration,
+};
 
 /// An action synchronizes an imperative `async` call to the synchronous reactive system.
 ///
@@ -82,6 +90,7 @@ where
     I: 'static,
     O: 'static,
 {
+    cx: Scope,
     /// How many times the action has successfully resolved.
     pub version: RwSignal<usize>,
     /// The current argument that was dispatched to the `async` function.
@@ -93,6 +102,10 @@ where
     url: Option<String>,
     #[allow(clippy::complexity)]
     action_fn: Rc<dyn Fn(&I) -> Pin<Box<dyn Future<Output = O>>>>,
+    #[allow(clippy::complexity)]
+    optimistic: Option<Rc<dyn Fn(&I) -> Box<dyn FnOnce(&O)>>>,
+    input_hash: Option<fn(&I) -> u64>,
+    in_flight: Rc<RefCell<HashSet<u64>>>,
 }
 
 impl<I, O> Action<I, O>
@@ -101,7 +114,22 @@ where
     O: 'static,
 {
     /// Calls the `async` function with a reference to the input type as its argument.
+    ///
+    /// If the action has an [optimistic](Action::optimistic) update, it is applied before the
+    /// `async` function is called. If the action [deduplicates](Action::deduplicate) its inputs,
+    /// dispatching an input that is equal to one that is still pending does nothing.
     pub fn dispatch(&self, input: I) {
+        let hash = self.input_hash.map(|input_hash| input_hash(&input));
+        if let Some(hash) = hash {
+            if !self.in_flight.borrow_mut().insert(hash) {
+                return;
+            }
+        }
+        let in_flight = Rc::clone(&self.in_flight);
+        let settle = self
+            .optimistic
+            .as_ref()
+            .map(|optimistic| optimistic(&input));
         let fut = (self.action_fn)(&input);
         self.input.set(Some(input));
         let input = self.input;
@@ -111,6 +139,12 @@ where
         pending.set(true);
         spawn_local(async move {
             let new_value = fut.await;
+            if let Some(hash) = hash {
+                in_flight.borrow_mut().remove(&hash);
+            }
+            if let Some(settle) = settle {
+                settle(&new_value);
+            }
             input.set(None);
             pending.set(false);
             value.set(Some(new_value));
@@ -142,6 +176,204 @@ where
     }
 }
 
+impl<I, O> Action<I, O>
+where
+    I: Hash + 'static,
+    O: 'static,
+{
+    /// Ignores any dispatch whose input is equal to the input of a call that is still pending,
+    /// as determined by its hash, so that, e.g., double-clicking a submit button doesn't
+    /// send the same mutation to the server twice. Different inputs still run concurrently.
+    ///
+    /// ```rust
+    /// # use leptos_reactive::*;
+    /// # use leptos_server::create_action;
+    /// # run_scope(create_runtime(), |cx| {
+    /// async fn add_todo(task: String) -> usize {
+    ///     // do something...
+    ///     42
+    /// }
+    /// let save_data = create_action(cx, |task: &String| add_todo(task.clone())).deduplicate();
+    /// # if false {
+    /// save_data.dispatch("My todo".to_string());
+    /// // ignored, as the first call is still pending
+    /// save_data.dispatch("My todo".to_string());
+    /// # }
+    /// # });
+    /// ```
+    pub fn deduplicate(mut self) -> Self {
+        self.input_hash = Some(|input: &I| {
+            let mut hasher = DefaultHasher::new();
+            input.hash(&mut hasher);
+            hasher.finish()
+        });
+        self
+    }
+}
+
+impl<I, T, E> Action<I, Result<T, E>>
+where
+    I: 'static,
+    T: 'static,
+    E: 'static,
+{
+    /// Adds an optimistic update to the action, which makes the UI respond to a dispatch
+    /// right away instead of waiting for the `async` function to resolve.
+    ///
+    /// Each time the action is dispatched, `optimistic` is called with a reference to the input,
+    /// before the `async` function. It should apply a provisional update to whichever signals
+    /// the action is expected to change, and return a function that undoes it. If the
+    /// function resolves to an `Err`, that function is called to roll the update back; if it
+    /// resolves to `Ok`, the update is kept.
+    ///
+    /// ```rust
+    /// # use leptos_reactive::*;
+    /// # use leptos_server::create_action;
+    /// # run_scope(create_runtime(), |cx| {
+    /// let todos = create_rw_signal(cx, vec!["Buy milk".to_string()]);
+    ///
+    /// let add_todo = create_action(cx, |task: &String| {
+    ///   let task = task.clone();
+    ///   async move {
+    ///     // pretend the server rejects empty tasks
+    ///     if task.is_empty() {
+    ///       Err("empty task".to_string())
+    ///     } else {
+    ///       Ok(())
+    ///     }
+    ///   }
+    /// })
+    /// .optimistic(move |task: &String| {
+    ///   // show the new task right away...
+    ///   todos.update(|todos| todos.push(task.clone()));
+    ///   // ...and remove it again if adding it fails
+    ///   move || todos.update(|todos| _ = todos.pop())
+    /// });
+    ///
+    /// # if !cfg!(any(feature = "csr", feature = "hydrate")) {
+    /// add_todo.dispatch("Profit!!!".to_string());
+    /// assert_eq!(todos.get().len(), 2);
+    ///
+    /// add_todo.dispatch(String::new());
+    /// assert_eq!(todos.get().len(), 2);
+    /// assert_eq!(add_todo.value.get(), Some(Err("empty task".to_string())));
+    /// # }
+    /// # });
+    /// ```
+    pub fn optimistic<F, R>(mut self, optimistic: F) -> Self
+    where
+        F: Fn(&I) -> R + 'static,
+        R: FnOnce() + 'static,
+    {
+        self.optimistic = Some(Rc::new(move |input: &I| {
+            let rollback = optimistic(input);
+            Box::new(move |result: &Result<T, E>| {
+                if result.is_err() {
+                    rollback();
+                }
+            }) as Box<dyn FnOnce(&Result<T, E>)>
+        }));
+        self
+    }
+
+    /// Calls the `async` function again when it resolves to an `Err`, as often and as long
+    /// after the failure as the [RetryPolicy] allows, e.g., so a form submission isn't lost to
+    /// a flaky mobile connection. The action stays pending until the last attempt resolves,
+    /// and only its result is stored in [Action::value].
+    ///
+    /// The delays between attempts are only applied in the browser; on the server, failed
+    /// calls are retried right away.
+    ///
+    /// ```rust
+    /// # use leptos_reactive::*;
+    /// # use leptos_server::{create_action, RetryPolicy};
+    /// # use std::{cell::Cell, rc::Rc, time::Duration};
+    /// # run_scope(create_runtime(), |cx| {
+    /// let attempts = Rc::new(Cell::new(0));
+    /// let flaky = create_action(cx, {
+    ///   let attempts = Rc::clone(&attempts);
+    ///   move |_: &()| {
+    ///     // pretend the first two attempts time out
+    ///     attempts.set(attempts.get() + 1);
+    ///     let result = if attempts.get() < 3 { Err("timed out") } else { Ok(42) };
+    ///     async move { result }
+    ///   }
+    /// })
+    /// .retry(RetryPolicy {
+    ///   retries: 3,
+    ///   delay: Duration::from_millis(200),
+    ///   backoff: 2.0,
+    /// });
+    ///
+    /// # if !cfg!(any(feature = "csr", feature = "hydrate")) {
+    /// flaky.dispatch(());
+    /// assert_eq!(attempts.get(), 3);
+    /// assert_eq!(flaky.value.get(), Some(Ok(42)));
+    /// # }
+    /// # });
+    /// ```
+    pub fn retry(mut self, policy: RetryPolicy) -> Self
+    where
+        I: Clone,
+    {
+        let cx = self.cx;
+        let action_fn = Rc::clone(&self.action_fn);
+        self.action_fn = Rc::new(move |input: &I| {
+            let action_fn = Rc::clone(&action_fn);
+            let input = input.clone();
+            Box::pin(async move {
+                let mut delay = policy.delay;
+                let mut retries = 0;
+                loop {
+                    let result = action_fn(&input).await;
+                    if result.is_ok() || retries >= policy.retries || !sleep(cx, delay).await {
+                        return result;
+                    }
+                    retries += 1;
+                    delay = delay.mul_f64(policy.backoff);
+                }
+            }) as Pin<Box<dyn Future<Output = Result<T, E>>>>
+        });
+        self
+    }
+}
+
+/// How an [Action] retries calls that fail, set with [Action::retry].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// How many times a failed call is retried before its error is returned.
+    pub retries: usize,
+    /// How long to wait before the first retry.
+    pub delay: Duration,
+    /// What the delay is multiplied by after each retry: `1.0` keeps it constant, while `2.0`
+    /// doubles it each time.
+    pub backoff: f64,
+}
+
+impl Default for RetryPolicy {
+    /// Retries three times, after 250ms, 500ms, and 1s.
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            delay: Duration::from_millis(250),
+            backoff: 2.0,
+        }
+    }
+}
+
+/// Waits for `duration` in the browser, returning `false` if `cx` is disposed first.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+async fn sleep(cx: Scope, duration: Duration) -> bool {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    leptos_reactive::create_timeout(cx, duration, move || _ = tx.send(()));
+    rx.await.is_ok()
+}
+
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+async fn sleep(_cx: Scope, _duration: Duration) -> bool {
+    true
+}
+
 /// Creates an [Action] to synchronize an imperative `async` call to the synchronous reactive system.
 ///
 /// If you’re trying to load data by running an `async` function reactively, you probably
@@ -234,12 +466,16 @@ where
     });
 
     Action {
+        cx,
         version,
         url: None,
         input,
         value,
         pending,
         action_fn,
+        optimistic: None,
+        input_hash: None,
+        in_flight: Default::default(),
     }
 }
 
@@ -259,7 +495,10 @@ where
 /// let my_server_action = create_server_action::<MyServerFn>(cx);
 /// # });
 /// ```
-pub fn create_server_action<S>(cx: Scope) -> Action<S, Result<S::Output, ServerFnError>>
+///
+/// As a server function can always fail, a server action can be given an
+/// [optimistic](Action::optimistic) update, which is rolled back if the call returns an error.
+pub fn create_server_action<S>(cx: Scope) -> Action<S, Result<S::Output, S::Error>>
 where
     S: Clone + ServerFn,
 {
@@ -0,0 +1,259 @@
+use crate::ServerFnError;
+use serde::{Deserialize, Serialize};
+
+/// The path, under the prefix of the server functions, that a batch of calls is sent to.
+pub const BATCH_PATH: &str = "__batch";
+
+/// The largest number of calls a batch can hold. The server refuses larger batches, so that
+/// one request can't make it run any number of functions, and the client splits the calls it
+/// has queued into batches of at most this many.
+pub const MAX_BATCH_SIZE: usize = 32;
+
+/// One call of a batch: the path of the server function, and its URL-encoded arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchedCall {
+    /// The path the server function is registered at.
+    pub path: String,
+    /// The arguments, encoded as they would be in the body of a request of their own.
+    pub args: String,
+}
+
+/// The result of one call of a batch: the status and the body of the response it would have
+/// been sent on its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchedResult {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The serialized value the function returned, or the error it failed with.
+    pub body: String,
+}
+
+/// Turns batching of server function calls on or off in the browser. It's off by default.
+///
+/// While it's on, the calls that are made within the same tick, e.g., by the resources of a
+/// page as it hydrates, are sent to the server together, in one request to the
+/// [BATCH_PATH] under their prefix, rather than each in a request of its own, in batches of
+/// at most [MAX_BATCH_SIZE] calls. The server integrations run each call as if it had been
+/// sent on its own, with the headers of the batch, so middleware, guards and limits apply to each of them, and respond with the
+/// results in order; headers set with [ResponseOptions](crate::ResponseOptions) aren't sent.
+///
+/// Only calls that can be sent as text are batched: functions that are called with `POST`
+/// and the `"url"` encoding, and that don't stream their values. Others are sent as usual.
+/// ```
+/// # use leptos_server::*;
+/// enable_server_fn_batching(true);
+/// assert!(server_fn_batching());
+/// ```
+pub fn enable_server_fn_batching(enabled: bool) {
+    BATCHING.with(|batching| batching.set(enabled));
+}
+
+/// Whether calls to server functions are batched, as set with [enable_server_fn_batching].
+pub fn server_fn_batching() -> bool {
+    BATCHING.with(|batching| batching.get())
+}
+
+thread_local! {
+    static BATCHING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Reads the calls of a batch from the body of the request, which can hold no more than
+/// [MAX_BATCH_SIZE] of them.
+#[cfg(any(feature = "ssr", doc))]
+pub fn parse_server_fn_batch(body: &[u8]) -> Result<Vec<BatchedCall>, ServerFnError> {
+    let calls: Vec<BatchedCall> =
+        serde_json::from_slice(body).map_err(|e| ServerFnError::Args(e.to_string()))?;
+    if calls.len() > MAX_BATCH_SIZE {
+        return Err(ServerFnError::Args(format!(
+            "a batch can hold at most {MAX_BATCH_SIZE} calls, but this one holds {}",
+            calls.len()
+        )));
+    }
+    Ok(calls)
+}
+
+/// Writes the results of a batch as the body of the response.
+#[cfg(any(feature = "ssr", doc))]
+pub fn encode_server_fn_batch(results: &[BatchedResult]) -> String {
+    serde_json::to_string(results).unwrap_or_default()
+}
+
+#[cfg(not(feature = "ssr"))]
+pub(crate) use client::call_batched;
+
+#[cfg(not(feature = "ssr"))]
+mod client {
+    use super::*;
+    use crate::{CsrfToken, CSRF_HEADER};
+    use futures::channel::oneshot;
+    use leptos_reactive::{queue_microtask, spawn_local};
+    use std::{cell::RefCell, collections::HashMap};
+
+    type PendingCall = (
+        BatchedCall,
+        oneshot::Sender<Result<BatchedResult, ServerFnError>>,
+    );
+
+    thread_local! {
+        static PENDING: RefCell<HashMap<&'static str, Vec<PendingCall>>> = Default::default();
+    }
+
+    /// Queues a call to be sent with the others made in the same tick under the same prefix.
+    pub(crate) async fn call_batched(
+        prefix: &'static str,
+        path: &'static str,
+        args: String,
+    ) -> Result<BatchedResult, ServerFnError> {
+        let (tx, rx) = oneshot::channel();
+        let call = BatchedCall {
+            path: path.to_string(),
+            args,
+        };
+        // the first call of a batch schedules it to be sent
+        let is_first = PENDING.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let calls = pending.entry(prefix).or_default();
+            calls.push((call, tx));
+            calls.len() == 1
+        });
+        if is_first {
+            queue_microtask(move || {
+                let mut calls = PENDING
+                    .with(|pending| pending.borrow_mut().remove(prefix))
+                    .unwrap_or_default();
+                // the server refuses larger batches, so the calls are split among several
+                while calls.len() > MAX_BATCH_SIZE {
+                    let rest = calls.split_off(MAX_BATCH_SIZE);
+                    spawn_local(send_batch(prefix, calls));
+                    calls = rest;
+                }
+                spawn_local(send_batch(prefix, calls));
+            });
+        }
+        rx.await
+            .unwrap_or_else(|_| Err(ServerFnError::Request("the batch was dropped".to_string())))
+    }
+
+    async fn send_batch(prefix: &'static str, calls: Vec<PendingCall>) {
+        let (calls, senders): (Vec<_>, Vec<_>) = calls.into_iter().unzip();
+        let results = match fetch_batch(prefix, &calls).await {
+            Ok(results) if results.len() == senders.len() => {
+                results.into_iter().map(Ok).collect::<Vec<_>>()
+            }
+            Ok(_) => {
+                let e = ServerFnError::Deserialization(
+                    "the batch has a different number of results than calls".to_string(),
+                );
+                vec![Err(e); senders.len()]
+            }
+            Err(e) => vec![Err(e); senders.len()],
+        };
+        for (tx, result) in senders.into_iter().zip(results) {
+            _ = tx.send(result);
+        }
+    }
+
+    async fn fetch_batch(
+        prefix: &str,
+        calls: &[BatchedCall],
+    ) -> Result<Vec<BatchedResult>, ServerFnError> {
+        let body = serde_json::to_string(calls)
+            .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+        let request = gloo_net::http::Request::post(&format!("{prefix}/{BATCH_PATH}"))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json");
+        let request = match CsrfToken::from_document() {
+            Some(token) => request.header(CSRF_HEADER, token.as_str()),
+            None => request,
+        };
+        let resp = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ServerFnError::Request(e.to_string()))?;
+        if resp.status() >= 400 {
+            return Err(ServerFnError::Request(resp.status_text()));
+        }
+        resp.json()
+            .await
+            .map_err(|e| ServerFnError::Deserialization(e.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn call(path: &str, args: &str) -> BatchedCall {
+        BatchedCall {
+            path: path.to_string(),
+            args: args.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_the_calls_of_a_batch() {
+        let body = r#"[{"path":"add","args":"a=1&b=2"},{"path":"get_user","args":"id=5"}]"#;
+        assert_eq!(
+            parse_server_fn_batch(body.as_bytes()).unwrap(),
+            [call("add", "a=1&b=2"), call("get_user", "id=5")]
+        );
+    }
+
+    #[test]
+    fn parses_what_the_client_sends() {
+        let calls = vec![call("add", "a=1&b=2"), call("add", "")];
+        let body = serde_json::to_string(&calls).unwrap();
+        assert_eq!(parse_server_fn_batch(body.as_bytes()).unwrap(), calls);
+    }
+
+    #[test]
+    fn rejects_malformed_batches() {
+        assert!(matches!(
+            parse_server_fn_batch(b"{\"path\":\"add\"}"),
+            Err(ServerFnError::Args(_))
+        ));
+        assert!(matches!(
+            parse_server_fn_batch(b"[{\"path\":\"add\"}]"),
+            Err(ServerFnError::Args(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_batches_with_too_many_calls() {
+        let calls = vec![call("add", "a=1&b=2"); MAX_BATCH_SIZE];
+        let body = serde_json::to_string(&calls).unwrap();
+        assert_eq!(
+            parse_server_fn_batch(body.as_bytes()).unwrap().len(),
+            MAX_BATCH_SIZE
+        );
+
+        let calls = vec![call("add", "a=1&b=2"); MAX_BATCH_SIZE + 1];
+        let body = serde_json::to_string(&calls).unwrap();
+        assert!(matches!(
+            parse_server_fn_batch(body.as_bytes()),
+            Err(ServerFnError::Args(_))
+        ));
+    }
+
+    #[test]
+    fn encodes_results_in_order() {
+        let results = [
+            BatchedResult {
+                status: 200,
+                body: "3".to_string(),
+            },
+            BatchedResult {
+                status: 500,
+                body: r#"{"ServerError":"no such user"}"#.to_string(),
+            },
+        ];
+        let encoded = encode_server_fn_batch(&results);
+        assert_eq!(
+            encoded,
+            r#"[{"status":200,"body":"3"},{"status":500,"body":"{\"ServerError\":\"no such user\"}"}]"#
+        );
+        let decoded: Vec<BatchedResult> = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, results);
+    }
+}
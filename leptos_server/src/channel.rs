@@ -0,0 +1,406 @@
+use crate::ServerFnError;
+use futures::{channel::mpsc::UnboundedSender, Stream};
+use leptos_reactive::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(any(feature = "ssr", doc))]
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+};
+
+/// Describes a bidirectional channel between the client and the server over a WebSocket,
+/// for realtime features like presence or collaborative editing that a request and a
+/// response can't express.
+///
+/// The type is shared by the client and the server: the client opens the channel with
+/// [create_server_channel], and the server answers it with the handler registered with
+/// [register_server_channel]. Each message is serialized as JSON and sent as a text frame.
+///
+/// The server integrations accept the WebSocket connection at the [ServerChannel::prefix]
+/// under which they are mounted, e.g., with `leptos_axum::handle_server_channels` or
+/// `leptos_actix::handle_server_channels()`.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use serde::{Deserialize, Serialize};
+/// use futures::StreamExt;
+///
+/// #[derive(Serialize, Deserialize, Clone)]
+/// pub struct Join {
+///     pub name: String,
+/// }
+///
+/// pub struct Presence;
+///
+/// impl ServerChannel for Presence {
+///     type ClientMsg = Join;
+///     type ServerMsg = Vec<String>;
+///
+///     fn prefix() -> &'static str {
+///         "/ws"
+///     }
+///
+///     fn url() -> &'static str {
+///         "presence"
+///     }
+/// }
+///
+/// // on the server
+/// # #[cfg(feature = "ssr")]
+/// register_server_channel::<Presence, _, _>(|_cx, mut incoming, outgoing| async move {
+///     let mut users = Vec::new();
+///     while let Some(Join { name }) = incoming.next().await {
+///         users.push(name);
+///         _ = outgoing.send(&users);
+///     }
+/// })
+/// .unwrap();
+///
+/// // in a component
+/// # run_scope(create_runtime(), |cx| {
+/// # if cfg!(feature = "ssr") {
+/// let presence = create_server_channel::<Presence>(cx);
+/// presence.send(&Join { name: "ferris".into() });
+/// let online = move || presence.message().with(|users| users.as_ref().map(Vec::len));
+/// # assert_eq!(online(), None);
+/// # }
+/// # });
+/// ```
+pub trait ServerChannel: 'static {
+    /// The messages the client sends to the server.
+    type ClientMsg: Serialize + DeserializeOwned + 'static;
+
+    /// The messages the server sends to the client.
+    type ServerMsg: Serialize + DeserializeOwned + 'static;
+
+    /// The URL prefix the server integration that accepts the channel is mounted under.
+    fn prefix() -> &'static str;
+
+    /// The path at which the channel can be opened on the server.
+    fn url() -> &'static str;
+}
+
+/// The state of the connection of a [ChannelHandle].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelState {
+    /// The connection is being opened.
+    Connecting,
+    /// Messages can be sent and received.
+    Open,
+    /// The connection was closed, or couldn't be opened. A channel isn't reopened
+    /// automatically; create a new one to reconnect.
+    Closed,
+}
+
+/// The client's side of a [ServerChannel], as returned by [create_server_channel]: sends
+/// messages to the server, and holds the latest message it received in a signal.
+///
+/// On the server, e.g., while rendering, the channel isn't connected, so it stays in the
+/// [ChannelState::Connecting] state and messages sent to it are dropped.
+pub struct ChannelHandle<C: ServerChannel> {
+    message: ReadSignal<Option<C::ServerMsg>>,
+    state: ReadSignal<ChannelState>,
+    outgoing: Option<UnboundedSender<String>>,
+}
+
+impl<C: ServerChannel> ChannelHandle<C> {
+    /// Sends a message to the server. Messages sent while the connection is being opened are
+    /// sent once it's open.
+    pub fn send(&self, message: &C::ClientMsg) {
+        let message = match serde_json::to_string(message) {
+            Ok(message) => message,
+            Err(e) => {
+                report_error(
+                    &ServerFnError::Serialization(e.to_string()),
+                    ErrorLocation::ServerFn(C::url().to_string()),
+                );
+                return;
+            }
+        };
+        if let Some(outgoing) = &self.outgoing {
+            _ = outgoing.unbounded_send(message);
+        }
+    }
+
+    /// The latest message received from the server, or `None` if none has arrived yet.
+    pub fn message(&self) -> ReadSignal<Option<C::ServerMsg>> {
+        self.message
+    }
+
+    /// The state of the connection.
+    pub fn state(&self) -> ReadSignal<ChannelState> {
+        self.state
+    }
+}
+
+impl<C: ServerChannel> Clone for ChannelHandle<C> {
+    fn clone(&self) -> Self {
+        Self {
+            message: self.message,
+            state: self.state,
+            outgoing: self.outgoing.clone(),
+        }
+    }
+}
+
+impl<C: ServerChannel> Debug for ChannelHandle<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelHandle")
+            .field("url", &C::url())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Opens the [ServerChannel] `C` over a WebSocket to the server the page was loaded from.
+///
+/// The connection is closed when the [Scope] is disposed. Messages from the server that can't
+/// be deserialized are passed to the hook set with
+/// [set_error_hook](leptos_reactive::set_error_hook) and skipped.
+pub fn create_server_channel<C: ServerChannel>(cx: Scope) -> ChannelHandle<C> {
+    let (message, set_message) = create_signal(cx, None);
+    let (state, set_state) = create_signal(cx, ChannelState::Connecting);
+
+    #[cfg(not(feature = "ssr"))]
+    let outgoing = connect::<C>(cx, set_message, set_state);
+    #[cfg(feature = "ssr")]
+    let outgoing = {
+        _ = (set_message, set_state);
+        None
+    };
+
+    ChannelHandle {
+        message,
+        state,
+        outgoing,
+    }
+}
+
+/// Opens the WebSocket of a channel, returning the queue of the messages to send over it.
+#[cfg(not(feature = "ssr"))]
+fn connect<C: ServerChannel>(
+    cx: Scope,
+    set_message: WriteSignal<Option<C::ServerMsg>>,
+    set_state: WriteSignal<ChannelState>,
+) -> Option<UnboundedSender<String>> {
+    use futures::{SinkExt, StreamExt};
+    use gloo_net::websocket::{futures::WebSocket, Message};
+
+    let url = channel_url(C::prefix(), C::url());
+    let socket = match WebSocket::open(&url) {
+        Ok(socket) => socket,
+        Err(e) => {
+            report_error(
+                &ServerFnError::Request(e.to_string()),
+                ErrorLocation::ServerFn(url),
+            );
+            set_state.set(ChannelState::Closed);
+            return None;
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+    let (outgoing, mut queued) = futures::channel::mpsc::unbounded::<String>();
+
+    spawn_local_scoped(cx, async move {
+        // the socket is ready once the connection is open
+        if futures::future::poll_fn(|cx| sink.poll_ready_unpin(cx))
+            .await
+            .is_err()
+        {
+            set_state.set(ChannelState::Closed);
+            return;
+        }
+        set_state.set(ChannelState::Open);
+        while let Some(message) = queued.next().await {
+            if sink.send(Message::Text(message)).await.is_err() {
+                break;
+            }
+        }
+    });
+    spawn_local_scoped(cx, async move {
+        while let Some(Ok(received)) = stream.next().await {
+            let received = match received {
+                Message::Text(text) => serde_json::from_str(&text),
+                Message::Bytes(bytes) => serde_json::from_slice(&bytes),
+            };
+            match received {
+                Ok(received) => set_message.set(Some(received)),
+                Err(e) => report_error(
+                    &ServerFnError::Deserialization(e.to_string()),
+                    ErrorLocation::ServerFn(url.clone()),
+                ),
+            }
+        }
+        set_state.set(ChannelState::Closed);
+    });
+
+    Some(outgoing)
+}
+
+/// The `ws:` or `wss:` URL of a channel on the server the page was loaded from.
+#[cfg(not(feature = "ssr"))]
+fn channel_url(prefix: &str, url: &str) -> String {
+    let location = leptos_dom::window().location();
+    let scheme = match location.protocol() {
+        Ok(protocol) if protocol == "https:" => "wss:",
+        _ => "ws:",
+    };
+    let host = location.host().unwrap_or_default();
+    format!("{scheme}//{host}{prefix}/{url}")
+}
+
+/// The messages a client sends over a [ServerChannel], as received by the handler registered
+/// with [register_server_channel]. The stream ends when the client disconnects.
+///
+/// Messages that can't be deserialized are passed to the hook set with
+/// [set_error_hook](leptos_reactive::set_error_hook) and skipped.
+pub struct ChannelReceiver<T> {
+    url: &'static str,
+    incoming: Pin<Box<dyn Stream<Item = String>>>,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Stream for ChannelReceiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.incoming.as_mut().poll_next(cx) {
+                Poll::Ready(Some(message)) => match serde_json::from_str(&message) {
+                    Ok(message) => return Poll::Ready(Some(message)),
+                    Err(e) => report_error(
+                        &ServerFnError::Args(e.to_string()),
+                        ErrorLocation::ServerFn(self.url.to_string()),
+                    ),
+                },
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Debug for ChannelReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelReceiver")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends messages to the client of a [ServerChannel] from the handler registered with
+/// [register_server_channel]. It can be cloned, e.g., to send messages from another task;
+/// the connection is closed once the handler has returned and all the senders are dropped.
+pub struct ChannelSender<T> {
+    outgoing: UnboundedSender<String>,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize> ChannelSender<T> {
+    /// Sends a message to the client, returning an error if it can't be serialized or the
+    /// client has disconnected.
+    pub fn send(&self, message: &T) -> Result<(), ServerFnError> {
+        let message = serde_json::to_string(message)
+            .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+        self.outgoing
+            .unbounded_send(message)
+            .map_err(|_| ServerFnError::Request("the client has disconnected".to_string()))
+    }
+
+    /// Whether the client has disconnected.
+    pub fn is_closed(&self) -> bool {
+        self.outgoing.is_closed()
+    }
+}
+
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            outgoing: self.outgoing.clone(),
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for ChannelSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelSender").finish_non_exhaustive()
+    }
+}
+
+/// A WebSocket connection that a server integration accepted for a [ServerChannel], which it
+/// passes to the handler found with [server_channel_by_path].
+pub struct ChannelConnection {
+    /// The text messages the client sends, which end when it disconnects.
+    pub incoming: Pin<Box<dyn Stream<Item = String>>>,
+    /// Sends text messages to the client. The integration closes the connection once all the
+    /// senders are dropped.
+    pub outgoing: UnboundedSender<String>,
+}
+
+impl Debug for ChannelConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelConnection").finish_non_exhaustive()
+    }
+}
+
+/// The handler of a [ServerChannel], as returned by [server_channel_by_path].
+#[cfg(any(feature = "ssr", doc))]
+pub type ServerChannelHandler =
+    dyn Fn(Scope, ChannelConnection) -> Pin<Box<dyn Future<Output = ()>>> + Send + Sync;
+
+#[cfg(any(feature = "ssr", doc))]
+lazy_static::lazy_static! {
+    static ref REGISTERED_SERVER_CHANNELS: Arc<RwLock<HashMap<&'static str, Arc<ServerChannelHandler>>>> = Default::default();
+}
+
+/// Registers the handler that answers the [ServerChannel] `C` on the server. It runs once for
+/// each connection, in a [Scope] that the integration provides the request as context to, and
+/// receives the messages of the client and a sender for the messages to it.
+#[cfg(any(feature = "ssr", doc))]
+pub fn register_server_channel<C, F, Fut>(handler: F) -> Result<(), ServerFnError>
+where
+    C: ServerChannel,
+    F: Fn(Scope, ChannelReceiver<C::ClientMsg>, ChannelSender<C::ServerMsg>) -> Fut
+        + Send
+        + Sync
+        + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let handler = Arc::new(move |cx: Scope, connection: ChannelConnection| {
+        let incoming = ChannelReceiver {
+            url: C::url(),
+            incoming: connection.incoming,
+            ty: PhantomData,
+        };
+        let outgoing = ChannelSender {
+            outgoing: connection.outgoing,
+            ty: PhantomData,
+        };
+        Box::pin(handler(cx, incoming, outgoing)) as Pin<Box<dyn Future<Output = ()>>>
+    });
+
+    let mut write = REGISTERED_SERVER_CHANNELS
+        .write()
+        .map_err(|e| ServerFnError::Registration(e.to_string()))?;
+    write.insert(C::url(), handler);
+    Ok(())
+}
+
+/// Returns the handler of the [ServerChannel] registered at the given path, which the server
+/// integrations run for each WebSocket connection they accept for it.
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_channel_by_path(path: &str) -> Option<Arc<ServerChannelHandler>> {
+    REGISTERED_SERVER_CHANNELS
+        .read()
+        .ok()
+        .and_then(|channels| channels.get(path).cloned())
+}
@@ -0,0 +1,321 @@
+use crate::{
+    Codec, CsrfToken, Encoding, HttpMethod, ServerFn, ServerFnError, ServerFnErrorKind, CSRF_HEADER,
+};
+use leptos_reactive::{report_error, ErrorLocation};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+};
+
+type HeaderHookFn = Arc<dyn Fn() -> Vec<(String, String)> + Send + Sync>;
+
+/// An HTTP request that calls a server function, which a [ClientTransport] sends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientRequest {
+    /// The method of the request.
+    pub method: HttpMethod,
+    /// The full URL of the server function, including the query string of a `GET` request.
+    pub url: String,
+    /// The headers of the request.
+    pub headers: Vec<(String, String)>,
+    /// The encoded arguments, which is empty for a `GET` request.
+    pub body: Vec<u8>,
+}
+
+/// The response to a [ClientRequest].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientResponse {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The reason phrase of the status, which is used as the error message if the body doesn't
+    /// hold an error.
+    pub status_text: String,
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+/// Sends the HTTP requests of a [ServerFnClient]. The default [FetchTransport] uses the
+/// browser's `fetch`; a native app plugs in its own HTTP client instead, e.g., `reqwest`.
+pub trait ClientTransport: Send + Sync + 'static {
+    /// Sends the request, returning an error only if no response was received.
+    #[allow(clippy::type_complexity)]
+    fn send(
+        &self,
+        req: ClientRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ClientResponse, ServerFnError>>>>;
+}
+
+/// A [ClientTransport] that sends requests with the browser's `fetch`, which only works when
+/// compiled to WebAssembly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchTransport;
+
+impl ClientTransport for FetchTransport {
+    fn send(
+        &self,
+        req: ClientRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ClientResponse, ServerFnError>>>> {
+        Box::pin(async move {
+            let mut request = match req.method {
+                HttpMethod::Get => gloo_net::http::Request::get(&req.url),
+                HttpMethod::Post => gloo_net::http::Request::post(&req.url)
+                    .body(leptos_dom::js_sys::Uint8Array::from(req.body.as_slice()).buffer()),
+            };
+            for (name, value) in &req.headers {
+                request = request.header(name, value);
+            }
+            let resp = request
+                .send()
+                .await
+                .map_err(|e| ServerFnError::Request(e.to_string()))?;
+            Ok(ClientResponse {
+                status: resp.status(),
+                status_text: resp.status_text(),
+                body: resp
+                    .binary()
+                    .await
+                    .map_err(|e| ServerFnError::Deserialization(e.to_string()))?,
+            })
+        })
+    }
+}
+
+/// Calls server functions from any Rust code, not only from components: e.g., from a native
+/// desktop or mobile app that shares its API layer with a Leptos app.
+///
+/// The `#[server]` macro generates a `call` function on the type of each server function
+/// that takes a client, so that `GetPosts::call(&client, page)` calls `get_posts(page)` on
+/// the server at the client's base URL. The client sends the headers returned by the hook
+/// set with [ServerFnClient::with_headers] with every request, e.g., to authenticate it.
+/// Server functions that stream their values can't be called with a client.
+///
+/// In the browser, the client sends the [CsrfToken] of the page with each `POST` request. A
+/// client outside the browser has to send the token cookie and the token itself, in the
+/// `X-CSRF-Token` header, or call functions that opt out with `csrf = false`.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// # use std::{future::Future, pin::Pin};
+/// #[server(Add, "/api")]
+/// async fn add(a: i32, b: i32) -> Result<i32, ServerFnError> {
+///     Ok(a + b)
+/// }
+///
+/// // stands in for a transport that uses an HTTP client like `reqwest`
+/// struct Mock;
+///
+/// impl ClientTransport for Mock {
+///     fn send(
+///         &self,
+///         req: ClientRequest,
+///     ) -> Pin<Box<dyn Future<Output = Result<ClientResponse, ServerFnError>>>> {
+///         assert!(req.url.starts_with("https://example.com/api/"));
+///         assert!(req.headers.contains(&("Authorization".into(), "Bearer token".into())));
+///         Box::pin(async move {
+///             Ok(ClientResponse {
+///                 status: 200,
+///                 status_text: "OK".into(),
+///                 body: b"3".to_vec(),
+///             })
+///         })
+///     }
+/// }
+///
+/// let client = ServerFnClient::new("https://example.com")
+///     .with_transport(Mock)
+///     .with_headers(|| vec![("Authorization".into(), "Bearer token".into())]);
+/// let sum = futures::executor::block_on(Add::call(&client, 1, 2));
+/// assert_eq!(sum.unwrap(), 3);
+/// ```
+#[derive(Clone)]
+pub struct ServerFnClient {
+    base_url: String,
+    headers: Option<HeaderHookFn>,
+    transport: Arc<dyn ClientTransport>,
+}
+
+impl ServerFnClient {
+    /// Creates a client that calls the server functions of the server at `base_url`, e.g.,
+    /// `"https://example.com"`, using the [FetchTransport].
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            headers: None,
+            transport: Arc::new(FetchTransport),
+        }
+    }
+
+    /// Sends the requests with the given transport.
+    pub fn with_transport(mut self, transport: impl ClientTransport) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Sets a hook that returns headers to send with every request, e.g., an `Authorization`
+    /// header with the current token.
+    pub fn with_headers(
+        mut self,
+        headers: impl Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    ) -> Self {
+        self.headers = Some(Arc::new(headers));
+        self
+    }
+
+    /// The URL of the server the client calls.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Calls the server function with the given arguments.
+    ///
+    /// If the call fails, the error is passed to the hook set with
+    /// [set_error_hook](leptos_reactive::set_error_hook) as well.
+    pub async fn call<F>(&self, args: F) -> Result<F::Output, F::Error>
+    where
+        F: ServerFn,
+        F::Output: DeserializeOwned,
+    {
+        let url = format!(
+            "{}{}/{}",
+            self.base_url.trim_end_matches('/'),
+            F::prefix(),
+            F::url()
+        );
+        let res = self.send(&url, args).await;
+        if let Err(e) = &res {
+            report_error(e, ErrorLocation::ServerFn(url));
+        }
+        res
+    }
+
+    async fn send<F>(&self, url: &str, args: F) -> Result<F::Output, F::Error>
+    where
+        F: ServerFn,
+        F::Output: DeserializeOwned,
+    {
+        let enc = F::encoding();
+        let (content_type, body) = encode_args(args, &enc)?;
+
+        let mut headers = vec![("Accept".to_string(), accept_header(&enc).to_string())];
+        // the arguments of a GET request are sent in the query string
+        let (url, body) = match F::method() {
+            HttpMethod::Get => (
+                format!("{url}?{}", String::from_utf8_lossy(&body)),
+                Vec::new(),
+            ),
+            HttpMethod::Post => {
+                headers.push(("Content-Type".to_string(), content_type));
+                if let Some(token) = CsrfToken::from_document() {
+                    headers.push((CSRF_HEADER.to_string(), token.as_str().to_string()));
+                }
+                (url.to_string(), body)
+            }
+        };
+        if let Some(hook) = &self.headers {
+            headers.extend(hook());
+        }
+
+        let resp = self
+            .transport
+            .send(ClientRequest {
+                method: F::method(),
+                url,
+                headers,
+                body,
+            })
+            .await?;
+        if resp.status >= 400 {
+            return Err(decode_error::<F::Error, F::Codec>(
+                &enc,
+                &resp.body,
+                &resp.status_text,
+            ));
+        }
+        Ok(decode_output::<F::Output, F::Codec>(&enc, &resp.body)?)
+    }
+}
+
+impl Debug for ServerFnClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerFnClient")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The `Accept` header of a request that calls a server function with the given encoding.
+pub(crate) fn accept_header(enc: &Encoding) -> &'static str {
+    match enc {
+        Encoding::Url => "application/x-www-form-urlencoded",
+        Encoding::Cbor => "application/cbor",
+        Encoding::Multipart => "application/json",
+        Encoding::Codec(content_type) => content_type,
+    }
+}
+
+/// Encodes the arguments of a server function, returning the `Content-Type` of the body and
+/// the body itself.
+pub(crate) fn encode_args<A: ServerFn>(
+    args: A,
+    enc: &Encoding,
+) -> Result<(String, Vec<u8>), ServerFnError> {
+    match enc {
+        Encoding::Url => serde_urlencoded::to_string(&args)
+            .map(|args| {
+                (
+                    "application/x-www-form-urlencoded".to_string(),
+                    args.into_bytes(),
+                )
+            })
+            .map_err(|e| ServerFnError::Serialization(e.to_string())),
+        Encoding::Cbor => {
+            let mut buffer: Vec<u8> = Vec::new();
+            ciborium::ser::into_writer(&args, &mut buffer)
+                .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+            Ok(("application/cbor".to_string(), buffer))
+        }
+        Encoding::Multipart => {
+            let data = args.into_multipart()?;
+            let boundary = data.boundary();
+            Ok((
+                format!("multipart/form-data; boundary={boundary}"),
+                data.encode(&boundary),
+            ))
+        }
+        Encoding::Codec(content_type) => Ok((content_type.to_string(), A::Codec::encode(&args)?)),
+    }
+}
+
+/// Decodes the body of a successful response to a server function call.
+pub(crate) fn decode_output<T: DeserializeOwned, C: Codec>(
+    enc: &Encoding,
+    body: &[u8],
+) -> Result<T, ServerFnError> {
+    match enc {
+        Encoding::Codec(_) => C::decode(body),
+        Encoding::Cbor => ciborium::de::from_reader(body)
+            .map_err(|e| ServerFnError::Deserialization(e.to_string())),
+        Encoding::Url | Encoding::Multipart => {
+            serde_json::from_slice(body).map_err(|e| ServerFnError::Deserialization(e.to_string()))
+        }
+    }
+}
+
+/// Decodes the error that a server function responded with, or, if the body doesn't hold
+/// one, creates a [ServerFnError::ServerError] from `status_text`.
+pub(crate) fn decode_error<E: ServerFnErrorKind, C: Codec>(
+    enc: &Encoding,
+    body: &[u8],
+    status_text: &str,
+) -> E {
+    let error = match enc {
+        Encoding::Codec(_) => C::decode(body).ok(),
+        Encoding::Cbor => ciborium::de::from_reader(body).ok(),
+        Encoding::Url | Encoding::Multipart => serde_json::from_slice(body).ok(),
+    };
+    error.unwrap_or_else(|| E::from(ServerFnError::ServerError(status_text.to_string())))
+}
@@ -0,0 +1,85 @@
+use crate::ServerFnError;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A format that the arguments, results and errors of a server function are encoded in,
+/// which is chosen with `#[server(Name, encoding = ...)]`.
+///
+/// Besides the built-in [Json] and [MsgPack] codecs, any type that implements this trait can be
+/// used, e.g., to send binary-heavy data like embeddings, images or game state in a compact
+/// format. The integrations send the encoded data with the codec's [Codec::CONTENT_TYPE].
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// # use serde::{de::DeserializeOwned, Serialize};
+/// pub struct Postcard;
+///
+/// impl Codec for Postcard {
+///     const CONTENT_TYPE: &'static str = "application/postcard";
+///
+///     fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, ServerFnError> {
+///         // e.g., `postcard::to_allocvec(value)`
+///         # unimplemented!()
+///     }
+///
+///     fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, ServerFnError> {
+///         // e.g., `postcard::from_bytes(data)`
+///         # unimplemented!()
+///     }
+/// }
+///
+/// #[server(GetEmbedding, encoding = Postcard)]
+/// async fn get_embedding(text: String) -> Result<Vec<f32>, ServerFnError> {
+///     todo!()
+/// }
+///
+/// #[server(GetGameState, encoding = "msgpack")]
+/// async fn get_game_state(id: u32) -> Result<Vec<u8>, ServerFnError> {
+///     todo!()
+/// }
+/// ```
+pub trait Codec: 'static {
+    /// The MIME type of the encoded data, which is sent in the `Content-Type` and `Accept`
+    /// headers.
+    const CONTENT_TYPE: &'static str;
+
+    /// Encodes a value.
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, ServerFnError>;
+
+    /// Decodes a value that was encoded with [Codec::encode].
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, ServerFnError>;
+}
+
+/// Encodes values as JSON (`application/json`), which is selected with `encoding = "json"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Json;
+
+impl Codec for Json {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, ServerFnError> {
+        serde_json::to_vec(value).map_err(|e| ServerFnError::Serialization(e.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, ServerFnError> {
+        serde_json::from_slice(data).map_err(|e| ServerFnError::Deserialization(e.to_string()))
+    }
+}
+
+/// Encodes values as [MessagePack](https://msgpack.org/) (`application/msgpack`), which is
+/// selected with `encoding = "msgpack"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MsgPack;
+
+impl Codec for MsgPack {
+    const CONTENT_TYPE: &'static str = "application/msgpack";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, ServerFnError> {
+        // structs are encoded as maps, so that fields can be added without breaking clients
+        rmp_serde::to_vec_named(value).map_err(|e| ServerFnError::Serialization(e.to_string()))
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, ServerFnError> {
+        rmp_serde::from_slice(data).map_err(|e| ServerFnError::Deserialization(e.to_string()))
+    }
+}
@@ -0,0 +1,240 @@
+use crate::RequestParts;
+
+#[cfg(any(feature = "ssr", doc))]
+use crate::ServerFnError;
+
+/// The name of the cookie that holds the CSRF token.
+pub const CSRF_COOKIE: &str = "leptos_csrf";
+
+/// The name of the header a client sends the CSRF token in when it calls a server function.
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// The name of the form field an `<ActionForm/>` sends the CSRF token in.
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// A token that protects server functions against cross-site request forgery.
+///
+/// The server integrations issue a token in a cookie when they render the app, and write it
+/// into a `<meta name="csrf-token">` tag in the `<head>` of the page, as well as provide it as
+/// context while rendering. Server functions that aren't called with `GET` are only run if the
+/// request sends the token of the cookie back, in the `X-CSRF-Token` header or in a `_csrf`
+/// form field: another site can make the browser send the cookie, but it can't read it, so it
+/// can't send the token. The client sends the header when it calls a server function, and
+/// `<ActionForm/>` adds the field, so this works without any changes to the app.
+///
+/// A server function can opt out with `#[server(Name, csrf = false)]`, e.g., if it's called
+/// by other services that don't have a token.
+/// ```
+/// # use leptos_server::*;
+/// let token = CsrfToken::from("abc".to_string());
+/// let req = RequestParts {
+///     path: "add_todo".to_string(),
+///     headers: vec![
+///         ("Cookie".to_string(), "leptos_csrf=abc".to_string()),
+///         ("X-CSRF-Token".to_string(), "abc".to_string()),
+///     ],
+/// };
+/// assert_eq!(CsrfToken::from_request(&req), Some(token.clone()));
+/// assert_eq!(token.meta_tag(), r#"<meta name="csrf-token" content="abc"/>"#);
+///
+/// # #[cfg(feature = "ssr")] {
+/// assert!(verify_csrf_token("POST", &req, b"title=Buy+milk").is_ok());
+///
+/// // the field of a form works as well as the header
+/// let mut req = req;
+/// req.headers.pop();
+/// assert!(verify_csrf_token("POST", &req, b"title=Buy+milk").is_err());
+/// assert!(verify_csrf_token("POST", &req, b"title=Buy+milk&_csrf=abc").is_ok());
+/// assert!(verify_csrf_token("POST", &req, b"title=Buy+milk&_csrf=xyz").is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    /// Creates a new random token.
+    #[cfg(feature = "ssr")]
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// Reads the token from the cookie of the request, if it sent one.
+    pub fn from_request(req: &RequestParts) -> Option<Self> {
+        req.cookie(CSRF_COOKIE)
+            .filter(|token| !token.is_empty())
+            .map(|token| Self(token.to_string()))
+    }
+
+    /// Reads the token from the `<meta name="csrf-token">` tag the server rendered into the
+    /// page. Returns `None` outside the browser.
+    pub fn from_document() -> Option<Self> {
+        if !cfg!(target_arch = "wasm32") {
+            return None;
+        }
+        web_sys::window()?
+            .document()?
+            .query_selector(r#"meta[name="csrf-token"]"#)
+            .ok()??
+            .get_attribute("content")
+            .filter(|token| !token.is_empty())
+            .map(Self)
+    }
+
+    /// The token itself.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The `<meta>` tag that holds the token in the `<head>` of the page.
+    pub fn meta_tag(&self) -> String {
+        format!(r#"<meta name="csrf-token" content="{}"/>"#, self.0)
+    }
+
+    /// The value of the `Set-Cookie` header that issues the token.
+    pub fn cookie(&self) -> String {
+        format!("{CSRF_COOKIE}={}; Path=/; HttpOnly; SameSite=Lax", self.0)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Default for CsrfToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<String> for CsrfToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+/// Checks that a request that calls a server function sends the [CsrfToken] of its cookie back,
+/// in the `X-CSRF-Token` header or in the `_csrf` field of a URL-encoded or
+/// `multipart/form-data` body. The server integrations call this before running a server
+/// function that isn't called with `GET`, and respond with `403 Forbidden` if it fails.
+///
+/// Requests with a safe method, like `GET` or `HEAD`, are let through, as they shouldn't
+/// change anything. Any other request is rejected if it doesn't send the token cookie, so a
+/// client outside the browser, e.g., one that authenticates with an `Authorization` header,
+/// either sends the cookie and the token or calls functions that opt out with `csrf = false`.
+#[cfg(any(feature = "ssr", doc))]
+pub fn verify_csrf_token(
+    method: &str,
+    req: &RequestParts,
+    body: &[u8],
+) -> Result<(), ServerFnError> {
+    if is_safe_method(method) {
+        return Ok(());
+    }
+    let expected = match CsrfToken::from_request(req) {
+        Some(token) => token,
+        None => return Err(csrf_error()),
+    };
+    let sent = match req.header(CSRF_HEADER) {
+        Some(token) => Some(token.to_string()),
+        None => csrf_field(req, body),
+    };
+    match sent {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_str().as_bytes()) => Ok(()),
+        _ => Err(csrf_error()),
+    }
+}
+
+/// Methods that don't change anything on the server, which need no token.
+#[cfg(any(feature = "ssr", doc))]
+fn is_safe_method(method: &str) -> bool {
+    ["GET", "HEAD", "OPTIONS", "TRACE"]
+        .iter()
+        .any(|safe| method.eq_ignore_ascii_case(safe))
+}
+
+#[cfg(any(feature = "ssr", doc))]
+fn csrf_error() -> ServerFnError {
+    ServerFnError::ServerError("missing or invalid CSRF token".to_string())
+}
+
+/// Reads the `_csrf` field of a form, if the body is one.
+#[cfg(any(feature = "ssr", doc))]
+fn csrf_field(req: &RequestParts, body: &[u8]) -> Option<String> {
+    let content_type = req.header("Content-Type").unwrap_or_default();
+    if content_type.starts_with("multipart/form-data") {
+        let boundary = crate::multipart::multipart_boundary(content_type)?;
+        let data = crate::MultipartData::parse(body, &boundary).ok()?;
+        let token = data
+            .fields()
+            .find(|(name, _)| *name == CSRF_FIELD)
+            .map(|(_, value)| value.to_string());
+        token
+    } else {
+        form_urlencoded::parse(body)
+            .find(|(name, _)| name == CSRF_FIELD)
+            .map(|(_, value)| value.into_owned())
+    }
+}
+
+/// Compares the tokens without returning early, so that the time it takes doesn't tell how
+/// much of a guessed token is right.
+#[cfg(any(feature = "ssr", doc))]
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> RequestParts {
+        RequestParts {
+            path: "add_todo".to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn rejects_a_request_without_the_cookie() {
+        let req = request(&[(CSRF_HEADER, "abc")]);
+        assert!(verify_csrf_token("POST", &req, b"").is_err());
+        assert!(verify_csrf_token("POST", &request(&[]), b"_csrf=abc").is_err());
+
+        // other cookies don't stand in for the token
+        let req = request(&[("Cookie", "session=1"), (CSRF_HEADER, "abc")]);
+        assert!(verify_csrf_token("POST", &req, b"").is_err());
+    }
+
+    #[test]
+    fn lets_safe_methods_through_without_the_cookie() {
+        for method in ["GET", "head", "OPTIONS", "TRACE"] {
+            assert!(verify_csrf_token(method, &request(&[]), b"").is_ok());
+        }
+        assert!(verify_csrf_token("DELETE", &request(&[]), b"").is_err());
+    }
+
+    #[test]
+    fn rejects_a_token_that_doesnt_match() {
+        let req = request(&[("Cookie", "leptos_csrf=abc"), (CSRF_HEADER, "xyz")]);
+        assert!(verify_csrf_token("POST", &req, b"").is_err());
+
+        let req = request(&[("Cookie", "leptos_csrf=abc")]);
+        assert!(verify_csrf_token("POST", &req, b"").is_err());
+        assert!(verify_csrf_token("POST", &req, b"_csrf=ab").is_err());
+    }
+
+    #[test]
+    fn accepts_a_token_that_matches() {
+        let req = request(&[
+            ("Cookie", "theme=dark; leptos_csrf=abc"),
+            (CSRF_HEADER, "abc"),
+        ]);
+        assert!(verify_csrf_token("POST", &req, b"").is_ok());
+
+        let req = request(&[
+            ("Cookie", "leptos_csrf=abc"),
+            ("Content-Type", "application/x-www-form-urlencoded"),
+        ]);
+        assert!(verify_csrf_token("PUT", &req, b"title=Buy+milk&_csrf=abc").is_ok());
+    }
+}
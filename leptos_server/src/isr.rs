@@ -0,0 +1,160 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{CsrfToken, ResponseParts};
+use leptos_dom::Nonce;
+
+/// The most pages an [IsrCache] holds; once it's full, the page that was rendered the longest
+/// time ago makes room for the next.
+const MAX_PAGES: usize = 1024;
+
+/// A page rendered by the server integrations, as it's kept in an [IsrCache].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedPage {
+    /// The whole HTML of the page.
+    pub html: String,
+    /// The [CsrfToken] the page was rendered with.
+    pub csrf_token: CsrfToken,
+    /// The [Nonce] the inline scripts of the page were marked with.
+    pub nonce: Nonce,
+    /// The status code and headers the app set while the page was rendered.
+    pub parts: ResponseParts,
+}
+
+impl CachedPage {
+    /// The HTML of the page, with the [CsrfToken] of the user it's served to and the [Nonce] of
+    /// the response in the places of the ones it was rendered with, since the page is shared
+    /// between users.
+    pub fn html_for(&self, csrf_token: &CsrfToken, nonce: &Nonce) -> String {
+        self.html
+            .replace(self.csrf_token.as_str(), csrf_token.as_str())
+            .replace(self.nonce.as_str(), nonce.as_str())
+    }
+
+    /// The status code and headers of the page, with the [Nonce] of the response in the places
+    /// of the one it was rendered with, e.g., in its `Content-Security-Policy`.
+    pub fn parts_for(&self, nonce: &Nonce) -> ResponseParts {
+        let mut parts = self.parts.clone();
+        for (_, value) in &mut parts.headers {
+            *value = value.replace(self.nonce.as_str(), nonce.as_str());
+        }
+        parts
+    }
+}
+
+/// What an [IsrCache] holds for a URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsrLookup {
+    /// A page that can be served as it is.
+    Fresh(CachedPage),
+    /// A page that can be served while it's rendered again in the background, which the
+    /// request that gets it is in charge of; others get it as [IsrLookup::Fresh] meanwhile.
+    Stale(CachedPage),
+    /// No page, so it has to be rendered.
+    Miss,
+}
+
+/// The pages the server integrations serve with incremental static regeneration: a page that
+/// sets a revalidation time, as given by [ResponseParts::revalidate], is rendered once and then
+/// served from the cache, and rendered again in the background once that time has passed, so
+/// that pages that seldom change are served as fast as static files while staying up to date.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_dom::Nonce;
+/// let cache = IsrCache::default();
+/// assert_eq!(cache.get("/about"), IsrLookup::Miss);
+///
+/// let parts = ResponseParts {
+///     status: None,
+///     headers: vec![("Cache-Control".to_string(), "s-maxage=60".to_string())],
+/// };
+/// let token = CsrfToken::new();
+/// let nonce = Nonce::from("rendered".to_string());
+/// let page = CachedPage {
+///     html: format!("<html>{}<script{}></script></html>", token.meta_tag(), nonce.to_attribute()),
+///     csrf_token: token,
+///     nonce,
+///     parts,
+/// };
+/// cache.insert("/about", page.clone());
+/// assert_eq!(cache.get("/about"), IsrLookup::Fresh(page.clone()));
+///
+/// let user = CsrfToken::new();
+/// let nonce = Nonce::from("served".to_string());
+/// assert_eq!(
+///     page.html_for(&user, &nonce),
+///     format!("<html>{}<script{}></script></html>", user.meta_tag(), nonce.to_attribute())
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IsrCache(Arc<Mutex<HashMap<String, IsrEntry>>>);
+
+#[derive(Debug)]
+struct IsrEntry {
+    page: CachedPage,
+    rendered_at: Instant,
+    revalidate: Duration,
+    regenerating: bool,
+}
+
+impl IsrCache {
+    /// Looks up the page at the given URL. A stale page is only returned as
+    /// [IsrLookup::Stale] once, until it's [inserted](IsrCache::insert) again.
+    pub fn get(&self, url: &str) -> IsrLookup {
+        let mut pages = self.0.lock().unwrap();
+        match pages.get_mut(url) {
+            None => IsrLookup::Miss,
+            Some(entry) => {
+                if entry.regenerating || entry.rendered_at.elapsed() < entry.revalidate {
+                    IsrLookup::Fresh(entry.page.clone())
+                } else {
+                    entry.regenerating = true;
+                    IsrLookup::Stale(entry.page.clone())
+                }
+            }
+        }
+    }
+
+    /// Keeps a page that was rendered for the given URL, if its [ResponseParts] let it be
+    /// cached, or removes the page that was kept for it otherwise.
+    pub fn insert(&self, url: &str, page: CachedPage) {
+        let mut pages = self.0.lock().unwrap();
+        match page.parts.revalidate() {
+            Some(revalidate) => {
+                if pages.len() >= MAX_PAGES && !pages.contains_key(url) {
+                    let oldest = pages
+                        .iter()
+                        .min_by_key(|(_, entry)| entry.rendered_at)
+                        .map(|(url, _)| url.clone());
+                    if let Some(oldest) = oldest {
+                        pages.remove(&oldest);
+                    }
+                }
+                pages.insert(
+                    url.to_string(),
+                    IsrEntry {
+                        page,
+                        rendered_at: Instant::now(),
+                        revalidate,
+                        regenerating: false,
+                    },
+                );
+            }
+            None => {
+                pages.remove(url);
+            }
+        }
+    }
+
+    /// Lets a stale page be rendered again by the next request, after the request that was in
+    /// charge of it failed to.
+    pub fn regeneration_failed(&self, url: &str) {
+        if let Some(entry) = self.0.lock().unwrap().get_mut(url) {
+            entry.regenerating = false;
+        }
+    }
+}
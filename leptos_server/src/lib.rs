@@ -59,9 +59,14 @@
 //! - **Server functions must return `Result<T, ServerFnError>`.** Even if the work being done
 //!   inside the function body can’t fail, the processes of serialization/deserialization and the
 //!   network call are fallible.
+//!   Instead of [ServerFnError], the error can be any type that implements
+//!   [ServerFnErrorKind], which is preserved across the wire.
 //! - **Return types must be [Serializable](leptos_reactive::Serializable).**
 //!   This should be fairly obvious: we have to serialize arguments to send them to the server, and we
 //!   need to deserialize the result to return it to the client.
+//!   A function can return `Result<impl Stream<Item = T>, _>` as well, which streams each `T` to
+//!   the client as it is produced, where it arrives as a [ServerStream]. A long-running
+//!   function can report its progress this way with [progress_stream].
 //! - **Arguments must be implement [serde::Serialize].** They are serialized as an `application/x-www-form-urlencoded`
 //!   form data using [`serde_urlencoded`](https://docs.rs/serde_urlencoded/latest/serde_urlencoded/) or as `application/cbor`
 //!   using [`cbor`](https://docs.rs/cbor/latest/cbor/).
@@ -71,11 +76,13 @@
 
 pub use form_urlencoded;
 use leptos_reactive::*;
+#[cfg(feature = "schemars")]
+pub use schemars;
 
 use proc_macro2::{Literal, TokenStream};
 use quote::TokenStreamExt;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{future::Future, pin::Pin, str::FromStr};
+use std::{future::Future, pin::Pin, str::FromStr, time::Duration};
 use syn::{
     parse::{Parse, ParseStream},
     parse_quote,
@@ -83,9 +90,41 @@ use syn::{
 use thiserror::Error;
 
 mod action;
+mod batch;
+mod channel;
+mod client;
+mod codec;
+mod csrf;
+#[cfg(any(feature = "ssr", doc))]
+mod isr;
+mod middleware;
 mod multi_action;
+mod multipart;
+mod offline;
+#[cfg(any(feature = "ssr", doc))]
+mod openapi;
+mod progress;
+mod response;
+mod sse;
+mod stream;
 pub use action::*;
+pub use batch::*;
+pub use channel::*;
+pub use client::*;
+pub use codec::*;
+pub use csrf::*;
+#[cfg(any(feature = "ssr", doc))]
+pub use isr::*;
+pub use middleware::*;
 pub use multi_action::*;
+pub use multipart::*;
+pub use offline::*;
+#[cfg(any(feature = "ssr", doc))]
+pub use openapi::*;
+pub use progress::*;
+pub use response::*;
+pub use sse::*;
+pub use stream::*;
 
 #[cfg(any(feature = "ssr", doc))]
 use std::{
@@ -94,13 +133,75 @@ use std::{
 };
 
 #[cfg(any(feature = "ssr", doc))]
-type ServerFnTraitObj = dyn Fn(Scope, &[u8]) -> Pin<Box<dyn Future<Output = Result<Payload, ServerFnError>>>>
+type ServerFnTraitObj = dyn Fn(Scope, &[u8]) -> Pin<Box<dyn Future<Output = Result<Payload, ServerFnErrorResponse>>>>
     + Send
     + Sync;
 
+/// Encodes an error as the error type of a server function, in its encoding.
+#[cfg(any(feature = "ssr", doc))]
+type ServerFnErrorEncoder = fn(ServerFnError) -> ServerFnErrorResponse;
+
+/// Generates the JSON schemas of the types in the signature of a server function.
+#[cfg(all(any(feature = "ssr", doc), feature = "schemars"))]
+type ServerFnSchemasGenerator = fn(&mut schemars::gen::SchemaGenerator) -> Option<ServerFnSchemas>;
+
 #[cfg(any(feature = "ssr", doc))]
 lazy_static::lazy_static! {
     static ref REGISTERED_SERVER_FUNCTIONS: Arc<RwLock<HashMap<&'static str, Arc<ServerFnTraitObj>>>> = Default::default();
+    static ref REGISTERED_SERVER_FN_INFO: Arc<RwLock<HashMap<&'static str, ServerFnInfo>>> = Default::default();
+    static ref REGISTERED_SERVER_FN_ERRORS: Arc<RwLock<HashMap<&'static str, ServerFnErrorEncoder>>> = Default::default();
+    static ref SERVER_FN_ALIASES: Arc<RwLock<HashMap<String, &'static str>>> = Default::default();
+}
+
+/// Looks up a server function by its path, or by a previous path it still accepts.
+#[cfg(any(feature = "ssr", doc))]
+fn lookup_server_fn<T: Clone>(fns: &HashMap<&'static str, T>, path: &str) -> Option<T> {
+    fns.get(path).cloned().or_else(|| {
+        let aliases = SERVER_FN_ALIASES.read().ok()?;
+        fns.get(aliases.get(path)?).cloned()
+    })
+}
+
+#[cfg(all(any(feature = "ssr", doc), feature = "schemars"))]
+lazy_static::lazy_static! {
+    static ref REGISTERED_SERVER_FN_SCHEMAS: Arc<RwLock<HashMap<&'static str, ServerFnSchemasGenerator>>> = Default::default();
+}
+
+/// Generates the JSON schemas of the types in the signature of the server function at `path`
+/// with `generator`, if the `#[server]` macro implemented [ServerFn::schemas] for it.
+#[cfg(all(any(feature = "ssr", doc), feature = "schemars"))]
+pub(crate) fn server_fn_schemas(
+    path: &str,
+    generator: &mut schemars::gen::SchemaGenerator,
+) -> Option<ServerFnSchemas> {
+    let schemas = REGISTERED_SERVER_FN_SCHEMAS
+        .read()
+        .ok()
+        .and_then(|fns| fns.get(path).copied())?;
+    schemas(generator)
+}
+
+/// The HTTP method a server function is called with.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(GetPosts, method = "GET", cache = "max-age=60")]
+/// async fn get_posts(page: u32) -> Result<Vec<String>, ServerFnError> {
+///     todo!()
+/// }
+///
+/// assert_eq!(GetPosts::method(), HttpMethod::Get);
+/// assert_eq!(GetPosts::cache_control(), Some("max-age=60"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    /// Sends the arguments in the body of the request. This is the default.
+    Post,
+    /// Sends the URL-encoded arguments in the query string, so that the response can be cached
+    /// by the browser or a CDN. This should only be used for functions that don't change any
+    /// state on the server.
+    Get,
 }
 
 /// A dual type to hold the possible Response datatypes
@@ -112,6 +213,10 @@ pub enum Payload {
     Url(String),
     ///Encodes Data using Json
     Json(String),
+    ///Streams values, each encoded using Json
+    Stream(ServerStream<String>),
+    ///Encodes data using a [Codec], with its content type
+    Codec(&'static str, Vec<u8>),
 }
 
 /// Attempts to find a server function registered at the given path.
@@ -162,17 +267,202 @@ pub fn server_fn_by_path(path: &str) -> Option<Arc<ServerFnTraitObj>> {
     REGISTERED_SERVER_FUNCTIONS
         .read()
         .ok()
-        .and_then(|fns| fns.get(path).cloned())
+        .and_then(|fns| lookup_server_fn(&fns, path))
+}
+
+/// Returns the [HttpMethod] of the server function registered at the given path, so that a
+/// server can accept `GET` requests for the functions that are called with them.
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_fn_method(path: &str) -> Option<HttpMethod> {
+    server_fn_info(path).map(|info| info.method)
+}
+
+/// Returns the `Cache-Control` header that a server should send with the successful response
+/// of the server function registered at the given path, if it declared one with
+/// `#[server(Name, method = "GET", cache = "...")]`.
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_fn_cache_control(path: &str) -> Option<&'static str> {
+    server_fn_info(path).and_then(|info| info.cache_control)
+}
+
+/// Describes the server function registered at the given path.
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_fn_info(path: &str) -> Option<ServerFnInfo> {
+    REGISTERED_SERVER_FN_INFO
+        .read()
+        .ok()
+        .and_then(|fns| lookup_server_fn(&fns, path))
+}
+
+/// Lets the server function registered at `path` be called at `previous` as well, e.g., at the
+/// path it had in the previous build, so that clients that loaded the app before a rolling
+/// deploy can still call it. Paths that are known when the function is written can be
+/// declared with `#[server(Name, "/api", previous = ["..."])]` instead; this is for paths that
+/// are only known when the server starts, e.g., read from the [server_fns] of the previous
+/// build.
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(AddTodo, "/api", endpoint = "todos/add", version = "v2", previous = ["todos/add"])]
+/// async fn add_todo(title: String) -> Result<(), ServerFnError> {
+///     Ok(())
+/// }
+///
+/// assert_eq!(AddTodo::url(), "v2/todos/add");
+/// # #[cfg(feature = "ssr")] {
+/// AddTodo::register().unwrap();
+/// // the path of the previous version is still accepted
+/// assert!(server_fn_by_path("todos/add").is_some());
+///
+/// register_server_fn_alias("src-todos.rs-add_todo", "v2/todos/add").unwrap();
+/// assert_eq!(server_fn_info("src-todos.rs-add_todo").unwrap().path, "v2/todos/add");
+/// # }
+/// ```
+#[cfg(any(feature = "ssr", doc))]
+pub fn register_server_fn_alias(
+    previous: impl Into<String>,
+    path: &str,
+) -> Result<(), ServerFnError> {
+    let path = REGISTERED_SERVER_FN_INFO
+        .read()
+        .map_err(|e| ServerFnError::Registration(e.to_string()))?
+        .get(path)
+        .map(|info| info.path)
+        .ok_or_else(|| {
+            ServerFnError::Registration(format!("no server function is registered at {path}"))
+        })?;
+    SERVER_FN_ALIASES
+        .write()
+        .map_err(|e| ServerFnError::Registration(e.to_string()))?
+        .insert(previous.into(), path);
+    Ok(())
+}
+
+/// Encodes an error for the server function registered at the given path as it would return
+/// it: as its own error type, in its encoding. The server integrations use this to respond
+/// with errors they detect before or while running the function, like
+/// [ServerFnError::Timeout], so that the client can deserialize them like any other.
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// # use std::time::Duration;
+/// #[server(ImportCsv, "/api", timeout = "10s", max_body = "2mb")]
+/// async fn import_csv(csv: String) -> Result<usize, ServerFnError> {
+///     Ok(csv.lines().count())
+/// }
+///
+/// # #[cfg(feature = "ssr")] {
+/// ImportCsv::register().unwrap();
+/// let info = server_fn_info(ImportCsv::url()).unwrap();
+/// assert_eq!(info.timeout, Some(Duration::from_secs(10)));
+/// assert_eq!(info.max_body_size, Some(2 * 1024 * 1024));
+///
+/// let res = server_fn_error_response(ImportCsv::url(), ServerFnError::PayloadTooLarge(10)).unwrap();
+/// assert_eq!(res.status, 413);
+/// let e: ServerFnError = match res.payload {
+///     Payload::Url(json) => server_fn_error_from_json(&json, ""),
+///     _ => unreachable!(),
+/// };
+/// assert!(matches!(e, ServerFnError::PayloadTooLarge(10)));
+/// # }
+/// ```
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_fn_error_response(path: &str, error: ServerFnError) -> Option<ServerFnErrorResponse> {
+    let encode = REGISTERED_SERVER_FN_ERRORS
+        .read()
+        .ok()
+        .and_then(|fns| lookup_server_fn(&fns, path))?;
+    Some(encode(error))
+}
+
+/// Describes all the server functions that have been registered, ordered by their path, e.g.,
+/// to generate documentation or contract tests for them. See [openapi_spec] as well.
+#[cfg(any(feature = "ssr", doc))]
+pub fn server_fns() -> Vec<ServerFnInfo> {
+    let mut fns: Vec<ServerFnInfo> = REGISTERED_SERVER_FN_INFO
+        .read()
+        .map(|fns| fns.values().cloned().collect())
+        .unwrap_or_default();
+    fns.sort_by_key(|info| (info.prefix, info.path));
+    fns
+}
+
+/// A registered server function, as returned by [server_fns].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerFnInfo {
+    /// The path the function is registered at, as given by [ServerFn::url].
+    pub path: &'static str,
+    /// The URL prefix the function is mounted under, as given by [ServerFn::prefix].
+    pub prefix: &'static str,
+    /// The HTTP method the function is called with.
+    pub method: HttpMethod,
+    /// How the arguments and the result are encoded.
+    pub encoding: Encoding,
+    /// The `Cache-Control` header sent with a successful response, if any.
+    pub cache_control: Option<&'static str>,
+    /// Whether the [CsrfToken] of a request is checked before the function runs.
+    pub csrf: bool,
+    /// How long the function may run before it's abandoned, if it overrides the limit of the
+    /// server integration.
+    pub timeout: Option<Duration>,
+    /// The largest request body, in bytes, the function accepts, if it overrides the limit of
+    /// the server integration.
+    pub max_body_size: Option<u64>,
+    /// The paths the function was reached at before, which it's still called at, as declared
+    /// with `#[server(Name, previous = [...])]`.
+    pub previous_paths: &'static [&'static str],
+    /// The arguments and the return type of the function.
+    pub signature: ServerFnSignature,
+}
+
+impl ServerFnInfo {
+    /// The URL the client calls the function at, relative to the server.
+    pub fn url(&self) -> String {
+        format!("{}/{}", self.prefix, self.path)
+    }
+}
+
+/// The arguments and the return type of a server function, as they're written in its
+/// signature, which the `#[server]` macro records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerFnSignature {
+    /// The name and the Rust type of each argument, leaving out the [Scope].
+    pub args: &'static [(&'static str, &'static str)],
+    /// The Rust type of the value the function returns, or of each value it streams.
+    pub output: &'static str,
+    /// The Rust type of the error the function returns.
+    pub error: &'static str,
+    /// Whether the function streams its values.
+    pub streaming: bool,
+}
+
+/// The JSON schemas of the arguments and the return type of a server function, which the
+/// `#[server]` macro generates with [schemars] when the `schemars` feature is enabled.
+#[cfg(feature = "schemars")]
+#[derive(Debug, Clone)]
+pub struct ServerFnSchemas {
+    /// The name and the schema of each argument, leaving out the [Scope] and uploads.
+    pub args: Vec<(&'static str, schemars::schema::Schema)>,
+    /// The schema of the value the function returns, or of each value it streams.
+    pub output: schemars::schema::Schema,
+    /// The schema of the error the function returns.
+    pub error: schemars::schema::Schema,
 }
 
 /// Holds the current options for encoding types.
 /// More could be added, but they need to be serde
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Encoding {
     /// A Binary Encoding Scheme Called Cbor
     Cbor,
     /// The Default URL-encoded encoding method
     Url,
+    /// Sends the arguments as `multipart/form-data`, which is used automatically when the
+    /// function takes a [MultipartData] or [UploadedFile] argument
+    Multipart,
+    /// Encodes the arguments and the result with the [ServerFn::Codec] of the function, which
+    /// is selected with `encoding = ...`. Holds the content type of the codec.
+    Codec(&'static str),
 }
 
 impl FromStr for Encoding {
@@ -182,6 +472,7 @@ impl FromStr for Encoding {
         match input {
             "URL" => Ok(Encoding::Url),
             "Cbor" => Ok(Encoding::Cbor),
+            "Multipart" => Ok(Encoding::Multipart),
             _ => Err(()),
         }
     }
@@ -192,6 +483,11 @@ impl quote::ToTokens for Encoding {
         let option: syn::Ident = match *self {
             Encoding::Cbor => parse_quote!(Cbor),
             Encoding::Url => parse_quote!(Url),
+            Encoding::Multipart => parse_quote!(Multipart),
+            Encoding::Codec(content_type) => {
+                tokens.extend(quote::quote! { Encoding::Codec(#content_type) });
+                return;
+            }
         };
         let expansion: syn::Ident = syn::parse_quote! {
           Encoding::#option
@@ -208,6 +504,7 @@ impl Parse for Encoding {
         match variant_name.as_ref() {
             "\"Url\"" => Ok(Self::Url),
             "\"Cbor\"" => Ok(Self::Cbor),
+            "\"Multipart\"" => Ok(Self::Multipart),
             _ => panic!("Encoding Not Found"),
         }
     }
@@ -230,30 +527,126 @@ where
     Self: Serialize + DeserializeOwned + Sized + 'static,
 {
     /// The return type of the function.
-    type Output: Serialize;
+    type Output: ServerFnOutput;
+
+    /// The error type of the function, which is sent to the client as it is.
+    type Error: ServerFnErrorKind;
+
+    /// The [Codec] the arguments, result and error are encoded with if the [ServerFn::encoding]
+    /// is [Encoding::Codec].
+    type Codec: Codec;
 
     /// URL prefix that should be prepended by the client to the generated URL.
     fn prefix() -> &'static str;
 
-    /// The path at which the server function can be reached on the server.
+    /// The path at which the server function can be reached on the server. It's derived from
+    /// the source file and the name of the function, unless a stable path is set with
+    /// `#[server(Name, endpoint = "...", version = "...")]`.
     fn url() -> &'static str;
 
     /// The path at which the server function can be reached on the server.
     fn encoding() -> Encoding;
 
+    /// The HTTP method the function is called with, which is set with
+    /// `#[server(Name, method = "GET")]`.
+    fn method() -> HttpMethod {
+        HttpMethod::Post
+    }
+
+    /// The `Cache-Control` header that is sent with a successful response to a `GET` request,
+    /// which is set with `#[server(Name, method = "GET", cache = "max-age=60")]`.
+    fn cache_control() -> Option<&'static str> {
+        None
+    }
+
+    /// Whether the server integrations check the [CsrfToken] of a request before running the
+    /// function, unless it's called with `GET`. Protection is on by default, and is turned off
+    /// with `#[server(Name, csrf = false)]`.
+    fn csrf() -> bool {
+        true
+    }
+
+    /// How long the function may run on the server before it's abandoned, which is set with
+    /// `#[server(Name, timeout = "10s")]`. Overrides the `server_fn_timeout` of the server
+    /// integration, which responds with [ServerFnError::Timeout] once it has passed.
+    fn timeout() -> Option<Duration> {
+        None
+    }
+
+    /// The largest request body, in bytes, the function accepts, which is set with
+    /// `#[server(Name, max_body = "2mb")]`. Overrides the `max_request_size` and
+    /// `max_upload_size` of the server integration, which responds with
+    /// [ServerFnError::PayloadTooLarge] to larger requests.
+    fn max_body_size() -> Option<u64> {
+        None
+    }
+
+    /// The paths the function was reached at before, e.g., before it was given a stable
+    /// `endpoint` or a new `version`, which the server still accepts so that clients that
+    /// loaded an earlier build of the app can call it during a rolling deploy. Set with
+    /// `#[server(Name, previous = ["..."])]`.
+    fn previous_urls() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// The arguments and the return type of the function, which describe it in
+    /// [server_fns] and [openapi_spec].
+    fn signature() -> ServerFnSignature {
+        ServerFnSignature::default()
+    }
+
+    /// Generates the JSON schemas of the arguments and the return type of the function, which
+    /// describe it in [openapi_spec]. The `#[server]` macro implements this when the `schemars`
+    /// feature is enabled, for which those types have to implement [schemars::JsonSchema].
+    #[cfg(feature = "schemars")]
+    fn schemas(_generator: &mut schemars::gen::SchemaGenerator) -> Option<ServerFnSchemas> {
+        None
+    }
+
     /// Runs the function on the server.
     #[cfg(any(feature = "ssr", doc))]
-    fn call_fn(
-        self,
-        cx: Scope,
-    ) -> Pin<Box<dyn Future<Output = Result<Self::Output, ServerFnError>>>>;
+    #[allow(clippy::type_complexity)]
+    fn call_fn(self, cx: Scope)
+        -> Pin<Box<dyn Future<Output = Result<Self::Output, Self::Error>>>>;
 
     /// Runs the function on the client by sending an HTTP request to the server.
     #[cfg(any(not(feature = "ssr"), doc))]
+    #[allow(clippy::type_complexity)]
     fn call_fn_client(
         self,
         cx: Scope,
-    ) -> Pin<Box<dyn Future<Output = Result<Self::Output, ServerFnError>>>>;
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Output, Self::Error>>>>;
+
+    /// Reads the arguments from a `multipart/form-data` request. By default, the text fields
+    /// are deserialized like a URL-encoded form; the `#[server]` macro reads each argument
+    /// with [MultipartArg] instead when the function takes a file.
+    #[cfg(any(feature = "ssr", doc))]
+    fn from_multipart(data: MultipartData) -> Result<Self, ServerFnError> {
+        let form = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(data.fields())
+            .finish();
+        serde_urlencoded::from_str(&form).map_err(|e| ServerFnError::Args(e.to_string()))
+    }
+
+    /// Writes the arguments as the fields of a `multipart/form-data` request, which is the
+    /// counterpart of [ServerFn::from_multipart] on the client.
+    fn into_multipart(self) -> Result<MultipartData, ServerFnError> {
+        let form = serde_urlencoded::to_string(&self)
+            .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+        let mut data = MultipartData::new();
+        for (name, value) in form_urlencoded::parse(form.as_bytes()) {
+            data.push_field(name, value);
+        }
+        Ok(data)
+    }
+
+    /// The [ServerFnMiddleware] that runs before the function on the server, which is attached
+    /// with `#[server(Name, middleware = [...])]`, followed by the [ServerFnGuard]s declared with
+    /// `#[server(Name, guard = ...)]`.
+    #[cfg(any(feature = "ssr", doc))]
+    fn middleware() -> Vec<Arc<dyn ServerFnMiddleware>> {
+        Vec::new()
+    }
 
     /// Registers the server function, allowing the server to query it by URL.
     #[cfg(any(feature = "ssr", doc))]
@@ -262,49 +655,71 @@ where
         // takes a String -> returns its async value
 
         let run_server_fn = Arc::new(|cx: Scope, data: &[u8]| {
-            // decode the args
-            let value = match Self::encoding() {
-                Encoding::Url => serde_urlencoded::from_bytes(data)
-                    .map_err(|e| ServerFnError::Deserialization(e.to_string())),
-                Encoding::Cbor => {
-                    println!("Deserialize Cbor!: {:x?}", &data);
-                    ciborium::de::from_reader(data)
-                        .map_err(|e| ServerFnError::Deserialization(e.to_string()))
-                }
-            };
+            let data = data.to_vec();
             Box::pin(async move {
+                // run the middleware before anything else, so it can reject the call before
+                // any work is done to decode the arguments
+                let req = use_context::<RequestParts>(cx).unwrap_or_default();
+                for middleware in global_middleware().iter().chain(Self::middleware().iter()) {
+                    if let Err(e) = middleware.before(cx, &req).await {
+                        return Err(error_response::<Self::Codec, _>(
+                            Self::encoding(),
+                            Self::Error::from(e),
+                        ));
+                    }
+                }
+
+                // decode the args
+                let data = data.as_slice();
+                let value = match Self::encoding() {
+                    Encoding::Url => serde_urlencoded::from_bytes(data)
+                        .map_err(|e| ServerFnError::Deserialization(e.to_string())),
+                    Encoding::Cbor => {
+                        println!("Deserialize Cbor!: {:x?}", &data);
+                        ciborium::de::from_reader(data)
+                            .map_err(|e| ServerFnError::Deserialization(e.to_string()))
+                    }
+                    Encoding::Multipart => req
+                        .header("Content-Type")
+                        .and_then(multipart_boundary)
+                        .ok_or_else(|| {
+                            ServerFnError::Deserialization(
+                                "missing multipart/form-data boundary".into(),
+                            )
+                        })
+                        .and_then(|boundary| MultipartData::parse(data, &boundary))
+                        .and_then(Self::from_multipart),
+                    Encoding::Codec(_) => Self::Codec::decode(data),
+                };
                 let value: Self = match value {
                     Ok(v) => v,
-                    Err(e) => return Err(e),
+                    Err(e) => {
+                        return Err(error_response::<Self::Codec, _>(
+                            Self::encoding(),
+                            Self::Error::from(e),
+                        ))
+                    }
                 };
 
                 // call the function
                 let result = match value.call_fn(cx).await {
                     Ok(r) => r,
-                    Err(e) => return Err(e),
+                    Err(e) => return Err(error_response::<Self::Codec, _>(Self::encoding(), e)),
                 };
 
                 // serialize the output
-                let result = match Self::encoding() {
-                    Encoding::Url => match serde_json::to_string(&result)
-                        .map_err(|e| ServerFnError::Serialization(e.to_string()))
-                    {
-                        Ok(r) => Payload::Url(r),
-                        Err(e) => return Err(e),
-                    },
-                    Encoding::Cbor => {
-                        let mut buffer: Vec<u8> = Vec::new();
-                        match ciborium::ser::into_writer(&result, &mut buffer)
-                            .map_err(|e| ServerFnError::Serialization(e.to_string()))
-                        {
-                            Ok(_) => Payload::Binary(buffer),
-                            Err(e) => return Err(e),
-                        }
+                let result = match result.into_payload::<Self::Codec>(Self::encoding()) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        return Err(error_response::<Self::Codec, _>(
+                            Self::encoding(),
+                            Self::Error::from(e),
+                        ))
                     }
                 };
 
                 Ok(result)
-            }) as Pin<Box<dyn Future<Output = Result<Payload, ServerFnError>>>>
+            }) as Pin<Box<dyn Future<Output = Result<Payload, ServerFnErrorResponse>>>>
         });
 
         // store it in the hashmap
@@ -313,12 +728,80 @@ where
             .map_err(|e| ServerFnError::Registration(e.to_string()))?;
         write.insert(Self::url(), run_server_fn);
 
+        let mut write = REGISTERED_SERVER_FN_INFO
+            .write()
+            .map_err(|e| ServerFnError::Registration(e.to_string()))?;
+        write.insert(
+            Self::url(),
+            ServerFnInfo {
+                path: Self::url(),
+                prefix: Self::prefix(),
+                method: Self::method(),
+                encoding: Self::encoding(),
+                cache_control: Self::cache_control(),
+                csrf: Self::csrf(),
+                timeout: Self::timeout(),
+                max_body_size: Self::max_body_size(),
+                previous_paths: Self::previous_urls(),
+                signature: Self::signature(),
+            },
+        );
+
+        let mut write = SERVER_FN_ALIASES
+            .write()
+            .map_err(|e| ServerFnError::Registration(e.to_string()))?;
+        for previous in Self::previous_urls() {
+            write.insert(previous.to_string(), Self::url());
+        }
+
+        let mut write = REGISTERED_SERVER_FN_ERRORS
+            .write()
+            .map_err(|e| ServerFnError::Registration(e.to_string()))?;
+        write.insert(Self::url(), |e| {
+            error_response::<Self::Codec, _>(Self::encoding(), Self::Error::from(e))
+        });
+
+        #[cfg(feature = "schemars")]
+        REGISTERED_SERVER_FN_SCHEMAS
+            .write()
+            .map_err(|e| ServerFnError::Registration(e.to_string()))?
+            .insert(Self::url(), Self::schemas);
+
         Ok(())
     }
 }
 
+/// A value that a server function can return: any type that implements [Serialize], or a
+/// [ServerStream] of such values.
+pub trait ServerFnOutput: 'static {
+    /// Serializes the value as the body of the response, in the given encoding.
+    #[cfg(any(feature = "ssr", doc))]
+    fn into_payload<C: Codec>(self, encoding: Encoding) -> Result<Payload, ServerFnError>;
+}
+
+impl<T: Serialize + 'static> ServerFnOutput for T {
+    #[cfg(any(feature = "ssr", doc))]
+    fn into_payload<C: Codec>(self, encoding: Encoding) -> Result<Payload, ServerFnError> {
+        match encoding {
+            Encoding::Url | Encoding::Multipart => serde_json::to_string(&self)
+                .map(Payload::Url)
+                .map_err(|e| ServerFnError::Serialization(e.to_string())),
+            Encoding::Cbor => {
+                let mut buffer: Vec<u8> = Vec::new();
+                ciborium::ser::into_writer(&self, &mut buffer)
+                    .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+                Ok(Payload::Binary(buffer))
+            }
+            Encoding::Codec(content_type) => {
+                C::encode(&self).map(|data| Payload::Codec(content_type, data))
+            }
+        }
+    }
+}
+
 /// Type for errors that can occur when using server functions.
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum ServerFnError {
     /// Error while trying to register the server function (only occurs in case of poisoned RwLock).
     #[error("error while trying to register the server function: {0}")]
@@ -341,95 +824,266 @@ pub enum ServerFnError {
     /// Occurs on the server if there's a missing argument.
     #[error("missing argument {0}")]
     MissingArg(String),
+    /// Occurs on the server if a guard or middleware rejects the request, e.g., because the
+    /// user isn't logged in or doesn't have the required role.
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+    /// Occurs on the server if the function runs longer than the timeout it or the server
+    /// integration sets.
+    #[error("server function timed out after {0:?}")]
+    Timeout(Duration),
+    /// Occurs on the server if the request body is larger than the limit, in bytes, that the
+    /// function or the server integration sets.
+    #[error("request body is larger than the limit of {0} bytes")]
+    PayloadTooLarge(u64),
+}
+
+/// An error type that server functions can return, instead of [ServerFnError], to preserve
+/// their own errors across the wire: the error is serialized on the server, sent with the
+/// HTTP status code given by [ServerFnErrorKind::status_code], and deserialized on the client.
+///
+/// As calling a server function can fail before it runs, e.g., if the server can't be
+/// reached, the type has to be able to hold a [ServerFnError] as well.
+///
+/// ```
+/// # use leptos_server::{ServerFn, ServerFnError, ServerFnErrorKind};
+/// # use leptos_macro::server;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(thiserror::Error, Debug, Clone, Serialize, Deserialize)]
+/// # #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+/// pub enum TodoError {
+///     #[error("todo {0} not found")]
+///     NotFound(u32),
+///     #[error(transparent)]
+///     ServerFn(#[from] ServerFnError),
+/// }
+///
+/// impl ServerFnErrorKind for TodoError {
+///     fn status_code(&self) -> u16 {
+///         match self {
+///             TodoError::NotFound(_) => 404,
+///             TodoError::ServerFn(e) => e.status_code(),
+///         }
+///     }
+///
+///     fn server_fn_error(&self) -> Option<&ServerFnError> {
+///         match self {
+///             TodoError::ServerFn(e) => Some(e),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// #[server(GetTodo)]
+/// async fn get_todo(id: u32) -> Result<String, TodoError> {
+///     Err(TodoError::NotFound(id))
+/// }
+/// ```
+pub trait ServerFnErrorKind:
+    std::error::Error + Serialize + DeserializeOwned + From<ServerFnError> + 'static
+{
+    /// The HTTP status code that the server responds with when a server function returns
+    /// this error. Defaults to `500 Internal Server Error`.
+    fn status_code(&self) -> u16 {
+        500
+    }
+
+    /// The [ServerFnError] this error holds, if it was caused by calling the server function
+    /// rather than by the function itself, e.g., to tell whether the server could be reached.
+    fn server_fn_error(&self) -> Option<&ServerFnError> {
+        None
+    }
+}
+
+impl ServerFnErrorKind for ServerFnError {
+    fn status_code(&self) -> u16 {
+        match self {
+            ServerFnError::Args(_) | ServerFnError::MissingArg(_) => 400,
+            ServerFnError::Unauthorized(_) => 403,
+            ServerFnError::PayloadTooLarge(_) => 413,
+            ServerFnError::Timeout(_) => 504,
+            _ => 500,
+        }
+    }
+
+    fn server_fn_error(&self) -> Option<&ServerFnError> {
+        Some(self)
+    }
+}
+
+/// The response that the server sends when a server function returns an error, as returned
+/// by the handlers found with [server_fn_by_path].
+#[derive(Error, Debug)]
+#[error("{message}")]
+pub struct ServerFnErrorResponse {
+    /// The HTTP status code of the response, given by [ServerFnErrorKind::status_code].
+    pub status: u16,
+    /// The serialized error, in the encoding of the server function.
+    pub payload: Payload,
+    /// The error as text, e.g., for logging.
+    pub message: String,
+}
+
+#[cfg(any(feature = "ssr", doc))]
+fn error_response<C: Codec, E: ServerFnErrorKind>(
+    encoding: Encoding,
+    error: E,
+) -> ServerFnErrorResponse {
+    let status = error.status_code();
+    let message = error.to_string();
+    let payload = match encoding {
+        Encoding::Url | Encoding::Multipart => {
+            Payload::Url(serde_json::to_string(&error).unwrap_or_default())
+        }
+        Encoding::Cbor => {
+            let mut buffer = Vec::new();
+            _ = ciborium::ser::into_writer(&error, &mut buffer);
+            Payload::Binary(buffer)
+        }
+        Encoding::Codec(content_type) => {
+            Payload::Codec(content_type, C::encode(&error).unwrap_or_default())
+        }
+    };
+    ServerFnErrorResponse {
+        status,
+        payload,
+        message,
+    }
+}
+
+/// Deserializes the error that a server function responded with from the JSON body of the
+/// response, or, if the body doesn't hold one, creates a [ServerFnError::ServerError] from
+/// `status_text`.
+pub fn server_fn_error_from_json<E: ServerFnErrorKind>(json: &str, status_text: &str) -> E {
+    serde_json::from_str(json)
+        .unwrap_or_else(|_| E::from(ServerFnError::ServerError(status_text.to_string())))
 }
 
 /// Executes the HTTP call to call a server function from the client, given its URL and argument type.
+///
+/// If the call fails, the error is passed to the hook set with
+/// [set_error_hook](leptos_reactive::set_error_hook) as well.
 #[cfg(not(feature = "ssr"))]
-pub async fn call_server_fn<T>(
-    url: &str,
-    args: impl ServerFn,
-    enc: Encoding,
-) -> Result<T, ServerFnError>
+pub async fn call_server_fn<T, E, A>(url: &str, args: A, enc: Encoding) -> Result<T, E>
 where
     T: serde::Serialize + serde::de::DeserializeOwned + Sized,
+    E: ServerFnErrorKind,
+    A: ServerFn,
 {
-    use ciborium::ser::into_writer;
-    use leptos_dom::js_sys::Uint8Array;
-    use serde_json::Deserializer as JSONDeserializer;
+    let res = fetch_server_fn(url, args, enc).await;
+    if let Err(e) = &res {
+        report_error(e, ErrorLocation::ServerFn(url.to_string()));
+    }
+    res
+}
 
-    #[derive(Debug)]
-    enum Payload {
-        Binary(Vec<u8>),
-        Url(String),
+/// Executes the HTTP call to call a server function that returns a [ServerStream] from the
+/// client, given its URL and argument type. The stream yields the values as they arrive.
+///
+/// If the call fails, or one of the values can't be deserialized, the error is passed to the
+/// hook set with [set_error_hook](leptos_reactive::set_error_hook) as well.
+#[cfg(not(feature = "ssr"))]
+pub async fn call_server_fn_stream<T, E, A>(
+    url: &str,
+    args: A,
+    enc: Encoding,
+) -> Result<ServerStream<T>, E>
+where
+    T: serde::de::DeserializeOwned + 'static,
+    E: ServerFnErrorKind,
+    A: ServerFn,
+{
+    match send_server_fn_request(url, args, &enc, "application/x-ndjson").await {
+        Ok(resp) => Ok(stream::read_ndjson(url.to_string(), resp)),
+        Err(e) => {
+            report_error(&e, ErrorLocation::ServerFn(url.to_string()));
+            Err(e)
+        }
     }
-    // log!("ARGS TO ENCODE: {:#}", &args);
-    let args_encoded = match &enc {
-        Encoding::Url => Payload::Url(
-            serde_urlencoded::to_string(&args)
-                .map_err(|e| ServerFnError::Serialization(e.to_string()))?,
-        ),
-        Encoding::Cbor => {
-            let mut buffer: Vec<u8> = Vec::new();
-            into_writer(&args, &mut buffer)
-                .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
-            Payload::Binary(buffer)
+}
+
+#[cfg(not(feature = "ssr"))]
+async fn fetch_server_fn<T, E, A>(url: &str, args: A, enc: Encoding) -> Result<T, E>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Sized,
+    E: ServerFnErrorKind,
+    A: ServerFn,
+{
+    // calls that can be sent as text are sent together with the others of the same tick
+    if server_fn_batching() && enc == Encoding::Url && A::method() == HttpMethod::Post {
+        let (_, args) = client::encode_args(args, &enc)?;
+        let args = String::from_utf8_lossy(&args).into_owned();
+        let result = batch::call_batched(A::prefix(), A::url(), args).await?;
+        if result.status >= 400 {
+            return Err(client::decode_error::<E, A::Codec>(
+                &enc,
+                result.body.as_bytes(),
+                &format!("status {}", result.status),
+            ));
         }
-    };
+        return Ok(client::decode_output::<T, A::Codec>(
+            &enc,
+            result.body.as_bytes(),
+        )?);
+    }
 
-    //log!("ENCODED DATA: {:#?}", args_encoded);
+    let resp = send_server_fn_request(url, args, &enc, client::accept_header(&enc)).await?;
 
-    let content_type_header = match &enc {
-        Encoding::Url => "application/x-www-form-urlencoded",
-        Encoding::Cbor => "application/cbor",
-    };
+    let body = resp
+        .binary()
+        .await
+        .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
+    Ok(client::decode_output::<T, A::Codec>(&enc, &body)?)
+}
 
-    let accept_header = match &enc {
-        Encoding::Url => "application/x-www-form-urlencoded",
-        Encoding::Cbor => "application/cbor",
-    };
+/// Sends the arguments to the server, returning the response if the function succeeded, and
+/// the error it responded with otherwise.
+#[cfg(not(feature = "ssr"))]
+async fn send_server_fn_request<E, A>(
+    url: &str,
+    args: A,
+    enc: &Encoding,
+    accept_header: &str,
+) -> Result<gloo_net::http::Response, E>
+where
+    E: ServerFnErrorKind,
+    A: ServerFn,
+{
+    use leptos_dom::js_sys::Uint8Array;
 
-    let resp = match args_encoded {
-        Payload::Binary(b) => {
-            let slice_ref: &[u8] = &b;
-            let js_array = Uint8Array::from(slice_ref).buffer();
-            gloo_net::http::Request::post(url)
-                .header("Content-Type", content_type_header)
-                .header("Accept", accept_header)
-                .body(js_array)
-                .send()
-                .await
-                .map_err(|e| ServerFnError::Request(e.to_string()))?
+    let (content_type, body) = client::encode_args(args, enc)?;
+
+    let request = match A::method() {
+        // the arguments of a GET request are sent in the query string, so that it can be cached
+        HttpMethod::Get => {
+            gloo_net::http::Request::get(&format!("{url}?{}", String::from_utf8_lossy(&body)))
+        }
+        HttpMethod::Post => {
+            let request = gloo_net::http::Request::post(url)
+                .header("Content-Type", &content_type)
+                .body(Uint8Array::from(body.as_slice()).buffer());
+            // send back the token the server rendered into the page
+            match CsrfToken::from_document() {
+                Some(token) => request.header(CSRF_HEADER, token.as_str()),
+                None => request,
+            }
         }
-        Payload::Url(s) => gloo_net::http::Request::post(url)
-            .header("Content-Type", content_type_header)
-            .header("Accept", accept_header)
-            .body(s)
-            .send()
-            .await
-            .map_err(|e| ServerFnError::Request(e.to_string()))?,
     };
+    let resp = request
+        .header("Accept", accept_header)
+        .send()
+        .await
+        .map_err(|e| ServerFnError::Request(e.to_string()))?;
 
-    // check for error status
-    let status = resp.status();
-    if (500..=599).contains(&status) {
-        return Err(ServerFnError::ServerError(resp.status_text()));
+    // check for error status, in which case the body holds the serialized error
+    if resp.status() >= 400 {
+        let body = resp.binary().await.unwrap_or_default();
+        return Err(client::decode_error::<E, A::Codec>(
+            enc,
+            &body,
+            &resp.status_text(),
+        ));
     }
 
-    if enc == Encoding::Cbor {
-        let binary = resp
-            .binary()
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-
-        ciborium::de::from_reader(binary.as_slice())
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))
-    } else {
-        let text = resp
-            .text()
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-
-        let mut deserializer = JSONDeserializer::from_str(&text);
-        T::deserialize(&mut deserializer).map_err(|e| ServerFnError::Deserialization(e.to_string()))
-    }
+    Ok(resp)
 }
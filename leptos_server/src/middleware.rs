@@ -0,0 +1,183 @@
+use crate::ServerFnError;
+use leptos_reactive::Scope;
+use std::{future::Future, pin::Pin};
+
+#[cfg(any(feature = "ssr", doc))]
+use std::sync::{Arc, RwLock};
+
+/// The parts of the HTTP request that called a server function. The server integrations
+/// provide them as context to the [Scope] the server function runs in, so that
+/// [ServerFnMiddleware] and the function itself can inspect them.
+///
+/// ```
+/// # use leptos_server::*;
+/// let req = RequestParts {
+///     path: "login".to_string(),
+///     headers: vec![("Cookie".to_string(), "theme=dark; user=ferris".to_string())],
+/// };
+/// assert_eq!(req.header("cookie"), Some("theme=dark; user=ferris"));
+/// assert_eq!(req.cookie("user"), Some("ferris"));
+/// assert_eq!(req.cookie("session"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestParts {
+    /// The path of the server function that was called.
+    pub path: String,
+    /// The headers of the request, in the order they were sent.
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestParts {
+    /// Returns the value of the first header with the given name, which is compared without
+    /// regard to case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of the cookie with the given name, if the request sent one.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case("Cookie"))
+            .flat_map(|(_, cookies)| cookies.split(';'))
+            .find_map(|cookie| {
+                let (cookie, value) = cookie.trim().split_once('=')?;
+                (cookie == name).then_some(value)
+            })
+    }
+}
+
+/// Runs on the server before the body of a server function, e.g., to check that the user is
+/// authorized, to log calls, or to limit their rate. If it returns an error, the function
+/// isn't run, and the error is sent to the client instead.
+///
+/// Middleware can be attached to a single server function with
+/// `#[server(Name, middleware = [...])]`, or to all of them with
+/// [register_server_fn_middleware]. Middleware registered for all server functions runs
+/// first; then the middleware of the function runs, in the order it is listed.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// # use std::{future::Future, pin::Pin};
+/// pub struct RequireAuth;
+///
+/// impl ServerFnMiddleware for RequireAuth {
+///     fn before(
+///         &self,
+///         _cx: Scope,
+///         req: &RequestParts,
+///     ) -> Pin<Box<dyn Future<Output = Result<(), ServerFnError>>>> {
+///         let authorized = req.header("Authorization").is_some();
+///         Box::pin(async move {
+///             if authorized {
+///                 Ok(())
+///             } else {
+///                 Err(ServerFnError::ServerError("unauthorized".into()))
+///             }
+///         })
+///     }
+/// }
+///
+/// #[server(DeleteTodo, middleware = [RequireAuth])]
+/// async fn delete_todo(id: u32) -> Result<(), ServerFnError> {
+///     todo!()
+/// }
+/// ```
+pub trait ServerFnMiddleware: Send + Sync + 'static {
+    /// Called before the server function runs, with the [Scope] it runs in and the request
+    /// that called it.
+    #[allow(clippy::type_complexity)]
+    fn before(
+        &self,
+        cx: Scope,
+        req: &RequestParts,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ServerFnError>>>>;
+}
+
+/// Turns an async function that checks whether a request may call a server function into
+/// [ServerFnMiddleware], which is how a guard declared with `#[server(Name, guard = ...)]`
+/// is run. The function receives the [Scope] the server function runs in and the request that
+/// called it, and rejects the call by returning an error, e.g.,
+/// [ServerFnError::Unauthorized], which is sent with `403 Forbidden`.
+///
+/// Guards run after any other middleware, but before the arguments are deserialized, so that
+/// unauthorized requests are rejected without doing any work for them.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// async fn admin_only(_cx: Scope, req: RequestParts) -> Result<(), ServerFnError> {
+///     match req.cookie("role") {
+///         Some("admin") => Ok(()),
+///         _ => Err(ServerFnError::Unauthorized("admins only".into())),
+///     }
+/// }
+///
+/// #[server(DeleteUser, guard = admin_only)]
+/// async fn delete_user(id: u32) -> Result<(), ServerFnError> {
+///     todo!()
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// let guard = ServerFnGuard::new(admin_only);
+/// let req = RequestParts {
+///     path: "delete_user".to_string(),
+///     headers: vec![("Cookie".to_string(), "role=user".to_string())],
+/// };
+/// let result = futures::executor::block_on(guard.before(cx, &req));
+/// assert!(matches!(result, Err(ServerFnError::Unauthorized(_))));
+/// # });
+/// ```
+pub struct ServerFnGuard<F>(F);
+
+impl<F, Fut> ServerFnGuard<F>
+where
+    F: Fn(Scope, RequestParts) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), ServerFnError>> + 'static,
+{
+    /// Wraps the guard function.
+    pub fn new(guard: F) -> Self {
+        Self(guard)
+    }
+}
+
+impl<F, Fut> ServerFnMiddleware for ServerFnGuard<F>
+where
+    F: Fn(Scope, RequestParts) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), ServerFnError>> + 'static,
+{
+    fn before(
+        &self,
+        cx: Scope,
+        req: &RequestParts,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ServerFnError>>>> {
+        Box::pin((self.0)(cx, req.clone()))
+    }
+}
+
+#[cfg(any(feature = "ssr", doc))]
+static GLOBAL_MIDDLEWARE: RwLock<Vec<Arc<dyn ServerFnMiddleware>>> = RwLock::new(Vec::new());
+
+/// Attaches middleware to all server functions, which runs before the middleware attached
+/// to each of them with `#[server(Name, middleware = [...])]`.
+#[cfg(any(feature = "ssr", doc))]
+pub fn register_server_fn_middleware(middleware: impl ServerFnMiddleware) {
+    GLOBAL_MIDDLEWARE
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(middleware));
+}
+
+#[cfg(any(feature = "ssr", doc))]
+pub(crate) fn global_middleware() -> Vec<Arc<dyn ServerFnMiddleware>> {
+    GLOBAL_MIDDLEWARE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
@@ -1,4 +1,4 @@
-use crate::{ServerFn, ServerFnError};
+use crate::ServerFn;
 use leptos_reactive::{create_rw_signal, spawn_local, ReadSignal, RwSignal, Scope};
 use std::{future::Future, pin::Pin, rc::Rc};
 
@@ -269,7 +269,7 @@ where
 /// let my_server_multi_action = create_server_multi_action::<MyServerFn>(cx);
 /// # });
 /// ```
-pub fn create_server_multi_action<S>(cx: Scope) -> MultiAction<S, Result<S::Output, ServerFnError>>
+pub fn create_server_multi_action<S>(cx: Scope) -> MultiAction<S, Result<S::Output, S::Error>>
 where
     S: Clone + ServerFn,
 {
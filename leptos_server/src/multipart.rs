@@ -0,0 +1,335 @@
+use crate::ServerFnError;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A file uploaded in a `multipart/form-data` request, e.g., from an
+/// `<input type="file">` in an `<ActionForm>` with `enctype="multipart/form-data"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedFile {
+    /// The name of the form field the file was uploaded with.
+    pub field: String,
+    /// The name of the file on the client.
+    pub file_name: String,
+    /// The MIME type of the file, if the client sent one.
+    pub content_type: Option<String>,
+    /// The contents of the file.
+    pub data: Vec<u8>,
+}
+
+/// The fields and files of a `multipart/form-data` request, in the order they were sent.
+///
+/// A server function that takes a `MultipartData` or an [UploadedFile] argument is called with
+/// a `multipart/form-data` request, so that it can be the target of a form that uploads files.
+/// Each of its other arguments is read from the text field with the same name, and a
+/// `MultipartData` argument receives all the fields and files that are left. The whole request
+/// is read into memory, up to the `max_upload_size` set in the `RenderOptions`.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(UploadAvatar)]
+/// async fn upload_avatar(user: String, avatar: UploadedFile) -> Result<usize, ServerFnError> {
+///     // save the file...
+///     Ok(avatar.data.len())
+/// }
+///
+/// let mut data = MultipartData::new();
+/// data.push_field("user", "ferris");
+/// data.push_file(UploadedFile {
+///     field: "avatar".into(),
+///     file_name: "crab.png".into(),
+///     content_type: Some("image/png".into()),
+///     data: vec![1, 2, 3],
+/// });
+/// let boundary = data.boundary();
+/// let body = data.encode(&boundary);
+/// assert_eq!(MultipartData::parse(&body, &boundary).unwrap(), data);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultipartData {
+    fields: Vec<(String, String)>,
+    files: Vec<UploadedFile>,
+}
+
+impl MultipartData {
+    /// Creates empty form data, e.g., to call a server function that takes it from the client.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the first text field with the given name.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterates over the names and values of the text fields.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(field, value)| (field.as_str(), value.as_str()))
+    }
+
+    /// Returns the first file that was uploaded with the given field name.
+    pub fn file(&self, name: &str) -> Option<&UploadedFile> {
+        self.files.iter().find(|file| file.field == name)
+    }
+
+    /// The files that were uploaded.
+    pub fn files(&self) -> &[UploadedFile] {
+        &self.files
+    }
+
+    /// Adds a text field.
+    pub fn push_field(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.fields.push((name.into(), value.into()));
+    }
+
+    /// Adds a file.
+    pub fn push_file(&mut self, file: UploadedFile) {
+        self.files.push(file);
+    }
+
+    /// Returns a boundary that doesn't occur in any of the fields or files, to separate them
+    /// in the body created by [MultipartData::encode].
+    pub fn boundary(&self) -> String {
+        (0..)
+            .map(|n| format!("leptos-boundary-{n}"))
+            .find(|boundary| {
+                let boundary = boundary.as_bytes();
+                !self
+                    .fields
+                    .iter()
+                    .any(|(_, value)| contains(value.as_bytes(), boundary))
+                    && !self.files.iter().any(|file| contains(&file.data, boundary))
+            })
+            .expect("there is always an unused boundary")
+    }
+
+    /// Encodes the fields, then the files, as the body of a `multipart/form-data` request,
+    /// separated by the given `boundary`.
+    pub fn encode(&self, boundary: &str) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, value) in &self.fields {
+            body.extend(format!("--{boundary}\r\n").as_bytes());
+            body.extend(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                    escape_param(name)
+                )
+                .as_bytes(),
+            );
+            body.extend(value.as_bytes());
+            body.extend(b"\r\n");
+        }
+        for file in &self.files {
+            body.extend(format!("--{boundary}\r\n").as_bytes());
+            body.extend(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n",
+                    escape_param(&file.field),
+                    escape_param(&file.file_name)
+                )
+                .as_bytes(),
+            );
+            body.extend(
+                format!(
+                    "Content-Type: {}\r\n\r\n",
+                    file.content_type
+                        .as_deref()
+                        .unwrap_or("application/octet-stream")
+                )
+                .as_bytes(),
+            );
+            body.extend(&file.data);
+            body.extend(b"\r\n");
+        }
+        body.extend(format!("--{boundary}--\r\n").as_bytes());
+        body
+    }
+
+    /// Parses the body of a `multipart/form-data` request, whose parts are separated by the
+    /// given `boundary`. Parts with a `filename` become [UploadedFile]s; all others are text
+    /// fields.
+    pub fn parse(body: &[u8], boundary: &str) -> Result<Self, ServerFnError> {
+        let malformed = |reason: &str| {
+            ServerFnError::Deserialization(format!("malformed multipart/form-data: {reason}"))
+        };
+        let delimiter = format!("--{boundary}").into_bytes();
+        let mut data = Self::default();
+
+        let mut pos = find(body, &delimiter, 0).ok_or_else(|| malformed("missing boundary"))?
+            + delimiter.len();
+        // the last delimiter is followed by `--`
+        while !body[pos..].starts_with(b"--") {
+            if body[pos..].starts_with(b"\r\n") {
+                pos += 2;
+            }
+            let headers_end =
+                find(body, b"\r\n\r\n", pos).ok_or_else(|| malformed("missing part headers"))?;
+            let headers = std::str::from_utf8(&body[pos..headers_end])
+                .map_err(|_| malformed("part headers aren't UTF-8"))?;
+            let content_start = headers_end + 4;
+            let content_end = find(
+                body,
+                &[b"\r\n", delimiter.as_slice()].concat(),
+                content_start,
+            )
+            .ok_or_else(|| malformed("missing closing boundary"))?;
+            let content = &body[content_start..content_end];
+            pos = content_end + 2 + delimiter.len();
+
+            let mut name = None;
+            let mut file_name = None;
+            let mut content_type = None;
+            for header in headers.split("\r\n") {
+                let (header, value) = header.split_once(':').unwrap_or((header, ""));
+                if header.eq_ignore_ascii_case("Content-Disposition") {
+                    for (param, value) in header_params(value) {
+                        match param.as_str() {
+                            "name" => name = Some(value),
+                            "filename" => file_name = Some(value),
+                            _ => {}
+                        }
+                    }
+                } else if header.eq_ignore_ascii_case("Content-Type") {
+                    content_type = Some(value.trim().to_string());
+                }
+            }
+
+            let name = name.ok_or_else(|| malformed("part without a name"))?;
+            match file_name {
+                Some(file_name) => data.files.push(UploadedFile {
+                    field: name,
+                    file_name,
+                    content_type,
+                    data: content.to_vec(),
+                }),
+                None => {
+                    let value = String::from_utf8(content.to_vec())
+                        .map_err(|_| malformed("text field isn't UTF-8"))?;
+                    data.fields.push((name, value));
+                }
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// Returns the `boundary` parameter of a `multipart/form-data` `Content-Type` header.
+#[cfg(any(feature = "ssr", doc))]
+pub(crate) fn multipart_boundary(content_type: &str) -> Option<String> {
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    header_params(params)
+        .into_iter()
+        .find(|(param, _)| param == "boundary")
+        .map(|(_, boundary)| boundary)
+}
+
+/// An argument of a server function that takes a `multipart/form-data` request. See
+/// [MultipartData] for how the arguments are read from the request.
+pub trait MultipartArg: Sized {
+    /// Takes the argument named `name` out of the data the request was parsed into.
+    fn from_multipart(data: &mut MultipartData, name: &str) -> Result<Self, ServerFnError>;
+
+    /// Adds the argument named `name` to the data that is sent with the request.
+    fn into_multipart(self, data: &mut MultipartData, name: &str);
+}
+
+impl MultipartArg for MultipartData {
+    fn from_multipart(data: &mut MultipartData, _name: &str) -> Result<Self, ServerFnError> {
+        Ok(std::mem::take(data))
+    }
+
+    fn into_multipart(self, data: &mut MultipartData, _name: &str) {
+        data.fields.extend(self.fields);
+        data.files.extend(self.files);
+    }
+}
+
+impl MultipartArg for UploadedFile {
+    fn from_multipart(data: &mut MultipartData, name: &str) -> Result<Self, ServerFnError> {
+        let index = data
+            .files
+            .iter()
+            .position(|file| file.field == name)
+            .ok_or_else(|| ServerFnError::MissingArg(name.to_string()))?;
+        Ok(data.files.remove(index))
+    }
+
+    fn into_multipart(mut self, data: &mut MultipartData, name: &str) {
+        self.field = name.to_string();
+        data.files.push(self);
+    }
+}
+
+impl<T> MultipartArg for T
+where
+    T: FromStr + ToString,
+{
+    fn from_multipart(data: &mut MultipartData, name: &str) -> Result<Self, ServerFnError> {
+        let index = data
+            .fields
+            .iter()
+            .position(|(field, _)| field == name)
+            .ok_or_else(|| ServerFnError::MissingArg(name.to_string()))?;
+        let (_, value) = data.fields.remove(index);
+        value
+            .parse()
+            .map_err(|_| ServerFnError::Args(format!("invalid value for {name}: {value:?}")))
+    }
+
+    fn into_multipart(self, data: &mut MultipartData, name: &str) {
+        data.push_field(name, self.to_string());
+    }
+}
+
+/// Parses the `; key=value` parameters of a header, whose values may be quoted.
+fn header_params(params: &str) -> Vec<(String, String)> {
+    let mut parsed = Vec::new();
+    let mut rest = params;
+    while let Some((param, value)) = rest.split_once('=') {
+        let param = param.trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+        let value = value.trim_start();
+        let (value, next) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+            }
+            None => {
+                let end = value.find(';').unwrap_or(value.len());
+                (value[..end].trim_end(), &value[end..])
+            }
+        };
+        // skip parameters without a value, like `form-data`
+        let param = param.rsplit(';').next().unwrap_or(param).trim();
+        parsed.push((param.to_ascii_lowercase(), value.to_string()));
+        rest = next;
+    }
+    parsed
+}
+
+/// Escapes a parameter value the way browsers do, so that it can be quoted.
+fn escape_param(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    find(haystack, needle, 0).is_some()
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
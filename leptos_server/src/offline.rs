@@ -0,0 +1,381 @@
+use crate::{ServerFn, ServerFnError, ServerFnErrorKind};
+use leptos_reactive::{
+    create_rw_signal, on_cleanup, spawn_local_scoped, ReadSignal, RwSignal, Scope,
+    UntrackedGettableSignal,
+};
+use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+use leptos_reactive::debug_warn;
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Creates an [OfflineAction], which queues calls to the server function `S` while the
+/// browser is offline and sends them once connectivity returns.
+///
+/// The queue is persisted in the browser's
+/// [IndexedDB](https://developer.mozilla.org/en-US/docs/Web/API/IndexedDB_API) under `name`,
+/// so calls that were queued before the page was closed or reloaded are sent the next time an
+/// `OfflineAction` with the same `name` is created. Calls are always sent one at a time, in the
+/// order in which they were dispatched.
+///
+/// If the server can't be reached, the call stays at the front of the queue until the browser
+/// fires an [`online`](https://developer.mozilla.org/en-US/docs/Web/API/Window/online_event)
+/// event or another call is dispatched. If the server returns any other error, for example
+/// because the data was changed by someone else in the meantime, the
+/// [on_conflict](OfflineAction::on_conflict) hook decides what to do with the call.
+///
+/// On the server, which is never offline, calls are sent right away and nothing is persisted.
+///
+/// ```rust,no_run
+/// # use leptos_reactive::*;
+/// # use leptos_server::{create_offline_action, ConflictResolution, ServerFnError, ServerFn};
+/// # use leptos_macro::server;
+///
+/// #[server(AddTodo)]
+/// async fn add_todo(title: String) -> Result<(), ServerFnError> {
+///   todo!()
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// let add_todo = create_offline_action::<AddTodo>(cx, "add_todo").on_conflict(|_, error| {
+///   // give up on calls the server rejects, instead of sending them again and again
+///   log::warn!("could not add todo: {error}");
+///   ConflictResolution::Discard
+/// });
+/// add_todo.dispatch(AddTodo { title: "Buy milk".to_string() });
+///
+/// // how many calls are still waiting to be sent
+/// let waiting = move || add_todo.queued().with(|queued| queued.len());
+/// # });
+/// ```
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub fn create_offline_action<S>(cx: Scope, name: &'static str) -> OfflineAction<S>
+where
+    S: Clone + ServerFn,
+{
+    let action = OfflineAction {
+        cx,
+        name,
+        queue: create_rw_signal(cx, Vec::new()),
+        value: create_rw_signal(cx, None),
+        version: create_rw_signal(cx, 0),
+        state: Rc::new(RefCell::new(QueueState {
+            loaded: cfg!(not(any(feature = "csr", feature = "hydrate"))),
+            replaying: false,
+            on_conflict: None,
+            #[cfg(any(feature = "csr", feature = "hydrate"))]
+            db: None,
+            #[cfg(any(feature = "csr", feature = "hydrate"))]
+            online_listener: None,
+        })),
+    };
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    {
+        if let Some(window) = web_sys::window() {
+            let listener = Closure::<dyn FnMut()>::new({
+                let action = action.clone();
+                move || action.replay()
+            });
+            _ = window
+                .add_event_listener_with_callback("online", listener.as_ref().unchecked_ref());
+            action.state.borrow_mut().online_listener = Some(listener);
+        }
+
+        spawn_local_scoped(cx, {
+            let action = action.clone();
+            async move {
+                match idb::open().await {
+                    Ok(db) => {
+                        let saved = match idb::load(&db, name).await {
+                            Ok(saved) => saved,
+                            Err(e) => {
+                                debug_warn!(
+                                    "[create_offline_action] could not load {name:?}: {e:?}"
+                                );
+                                None
+                            }
+                        };
+                        let saved = saved
+                            .and_then(|saved| serde_json::from_str::<Vec<S>>(&saved).ok())
+                            .unwrap_or_default();
+                        {
+                            let mut state = action.state.borrow_mut();
+                            state.db = Some(db);
+                            state.loaded = true;
+                        }
+                        // calls saved in an earlier session were dispatched first
+                        action.queue.update(|queue| {
+                            let dispatched = std::mem::replace(queue, saved);
+                            queue.extend(dispatched);
+                        });
+                        action.persist();
+                    }
+                    Err(e) => {
+                        debug_warn!("[create_offline_action] could not open IndexedDB: {e:?}");
+                        action.state.borrow_mut().loaded = true;
+                    }
+                }
+                action.replay();
+            }
+        });
+    }
+
+    on_cleanup(cx, {
+        let state = Rc::clone(&action.state);
+        move || {
+            let mut state = state.borrow_mut();
+            state.on_conflict = None;
+            #[cfg(any(feature = "csr", feature = "hydrate"))]
+            if let (Some(listener), Some(window)) =
+                (state.online_listener.take(), web_sys::window())
+            {
+                _ = window.remove_event_listener_with_callback(
+                    "online",
+                    listener.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    });
+
+    action
+}
+
+/// An action that queues calls to a server function while the browser is offline, persists
+/// them, and sends them once connectivity returns. Created with [create_offline_action].
+pub struct OfflineAction<S>
+where
+    S: ServerFn,
+{
+    cx: Scope,
+    name: &'static str,
+    queue: RwSignal<Vec<S>>,
+    /// The result of the most recent call that has been sent to the server, unless it is
+    /// being retried.
+    pub value: RwSignal<Option<Result<S::Output, S::Error>>>,
+    /// How many queued calls have been sent to the server and removed from the queue.
+    pub version: RwSignal<usize>,
+    state: Rc<RefCell<QueueState<S>>>,
+}
+
+/// What an [OfflineAction] does with a queued call that the server returned an error for,
+/// decided by its [on_conflict](OfflineAction::on_conflict) hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictResolution<S> {
+    /// Removes the call from the queue, and stores the error in [OfflineAction::value].
+    Discard,
+    /// Keeps the call at the front of the queue, and stops sending calls until connectivity
+    /// returns or another call is dispatched.
+    Keep,
+    /// Sends the given arguments instead, e.g., after merging the queued change with the
+    /// server's version of the data.
+    Replace(S),
+}
+
+type ConflictFn<S> = dyn Fn(&S, &<S as ServerFn>::Error) -> ConflictResolution<S>;
+
+struct QueueState<S>
+where
+    S: ServerFn,
+{
+    /// Whether the calls persisted in an earlier session have been added to the queue.
+    loaded: bool,
+    replaying: bool,
+    on_conflict: Option<Rc<ConflictFn<S>>>,
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    db: Option<web_sys::IdbDatabase>,
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    online_listener: Option<Closure<dyn FnMut()>>,
+}
+
+impl<S> Clone for OfflineAction<S>
+where
+    S: ServerFn,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cx: self.cx,
+            name: self.name,
+            queue: self.queue,
+            value: self.value,
+            version: self.version,
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<S> OfflineAction<S>
+where
+    S: Clone + ServerFn,
+{
+    /// Adds a call with the given arguments to the end of the queue, and sends it to the
+    /// server once the calls before it have been sent, if the browser is online.
+    pub fn dispatch(&self, input: S) {
+        self.queue.update(|queue| queue.push(input));
+        self.persist();
+        self.replay();
+    }
+
+    /// The arguments of the calls that are waiting to be sent to the server, in order.
+    pub fn queued(&self) -> ReadSignal<Vec<S>> {
+        self.queue.read_only()
+    }
+
+    /// Sets the hook that decides what happens to a queued call when the server returns an
+    /// error other than a network error. Without a hook, the call is
+    /// [discarded](ConflictResolution::Discard).
+    pub fn on_conflict(
+        self,
+        on_conflict: impl Fn(&S, &S::Error) -> ConflictResolution<S> + 'static,
+    ) -> Self {
+        self.state.borrow_mut().on_conflict = Some(Rc::new(on_conflict));
+        self
+    }
+
+    /// Sends the queued calls to the server, one at a time, unless they are already being sent.
+    fn replay(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            if !state.loaded || state.replaying || !is_online() {
+                return;
+            }
+            state.replaying = true;
+        }
+
+        let action = self.clone();
+        spawn_local_scoped(self.cx, async move {
+            while let Some(input) = action.queue.with_untracked(|queue| queue.first().cloned()) {
+                match call(action.cx, input.clone()).await {
+                    // the server couldn't be reached, so wait until it can
+                    Err(e) if matches!(e.server_fn_error(), Some(ServerFnError::Request(_))) => {
+                        break
+                    }
+                    Err(e) => {
+                        let on_conflict = action.state.borrow().on_conflict.clone();
+                        let resolution = on_conflict
+                            .map(|on_conflict| on_conflict(&input, &e))
+                            .unwrap_or(ConflictResolution::Discard);
+                        match resolution {
+                            ConflictResolution::Discard => action.complete(Err(e)),
+                            ConflictResolution::Keep => break,
+                            ConflictResolution::Replace(input) => {
+                                action.queue.update(|queue| queue[0] = input);
+                                action.persist();
+                            }
+                        }
+                    }
+                    Ok(value) => action.complete(Ok(value)),
+                }
+            }
+            action.state.borrow_mut().replaying = false;
+        });
+    }
+
+    /// Removes the call at the front of the queue, which has been sent, and stores its result.
+    fn complete(&self, result: Result<S::Output, S::Error>) {
+        self.queue.update(|queue| _ = queue.remove(0));
+        self.persist();
+        self.value.set(Some(result));
+        self.version.update(|n| *n += 1);
+    }
+
+    /// Saves the queue to IndexedDB, once the calls saved in an earlier session have been loaded.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    fn persist(&self) {
+        #[cfg(any(feature = "csr", feature = "hydrate"))]
+        {
+            let state = self.state.borrow();
+            if let Some(db) = &state.db {
+                let queue = self.queue.with_untracked(serde_json::to_string);
+                // the request is made synchronously, so saves happen in the order they are made
+                let saved = queue
+                    .map_err(|e| wasm_bindgen::JsValue::from_str(&e.to_string()))
+                    .and_then(|queue| idb::save(db, self.name, &queue));
+                if let Err(e) = saved {
+                    debug_warn!("[OfflineAction] could not save {:?}: {e:?}", self.name);
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn call<S>(cx: Scope, input: S) -> Pin<Box<dyn Future<Output = Result<S::Output, S::Error>>>>
+where
+    S: ServerFn,
+{
+    #[cfg(feature = "ssr")]
+    return input.call_fn(cx);
+    #[cfg(not(feature = "ssr"))]
+    return input.call_fn_client(cx);
+}
+
+fn is_online() -> bool {
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    return web_sys::window()
+        .map(|window| window.navigator().on_line())
+        .unwrap_or(true);
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    return true;
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+mod idb {
+    use leptos_dom::js_sys::Promise;
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode};
+
+    const DATABASE: &str = "leptos_server";
+    const STORE: &str = "offline_actions";
+
+    /// Waits for a request to succeed, and returns its result.
+    async fn wait(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = Promise::new(&mut |resolve, reject| {
+            request.set_onsuccess(Some(&resolve));
+            request.set_onerror(Some(&reject));
+        });
+        let done = JsFuture::from(promise).await;
+        request.set_onsuccess(None);
+        request.set_onerror(None);
+        done?;
+        request.result()
+    }
+
+    pub(crate) async fn open() -> Result<IdbDatabase, JsValue> {
+        let factory = web_sys::window()
+            .ok_or(JsValue::NULL)?
+            .indexed_db()?
+            .ok_or(JsValue::NULL)?;
+        let request = factory.open_with_u32(DATABASE, 1)?;
+        let upgrade = Closure::<dyn FnMut()>::new({
+            let request = request.clone();
+            move || {
+                if let Ok(db) = request.result() {
+                    _ = db
+                        .unchecked_into::<IdbDatabase>()
+                        .create_object_store(STORE);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+        let db = wait(&request).await;
+        request.set_onupgradeneeded(None);
+        Ok(db?.unchecked_into())
+    }
+
+    pub(crate) async fn load(db: &IdbDatabase, name: &str) -> Result<Option<String>, JsValue> {
+        let store = db.transaction_with_str(STORE)?.object_store(STORE)?;
+        let value = wait(&store.get(&JsValue::from_str(name))?).await?;
+        Ok(value.as_string())
+    }
+
+    pub(crate) fn save(db: &IdbDatabase, name: &str, queue: &str) -> Result<(), JsValue> {
+        let store = db
+            .transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?
+            .object_store(STORE)?;
+        store.put_with_key(&JsValue::from_str(queue), &JsValue::from_str(name))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{server_fns, Encoding, HttpMethod, ServerFnInfo};
+use serde_json::{json, Map, Value};
+
+/// Describes all the server functions that have been registered as an
+/// [OpenAPI 3.0](https://spec.openapis.org/oas/v3.0.3) document, so that docs and contract
+/// tests can be generated for the endpoints that the `#[server]` macro creates.
+///
+/// Each function becomes an operation at its URL, whose `operationId` is its path. The
+/// arguments of a `GET` function are query parameters, and those of all others are the
+/// properties of the request body. The document can be edited before it is served, e.g., to
+/// set its `info`.
+///
+/// By default, the schemas are only guessed from the names of the Rust types written in the
+/// signature: primitives, strings, `Option`, `Vec` and maps are described as such, but any
+/// other type is only an object with the name of the type as its title, without its fields.
+/// With the `schemars` feature, the `#[server]` macro generates the schemas of the types with
+/// [schemars](https://docs.rs/schemars), so they describe what's actually sent, and the types
+/// they refer to are listed in `components`. The arguments, the return type and the error type
+/// of every server function then have to implement `JsonSchema`.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(AddTodo, "/api")]
+/// async fn add_todo(title: String, due: Option<u64>) -> Result<u32, ServerFnError> {
+///     todo!()
+/// }
+///
+/// # #[cfg(feature = "ssr")] {
+/// AddTodo::register().unwrap();
+/// let spec = openapi_spec();
+/// let (url, path) = spec["paths"].as_object().unwrap().iter().next().unwrap();
+/// assert!(url.starts_with("/api/"));
+/// let schema = &path["post"]["requestBody"]["content"]["application/x-www-form-urlencoded"]["schema"];
+/// assert_eq!(schema["properties"]["title"]["type"], "string");
+/// assert_eq!(schema["properties"]["due"]["nullable"], true);
+/// assert_eq!(schema["required"], serde_json::json!(["title"]));
+/// # }
+/// ```
+///
+/// With the `schemars` feature, types that derive `JsonSchema` are described by their fields.
+///
+/// ```
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+/// pub struct Todo {
+///     title: String,
+///     done: bool,
+/// }
+///
+/// #[server(ListTodos, "/api")]
+/// async fn list_todos(done: Option<bool>) -> Result<Vec<Todo>, ServerFnError> {
+///     todo!()
+/// }
+///
+/// # #[cfg(all(feature = "ssr", feature = "schemars"))] {
+/// ListTodos::register().unwrap();
+/// let spec = openapi_spec();
+/// assert_eq!(spec["components"]["schemas"]["Todo"]["properties"]["done"]["type"], "boolean");
+/// # }
+/// ```
+pub fn openapi_spec() -> Value {
+    #[cfg(feature = "schemars")]
+    let mut generator = schemars::gen::SchemaSettings::openapi3().into_generator();
+
+    let mut paths = Map::new();
+    for info in server_fns() {
+        let method = match info.method {
+            HttpMethod::Get => "get",
+            HttpMethod::Post => "post",
+        };
+        #[cfg(feature = "schemars")]
+        let schemas = crate::server_fn_schemas(info.path, &mut generator)
+            .map(Schemas::from)
+            .unwrap_or_default();
+        #[cfg(not(feature = "schemars"))]
+        let schemas = Schemas::default();
+        paths.insert(info.url(), json!({ method: operation(&info, &schemas) }));
+    }
+
+    #[allow(unused_mut)]
+    let mut spec = json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Server functions",
+            "version": "0.0.0",
+        },
+        "paths": paths,
+    });
+    #[cfg(feature = "schemars")]
+    {
+        spec["components"] = json!({ "schemas": generator.definitions() });
+    }
+    spec
+}
+
+/// The schemas that were generated for the types in a signature, which are used instead of
+/// the ones guessed from their names.
+#[derive(Default)]
+struct Schemas {
+    args: Vec<(&'static str, Value)>,
+    output: Option<Value>,
+    error: Option<Value>,
+}
+
+impl Schemas {
+    fn arg(&self, name: &str, ty: &str) -> Value {
+        self.args
+            .iter()
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, schema)| schema.clone())
+            .unwrap_or_else(|| type_schema(ty))
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl From<crate::ServerFnSchemas> for Schemas {
+    fn from(schemas: crate::ServerFnSchemas) -> Self {
+        let to_value = |schema| serde_json::to_value(schema).unwrap_or_default();
+        Self {
+            args: schemas
+                .args
+                .into_iter()
+                .map(|(name, schema)| (name, to_value(schema)))
+                .collect(),
+            output: Some(to_value(schemas.output)),
+            error: Some(to_value(schemas.error)),
+        }
+    }
+}
+
+fn operation(info: &ServerFnInfo, schemas: &Schemas) -> Value {
+    let signature = &info.signature;
+    let mut operation = Map::new();
+    operation.insert("operationId".into(), info.path.into());
+
+    let required = |ty: &str| !ty.starts_with("Option<");
+    if info.method == HttpMethod::Get {
+        let parameters = signature
+            .args
+            .iter()
+            .map(|(name, ty)| {
+                json!({
+                    "name": name,
+                    "in": "query",
+                    "required": required(ty),
+                    "schema": schemas.arg(name, ty),
+                })
+            })
+            .collect::<Vec<_>>();
+        operation.insert("parameters".into(), parameters.into());
+    } else {
+        let properties = signature
+            .args
+            .iter()
+            .map(|(name, ty)| (name.to_string(), schemas.arg(name, ty)))
+            .collect::<Map<_, _>>();
+        let required = signature
+            .args
+            .iter()
+            .filter(|(_, ty)| required(ty))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        let content_type = match &info.encoding {
+            Encoding::Url => "application/x-www-form-urlencoded",
+            Encoding::Cbor => "application/cbor",
+            Encoding::Multipart => "multipart/form-data",
+            Encoding::Codec(content_type) => content_type,
+        };
+        operation.insert(
+            "requestBody".into(),
+            json!({
+                "required": true,
+                "content": {
+                    content_type: {
+                        "schema": {
+                            "type": "object",
+                            "properties": properties,
+                            "required": required,
+                        },
+                    },
+                },
+            }),
+        );
+    }
+
+    let content_type = match &info.encoding {
+        Encoding::Url | Encoding::Multipart => "application/json",
+        Encoding::Cbor => "application/cbor",
+        Encoding::Codec(content_type) => content_type,
+    };
+    // streamed values are sent as newline-delimited JSON; errors are sent in the encoding
+    let output_content_type = if signature.streaming {
+        "application/x-ndjson"
+    } else {
+        content_type
+    };
+    let output_schema = schemas
+        .output
+        .clone()
+        .unwrap_or_else(|| type_schema(signature.output));
+    let error_schema = schemas
+        .error
+        .clone()
+        .unwrap_or_else(|| type_schema(signature.error));
+    let mut success = json!({
+        "description": "The value the function returned.",
+        "content": {
+            output_content_type: { "schema": output_schema },
+        },
+    });
+    if let Some(cache_control) = info.cache_control {
+        success["headers"] = json!({
+            "Cache-Control": { "schema": { "type": "string", "example": cache_control } },
+        });
+    }
+    operation.insert(
+        "responses".into(),
+        json!({
+            "200": success,
+            "default": {
+                "description": "The error the function returned.",
+                "content": { content_type: { "schema": error_schema } },
+            },
+        }),
+    );
+    operation.into()
+}
+
+/// Guesses the JSON schema of a Rust type from its name, as it's written in a signature.
+fn type_schema(ty: &str) -> Value {
+    let ty = ty.trim().trim_start_matches('&').trim();
+    // the path of a type doesn't change how it's serialized
+    let name = ty.split('<').next().unwrap_or(ty);
+    let name = name.rsplit("::").next().unwrap_or(name).trim();
+    let generic = |index: usize| generic_args(ty).get(index).copied().unwrap_or("");
+
+    match name {
+        "bool" => json!({ "type": "boolean" }),
+        "i8" | "i16" | "i32" | "u8" | "u16" => json!({ "type": "integer", "format": "int32" }),
+        "i64" | "i128" | "isize" | "u32" | "u64" | "u128" | "usize" => {
+            json!({ "type": "integer", "format": "int64" })
+        }
+        "f32" => json!({ "type": "number", "format": "float" }),
+        "f64" => json!({ "type": "number", "format": "double" }),
+        "String" | "str" | "char" | "'static str" => json!({ "type": "string" }),
+        "()" => json!({ "nullable": true }),
+        "Option" => {
+            let mut schema = type_schema(generic(0));
+            schema["nullable"] = true.into();
+            schema
+        }
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => {
+            json!({ "type": "array", "items": type_schema(generic(0)) })
+        }
+        "HashMap" | "BTreeMap" => {
+            json!({ "type": "object", "additionalProperties": type_schema(generic(1)) })
+        }
+        "Box" | "Rc" | "Arc" => type_schema(generic(0)),
+        _ if name.starts_with('[') => {
+            let item = name.trim_start_matches('[').trim_end_matches(']');
+            let item = item.split(';').next().unwrap_or(item);
+            json!({ "type": "array", "items": type_schema(item) })
+        }
+        _ => json!({ "type": "object", "title": ty }),
+    }
+}
+
+/// Splits the generic arguments of a type like `HashMap<String, Vec<u8>>` at the top level.
+fn generic_args(ty: &str) -> Vec<&str> {
+    let inner = match (ty.find('<'), ty.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &ty[start + 1..end],
+        _ => return Vec::new(),
+    };
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut from = 0;
+    for (index, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(inner[from..index].trim());
+                from = index + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(inner[from..].trim());
+    args
+}
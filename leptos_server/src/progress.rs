@@ -0,0 +1,143 @@
+use crate::ServerFnError;
+use futures::{
+    channel::mpsc,
+    future::{self, Either},
+    Stream, StreamExt,
+};
+use leptos_reactive::*;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// An event of a long-running server function that reports its progress, as streamed by
+/// [progress_stream]: any number of updates, followed by the result of the function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Progress<P, T, E = ServerFnError> {
+    /// The function has made progress, e.g., the number of rows imported so far.
+    Update(P),
+    /// The function has finished with this value.
+    Done(T),
+    /// The function has failed with this error.
+    Failed(E),
+}
+
+/// Reports the progress of the job that [progress_stream] runs.
+#[derive(Debug, Clone)]
+pub struct ProgressSender<P> {
+    tx: mpsc::UnboundedSender<P>,
+}
+
+impl<P> ProgressSender<P> {
+    /// Sends an update to the client. Updates sent after the job has finished are dropped.
+    pub fn send(&self, progress: P) {
+        _ = self.tx.unbounded_send(progress);
+    }
+}
+
+/// Runs a long-running job, e.g., an import or an export, and streams the updates it sends
+/// with its [ProgressSender] as they're made, followed by its result. A server function
+/// returns this as `impl Stream<Item = Progress<P, T>>`, and the client awaits its result
+/// while it receives the updates with [await_with_progress].
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// use futures::Stream;
+///
+/// #[server(ImportRows)]
+/// async fn import_rows(
+///     rows: Vec<String>,
+/// ) -> Result<impl Stream<Item = Progress<usize, usize>>, ServerFnError> {
+///     Ok(progress_stream(move |progress| async move {
+///         for (imported, _row) in rows.iter().enumerate() {
+///             // insert the row into the database...
+///             progress.send(imported + 1);
+///         }
+///         Ok(rows.len())
+///     }))
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// let (imported, set_imported) = create_signal(cx, None);
+/// let import = create_action(cx, move |rows: &Vec<String>| {
+///     await_with_progress(import_rows(rows.clone()), set_imported)
+/// });
+/// # #[cfg(feature = "ssr")]
+/// # {
+/// # let rows = vec!["a".to_string(), "b".to_string()];
+/// # let total = futures::executor::block_on(await_with_progress(import_rows(rows), set_imported));
+/// # assert_eq!(total.unwrap(), 2);
+/// # assert_eq!(imported.get(), Some(2));
+/// # }
+/// # });
+/// ```
+pub fn progress_stream<P, T, E, Fu>(
+    job: impl FnOnce(ProgressSender<P>) -> Fu + 'static,
+) -> impl Stream<Item = Progress<P, T, E>> + 'static
+where
+    P: 'static,
+    T: 'static,
+    E: 'static,
+    Fu: Future<Output = Result<T, E>> + 'static,
+{
+    let (tx, rx) = mpsc::unbounded();
+    let job = Box::pin(job(ProgressSender { tx }));
+    let finish = |result: Result<T, E>| match result {
+        Ok(value) => Progress::Done(value),
+        Err(e) => Progress::Failed(e),
+    };
+
+    // the stream ends with the result, even if a sender is still held elsewhere
+    futures::stream::unfold(Some((job, rx)), move |state| async move {
+        let (mut job, mut rx) = state?;
+        let next = match future::select(rx.next(), job.as_mut()).await {
+            Either::Left((progress, _)) => Either::Left(progress),
+            Either::Right((result, _)) => Either::Right(result),
+        };
+        let events = match next {
+            Either::Left(Some(progress)) => {
+                return Some((vec![Progress::Update(progress)], Some((job, rx))))
+            }
+            Either::Left(None) => vec![finish(job.await)],
+            Either::Right(result) => {
+                // updates sent as the job finished still arrive before its result
+                let mut events = Vec::new();
+                while let Ok(progress) = rx.try_recv() {
+                    events.push(Progress::Update(progress));
+                }
+                events.push(finish(result));
+                events
+            }
+        };
+        Some((events, None))
+    })
+    .flat_map(futures::stream::iter)
+}
+
+/// Awaits the result of a server function that returns a [progress_stream], setting
+/// `progress` to each update as it arrives, e.g., to show a progress bar while the job runs.
+///
+/// Fails with a [ServerFnError::Deserialization] if the stream ends without a result.
+pub async fn await_with_progress<P, T, E, S>(
+    call: impl Future<Output = Result<S, E>>,
+    progress: WriteSignal<Option<P>>,
+) -> Result<T, E>
+where
+    P: 'static,
+    S: Stream<Item = Progress<P, T, E>>,
+    E: From<ServerFnError>,
+{
+    let mut events = Box::pin(call.await?);
+    while let Some(event) = events.next().await {
+        match event {
+            Progress::Update(value) => progress.set(Some(value)),
+            Progress::Done(value) => return Ok(value),
+            Progress::Failed(e) => return Err(e),
+        }
+    }
+    Err(ServerFnError::Deserialization(
+        "the stream ended before the result of the server function".to_string(),
+    )
+    .into())
+}
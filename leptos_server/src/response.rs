@@ -0,0 +1,122 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+/// The status code and headers that a server function has set for its response with
+/// [ResponseOptions].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseParts {
+    /// The status code that replaces the one the integration would send, if one was set.
+    pub status: Option<u16>,
+    /// The headers that were set, in the order they were set. A header with the same name as
+    /// one that the integration sends, like `Content-Type`, replaces it.
+    pub headers: Vec<(String, String)>,
+}
+
+impl ResponseParts {
+    /// How long a page rendered with these parts may be served from a cache before it's
+    /// rendered again, as set by the `s-maxage` of its `Cache-Control` header. Only a page
+    /// with a `200 OK` status that sets no cookies and has no `Vary` header can be cached, since
+    /// it's shared between users.
+    ///
+    /// ```
+    /// # use leptos_server::*;
+    /// let res = ResponseOptions::default();
+    /// res.insert_header("Cache-Control", "public, s-maxage=60");
+    /// assert_eq!(res.parts().revalidate(), Some(std::time::Duration::from_secs(60)));
+    ///
+    /// res.set_cookie("user=ferris");
+    /// assert_eq!(res.parts().revalidate(), None);
+    /// ```
+    pub fn revalidate(&self) -> Option<Duration> {
+        if !matches!(self.status, None | Some(200)) {
+            return None;
+        }
+        let header = |name: &'static str| {
+            self.headers
+                .iter()
+                .filter(move |(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        if header("Set-Cookie").next().is_some() || header("Vary").next().is_some() {
+            return None;
+        }
+        header("Cache-Control")
+            .flat_map(|value| value.split(','))
+            .find_map(|directive| directive.trim().strip_prefix("s-maxage="))
+            .and_then(|seconds| seconds.parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/// Lets a server function set the status code, headers and cookies of its response. The
+/// server integrations provide it as context to the [Scope](leptos_reactive::Scope) the
+/// function runs in, and apply it to the response once the function has returned, whether it
+/// succeeded or not.
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(Login)]
+/// async fn login(cx: Scope, user: String) -> Result<(), ServerFnError> {
+///     if let Some(res) = use_context::<ResponseOptions>(cx) {
+///         res.set_cookie(format!("user={user}; Path=/; HttpOnly"));
+///         res.insert_header("Cache-Control", "no-store");
+///     }
+///     Ok(())
+/// }
+///
+/// let res = ResponseOptions::default();
+/// res.set_status(201);
+/// res.set_cookie("user=ferris");
+/// res.insert_header("X-Request-Id", "1");
+/// res.insert_header("X-Request-Id", "2");
+/// assert_eq!(
+///     res.parts(),
+///     ResponseParts {
+///         status: Some(201),
+///         headers: vec![
+///             ("Set-Cookie".to_string(), "user=ferris".to_string()),
+///             ("X-Request-Id".to_string(), "2".to_string()),
+///         ],
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseOptions(Rc<RefCell<ResponseParts>>);
+
+impl ResponseOptions {
+    /// Sets the status code of the response.
+    pub fn set_status(&self, status: u16) {
+        self.0.borrow_mut().status = Some(status);
+    }
+
+    /// Sets a header, replacing any value that was set for it before.
+    pub fn insert_header(&self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        let mut parts = self.0.borrow_mut();
+        parts
+            .headers
+            .retain(|(header, _)| !header.eq_ignore_ascii_case(&name));
+        parts.headers.push((name, value.into()));
+    }
+
+    /// Adds a value for a header, keeping the values that were set for it before.
+    pub fn append_header(&self, name: impl Into<String>, value: impl Into<String>) {
+        self.0
+            .borrow_mut()
+            .headers
+            .push((name.into(), value.into()));
+    }
+
+    /// Sets a cookie with a `Set-Cookie` header, e.g., `"session=abc; Path=/; HttpOnly"`.
+    pub fn set_cookie(&self, cookie: impl Into<String>) {
+        self.append_header("Set-Cookie", cookie);
+    }
+
+    /// Returns the status code and headers that have been set, which the integrations apply
+    /// to the response.
+    pub fn parts(&self) -> ResponseParts {
+        self.0.borrow().clone()
+    }
+}
@@ -0,0 +1,121 @@
+use leptos_reactive::*;
+use serde::de::DeserializeOwned;
+use std::{fmt::Debug, future::Future};
+
+#[cfg(any(feature = "ssr", doc))]
+use crate::ServerFnError;
+
+/// Creates a signal that holds the latest value of a stream of
+/// [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+/// e.g., for a dashboard that stays up to date without polling.
+///
+/// The signal starts with the value `initial` resolves to, which is loaded like a
+/// [Resource](leptos_reactive::Resource): it's rendered on the server and sent to the client
+/// with the page, so that the page hydrates with it, and reading it inside a `<Suspense/>`
+/// waits for it. Once the page has loaded in the browser, an `EventSource` subscribes to `url`,
+/// and each event it receives replaces the value. The browser reconnects if the connection
+/// is lost, and the connection is closed when the [Scope] is disposed.
+///
+/// Each event should hold a value serialized as JSON, as sent by `leptos_axum::sse_response`
+/// or `leptos_actix::sse_response`. Events that can't be deserialized are passed to the hook
+/// set with [set_error_hook](leptos_reactive::set_error_hook) and skipped.
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// #[server(VisitorCount)]
+/// async fn visitor_count() -> Result<u32, ServerFnError> {
+///     Ok(42)
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// # if false { // resources can't load outside a browser or server runtime
+/// let visitors = create_sse_signal(cx, "/events/visitors", || async {
+///     visitor_count().await.unwrap_or_default()
+/// });
+///
+/// let label = move || match visitors.get() {
+///     Some(count) => format!("{count} visitors"),
+///     None => "Loading...".to_string(),
+/// };
+/// # }
+/// # });
+/// ```
+pub fn create_sse_signal<T, Fu>(
+    cx: Scope,
+    url: &str,
+    initial: impl Fn() -> Fu + 'static,
+) -> Signal<Option<T>>
+where
+    T: Debug + Clone + Serializable + DeserializeOwned + 'static,
+    Fu: Future<Output = T> + 'static,
+{
+    let initial = create_resource(cx, || (), move |_| initial());
+    let (latest, set_latest) = create_signal(cx, None);
+
+    #[cfg(not(feature = "ssr"))]
+    subscribe(cx, url, set_latest);
+    #[cfg(feature = "ssr")]
+    {
+        _ = (url, set_latest);
+    }
+
+    // the latest event wins over the value the page was rendered with
+    Signal::derive(cx, move || latest.get().or_else(|| initial.read()))
+}
+
+/// Opens an `EventSource` that sets the signal to each event it receives.
+#[cfg(not(feature = "ssr"))]
+fn subscribe<T>(cx: Scope, url: &str, set_latest: WriteSignal<Option<T>>)
+where
+    T: DeserializeOwned + 'static,
+{
+    use crate::ServerFnError;
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let source = match web_sys::EventSource::new(url) {
+        Ok(source) => source,
+        Err(e) => {
+            report_error(
+                &ServerFnError::Request(format!("{e:?}")),
+                ErrorLocation::ServerFn(url.to_string()),
+            );
+            return;
+        }
+    };
+
+    let url = url.to_string();
+    let on_message =
+        Closure::<dyn Fn(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+            let data = event.data().as_string().unwrap_or_default();
+            match serde_json::from_str(&data) {
+                Ok(value) => set_latest.set(Some(value)),
+                Err(e) => report_error(
+                    &ServerFnError::Deserialization(e.to_string()),
+                    ErrorLocation::ServerFn(url.clone()),
+                ),
+            }
+        });
+    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    on_cleanup(cx, move || {
+        source.close();
+        drop(on_message);
+    });
+}
+
+/// Formats a value as a server-sent event that [create_sse_signal] can read: its JSON, as the
+/// `data` of the event. The server integrations use this to send the values of the stream
+/// passed to their `sse_response`.
+/// ```
+/// # use leptos_server::*;
+/// # #[cfg(feature = "ssr")]
+/// assert_eq!(sse_event(&vec![1, 2]).unwrap(), "data: [1,2]\n\n");
+/// ```
+#[cfg(any(feature = "ssr", doc))]
+pub fn sse_event<T: serde::Serialize>(value: &T) -> Result<String, ServerFnError> {
+    let json =
+        serde_json::to_string(value).map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+    // JSON holds no newlines, so it always fits on the one `data` line
+    Ok(format!("data: {json}\n\n"))
+}
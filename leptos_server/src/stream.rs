@@ -0,0 +1,204 @@
+use futures::{Stream, StreamExt};
+use leptos_reactive::*;
+use std::{
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::ServerFnOutput;
+use serde::Serialize;
+
+#[cfg(any(feature = "ssr", doc))]
+use crate::{Codec, Encoding, Payload, ServerFnError};
+
+/// The values streamed by a server function that returns `impl Stream<Item = T>`.
+///
+/// The server sends each value to the client as soon as the stream yields it, serialized
+/// as JSON: as newline-delimited JSON (`application/x-ndjson`), or as server-sent events if
+/// the request accepts `text/event-stream`. On the client, the stream yields the values as
+/// they arrive, so that it can be iterated over with [StreamExt::next], or read from a signal
+/// created with [create_stream_signal].
+///
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use leptos_macro::server;
+/// use futures::{Stream, StreamExt};
+///
+/// #[server(CountTo)]
+/// async fn count_to(to: u32) -> Result<impl Stream<Item = u32>, ServerFnError> {
+///     Ok(futures::stream::iter(1..=to))
+/// }
+///
+/// async fn print_counts() -> Result<(), ServerFnError> {
+///     let mut counts = Box::pin(count_to(3).await?);
+///     while let Some(count) = counts.next().await {
+///         println!("{count}");
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct ServerStream<T> {
+    inner: Pin<Box<dyn Stream<Item = T>>>,
+}
+
+impl<T> ServerStream<T> {
+    /// Wraps the stream returned by the body of a server function.
+    pub fn new(stream: impl Stream<Item = T> + 'static) -> Self {
+        Self {
+            inner: Box::pin(stream),
+        }
+    }
+}
+
+impl<T> Stream for ServerStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Debug for ServerStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerStream").finish_non_exhaustive()
+    }
+}
+
+impl<T: Serialize + 'static> ServerFnOutput for ServerStream<T> {
+    #[cfg(any(feature = "ssr", doc))]
+    fn into_payload<C: Codec>(self, _encoding: Encoding) -> Result<Payload, ServerFnError> {
+        // values are always streamed as JSON, so that each one can be told apart from the next;
+        // a value that can't be serialized ends the stream, as it can't be skipped silently
+        Ok(Payload::Stream(ServerStream::new(
+            self.scan((), |_, value| {
+                futures::future::ready(serde_json::to_string(&value).ok())
+            }),
+        )))
+    }
+}
+
+#[cfg(any(feature = "ssr", doc))]
+impl ServerStream<String> {
+    /// Formats the serialized values of a [Payload::Stream] as the body of the response: as
+    /// server-sent events if `sse` is `true`, and as newline-delimited JSON otherwise.
+    pub fn into_body(self, sse: bool) -> ServerStream<String> {
+        ServerStream::new(self.map(move |json| {
+            if sse {
+                format!("data: {json}\n\n")
+            } else {
+                format!("{json}\n")
+            }
+        }))
+    }
+}
+
+/// Reads the newline-delimited JSON values that a server function streamed in the body of
+/// `resp`, ending the stream at the first value that can't be deserialized.
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn read_ndjson<T>(url: String, resp: gloo_net::http::Response) -> ServerStream<T>
+where
+    T: serde::de::DeserializeOwned + 'static,
+{
+    use leptos_dom::js_sys::{Reflect, Uint8Array};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let reader = resp.body().map(|body| {
+        body.get_reader()
+            .unchecked_into::<web_sys::ReadableStreamDefaultReader>()
+    });
+    let chunks = futures::stream::unfold(reader, |reader| async move {
+        let reader = reader?;
+        let result = JsFuture::from(reader.read()).await.ok()?;
+        let done = Reflect::get(&result, &"done".into()).ok()?.as_bool()?;
+        if done {
+            return None;
+        }
+        let value = Reflect::get(&result, &"value".into()).ok()?;
+        Some((Uint8Array::new(&value).to_vec(), Some(reader)))
+    });
+
+    // a value may be split across chunks, so only complete lines are deserialized
+    let mut buffer = Vec::new();
+    let lines = chunks.flat_map(move |chunk| {
+        buffer.extend(chunk);
+        let mut lines = Vec::new();
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                lines.push(line);
+            }
+        }
+        futures::stream::iter(lines)
+    });
+
+    ServerStream::new(lines.scan((), move |_, line| {
+        futures::future::ready(match serde_json::from_slice(&line) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                report_error(
+                    &crate::ServerFnError::Deserialization(e.to_string()),
+                    ErrorLocation::ServerFn(url.clone()),
+                );
+                None
+            }
+        })
+    }))
+}
+
+/// Creates a signal that holds the values streamed by a server function, or any other stream,
+/// as they arrive. It holds the error instead if the stream can't be opened, e.g., if the
+/// server function fails before it starts streaming.
+///
+/// The stream is read until it ends, or until the [Scope] is disposed.
+/// ```
+/// # use leptos_reactive::*;
+/// # use leptos_server::*;
+/// # use futures::Stream;
+/// // stands in for a `#[server]` function that streams its values
+/// async fn count_to(to: u32) -> Result<impl Stream<Item = u32>, ServerFnError> {
+///     Ok(futures::stream::iter(1..=to))
+/// }
+///
+/// # run_scope(create_runtime(), |cx| {
+/// # if cfg!(not(any(feature = "csr", feature = "hydrate", feature = "ssr"))) {
+/// let counts = create_stream_signal(cx, count_to(3));
+///
+/// let total = move || counts.with(|counts| match counts {
+///     Ok(counts) => counts.iter().sum(),
+///     Err(_) => 0,
+/// });
+/// # assert_eq!(total(), 6);
+/// # }
+/// # });
+/// ```
+pub fn create_stream_signal<T, S, E>(
+    cx: Scope,
+    stream: impl Future<Output = Result<S, E>> + 'static,
+) -> ReadSignal<Result<Vec<T>, E>>
+where
+    T: 'static,
+    S: Stream<Item = T> + 'static,
+    E: 'static,
+{
+    let (values, set_values) = create_signal(cx, Ok(Vec::new()));
+    spawn_local_scoped(cx, async move {
+        match stream.await {
+            Ok(stream) => {
+                let mut stream = Box::pin(stream);
+                while let Some(value) = stream.next().await {
+                    set_values.update(|values| {
+                        if let Ok(values) = values {
+                            values.push(value);
+                        }
+                    });
+                }
+            }
+            Err(e) => set_values.set(Err(e)),
+        }
+    });
+    values
+}
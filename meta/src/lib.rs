@@ -7,7 +7,9 @@
 //!
 //! Document metadata is updated automatically when running in the browser. For server-side
 //! rendering, after the component tree is rendered to HTML, [MetaContext::dehydrate] can generate
-//! HTML that should be injected into the `<head>` of the HTML document being rendered.
+//! HTML that should be injected into the `<head>` of the HTML document being rendered. When the
+//! page is streamed, [MetaContext::head_patch] generates a script that adds whatever has been
+//! set by the parts of the page rendered after the `<head>` was sent.
 //!
 //! ```
 //! use leptos::*;
@@ -36,14 +38,18 @@
 //!
 //! ```
 
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+use std::{cell::RefCell, collections::HashSet};
 use std::{fmt::Debug, rc::Rc};
 
 use leptos::{leptos_dom::debug_warn, *};
 
 mod meta_tags;
+mod runtime_config;
 mod stylesheet;
 mod title;
 pub use meta_tags::*;
+pub use runtime_config::*;
 pub use stylesheet::*;
 pub use title::*;
 
@@ -55,7 +61,20 @@ pub use title::*;
 pub struct MetaContext {
     pub(crate) title: TitleContext,
     pub(crate) stylesheets: StylesheetContext,
-    pub(crate) meta_tags: MetaTagsContext
+    pub(crate) meta_tags: MetaTagsContext,
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    sent: Rc<RefCell<Option<SentHead>>>,
+}
+
+/// What has been sent in the `<head>` of a page being rendered on the server, or in the patches
+/// sent after it.
+#[cfg(not(any(feature = "csr", feature = "hydrate")))]
+#[derive(Debug, Default)]
+struct SentHead {
+    title: Option<String>,
+    stylesheets: HashSet<String>,
+    meta_tags: HashSet<usize>,
+    patches: usize,
 }
 
 /// Returns the current [MetaContext].
@@ -114,20 +133,94 @@ impl MetaContext {
     /// # }
     /// ```
     pub fn dehydrate(&self) -> String {
+        let mut sent = SentHead::default();
+        let tags = self.unsent_tags(&mut sent);
+        *self.sent.borrow_mut() = Some(sent);
+        tags
+    }
+
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    /// Converts the metadata that has been set since the head was sent with
+    /// [dehydrate](MetaContext::dehydrate), e.g., by a component under a
+    /// [Suspense](leptos::Suspense) that was streamed after the `<head>`, into a `<template>` and a
+    /// small inline script that moves it into the `<head>`, so that it isn't dropped. A new
+    /// `<title>` replaces the one that was sent.
+    ///
+    /// Returns `None` if nothing new has been set, or if the head hasn't been sent yet. The script
+    /// is marked with the given [Nonce], if any.
+    ///
+    /// ```
+    /// use leptos::*;
+    /// use leptos_meta::*;
+    ///
+    /// # #[cfg(not(any(feature = "csr", feature = "hydrate")))] {
+    /// run_scope(create_runtime(), |cx| {
+    ///   provide_context(cx, MetaContext::new());
+    ///
+    ///   _ = view! { cx, <main><Title text="Loading"/></main> };
+    ///   assert_eq!(use_head(cx).dehydrate(), "<title>Loading</title>");
+    ///   assert_eq!(use_head(cx).head_patch(None), None);
+    ///
+    ///   // e.g., once the data of a post has loaded
+    ///   _ = view! { cx, <main><Title text="My post"/><Meta name="author" content="Alice"/></main> };
+    ///   let patch = use_head(cx).head_patch(None).unwrap();
+    ///   assert!(patch.starts_with(r#"<template id="leptos-head-0"><title>My post</title><meta name="author" content="Alice" data-leptos-meta="1">"#));
+    ///   assert_eq!(use_head(cx).head_patch(None), None);
+    /// });
+    /// # }
+    /// ```
+    pub fn head_patch(&self, nonce: Option<&Nonce>) -> Option<String> {
+        let mut sent = self.sent.borrow_mut();
+        let sent = sent.as_mut()?;
+        let tags = self.unsent_tags(sent);
+        if tags.is_empty() {
+            return None;
+        }
+
+        let id = format!("leptos-head-{}", sent.patches);
+        sent.patches += 1;
+        let nonce = nonce.map(|nonce| nonce.to_attribute()).unwrap_or_default();
+        Some(format!(
+            r#"<template id="{id}">{tags}</template><script{nonce}>(function() {{
+                var tpl = document.getElementById("{id}");
+                Array.prototype.slice.call(tpl.content.children).forEach(function(el) {{
+                    if(el.nodeName === "TITLE") document.title = el.textContent;
+                    else document.head.appendChild(el);
+                }});
+                tpl.remove();
+            }})()</script>"#
+        ))
+    }
+
+    /// The HTML of the metadata that isn't in `sent`, which is then added to it.
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    fn unsent_tags(&self, sent: &mut SentHead) -> String {
         let mut tags = String::new();
 
         // Title
-        if let Some(title) = self.title.as_string() {
-            tags.push_str("<title>");
-            tags.push_str(&title);
-            tags.push_str("</title>");
+        let title = self.title.as_string();
+        if title != sent.title {
+            if let Some(title) = &title {
+                tags.push_str("<title>");
+                tags.push_str(title);
+                tags.push_str("</title>");
+            }
+            sent.title = title;
         }
 
         // Stylesheets
-        tags.push_str(&self.stylesheets.as_string());
+        for (href, tag) in self.stylesheets.tags() {
+            if sent.stylesheets.insert(href) {
+                tags.push_str(&tag);
+            }
+        }
 
         // Meta tags
-        tags.push_str(&self.meta_tags.as_string());
+        for (id, tag) in self.meta_tags.tags() {
+            if sent.meta_tags.insert(id) {
+                tags.push_str(&tag);
+            }
+        }
 
         tags
     }
@@ -41,6 +41,11 @@ enum MetaTag {
 impl MetaTagsContext {
     /// Converts the set of `<meta>` elements into an HTML string that can be injected into the `<head>`.
     pub fn as_string(&self) -> String {
+        self.tags().into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    /// The HTML of each `<meta>` element, along with its id.
+    pub(crate) fn tags(&self) -> Vec<(usize, String)> {
         self.els
             .borrow()
             .iter()
@@ -48,7 +53,7 @@ impl MetaTagsContext {
 				tag.as_ref().map(|tag| {
 					let id = id.0;
 
-					match tag {
+					let tag = match tag {
 						MetaTag::Charset(charset) => format!(r#"<meta charset="{}" data-leptos-meta="{id}">"#, charset.get()),
 						MetaTag::HttpEquiv { http_equiv, content } => {
 							if let Some(content) = &content {
@@ -58,7 +63,8 @@ impl MetaTagsContext {
 							}
 						},
 						MetaTag::Name { name, content } => format!(r#"<meta name="{}" content="{}" data-leptos-meta="{id}">"#, name.get(), content.get()),
-					}
+					};
+					(id, tag)
 				})
 			})
             .collect()
@@ -0,0 +1,47 @@
+use cfg_if::cfg_if;
+use leptos::*;
+
+/// Returns the [RuntimeConfig] the server sent along with this page, so deploy-time settings
+/// like an API base URL can be read without recompiling the WASM bundle.
+///
+/// On the server, the integrations provide the config built from
+/// [RenderOptions::runtime_config](leptos::RenderOptions::runtime_config) and embed it in the
+/// `<head>`. In the browser, it is read from that `<script>` tag the first time this is called
+/// and then provided to the current [Scope](leptos::Scope). An empty config is returned if
+/// there is none, e.g., in a purely client-side rendered app.
+///
+/// ```
+/// use leptos::*;
+/// use leptos_meta::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> Element {
+///   let config = use_runtime_config(cx);
+///   let api = config.get("api_base_url").unwrap_or("/api").to_string();
+///
+///   view! { cx,
+///     <main>
+///       <a href=api>"API"</a>
+///     </main>
+///   }
+/// }
+/// ```
+pub fn use_runtime_config(cx: Scope) -> RuntimeConfig {
+    if let Some(config) = use_context::<RuntimeConfig>(cx) {
+        return config;
+    }
+
+    cfg_if! {
+        if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            let config = document()
+                .get_element_by_id(RUNTIME_CONFIG_ELEMENT_ID)
+                .and_then(|el| el.text_content())
+                .and_then(|json| RuntimeConfig::from_json(&json).ok())
+                .unwrap_or_default();
+            provide_context(cx, config.clone());
+            config
+        } else {
+            RuntimeConfig::default()
+        }
+    }
+}
@@ -13,10 +13,20 @@ pub struct StylesheetContext {
 impl StylesheetContext {
     /// Converts the set of stylesheets into an HTML string that can be injected into the `<head>`.
     pub fn as_string(&self) -> String {
+        self.tags().into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    /// The `<link>` of each stylesheet, along with its `href`.
+    pub(crate) fn tags(&self) -> Vec<(String, String)> {
         self.els
             .borrow()
-            .iter()
-            .map(|(href, _)| format!(r#"<link rel="stylesheet" href="{href}">"#))
+            .keys()
+            .map(|href| {
+                (
+                    href.clone(),
+                    format!(r#"<link rel="stylesheet" href="{href}">"#),
+                )
+            })
             .collect()
     }
 }
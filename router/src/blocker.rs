@@ -0,0 +1,327 @@
+use std::{cell::RefCell, future::Future, rc::Rc};
+
+use futures::channel::oneshot;
+use leptos::*;
+
+use crate::{use_after_navigate, use_before_navigate, use_router, Navigation, NavigationDecision};
+
+/// Whether a [NavigationBlocker] is holding a navigation back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockerState {
+    /// No navigation is blocked.
+    Unblocked,
+    /// A navigation is blocked, and waits for [NavigationBlocker::proceed] or
+    /// [NavigationBlocker::reset].
+    Blocked(Navigation),
+    /// A blocked navigation was let through, and goes on until the route navigated to has
+    /// loaded its resources, as the [Transition] of the router does.
+    Proceeding(Navigation),
+}
+
+/// Holds back navigations away from the current page while a condition holds, e.g., while a
+/// form has unsaved changes, until the user decides to leave or to stay. Created with
+/// [use_navigation_blocker].
+#[derive(Clone)]
+pub struct NavigationBlocker {
+    state: ReadSignal<BlockerState>,
+    set_state: WriteSignal<BlockerState>,
+    decision: Rc<RefCell<Option<oneshot::Sender<NavigationDecision>>>>,
+}
+
+impl NavigationBlocker {
+    /// Whether a navigation is blocked, e.g., to show a dialog that asks whether to leave.
+    pub fn state(&self) -> ReadSignal<BlockerState> {
+        self.state
+    }
+
+    /// Lets the blocked navigation go on.
+    pub fn proceed(&self) {
+        if let BlockerState::Blocked(navigation) = self.state.get_untracked() {
+            self.set_state.set(BlockerState::Proceeding(navigation));
+            self.decide(NavigationDecision::Continue);
+        }
+    }
+
+    /// Cancels the blocked navigation, staying on the current page.
+    pub fn reset(&self) {
+        if self.decide(NavigationDecision::Cancel) {
+            self.set_state.set(BlockerState::Unblocked);
+        }
+    }
+
+    /// Holds a navigation back until it's decided on, if it's `blocked`.
+    fn block(
+        &self,
+        navigation: Navigation,
+        blocked: bool,
+    ) -> impl Future<Output = NavigationDecision> {
+        let blocker = self.clone();
+        async move {
+            // a navigation that was let through, but didn't arrive, is over
+            if let BlockerState::Proceeding(_) = blocker.state.get_untracked() {
+                blocker.set_state.set(BlockerState::Unblocked);
+            }
+            if !blocked {
+                return NavigationDecision::Continue;
+            }
+            blocker.reset();
+            let (tx, rx) = oneshot::channel();
+            *blocker.decision.borrow_mut() = Some(tx);
+            blocker.set_state.set(BlockerState::Blocked(navigation));
+            // the navigation is cancelled if the blocker is gone before it's decided on
+            rx.await.unwrap_or(NavigationDecision::Cancel)
+        }
+    }
+
+    fn decide(&self, decision: NavigationDecision) -> bool {
+        match self.decision.borrow_mut().take() {
+            Some(tx) => {
+                _ = tx.send(decision);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for NavigationBlocker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NavigationBlocker")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Blocks every navigation started by the router, like a [BeforeNavigate](crate::BeforeNavigate)
+/// hook, for as long as the current [Scope] lives, whenever `when` returns `true`. The
+/// navigation waits until [NavigationBlocker::proceed] or [NavigationBlocker::reset] is called,
+/// e.g., by the buttons of a dialog shown while the [BlockerState] is
+/// [Blocked](BlockerState::Blocked). A navigation that is still blocked when another one is
+/// started is cancelled.
+///
+/// Closing the tab or leaving the app by its URL is blocked with the browser's own dialog,
+/// through the [`beforeunload`](https://developer.mozilla.org/en-US/docs/Web/API/Window/beforeunload_event)
+/// event. Going back or forward in the browser's history can't be blocked. To ask with the
+/// browser's confirm dialog instead of a UI of your own, see [use_navigation_prompt].
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Editor(cx: Scope) -> Element {
+///     let (dirty, set_dirty) = create_signal(cx, false);
+///     let blocker = use_navigation_blocker(cx, move || dirty.get());
+///     let state = blocker.state();
+///     let is_blocked = move || matches!(state.get(), BlockerState::Blocked(_));
+///
+///     view! {
+///         cx,
+///         <div>
+///             <textarea on:input=move |_| set_dirty.set(true)/>
+///             <dialog open=is_blocked>
+///                 <p>"You have unsaved changes."</p>
+///                 <button on:click={
+///                     let blocker = blocker.clone();
+///                     move |_| blocker.proceed()
+///                 }>"Leave"</button>
+///                 <button on:click=move |_| blocker.reset()>"Stay"</button>
+///             </dialog>
+///         </div>
+///     }
+/// }
+/// ```
+pub fn use_navigation_blocker(cx: Scope, when: impl Fn() -> bool + 'static) -> NavigationBlocker {
+    let (state, set_state) = create_signal(cx, BlockerState::Unblocked);
+    let blocker = NavigationBlocker {
+        state,
+        set_state,
+        decision: Default::default(),
+    };
+    let when = Rc::new(when);
+
+    use_before_navigate(cx, {
+        let blocker = blocker.clone();
+        let when = Rc::clone(&when);
+        move |navigation: Navigation| blocker.block(navigation, when())
+    });
+
+    // a navigation that was let through is over once its route has loaded
+    let (arrived, set_arrived) = create_signal(cx, false);
+    use_after_navigate(cx, move |_: Navigation| {
+        if let BlockerState::Proceeding(_) = state.get_untracked() {
+            set_arrived.set(true);
+        }
+    });
+    let pending = use_router(cx).transition().pending();
+    create_effect(cx, move |_| {
+        if arrived.get() && !pending.get() {
+            set_arrived.set(false);
+            set_state.set(BlockerState::Unblocked);
+        }
+    });
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    {
+        use leptos::wasm_bindgen::{closure::Closure, JsCast};
+
+        let listener = Closure::<dyn Fn(web_sys::BeforeUnloadEvent)>::new(
+            move |ev: web_sys::BeforeUnloadEvent| {
+                if when() {
+                    ev.prevent_default();
+                    // older browsers only show the dialog if a return value is set
+                    ev.set_return_value("");
+                }
+            },
+        )
+        .into_js_value();
+        _ = leptos_dom::window()
+            .add_event_listener_with_callback("beforeunload", listener.unchecked_ref());
+        on_cleanup(cx, move || {
+            _ = leptos_dom::window()
+                .remove_event_listener_with_callback("beforeunload", listener.unchecked_ref());
+        });
+    }
+
+    blocker
+}
+
+/// Blocks navigations like [use_navigation_blocker] whenever `when` returns `true`, asking
+/// whether to leave with the browser's confirm dialog, which shows `message`. Browsers show a
+/// message of their own when the tab is closed instead.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Editor(cx: Scope) -> Element {
+///     let (dirty, set_dirty) = create_signal(cx, false);
+///     use_navigation_prompt(cx, "Leave without saving your changes?", move || dirty.get());
+///
+///     view! { cx, <textarea on:input=move |_| set_dirty.set(true)/> }
+/// }
+/// ```
+pub fn use_navigation_prompt(
+    cx: Scope,
+    message: impl Into<String>,
+    when: impl Fn() -> bool + 'static,
+) {
+    let message = message.into();
+    let blocker = use_navigation_blocker(cx, when);
+    create_effect(cx, move |_| {
+        if let BlockerState::Blocked(_) = blocker.state().get() {
+            let leave = leptos_dom::window()
+                .confirm_with_message(&message)
+                .unwrap_or(false);
+            if leave {
+                blocker.proceed();
+            } else {
+                blocker.reset();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    fn blocker(cx: Scope) -> NavigationBlocker {
+        let (state, set_state) = create_signal(cx, BlockerState::Unblocked);
+        NavigationBlocker {
+            state,
+            set_state,
+            decision: Default::default(),
+        }
+    }
+
+    fn navigation(to: &str) -> Navigation {
+        Navigation {
+            from: "/edit".to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn lets_navigations_through_unless_blocked() {
+        run_scope(create_runtime(), |cx| {
+            let blocker = blocker(cx);
+            let decision = blocker.block(navigation("/"), false).now_or_never();
+            assert_eq!(decision, Some(NavigationDecision::Continue));
+            assert_eq!(blocker.state().get(), BlockerState::Unblocked);
+        });
+    }
+
+    #[test]
+    fn proceeds_with_a_blocked_navigation() {
+        run_scope(create_runtime(), |cx| {
+            let blocker = blocker(cx);
+            let mut pending = Box::pin(blocker.block(navigation("/"), true));
+            assert_eq!((&mut pending).now_or_never(), None);
+            assert_eq!(
+                blocker.state().get(),
+                BlockerState::Blocked(navigation("/"))
+            );
+
+            blocker.proceed();
+            assert_eq!(pending.now_or_never(), Some(NavigationDecision::Continue));
+            assert_eq!(
+                blocker.state().get(),
+                BlockerState::Proceeding(navigation("/"))
+            );
+
+            // the next navigation ends the one that was let through
+            let decision = blocker.block(navigation("/about"), false).now_or_never();
+            assert_eq!(decision, Some(NavigationDecision::Continue));
+            assert_eq!(blocker.state().get(), BlockerState::Unblocked);
+        });
+    }
+
+    #[test]
+    fn resets_a_blocked_navigation() {
+        run_scope(create_runtime(), |cx| {
+            let blocker = blocker(cx);
+            let mut pending = Box::pin(blocker.block(navigation("/"), true));
+            assert_eq!((&mut pending).now_or_never(), None);
+
+            blocker.reset();
+            assert_eq!(pending.now_or_never(), Some(NavigationDecision::Cancel));
+            assert_eq!(blocker.state().get(), BlockerState::Unblocked);
+
+            // there's nothing left to decide on
+            blocker.proceed();
+            assert_eq!(blocker.state().get(), BlockerState::Unblocked);
+        });
+    }
+
+    #[test]
+    fn cancels_a_blocked_navigation_when_another_starts() {
+        run_scope(create_runtime(), |cx| {
+            let blocker = blocker(cx);
+            let mut first = Box::pin(blocker.block(navigation("/"), true));
+            assert_eq!((&mut first).now_or_never(), None);
+
+            let mut second = Box::pin(blocker.block(navigation("/about"), true));
+            assert_eq!((&mut second).now_or_never(), None);
+            assert_eq!(first.now_or_never(), Some(NavigationDecision::Cancel));
+            assert_eq!(
+                blocker.state().get(),
+                BlockerState::Blocked(navigation("/about"))
+            );
+
+            blocker.proceed();
+            assert_eq!(second.now_or_never(), Some(NavigationDecision::Continue));
+        });
+    }
+
+    #[test]
+    fn cancels_a_navigation_that_is_never_decided_on() {
+        run_scope(create_runtime(), |cx| {
+            let blocker = blocker(cx);
+            let mut pending = Box::pin(blocker.block(navigation("/"), true));
+            assert_eq!((&mut pending).now_or_never(), None);
+
+            // e.g., the page with the blocker is gone
+            blocker.decision.borrow_mut().take();
+            assert_eq!(pending.now_or_never(), Some(NavigationDecision::Cancel));
+        });
+    }
+}
@@ -0,0 +1,254 @@
+use leptos::*;
+use typed_builder::TypedBuilder;
+
+use crate::{matching::RouteDefinition, outlet_child, routes_root};
+
+/// Props for the [AnimatedRoutes] component, which is a [Routes](crate::Routes) that animates
+/// the change from one route to the next.
+#[derive(TypedBuilder)]
+pub struct AnimatedRoutesProps {
+    #[builder(default, setter(strip_option))]
+    base: Option<String>,
+    /// Sets the `class` attribute of the `<div>` that holds the routes.
+    #[builder(default, setter(strip_option, into))]
+    class: Option<String>,
+    /// The CSS class added to the view of the route that is being navigated to, until its
+    /// `animation` ends.
+    #[builder(default, setter(strip_option))]
+    intro: Option<&'static str>,
+    /// The CSS class added to the view of the route that is being navigated away from, which
+    /// stays mounted until its `animation` ends.
+    #[builder(default, setter(strip_option))]
+    outro: Option<&'static str>,
+    /// If `true`, the change is animated with the
+    /// [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API)
+    /// instead, in browsers that support it; `intro` and `outro` are used in those that don't.
+    #[builder(default)]
+    view_transition: bool,
+    children: Box<dyn Fn() -> Vec<RouteDefinition>>,
+}
+
+/// Contains route definitions and manages the actual routing process, like
+/// [Routes](crate::Routes), and animates the change from one route to the next.
+///
+/// Each view is shown in a `<div>` of its own. When the route changes, the `intro` class is
+/// added to the new view, and the `outro` class to the old one, which is kept mounted, though
+/// no longer reactive, until the `animation` of the class ends; a class without an
+/// `animation` removes it right away. The views are shown side by side until then, so the
+/// classes will usually position them on top of each other.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// // .slide-in { animation: slide-in 0.2s; }
+/// // .fade-out { position: absolute; animation: fade-out 0.2s; }
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router>
+///             <main>
+///                 <AnimatedRoutes intro="slide-in" outro="fade-out">
+///                     <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                     <Route path="about" element=|cx| view! { cx, <p>"About"</p> }/>
+///                 </AnimatedRoutes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+#[allow(non_snake_case)]
+pub fn AnimatedRoutes(cx: Scope, props: AnimatedRoutesProps) -> Element {
+    let root = routes_root(cx, props.base, props.children);
+    let el = animated(
+        cx,
+        move || root.get(),
+        Animation {
+            class: props.class,
+            intro: props.intro,
+            outro: props.outro,
+            view_transition: props.view_transition,
+        },
+    );
+
+    cfg_if::cfg_if! {
+        if #[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))] {
+            crate::mark_outlet(cx, el)
+        } else {
+            el
+        }
+    }
+}
+
+/// Props for the [AnimatedOutlet] component, which is an [Outlet](crate::Outlet) that animates
+/// the change from one nested route to the next.
+#[derive(TypedBuilder)]
+pub struct AnimatedOutletProps {
+    /// Sets the `class` attribute of the `<div>` that holds the nested route.
+    #[builder(default, setter(strip_option, into))]
+    class: Option<String>,
+    /// The CSS class added to the view of the nested route that is being navigated to, until
+    /// its `animation` ends.
+    #[builder(default, setter(strip_option))]
+    intro: Option<&'static str>,
+    /// The CSS class added to the view of the nested route that is being navigated away from,
+    /// which stays mounted until its `animation` ends.
+    #[builder(default, setter(strip_option))]
+    outro: Option<&'static str>,
+    /// If `true`, the change is animated with the
+    /// [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API)
+    /// instead, in browsers that support it; `intro` and `outro` are used in those that don't.
+    #[builder(default)]
+    view_transition: bool,
+}
+
+/// Displays the child route nested in a parent route, like [Outlet](crate::Outlet), and
+/// animates the change from one child route to the next, like [AnimatedRoutes].
+#[allow(non_snake_case)]
+pub fn AnimatedOutlet(cx: Scope, props: AnimatedOutletProps) -> Element {
+    let outlet = outlet_child(cx);
+    animated(
+        cx,
+        move || outlet.get(),
+        Animation {
+            class: props.class,
+            intro: props.intro,
+            outro: props.outro,
+            view_transition: props.view_transition,
+        },
+    )
+}
+
+#[derive(Clone)]
+#[cfg_attr(not(any(feature = "csr", feature = "hydrate")), allow(dead_code))]
+struct Animation {
+    class: Option<String>,
+    intro: Option<&'static str>,
+    outro: Option<&'static str>,
+    view_transition: bool,
+}
+
+/// Shows the view returned by `child` in a `<div>`, animating each change of it.
+fn animated(
+    cx: Scope,
+    child: impl Fn() -> Option<Child> + 'static,
+    animation: Animation,
+) -> Element {
+    let class = animation.class.clone();
+    let wrapper = view! { cx, <div class=class><div>{child()}</div></div> };
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    {
+        use leptos::wasm_bindgen::closure::Closure;
+        use std::{cell::RefCell, rc::Rc};
+
+        let current = Rc::new(RefCell::new(wrapper.first_element_child()));
+        create_effect(cx, {
+            let wrapper = wrapper.clone();
+            move |prev: Option<Option<Child>>| {
+                let next = child();
+                // the first view was rendered along with the wrapper
+                if prev.is_none() || prev.as_ref() == Some(&next) {
+                    return next;
+                }
+
+                let swap = {
+                    let wrapper = wrapper.clone();
+                    let current = Rc::clone(&current);
+                    let next = next.clone();
+                    move |animate: bool| {
+                        let page = view! { cx, <div>{next}</div> };
+                        if let Some(old) = current.borrow_mut().take() {
+                            if animate {
+                                animate_out(old, animation.outro);
+                            } else {
+                                old.remove();
+                            }
+                        }
+                        if animate {
+                            animate_in(&page, animation.intro);
+                        }
+                        _ = wrapper.append_child(&page);
+                        *current.borrow_mut() = Some(page);
+                    }
+                };
+                match view_transition().filter(|_| animation.view_transition) {
+                    Some(start) => {
+                        let update = Closure::once_into_js(move || swap(false));
+                        _ = start.call1(&leptos_dom::document(), &update);
+                    }
+                    None => swap(true),
+                }
+                next
+            }
+        });
+    }
+
+    wrapper
+}
+
+/// Adds the `intro` class to the view that is entering, and removes it when its animation ends.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn animate_in(page: &web_sys::Element, intro: Option<&'static str>) {
+    if let Some(intro) = intro {
+        _ = page.class_list().add_1(intro);
+        on_animation_end(page, move |page| {
+            _ = page.class_list().remove_1(intro);
+        });
+    }
+}
+
+/// Adds the `outro` class to the view that is leaving, and removes it when its animation ends,
+/// or right away if the class has no animation.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn animate_out(page: web_sys::Element, outro: Option<&'static str>) {
+    if let Some(outro) = outro {
+        _ = page.class_list().add_1(outro);
+        let animated = leptos_dom::window()
+            .get_computed_style(&page)
+            .ok()
+            .flatten()
+            .and_then(|style| style.get_property_value("animation-name").ok())
+            .map(|name| !name.is_empty() && name != "none")
+            .unwrap_or(false);
+        if animated {
+            on_animation_end(&page, |page| page.remove());
+            return;
+        }
+    }
+    page.remove();
+}
+
+/// Calls `f` when an animation of the element itself, rather than of one of its children, ends.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn on_animation_end(el: &web_sys::Element, f: impl Fn(&web_sys::Element) + 'static) {
+    use leptos::wasm_bindgen::{closure::Closure, JsCast};
+
+    let target = el.clone();
+    let listener = Closure::<dyn Fn(web_sys::Event)>::new(move |ev: web_sys::Event| {
+        let is_target = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .map(|t| t == target)
+            .unwrap_or(false);
+        if is_target {
+            f(&target);
+        }
+    })
+    .into_js_value();
+    _ = el.add_event_listener_with_callback("animationend", listener.unchecked_ref());
+}
+
+/// `document.startViewTransition`, if the browser supports view transitions.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn view_transition() -> Option<js_sys::Function> {
+    use leptos::wasm_bindgen::{JsCast, JsValue};
+
+    js_sys::Reflect::get(
+        &leptos_dom::document(),
+        &JsValue::from_str("startViewTransition"),
+    )
+    .ok()
+    .and_then(|start| start.dyn_into::<js_sys::Function>().ok())
+}
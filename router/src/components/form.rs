@@ -88,12 +88,16 @@ where
 
             let on_response = on_response.clone();
             spawn_local(async move {
-                let res = gloo_net::http::Request::post(&action)
-                    .header("Accept", "application/json")
-                    .header("Content-Type", &enctype)
-                    .body(params)
-                    .send()
-                    .await;
+                let req =
+                    gloo_net::http::Request::post(&action).header("Accept", "application/json");
+                // the browser sets the Content-Type of multipart/form-data itself, as it
+                // includes the boundary between the fields
+                let req = if enctype.starts_with("multipart/form-data") {
+                    req.body(form_data)
+                } else {
+                    req.header("Content-Type", &enctype).body(params)
+                };
+                let res = req.send().await;
                 match res {
                     Err(e) => {
                         log::error!("<Form/> error while POSTing: {e:#?}");
@@ -173,13 +177,19 @@ where
 #[derive(TypedBuilder)]
 pub struct ActionFormProps<I, O>
 where
-    I: 'static,
+    I: ServerFn + 'static,
     O: 'static,
 {
     /// The action from which to build the form. This should include a URL, which can be generated
     /// by default using [create_server_action](leptos_server::create_server_action) or added
     /// manually using [leptos_server::Action::using_server_fn].
-    pub action: Action<I, Result<O, ServerFnError>>,
+    pub action: Action<I, Result<O, I::Error>>,
+    /// [`enctype`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/form#attr-enctype)
+    /// is the MIME type of the form submission. Set it to `multipart/form-data` to upload
+    /// files to a server function that takes a [MultipartData](leptos_server::MultipartData)
+    /// or [UploadedFile](leptos_server::UploadedFile).
+    #[builder(default, setter(strip_option, into))]
+    pub enctype: Option<String>,
     /// Component children; should include the HTML of the form elements.
     pub children: Box<dyn Fn() -> Vec<Element>>,
 }
@@ -202,8 +212,16 @@ where
     let version = props.action.version;
     let value = props.action.value;
     let input = props.action.input;
+    let enctype = props.enctype;
+    // files can't be read into the input until they've been uploaded
+    let is_upload = enctype
+        .as_deref()
+        .map_or(false, |enctype| enctype.starts_with("multipart/form-data"));
 
     let on_form_data = Rc::new(move |form_data: &web_sys::FormData| {
+        if is_upload {
+            return;
+        }
         let data = action_input_from_form_data(form_data);
         match data {
             Ok(data) => input.set(Some(data)),
@@ -223,13 +241,20 @@ where
                         json.as_string().unwrap(),
                         std::any::type_name::<O>()
                     );
-                    match O::from_json(
-                        &json.as_string().expect("couldn't get String from JsString"),
-                    ) {
+                    let json = json.as_string().expect("couldn't get String from JsString");
+                    // an error status means the body holds the error the server function returned
+                    if !resp.ok() {
+                        value.set(Some(Err(server_fn_error_from_json(
+                            &json,
+                            &resp.status_text(),
+                        ))));
+                        return;
+                    }
+                    match O::from_json(&json) {
                         Ok(res) => value.set(Some(Ok(res))),
-                        Err(e) => {
-                            value.set(Some(Err(ServerFnError::Deserialization(e.to_string()))))
-                        }
+                        Err(e) => value.set(Some(Err(I::Error::from(
+                            ServerFnError::Deserialization(e.to_string()),
+                        )))),
                     }
                 }
                 Err(e) => log::error!("{e:?}"),
@@ -245,7 +270,12 @@ where
             .on_form_data(on_form_data)
             .on_response(on_response)
             .method("post")
-            .children(props.children)
+            .enctype(enctype.unwrap_or_else(|| "application/x-www-form-urlencoded".to_string()))
+            .children(Box::new(move || {
+                let mut children = (props.children)();
+                children.push(csrf_input(cx));
+                children
+            }))
             .build(),
     )
 }
@@ -257,13 +287,13 @@ where
 #[derive(TypedBuilder)]
 pub struct MultiActionFormProps<I, O>
 where
-    I: 'static,
+    I: ServerFn + 'static,
     O: 'static,
 {
     /// The action from which to build the form. This should include a URL, which can be generated
     /// by default using [create_server_action](leptos_server::create_server_action) or added
     /// manually using [leptos_server::Action::using_server_fn].
-    pub action: MultiAction<I, Result<O, ServerFnError>>,
+    pub action: MultiAction<I, Result<O, I::Error>>,
     /// Component children; should include the HTML of the form elements.
     pub children: Box<dyn Fn() -> Vec<Element>>,
 }
@@ -303,7 +333,8 @@ where
         }
     };
 
-    let children = (props.children)();
+    let mut children = (props.children)();
+    children.push(csrf_input(cx));
 
     cfg_if! {
         if #[cfg(feature = "stable")] {
@@ -322,6 +353,16 @@ where
     }
 }
 
+/// A hidden field that sends the [CsrfToken] along with the form, so that the server function
+/// accepts it even when the form is submitted before the app has loaded.
+fn csrf_input(cx: Scope) -> Element {
+    let token = use_context::<CsrfToken>(cx)
+        .or_else(CsrfToken::from_document)
+        .map(|token| token.as_str().to_string())
+        .unwrap_or_default();
+    view! { cx, <input type="hidden" name=CSRF_FIELD value=token/> }
+}
+
 fn extract_form_attributes(
     ev: &web_sys::Event,
 ) -> (web_sys::HtmlFormElement, String, String, String) {
@@ -6,7 +6,7 @@ use typed_builder::TypedBuilder;
 #[cfg(any(feature = "csr", feature = "hydrate"))]
 use wasm_bindgen::JsCast;
 
-use crate::{use_location, use_resolved_path, State};
+use crate::{use_location, use_resolved_path, RouterMode, State};
 
 /// Describes a value that is either a static or a reactive URL, i.e.,
 /// a [String], a [&str], or a reactive `Fn() -> String`.
@@ -62,6 +62,14 @@ where
     /// will skip this page.)
     #[builder(default)]
     pub replace: bool,
+    /// If `true`, the window won't be scrolled to the top, or to the element the `#hash` of the
+    /// link points to, after navigating.
+    #[builder(default)]
+    pub noscroll: bool,
+    /// The locale the link points to, e.g., to link to the current page in other languages,
+    /// if the [Router](crate::Router) has `locales`. Defaults to the current locale.
+    #[builder(default, setter(strip_option))]
+    pub locale: Option<&'static str>,
     /// Sets the `class` attribute on the underlying `<a>` tag, making it easier to style.
     #[builder(default, setter(strip_option, into))]
     pub class: Option<MaybeSignal<String>>,
@@ -81,7 +89,21 @@ where
     H: TextProp + 'static,
 {
     let location = use_location(cx);
+    let router = crate::use_router(cx);
     let href = use_resolved_path(cx, move || props.href.to_value()());
+    // the link stays in the current locale, unless it points to another one
+    let href = create_memo(cx, {
+        let router = router.clone();
+        let locale = props.locale;
+        move |_| href.get().map(|href| router.localize(&href, locale))
+    });
+    // in hash mode, the link points to the hash, so that it works without client-side routing
+    let hash_mode = router.inner.mode == RouterMode::Hash;
+    let to_href = move || {
+        href.get()
+            .map(|href| if hash_mode { format!("#{href}") } else { href })
+            .unwrap_or_default()
+    };
     let is_active = create_memo(cx, move |_| match href.get() {
         None => false,
 
@@ -109,11 +131,20 @@ where
 
     cfg_if! {
         if #[cfg(any(feature = "csr", feature = "hydrate"))] {
+            // start loading the lazy views of the route before the link is clicked
+            let preload = move |_| {
+                if let Some(href) = href.get() {
+                    router.preload(&href);
+                }
+            };
+
             view! { cx,
                 <a
-                    href=move || href.get().unwrap_or_default()
+                    href=to_href
+                    on:mouseenter=preload
                     prop:state={props.state.map(|s| s.to_js_value())}
                     prop:replace={props.replace}
+                    noscroll=props.noscroll.then_some("")
                     aria-current=move || if is_active.get() { Some("page") } else { None }
                     class=move || class.as_ref().map(|class| class.get())
                 >
@@ -123,7 +154,7 @@ where
         } else {
             view! { cx,
                 <a
-                    href=move || href().unwrap_or_default()
+                    href=to_href
                     aria-current=move || if is_active() { Some("page") } else { None }
                     class=move || class.as_ref().map(|class| class.get())
                 >
@@ -1,3 +1,4 @@
+mod animated;
 mod form;
 mod link;
 mod outlet;
@@ -5,6 +6,7 @@ mod route;
 mod router;
 mod routes;
 
+pub use animated::*;
 pub use form::*;
 pub use link::*;
 pub use outlet::*;
@@ -8,6 +8,13 @@ use leptos::*;
 
 #[component]
 pub fn Outlet(cx: Scope) -> Child {
+    let outlet = outlet_child(cx);
+    (move || outlet.get()).into_child(cx)
+}
+
+/// The view of the child route nested in the current route, if any, which changes only when
+/// the child route does.
+pub(crate) fn outlet_child(cx: Scope) -> ReadSignal<Option<Child>> {
     let route = use_route(cx);
     let is_showing = Rc::new(RefCell::new(None));
     let (outlet, set_outlet) = create_signal(cx, None);
@@ -16,19 +23,18 @@ pub fn Outlet(cx: Scope) -> Child {
         let child = route.child();
         match (route.child(), &is_showing_val) {
             (None, _) => {
+                *is_showing.borrow_mut() = None;
                 set_outlet.set(None);
             }
-            (Some(child), Some(path))
-                if Some(child.original_path().to_string()) == is_showing_val =>
-            {
+            (Some(child), Some(showing)) if child == *showing => {
                 // do nothing: we don't need to rerender the component, because it's the same
             }
             (Some(child), _) => {
-                *is_showing.borrow_mut() = Some(child.original_path().to_string());
+                *is_showing.borrow_mut() = Some(child.clone());
                 provide_context(child.cx(), child.clone());
                 set_outlet.set(Some(child.outlet().into_child(cx)))
             }
         }
     });
-    (move || outlet.get()).into_child(cx)
+    outlet
 }
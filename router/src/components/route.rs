@@ -1,11 +1,11 @@
-use std::{borrow::Cow, rc::Rc};
+use std::{any::Any, borrow::Cow, fmt::Debug, future::Future, rc::Rc};
 
 use leptos::*;
 use typed_builder::TypedBuilder;
 
 use crate::{
     matching::{resolve_path, PathMatch, RouteDefinition, RouteMatch},
-    ParamsMap, RouterContext,
+    AfterNavigate, BeforeNavigate, Lazy, ParamsMap, RouteMeta, RouterContext,
 };
 
 /// Properties that can be passed to a [Route] component, which describes
@@ -19,7 +19,11 @@ where
 {
     /// The path fragment that this route should match. This can be static (`users`),
     /// include a parameter (`:id`) or an optional parameter (`:id?`), or match a
-    /// wildcard (`user/*any`).
+    /// wildcard (`user/*any`). A parameter can be followed by a regular expression its value
+    /// must match (`:id(\d+)`), which can't contain `/` or `?`, so that `users/:id(\d+)`
+    /// matches `users/5` but not `users/settings`; a route with an invalid pattern never
+    /// matches. The [PATH](crate::TypedRoute::PATH) of a [TypedRoute](crate::TypedRoute)
+    /// checks its params against the fields of a struct at compile time.
     pub path: &'static str,
     /// The view that should be shown when this route is matched. This can be any function
     /// that takes a [Scope] and returns an [Element] (like `|cx| view! { cx, <p>"Show this"</p> })`
@@ -28,6 +32,95 @@ where
     /// `children` may be empty or include nested routes.
     #[builder(default, setter(strip_option))]
     pub children: Option<Box<dyn Fn() -> Vec<RouteDefinition>>>,
+    /// Loads the data of the route, which the element and its children read with
+    /// [use_loader](crate::use_loader). See [Loader].
+    #[builder(default, setter(strip_option, into))]
+    pub loader: Option<Loader>,
+    /// Loads the view of the route the first time it's needed, showing `element` until it's
+    /// loaded. See [Lazy].
+    #[builder(default, setter(strip_option, into))]
+    pub lazy: Option<Lazy>,
+    /// The status code of the response when this route is rendered on the server, e.g., `404`
+    /// for a catch-all route that shows a "not found" page, or `410` for one that's gone. The
+    /// server integrations apply it, with any other [ResponseOptions] set while rendering the
+    /// app; the status of a nested route replaces that of its parent.
+    /// ```
+    /// # use leptos::*;
+    /// # use leptos_router::*;
+    /// # fn app(cx: Scope) -> Element {
+    /// view! {
+    ///     cx,
+    ///     <div>
+    ///         <Router>
+    ///             <main>
+    ///                 <Routes>
+    ///                     <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+    ///                     <Route path="*any" status=404 element=|cx| view! { cx, <p>"Not found"</p> }/>
+    ///                 </Routes>
+    ///             </main>
+    ///         </Router>
+    ///     </div>
+    /// }
+    /// # }
+    /// ```
+    #[builder(default, setter(strip_option))]
+    pub status: Option<u16>,
+    /// The number of seconds the server integrations may serve this route from their cache,
+    /// when rendered with incremental static regeneration, before rendering it again in the
+    /// background. It's sent as the `s-maxage` of the `Cache-Control` header, which a CDN
+    /// honors as well; that of a nested route replaces that of its parent.
+    #[builder(default, setter(strip_option))]
+    pub revalidate: Option<u64>,
+    /// The title of the page this route shows, as read by [use_route_meta](crate::use_route_meta).
+    #[builder(default, setter(strip_option))]
+    pub title: Option<&'static str>,
+    /// The label of this route in the breadcrumbs read by [use_route_meta](crate::use_route_meta).
+    #[builder(default, setter(strip_option))]
+    pub breadcrumb: Option<&'static str>,
+    /// The roles a user needs to see this route, as read by [use_route_meta](crate::use_route_meta).
+    #[builder(default)]
+    pub roles: &'static [&'static str],
+    /// Whether the element stays mounted, with its state, as long as this route is matched,
+    /// e.g., while navigating between its child routes or to other values of its params. If
+    /// `false`, it's mounted again, along with its children, whenever the part of the URL it
+    /// matches changes. Defaults to `true`.
+    /// ```
+    /// # use leptos::*;
+    /// # use leptos_router::*;
+    /// # fn app(cx: Scope) -> Element {
+    /// view! {
+    ///     cx,
+    ///     <div>
+    ///         <Router>
+    ///             <main>
+    ///                 <Routes>
+    ///                     // the form of one user is reset when another user is opened
+    ///                     <Route
+    ///                         path="users/:id"
+    ///                         keep_alive=false
+    ///                         element=|cx| view! { cx, <div><input/><Outlet/></div> }
+    ///                     >
+    ///                         <Route path="" element=|cx| view! { cx, <p>"Profile"</p> }/>
+    ///                         <Route path="posts" element=|cx| view! { cx, <p>"Posts"</p> }/>
+    ///                     </Route>
+    ///                 </Routes>
+    ///             </main>
+    ///         </Router>
+    ///     </div>
+    /// }
+    /// # }
+    /// ```
+    #[builder(default = true)]
+    pub keep_alive: bool,
+    /// A hook that runs before every navigation to a URL that matches this route, after the
+    /// hooks of the [Router](crate::Router) and of its parent routes, and can cancel or
+    /// redirect it. See [BeforeNavigate].
+    #[builder(default, setter(strip_option, into))]
+    pub before_navigate: Option<BeforeNavigate>,
+    /// A hook that runs after every navigation to a URL that matches this route.
+    /// See [AfterNavigate].
+    #[builder(default, setter(strip_option, into))]
+    pub after_navigate: Option<AfterNavigate>,
 }
 
 /// Describes a portion of the nested layout of the app, specifying the route it should match,
@@ -41,7 +134,103 @@ where
     RouteDefinition {
         path: props.path,
         children: props.children.map(|c| c()).unwrap_or_default(),
-        element: Rc::new(move |cx| (props.element)(cx).into_child(cx)),
+        element: match props.lazy.clone() {
+            Some(lazy) => {
+                let fallback: Rc<dyn Fn(Scope) -> Child> =
+                    Rc::new(move |cx| (props.element)(cx).into_child(cx));
+                Rc::new(move |cx| lazy.view(cx, Rc::clone(&fallback)))
+            }
+            None => Rc::new(move |cx| (props.element)(cx).into_child(cx)),
+        },
+        loader: props.loader,
+        lazy: props.lazy,
+        status: props.status,
+        revalidate: props.revalidate,
+        meta: RouteMeta {
+            title: props.title,
+            breadcrumb: props.breadcrumb,
+            roles: props.roles,
+        },
+        keep_alive: props.keep_alive,
+        before_navigate: props.before_navigate,
+        after_navigate: props.after_navigate,
+    }
+}
+
+/// Loads the data of a [Route], so that data fetching is declared alongside the route rather
+/// than inside its component. Any `async` function that takes the [ParamsMap] of the route can
+/// be passed as its `loader`.
+///
+/// The loader is started as soon as the route is matched, before its element is rendered, as
+/// a [Resource] that reloads whenever the params change. It's loaded on the server during
+/// server-side rendering, and streamed to the client with the page, like any other resource;
+/// on the client, it's loaded during the navigation to the route, which waits for it if it's
+/// read inside a [Suspense] or [Transition].
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// async fn load_user(params: ParamsMap) -> String {
+///     format!("User {}", params.get("id").cloned().unwrap_or_default())
+/// }
+///
+/// #[component]
+/// fn User(cx: Scope) -> Element {
+///     let user = use_loader::<String>(cx);
+///     view! {
+///         cx,
+///         <div>
+///             <Suspense fallback=|| "Loading...">
+///                 {move || user.read().map(|user| view! { cx, <p>{user}</p> })}
+///             </Suspense>
+///         </div>
+///     }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router>
+///             <main>
+///                 <Routes>
+///                     <Route path="users/:id" loader=load_user element=|cx| view! { cx, <User/> }/>
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Loader(Rc<dyn Fn(Scope, Memo<ParamsMap>) -> Rc<dyn Any>>);
+
+impl Loader {
+    /// Starts loading the data of a route in its scope.
+    pub(crate) fn load(&self, cx: Scope, params: Memo<ParamsMap>) -> Rc<dyn Any> {
+        (self.0)(cx, params)
+    }
+}
+
+impl<F, Fu, T> From<F> for Loader
+where
+    F: Fn(ParamsMap) -> Fu + 'static,
+    Fu: Future<Output = T> + 'static,
+    T: Debug + Clone + Serializable + 'static,
+{
+    fn from(fetcher: F) -> Self {
+        let fetcher = Rc::new(fetcher);
+        Self(Rc::new(move |cx, params| {
+            let fetcher = Rc::clone(&fetcher);
+            let resource = create_resource(cx, move || params.get(), move |params| fetcher(params));
+            Rc::new(resource)
+        }))
+    }
+}
+
+impl Debug for Loader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Loader").finish_non_exhaustive()
     }
 }
 
@@ -62,12 +251,32 @@ impl RouteContext {
         let base = base.path();
         let RouteMatch { path_match, route } = matcher()?;
         let PathMatch { path, .. } = path_match;
-        let RouteDefinition { element, .. } = route.key;
+        let RouteDefinition {
+            element,
+            loader,
+            status,
+            revalidate,
+            ..
+        } = route.key;
         let params = create_memo(cx, move |_| {
             matcher()
                 .map(|matched| matched.path_match.params)
                 .unwrap_or_default()
         });
+        // on the server, the integration sends the status and caching of the route
+        if let Some(res) = use_context::<ResponseOptions>(cx) {
+            if let Some(status) = status {
+                res.set_status(status);
+            }
+            if let Some(revalidate) = revalidate {
+                res.insert_header(
+                    "Cache-Control",
+                    format!("public, s-maxage={revalidate}, stale-while-revalidate"),
+                );
+            }
+        }
+        // the data starts loading before the element is rendered
+        let loader = loader.map(|loader| loader.load(cx, params));
 
         Some(Self {
             inner: Rc::new(RouteContextInner {
@@ -77,6 +286,7 @@ impl RouteContext {
                 path,
                 original_path: route.original_path.to_string(),
                 params,
+                loader,
                 outlet: Box::new(move || Some(element(cx))),
             }),
         })
@@ -119,6 +329,7 @@ impl RouteContext {
                 path: path.to_string(),
                 original_path: path.to_string(),
                 params: create_memo(cx, |_| ParamsMap::new()),
+                loader: None,
                 outlet: Box::new(move || fallback.map(|f| f().into_child(cx))),
             }),
         }
@@ -129,6 +340,11 @@ impl RouteContext {
         resolve_path(&self.inner.base_path, to, Some(&self.inner.path))
     }
 
+    /// The data of the route, as started by its [Loader], if it has one.
+    pub(crate) fn loader(&self) -> Option<Rc<dyn Any>> {
+        self.inner.loader.clone()
+    }
+
     /// The nested child route, if any.
     pub fn child(&self) -> Option<RouteContext> {
         (self.inner.child)()
@@ -147,6 +363,7 @@ pub(crate) struct RouteContextInner {
     pub(crate) path: String,
     pub(crate) original_path: String,
     pub(crate) params: Memo<ParamsMap>,
+    pub(crate) loader: Option<Rc<dyn Any>>,
     pub(crate) outlet: Box<dyn Fn() -> Option<Child>>,
 }
 
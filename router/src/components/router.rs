@@ -8,12 +8,10 @@ use typed_builder::TypedBuilder;
 #[cfg(not(feature = "ssr"))]
 use wasm_bindgen::JsCast;
 
-#[cfg(feature = "transition")]
-use leptos_reactive::use_transition;
-
 use crate::{
-    create_location, matching::resolve_path, History, Location, LocationChange, RouteContext,
-    RouterIntegrationContext, State,
+    create_location, detect_locale, localize_path, matching::resolve_path, query_pairs,
+    split_locale, AfterNavigate, BeforeNavigate, History, Location, LocationChange, Navigation,
+    NavigationDecision, NavigationHooks, RouteContext, RouterIntegrationContext, State,
 };
 
 #[cfg(not(feature = "ssr"))]
@@ -28,6 +26,23 @@ pub struct RouterProps {
     #[builder(default, setter(strip_option))]
     /// A fallback that should be shown if no route is matched.
     pub fallback: Option<fn() -> Element>,
+    /// Whether the path is kept in the path of the URL or in its hash. Defaults to
+    /// [RouterMode::History].
+    #[builder(default)]
+    pub mode: RouterMode,
+    /// A hook that runs before every navigation started by the router, and can cancel or
+    /// redirect it. See [BeforeNavigate].
+    #[builder(default, setter(strip_option, into))]
+    pub before_navigate: Option<BeforeNavigate>,
+    /// A hook that runs after every change of the URL. See [AfterNavigate].
+    #[builder(default, setter(strip_option, into))]
+    pub after_navigate: Option<AfterNavigate>,
+    /// The locales the app is served in, e.g., `&["en", "de"]`. Each page is served under a
+    /// prefix for each locale, e.g., `/de/about`, and routes are matched without it. A URL
+    /// without a prefix is shown in the locale the user prefers, or else the first one. See
+    /// [use_locale](crate::use_locale).
+    #[builder(default)]
+    pub locales: &'static [&'static str],
     /// The `<Router/>` should usually wrap your whole page. It can contain
     /// any elements, and should include a [Routes](crate::Routes) component somewhere
     /// to define and display [Route](crate::Route)s.
@@ -39,12 +54,56 @@ pub struct RouterProps {
 #[allow(non_snake_case)]
 pub fn Router(cx: Scope, props: RouterProps) -> impl IntoChild {
     // create a new RouterContext and provide it to every component beneath the router
-    let router = RouterContext::new(cx, props.base, props.fallback);
+    let router = RouterContext::new(cx, props.base, props.fallback, props.mode, props.locales);
+    if let Some(hook) = props.before_navigate {
+        router.inner.hooks.add_before(hook);
+    }
+    if let Some(hook) = props.after_navigate {
+        router.inner.hooks.add_after(hook);
+    }
     provide_context(cx, router);
 
     props.children
 }
 
+/// Where a [Router] keeps the path of the current route in the URL.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         // links and the address bar read `index.html#/about`
+///         <Router mode=RouterMode::Hash>
+///             <nav>
+///                 <A href="/about">"About"</A>
+///             </nav>
+///             <main>
+///                 <Routes>
+///                     <Route path="about" element=|cx| view! { cx, <p>"About"</p> }/>
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RouterMode {
+    /// In the path of the URL, e.g., `/users/5`, using the
+    /// [History API](https://developer.mozilla.org/en-US/docs/Web/API/History). This needs
+    /// the server to serve the app at every path it routes.
+    #[default]
+    History,
+    /// In the hash of the URL, e.g., `index.html#/users/5`, for hosts that can only serve
+    /// files as they are, like a static file host or a `file://` URL in Electron or Tauri.
+    /// Links made with [A](crate::A) point to the hash. The server never sees the hash, so
+    /// this is meant for client-side rendering.
+    Hash,
+}
+
 /// Context type that contains information about the current router state.
 #[derive(Debug, Clone)]
 pub struct RouterContext {
@@ -53,6 +112,11 @@ pub struct RouterContext {
 pub(crate) struct RouterContextInner {
     pub location: Location,
     pub base: RouteContext,
+    pub(crate) mode: RouterMode,
+    pub(crate) locales: &'static [&'static str],
+    pub(crate) locale: Memo<&'static str>,
+    /// The path routes are matched against, which is the current path without its locale prefix.
+    pub(crate) route_path: Memo<String>,
     base_path: String,
     history: Box<dyn History>,
     cx: Scope,
@@ -61,19 +125,30 @@ pub(crate) struct RouterContextInner {
     referrers: Rc<RefCell<Vec<LocationChange>>>,
     state: ReadSignal<State>,
     set_state: WriteSignal<State>,
+    transition: Transition,
+    pub(crate) hooks: NavigationHooks,
+    pub(crate) preloaders: RefCell<Vec<Preloader>>,
 }
 
+/// Starts loading the lazy views of the routes a path matches.
+pub(crate) type Preloader = Rc<dyn Fn(&str)>;
+
 impl std::fmt::Debug for RouterContextInner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RouterContextInner")
             .field("location", &self.location)
             .field("base", &self.base)
+            .field("mode", &self.mode)
+            .field("locales", &self.locales)
+            .field("locale", &self.locale)
             .field("cx", &self.cx)
             .field("reference", &self.reference)
             .field("set_reference", &self.set_reference)
             .field("referrers", &self.referrers)
             .field("state", &self.state)
             .field("set_state", &self.set_state)
+            .field("transition", &self.transition)
+            .field("hooks", &self.hooks)
             .finish()
     }
 }
@@ -83,11 +158,16 @@ impl RouterContext {
         cx: Scope,
         base: Option<&'static str>,
         fallback: Option<fn() -> Element>,
+        mode: RouterMode,
+        locales: &'static [&'static str],
     ) -> Self {
         cfg_if! {
             if #[cfg(any(feature = "csr", feature = "hydrate"))] {
                 let history = use_context::<RouterIntegrationContext>(cx)
-                    .unwrap_or_else(|| RouterIntegrationContext(Rc::new(crate::BrowserIntegration {})));
+                    .unwrap_or_else(|| match mode {
+                        RouterMode::History => RouterIntegrationContext(Rc::new(crate::BrowserIntegration {})),
+                        RouterMode::Hash => RouterIntegrationContext(Rc::new(crate::HashIntegration {})),
+                    });
             } else {
                 let history = use_context::<RouterIntegrationContext>(cx).expect("You must call provide_context::<RouterIntegrationContext>(cx, ...) somewhere above the <Router/>.\n\n \
                 If you are using `leptos_actix` or `leptos_axum` and seeing this message, it is a bug: \n \
@@ -123,8 +203,7 @@ impl RouterContext {
         let (state, set_state) = create_signal(cx, source.with(|s| s.state.clone()));
 
         // we'll use this transition to wait for async resources to load when navigating to a new route
-        #[cfg(feature = "transition")]
-        let transition = use_transition(cx);
+        let transition = create_transition(cx);
 
         // Each field of `location` reactively represents a different part of the current location
         let location = create_location(cx, reference, state);
@@ -132,6 +211,32 @@ impl RouterContext {
 
         // Create base route with fallback element
         let base_path = base_path.unwrap_or_default();
+
+        // the locale prefix is split off the path before it's matched
+        let locale_path = create_memo(cx, {
+            let base_path = base_path.to_string();
+            move |_| split_locale(&base_path, locales, &location.pathname.get())
+        });
+        let detected_locale = if locales.is_empty() {
+            ""
+        } else {
+            detect_locale(cx, locales)
+        };
+        let locale = create_memo(cx, move |_| {
+            locale_path.with(|(locale, _)| locale.unwrap_or(detected_locale))
+        });
+        let route_path = create_memo(cx, move |_| locale_path.with(|(_, path)| path.clone()));
+        if !locales.is_empty() {
+            // the server renders a URL without a locale prefix in the locale of the user
+            if let Some(res) = use_context::<ResponseOptions>(cx) {
+                if locale_path.with(|(locale, _)| locale.is_none()) {
+                    res.append_header("Vary", "Accept-Language, Cookie");
+                }
+            }
+            // the locale of the user is kept for the URLs without a locale prefix
+            #[cfg(not(feature = "ssr"))]
+            create_effect(cx, move |_| crate::store_locale(locale.get()));
+        }
         let base = RouteContext::base(cx, &base_path, fallback);
 
         // Every time the History gives us a new location,
@@ -143,14 +248,20 @@ impl RouterContext {
             let LocationChange { value, state, .. } = source.get();
             cx.untrack(move || {
                 if value != reference.get() {
-                    set_reference.update(move |r| *r = value);
-                    set_state.update(move |s| *s = state);
+                    transition.start(move || {
+                        set_reference.update(move |r| *r = value);
+                        set_state.update(move |s| *s = state);
+                    });
                 }
             });
         });
 
         let inner = Rc::new(RouterContextInner {
             base_path: base_path.into_owned(),
+            mode,
+            locales,
+            locale,
+            route_path,
             location,
             base,
             history: Box::new(history),
@@ -160,6 +271,27 @@ impl RouterContext {
             referrers,
             state,
             set_state,
+            transition,
+            hooks: NavigationHooks::default(),
+            preloaders: Default::default(),
+        });
+
+        // run the AfterNavigate hooks whenever the URL changes, however it was changed
+        create_effect(cx, {
+            let inner = Rc::clone(&inner);
+            move |prev: Option<String>| {
+                let to = reference.get();
+                if let Some(from) = prev {
+                    if from != to {
+                        let navigation = Navigation {
+                            from,
+                            to: to.clone(),
+                        };
+                        cx.untrack(|| inner.hooks.run_after(navigation));
+                    }
+                }
+                to
+            }
         });
 
         // handle all click events on anchor tags
@@ -178,17 +310,71 @@ impl RouterContext {
         self.inner.location.pathname
     }
 
+    /// The current path without its locale prefix, which routes are matched against.
+    pub(crate) fn route_path(&self) -> Memo<String> {
+        self.inner.route_path
+    }
+
+    /// The given path with the locale prefix of the given locale, or of the current one, in the
+    /// place of its own, if the router has `locales`.
+    pub(crate) fn localize(&self, path: &str, locale: Option<&str>) -> String {
+        self.inner.localize(path, locale)
+    }
+
     /// The [RouteContext] of the base route.
     pub fn base(&self) -> RouteContext {
         self.inner.base.clone()
     }
+
+    /// The [Transition] that every navigation is started in, so that
+    /// [Transition::pending] is `true` while the resources of the route that is being
+    /// navigated to are loading, e.g., to show a progress bar while the previous route
+    /// remains visible.
+    pub fn transition(&self) -> Transition {
+        self.inner.transition
+    }
+
+    /// Starts loading the [Lazy](crate::Lazy) views of the routes that the given path matches,
+    /// so that they're ready, or closer to it, when it's navigated to. An [A](crate::A) does
+    /// this when the pointer is over it.
+    pub fn preload(&self, path: &str) {
+        let preloaders = self.inner.preloaders.borrow().clone();
+        for preload in preloaders {
+            preload(path);
+        }
+    }
 }
 
 impl RouterContextInner {
+    fn localize(&self, path: &str, locale: Option<&str>) -> String {
+        let locale = locale.unwrap_or_else(|| self.locale.get());
+        localize_path(&self.base_path, self.locales, path, locale)
+    }
+
     pub(crate) fn navigate_from_route(
         self: Rc<Self>,
         to: &str,
         options: &NavigateOptions,
+    ) -> Result<(), NavigationError> {
+        self.navigate_with_redirects(to, options, true, 0)
+    }
+
+    /// Follows a change of the URL that has already happened, e.g., going back in the
+    /// browser's history, which the [BeforeNavigate] hooks can't cancel, so they aren't run.
+    pub(crate) fn navigate_from_history(
+        self: Rc<Self>,
+        to: &str,
+        options: &NavigateOptions,
+    ) -> Result<(), NavigationError> {
+        self.navigate_with_redirects(to, options, false, 0)
+    }
+
+    fn navigate_with_redirects(
+        self: Rc<Self>,
+        to: &str,
+        options: &NavigateOptions,
+        guarded: bool,
+        redirects: usize,
     ) -> Result<(), NavigationError> {
         let cx = self.cx;
         let this = Rc::clone(&self);
@@ -203,60 +389,53 @@ impl RouterContextInner {
             match resolved_to {
                 None => Err(NavigationError::NotRoutable(to.to_string())),
                 Some(resolved_to) => {
-                    let resolved_to = resolved_to.to_string();
-                    if self.referrers.borrow().len() > 32 {
+                    // a path resolved like a link stays in the current locale
+                    let resolved_to = if options.resolve {
+                        this.localize(&resolved_to, None)
+                    } else {
+                        resolved_to.to_string()
+                    };
+                    let resolved_to = options
+                        .query
+                        .apply(&resolved_to, &this.location.search.get());
+                    if self.referrers.borrow().len() > 32 || redirects > 32 {
                         return Err(NavigationError::MaxRedirects);
                     }
 
                     if resolved_to != this.reference.get() || options.state != (this.state).get() {
-                        if cfg!(feature = "server") {
-                            // TODO server out
-                            self.history.navigate(&LocationChange {
-                                value: resolved_to,
-                                replace: options.replace,
-                                scroll: options.scroll,
-                                state: options.state.clone(),
-                            });
-                        } else {
-                            {
-                                self.referrers.borrow_mut().push(LocationChange {
-                                    value: self.reference.get(),
-                                    replace: options.replace,
-                                    scroll: options.scroll,
-                                    state: self.state.get(),
-                                });
-                            }
-                            let len = self.referrers.borrow().len();
-
-                            #[cfg(feature = "transition")]
-                            let transition = use_transition(self.cx);
-                            //transition.start({
-                            let set_reference = self.set_reference;
-                            let set_state = self.set_state;
-                            let referrers = self.referrers.clone();
-                            let this = Rc::clone(&self);
-                            //move || {
-
-                            let resolved = resolved_to.to_string();
-                            let state = options.state.clone();
-                            queue_microtask(move || {
-                                set_reference.update(move |r| *r = resolved);
-
-                                set_state.update({
-                                    let next_state = state.clone();
-                                    move |state| *state = next_state
-                                });
-                                if referrers.borrow().len() == len {
-                                    this.navigate_end(LocationChange {
-                                        value: resolved_to.to_string(),
-                                        replace: false,
-                                        scroll: true,
-                                        state,
-                                    })
-                                    //}
+                        if guarded && self.hooks.has_before() {
+                            // the navigation waits for the BeforeNavigate hooks to decide
+                            let navigation = Navigation {
+                                from: this.reference.get(),
+                                to: resolved_to,
+                            };
+                            let options = options.clone();
+                            spawn_local(async move {
+                                let decision = this.hooks.run_before(navigation.clone()).await;
+                                let res = match decision {
+                                    NavigationDecision::Continue => {
+                                        this.navigate_to(navigation.to, &options);
+                                        Ok(())
+                                    }
+                                    NavigationDecision::Cancel => Ok(()),
+                                    NavigationDecision::Redirect(to) => this
+                                        .navigate_with_redirects(
+                                            &to,
+                                            &NavigateOptions {
+                                                resolve: false,
+                                                query: NavigateQuery::Replace,
+                                                ..options
+                                            },
+                                            true,
+                                            redirects + 1,
+                                        ),
+                                };
+                                if let Err(e) = res {
+                                    log::error!("{e:#?}");
                                 }
                             });
-                            //});
+                        } else {
+                            self.navigate_to(resolved_to, options);
                         }
                     }
 
@@ -266,6 +445,58 @@ impl RouterContextInner {
         })
     }
 
+    fn navigate_to(self: Rc<Self>, resolved_to: String, options: &NavigateOptions) {
+        // the page is scrolled back to where it was left when it's gone back to
+        #[cfg(not(feature = "ssr"))]
+        crate::save_scroll_position(&self.reference.get());
+
+        if cfg!(feature = "server") {
+            // TODO server out
+            self.history.navigate(&LocationChange {
+                value: resolved_to,
+                replace: options.replace,
+                scroll: options.scroll,
+                state: options.state.clone(),
+            });
+        } else {
+            {
+                self.referrers.borrow_mut().push(LocationChange {
+                    value: self.reference.get(),
+                    replace: options.replace,
+                    scroll: options.scroll,
+                    state: self.state.get(),
+                });
+            }
+            let len = self.referrers.borrow().len();
+
+            let transition = self.transition;
+            let set_reference = self.set_reference;
+            let set_state = self.set_state;
+            let referrers = self.referrers.clone();
+            let this = Rc::clone(&self);
+            let resolved = resolved_to.to_string();
+            let state = options.state.clone();
+            queue_microtask(move || {
+                transition.start(|| {
+                    set_reference.update(move |r| *r = resolved);
+
+                    set_state.update({
+                        let next_state = state.clone();
+                        move |state| *state = next_state
+                    });
+                });
+                if referrers.borrow().len() == len {
+                    this.navigate_end(LocationChange {
+                        value: resolved_to.to_string(),
+                        replace: false,
+                        scroll: true,
+                        state,
+                    })
+                }
+            });
+        }
+    }
+
     pub(crate) fn navigate_end(self: Rc<Self>, mut next: LocationChange) {
         let first = self.referrers.borrow().get(0).cloned();
         if let Some(first) = first {
@@ -321,7 +552,14 @@ impl RouterContextInner {
             }
 
             let url = Url::try_from(href.as_str()).unwrap();
-            let path_name = unescape(&url.pathname);
+            let path_name = match self.mode {
+                RouterMode::History => unescape(&url.pathname),
+                // links to routes point to the hash, and any other hash is left to the browser
+                RouterMode::Hash => match url.hash.strip_prefix('#') {
+                    Some(path) if path.starts_with('/') => unescape(path),
+                    _ => return,
+                },
+            };
 
             // let browser handle this event if it leaves our domain
             // or our base path
@@ -335,9 +573,19 @@ impl RouterContextInner {
                 return;
             }
 
-            let to = path_name + &unescape(&url.search) + &unescape(&url.hash);
-            // TODO "state" is set as a prop, not an attribute
-            let state = a.get_attribute("state"); // TODO state
+            let to = match self.mode {
+                RouterMode::History => path_name + &unescape(&url.search) + &unescape(&url.hash),
+                RouterMode::Hash => path_name,
+            };
+            // an <A/> sets `state` and `replace` as properties of the element
+            let prop = |name: &str| {
+                js_sys::Reflect::get(&a, &wasm_bindgen::JsValue::from_str(name))
+                    .ok()
+                    .filter(|value| !value.is_undefined() && !value.is_null())
+            };
+            let state = State(prop("state"));
+            let replace =
+                a.has_attribute("replace") || prop("replace").map_or(false, |v| v.is_truthy());
 
             ev.prevent_default();
 
@@ -345,10 +593,10 @@ impl RouterContextInner {
                 &to,
                 &NavigateOptions {
                     resolve: false,
-                    // TODO "replace" is set as a prop, not an attribute
-                    replace: a.has_attribute("replace"),
+                    replace,
                     scroll: !a.has_attribute("noscroll"),
-                    state: State(None), // TODO state
+                    state,
+                    query: NavigateQuery::Replace,
                 },
             ) {
                 log::error!("{e:#?}");
@@ -376,12 +624,19 @@ pub struct NavigateOptions {
     /// If `true` the new location will replace the current route in the history stack, meaning
     /// the "back" button will skip over the current route. (Defaults to `false`).
     pub replace: bool,
-    /// If `true`, the router will scroll to the top of the window at the end of navigation.
-    /// Defaults to `true.
+    /// If `true`, the router will scroll to the element the `#hash` of the URL points to, or
+    /// else to the top of the window, at the end of navigation. Defaults to `true`.
+    ///
+    /// Going back or forward in the browser's history instead scrolls to the position the
+    /// page was left at.
     pub scroll: bool,
     /// [State](https://developer.mozilla.org/en-US/docs/Web/API/History/state) that should be pushed
-    /// onto the history stack during navigation.
+    /// onto the history stack during navigation. A value serialized with [State::serialize]
+    /// can be read on the page navigated to with [use_location_state](crate::use_location_state).
     pub state: State,
+    /// What happens to the query string of the current URL. Defaults to
+    /// [NavigateQuery::Replace].
+    pub query: NavigateQuery,
 }
 
 impl Default for NavigateOptions {
@@ -391,6 +646,54 @@ impl Default for NavigateOptions {
             replace: false,
             scroll: true,
             state: State(None),
+            query: NavigateQuery::Replace,
+        }
+    }
+}
+
+/// What happens to the query string of the current URL when navigating, as set by
+/// [NavigateOptions::query], e.g., to keep the filters of a list when going to its next page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NavigateQuery {
+    /// The URL has the query of the path navigated to, if any.
+    #[default]
+    Replace,
+    /// The URL keeps the current query, in the place of that of the path navigated to.
+    Preserve,
+    /// The URL keeps the current query, with the keys of the query of the path navigated to
+    /// added to it, in the place of those it already has.
+    Merge,
+}
+
+impl NavigateQuery {
+    /// Applies this to a path that's navigated to, given the query string of the current URL.
+    /// ```
+    /// # use leptos_router::*;
+    /// let search = "?sort=name&page=2";
+    /// assert_eq!(NavigateQuery::Replace.apply("/todos?page=3", search), "/todos?page=3");
+    /// assert_eq!(NavigateQuery::Preserve.apply("/todos/5#notes", search), "/todos/5?sort=name&page=2#notes");
+    /// assert_eq!(NavigateQuery::Merge.apply("/todos?page=3&q=milk", search), "/todos?sort=name&page=3&q=milk");
+    /// ```
+    pub fn apply(&self, to: &str, search: &str) -> String {
+        if *self == NavigateQuery::Replace {
+            return to.to_string();
+        }
+        let (to, hash) = match to.find('#') {
+            Some(index) => to.split_at(index),
+            None => (to, ""),
+        };
+        let (path, query) = to.split_once('?').unwrap_or((to, ""));
+        let mut pairs = query_pairs(search);
+        if *self == NavigateQuery::Merge {
+            let added = query_pairs(query);
+            pairs.retain(|(key, _)| !added.iter().any(|(added, _)| added == key));
+            pairs.extend(added);
+        }
+        if pairs.is_empty() {
+            format!("{path}{hash}")
+        } else {
+            let query = serde_urlencoded::to_string(pairs).unwrap_or_default();
+            format!("{path}?{query}{hash}")
         }
     }
 }
@@ -10,10 +10,11 @@ use typed_builder::TypedBuilder;
 
 use crate::{
     matching::{
-        expand_optionals, get_route_matches, join_paths, Branch, Matcher, RouteDefinition,
-        RouteMatch,
+        check_constraints, expand_optionals, get_route_matches, join_paths, split_constraint,
+        Branch, Matcher, RouteDefinition, RouteMatch,
     },
-    RouteContext, RouterContext,
+    run_before, AfterNavigate, BeforeNavigate, MatchedMeta, MatchedRouteMeta, Navigation,
+    Preloader, RouteContext, RouterContext,
 };
 
 /// Props for the [Routes] component, which contains route definitions and manages routing.
@@ -29,6 +30,28 @@ pub struct RoutesProps {
 /// You should locate the `<Routes/>` component wherever on the page you want the routes to appear.
 #[allow(non_snake_case)]
 pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
+    let root = routes_root(cx, props.base, props.children);
+
+    cfg_if::cfg_if! {
+        if #[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))] {
+            move || {
+                let html = root.get().map(|child| child.as_child_string()).unwrap_or_default();
+                Child::Node(crate::mark_outlet(cx, html))
+            }
+        } else if #[cfg(feature = "stable")] {
+            move || root.get()
+        } else {
+            root
+        }
+    }
+}
+
+/// Matches the routes against the current location, and returns the view of the root route.
+pub(crate) fn routes_root(
+    cx: Scope,
+    base: Option<String>,
+    children: Box<dyn Fn() -> Vec<RouteDefinition>>,
+) -> Memo<Option<Child>> {
     let router = use_context::<RouterContext>(cx).unwrap_or_else(|| {
         log::warn!("<Routes/> component should be nested within a <Router/>.");
         panic!()
@@ -36,18 +59,42 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
 
     let mut branches = Vec::new();
     create_branches(
-        &(props.children)(),
-        &props.base.unwrap_or_default(),
+        &children(),
+        &base.unwrap_or_default(),
         &mut Vec::new(),
         &mut branches,
     );
 
+    // the navigation hooks of a route run for every URL that matches it
+    add_route_hooks(cx, &router, &branches);
+    add_preloader(cx, &router, &branches);
+
+    // a static site is generated from the routes found while rendering it
+    #[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+    if let Some(collector) = use_context::<crate::StaticRouteCollector>(cx) {
+        collector.add(&branches);
+    }
+
     // whenever path changes, update matches
     let matches = create_memo(cx, {
         let router = router.clone();
-        move |_| get_route_matches(branches.clone(), router.pathname().get())
+        move |_| get_route_matches(branches.clone(), router.route_path().get())
     });
 
+    // the metadata of the matched routes, for use_route_meta
+    let meta = create_memo(cx, move |_| {
+        matches.with(|matches| MatchedMeta {
+            routes: matches
+                .iter()
+                .map(|matched| MatchedRouteMeta {
+                    path: matched.path_match.path.clone(),
+                    meta: matched.route.key.meta,
+                })
+                .collect(),
+        })
+    });
+    provide_context(cx, meta);
+
     // Rebuild the list of nested routes conservatively, and show the root route here
     let disposers = RefCell::new(Vec::<ScopeDisposer>::new());
 
@@ -71,6 +118,8 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
             let mut equal = prev_matches
                 .map(|prev_matches| next_matches.len() == prev_matches.len())
                 .unwrap_or(false);
+            // once a route is mounted again, so are all the routes nested in it
+            let mut remounted = false;
 
             for i in 0..next_matches.len() {
                 let next = next.clone();
@@ -79,7 +128,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
 
                 match (prev_routes, prev_match) {
                     (Some(prev), Some(prev_match))
-                        if next_match.route.key == prev_match.route.key =>
+                        if !remounted && keeps_route(prev_match, next_match) =>
                     {
                         let prev_one = { prev.borrow()[i].clone() };
                         if i >= next.borrow().len() {
@@ -90,6 +139,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
                     }
                     _ => {
                         equal = false;
+                        remounted = true;
                         if i == 0 {
                             root_equal.set(false);
                         }
@@ -130,7 +180,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
                             }
                         });
 
-                        if disposers.borrow().len() > i + 1 {
+                        if disposers.borrow().len() > i {
                             let mut disposers = disposers.borrow_mut();
                             let old_route_disposer = std::mem::replace(&mut disposers[i], disposer);
                             old_route_disposer.dispose();
@@ -142,7 +192,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
             }
 
             if disposers.borrow().len() > next_matches.len() {
-                let surplus_disposers = disposers.borrow_mut().split_off(next_matches.len() + 1);
+                let surplus_disposers = disposers.borrow_mut().split_off(next_matches.len());
                 for disposer in surplus_disposers {
                     disposer.dispose();
                 }
@@ -175,7 +225,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
     });
 
     // show the root route
-    let root = create_memo(cx, move |prev| {
+    create_memo(cx, move |prev| {
         provide_context(cx, route_states);
         route_states.with(|state| {
             let root = state.routes.borrow();
@@ -190,15 +240,7 @@ pub fn Routes(cx: Scope, props: RoutesProps) -> impl IntoChild {
                 prev.cloned().unwrap()
             }
         })
-    });
-
-    cfg_if::cfg_if! {
-        if #[cfg(feature = "stable")] {
-            move || root.get()
-        } else {
-            root
-        }
-    }
+    })
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -226,13 +268,117 @@ impl RouteData {
             .split('/')
             .filter(|n| !n.is_empty())
             .collect::<Vec<_>>();
+        // a param whose value must match a pattern is more specific than one that matches any
+        // value, though less so than a static segment
         segments.iter().fold(
-            (segments.len() as i32) - if splat.is_none() { 0 } else { 1 },
-            |score, segment| score + if segment.starts_with(':') { 2 } else { 3 },
+            (segments.len() as i32) * 2 - if splat.is_none() { 0 } else { 2 },
+            |score, segment| {
+                score
+                    + match segment.strip_prefix(':').map(split_constraint) {
+                        Some((_, Some(_))) => 5,
+                        Some((_, None)) => 4,
+                        None => 6,
+                    }
+            },
         )
     }
 }
 
+/// Whether the route that was matched can stay mounted for the one that is matched now, i.e.,
+/// whether it's the same route and, unless it's kept alive, matches the same part of the URL.
+fn keeps_route(prev: &RouteMatch, next: &RouteMatch) -> bool {
+    next.route.key == prev.route.key
+        && (next.route.key.keep_alive || next.path_match.path == prev.path_match.path)
+}
+
+/// Adds hooks to the router that run the navigation hooks of the routes each URL matches,
+/// from the outermost route to the innermost, for as long as the `<Routes/>` lives.
+fn add_route_hooks(cx: Scope, router: &RouterContext, branches: &[Branch]) {
+    let has_hooks = |hook: fn(&RouteDefinition) -> bool| {
+        branches
+            .iter()
+            .any(|branch| branch.routes.iter().any(|route| hook(&route.key)))
+    };
+    let mut ids = Vec::new();
+
+    if has_hooks(|route| route.before_navigate.is_some()) {
+        let branches = branches.to_vec();
+        ids.push(router.inner.hooks.add_before(BeforeNavigate::from(
+            move |navigation: Navigation| {
+                let hooks = matched_hooks(&branches, &navigation, |route| {
+                    route.before_navigate.clone()
+                });
+                async move { run_before(&hooks, navigation).await }
+            },
+        )));
+    }
+    if has_hooks(|route| route.after_navigate.is_some()) {
+        let branches = branches.to_vec();
+        ids.push(router.inner.hooks.add_after(AfterNavigate::from(
+            move |navigation: Navigation| {
+                let hooks =
+                    matched_hooks(&branches, &navigation, |route| route.after_navigate.clone());
+                for hook in hooks {
+                    hook.run(navigation.clone());
+                }
+            },
+        )));
+    }
+
+    let router = Rc::clone(&router.inner);
+    on_cleanup(cx, move || {
+        for id in ids {
+            router.hooks.remove(id);
+        }
+    });
+}
+
+/// Adds a preloader to the router that starts loading the [Lazy](crate::Lazy) views of the
+/// routes a path matches, for as long as the `<Routes/>` lives.
+fn add_preloader(cx: Scope, router: &RouterContext, branches: &[Branch]) {
+    let has_lazy = branches
+        .iter()
+        .any(|branch| branch.routes.iter().any(|route| route.key.lazy.is_some()));
+    if !has_lazy {
+        return;
+    }
+
+    let branches = branches.to_vec();
+    let preloader: Preloader = Rc::new(move |path| {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        for matched in get_route_matches(branches.clone(), path.to_string()) {
+            if let Some(lazy) = &matched.route.key.lazy {
+                lazy.preload();
+            }
+        }
+    });
+    router
+        .inner
+        .preloaders
+        .borrow_mut()
+        .push(Rc::clone(&preloader));
+
+    let router = Rc::clone(&router.inner);
+    on_cleanup(cx, move || {
+        router
+            .preloaders
+            .borrow_mut()
+            .retain(|other| !Rc::ptr_eq(other, &preloader));
+    });
+}
+
+/// The hooks of the routes that the URL being navigated to matches.
+fn matched_hooks<T>(
+    branches: &[Branch],
+    navigation: &Navigation,
+    hook: impl Fn(&RouteDefinition) -> Option<T>,
+) -> Vec<T> {
+    get_route_matches(branches.to_vec(), navigation.to_path().to_string())
+        .iter()
+        .filter_map(|matched| hook(&matched.route.key))
+        .collect()
+}
+
 fn create_branches(
     route_defs: &[RouteDefinition],
     base: &str,
@@ -271,6 +417,11 @@ fn create_routes(route_def: &RouteDefinition, base: &str) -> Vec<RouteData> {
     let RouteDefinition { children, .. } = route_def;
     let is_leaf = children.is_empty();
     let mut acc = Vec::new();
+    // a route whose params can't be matched is left out, so it never matches
+    if let Err(e) = check_constraints(route_def.path) {
+        log::error!("[Leptos Router] invalid path {:?}: {e}", route_def.path);
+        return acc;
+    }
     for original_path in expand_optionals(route_def.path) {
         let path = join_paths(base, &original_path);
         let pattern = if is_leaf {
@@ -290,3 +441,50 @@ fn create_routes(route_def: &RouteDefinition, base: &str) -> Vec<RouteData> {
     }
     acc
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(pattern: &str) -> RouteData {
+        RouteData {
+            key: RouteDefinition::default(),
+            pattern: pattern.to_string(),
+            original_path: pattern.to_string(),
+            matcher: Matcher::new(pattern),
+        }
+    }
+
+    #[test]
+    fn scores_each_kind_of_segment() {
+        // every segment counts twice, on top of 6 for a static one, 5 for a param with a
+        // pattern, and 4 for one without
+        assert_eq!(route("/users/settings").score(), 16);
+        assert_eq!(route(r"/users/:id(\d+)").score(), 15);
+        assert_eq!(route("/users/:id").score(), 14);
+        // a splat counts less than nothing
+        assert_eq!(route("/users/*any").score(), 6);
+        assert_eq!(route("/").score(), 0);
+    }
+
+    #[test]
+    fn prefers_the_most_specific_route() {
+        let mut routes = [
+            route("/users/*any"),
+            route("/users/:name"),
+            route(r"/users/:id(\d+)"),
+            route("/users/settings"),
+        ];
+        routes.sort_by_key(|route| Reverse(route.score()));
+        let matched = |location: &str| {
+            routes
+                .iter()
+                .find(|route| route.matcher.test(location).is_some())
+                .map(|route| route.pattern.as_str())
+        };
+        assert_eq!(matched("/users/settings"), Some("/users/settings"));
+        assert_eq!(matched("/users/5"), Some(r"/users/:id(\d+)"));
+        assert_eq!(matched("/users/bob"), Some("/users/:name"));
+        assert_eq!(matched("/users/bob/posts"), Some("/users/*any"));
+    }
+}
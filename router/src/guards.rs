@@ -0,0 +1,253 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+};
+
+use leptos::*;
+
+use crate::use_router;
+
+/// A navigation that is about to happen, or has just happened, as passed to navigation hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Navigation {
+    /// The URL that is being navigated away from.
+    pub from: String,
+    /// The URL that is being navigated to, including its search query and hash.
+    pub to: String,
+}
+
+impl Navigation {
+    /// The path of the URL that is being navigated to, without its search query or hash.
+    pub fn to_path(&self) -> &str {
+        self.to.split(['?', '#']).next().unwrap_or_default()
+    }
+}
+
+/// What a [BeforeNavigate] hook decides to do with a navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationDecision {
+    /// Lets the navigation go on, to the next hook or to the new URL.
+    Continue,
+    /// Cancels the navigation, staying at the current URL.
+    Cancel,
+    /// Cancels the navigation and navigates to the given URL instead, e.g., to a login page.
+    Redirect(String),
+}
+
+/// A hook that runs before each navigation started by the router, i.e., by clicking a link,
+/// submitting a [Form](crate::Form) or calling [use_navigate](crate::use_navigate), and can
+/// cancel or redirect it, e.g., to keep users who aren't logged in out of a page, or to ask
+/// whether changes that haven't been saved should be thrown away.
+///
+/// Any function that takes a [Navigation] and returns a [Future] of a [NavigationDecision]
+/// can be used as one. The hooks run one after another, and the navigation waits for them;
+/// the first that doesn't [continue](NavigationDecision::Continue) decides what happens.
+///
+/// Going back or forward in the browser's history is not started by the router, so it can't
+/// be cancelled, but it still runs the [AfterNavigate] hooks.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// async fn require_login(navigation: Navigation) -> NavigationDecision {
+///     let logged_in = false; // e.g., read from a cookie or a server function
+///     if logged_in {
+///         NavigationDecision::Continue
+///     } else {
+///         NavigationDecision::Redirect(format!("/login?next={}", navigation.to))
+///     }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router after_navigate=|navigation: Navigation| log::info!("page view: {}", navigation.to)>
+///             <main>
+///                 <Routes>
+///                     <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                     <Route path="login" element=|cx| view! { cx, <p>"Log in"</p> }/>
+///                     <Route
+///                         path="account"
+///                         before_navigate=require_login
+///                         element=|cx| view! { cx, <p>"Your account"</p> }
+///                     />
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct BeforeNavigate(Rc<BeforeNavigateFn>);
+
+type BeforeNavigateFn = dyn Fn(Navigation) -> Pin<Box<dyn Future<Output = NavigationDecision>>>;
+
+impl BeforeNavigate {
+    pub(crate) fn run(&self, navigation: Navigation) -> impl Future<Output = NavigationDecision> {
+        (self.0)(navigation)
+    }
+}
+
+impl<F, Fu> From<F> for BeforeNavigate
+where
+    F: Fn(Navigation) -> Fu + 'static,
+    Fu: Future<Output = NavigationDecision> + 'static,
+{
+    fn from(hook: F) -> Self {
+        Self(Rc::new(move |navigation| Box::pin(hook(navigation))))
+    }
+}
+
+impl Debug for BeforeNavigate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BeforeNavigate").finish_non_exhaustive()
+    }
+}
+
+/// A hook that runs after the URL has changed, whether by a navigation started by the router
+/// or by going back or forward in the browser's history, e.g., to report page views.
+#[derive(Clone)]
+pub struct AfterNavigate(Rc<dyn Fn(Navigation)>);
+
+impl AfterNavigate {
+    pub(crate) fn run(&self, navigation: Navigation) {
+        (self.0)(navigation)
+    }
+}
+
+impl<F> From<F> for AfterNavigate
+where
+    F: Fn(Navigation) + 'static,
+{
+    fn from(hook: F) -> Self {
+        Self(Rc::new(hook))
+    }
+}
+
+impl Debug for AfterNavigate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AfterNavigate").finish_non_exhaustive()
+    }
+}
+
+/// The navigation hooks that are registered with a router, each with an id to remove it by.
+#[derive(Debug, Default)]
+pub(crate) struct NavigationHooks {
+    next_id: Cell<usize>,
+    before: RefCell<Vec<(usize, BeforeNavigate)>>,
+    after: RefCell<Vec<(usize, AfterNavigate)>>,
+}
+
+impl NavigationHooks {
+    fn next_id(&self) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    pub(crate) fn add_before(&self, hook: BeforeNavigate) -> usize {
+        let id = self.next_id();
+        self.before.borrow_mut().push((id, hook));
+        id
+    }
+
+    pub(crate) fn add_after(&self, hook: AfterNavigate) -> usize {
+        let id = self.next_id();
+        self.after.borrow_mut().push((id, hook));
+        id
+    }
+
+    pub(crate) fn remove(&self, id: usize) {
+        self.before
+            .borrow_mut()
+            .retain(|(hook_id, _)| *hook_id != id);
+        self.after
+            .borrow_mut()
+            .retain(|(hook_id, _)| *hook_id != id);
+    }
+
+    pub(crate) fn has_before(&self) -> bool {
+        !self.before.borrow().is_empty()
+    }
+
+    /// Runs the [BeforeNavigate] hooks in the order they were added, until one of them
+    /// doesn't continue.
+    pub(crate) async fn run_before(&self, navigation: Navigation) -> NavigationDecision {
+        // the hooks are cloned, so that they can add or remove hooks while they're waited for
+        let hooks = self
+            .before
+            .borrow()
+            .iter()
+            .map(|(_, hook)| hook.clone())
+            .collect::<Vec<_>>();
+        run_before(&hooks, navigation).await
+    }
+
+    pub(crate) fn run_after(&self, navigation: Navigation) {
+        let hooks = self
+            .after
+            .borrow()
+            .iter()
+            .map(|(_, hook)| hook.clone())
+            .collect::<Vec<_>>();
+        for hook in hooks {
+            hook.run(navigation.clone());
+        }
+    }
+}
+
+/// Runs the given hooks one after another, until one of them doesn't continue.
+pub(crate) async fn run_before(
+    hooks: &[BeforeNavigate],
+    navigation: Navigation,
+) -> NavigationDecision {
+    for hook in hooks {
+        match hook.run(navigation.clone()).await {
+            NavigationDecision::Continue => {}
+            decision => return decision,
+        }
+    }
+    NavigationDecision::Continue
+}
+
+/// Adds a [BeforeNavigate] hook to the router for as long as the current [Scope] lives, e.g.,
+/// to ask whether a form that has unsaved changes should be left. [use_navigation_blocker]
+/// does this with a UI of your own, and also when the tab is closed.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Editor(cx: Scope) -> Element {
+///     let (dirty, set_dirty) = create_signal(cx, false);
+///     use_before_navigate(cx, move |_| {
+///         let dirty = dirty.get();
+///         async move {
+///             if dirty {
+///                 // e.g., ask with `window().confirm_with_message(...)`
+///                 NavigationDecision::Cancel
+///             } else {
+///                 NavigationDecision::Continue
+///             }
+///         }
+///     });
+///
+///     view! { cx, <textarea on:input=move |_| set_dirty.set(true)/> }
+/// }
+/// ```
+pub fn use_before_navigate(cx: Scope, hook: impl Into<BeforeNavigate>) {
+    let router = use_router(cx);
+    let id = router.inner.hooks.add_before(hook.into());
+    on_cleanup(cx, move || router.inner.hooks.remove(id));
+}
+
+/// Adds an [AfterNavigate] hook to the router for as long as the current [Scope] lives.
+pub fn use_after_navigate(cx: Scope, hook: impl Into<AfterNavigate>) {
+    let router = use_router(cx);
+    let id = router.inner.hooks.add_after(hook.into());
+    on_cleanup(cx, move || router.inner.hooks.remove(id));
+}
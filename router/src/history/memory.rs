@@ -0,0 +1,232 @@
+use std::{cell::RefCell, rc::Rc};
+
+use leptos::*;
+
+use crate::{
+    History, LocationChange, NavigateOptions, NavigateQuery, NavigationError, RouterContext, State,
+};
+
+/// An integration that keeps the history of the [Router](crate::Router) in memory rather than
+/// in the URL of the page, and that can be navigated from code, so that routing can be
+/// exercised without a `window.location`, e.g., in the tests of components or in a native
+/// shell. Like any other integration, it's provided as a
+/// [RouterIntegrationContext](crate::RouterIntegrationContext) above the router.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # run_scope(create_runtime(), |cx| {
+/// let history = MemoryIntegration::new("/users");
+/// provide_context(cx, RouterIntegrationContext::new(history.clone()));
+///
+/// // e.g., once a <Router/> has been rendered below
+/// history.navigate_to("/users/5").unwrap();
+/// history.navigate_to("/users/6").unwrap();
+/// history.back();
+/// assert_eq!(history.current(), "/users/5");
+/// assert_eq!(history.entries(), ["/users", "/users/5", "/users/6"]);
+/// # });
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryIntegration(Rc<RefCell<MemoryHistory>>);
+
+#[derive(Debug)]
+struct MemoryHistory {
+    entries: Vec<LocationChange>,
+    index: usize,
+    /// The scope of the router and the setter of its location, once it has been created.
+    router: Option<(Scope, WriteSignal<LocationChange>)>,
+}
+
+impl MemoryIntegration {
+    /// Creates a history with a single entry, at the given path.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(Rc::new(RefCell::new(MemoryHistory {
+            entries: vec![LocationChange {
+                value: path.into(),
+                replace: true,
+                scroll: true,
+                state: State(None),
+            }],
+            index: 0,
+            router: None,
+        })))
+    }
+
+    /// The path of the current entry.
+    pub fn current(&self) -> String {
+        let history = self.0.borrow();
+        history.entries[history.index].value.clone()
+    }
+
+    /// The path of every entry, from the oldest to the newest, including the ones after the
+    /// current entry that have been gone back from.
+    pub fn entries(&self) -> Vec<String> {
+        self.0
+            .borrow()
+            .entries
+            .iter()
+            .map(|entry| entry.value.clone())
+            .collect()
+    }
+
+    /// Navigates to the given path, as following a link to it would: the
+    /// [BeforeNavigate](crate::BeforeNavigate) hooks of the router can cancel or redirect it,
+    /// and the entries after the current one are dropped once it's pushed.
+    pub fn navigate_to(&self, path: &str) -> Result<(), NavigationError> {
+        let router = self
+            .0
+            .borrow()
+            .router
+            .and_then(|(cx, _)| use_context::<RouterContext>(cx));
+        if let Some(router) = router {
+            router.inner.navigate_from_route(
+                path,
+                &NavigateOptions {
+                    resolve: false,
+                    ..Default::default()
+                },
+            )
+        } else {
+            self.navigate(&LocationChange {
+                value: path.to_string(),
+                replace: false,
+                scroll: true,
+                state: State(None),
+            });
+            Ok(())
+        }
+    }
+
+    /// Goes back to the previous entry, if there is one.
+    pub fn back(&self) {
+        self.go(-1)
+    }
+
+    /// Goes forward to the next entry, if there is one.
+    pub fn forward(&self) {
+        self.go(1)
+    }
+
+    /// Goes back, if `delta` is negative, or forward by that many entries, as the buttons of a
+    /// browser do, unless there aren't that many. As in a browser, this has already happened
+    /// by the time the router follows it, so the [BeforeNavigate](crate::BeforeNavigate) hooks
+    /// aren't run.
+    pub fn go(&self, delta: isize) {
+        let (change, router) = {
+            let mut history = self.0.borrow_mut();
+            let index = history.index as isize + delta;
+            if index < 0 || index as usize >= history.entries.len() {
+                return;
+            }
+            history.index = index as usize;
+            (history.entries[history.index].clone(), history.router)
+        };
+
+        if let Some((cx, set_location)) = router {
+            if let Some(router) = use_context::<RouterContext>(cx) {
+                if let Err(e) = router.inner.navigate_from_history(
+                    &change.value,
+                    &NavigateOptions {
+                        resolve: false,
+                        replace: true,
+                        scroll: false,
+                        state: change.state.clone(),
+                        query: NavigateQuery::Replace,
+                    },
+                ) {
+                    log::error!("{e:#?}");
+                }
+            }
+            set_location.set(change);
+        }
+    }
+}
+
+impl History for MemoryIntegration {
+    fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
+        let current = {
+            let history = self.0.borrow();
+            history.entries[history.index].clone()
+        };
+        let (location, set_location) = create_signal(cx, current);
+        self.0.borrow_mut().router = Some((cx, set_location));
+        location
+    }
+
+    fn navigate(&self, loc: &LocationChange) {
+        let mut history = self.0.borrow_mut();
+        if loc.replace {
+            let index = history.index;
+            history.entries[index] = loc.clone();
+        } else {
+            let index = history.index + 1;
+            history.entries.truncate(index);
+            history.entries.push(loc.clone());
+            history.index = index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushes_and_moves_through_entries() {
+        let history = MemoryIntegration::new("/");
+        history.navigate_to("/a").unwrap();
+        history.navigate_to("/b").unwrap();
+        assert_eq!(history.entries(), ["/", "/a", "/b"]);
+        assert_eq!(history.current(), "/b");
+
+        history.back();
+        history.back();
+        assert_eq!(history.current(), "/");
+        // there's nothing before the first entry
+        history.back();
+        assert_eq!(history.current(), "/");
+
+        history.go(2);
+        assert_eq!(history.current(), "/b");
+        history.forward();
+        assert_eq!(history.current(), "/b");
+    }
+
+    #[test]
+    fn navigating_drops_the_entries_gone_back_from() {
+        let history = MemoryIntegration::new("/");
+        history.navigate_to("/a").unwrap();
+        history.navigate_to("/b").unwrap();
+        history.back();
+        history.navigate_to("/c").unwrap();
+        assert_eq!(history.entries(), ["/", "/a", "/c"]);
+        history.forward();
+        assert_eq!(history.current(), "/c");
+    }
+
+    #[test]
+    fn replaces_the_current_entry() {
+        let history = MemoryIntegration::new("/");
+        history.navigate_to("/a").unwrap();
+        history.navigate(&LocationChange {
+            value: "/b".to_string(),
+            replace: true,
+            scroll: true,
+            state: State(None),
+        });
+        assert_eq!(history.entries(), ["/", "/b"]);
+    }
+
+    #[test]
+    fn going_back_updates_the_location() {
+        create_scope(create_runtime(), |cx| {
+            let history = MemoryIntegration::new("/a");
+            let location = history.location(cx);
+            history.navigate_to("/b").unwrap();
+            history.back();
+            assert_eq!(location.with(|location| location.value.clone()), "/a");
+        })
+        .dispose();
+    }
+}
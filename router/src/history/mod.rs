@@ -3,13 +3,17 @@ use std::rc::Rc;
 use leptos::*;
 
 mod location;
+mod memory;
 mod params;
+mod scroll;
 mod state;
 mod url;
 
 pub use self::url::*;
 pub use location::*;
+pub use memory::*;
 pub use params::*;
+pub(crate) use scroll::*;
 pub use state::*;
 
 impl std::fmt::Debug for RouterIntegrationContext {
@@ -43,17 +47,22 @@ impl BrowserIntegration {
                 + &loc.hash().unwrap_or_default(),
             replace: true,
             scroll: true,
-            state: State(None), // TODO
+            state: history_state(),
         }
     }
 }
 
 impl History for BrowserIntegration {
     fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
-        use crate::{NavigateOptions, RouterContext};
+        use crate::{NavigateOptions, NavigateQuery, RouterContext};
 
         let (location, set_location) = create_signal(cx, Self::current());
 
+        // the router saves and restores the scroll position itself, once the route is rendered
+        _ = leptos_dom::window()
+            .history()
+            .map(|history| history.set_scroll_restoration(web_sys::ScrollRestoration::Manual));
+
         leptos_dom::window_event_listener("popstate", move |_| {
             log::debug!(
                 "[BrowserIntegration::location] popstate fired {:#?}",
@@ -62,17 +71,21 @@ impl History for BrowserIntegration {
             let router = use_context::<RouterContext>(cx);
             if let Some(router) = router {
                 let change = Self::current();
-                if let Err(e) = router.inner.navigate_from_route(
+                if let Err(e) = router.inner.navigate_from_history(
                     &change.value,
                     &NavigateOptions {
                         resolve: false,
                         replace: change.replace,
-                        scroll: change.scroll,
+                        // the position the page was left at is restored below instead
+                        scroll: false,
                         state: change.state,
+                        query: NavigateQuery::Replace,
                     },
                 ) {
                     log::error!("{e:#?}");
                 }
+                // runs after the navigation, which is queued as well
+                queue_microtask(move || restore_scroll_position(&change.value));
                 set_location.set(Self::current());
             } else {
                 log::warn!("RouterContext not found");
@@ -94,24 +107,104 @@ impl History for BrowserIntegration {
                 .push_state_with_url(&loc.state.to_js_value(), "", Some(&loc.value))
                 .unwrap_throw();
         }
-        // scroll to el
-        if let Ok(hash) = leptos_dom::location().hash() {
-            if !hash.is_empty() {
-                let hash = js_sys::decode_uri(&hash[1..])
-                    .ok()
-                    .and_then(|decoded| decoded.as_string())
-                    .unwrap_or(hash);
-                let el = leptos_dom::document().get_element_by_id(&hash);
-                if let Some(el) = el {
-                    el.scroll_into_view()
-                } else if loc.scroll {
-                    leptos_dom::window().scroll_to_with_x_and_y(0.0, 0.0);
+        if loc.scroll {
+            scroll_to_hash_or_top();
+        }
+    }
+}
+
+/// An integration that keeps the path in the
+/// [hash](https://developer.mozilla.org/en-US/docs/Web/API/Location/hash) of the URL, e.g.,
+/// `index.html#/users/5`, rather than in the path itself, so that every route is served by the
+/// same file. This is used by a [Router](crate::Router) with [RouterMode::Hash](crate::RouterMode::Hash).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HashIntegration {}
+
+impl HashIntegration {
+    fn current() -> LocationChange {
+        let hash = leptos_dom::location().hash().unwrap_or_default();
+        let value = match hash.strip_prefix('#') {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => "/".to_string(),
+        };
+        LocationChange {
+            value,
+            replace: true,
+            scroll: true,
+            state: history_state(),
+        }
+    }
+}
+
+impl History for HashIntegration {
+    fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
+        use crate::{NavigateOptions, NavigateQuery, RouterContext};
+
+        let (location, set_location) = create_signal(cx, Self::current());
+
+        _ = leptos_dom::window()
+            .history()
+            .map(|history| history.set_scroll_restoration(web_sys::ScrollRestoration::Manual));
+
+        // fired both by going back or forward and by changing the hash by hand
+        leptos_dom::window_event_listener("popstate", move |_| {
+            let router = use_context::<RouterContext>(cx);
+            if let Some(router) = router {
+                let change = Self::current();
+                if let Err(e) = router.inner.navigate_from_history(
+                    &change.value,
+                    &NavigateOptions {
+                        resolve: false,
+                        replace: change.replace,
+                        scroll: false,
+                        state: change.state,
+                        query: NavigateQuery::Replace,
+                    },
+                ) {
+                    log::error!("{e:#?}");
                 }
+                queue_microtask(move || restore_scroll_position(&change.value));
+                set_location.set(Self::current());
+            } else {
+                log::warn!("RouterContext not found");
             }
+        });
+
+        location
+    }
+
+    fn navigate(&self, loc: &LocationChange) {
+        let history = leptos_dom::window().history().unwrap_throw();
+        let url = format!("#{}", loc.value);
+
+        if loc.replace {
+            history
+                .replace_state_with_url(&loc.state.to_js_value(), "", Some(&url))
+                .unwrap_throw();
+        } else {
+            history
+                .push_state_with_url(&loc.state.to_js_value(), "", Some(&url))
+                .unwrap_throw();
+        }
+        // the hash holds the path, so it can't point to an element as well
+        if loc.scroll {
+            leptos_dom::window().scroll_to_with_x_and_y(0.0, 0.0);
         }
     }
 }
 
+/// The [`state`](https://developer.mozilla.org/en-US/docs/Web/API/History/state) of the current
+/// entry of the browser's history, e.g., after going back to it.
+fn history_state() -> State {
+    State(
+        leptos_dom::window()
+            .history()
+            .and_then(|history| history.state())
+            .ok()
+            .filter(|state| !state.is_undefined() && !state.is_null()),
+    )
+}
+
 /// The wrapper type that the [Router](crate::Router) uses to interact with a [History].
 /// This is automatically provided in the browser. For the server, it should be provided
 /// as a context.
@@ -0,0 +1,54 @@
+use leptos::leptos_dom;
+
+const SCROLL_KEY_PREFIX: &str = "leptos_router:scroll:";
+
+/// Saves the current scroll position of the window as that of the given URL, so that it can be
+/// restored when the URL is gone back or forward to. It's kept in `sessionStorage`, so that it
+/// also survives a reload.
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn save_scroll_position(url: &str) {
+    let window = leptos_dom::window();
+    if let (Ok(x), Ok(y), Ok(Some(storage))) = (
+        window.scroll_x(),
+        window.scroll_y(),
+        window.session_storage(),
+    ) {
+        _ = storage.set_item(&format!("{SCROLL_KEY_PREFIX}{url}"), &format!("{x},{y}"));
+    }
+}
+
+fn saved_scroll_position(url: &str) -> Option<(f64, f64)> {
+    let storage = leptos_dom::window().session_storage().ok()??;
+    let position = storage
+        .get_item(&format!("{SCROLL_KEY_PREFIX}{url}"))
+        .ok()??;
+    let (x, y) = position.split_once(',')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+/// Scrolls to the position the given URL was left at, or, if it has none, as if it had just
+/// been navigated to.
+pub(crate) fn restore_scroll_position(url: &str) {
+    if let Some((x, y)) = saved_scroll_position(url) {
+        leptos_dom::window().scroll_to_with_x_and_y(x, y);
+    } else {
+        scroll_to_hash_or_top();
+    }
+}
+
+/// Scrolls to the element the `#hash` of the current URL points to, or to the top of the
+/// window if there's none.
+pub(crate) fn scroll_to_hash_or_top() {
+    let hash = leptos_dom::location().hash().unwrap_or_default();
+    let el = hash.strip_prefix('#').and_then(|hash| {
+        let id = urlencoding::decode(hash)
+            .map(|id| id.into_owned())
+            .unwrap_or_else(|_| hash.to_string());
+        leptos_dom::document().get_element_by_id(&id)
+    });
+    if let Some(el) = el {
+        el.scroll_into_view();
+    } else {
+        leptos_dom::window().scroll_to_with_x_and_y(0.0, 0.0);
+    }
+}
@@ -1,4 +1,5 @@
 use leptos::wasm_bindgen::JsValue;
+use serde::{de::DeserializeOwned, Serialize};
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct State(pub Option<JsValue>);
@@ -10,6 +11,19 @@ impl State {
             None => JsValue::UNDEFINED,
         }
     }
+
+    /// Serializes a value as JSON into a [State], to be read on the page that's navigated to
+    /// with [use_location_state](crate::use_location_state).
+    pub fn serialize<T: Serialize>(value: &T) -> Result<Self, serde_json::Error> {
+        serde_json::to_string(value).map(|json| State(Some(JsValue::from_str(&json))))
+    }
+
+    /// Deserializes the value written into this by [State::serialize], if it holds a value of
+    /// the given type.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Option<T> {
+        let json = self.0.as_ref()?.as_string()?;
+        serde_json::from_str(&json).ok()
+    }
 }
 
 impl<T> From<T> for State
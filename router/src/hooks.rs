@@ -1,10 +1,11 @@
 use std::rc::Rc;
 
-use leptos::{create_memo, use_context, Memo, Scope};
+use leptos::{create_memo, use_context, Memo, Resource, Scope};
+use serde::de::DeserializeOwned;
 
 use crate::{
     Location, NavigateOptions, NavigationError, Params, ParamsError, ParamsMap, RouteContext,
-    RouterContext,
+    RouterContext, State,
 };
 
 /// Returns the current [RouterContext], containing information about the router's state.
@@ -42,12 +43,41 @@ where
     create_memo(cx, move |_| route.params().with(T::from_map))
 }
 
+/// Returns the data of the current route, as loaded by the [Loader](crate::Loader) passed to
+/// its [Route](crate::Route).
+///
+/// Panics if the route has no loader, or if its loader returns a type other than `T`.
+pub fn use_loader<T>(cx: Scope) -> Resource<ParamsMap, T>
+where
+    T: std::fmt::Debug + Clone + 'static,
+{
+    let route = use_route(cx);
+    match route
+        .loader()
+        .and_then(|data| data.downcast_ref::<Resource<ParamsMap, T>>().copied())
+    {
+        Some(data) => data,
+        None => {
+            leptos::leptos_dom::debug_warn!(
+                "use_loader::<{}>() was called in a route without a loader of that type",
+                std::any::type_name::<T>()
+            );
+            panic!(
+                "use_loader::<{}>() was called in a route without a loader of that type",
+                std::any::type_name::<T>()
+            );
+        }
+    }
+}
+
 /// Returns a raw key-value map of the URL search query.
 pub fn use_query_map(cx: Scope) -> Memo<ParamsMap> {
     use_router(cx).inner.location.query
 }
 
 /// Returns the current URL search query, parsed into the given type, or an error.
+///
+/// To deserialize the query with [serde] instead, see [use_serde_query](crate::use_serde_query).
 pub fn use_query<T: Params>(cx: Scope) -> Memo<Result<T, ParamsError>>
 where
     T: PartialEq + std::fmt::Debug,
@@ -72,8 +102,60 @@ pub fn use_resolved_path(cx: Scope, path: impl Fn() -> String + 'static) -> Memo
     })
 }
 
-/// Returns a function that can be used to navigate to a new route.
+/// Returns a function that can be used to navigate to a new route, with [NavigateOptions]
+/// that set whether it replaces the current entry of the browser's history, whether the page is
+/// scrolled, the [State] pushed with it, and what happens to the current query string.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// struct Flash {
+///     message: String,
+/// }
+///
+/// #[component]
+/// fn NewTodo(cx: Scope) -> Element {
+///     let navigate = use_navigate(cx);
+///     let on_saved = move |_| {
+///         let flash = Flash { message: "Saved!".to_string() };
+///         let options = NavigateOptions {
+///             replace: true,
+///             state: State::serialize(&flash).unwrap(),
+///             // keeps the filters of the list
+///             query: NavigateQuery::Preserve,
+///             ..Default::default()
+///         };
+///         if let Err(e) = navigate("/todos", options) {
+///             log::error!("{e}");
+///         }
+///     };
+///     view! { cx, <button on:click=on_saved>"Save"</button> }
+/// }
+///
+/// #[component]
+/// fn Todos(cx: Scope) -> Element {
+///     // the state is gone once the page is reloaded from scratch
+///     let flash = use_location_state::<Flash>(cx);
+///     view! {
+///         cx,
+///         <p>{move || flash.get().map(|flash| flash.message)}</p>
+///     }
+/// }
+/// ```
 pub fn use_navigate(cx: Scope) -> impl Fn(&str, NavigateOptions) -> Result<(), NavigationError> {
     let router = use_router(cx);
     move |to, options| Rc::clone(&router.inner).navigate_from_route(to, &options)
 }
+
+/// Returns the [State] of the current entry of the browser's history, deserialized into the
+/// given type, as written by [State::serialize] when it was navigated to. It's `None` if the
+/// state holds no value of that type, e.g., because the page was loaded from its URL, and
+/// always on the server. See [use_navigate] for an example.
+pub fn use_location_state<T>(cx: Scope) -> Memo<Option<T>>
+where
+    T: DeserializeOwned + PartialEq + std::fmt::Debug + 'static,
+{
+    let state = use_router(cx).inner.location.state;
+    create_memo(cx, move |_| state.with(State::deserialize))
+}
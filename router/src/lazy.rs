@@ -0,0 +1,157 @@
+use std::{cell::RefCell, fmt::Debug, future::Future, rc::Rc};
+
+use futures::{
+    future::{LocalBoxFuture, Shared},
+    FutureExt,
+};
+use leptos::leptos_dom::{Child, IntoChild};
+use leptos::*;
+
+type LazyView = Rc<dyn Fn(Scope) -> Child>;
+
+/// Loads the view of a [Route](crate::Route) the first time it's needed, rather than along
+/// with the rest of the app, so that a large page that is seldom visited doesn't add to the
+/// size of the initial bundle.
+///
+/// Any function that returns a [Future] of a function that renders the view can be used as
+/// one. In an app whose wasm is split into several modules, the future would load the module
+/// the route is compiled into, and the view call into it; the router doesn't split the binary
+/// itself. Until the future is ready, the route's `element` is shown as a fallback.
+///
+/// The view is loaded the first time the route is matched, or as soon as the pointer is over
+/// an [A](crate::A) that links to it, whichever happens first, and is kept once it's loaded.
+/// During server-side rendering, where nothing is split off, a future that's ready as soon as
+/// it's polled renders the view right away, so the server sends the whole page; one that isn't
+/// renders the fallback.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Settings(cx: Scope) -> Element {
+///     view! { cx, <p>"Settings"</p> }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router>
+///             <nav>
+///                 <A href="settings">"Settings"</A>
+///             </nav>
+///             <main>
+///                 <Routes>
+///                     <Route
+///                         path="settings"
+///                         lazy=|| async { |cx| view! { cx, <Settings/> } }
+///                         element=|cx| view! { cx, <p>"Loading..."</p> }
+///                     />
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Lazy {
+    load: Rc<dyn Fn() -> LocalBoxFuture<'static, LazyView>>,
+    state: Rc<RefCell<LazyState>>,
+}
+
+enum LazyState {
+    NotLoaded,
+    Loading(Shared<LocalBoxFuture<'static, LazyView>>),
+    Loaded(LazyView),
+}
+
+impl Lazy {
+    /// Starts loading the view, if it hasn't been loaded yet.
+    pub fn preload(&self) {
+        _ = self.start();
+    }
+
+    /// Whether the view has been loaded.
+    pub fn is_loaded(&self) -> bool {
+        matches!(*self.state.borrow(), LazyState::Loaded(_))
+    }
+
+    /// Starts loading the view, if it hasn't been, and returns the future that loads it, or
+    /// `None` once it's loaded.
+    fn start(&self) -> Option<Shared<LocalBoxFuture<'static, LazyView>>> {
+        let loading = match &*self.state.borrow() {
+            LazyState::Loaded(_) => return None,
+            LazyState::Loading(loading) => return Some(loading.clone()),
+            LazyState::NotLoaded => (self.load)().shared(),
+        };
+
+        // a view that's ready right away, e.g., on the server, is used without waiting for it
+        if let Some(view) = loading.clone().now_or_never() {
+            *self.state.borrow_mut() = LazyState::Loaded(view);
+            return None;
+        }
+
+        *self.state.borrow_mut() = LazyState::Loading(loading.clone());
+        spawn_local({
+            let state = Rc::clone(&self.state);
+            let loading = loading.clone();
+            async move {
+                let view = loading.await;
+                *state.borrow_mut() = LazyState::Loaded(view);
+            }
+        });
+        Some(loading)
+    }
+
+    /// Renders the view, or the fallback until it's loaded.
+    pub(crate) fn view(&self, cx: Scope, fallback: LazyView) -> Child {
+        match self.start() {
+            None => match &*self.state.borrow() {
+                LazyState::Loaded(view) => view(cx),
+                _ => unreachable!(),
+            },
+            Some(loading) => {
+                let (view, set_view) = create_signal(cx, None::<LazyView>);
+                spawn_local_scoped(cx, async move {
+                    set_view.set(Some(loading.await));
+                });
+                (move || match view.get() {
+                    Some(view) => view(cx),
+                    None => fallback(cx),
+                })
+                .into_child(cx)
+            }
+        }
+    }
+}
+
+impl<F, Fu, V, E> From<F> for Lazy
+where
+    F: Fn() -> Fu + 'static,
+    Fu: Future<Output = V> + 'static,
+    V: Fn(Scope) -> E + 'static,
+    E: IntoChild,
+{
+    fn from(load: F) -> Self {
+        Self {
+            load: Rc::new(move || {
+                let view = load();
+                async move {
+                    let view = view.await;
+                    Rc::new(move |cx| view(cx).into_child(cx)) as LazyView
+                }
+                .boxed_local()
+            }),
+            state: Rc::new(RefCell::new(LazyState::NotLoaded)),
+        }
+    }
+}
+
+impl Debug for Lazy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lazy")
+            .field("loaded", &self.is_loaded())
+            .finish_non_exhaustive()
+    }
+}
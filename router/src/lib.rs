@@ -142,12 +142,38 @@
 #![cfg_attr(not(feature = "stable"), feature(negative_impls))]
 #![cfg_attr(not(feature = "stable"), feature(type_name_of_val))]
 
+mod blocker;
 mod components;
+mod guards;
 mod history;
 mod hooks;
+mod lazy;
+mod locale;
 mod matching;
+mod meta;
+mod query;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+mod server_navigation;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+mod sitemap;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+mod static_site;
+mod typed_route;
 
+pub use blocker::*;
 pub use components::*;
+pub use guards::*;
 pub use history::*;
 pub use hooks::*;
+pub use lazy::*;
+pub use locale::*;
 pub use matching::Branch;
+pub use meta::*;
+pub use query::*;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+pub use server_navigation::*;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+pub use sitemap::*;
+#[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+pub use static_site::*;
+pub use typed_route::*;
@@ -0,0 +1,346 @@
+use leptos::*;
+
+use crate::use_router;
+
+/// The name of the cookie the router keeps the locale of the user in, once they've visited a
+/// page with a locale prefix, so that it's detected again on pages without one.
+pub const LOCALE_COOKIE: &str = "leptos_locale";
+
+/// The locale preferences of the user a page is rendered for, which a [Router](crate::Router)
+/// with `locales` uses for a URL without a locale prefix. The server integrations provide it
+/// from the `Cookie` and `Accept-Language` headers of the request; in the browser, it's read
+/// from `document.cookie` and `navigator.languages`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocaleRequest {
+    /// The value of the `Cookie` header.
+    pub cookie: Option<String>,
+    /// The value of the `Accept-Language` header.
+    pub accept_language: Option<String>,
+}
+
+impl LocaleRequest {
+    /// The one of `locales` the user prefers: the one in the [LOCALE_COOKIE], if any, or else
+    /// the first language in `Accept-Language`, by quality, that matches one of them, either
+    /// exactly or by its primary language, e.g., `de-CH` matches `de`.
+    ///
+    /// ```
+    /// # use leptos_router::*;
+    /// let request = LocaleRequest {
+    ///     cookie: None,
+    ///     accept_language: Some("fr;q=0.5, de-CH, en;q=0.8".to_string()),
+    /// };
+    /// assert_eq!(request.preferred(&["en", "de"]), Some("de"));
+    ///
+    /// let request = LocaleRequest {
+    ///     cookie: Some("theme=dark; leptos_locale=en".to_string()),
+    ///     ..request
+    /// };
+    /// assert_eq!(request.preferred(&["en", "de"]), Some("en"));
+    /// ```
+    pub fn preferred(&self, locales: &[&'static str]) -> Option<&'static str> {
+        let cookie = self.cookie.as_deref().unwrap_or_default();
+        let from_cookie = cookie
+            .split(';')
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == LOCALE_COOKIE)
+            .and_then(|(_, value)| find_locale(locales, value));
+        if from_cookie.is_some() {
+            return from_cookie;
+        }
+
+        let mut languages = self
+            .accept_language
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|language| {
+                let mut parts = language.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect::<Vec<_>>();
+        // a stable sort keeps the order of languages with the same quality
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+        languages.into_iter().find_map(|(tag, _)| {
+            find_locale(locales, tag).or_else(|| {
+                let primary = primary_language(tag);
+                locales
+                    .iter()
+                    .find(|locale| primary_language(locale).eq_ignore_ascii_case(primary))
+                    .copied()
+            })
+        })
+    }
+
+    /// The preferences of the user in the browser.
+    #[cfg(not(feature = "ssr"))]
+    fn from_browser() -> Self {
+        use wasm_bindgen::JsCast;
+
+        let cookie = document()
+            .dyn_into::<web_sys::HtmlDocument>()
+            .ok()
+            .and_then(|document| document.cookie().ok());
+        let accept_language = window()
+            .navigator()
+            .languages()
+            .iter()
+            .filter_map(|language| language.as_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        Self {
+            cookie,
+            accept_language: Some(accept_language),
+        }
+    }
+}
+
+/// Returns the locale of the current page: the locale prefix of the URL, if it has one, or
+/// else the locale preferred by the user, as given by [LocaleRequest::preferred], or else the
+/// first of the `locales` of the [Router](crate::Router). It's empty if the router has no
+/// `locales`.
+///
+/// Routes are matched without the locale prefix, so they're only defined once for all
+/// locales, and an [A](crate::A) adds the prefix of the current locale to its `href`.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Greeting(cx: Scope) -> Element {
+///     let locale = use_locale(cx);
+///     view! {
+///         cx,
+///         <p>{move || if locale.get() == "de" { "Hallo!" } else { "Hello!" }}</p>
+///     }
+/// }
+///
+/// #[component]
+/// fn LocaleSwitcher(cx: Scope) -> Element {
+///     // the current page, in another locale
+///     let location = use_location(cx);
+///     view! {
+///         cx,
+///         <nav>
+///             <A href=move || location.pathname.get() locale="en">"English"</A>
+///             <A href=move || location.pathname.get() locale="de">"Deutsch"</A>
+///         </nav>
+///     }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         // `/about` is shown at `/en/about` and `/de/about`
+///         <Router locales=&["en", "de"]>
+///             <LocaleSwitcher/>
+///             <nav>
+///                 <A href="/about">"About"</A>
+///             </nav>
+///             <main>
+///                 <Routes>
+///                     <Route path="about" element=|cx| view! { cx, <Greeting/> }/>
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+pub fn use_locale(cx: Scope) -> Memo<&'static str> {
+    use_router(cx).inner.locale
+}
+
+/// The locale the user prefers, for a URL without a locale prefix.
+pub(crate) fn detect_locale(cx: Scope, locales: &[&'static str]) -> &'static str {
+    #[cfg(feature = "ssr")]
+    let request = use_context::<LocaleRequest>(cx).unwrap_or_default();
+    #[cfg(not(feature = "ssr"))]
+    let request = {
+        _ = cx;
+        LocaleRequest::from_browser()
+    };
+    request
+        .preferred(locales)
+        .or_else(|| locales.first().copied())
+        .unwrap_or_default()
+}
+
+/// Keeps the locale of the user in the [LOCALE_COOKIE].
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn store_locale(locale: &str) {
+    use wasm_bindgen::JsCast;
+
+    if let Ok(document) = document().dyn_into::<web_sys::HtmlDocument>() {
+        _ = document.set_cookie(&format!(
+            "{LOCALE_COOKIE}={locale}; Path=/; Max-Age=31536000; SameSite=Lax"
+        ));
+    }
+}
+
+/// Splits the locale prefix, which comes right after the base of the router, off a path,
+/// returning the locale, if there's one, and the path without it.
+pub(crate) fn split_locale(
+    base: &str,
+    locales: &[&'static str],
+    path: &str,
+) -> (Option<&'static str>, String) {
+    let base = base.trim_end_matches('/');
+    if let Some(rest) = strip_base(base, path) {
+        let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        if let Some(locale) = find_locale(locales, &rest[..end]) {
+            let rest = rest[end..].strip_prefix('/').unwrap_or(&rest[end..]);
+            return (Some(locale), format!("{base}/{rest}"));
+        }
+    }
+    (None, path.to_string())
+}
+
+/// Replaces the locale prefix of a path, if it has one, with the given locale.
+pub(crate) fn localize_path(
+    base: &str,
+    locales: &[&'static str],
+    path: &str,
+    locale: &str,
+) -> String {
+    if locales.is_empty() || locale.is_empty() {
+        return path.to_string();
+    }
+    let base = base.trim_end_matches('/');
+    let (_, path) = split_locale(base, locales, path);
+    match strip_base(base, &path) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['?', '#']) => {
+            format!("{base}/{locale}{rest}")
+        }
+        Some(rest) => format!("{base}/{locale}/{rest}"),
+        None => path,
+    }
+}
+
+/// The rest of a path under the base of the router, without its leading slash.
+fn strip_base<'a>(base: &str, path: &'a str) -> Option<&'a str> {
+    let rest = path.strip_prefix(base)?;
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix('/')
+    }
+}
+
+fn find_locale(locales: &[&'static str], tag: &str) -> Option<&'static str> {
+    locales
+        .iter()
+        .find(|locale| locale.eq_ignore_ascii_case(tag))
+        .copied()
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALES: &[&str] = &["en", "de", "pt-BR"];
+
+    fn request(cookie: Option<&str>, accept_language: Option<&str>) -> LocaleRequest {
+        LocaleRequest {
+            cookie: cookie.map(str::to_string),
+            accept_language: accept_language.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn prefers_the_cookie() {
+        let preferred = request(Some("theme=dark; leptos_locale=DE"), Some("en"));
+        assert_eq!(preferred.preferred(LOCALES), Some("de"));
+
+        // a locale the app doesn't have falls back to the header
+        let preferred = request(Some("leptos_locale=fr"), Some("en"));
+        assert_eq!(preferred.preferred(LOCALES), Some("en"));
+    }
+
+    #[test]
+    fn orders_languages_by_quality() {
+        let preferred = request(None, Some("en;q=0.5, de;q=0.9, fr"));
+        assert_eq!(preferred.preferred(LOCALES), Some("de"));
+
+        // the same quality keeps the order of the header
+        let preferred = request(None, Some("de, en"));
+        assert_eq!(preferred.preferred(LOCALES), Some("de"));
+
+        // languages with a quality of 0 aren't acceptable
+        let preferred = request(None, Some("de;q=0, en;q=0.1"));
+        assert_eq!(preferred.preferred(LOCALES), Some("en"));
+    }
+
+    #[test]
+    fn matches_by_primary_language() {
+        assert_eq!(request(None, Some("de-AT")).preferred(LOCALES), Some("de"));
+        assert_eq!(request(None, Some("pt")).preferred(LOCALES), Some("pt-BR"));
+        assert_eq!(
+            request(None, Some("PT-br")).preferred(LOCALES),
+            Some("pt-BR")
+        );
+    }
+
+    #[test]
+    fn prefers_nothing_without_a_match() {
+        assert_eq!(request(None, None).preferred(LOCALES), None);
+        assert_eq!(request(None, Some("fr, *;q=0.1")).preferred(LOCALES), None);
+        assert_eq!(request(None, Some("en")).preferred(&[]), None);
+    }
+
+    #[test]
+    fn splits_the_locale_prefix() {
+        assert_eq!(
+            split_locale("", LOCALES, "/de/about"),
+            (Some("de"), "/about".to_string())
+        );
+        assert_eq!(
+            split_locale("", LOCALES, "/de"),
+            (Some("de"), "/".to_string())
+        );
+        assert_eq!(
+            split_locale("", LOCALES, "/de?q=1"),
+            (Some("de"), "/?q=1".to_string())
+        );
+        assert_eq!(
+            split_locale("/app/", LOCALES, "/app/en/posts/1"),
+            (Some("en"), "/app/posts/1".to_string())
+        );
+        // only a whole segment is a locale
+        assert_eq!(
+            split_locale("", LOCALES, "/english"),
+            (None, "/english".to_string())
+        );
+        assert_eq!(
+            split_locale("/app", LOCALES, "/en/app"),
+            (None, "/en/app".to_string())
+        );
+    }
+
+    #[test]
+    fn localizes_paths() {
+        assert_eq!(localize_path("", LOCALES, "/about", "de"), "/de/about");
+        assert_eq!(localize_path("", LOCALES, "/en/about", "de"), "/de/about");
+        assert_eq!(localize_path("", LOCALES, "/", "de"), "/de");
+        assert_eq!(
+            localize_path("", LOCALES, "/en?q=1#top", "de"),
+            "/de?q=1#top"
+        );
+        assert_eq!(
+            localize_path("/app", LOCALES, "/app/about", "en"),
+            "/app/en/about"
+        );
+        // paths outside the router and routers without locales are left as they are
+        assert_eq!(localize_path("/app", LOCALES, "/other", "en"), "/other");
+        assert_eq!(localize_path("", &[], "/about", "en"), "/about");
+        assert_eq!(localize_path("", LOCALES, "/about", ""), "/about");
+    }
+}
@@ -2,6 +2,8 @@
 // see https://github.com/solidjs/solid-router/blob/main/src/utils.ts
 
 use crate::ParamsMap;
+use regex::Regex;
+use std::{cell::RefCell, collections::HashMap};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[doc(hidden)]
@@ -69,7 +71,14 @@ impl Matcher {
             let mut params = ParamsMap::new();
 
             for (segment, loc_segment) in self.segments.iter().zip(loc_segments.iter()) {
-                if let Some(param_name) = segment.strip_prefix(':') {
+                if let Some(param) = segment.strip_prefix(':') {
+                    let (param_name, constraint) = split_constraint(param);
+                    if let Some(constraint) = constraint {
+                        // a param whose value doesn't match its pattern is not a match either
+                        if !matches_constraint(constraint, loc_segment) {
+                            return None;
+                        }
+                    }
                     params.insert(param_name.into(), (*loc_segment).into());
                 } else if segment != loc_segment {
                     // if any segment doesn't match and isn't a param, there's no path match
@@ -95,3 +104,188 @@ impl Matcher {
         }
     }
 }
+
+/// Splits a param, e.g., `id(\d+)` in `:id(\d+)`, into its name and the regular expression its
+/// value must match, if it has one.
+pub(crate) fn split_constraint(param: &str) -> (&str, Option<&str>) {
+    match param.split_once('(') {
+        Some((name, constraint)) => match constraint.strip_suffix(')') {
+            Some(constraint) => (name, Some(constraint)),
+            None => (param, None),
+        },
+        None => (param, None),
+    }
+}
+
+/// Checks the patterns of the params in a route's path, e.g., `\d+` in `users/:id(\d+)`. As the
+/// path is split into segments at `/`, and its optional params are found by their `?`, a
+/// pattern can't contain either, and it must be a valid regular expression that ends its
+/// segment.
+pub(crate) fn check_constraints(path: &str) -> Result<(), String> {
+    for (start, _) in path.match_indices(':') {
+        // a param starts a segment
+        if start > 0 && !path[..start].ends_with('/') {
+            continue;
+        }
+        let param = &path[start + 1..];
+        let name_len = param.find(['/', '(', '?']).unwrap_or(param.len());
+        let rest = &param[name_len..];
+        if !rest.starts_with('(') {
+            continue;
+        }
+
+        // the pattern ends at the parenthesis that closes the one it starts with
+        let mut depth = 0;
+        let mut escaped = false;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            None
+        });
+        let end = end.ok_or_else(|| format!("the pattern of the param at {start} isn't closed"))?;
+        let constraint = &rest[1..end];
+        if constraint.contains(['/', '?']) {
+            return Err(format!(
+                "the pattern {constraint:?} of a param can't contain `/` or `?`"
+            ));
+        }
+        if !matches!(rest[end + 1..].chars().next(), None | Some('/' | '?')) {
+            return Err(format!(
+                "the pattern {constraint:?} of a param must end its segment"
+            ));
+        }
+        constraint_regex(constraint).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether the value of a param matches the pattern it's constrained to. A pattern that isn't
+/// a valid regular expression doesn't match any value.
+pub(crate) fn matches_constraint(constraint: &str, value: &str) -> bool {
+    match constraint_regex(constraint) {
+        Ok(re) => re.is_match(value),
+        Err(e) => {
+            log::error!("[Leptos Router] invalid pattern for a param: {e}");
+            false
+        }
+    }
+}
+
+/// Compiles the regular expression a param's value must match, once per pattern.
+fn constraint_regex(constraint: &str) -> Result<Regex, regex::Error> {
+    thread_local! {
+        static CONSTRAINTS: RefCell<HashMap<String, Result<Regex, regex::Error>>> = Default::default();
+    }
+
+    CONSTRAINTS.with(|constraints| {
+        constraints
+            .borrow_mut()
+            .entry(constraint.to_string())
+            .or_insert_with(|| Regex::new(&format!("^(?:{constraint})$")))
+            .clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(location: &str, path: &str) -> Option<Vec<(String, String)>> {
+        Matcher::new(path)
+            .test(location)
+            .map(|m| m.params.0.into_iter().collect())
+    }
+
+    fn param(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn matches_static_segments() {
+        let matcher = Matcher::new("/users/settings");
+        assert_eq!(
+            matcher.test("/users/settings").map(|m| m.path),
+            Some("/users/settings".to_string())
+        );
+        assert_eq!(matcher.test("/users/5"), None);
+        assert_eq!(matcher.test("/users"), None);
+        assert_eq!(matcher.test("/users/settings/more"), None);
+    }
+
+    #[test]
+    fn matches_params_and_splats() {
+        assert_eq!(
+            params("/users/5", "/users/:id"),
+            Some(vec![param("id", "5")])
+        );
+        assert_eq!(
+            params("/files/a/b/c", "/files/*rest"),
+            Some(vec![param("rest", "a/b/c")])
+        );
+        assert_eq!(
+            params("/files", "/files/*rest"),
+            Some(vec![param("rest", "")])
+        );
+    }
+
+    #[test]
+    fn matches_the_start_of_a_path_when_partial() {
+        let matcher = Matcher::new_with_partial("/users/:id", true);
+        assert_eq!(
+            matcher.test("/users/5/posts").map(|m| m.path),
+            Some("/users/5".to_string())
+        );
+    }
+
+    #[test]
+    fn matches_params_against_their_patterns() {
+        assert_eq!(
+            params("/users/5", r"/users/:id(\d+)"),
+            Some(vec![param("id", "5")])
+        );
+        assert_eq!(params("/users/settings", r"/users/:id(\d+)"), None);
+        // the pattern has to match the whole value
+        assert_eq!(params("/users/5a", r"/users/:id(\d+)"), None);
+        assert_eq!(
+            params("/posts/b", "/posts/:slug((a|b)+)"),
+            Some(vec![param("slug", "b")])
+        );
+    }
+
+    #[test]
+    fn an_invalid_pattern_matches_nothing() {
+        assert!(!matches_constraint(r"\d+(", "5"));
+        assert_eq!(params("/users/5", r"/users/:id(\d+()"), None);
+    }
+
+    #[test]
+    fn splits_params_from_their_patterns() {
+        assert_eq!(split_constraint(r"id(\d+)"), ("id", Some(r"\d+")));
+        assert_eq!(split_constraint("id"), ("id", None));
+        assert_eq!(split_constraint("id(open"), ("id(open", None));
+    }
+
+    #[test]
+    fn checks_the_patterns_of_a_path() {
+        assert!(check_constraints("/users/:id").is_ok());
+        assert!(check_constraints(r"/users/:id(\d+)/posts/:slug([a-z\(]+)?").is_ok());
+        assert!(check_constraints(r":id(\d+)").is_ok());
+        // a pattern can't hold the characters a path is split at
+        assert!(check_constraints(r"/users/:id(\d+/\d+)").is_err());
+        assert!(check_constraints(r"/users/:id(\d?)").is_err());
+        // and it has to be closed, valid, and end its segment
+        assert!(check_constraints(r"/users/:id(\d+").is_err());
+        assert!(check_constraints(r"/users/:id([a-)").is_err());
+        assert!(check_constraints(r"/users/:id(\d+)x").is_err());
+    }
+}
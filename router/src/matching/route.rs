@@ -3,11 +3,21 @@ use std::rc::Rc;
 use leptos::leptos_dom::Child;
 use leptos::*;
 
+use crate::{AfterNavigate, BeforeNavigate, Lazy, Loader, RouteMeta};
+
 #[derive(Clone)]
 pub struct RouteDefinition {
     pub path: &'static str,
     pub children: Vec<RouteDefinition>,
     pub element: Rc<dyn Fn(Scope) -> Child>,
+    pub loader: Option<Loader>,
+    pub lazy: Option<Lazy>,
+    pub status: Option<u16>,
+    pub revalidate: Option<u64>,
+    pub meta: RouteMeta,
+    pub keep_alive: bool,
+    pub before_navigate: Option<BeforeNavigate>,
+    pub after_navigate: Option<AfterNavigate>,
 }
 
 impl std::fmt::Debug for RouteDefinition {
@@ -31,6 +41,14 @@ impl Default for RouteDefinition {
             path: Default::default(),
             children: Default::default(),
             element: Rc::new(|_| Child::Null),
+            loader: None,
+            lazy: None,
+            status: None,
+            revalidate: None,
+            meta: Default::default(),
+            keep_alive: true,
+            before_navigate: None,
+            after_navigate: None,
         }
     }
 }
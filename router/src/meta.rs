@@ -0,0 +1,125 @@
+use leptos::*;
+
+/// Static metadata of a [Route](crate::Route), as given by its `title`, `breadcrumb` and
+/// `roles` props.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteMeta {
+    /// The title of the page the route shows.
+    pub title: Option<&'static str>,
+    /// The label of the route in a list of breadcrumbs.
+    pub breadcrumb: Option<&'static str>,
+    /// The roles a user needs to see the route. The router doesn't check them itself; a
+    /// layout can, e.g., by showing a login form instead of its [Outlet](crate::Outlet).
+    pub roles: &'static [&'static str],
+}
+
+/// The metadata of one of the routes the current URL matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedRouteMeta {
+    /// The part of the URL path the route matches, including param values in their places.
+    pub path: String,
+    /// The metadata of the route.
+    pub meta: RouteMeta,
+}
+
+/// A link to one of the routes the current URL matches, as rendered in a list of breadcrumbs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breadcrumb {
+    /// The `breadcrumb` of the route.
+    pub label: &'static str,
+    /// The part of the URL path the route matches, to link to.
+    pub path: String,
+}
+
+/// The metadata of all the routes the current URL matches, from the outermost to the innermost.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchedMeta {
+    /// The matched routes, from the outermost to the innermost.
+    pub routes: Vec<MatchedRouteMeta>,
+}
+
+impl MatchedMeta {
+    /// The title of the innermost route that has one.
+    pub fn title(&self) -> Option<&'static str> {
+        self.routes.iter().rev().find_map(|route| route.meta.title)
+    }
+
+    /// A breadcrumb for each matched route that has a `breadcrumb` label, from the outermost
+    /// to the innermost.
+    pub fn breadcrumbs(&self) -> Vec<Breadcrumb> {
+        self.routes
+            .iter()
+            .filter_map(|route| {
+                route.meta.breadcrumb.map(|label| Breadcrumb {
+                    label,
+                    path: route.path.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// The roles required by any of the matched routes, each once.
+    pub fn roles(&self) -> Vec<&'static str> {
+        let mut roles = Vec::new();
+        for role in self.routes.iter().flat_map(|route| route.meta.roles) {
+            if !roles.contains(role) {
+                roles.push(*role);
+            }
+        }
+        roles
+    }
+}
+
+/// Returns the metadata of all the routes the current URL matches, from the outermost to the
+/// innermost, so that a layout can render breadcrumbs or set the title of the page without
+/// repeating the route table. Outside of a [Routes](crate::Routes), nothing is matched.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Layout(cx: Scope) -> Element {
+///     let meta = use_route_meta(cx);
+///     view! {
+///         cx,
+///         <div>
+///             <h1>{move || meta.with(|meta| meta.title().unwrap_or_default())}</h1>
+///             <nav>
+///                 {move || meta.with(|meta| {
+///                     meta.breadcrumbs()
+///                         .into_iter()
+///                         .map(|crumb| view! { cx, <a href=crumb.path>{crumb.label}</a> })
+///                         .collect::<Vec<_>>()
+///                 })}
+///             </nav>
+///             <Outlet/>
+///         </div>
+///     }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router>
+///             <main>
+///                 <Routes>
+///                     <Route path="" breadcrumb="Home" element=|cx| view! { cx, <Layout/> }>
+///                         <Route
+///                             path="admin"
+///                             title="Admin"
+///                             breadcrumb="Admin"
+///                             roles=&["admin"]
+///                             element=|cx| view! { cx, <p>"Admin"</p> }
+///                         />
+///                     </Route>
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+pub fn use_route_meta(cx: Scope) -> Memo<MatchedMeta> {
+    use_context::<Memo<MatchedMeta>>(cx)
+        .unwrap_or_else(|| create_memo(cx, |_| MatchedMeta::default()))
+}
@@ -0,0 +1,567 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc, str::FromStr, time::Duration};
+
+use leptos::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{use_router, NavigateOptions, ParamsError, RouterContext, State};
+
+/// How a value is written to, and read from, the query string of the URL by
+/// [use_serde_query] and [create_query_signal].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryEncoding {
+    /// Each field is a key of its own, e.g., `?page=2&sort=name`, encoded with
+    /// [serde_urlencoded](https://docs.rs/serde_urlencoded). This supports fields that are
+    /// strings, numbers, `bool`s, `Option`s of them, which are left out when they're `None`,
+    /// and enums whose variants hold no data.
+    Form,
+    /// The whole value is JSON in the single key given, e.g., `?filter={"tags":["a","b"]}`,
+    /// which supports any type, including arrays, nested structs and enums.
+    Json(&'static str),
+}
+
+impl QueryEncoding {
+    /// Reads a value from the query string, with or without its leading `?`.
+    /// ```
+    /// # use leptos_router::*;
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// struct Search {
+    ///     q: String,
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     QueryEncoding::Form.decode::<Search>("?q=hello+world&page=2"),
+    ///     Ok(Search { q: "hello world".to_string(), page: Some(2) })
+    /// );
+    /// assert_eq!(
+    ///     QueryEncoding::Json("tags").decode::<Vec<String>>("tags=%5B%22a%22%2C%22b%22%5D"),
+    ///     Ok(vec!["a".to_string(), "b".to_string()])
+    /// );
+    /// ```
+    pub fn decode<T: DeserializeOwned>(&self, search: &str) -> Result<T, ParamsError> {
+        let search = search.trim_start_matches('?');
+        match self {
+            Self::Form => {
+                serde_urlencoded::from_str(search).map_err(|e| ParamsError::Params(Rc::new(e)))
+            }
+            Self::Json(key) => {
+                let value = query_pairs(search)
+                    .into_iter()
+                    .find_map(|(k, value)| (k == *key).then_some(value))
+                    .ok_or_else(|| ParamsError::MissingParam(key.to_string()))?;
+                serde_json::from_str(&value).map_err(|e| ParamsError::Params(Rc::new(e)))
+            }
+        }
+    }
+
+    /// Writes a value as the key-value pairs of a query string.
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<(String, String)>, ParamsError> {
+        match self {
+            Self::Form => serde_urlencoded::to_string(value)
+                .map(|query| query_pairs(&query))
+                .map_err(|e| ParamsError::Params(Rc::new(e))),
+            Self::Json(key) => serde_json::to_string(value)
+                .map(|json| vec![(key.to_string(), json)])
+                .map_err(|e| ParamsError::Params(Rc::new(e))),
+        }
+    }
+}
+
+/// Splits a query string into its decoded key-value pairs.
+pub(crate) fn query_pairs(search: &str) -> Vec<(String, String)> {
+    serde_urlencoded::from_str(search.trim_start_matches('?')).unwrap_or_default()
+}
+
+/// Returns the current URL search query, deserialized into the given type with [serde] in
+/// the given [QueryEncoding], or an error.
+///
+/// Unlike [use_query](crate::use_query), which parses each field from a string with
+/// [Params](crate::Params), this supports any type that implements
+/// [Deserialize](serde::Deserialize), like `Option`s, enums, and, with [QueryEncoding::Json],
+/// arrays and nested structs.
+pub fn use_serde_query<T>(cx: Scope, encoding: QueryEncoding) -> Memo<Result<T, ParamsError>>
+where
+    T: DeserializeOwned + PartialEq + Debug,
+{
+    let search = use_router(cx).inner.location.search;
+    create_memo(cx, move |_| search.with(|search| encoding.decode(search)))
+}
+
+/// Creates a [SignalQuery], which holds a value that's read from the URL search query and
+/// written back to it when it's set, e.g., for the filters of a list, so that they're kept
+/// when the page is reloaded or the link is shared.
+///
+/// If the query can't be deserialized into `T`, e.g., because it's empty, the signal holds
+/// `T::default()`.
+///
+/// To bind a single key, like the page of a table, with debounced writes, see
+/// [create_query_param_signal].
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+/// struct Filters {
+///     page: Option<u32>,
+///     sort: Option<String>,
+/// }
+///
+/// #[component]
+/// fn TodoList(cx: Scope) -> Element {
+///     let filters = create_query_signal::<Filters>(cx, QueryEncoding::Form);
+///     let next_page = {
+///         let filters = filters.clone();
+///         move |_| filters.update(|filters| filters.page = Some(filters.page.unwrap_or(1) + 1))
+///     };
+///
+///     view! {
+///         cx,
+///         <div>
+///             <p>"Page " {move || filters.with(|filters| filters.page.unwrap_or(1))}</p>
+///             <button on:click=next_page>"Next"</button>
+///         </div>
+///     }
+/// }
+/// ```
+pub fn create_query_signal<T>(cx: Scope, encoding: QueryEncoding) -> SignalQuery<T>
+where
+    T: Serialize + DeserializeOwned + Default + Clone + PartialEq + Debug + 'static,
+{
+    let router = use_router(cx);
+    let search = router.inner.location.search;
+    let value = create_memo(cx, move |_| {
+        search.with(|search| encoding.decode(search).unwrap_or_default())
+    });
+    SignalQuery {
+        value,
+        router,
+        encoding,
+    }
+}
+
+/// A value that's read from the URL search query and written back to it when it's set,
+/// created with [create_query_signal].
+///
+/// Setting it replaces the keys the value is encoded in, leaving any other keys of the query
+/// as they are, and navigates to the new URL, replacing the current entry in the history
+/// stack.
+#[derive(Clone)]
+pub struct SignalQuery<T>
+where
+    T: 'static,
+{
+    value: Memo<T>,
+    router: RouterContext,
+    encoding: QueryEncoding,
+}
+
+impl<T> SignalQuery<T>
+where
+    T: Serialize + Clone + 'static,
+{
+    /// Clones and returns the current value, subscribing to changes.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Applies a function to the current value, subscribing to changes.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.value.with(f)
+    }
+
+    /// Writes the value to the query string.
+    pub fn set(&self, value: T) {
+        let location = &self.router.inner.location;
+        let pairs = self.value.with_untracked(|current| {
+            let replaced = self.encoding.encode(current)?;
+            let pairs = self.encoding.encode(&value)?;
+            let mut query = location.search.with_untracked(|search| query_pairs(search));
+            query.retain(|(key, _)| {
+                !replaced
+                    .iter()
+                    .chain(&pairs)
+                    .any(|(replaced, _)| replaced == key)
+            });
+            query.extend(pairs);
+            Ok::<_, ParamsError>(query)
+        });
+        match pairs {
+            Ok(pairs) => write_query(&self.router, pairs, true),
+            Err(e) => log::error!("[Leptos Router] could not write the query: {e}"),
+        }
+    }
+
+    /// Updates the value with a function, and writes it to the query string.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.value.get_untracked();
+        f(&mut value);
+        self.set(value);
+    }
+}
+
+impl<T> Debug for SignalQuery<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignalQuery")
+            .field("value", &self.value)
+            .field("encoding", &self.encoding)
+            .finish()
+    }
+}
+
+/// Creates a [SignalQueryParam], which holds the value of a single key of the URL search query,
+/// e.g., the page, sort order or filter of a table, and writes it back to the URL when it's
+/// set, so that it's kept when the page is reloaded or the link is shared.
+///
+/// The value is parsed with [FromStr], and written with [ToString]. If the key is missing or
+/// can't be parsed, the signal holds `default`, and setting it to `default` removes the key
+/// from the URL, so that it stays clean.
+///
+/// By default, setting the value replaces the current entry in the history stack right away.
+/// [SignalQueryParam::debounce] writes it only once it has stopped changing, e.g., while typing
+/// a filter, and [SignalQueryParam::replace] pushes a new entry instead, so that the "back"
+/// button returns to the previous value.
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # use std::time::Duration;
+/// #[component]
+/// fn UserTable(cx: Scope) -> Element {
+///     let page = create_query_param_signal(cx, "page", 1_u32).replace(false);
+///     let filter = create_query_param_signal(cx, "q", String::new())
+///         .debounce(Duration::from_millis(300));
+///
+///     let next_page = {
+///         let page = page.clone();
+///         move |_| page.update(|page| *page += 1)
+///     };
+///     let on_input = {
+///         let (page, filter) = (page.clone(), filter.clone());
+///         move |ev| {
+///             filter.set(event_target_value(&ev));
+///             page.set(1);
+///         }
+///     };
+///
+///     view! {
+///         cx,
+///         <div>
+///             <input prop:value={move || filter.get()} on:input=on_input/>
+///             <p>"Page " {move || page.get()}</p>
+///             <button on:click=next_page>"Next"</button>
+///         </div>
+///     }
+/// }
+/// ```
+pub fn create_query_param_signal<T>(cx: Scope, key: &'static str, default: T) -> SignalQueryParam<T>
+where
+    T: FromStr + ToString + Clone + PartialEq + 'static,
+{
+    let router = use_router(cx);
+    let search = router.inner.location.search;
+    let parse = {
+        let default = default.clone();
+        move |search: &str| {
+            query_pairs(search)
+                .into_iter()
+                .find_map(|(k, value)| (k == key).then_some(value))
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| default.clone())
+        }
+    };
+    let (value, set_value) = create_signal(cx, search.with_untracked(|search| parse(search)));
+    // incremented on each change, so that only the latest pending write goes to the URL
+    let generation = Rc::new(Cell::new(0_usize));
+    let pending = Rc::new(Cell::new(false));
+
+    on_cleanup(cx, {
+        let generation = Rc::clone(&generation);
+        move || generation.set(generation.get().wrapping_add(1))
+    });
+
+    // follows the URL when it changes otherwise, e.g., when going back, unless a newer value
+    // is waiting to be written to it
+    create_effect(cx, {
+        let pending = Rc::clone(&pending);
+        move |_| {
+            let from_url = search.with(|search| parse(search));
+            if !pending.get() && value.with_untracked(|value| *value != from_url) {
+                set_value.set(from_url);
+            }
+        }
+    });
+
+    SignalQueryParam {
+        key,
+        default,
+        value,
+        set_value,
+        router,
+        debounce: Duration::ZERO,
+        replace: true,
+        generation,
+        pending,
+    }
+}
+
+/// The value of a single key of the URL search query, which is written back to it when it's
+/// set, created with [create_query_param_signal].
+#[derive(Clone)]
+pub struct SignalQueryParam<T>
+where
+    T: 'static,
+{
+    key: &'static str,
+    default: T,
+    value: ReadSignal<T>,
+    set_value: WriteSignal<T>,
+    router: RouterContext,
+    debounce: Duration,
+    replace: bool,
+    generation: Rc<Cell<usize>>,
+    pending: Rc<Cell<bool>>,
+}
+
+impl<T> SignalQueryParam<T>
+where
+    T: ToString + Clone + PartialEq + 'static,
+{
+    /// Writes the value to the URL only once it hasn't changed for `delay`. The signal itself
+    /// is updated right away. Each change restarts the wait, so a burst of changes, like
+    /// keystrokes in a search box, produces a single navigation with the last value.
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = delay;
+        self
+    }
+
+    /// Whether writing the value to the URL replaces the current entry in the history stack,
+    /// rather than pushing a new one. Defaults to `true`.
+    pub fn replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    /// Clones and returns the current value, subscribing to changes.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Applies a function to the current value, subscribing to changes.
+    pub fn with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.value.with(f)
+    }
+
+    /// Sets the value, and writes it to the query string.
+    pub fn set(&self, value: T) {
+        if self.value.with_untracked(|current| *current != value) {
+            self.set_value.set(value);
+        }
+
+        let generation = self.generation.get().wrapping_add(1);
+        self.generation.set(generation);
+        self.pending.set(true);
+
+        #[cfg(any(feature = "csr", feature = "hydrate"))]
+        if !self.debounce.is_zero() {
+            let this = self.clone();
+            leptos_dom::set_timeout(
+                move || {
+                    if this.generation.get() == generation {
+                        this.write();
+                    }
+                },
+                self.debounce,
+            );
+            return;
+        }
+        self.write();
+    }
+
+    /// Updates the value with a function, and writes it to the query string.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.value.get_untracked();
+        f(&mut value);
+        self.set(value);
+    }
+
+    fn write(&self) {
+        self.pending.set(false);
+        let mut query = self
+            .router
+            .inner
+            .location
+            .search
+            .with_untracked(|search| query_pairs(search));
+        let value = self.value.get_untracked();
+        let position = query.iter().position(|(key, _)| key == self.key);
+        query.retain(|(key, _)| key != self.key);
+        if value != self.default {
+            // keeps the key where it was, so that the URL doesn't change more than it has to
+            let pair = (self.key.to_string(), value.to_string());
+            query.insert(position.unwrap_or(query.len()), pair);
+        }
+        write_query(&self.router, query, self.replace);
+    }
+}
+
+impl<T> Debug for SignalQueryParam<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignalQueryParam")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .field("debounce", &self.debounce)
+            .field("replace", &self.replace)
+            .finish()
+    }
+}
+
+/// Navigates to the current path with the given query, keeping its hash.
+fn write_query(router: &RouterContext, query: Vec<(String, String)>, replace: bool) {
+    let location = &router.inner.location;
+    let query = if query.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "?{}",
+            serde_urlencoded::to_string(query).unwrap_or_default()
+        )
+    };
+    // nothing to navigate to if the query is already the same
+    if location
+        .search
+        .with_untracked(|search| search.trim_start_matches('?') == query.trim_start_matches('?'))
+    {
+        return;
+    }
+    let to = format!(
+        "{}{query}{}",
+        location.pathname.get_untracked(),
+        location.hash.get_untracked()
+    );
+    let options = NavigateOptions {
+        resolve: false,
+        replace,
+        scroll: false,
+        state: State(None),
+        ..Default::default()
+    };
+    if let Err(e) = Rc::clone(&router.inner).navigate_from_route(&to, &options) {
+        log::error!("[Leptos Router] could not write the query: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Sort {
+        Name,
+        Date,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Filters {
+        q: String,
+        page: Option<u32>,
+        sort: Option<Sort>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Nested {
+        tags: Vec<String>,
+        range: (u32, u32),
+    }
+
+    #[test]
+    fn form_round_trips() {
+        let filters = Filters {
+            q: "a & b".to_string(),
+            page: Some(2),
+            sort: Some(Sort::Date),
+        };
+        let pairs = QueryEncoding::Form.encode(&filters).unwrap();
+        assert_eq!(
+            pairs,
+            [
+                ("q".to_string(), "a & b".to_string()),
+                ("page".to_string(), "2".to_string()),
+                ("sort".to_string(), "date".to_string()),
+            ]
+        );
+        let search = serde_urlencoded::to_string(&pairs).unwrap();
+        assert_eq!(QueryEncoding::Form.decode::<Filters>(&search), Ok(filters));
+    }
+
+    #[test]
+    fn form_leaves_out_none() {
+        let filters = Filters {
+            q: String::new(),
+            page: None,
+            sort: None,
+        };
+        let pairs = QueryEncoding::Form.encode(&filters).unwrap();
+        assert_eq!(pairs, [("q".to_string(), String::new())]);
+        assert_eq!(QueryEncoding::Form.decode::<Filters>("?q="), Ok(filters));
+    }
+
+    #[test]
+    fn form_rejects_values_of_the_wrong_type() {
+        assert!(matches!(
+            QueryEncoding::Form.decode::<Filters>("q=a&page=two"),
+            Err(ParamsError::Params(_))
+        ));
+        assert!(matches!(
+            QueryEncoding::Form.decode::<Filters>("q=a&sort=size"),
+            Err(ParamsError::Params(_))
+        ));
+    }
+
+    #[test]
+    fn json_round_trips_nested_values() {
+        let encoding = QueryEncoding::Json("filter");
+        let nested = Nested {
+            tags: vec!["a".to_string(), "b c".to_string()],
+            range: (1, 10),
+        };
+        let pairs = encoding.encode(&nested).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0, "filter");
+        let search = format!("?other=1&{}", serde_urlencoded::to_string(&pairs).unwrap());
+        assert_eq!(encoding.decode::<Nested>(&search), Ok(nested));
+    }
+
+    #[test]
+    fn json_reports_a_missing_key_or_invalid_json() {
+        let encoding = QueryEncoding::Json("filter");
+        assert_eq!(
+            encoding.decode::<Nested>("other=1"),
+            Err(ParamsError::MissingParam("filter".to_string()))
+        );
+        assert!(matches!(
+            encoding.decode::<Nested>("filter=%7Bnot+json"),
+            Err(ParamsError::Params(_))
+        ));
+    }
+
+    #[test]
+    fn splits_and_decodes_pairs() {
+        assert_eq!(
+            query_pairs("?a=1&b=x+y&c=%26"),
+            [
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "x y".to_string()),
+                ("c".to_string(), "&".to_string()),
+            ]
+        );
+        assert!(query_pairs("").is_empty());
+    }
+}
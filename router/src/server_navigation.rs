@@ -0,0 +1,102 @@
+use leptos::*;
+
+/// Navigation between the pages of a multi-page app by the server: the server integrations
+/// provide it as context when the `server_navigation` option is set, so that the
+/// [Routes](crate::Routes) of each page are marked as its outlet, and send a small script with
+/// the page instead of hydrating the app.
+///
+/// Once a link to another page of the app is clicked, the script fetches that page, rendered on
+/// the server as it would be if it were loaded, and swaps its outlet into the one of the current
+/// page, turbolinks-style, so that everything around the [Routes](crate::Routes), like a header
+/// or an island in it, stays as it is. The `<title>` and the URL are then updated, and the
+/// islands of the new outlet are hydrated, if there are any.
+///
+/// A link is followed as usual if it opens in another window, downloads a file, or leads to
+/// another origin or to an anchor on the same page, or if the page it leads to has no outlet.
+/// Like [A](crate::A), a link with a `noscroll` attribute keeps the scroll position.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ServerNavigation;
+
+impl ServerNavigation {
+    /// The script that navigates between pages by swapping their outlets, which loads the JS and
+    /// WASM files of the app, at the given paths, only to hydrate the islands of a new outlet.
+    /// The script is marked with the [Nonce] of the page, if any.
+    pub fn to_script(&self, js: &str, wasm: &str, nonce: Option<&Nonce>) -> String {
+        let nonce = nonce.map(|nonce| nonce.to_attribute()).unwrap_or_default();
+        format!(
+            r#"<script type="module"{nonce}>
+                    let page = location.pathname + location.search;
+                    async function hydrateIslands(outlet, doc) {{
+                        const manifest = doc.getElementById("leptos-islands");
+                        if (!manifest) return;
+                        const islands = await import('{js}');
+                        await islands.default('{wasm}');
+                        for (const {{ id, component }} of JSON.parse(manifest.textContent)) {{
+                            const el = outlet.querySelector(`leptos-island[data-island="${{id}}"]`);
+                            if (el && !el.parentElement.closest("leptos-island")) {{
+                                islands["_island_" + component](el);
+                            }}
+                        }}
+                    }}
+                    async function navigate(url, push, scroll) {{
+                        let res, doc;
+                        try {{
+                            res = await fetch(url);
+                            doc = new DOMParser().parseFromString(await res.text(), "text/html");
+                        }} catch (e) {{
+                            location.href = url;
+                            return;
+                        }}
+                        for (const tpl of doc.querySelectorAll("template[id]")) {{
+                            const frag = doc.querySelector(`[data-fragment-id="${{tpl.id}}"]`);
+                            if (frag) frag.replaceWith(tpl.content.cloneNode(true));
+                        }}
+                        // the scripts that patch the <head> don't run in a parsed page
+                        for (const tpl of doc.querySelectorAll('template[id^="leptos-head-"]')) {{
+                            const title = tpl.content.querySelector("title");
+                            if (title) doc.title = title.textContent;
+                        }}
+                        const outlet = document.querySelector("leptos-outlet");
+                        const next = doc.querySelector("leptos-outlet");
+                        if (!outlet || !next) {{
+                            location.href = res.url;
+                            return;
+                        }}
+                        outlet.replaceChildren(...next.childNodes);
+                        if (doc.title) document.title = doc.title;
+                        if (push) history.pushState(null, "", res.url);
+                        page = location.pathname + location.search;
+                        if (scroll) {{
+                            const target = location.hash && document.getElementById(decodeURIComponent(location.hash.slice(1)));
+                            if (target) target.scrollIntoView(); else window.scrollTo(0, 0);
+                        }}
+                        await hydrateIslands(outlet, doc);
+                    }}
+                    document.addEventListener("click", (ev) => {{
+                        if (ev.defaultPrevented || ev.button !== 0 || ev.metaKey || ev.altKey || ev.ctrlKey || ev.shiftKey) return;
+                        const a = ev.composedPath().find((el) => el instanceof Element && el.nodeName.toUpperCase() === "A");
+                        if (!a || !a.hasAttribute("href") || a.hasAttribute("download") || a.getAttribute("rel") === "external") return;
+                        if (a.target && a.target !== "_self") return;
+                        const url = new URL(a.href, location.href);
+                        if (url.origin !== location.origin) return;
+                        if (url.hash && url.pathname + url.search === page) return;
+                        ev.preventDefault();
+                        navigate(url.href, true, !a.hasAttribute("noscroll"));
+                    }});
+                    window.addEventListener("popstate", () => {{
+                        if (location.pathname + location.search !== page) navigate(location.href, false, false);
+                    }});
+                </script>"#
+        )
+    }
+}
+
+/// Marks the HTML of the routes of a page as the outlet that [ServerNavigation] swaps, if it's
+/// provided as context.
+pub(crate) fn mark_outlet(cx: Scope, html: String) -> String {
+    if use_context::<ServerNavigation>(cx).is_some() {
+        format!("<leptos-outlet>{html}</leptos-outlet>")
+    } else {
+        html
+    }
+}
@@ -0,0 +1,322 @@
+use std::rc::Rc;
+
+use leptos::*;
+
+use crate::{static_site::Page, StaticRoutes, StaticSiteError};
+
+/// A URL listed in a sitemap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapUrl {
+    /// The path of the URL, e.g., `/posts/hello-world`.
+    pub path: String,
+    /// When the page was last modified, in the
+    /// [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) format, e.g., `2023-01-31`.
+    pub lastmod: Option<String>,
+}
+
+impl SitemapUrl {
+    /// Creates a URL with the given path and no modification date.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            lastmod: None,
+        }
+    }
+
+    /// Sets when the page was last modified.
+    pub fn lastmod(mut self, lastmod: impl Into<String>) -> Self {
+        self.lastmod = Some(lastmod.into());
+        self
+    }
+}
+
+/// The URLs to list in a `sitemap.xml`, as generated by [generate_sitemap]: every route of
+/// the app without params, each route with params once for every set of params given for it
+/// in its [StaticRoutes], and any other URL added with [Sitemap::urls].
+///
+/// ```
+/// # use leptos_router::*;
+/// let sitemap = Sitemap::new("https://example.com")
+///     .routes(StaticRoutes::new().params(
+///         "posts/:slug",
+///         [params_map! { "slug".to_string() => "hello-world".to_string() }],
+///     ))
+///     .urls([SitemapUrl::new("/feed.xml").lastmod("2023-01-31")])
+///     .lastmod(|path| (path == "/").then(|| "2023-01-01".to_string()));
+/// ```
+#[derive(Clone)]
+pub struct Sitemap {
+    base_url: String,
+    routes: StaticRoutes,
+    urls: Vec<SitemapUrl>,
+    lastmod: LastmodFn,
+}
+
+type LastmodFn = Rc<dyn Fn(&str) -> Option<String>>;
+
+impl Sitemap {
+    /// Creates a sitemap of the app served at `base_url`, e.g., `https://example.com`, which
+    /// lists only its routes without params.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            routes: StaticRoutes::new(),
+            urls: Vec::new(),
+            lastmod: Rc::new(|_| None),
+        }
+    }
+
+    /// Sets the params the routes with params are listed with.
+    pub fn routes(mut self, routes: StaticRoutes) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Adds URLs that aren't found in the routes of the app. A URL with the same path as one
+    /// of its routes replaces it.
+    pub fn urls(mut self, urls: impl IntoIterator<Item = SitemapUrl>) -> Self {
+        self.urls.extend(urls);
+        self
+    }
+
+    /// Sets a function that returns when the page at a path was last modified, for every URL
+    /// that doesn't set it itself.
+    pub fn lastmod(mut self, lastmod: impl Fn(&str) -> Option<String> + 'static) -> Self {
+        self.lastmod = Rc::new(lastmod);
+        self
+    }
+
+    /// The `robots.txt` of the app, which lets crawlers index every page and points them to
+    /// its `sitemap.xml`.
+    pub fn robots_txt(&self) -> String {
+        robots_txt(&self.base_url)
+    }
+
+    fn to_xml(&self, urls: &[SitemapUrl]) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for url in urls {
+            let lastmod = url.lastmod.clone().or_else(|| (self.lastmod)(&url.path));
+            xml.push_str("<url><loc>");
+            xml.push_str(&escape_xml(&format!("{}{}", self.base_url, url.path)));
+            xml.push_str("</loc>");
+            if let Some(lastmod) = lastmod {
+                xml.push_str("<lastmod>");
+                xml.push_str(&escape_xml(&lastmod));
+                xml.push_str("</lastmod>");
+            }
+            xml.push_str("</url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+}
+
+impl std::fmt::Debug for Sitemap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sitemap")
+            .field("base_url", &self.base_url)
+            .field("routes", &self.routes)
+            .field("urls", &self.urls)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Generates the `sitemap.xml` of an app, listing the URLs given by `sitemap`.
+///
+/// The routes are found by rendering the app, like [generate_static_site] does, so the sitemap
+/// always matches the route tree. This has to be awaited within a [Tokio](https://docs.rs/tokio)
+/// runtime, which resources are loaded in.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn App(cx: Scope) -> Element {
+///     view! {
+///         cx,
+///         <div>
+///             <Router>
+///                 <main>
+///                     <Routes>
+///                         <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                         <Route path="about" element=|cx| view! { cx, <p>"About"</p> }/>
+///                     </Routes>
+///                 </main>
+///             </Router>
+///         </div>
+///     }
+/// }
+///
+/// async fn sitemap() -> Result<String, StaticSiteError> {
+///     // lists https://example.com/ and https://example.com/about
+///     generate_sitemap(|cx| view! { cx, <App/> }, &Sitemap::new("https://example.com")).await
+/// }
+/// ```
+pub async fn generate_sitemap(
+    app_fn: impl Fn(Scope) -> Element + 'static,
+    sitemap: &Sitemap,
+) -> Result<String, StaticSiteError> {
+    let mut urls = Vec::new();
+    Page::bare(app_fn)
+        .render_site(&sitemap.routes, |path, _| {
+            if !sitemap.urls.iter().any(|url| url.path == path) {
+                urls.push(SitemapUrl::new(path));
+            }
+            Ok(())
+        })
+        .await?;
+    urls.sort_by(|a, b| a.path.cmp(&b.path));
+    urls.extend(sitemap.urls.iter().cloned());
+    Ok(sitemap.to_xml(&urls))
+}
+
+/// The `robots.txt` of an app served at `base_url`, which lets crawlers index every page and
+/// points them to its `sitemap.xml`.
+///
+/// ```
+/// # use leptos_router::*;
+/// assert_eq!(
+///     robots_txt("https://example.com/"),
+///     "User-agent: *\nAllow: /\n\nSitemap: https://example.com/sitemap.xml\n"
+/// );
+/// ```
+pub fn robots_txt(base_url: &str) -> String {
+    format!(
+        "User-agent: *\nAllow: /\n\nSitemap: {}/sitemap.xml\n",
+        base_url.trim_end_matches('/')
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[component]
+    fn App(cx: Scope) -> Element {
+        view! {
+            cx,
+            <div>
+                <Router>
+                    <main>
+                        <Routes>
+                            <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+                            <Route path="about" element=|cx| view! { cx, <p>"About"</p> }/>
+                            <Route path="posts/:slug" element=|cx| view! { cx, <p>"Post"</p> }/>
+                        </Routes>
+                    </main>
+                </Router>
+            </div>
+        }
+    }
+
+    fn generate(sitemap: &Sitemap) -> Result<String, StaticSiteError> {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(generate_sitemap(|cx| view! { cx, <App/> }, sitemap))
+    }
+
+    fn slugs(slugs: &[&str]) -> Vec<ParamsMap> {
+        slugs
+            .iter()
+            .map(|slug| {
+                let mut params = ParamsMap::new();
+                params.insert("slug".to_string(), slug.to_string());
+                params
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lists_routes_without_params() {
+        let xml = generate(&Sitemap::new("https://example.com/")).unwrap();
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n\
+             <url><loc>https://example.com/</loc></url>\n\
+             <url><loc>https://example.com/about</loc></url>\n\
+             </urlset>\n"
+        );
+    }
+
+    #[test]
+    fn lists_routes_with_params_for_each_set_of_params() {
+        let sitemap = Sitemap::new("https://example.com")
+            .routes(StaticRoutes::new().params("posts/:slug", slugs(&["b", "a"])));
+        let xml = generate(&sitemap).unwrap();
+        let locs: Vec<_> = xml
+            .lines()
+            .filter_map(|line| line.strip_prefix("<url><loc>"))
+            .map(|line| line.trim_end_matches("</loc></url>"))
+            .collect();
+        assert_eq!(
+            locs,
+            [
+                "https://example.com/",
+                "https://example.com/about",
+                "https://example.com/posts/a",
+                "https://example.com/posts/b",
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_urls_replace_routes_and_set_lastmod() {
+        let sitemap = Sitemap::new("https://example.com")
+            .urls([
+                SitemapUrl::new("/about").lastmod("2023-02-01"),
+                SitemapUrl::new("/feed.xml"),
+            ])
+            .lastmod(|path| (path != "/feed.xml").then(|| "2023-01-01".to_string()));
+        let xml = generate(&sitemap).unwrap();
+        assert_eq!(xml.matches("/about</loc>").count(), 1);
+        assert!(
+            xml.contains("<url><loc>https://example.com/</loc><lastmod>2023-01-01</lastmod></url>")
+        );
+        // the URL's own date wins over the function
+        assert!(xml.contains(
+            "<url><loc>https://example.com/about</loc><lastmod>2023-02-01</lastmod></url>"
+        ));
+        assert!(xml.contains("<url><loc>https://example.com/feed.xml</loc></url>"));
+    }
+
+    #[test]
+    fn escapes_urls() {
+        let sitemap =
+            Sitemap::new("https://example.com").urls([SitemapUrl::new("/search?q=a&sort='new'")]);
+        assert!(generate(&sitemap)
+            .unwrap()
+            .contains("<loc>https://example.com/search?q=a&amp;sort=&apos;new&apos;</loc>"));
+    }
+
+    #[test]
+    fn reports_routes_missing_params() {
+        let sitemap = Sitemap::new("https://example.com")
+            .routes(StaticRoutes::new().params("posts/:slug", [ParamsMap::new()]));
+        assert!(matches!(
+            generate(&sitemap),
+            Err(StaticSiteError::MissingParam { param, .. }) if param == "slug"
+        ));
+    }
+
+    #[test]
+    fn robots_txt_points_to_the_sitemap() {
+        assert_eq!(
+            Sitemap::new("https://example.com/").robots_txt(),
+            "User-agent: *\nAllow: /\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+    }
+}
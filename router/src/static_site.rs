@@ -0,0 +1,345 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use futures::StreamExt;
+use leptos::*;
+use thiserror::Error;
+
+use crate::{
+    matching::{matches_constraint, split_constraint, Branch},
+    ParamsMap, RouterIntegrationContext, ServerIntegration,
+};
+
+/// The routes to render with [generate_static_site]: every route without params is rendered
+/// as it is, and each route with params once for every set of params given for it.
+///
+/// ```
+/// # use leptos_router::*;
+/// let routes = StaticRoutes::new().params(
+///     "posts/:slug",
+///     [
+///         params_map! { "slug".to_string() => "hello-world".to_string() },
+///         params_map! { "slug".to_string() => "static-sites".to_string() },
+///     ],
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct StaticRoutes {
+    params: Vec<(String, Vec<ParamsMap>)>,
+    head: Option<Rc<dyn Fn(Scope) -> String>>,
+}
+
+impl StaticRoutes {
+    /// Creates a list of routes that contains only the routes without params.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the params a route is rendered with, once for each [ParamsMap]. The route is given
+    /// by its full path, as it's nested in its parent routes, e.g., `posts/:slug`.
+    pub fn params(mut self, route: &str, params: impl IntoIterator<Item = ParamsMap>) -> Self {
+        self.params
+            .push((route.to_string(), params.into_iter().collect()));
+        self
+    }
+
+    /// Sets a function that renders the tags to add to the `<head>` of each page after the app
+    /// has been rendered, e.g., those of a `leptos_meta::MetaContext` provided by the app.
+    pub fn head(mut self, head: impl Fn(Scope) -> String + 'static) -> Self {
+        self.head = Some(Rc::new(head));
+        self
+    }
+
+    /// The paths a route is rendered at.
+    fn paths(&self, pattern: &str) -> Result<Vec<String>, StaticSiteError> {
+        let is_static = !pattern
+            .split('/')
+            .any(|segment| segment.starts_with(':') || segment.starts_with('*'));
+        if is_static {
+            return Ok(vec![fill_params(pattern, &ParamsMap::new())?]);
+        }
+
+        let route = strip_constraints(pattern);
+        self.params
+            .iter()
+            .filter(|(other, _)| strip_constraints(other) == route)
+            .flat_map(|(_, params)| params)
+            .map(|params| fill_params(pattern, params))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for StaticRoutes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticRoutes")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error that occurs while generating a static site.
+#[derive(Debug, Error)]
+pub enum StaticSiteError {
+    /// The paths of the JS and WASM files couldn't be resolved.
+    #[error(transparent)]
+    Config(#[from] LeptosConfigError),
+    /// A set of params given for a route doesn't include one of its params.
+    #[error("no value was given for the param {param:?} of the route {route:?}")]
+    MissingParam { route: String, param: String },
+    /// A value given for a param doesn't match the regular expression of the param.
+    #[error("the value {value:?} of the param {param:?} doesn't match the route {route:?}")]
+    InvalidParam {
+        route: String,
+        param: String,
+        value: String,
+    },
+    /// A page couldn't be written.
+    #[error("couldn't write {0:?}: {1}")]
+    Io(PathBuf, std::io::Error),
+}
+
+/// Renders every route of the app to an HTML file in `out_dir`, producing a static site that
+/// can be served by any file server.
+///
+/// The routes are found by rendering the app, so every `<Routes/>` in it is found as long as
+/// it's rendered by one of the pages. Every route without params is rendered, and every route
+/// with params once for each set of params given for it in `routes`. Each page is written to
+/// `index.html` in the directory of its path, e.g., `posts/hello-world/index.html`, once all its
+/// resources have loaded, along with the scripts that hydrate it. The JS and WASM files named
+/// by `options` aren't copied; they have to be deployed along with the pages.
+///
+/// Returns the files that were written. This has to be awaited within a
+/// [Tokio](https://docs.rs/tokio) runtime, which resources are loaded in.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn App(cx: Scope) -> Element {
+///     view! {
+///         cx,
+///         <div>
+///             <Router>
+///                 <main>
+///                     <Routes>
+///                         <Route path="" element=|cx| view! { cx, <p>"Home"</p> }/>
+///                         <Route path="posts/:slug" element=|cx| view! { cx, <p>"Post"</p> }/>
+///                     </Routes>
+///                 </main>
+///             </Router>
+///         </div>
+///     }
+/// }
+///
+/// async fn export(options: &RenderOptions) -> Result<(), StaticSiteError> {
+///     let routes = StaticRoutes::new().params(
+///         "posts/:slug",
+///         [params_map! { "slug".to_string() => "hello-world".to_string() }],
+///     );
+///     // writes ./dist/index.html and ./dist/posts/hello-world/index.html
+///     generate_static_site(options, |cx| view! { cx, <App/> }, routes, "./dist").await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn generate_static_site(
+    options: &RenderOptions,
+    app_fn: impl Fn(Scope) -> Element + Clone + 'static,
+    routes: StaticRoutes,
+    out_dir: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>, StaticSiteError> {
+    let AssetPaths { js, wasm } = options.asset_paths()?;
+    let runtime_config = options.runtime_config();
+    let runtime_config_script = runtime_config.to_script();
+    let head = format!(
+        r#"<!DOCTYPE html>
+        <html lang="en">
+            <head>
+                <meta charset="utf-8"/>
+                <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                <link rel="modulepreload" href="{js}">
+                <link rel="preload" href="{wasm}" as="fetch" type="application/wasm" crossorigin="">
+                <script type="module">import init, {{ hydrate }} from '{js}'; init('{wasm}').then(hydrate);</script>
+                {runtime_config_script}
+                "#
+    );
+    let page = Page {
+        app_fn: Rc::new(app_fn),
+        head,
+        head_fn: routes.head.clone(),
+        runtime_config,
+        collector: StaticRouteCollector::default(),
+    };
+    let out_dir = out_dir.as_ref();
+
+    let mut files = Vec::new();
+    page.render_site(&routes, |path, html| {
+        let dir = out_dir.join(path.trim_matches('/'));
+        let file = dir.join("index.html");
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&file, html))
+            .map_err(|e| StaticSiteError::Io(file.clone(), e))?;
+        files.push(file);
+        Ok(())
+    })
+    .await?;
+    Ok(files)
+}
+
+/// Renders the pages of a static site.
+pub(crate) struct Page {
+    app_fn: Rc<dyn Fn(Scope) -> Element>,
+    head: String,
+    head_fn: Option<Rc<dyn Fn(Scope) -> String>>,
+    runtime_config: RuntimeConfig,
+    collector: StaticRouteCollector,
+}
+
+impl Page {
+    /// Renders nothing but the app, to find its routes.
+    pub(crate) fn bare(app_fn: impl Fn(Scope) -> Element + 'static) -> Self {
+        Self {
+            app_fn: Rc::new(app_fn),
+            head: String::new(),
+            head_fn: None,
+            runtime_config: RuntimeConfig::new(),
+            collector: StaticRouteCollector::default(),
+        }
+    }
+
+    /// Renders every page of the app that `routes` give the paths of, calling `on_page` with
+    /// the path and the HTML of each.
+    pub(crate) async fn render_site(
+        &self,
+        routes: &StaticRoutes,
+        mut on_page: impl FnMut(&str, String) -> Result<(), StaticSiteError>,
+    ) -> Result<(), StaticSiteError> {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                // the routes of the app are collected as its pages are rendered, starting at
+                // the root
+                self.render("/").await;
+
+                let mut rendered_routes = 0;
+                let mut rendered_paths = HashSet::new();
+                loop {
+                    let patterns = self.collector.0.borrow()[rendered_routes..].to_vec();
+                    if patterns.is_empty() {
+                        return Ok(());
+                    }
+                    rendered_routes += patterns.len();
+
+                    for pattern in patterns {
+                        for path in routes.paths(&pattern)? {
+                            if !rendered_paths.insert(path.clone()) {
+                                continue;
+                            }
+                            let html = self.render(&path).await;
+                            on_page(&path, html)?;
+                        }
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn render(&self, path: &str) -> String {
+        let app = render_to_stream({
+            let app_fn = Rc::clone(&self.app_fn);
+            let head_fn = self.head_fn.clone();
+            let runtime_config = self.runtime_config.clone();
+            let collector = self.collector.clone();
+            let path = format!("http://leptos{path}");
+            move |cx| {
+                provide_context(
+                    cx,
+                    RouterIntegrationContext::new(ServerIntegration { path }),
+                );
+                provide_context(cx, runtime_config);
+                provide_context(cx, collector);
+                let app = app_fn(cx);
+                let head = head_fn.map(|head| head(cx)).unwrap_or_default();
+                format!("{head}</head><body>{app}")
+            }
+        })
+        .collect::<String>()
+        .await;
+        format!("{}{app}</body></html>", self.head)
+    }
+}
+
+/// The patterns of the routes found while rendering a static site, which each `<Routes/>`
+/// adds its routes to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StaticRouteCollector(Rc<RefCell<Vec<String>>>);
+
+impl StaticRouteCollector {
+    pub(crate) fn add(&self, branches: &[Branch]) {
+        let mut patterns = self.0.borrow_mut();
+        for route in branches.iter().filter_map(|branch| branch.routes.last()) {
+            if !patterns.contains(&route.pattern) {
+                patterns.push(route.pattern.clone());
+            }
+        }
+    }
+}
+
+/// The pattern of a route without the regular expressions of its params, and without leading
+/// or trailing slashes, to compare it with the one given by the user.
+fn strip_constraints(pattern: &str) -> String {
+    pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!(":{}", split_constraint(param).0),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The path of a route with the given params in the places of its own.
+fn fill_params(pattern: &str, params: &ParamsMap) -> Result<String, StaticSiteError> {
+    let mut path = String::new();
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        let param = match segment.strip_prefix(':') {
+            Some(param) => Some(split_constraint(param)),
+            None => segment.strip_prefix('*').map(|splat| (splat, None)),
+        };
+        match param {
+            Some((param, constraint)) => {
+                let value = params
+                    .get(param)
+                    .ok_or_else(|| StaticSiteError::MissingParam {
+                        route: pattern.to_string(),
+                        param: param.to_string(),
+                    })?;
+                if let Some(constraint) = constraint {
+                    if !matches_constraint(constraint, value) {
+                        return Err(StaticSiteError::InvalidParam {
+                            route: pattern.to_string(),
+                            param: param.to_string(),
+                            value: value.to_string(),
+                        });
+                    }
+                }
+                // an empty splat matches the path without it
+                if !value.is_empty() {
+                    path.push('/');
+                    path.push_str(value.trim_matches('/'));
+                }
+            }
+            None => {
+                path.push('/');
+                path.push_str(segment);
+            }
+        }
+    }
+    if path.is_empty() {
+        path.push('/');
+    }
+    Ok(path)
+}
@@ -0,0 +1,104 @@
+use crate::{matching::split_constraint, Params, ParamsMap};
+
+/// A route whose params are the fields of a struct, which is usually derived with
+/// `#[derive(TypedRoute)]` and `#[route(path = "...")]`.
+///
+/// Its [PATH](TypedRoute::PATH) can be passed to a [Route](crate::Route), its params read with
+/// [use_params](crate::use_params), and links to it built with [to_href](TypedRoute::to_href),
+/// so that a typo in a path or a param is a compile error rather than a link that goes nowhere.
+///
+/// ```
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[derive(TypedRoute, Debug, Clone, PartialEq)]
+/// #[route(path = "/users/:id")]
+/// struct UserRoute {
+///     id: u32,
+/// }
+///
+/// #[component]
+/// fn User(cx: Scope) -> Element {
+///     let params = use_params::<UserRoute>(cx);
+///     let id = move || params.with(|params| params.as_ref().map(|params| params.id).ok());
+///     view! { cx, <p>"User " {id}</p> }
+/// }
+///
+/// # fn app(cx: Scope) -> Element {
+/// view! {
+///     cx,
+///     <div>
+///         <Router>
+///             <nav>
+///                 <A href=move || UserRoute { id: 5 }.to_href()>"The fifth user"</A>
+///             </nav>
+///             <main>
+///                 <Routes>
+///                     <Route path=UserRoute::PATH element=|cx| view! { cx, <User/> }/>
+///                 </Routes>
+///             </main>
+///         </Router>
+///     </div>
+/// }
+/// # }
+/// ```
+pub trait TypedRoute: Params {
+    /// The path pattern the route is matched at, e.g., `/users/:id`.
+    const PATH: &'static str;
+
+    /// The values of the params, by name. An optional param that's `None` is left out.
+    fn to_params(&self) -> ParamsMap;
+
+    /// The URL of the route, with the params filled in, e.g., `/users/5`.
+    fn to_href(&self) -> String {
+        expand_path(Self::PATH, &self.to_params())
+    }
+}
+
+/// Fills in the params of a path pattern with the given values, escaping each of them as a
+/// segment of the path. The segment of an optional param without a value is left out, as is
+/// a segment whose param is missing.
+/// ```
+/// # use leptos_router::*;
+/// let params = params_map! {
+///     "id".to_string() => "5".to_string(),
+///     "rest".to_string() => "a/b".to_string()
+/// };
+/// assert_eq!(expand_path("/users/:id/:tab?/*rest", &params), "/users/5/a/b");
+/// assert_eq!(expand_path("/users/:id(\\d+)", &params), "/users/5");
+/// ```
+pub fn expand_path(path: &str, params: &ParamsMap) -> String {
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .filter_map(|segment| {
+            if let Some(name) = segment.strip_prefix(':') {
+                let name = name.strip_suffix('?').unwrap_or(name);
+                let (name, _) = split_constraint(name);
+                params
+                    .get(name)
+                    .map(|value| urlencoding::encode(value).into_owned())
+            } else if let Some(name) = segment.strip_prefix('*') {
+                // the rest of the path can hold several segments
+                params
+                    .get(name)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| {
+                        value
+                            .split('/')
+                            .map(|segment| urlencoding::encode(segment).into_owned())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    })
+            } else {
+                Some(segment.to_string())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if path.starts_with('/') {
+        format!("/{segments}")
+    } else {
+        segments
+    }
+}